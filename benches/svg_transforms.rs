@@ -0,0 +1,23 @@
+//! Criterion benchmarks of the SVG transforms on the built-in fixtures of `webtk svg bench`
+//! (`cargo bench --bench svg_transforms`). For the numbers on real assets, see `webtk svg bench <dir>`.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use webtk::bench::{BENCH_FIXTURE_SHAPE_COUNTS, BENCH_TRANSFORMS, bench_fixtures, run_bench_transform};
+
+fn svg_transforms(c: &mut Criterion) {
+	let fixtures = bench_fixtures();
+	for transform in BENCH_TRANSFORMS {
+		let mut group = c.benchmark_group(*transform);
+		for (shape_count, fixture) in BENCH_FIXTURE_SHAPE_COUNTS.iter().zip(&fixtures) {
+			group.throughput(Throughput::Bytes(fixture.len() as u64));
+			group.bench_with_input(BenchmarkId::new("shapes", shape_count), fixture, |b, fixture| {
+				b.iter(|| run_bench_transform(transform, black_box(fixture)))
+			});
+		}
+		group.finish();
+	}
+}
+
+criterion_group!(benches, svg_transforms);
+criterion_main!(benches);
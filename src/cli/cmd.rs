@@ -4,16 +4,199 @@ use clap::{Args, Parser, Subcommand};
 #[derive(Parser, Debug)]
 #[command(version)]
 pub struct CliCmd {
+	/// Colorize the output: auto (terminal, unless NO_COLOR is set), always, never
+	#[arg(long, global = true, default_value = "auto", value_parser = ["auto", "always", "never"])]
+	pub color: String,
+
+	/// Timeout in seconds of the downloads of the URL inputs (e.g., https://cdn.example.com/icons.svg)
+	#[arg(long, global = true, default_value_t = 30)]
+	pub http_timeout: u64,
+
+	/// Directory of the downloaded URL inputs
+	#[arg(long, global = true, default_value = ".cache-http")]
+	pub http_cache_dir: String,
+
+	/// Download the URL inputs even when they are in the HTTP cache
+	#[arg(long, global = true)]
+	pub no_http_cache: bool,
+
+	/// CA bundle (PEM) to verify HTTPS servers with (e.g., the certificate of a TLS-intercepting proxy)
+	#[arg(long, global = true, value_name = "FILE")]
+	pub cacert: Option<String>,
+
+	/// Do not verify HTTPS certificates (unsafe, prefer --cacert)
+	#[arg(long, global = true)]
+	pub insecure: bool,
+
+	/// Pass an environment variable to sketchtool, which only gets the system ones (PATH, HOME, USER, TMPDIR, locale,
+	/// ...), not the credentials of the CI job. Repeatable
+	#[arg(long, global = true, value_name = "KEY")]
+	pub allow_env: Vec<String>,
+
+	/// Write a profile of the run (spans of the command, sketchtool runs, transforms, and file IO) as a Chrome trace
+	/// JSON file, to open in about://tracing or Perfetto
+	#[arg(long, global = true, value_name = "FILE")]
+	pub profile_output: Option<String>,
+
+	/// Forbid all network access (URL inputs, APIs, webhooks, remote sketchtool): such a command fails (exit code 6),
+	/// for hermetic builds. Also `[network] offline = true` in webtk.toml
+	#[arg(long, global = true)]
+	pub offline: bool,
+
+	/// Run sketchtool on a remote macOS host over SSH (e.g., ci@mac-mini.local), copying the Sketch files up and the
+	/// exported files back
+	#[arg(long, global = true, value_name = "USER@HOST")]
+	pub remote: Option<String>,
+
+	/// A volume mounted on both machines, as LOCAL_DIR=REMOTE_DIR, to map the paths instead of copying the files
+	#[arg(long, global = true, value_name = "LOCAL=REMOTE", requires = "remote")]
+	pub remote_shared: Option<String>,
+
+	/// Run sketchtool through a `webtk export-server` on a Mac (e.g., http://mac-runner:9000)
+	#[arg(long, global = true, value_name = "URL", conflicts_with = "remote")]
+	pub export_server: Option<String>,
+
 	#[command(subcommand)]
 	pub command: Option<CliSubCmd>,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum CliSubCmd {
+	/// List the items (artboards, frames) of a design source: a .sketch file, or a Figma file URL
+	List(SourceListArgs),
+
+	/// Export the items (artboards, frames) of a design source: a .sketch file, or a Figma file URL
+	Export(SourceExportArgs),
+
 	#[command(subcommand)]
 	Sketch(SketchCommand),
+
+	#[command(subcommand)]
+	Icons(IconsCommand),
+
+	#[command(subcommand)]
+	Svg(SvgCommand),
+
+	/// Run the export jobs of a webtk.toml (or of all the projects of a workspace with --all)
+	Run(RunArgs),
+
+	#[command(subcommand)]
+	Config(ConfigCommand),
+
+	/// Serve JSON-RPC requests on a local socket (list artboards, run jobs, last report), for editor integrations
+	Daemon(DaemonArgs),
+
+	/// Serve an HTTP endpoint a Sketch plugin can push exported SVGs to (--ingest), rebuilding the sprite on arrival
+	Serve(ServeArgs),
+
+	/// Serve the sketchtool commands over HTTP (on a Mac), for the clients using --export-server (e.g., a Linux CI)
+	ExportServer(ExportServerArgs),
+
+	#[command(subcommand)]
+	Snapshot(SnapshotCommand),
+
+	/// Render exported SVGs and compare them with baseline PNGs (perceptual diff), writing diff images for failures
+	VisualTest(VisualTestArgs),
+
+	#[command(subcommand)]
+	Codegen(CodegenCommand),
+
+	#[command(subcommand)]
+	Auth(AuthCommand),
+
+	#[command(subcommand)]
+	Figma(FigmaCommand),
+
+	#[command(subcommand)]
+	Tokens(TokensCommand),
+
+	/// Verify the files of a SHA256SUMS file (or of the one in a directory), e.g., a delivered asset drop
+	VerifySums(VerifySumsArgs),
+
+	/// Update webtk to the latest GitHub release of its channel (checksum verified, atomic swap)
+	SelfUpdate(SelfUpdateArgs),
+
+	/// The local usage stats (opt-in with `[stats] enabled = true` in webtk.toml)
+	#[command(subcommand)]
+	Stats(StatsCommand),
+
+	/// Generate kits from the artboards of a Sketch file (e.g., a logo pack)
+	#[command(subcommand)]
+	Gen(GenCommand),
+
+	/// Service worker files for the PWAs (e.g., the precache manifest)
+	#[command(subcommand)]
+	Sw(SwCommand),
+
+	/// Site files of a built site (robots.txt, sitemap.xml)
+	#[command(subcommand)]
+	Site(SiteCommand),
+
+	/// Checks of the HTML pages of a built site (e.g., the broken links)
+	#[command(subcommand)]
+	Html(HtmlCommand),
+
+	/// Checks of the Lottie animations (structure, assets, size budget)
+	#[command(subcommand)]
+	Lottie(LottieCommand),
+
+	/// Conversions of the images (e.g., the animated GIFs to WebP, AVIF, or MP4)
+	#[command(subcommand)]
+	Img(ImgCommand),
+
+	/// The [[pipelines]] of a webtk.toml (e.g., their dependency graph)
+	#[command(subcommand)]
+	Pipeline(PipelineCommand),
+}
+
+// region:    --- Source
+
+#[derive(Args, Debug)]
+pub struct SourceListArgs {
+	/// The design source: a .sketch file, or a Figma file URL (or key, with --source figma)
+	pub input: String,
+
+	/// The source kind: sketch, figma (default: detected from the extension or URL)
+	#[arg(long, value_parser = ["sketch", "figma"])]
+	pub source: Option<String>,
+
+	/// Optional glob patterns to filter items by name (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Print stable tab-separated records instead of the human output (format version, default v1, see README)
+	#[arg(long, value_name = "VERSION", num_args = 0..=1, require_equals = true, default_missing_value = "v1", value_parser = ["v1"])]
+	pub porcelain: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct SourceExportArgs {
+	/// The design source: a .sketch file, or a Figma file URL (or key, with --source figma)
+	pub input: String,
+
+	/// The source kind: sketch, figma (default: detected from the extension or URL)
+	#[arg(long, value_parser = ["sketch", "figma"])]
+	pub source: Option<String>,
+
+	/// Optional glob patterns to filter items by name (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Export format(s), e.g., svg, png (comma-delimited or multiple flags)
+	#[arg(long, value_delimiter = ',', default_value = "svg")]
+	pub format: Vec<String>,
+
+	/// Output directory for exported files
+	#[arg(short, long)]
+	pub output: String,
+
+	/// Print stable tab-separated records instead of the human output (format version, default v1, see README)
+	#[arg(long, value_name = "VERSION", num_args = 0..=1, require_equals = true, default_missing_value = "v1", value_parser = ["v1"])]
+	pub porcelain: Option<String>,
 }
 
+// endregion: --- Source
+
 // region:    --- Sketch
 
 #[derive(Subcommand, Debug)]
@@ -22,7 +205,43 @@ pub enum SketchCommand {
 	ListArtboards(ListArtboardsArgs),
 
 	/// Export artboards from a Sketch file
-	Export(ExportArgs),
+	Export(Box<ExportArgs>),
+
+	/// Print the resolved export plan (artboard, format, output file, transforms) without exporting
+	Plan(Box<PlanArgs>),
+
+	/// Report which symbols are used on which artboards of a Sketch file
+	SymbolsUsage(SymbolsUsageArgs),
+
+	/// Show artboard stats of a Sketch file (prefixes, sizes, naming compliance, largest artboards)
+	Stats(StatsArgs),
+
+	/// Check the layers of the artboards of a Sketch file (default names, hidden layers, clipping masks, detached symbols)
+	Lint(LintArgs),
+
+	/// List the fonts of the text layers of a Sketch file, flagging the non-brand fonts and the missing fonts
+	Fonts(FontsArgs),
+
+	/// Extract the text content of the artboards (text layers and text overrides), for copy review
+	Copy(CopyArgs),
+
+	/// Print the prototyping flows between the artboards as a graph (DOT, JSON, or Mermaid), with the dead-end screens
+	Flows(FlowsArgs),
+
+	/// Generate HTML spec sheets of artboards (image, size, colors, text styles, layer measurements)
+	Spec(SpecArgs),
+
+	/// Write a contact sheet PDF of artboards (thumbnails with their names, for offline review or printing)
+	ContactSheet(ContactSheetArgs),
+
+	/// Rename artboards in place in the Sketch file, from a CSV map of the current names (or UIDs) to the new names
+	Rename(RenameArgs),
+
+	/// Set the export presets of artboards in place in the Sketch file (e.g., "svg@1x", "png@2x")
+	SetPresets(SetPresetsArgs),
+
+	/// Reposition the artboards of each page in a tidy grid, sorted by name or size, in place in the Sketch file
+	Sort(SortArgs),
 }
 
 #[derive(Args, Debug)]
@@ -33,6 +252,26 @@ pub struct ListArtboardsArgs {
 	/// Optional glob patterns to filter artboards by name (can be specified multiple times)
 	#[arg(short, long)]
 	pub glob: Vec<String>,
+
+	/// What the globs match: name (the artboard name), page-path (the page and artboard name, e.g., "Icons/ico/*")
+	#[arg(long, value_name = "SCOPE", value_parser = ["name", "page-path"])]
+	pub glob_scope: Option<String>,
+
+	/// Match the globs case insensitively (e.g., "ico/*" matches "Ico/User")
+	#[arg(long, conflicts_with = "smart_case")]
+	pub ignore_case: bool,
+
+	/// Match the globs case insensitively, unless they have an uppercase letter
+	#[arg(long)]
+	pub smart_case: bool,
+
+	/// Show the export presets of the artboards (formats and sizes configured in the Sketch file)
+	#[arg(long, conflicts_with = "porcelain")]
+	pub presets: bool,
+
+	/// Print stable tab-separated records instead of the human output (format version, default v1, see README)
+	#[arg(long, value_name = "VERSION", num_args = 0..=1, require_equals = true, default_missing_value = "v1", value_parser = ["v1"])]
+	pub porcelain: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -44,10 +283,35 @@ pub struct ExportArgs {
 	#[arg(short, long)]
 	pub glob: Vec<String>,
 
-	/// Export format(s): svg, png, jpeg, svg-symbols (comma-delimited or multiple flags)
+	/// Export format(s): svg, png, jpeg, svg-symbols, pdf-book (comma-delimited or multiple flags)
 	#[arg(long, value_delimiter = ',')]
 	pub format: Vec<String>,
 
+	/// Optional glob patterns of artboards to exclude (can be specified multiple times)
+	#[arg(long)]
+	pub exclude: Vec<String>,
+
+	/// What the globs match: name (the artboard name), page-path (the page and artboard name, e.g., "Icons/ico/*")
+	#[arg(long, value_name = "SCOPE", value_parser = ["name", "page-path"])]
+	pub glob_scope: Option<String>,
+
+	/// Match the globs case insensitively (e.g., "ico/*" matches "Ico/User")
+	#[arg(long, conflicts_with = "smart_case")]
+	pub ignore_case: bool,
+
+	/// Match the globs case insensitively, unless they have an uppercase letter
+	#[arg(long)]
+	pub smart_case: bool,
+
+	/// Export scale(s) for png, jpeg, svg, e.g., "1,2" (files of other scales than 1 get a "@2x" suffix)
+	#[arg(long, value_delimiter = ',')]
+	pub scales: Vec<String>,
+
+	/// Export the artboards with their export presets of the Sketch file (formats and scales), the artboards
+	/// without presets with --format and --scales
+	#[arg(long)]
+	pub use_design_presets: bool,
+
 	/// Output directory for exported files
 	#[arg(short, long)]
 	pub output: String,
@@ -59,6 +323,1190 @@ pub struct ExportArgs {
 	/// Keep the raw export cache directory (.cache-raw-export) instead of deleting it
 	#[arg(long)]
 	pub keep_raw_export: bool,
+
+	/// Annotate each svg-symbols `<symbol>` with `data-name` and `data-page` (original artboard name and page).
+	/// Omit for production builds to keep the sprite minimal.
+	#[arg(long)]
+	pub symbol_data_attrs: bool,
+
+	/// Also write a symbols manifest (e.g., symbols.json) with ids, names, viewBox, and pixel width/height
+	#[arg(long)]
+	pub symbols_manifest: bool,
+
+	/// Also write a TypeScript module of the svg-symbols ids (`export type IconName = "ico-user-fill" | ...` and a const array)
+	#[arg(long)]
+	pub ts_out: Option<String>,
+
+	/// The TypeScript type name for --ts-out (the const array is named after it, e.g., "iconNames")
+	#[arg(long, requires = "ts_out")]
+	pub ts_type_name: Option<String>,
+
+	/// Also write a Rust module of the svg-symbols ids (`pub const ICO_USER_FILL: &str = "ico-user-fill";` and `ALL_ICONS`)
+	#[arg(long)]
+	pub rust_out: Option<String>,
+
+	/// Also write an HTML file of the `<use>` snippets of the svg-symbols (one `<svg>` per symbol, with its size and
+	/// preserveAspectRatio)
+	#[arg(long, value_name = "FILE")]
+	pub use_snippets_out: Option<String>,
+
+	/// Also write the usage docs of the svg-symbols icons (.md or .html), with the copy-pasteable snippets of each
+	/// icon (<use> markup, React, CSS class)
+	#[arg(long, value_name = "FILE")]
+	pub usage_out: Option<String>,
+
+	/// Also write a TypeScript module of the path data of the single-path svg-symbols icons (`{ viewBox, path }` by
+	/// symbol id), for the icon components rendering `<path d>` directly without the sprite
+	#[arg(long, value_name = "FILE")]
+	pub paths_ts_out: Option<String>,
+
+	/// Directory of codegen templates overriding the built-in ones by name (e.g., icon-names.ts.jinja for --ts-out)
+	#[arg(long)]
+	pub template_dir: Option<String>,
+
+	/// preserveAspectRatio for svg-symbols, as VALUE or GLOB=VALUE (can be specified multiple times, first match wins)
+	#[arg(long)]
+	pub preserve_aspect_ratio: Vec<String>,
+
+	/// Default width/height of the svg-symbols, as VALUE or GLOB=VALUE with VALUE "intrinsic" (the artboard px size),
+	/// a length (e.g., "1em"), or WIDTH,HEIGHT (can be specified multiple times, first match wins)
+	#[arg(long, value_name = "VALUE|GLOB=VALUE")]
+	pub symbol_size: Vec<String>,
+
+	/// Namespace prefix to strip from svg-symbols content, e.g., "sketch" (can be specified multiple times)
+	#[arg(long)]
+	pub strip_namespace: Vec<String>,
+
+	/// Trim the transparent space around the exported images (sketchtool --trimmed)
+	#[arg(long)]
+	pub trim: bool,
+
+	/// Only export the layers within the artboard bounds, without the artboard background
+	#[arg(long)]
+	pub group_contents_only: bool,
+
+	/// Transparent padding in px on each side of the svg, png, and svg-symbols files (scaled with --scales)
+	#[arg(long, value_name = "PX")]
+	pub padding: Option<u32>,
+
+	/// Background of the raster exports: "transparent" or a hex color (e.g., "#ffffff"), via sketchtool and
+	/// composited onto the png files
+	#[arg(long, value_name = "transparent|#HEX")]
+	pub background: Option<String>,
+
+	/// Split the png exports into tiles of this size in px, in <name>_tiles/ with a <name>.tiles.json descriptor
+	#[arg(long, value_name = "PX")]
+	pub tile: Option<u32>,
+
+	/// The px each tile extends into its neighbors (with --tile)
+	#[arg(long, value_name = "PX", default_value_t = 0, requires = "tile")]
+	pub tile_overlap: u32,
+
+	/// Write a copy of the png exports with a grid every PX (e.g., 8) and the artboard size, in <output>/redlines/
+	#[arg(long, value_name = "PX")]
+	pub redline: Option<u32>,
+
+	/// Export email-safe images: png or jpeg only (png without --format), scaled down to --email-max-width,
+	/// with lowercase flattened names usable as cid: references
+	#[arg(long)]
+	pub email_safe: bool,
+
+	/// The max display width of the email-safe images in px (default 600), times the scale for the files
+	#[arg(long, value_name = "PX", requires = "email_safe")]
+	pub email_max_width: Option<u32>,
+
+	/// Write the email snippet of the email-safe images (<img src="cid:..">) to this .html or .mjml file
+	#[arg(long, requires = "email_safe")]
+	pub email_snippet_out: Option<String>,
+
+	/// The CDN URL of the output (e.g., https://cdn.example.com/assets/), writes <output>/urls.json of the
+	/// exported files URLs by logical name (e.g., "ico/user/fill@2x.png")
+	#[arg(long, value_name = "URL")]
+	pub cdn_base: Option<String>,
+
+	/// Write a TypeScript module of the --cdn-base URLs (ASSET_URLS and the AssetName type) to this file
+	#[arg(long, requires = "cdn_base")]
+	pub cdn_ts_out: Option<String>,
+
+	/// Add a bookmark per page (the artboard name) to the pdf-book file
+	#[arg(long)]
+	pub pdf_bookmarks: bool,
+
+	/// Set vector-effect="non-scaling-stroke" on the stroked shapes of the svg and svg-symbols exports, so the
+	/// strokes keep their width at any rendered size
+	#[arg(long)]
+	pub non_scaling_stroke: bool,
+
+	/// Inline the raster images referenced by the svg and svg-symbols exports (png, jpeg, gif, webp files and URLs)
+	/// as base64 data: URIs, so each SVG is self-contained
+	#[arg(long)]
+	pub embed_images: bool,
+
+	/// Write the images inlined in the svg and svg-symbols exports (base64 data: URIs) as files in this directory,
+	/// and reference them instead (e.g., to run them through an image optimizer)
+	#[arg(long, value_name = "DIR")]
+	pub extract_images: Option<String>,
+
+	/// What to do with the references to local files (e.g., an image at /Users/...) in the svg and svg-symbols
+	/// exports: warn (default), fail, strip (remove them), or embed (as data: URIs)
+	#[arg(long, value_name = "POLICY")]
+	pub local_refs: Option<String>,
+
+	/// Write (or update) a SHA256SUMS file in the output directory, covering the exported files
+	#[arg(long)]
+	pub checksums: bool,
+
+	/// Write a uid-map.json in the output directory, relating each artboard UID to its name and output files
+	#[arg(long)]
+	pub uid_map: bool,
+
+	/// The modification time of the output files: source (the Sketch file mtime), epoch (SOURCE_DATE_EPOCH, or the
+	/// Unix epoch) or now (default)
+	#[arg(long, value_name = "MODE")]
+	pub preserve_mtime: Option<String>,
+
+	/// The octal mode of the output files (e.g., 644). Default: 666 less the umask
+	#[arg(long, value_name = "MODE")]
+	pub chmod: Option<String>,
+
+	/// Raw sketchtool argument, as ARG or FORMAT:ARG (e.g., "png:--background=#ffffff"), appended to the export
+	/// commands (can be specified multiple times)
+	#[arg(long, value_name = "[FORMAT:]ARG", allow_hyphen_values = true)]
+	pub tool_arg: Vec<String>,
+
+	/// The max artboards of a sketchtool export command (default: 500). More are exported in several commands, so the
+	/// command line stays under the OS limit
+	#[arg(long, value_name = "N")]
+	pub items_batch_size: Option<usize>,
+
+	/// Write a JSON report (exported files and warnings) to this file
+	#[arg(long)]
+	pub report: Option<String>,
+
+	/// Fail when the export has warnings (e.g., non-square or empty artboards), for CI.
+	/// Same as `--fail-on warning`.
+	#[arg(long)]
+	pub deny_warnings: bool,
+
+	/// Fail when artboards render identically (likely copy-paste duplicates, exit code 4)
+	#[arg(long)]
+	pub deny_duplicates: bool,
+
+	/// When to fail: empty (no artboards matched, exit code 2), warning (empty or warnings, exit code 4), never
+	#[arg(long, default_value = "empty", value_parser = ["empty", "warning", "never"])]
+	pub fail_on: String,
+
+	/// Succeed (with nothing exported) when the globs match no artboards
+	#[arg(long)]
+	pub allow_empty: bool,
+
+	/// Only export the artboards changed since this git ref (e.g., origin/main), compared with `git show`
+	#[arg(long, value_name = "GIT_REF")]
+	pub changed_since: Option<String>,
+
+	/// Only print the output file paths (one per line) on stdout, all diagnostics on stderr
+	#[arg(short, long)]
+	pub quiet: bool,
+
+	/// Print each exported file instead of the summary by format
+	#[arg(long)]
+	pub list_files: bool,
+
+	/// Print stable tab-separated records instead of the human output (format version, default v1, see README)
+	#[arg(long, value_name = "VERSION", num_args = 0..=1, require_equals = true, default_missing_value = "v1", value_parser = ["v1"], conflicts_with = "quiet")]
+	pub porcelain: Option<String>,
 }
 
-// endregion: --- Sketch
+#[derive(Args, Debug)]
+pub struct PlanArgs {
+	/// Same arguments as `sketch export`
+	#[command(flatten)]
+	pub export: ExportArgs,
+
+	/// Print the plan as JSON instead of a table
+	#[arg(long, conflicts_with_all = ["quiet", "porcelain"])]
+	pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SymbolsUsageArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+}
+
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+
+	/// Number of largest artboards to show
+	#[arg(long, default_value_t = 5)]
+	pub top: usize,
+
+	/// Print the stats as JSON
+	#[arg(long)]
+	pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct LintArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+
+	/// Optional glob patterns to filter artboards by name (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Rule to skip (can be specified multiple times)
+	#[arg(long, value_parser = ["default-layer-name", "hidden-layer", "clipping-mask", "detached-symbol"])]
+	pub ignore: Vec<String>,
+
+	/// Print the violations as JSON
+	#[arg(long)]
+	pub json: bool,
+
+	/// Print stable tab-separated records instead of the human output (format version, default v1, see README)
+	#[arg(long, value_name = "VERSION", num_args = 0..=1, require_equals = true, default_missing_value = "v1", value_parser = ["v1"], conflicts_with = "json")]
+	pub porcelain: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct FontsArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+
+	/// Brand font, as a family or PostScript name (can be specified multiple times). Other fonts are flagged
+	#[arg(long)]
+	pub brand_font: Vec<String>,
+
+	/// Print the report as JSON
+	#[arg(long)]
+	pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CopyArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+
+	/// Optional glob patterns to filter artboards by name (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Output format
+	#[arg(long, default_value = "csv", value_parser = ["csv", "json"])]
+	pub format: String,
+
+	/// Output file (e.g., copy.csv). Printed to stdout when not set
+	#[arg(short, long)]
+	pub output: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct RenameArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+
+	/// The rename map CSV, `from,to` rows (the current artboard name or UID, and the new name)
+	#[arg(long)]
+	pub map: String,
+
+	/// Copy the Sketch file to <name>.backup.sketch before writing it
+	#[arg(long)]
+	pub backup: bool,
+
+	/// Print the renames without writing the Sketch file
+	#[arg(long)]
+	pub dry_run: bool,
+
+	/// Print the report as JSON
+	#[arg(long)]
+	pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SetPresetsArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+
+	/// Optional glob patterns to filter artboards by name (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Export preset as FORMAT[@SIZE], e.g., svg, png@2x, png@512w (can be specified multiple times)
+	#[arg(long, required = true)]
+	pub preset: Vec<String>,
+
+	/// Add the presets the artboards do not have, instead of replacing their presets
+	#[arg(long)]
+	pub append: bool,
+
+	/// Copy the Sketch file to <name>.backup.sketch before writing it
+	#[arg(long)]
+	pub backup: bool,
+
+	/// Print the changes without writing the Sketch file
+	#[arg(long)]
+	pub dry_run: bool,
+
+	/// Print the report as JSON
+	#[arg(long)]
+	pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SortArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+
+	/// Optional glob patterns to filter artboards by name (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Sort order: name, or size (the largest first)
+	#[arg(long, default_value = "name", value_parser = ["name", "size"])]
+	pub by: String,
+
+	/// The gap between the artboards in px, also the unit of their positions (default: 8)
+	#[arg(long, value_name = "PX")]
+	pub grid: Option<u32>,
+
+	/// The artboards per row (default: the square root of the artboard count of the page)
+	#[arg(long)]
+	pub columns: Option<u32>,
+
+	/// Copy the Sketch file to <name>.backup.sketch before writing it
+	#[arg(long)]
+	pub backup: bool,
+
+	/// Print the summary without writing the Sketch file
+	#[arg(long)]
+	pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct FlowsArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+
+	/// Output format
+	#[arg(long, default_value = "dot", value_parser = ["dot", "json", "mermaid"])]
+	pub format: String,
+
+	/// Output file (e.g., flows.dot). Printed to stdout when not set
+	#[arg(short, long)]
+	pub output: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct SpecArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+
+	/// Optional glob patterns to filter artboards by name (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Output directory (index.html, one page per artboard, and the images)
+	#[arg(short, long)]
+	pub output: String,
+
+	/// Directory of codegen templates overriding the built-in ones by name (spec.html.jinja, spec-index.html.jinja)
+	#[arg(long)]
+	pub template_dir: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ContactSheetArgs {
+	/// Path to the Sketch file
+	pub sketch_file: String,
+
+	/// Optional glob patterns to filter artboards by name (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Output PDF file (A4 pages)
+	#[arg(short, long)]
+	pub output: String,
+
+	/// Number of thumbnails per row
+	#[arg(long, value_name = "N", default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..=12))]
+	pub columns: u32,
+}
+
+// endregion: --- Sketch
+
+// region:    --- Icons
+
+#[derive(Subcommand, Debug)]
+pub enum IconsCommand {
+	/// Merge two icon sets (directories of SVG files, or SVG sprite files)
+	Merge(IconsMergeArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct IconsMergeArgs {
+	/// First icon set (directory of SVG files, or SVG sprite file or URL)
+	pub set_a: String,
+
+	/// Second icon set (same kind as the first one)
+	pub set_b: String,
+
+	/// Output directory (or sprite file when merging sprites)
+	#[arg(short, long)]
+	pub output: String,
+
+	/// What to do when both sets have the same icon name with different content: prefer-a, prefer-b, error
+	#[arg(long, default_value = "error", value_parser = ["prefer-a", "prefer-b", "error"])]
+	pub on_conflict: String,
+}
+
+// endregion: --- Icons
+
+// region:    --- Svg
+
+#[derive(Subcommand, Debug)]
+pub enum SvgCommand {
+	/// Split an SVG sprite (symbols file) into standalone SVG files
+	Unsprite(UnspriteArgs),
+
+	/// Measure the throughput (MB/s) of the SVG transforms on the SVG files of a directory (or built-in fixtures)
+	#[command(hide = true)]
+	Bench(SvgBenchArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct UnspriteArgs {
+	/// Path or URL of the SVG sprite file
+	pub sprite_file: String,
+
+	/// Output directory for the extracted SVG files
+	#[arg(short, long)]
+	pub output: String,
+}
+
+#[derive(Args, Debug)]
+pub struct SvgBenchArgs {
+	/// Directory of the SVG files (recursive); the built-in fixtures when omitted
+	pub dir: Option<String>,
+
+	/// The passes of each transform over the files (after a warm-up pass)
+	#[arg(long, default_value_t = crate::handlers::svg::DEFAULT_BENCH_ITERATIONS, value_parser = clap::value_parser!(u32).range(1..))]
+	pub iterations: u32,
+
+	/// Print the results as JSON
+	#[arg(long)]
+	pub json: bool,
+}
+
+// endregion: --- Svg
+
+// region:    --- Run
+
+#[derive(Args, Debug)]
+pub struct RunArgs {
+	/// Names of the jobs to run (all when none)
+	pub jobs: Vec<String>,
+
+	/// Path to the config file (default: ./webtk.toml)
+	#[arg(short, long)]
+	pub config: Option<String>,
+
+	/// Run all the projects of the workspace (members of the root webtk.toml [workspace], or all webtk.toml found below it)
+	#[arg(long, conflicts_with = "config")]
+	pub all: bool,
+
+	/// Write a JSON report (all projects and jobs) to this file
+	#[arg(long)]
+	pub report: Option<String>,
+
+	/// Fail when the jobs have warnings
+	#[arg(long)]
+	pub deny_warnings: bool,
+
+	/// Fail when artboards of a job render identically
+	#[arg(long)]
+	pub deny_duplicates: bool,
+
+	/// Print the resolved export plans of the jobs without exporting
+	#[arg(long)]
+	pub plan: bool,
+
+	/// With --plan, print the plans as JSON instead of tables
+	#[arg(long, requires = "plan")]
+	pub json: bool,
+
+	/// Print each exported file instead of the summary by format
+	#[arg(long)]
+	pub list_files: bool,
+
+	/// The max number of jobs running at once (default: the CPU count, up to 4; 1 runs them in order)
+	#[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+	pub parallel: Option<u32>,
+
+	/// Keep running, and re-run the jobs of a Sketch file when it is saved
+	#[arg(long, conflicts_with = "plan")]
+	pub watch: bool,
+
+	/// Run the steps of a [[pipelines]] of the config whose inputs changed (can be specified multiple times);
+	/// with --watch, on each batch of changes
+	#[arg(long, value_name = "NAME", conflicts_with_all = ["jobs", "plan", "report", "notify", "webhook", "porcelain"])]
+	pub pipeline: Vec<String>,
+
+	/// With --watch, notify the rebuild results in the macOS notification center (or [notify] desktop)
+	#[arg(long, requires = "watch")]
+	pub notify: bool,
+
+	/// With --watch, POST the rebuild results as Slack-compatible JSON to this URL (or [notify] webhook)
+	#[arg(long, requires = "watch")]
+	pub webhook: Option<String>,
+	/// Print stable tab-separated records instead of the human output (format version, default v1, see README)
+	#[arg(long, value_name = "VERSION", num_args = 0..=1, require_equals = true, default_missing_value = "v1", value_parser = ["v1"], conflicts_with_all = ["plan", "watch"])]
+	pub porcelain: Option<String>,
+}
+
+// endregion: --- Run
+
+// region:    --- Config
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+	/// Print (or write) the JSON Schema of webtk.toml, for editor tooling
+	Schema(ConfigSchemaArgs),
+
+	/// Validate a webtk.toml (unknown keys, types, formats), with error locations
+	Validate(ConfigValidateArgs),
+
+	/// Write a starter webtk.toml, with one export job per .sketch file found in the current directory
+	Init(ConfigInitArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigSchemaArgs {
+	/// Write the schema to this file instead of stdout
+	#[arg(short, long)]
+	pub output: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigValidateArgs {
+	/// Path to the config file (default: ./webtk.toml)
+	pub config: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigInitArgs {
+	/// Do not ask, use the defaults (also when stdin is not a terminal)
+	#[arg(short, long)]
+	pub yes: bool,
+
+	/// Overwrite an existing webtk.toml
+	#[arg(long)]
+	pub force: bool,
+}
+
+// endregion: --- Config
+
+// region:    --- Daemon
+
+#[derive(Args, Debug)]
+pub struct DaemonArgs {
+	/// Path of the Unix socket to listen on (default: ./.webtk-daemon.sock)
+	#[arg(long)]
+	pub socket: Option<String>,
+}
+
+// endregion: --- Daemon
+
+// region:    --- Serve
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+	/// Accept SVGs on POST /ingest?name=<artboard name>, and rebuild the --output sprite with them
+	#[arg(long, requires = "output")]
+	pub ingest: bool,
+
+	/// The sprite file rebuilt from all the ingested SVGs
+	#[arg(short, long)]
+	pub output: Option<String>,
+
+	/// Address to listen on (default: 127.0.0.1:7331)
+	#[arg(long)]
+	pub addr: Option<String>,
+
+	/// Directory keeping the ingested SVGs, by artboard name (default: ./.cache-ingest)
+	#[arg(long)]
+	pub ingest_dir: Option<String>,
+
+	/// Add the data-name attribute (artboard name) to the symbols
+	#[arg(long)]
+	pub symbol_data_attrs: bool,
+
+	/// Namespace prefix to strip from the symbols, e.g., "sketch" (can be specified multiple times)
+	#[arg(long)]
+	pub strip_namespace: Vec<String>,
+}
+
+// endregion: --- Serve
+
+// region:    --- Export Server
+
+#[derive(Args, Debug)]
+pub struct ExportServerArgs {
+	/// Address to listen on (default: 127.0.0.1:9000, e.g., 0.0.0.0:9000 for the other machines)
+	#[arg(long)]
+	pub addr: Option<String>,
+
+	/// Directory of the uploaded Sketch files and the results not downloaded yet (default: ./.cache-export-server)
+	#[arg(long)]
+	pub work_dir: Option<String>,
+}
+
+// endregion: --- Export Server
+
+// region:    --- Snapshot
+
+#[derive(Subcommand, Debug)]
+pub enum SnapshotCommand {
+	/// Run the jobs and record the normalized hashes of the exported files in __webtk_snapshots__/
+	Record(SnapshotArgs),
+
+	/// Run the jobs and fail when the exported files differ from the recorded snapshot
+	Check(SnapshotCheckArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct SnapshotArgs {
+	/// Names of the jobs to snapshot (all when none)
+	pub jobs: Vec<String>,
+
+	/// Path to the config file (default: ./webtk.toml)
+	#[arg(short, long)]
+	pub config: Option<String>,
+
+	/// Snapshot all the projects of the workspace
+	#[arg(long, conflicts_with = "config")]
+	pub all: bool,
+
+	/// Also keep copies of the raster files (png, jpeg, ...) in the snapshot, for reviewing the differences
+	#[arg(long)]
+	pub rasters: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SnapshotCheckArgs {
+	#[command(flatten)]
+	pub snapshot: SnapshotArgs,
+
+	/// Accept the differences (record the new snapshot) instead of failing
+	#[arg(long)]
+	pub update: bool,
+}
+
+// endregion: --- Snapshot
+
+// region:    --- VisualTest
+
+#[derive(Args, Debug)]
+pub struct VisualTestArgs {
+	/// The SVG files to test: a directory (recursively), an SVG file, or an SVG URL
+	pub input: String,
+
+	/// Directory of the baseline PNGs, mirroring the SVG paths (e.g., ico/user.svg -> baselines/ico/user.png)
+	#[arg(long)]
+	pub baseline: String,
+
+	/// Differing pixels allowed per SVG, as a percentage (e.g., 0.1%) or a ratio
+	#[arg(long, default_value = "0%")]
+	pub threshold: String,
+
+	/// Color distance (0 to 1) under which pixels match (default: 0.1)
+	#[arg(long)]
+	pub pixel_threshold: Option<f64>,
+
+	/// Render scale (e.g., 2 for @2x baselines)
+	#[arg(long, default_value_t = 1.0)]
+	pub scale: f32,
+
+	/// Directory of the diff and actual images of the failures (default: ./.visual-diff)
+	#[arg(long)]
+	pub diff_dir: Option<String>,
+
+	/// Write the baselines of the missing and failed SVGs instead of failing
+	#[arg(long)]
+	pub update: bool,
+
+	/// Write a JSON report (status and diff ratio per SVG) to this file
+	#[arg(long)]
+	pub report: Option<String>,
+}
+
+// endregion: --- VisualTest
+
+// region:    --- Codegen
+
+#[derive(Subcommand, Debug)]
+pub enum CodegenCommand {
+	/// List the built-in codegen templates, or write them to a directory as a starting point for --template-dir
+	Templates(CodegenTemplatesArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CodegenTemplatesArgs {
+	/// Write the templates to this directory (e.g., templates/)
+	#[arg(short, long)]
+	pub output: Option<String>,
+
+	/// Overwrite existing template files
+	#[arg(long, requires = "output")]
+	pub force: bool,
+}
+
+// endregion: --- Codegen
+
+// region:    --- Auth
+
+#[derive(Subcommand, Debug)]
+pub enum AuthCommand {
+	/// Store the API token of a service in the macOS keychain (read from stdin, never from the arguments)
+	Set(AuthServiceArgs),
+
+	/// Remove the API token of a service from the macOS keychain
+	Remove(AuthServiceArgs),
+
+	/// Show which services have a token, and where it comes from (env var or keychain)
+	Status,
+}
+
+#[derive(Args, Debug)]
+pub struct AuthServiceArgs {
+	/// The service: figma, sketch-cloud
+	#[arg(value_parser = ["figma", "sketch-cloud"])]
+	pub service: String,
+}
+
+// endregion: --- Auth
+
+// region:    --- Figma
+
+#[derive(Subcommand, Debug)]
+pub enum FigmaCommand {
+	/// List the frames of the pages of a Figma file (token from `webtk auth set figma`)
+	List(FigmaListArgs),
+
+	/// Export frames of a Figma file, rendered by the Figma API
+	Export(FigmaExportArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct FigmaListArgs {
+	/// The Figma file key or URL (e.g., https://www.figma.com/design/<key>/Icons)
+	pub file: String,
+
+	/// Optional glob patterns to filter frames by name (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct FigmaExportArgs {
+	/// The Figma file key or URL (e.g., https://www.figma.com/design/<key>/Icons)
+	pub file: String,
+
+	/// Optional glob patterns to filter frames by name (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Node ids to export instead of the frames (e.g., "12:34", comma-delimited or multiple flags)
+	#[arg(long, value_delimiter = ',', conflicts_with = "glob")]
+	pub ids: Vec<String>,
+
+	/// Export format: svg, png, jpg, pdf
+	#[arg(long, default_value = "svg", value_parser = ["svg", "png", "jpg", "pdf"])]
+	pub format: String,
+
+	/// Export scale (png and jpg, 0.01 to 4)
+	#[arg(long, default_value_t = 1.0)]
+	pub scale: f32,
+
+	/// Output directory for exported files
+	#[arg(short, long)]
+	pub output: String,
+}
+
+// endregion: --- Figma
+
+// region:    --- Tokens
+
+#[derive(Subcommand, Debug)]
+pub enum TokensCommand {
+	/// Export the color variables and text styles of a .sketch file as W3C design tokens (JSON)
+	Export(TokensExportArgs),
+
+	/// Convert W3C design tokens (JSON) to CSS, SCSS, TypeScript, Android or iOS
+	Convert(TokensConvertArgs),
+
+	/// Compare the tokens of a .sketch file with the tokens of the code (W3C JSON); fails (exit code 4) on differences
+	Diff(TokensDiffArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct TokensExportArgs {
+	/// Path to the .sketch file
+	pub sketch_file: String,
+
+	/// Output file (e.g., tokens.json). Printed to stdout when not set
+	#[arg(short, long)]
+	pub output: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct TokensConvertArgs {
+	/// The design tokens file (W3C format, e.g., tokens.json), or its URL
+	pub tokens_file: String,
+
+	/// Target format: css, scss, ts, android, ios
+	#[arg(long, value_parser = ["css", "scss", "ts", "android", "ios"])]
+	pub to: String,
+
+	/// Output file (e.g., src/tokens.css). Printed to stdout when not set
+	#[arg(short, long)]
+	pub output: Option<String>,
+
+	/// Directory of codegen templates overriding the built-in ones by name (e.g., tokens.css.jinja for --to css)
+	#[arg(long)]
+	pub template_dir: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct TokensDiffArgs {
+	/// The design source (.sketch file)
+	#[arg(long)]
+	pub design: String,
+
+	/// The tokens of the code (W3C format, e.g., tokens.json), or its URL
+	#[arg(long)]
+	pub code: String,
+
+	/// Print the differences as JSON (added, removed, changed)
+	#[arg(long)]
+	pub json: bool,
+}
+
+// endregion: --- Tokens
+
+// region:    --- VerifySums
+
+#[derive(Args, Debug)]
+pub struct VerifySumsArgs {
+	/// The SHA256SUMS file, or a directory containing one
+	pub path: String,
+
+	/// Print the verification as JSON (ok, mismatched, and missing files)
+	#[arg(long)]
+	pub json: bool,
+
+	/// Print stable tab-separated records instead of the human output (format version, default v1, see README)
+	#[arg(long, value_name = "VERSION", num_args = 0..=1, require_equals = true, default_missing_value = "v1", value_parser = ["v1"], conflicts_with = "json")]
+	pub porcelain: Option<String>,
+}
+
+// endregion: --- VerifySums
+
+// region:    --- SelfUpdate
+
+#[derive(Args, Debug)]
+pub struct SelfUpdateArgs {
+	/// The release channel: stable (releases), or beta (releases and pre-releases)
+	#[arg(long, default_value = "stable", value_parser = ["stable", "beta"])]
+	pub channel: String,
+
+	/// Only check for a newer version (without installing it)
+	#[arg(long)]
+	pub check: bool,
+
+	/// Install the latest release of the channel even when it is not newer
+	#[arg(long)]
+	pub force: bool,
+
+	/// The GitHub releases API URL (e.g., a mirror)
+	#[arg(long, value_name = "URL", default_value = crate::handlers::update::RELEASES_URL)]
+	pub releases_url: String,
+}
+
+// endregion: --- SelfUpdate
+
+// region:    --- Stats
+
+#[derive(Subcommand, Debug)]
+pub enum StatsCommand {
+	/// Show the runs, failures, durations, and artboards by command
+	Show(StatsShowArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct StatsShowArgs {
+	/// The stats file (default: the [stats] file of ./webtk.toml, or ~/.webtk/stats.jsonl)
+	#[arg(long)]
+	pub file: Option<String>,
+
+	/// Print the stats as JSON
+	#[arg(long)]
+	pub json: bool,
+}
+
+// endregion: --- Stats
+
+// region:    --- Gen
+
+#[derive(Subcommand, Debug)]
+pub enum GenCommand {
+	/// Write the logo kit of artboards: SVG and PNG files of the color, mono, and inverse variants, and a favicon set
+	LogoPack(LogoPackArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct LogoPackArgs {
+	/// Path to the Sketch file of the logos
+	#[arg(long)]
+	pub input: String,
+
+	/// Glob patterns of the logo artboards (can be specified multiple times)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Output directory (a folder per logo, and a manifest.json)
+	#[arg(short, long, default_value = "logo-pack")]
+	pub output: String,
+
+	/// Widths in px of the PNG files of each variant, e.g., "512,1024" (default: 256,512,1024,2048)
+	#[arg(long, value_delimiter = ',')]
+	pub png_widths: Vec<u32>,
+
+	/// Hex color of the monochrome variant
+	#[arg(long, default_value = "#000000")]
+	pub mono_color: String,
+
+	/// Hex color of the inverse variant (for dark backgrounds)
+	#[arg(long, default_value = "#ffffff")]
+	pub inverse_color: String,
+}
+
+// endregion: --- Gen
+
+// region:    --- Sw
+
+#[derive(Subcommand, Debug)]
+pub enum SwCommand {
+	/// Write the Workbox precache manifest of a directory: the URL and revision hash of each file
+	Precache(SwPrecacheArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct SwPrecacheArgs {
+	/// The directory of the files to precache (e.g., dist/)
+	pub dir: String,
+
+	/// The manifest file
+	#[arg(short, long, default_value = "precache-manifest.json")]
+	pub output: String,
+
+	/// Glob patterns of the files to precache, relative to the directory (default: all the files)
+	#[arg(short, long)]
+	pub glob: Vec<String>,
+
+	/// Glob patterns of the files not to precache, e.g., "**/*.map" (can be specified multiple times)
+	#[arg(long)]
+	pub ignore: Vec<String>,
+
+	/// The prefix of the URLs, e.g., "/assets/" (default: the paths relative to the directory)
+	#[arg(long)]
+	pub url_prefix: Option<String>,
+}
+
+// endregion: --- Sw
+
+// region:    --- Site
+
+#[derive(Subcommand, Debug)]
+pub enum SiteCommand {
+	/// Write the robots.txt of a site (the rules for a user agent, and the sitemap URL)
+	Robots(SiteRobotsArgs),
+
+	/// Write the sitemap.xml of the HTML pages of a built site, with their lastmod from the file mtimes
+	Sitemap(SiteSitemapArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct SiteRobotsArgs {
+	/// The root directory of the site (where robots.txt is written)
+	#[arg(long, default_value = ".")]
+	pub root: String,
+
+	/// The robots file (default: <root>/robots.txt)
+	#[arg(short, long)]
+	pub output: Option<String>,
+
+	/// The user agent of the rules
+	#[arg(long, default_value = "*")]
+	pub user_agent: String,
+
+	/// Path prefixes the crawlers must not visit, e.g., "/admin/" (can be specified multiple times)
+	#[arg(long)]
+	pub disallow: Vec<String>,
+
+	/// Path prefixes allowed within the disallowed ones (can be specified multiple times)
+	#[arg(long)]
+	pub allow: Vec<String>,
+
+	/// The site URL, e.g., https://example.com (adds the Sitemap line of <base-url>/sitemap.xml)
+	#[arg(long, value_name = "URL")]
+	pub base_url: Option<String>,
+
+	/// The sitemap URL, when not <base-url>/sitemap.xml
+	#[arg(long, value_name = "URL")]
+	pub sitemap_url: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct SiteSitemapArgs {
+	/// The root directory of the built site (e.g., dist/)
+	#[arg(long)]
+	pub root: String,
+
+	/// The site URL, e.g., https://example.com
+	#[arg(long, value_name = "URL")]
+	pub base_url: String,
+
+	/// The sitemap file (default: <root>/sitemap.xml)
+	#[arg(short, long)]
+	pub output: Option<String>,
+
+	/// Glob patterns of the pages not in the sitemap, e.g., "404.html" (can be specified multiple times)
+	#[arg(long)]
+	pub ignore: Vec<String>,
+
+	/// The page URLs without the .html extension (e.g., /about for about.html)
+	#[arg(long)]
+	pub clean_urls: bool,
+}
+
+// endregion: --- Site
+
+// region:    --- Html
+
+#[derive(Subcommand, Debug)]
+pub enum HtmlCommand {
+	/// Check the href, src, and srcset references of the HTML pages (internal on disk, external with --external)
+	CheckLinks(HtmlCheckLinksArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct HtmlCheckLinksArgs {
+	/// The root directory of the built site (e.g., dist/)
+	pub dir: String,
+
+	/// Also check the external links (http, https) with requests
+	#[arg(long)]
+	pub external: bool,
+
+	/// The max concurrent requests of the external link checks
+	#[arg(long, default_value_t = 8)]
+	pub concurrency: usize,
+
+	/// Print the report as JSON
+	#[arg(long)]
+	pub json: bool,
+}
+
+// endregion: --- Html
+
+// region:    --- Lottie
+
+#[derive(Subcommand, Debug)]
+pub enum LottieCommand {
+	/// Check the Lottie JSON files: their structure, their image assets present, and their size budget
+	Check(LottieCheckArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct LottieCheckArgs {
+	/// The Lottie files, as glob patterns or paths (e.g., "assets/**/*.json")
+	#[arg(required = true)]
+	pub globs: Vec<String>,
+
+	/// The max size of each file, e.g., "200KB" (after --optimize)
+	#[arg(long, value_name = "SIZE")]
+	pub max_size: Option<String>,
+
+	/// Strip the hidden layers and the metadata, and write the files minified
+	#[arg(long)]
+	pub optimize: bool,
+
+	/// Print the report as JSON
+	#[arg(long)]
+	pub json: bool,
+}
+
+// endregion: --- Lottie
+
+// region:    --- Img
+
+#[derive(Subcommand, Debug)]
+pub enum ImgCommand {
+	/// Convert the GIFs and animated PNGs to animated WebP, AVIF, or MP4 (with a poster), and compare the sizes
+	Modernize(ImgModernizeArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ImgModernizeArgs {
+	/// The images, as glob patterns or paths (e.g., "public/**/*.gif")
+	#[arg(required = true)]
+	pub globs: Vec<String>,
+
+	/// The target format(s): webp, avif, mp4 (comma-delimited or multiple flags)
+	#[arg(long, value_delimiter = ',', default_value = "webp", value_parser = ["webp", "avif", "mp4"])]
+	pub format: Vec<String>,
+
+	/// Output directory for the converted files (default: next to each image)
+	#[arg(short, long)]
+	pub output: Option<String>,
+
+	/// Print the report as JSON
+	#[arg(long)]
+	pub json: bool,
+}
+
+// endregion: --- Img
+
+// region:    --- Pipeline
+
+#[derive(Subcommand, Debug)]
+pub enum PipelineCommand {
+	/// Render the steps of the pipelines, their source inputs, and their dependencies (Graphviz DOT or Mermaid)
+	Graph(PipelineGraphArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct PipelineGraphArgs {
+	/// Names of the pipelines (all when none)
+	pub pipelines: Vec<String>,
+
+	/// Path to the config file (default: ./webtk.toml)
+	#[arg(short, long)]
+	pub config: Option<String>,
+
+	/// The pipelines of all the projects of the workspace
+	#[arg(long, conflicts_with = "config")]
+	pub all: bool,
+
+	/// The graph format
+	#[arg(long, default_value = "mermaid", value_parser = ["dot", "mermaid"])]
+	pub format: String,
+
+	/// Write the graph to this file instead of stdout
+	#[arg(short, long)]
+	pub output: Option<String>,
+}
+
+// endregion: --- Pipeline
@@ -0,0 +1,61 @@
+use crate::Result;
+use crate::cli::cmd::AuthCommand;
+use crate::handlers::auth::{self, AuthService, TokenSource};
+use crate::support::console::{self, Style};
+
+pub fn exec_command(command: AuthCommand) -> Result<()> {
+	match command {
+		AuthCommand::Set(args) => exec_set(AuthService::try_from(args.service.as_str())?),
+		AuthCommand::Remove(args) => exec_remove(AuthService::try_from(args.service.as_str())?),
+		AuthCommand::Status => exec_status(),
+	}
+}
+
+/// The token is read from stdin (typed without echo, or piped, e.g., `pbpaste | webtk auth set figma`),
+/// so it does not end up in the shell history nor on the screen.
+fn exec_set(service: AuthService) -> Result<()> {
+	let token = console::read_secret_line(&format!("{} token: ", service.id()))?;
+
+	auth::set_token(service, &token)?;
+	console::print_label("Stored", Style::Green, format!("{} token in the keychain", service.id()));
+
+	if std::env::var_os(service.env_var()).is_some() {
+		console::eprint_warning(format!("{} is set, and takes precedence over the keychain", service.env_var()));
+	}
+
+	Ok(())
+}
+
+fn exec_remove(service: AuthService) -> Result<()> {
+	if auth::remove_token(service)? {
+		console::print_label("Removed", Style::Green, format!("{} token from the keychain", service.id()));
+	} else {
+		console::print_label("Skipped", Style::Dim, format!("no {} token in the keychain", service.id()));
+	}
+
+	Ok(())
+}
+
+fn exec_status() -> Result<()> {
+	for service in AuthService::ALL {
+		match auth::find_token(service)? {
+			Some(auth_token) => {
+				let source = match auth_token.source {
+					TokenSource::Env => service.env_var(),
+					TokenSource::Keychain => "keychain",
+				};
+				console::print_label(service.id(), Style::Green, format!("{} (from {source})", auth_token.masked()));
+			}
+			None => {
+				let hint = if auth::keychain_supported() {
+					format!("run `webtk auth set {}` or set {}", service.id(), service.env_var())
+				} else {
+					format!("set {}", service.env_var())
+				};
+				console::print_label(service.id(), Style::Dim, format!("no token ({hint})"));
+			}
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::cli::cmd::VerifySumsArgs;
+use crate::cli::exec_source::porcelain_version;
+use crate::handlers::checksums;
+use crate::support::console::{self, Style};
+use crate::support::{files, porcelain};
+use crate::{Error, Result};
+use simple_fs::SPath;
+
+pub fn exec_verify_sums(args: VerifySumsArgs) -> Result<()> {
+	let path = SPath::new(files::expand_path(&args.path)?);
+	let porcelain = porcelain_version(args.porcelain.as_deref())?;
+	let verification = checksums::verify_sha256_sums(&path)?;
+
+	if let Some(version) = porcelain {
+		porcelain::print_header(version);
+		let records = [
+			("ok", &verification.ok),
+			("mismatch", &verification.mismatched),
+			("missing", &verification.missing),
+		];
+		for (record_type, files) in records {
+			for file in files {
+				porcelain::print_record(record_type, &[file]);
+			}
+		}
+	} else if args.json {
+		println!("{}", serde_json::to_string_pretty(&verification)?);
+	} else {
+		for file in &verification.mismatched {
+			console::print_label("Mismatch", Style::Red, file);
+		}
+		for file in &verification.missing {
+			console::print_label("Missing", Style::Red, file);
+		}
+		if verification.is_ok() {
+			console::print_label("OK", Style::Green, format!("{} file(s) verified", verification.ok.len()));
+		}
+	}
+
+	if !verification.is_ok() {
+		return Err(Error::ValidationFailed(format!(
+			"{} mismatched and {} missing file(s) in '{}'",
+			verification.mismatched.len(),
+			verification.missing.len(),
+			verification.sums_file
+		)));
+	}
+
+	Ok(())
+}
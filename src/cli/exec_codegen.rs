@@ -0,0 +1,36 @@
+use crate::Result;
+use crate::cli::cmd::{CodegenCommand, CodegenTemplatesArgs};
+use crate::handlers::codegen;
+use crate::support::console::{self, Style};
+use crate::support::files;
+use simple_fs::{SPath, ensure_dir};
+
+pub fn exec_command(command: CodegenCommand) -> Result<()> {
+	match command {
+		CodegenCommand::Templates(args) => exec_templates(args),
+	}
+}
+
+fn exec_templates(args: CodegenTemplatesArgs) -> Result<()> {
+	let Some(output) = args.output else {
+		for (name, _) in codegen::builtin_templates() {
+			println!("{name}");
+		}
+		return Ok(());
+	};
+
+	let output_dir = SPath::new(files::expand_path(&output)?);
+	ensure_dir(output_dir.as_std_path()).map_err(|e| format!("Failed to create directory '{output_dir}': {e}"))?;
+
+	for (name, content) in codegen::builtin_templates() {
+		let file = output_dir.join(name);
+		if file.exists() && !args.force {
+			console::print_label("Skipped", Style::Dim, format!("{file} (exists, use --force to overwrite)"));
+			continue;
+		}
+		std::fs::write(file.as_std_path(), content).map_err(|e| format!("Failed to write template '{file}': {e}"))?;
+		console::print_label("Written", Style::Green, file);
+	}
+
+	Ok(())
+}
@@ -0,0 +1,115 @@
+use crate::cli::cmd::{ConfigCommand, ConfigInitArgs, ConfigSchemaArgs, ConfigValidateArgs};
+use crate::handlers::config::{self, CONFIG_FILE_NAME, InitJob, ProjectConfig};
+use crate::support::console::{self, Stream, Style};
+use crate::support::files;
+use crate::{Error, Result};
+use simple_fs::{SPath, ensure_dir};
+use std::io::{BufRead as _, IsTerminal as _, Write as _};
+
+pub fn exec_command(command: ConfigCommand) -> Result<()> {
+	match command {
+		ConfigCommand::Schema(args) => exec_schema(args),
+		ConfigCommand::Validate(args) => exec_validate(args),
+		ConfigCommand::Init(args) => exec_init(args),
+	}
+}
+
+fn exec_schema(args: ConfigSchemaArgs) -> Result<()> {
+	let content = serde_json::to_string_pretty(&config::config_schema())?;
+
+	match args.output {
+		Some(output) => {
+			let output = SPath::new(files::expand_path(&output)?);
+			if let Some(parent) = output.parent() {
+				ensure_dir(parent.as_std_path())
+					.map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+			}
+			std::fs::write(output.as_std_path(), format!("{content}\n"))
+				.map_err(|e| format!("Failed to write schema '{}': {e}", output))?;
+			console::print_label("Schema", Style::Cyan, output);
+		}
+		None => println!("{content}"),
+	}
+
+	Ok(())
+}
+
+fn exec_validate(args: ConfigValidateArgs) -> Result<()> {
+	let config_file = SPath::new(files::expand_path(args.config.as_deref().unwrap_or(CONFIG_FILE_NAME))?);
+	let project = ProjectConfig::load(&config_file)?;
+
+	let status = console::paint("valid", Style::Green, Stream::Stdout);
+	println!("{config_file}: {status} ({} job(s))", project.config.jobs.len());
+
+	Ok(())
+}
+
+fn exec_init(args: ConfigInitArgs) -> Result<()> {
+	let config_file = SPath::new(CONFIG_FILE_NAME);
+	if config_file.exists() && !args.force {
+		return Err(Error::custom(format!("'{config_file}' already exists (use --force to overwrite)")));
+	}
+
+	let current_dir = std::env::current_dir().map_err(Error::custom_from_err)?;
+	let current_dir = SPath::from_std_path(current_dir).map_err(Error::custom_from_err)?;
+
+	let sketch_files = config::find_sketch_files(&current_dir)?;
+	if sketch_files.is_empty() {
+		return Err(Error::custom(format!("No .sketch files found in '{current_dir}'")));
+	}
+	let output_base = config::guess_output_base(&current_dir);
+
+	let interactive = !args.yes && std::io::stdin().is_terminal();
+
+	let mut jobs: Vec<InitJob> = Vec::new();
+	for sketch_file in &sketch_files {
+		let mut job = config::default_init_job(sketch_file, &output_base);
+		if interactive {
+			if !prompt_yes_no(&format!("Add a job for '{sketch_file}'?"), true)? {
+				continue;
+			}
+			job.name = prompt("  Job name", &job.name)?;
+			let formats = prompt("  Formats (svg-symbols, svg, png, ...)", &job.formats.join(","))?;
+			job.formats = formats
+				.split(',')
+				.map(|f| f.trim().to_string())
+				.filter(|f| !f.is_empty())
+				.collect();
+			job.output = prompt("  Output", &job.output)?;
+		}
+		jobs.push(job);
+	}
+
+	let content = config::render_init_config(&jobs)?;
+	std::fs::write(config_file.as_std_path(), &content).map_err(|e| format!("Failed to write '{config_file}': {e}"))?;
+
+	console::print_label(
+		"Created",
+		Style::Green,
+		format!("{config_file} ({} job(s)). Run it with: webtk run", jobs.len()),
+	);
+
+	Ok(())
+}
+
+/// Asks a question on stdin, returning the answer, or `default` when empty.
+fn prompt(question: &str, default: &str) -> Result<String> {
+	print!("{question} [{default}]: ");
+	std::io::stdout().flush().map_err(Error::custom_from_err)?;
+
+	let mut answer = String::new();
+	std::io::stdin().lock().read_line(&mut answer).map_err(Error::custom_from_err)?;
+	let answer = answer.trim();
+
+	Ok(if answer.is_empty() { default.to_string() } else { answer.to_string() })
+}
+
+fn prompt_yes_no(question: &str, default: bool) -> Result<bool> {
+	let hint = if default { "Y/n" } else { "y/N" };
+	let answer = prompt(question, hint)?;
+	Ok(match answer.to_lowercase().as_str() {
+		"y" | "yes" => true,
+		"n" | "no" => false,
+		_ => default,
+	})
+}
@@ -0,0 +1,17 @@
+use crate::Result;
+use crate::cli::cmd::DaemonArgs;
+use crate::handlers::daemon::{self, DEFAULT_DAEMON_SOCKET};
+use crate::support::files;
+use simple_fs::SPath;
+
+pub fn exec_daemon(args: DaemonArgs) -> Result<()> {
+	let socket_path = SPath::new(files::expand_path(args.socket.as_deref().unwrap_or(DEFAULT_DAEMON_SOCKET))?);
+
+	daemon::serve_daemon(&socket_path, || {
+		println!("Listening on {socket_path} (JSON-RPC 2.0, one request per line)");
+	})?;
+
+	println!("Daemon stopped");
+
+	Ok(())
+}
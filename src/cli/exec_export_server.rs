@@ -0,0 +1,32 @@
+use crate::Result;
+use crate::cli::cmd::ExportServerArgs;
+use crate::handlers::export_server::{
+	self, DEFAULT_EXPORT_SERVER_ADDR, DEFAULT_EXPORT_SERVER_DIR, ExportServerOptions,
+};
+use crate::support::console::{self, Style};
+use crate::support::files;
+use simple_fs::SPath;
+
+pub fn exec_export_server(args: ExportServerArgs) -> Result<()> {
+	let options = ExportServerOptions {
+		work_dir: SPath::new(files::expand_path(args.work_dir.as_deref().unwrap_or(DEFAULT_EXPORT_SERVER_DIR))?),
+	};
+	let addr = args.addr.as_deref().unwrap_or(DEFAULT_EXPORT_SERVER_ADDR);
+
+	export_server::serve_exports(
+		addr,
+		&options,
+		|| println!("Listening on http://{addr} (webtk --export-server http://<this host>:<port> ...)"),
+		|job| match job {
+			Ok(job) => {
+				let message = format!("{} {} ({}ms)", job.command, job.file, job.duration_ms);
+				if job.success {
+					console::print_label("Ran", Style::Green, message);
+				} else {
+					console::print_label("Failed", Style::Red, message);
+				}
+			}
+			Err(message) => console::eprint_error(message),
+		},
+	)
+}
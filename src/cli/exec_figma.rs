@@ -0,0 +1,52 @@
+use crate::cli::cmd::{FigmaCommand, FigmaExportArgs, FigmaListArgs};
+use crate::cli::exec_source;
+use crate::handlers::figma::{self, FigmaClient, FigmaExportOptions, FigmaImageFormat, FigmaSource};
+use crate::support::console::{self, Style};
+use crate::support::files;
+use crate::{Error, Result};
+use simple_fs::SPath;
+
+pub fn exec_command(command: FigmaCommand) -> Result<()> {
+	match command {
+		FigmaCommand::List(args) => exec_list(args),
+		FigmaCommand::Export(args) => exec_export(args),
+	}
+}
+
+fn exec_list(args: FigmaListArgs) -> Result<()> {
+	let figma_source = FigmaSource::new(FigmaClient::from_auth()?, file_key(&args.file)?);
+	exec_source::print_items(&figma_source, &args.glob, true, None)
+}
+
+fn exec_export(args: FigmaExportArgs) -> Result<()> {
+	let file_key = file_key(&args.file)?;
+	if !(0.01..=4.0).contains(&args.scale) {
+		return Err(Error::custom(format!("Invalid scale '{}'. Expected 0.01 to 4", args.scale)));
+	}
+
+	let client = FigmaClient::from_auth()?;
+	let options = FigmaExportOptions {
+		globs: args.glob,
+		ids: args.ids,
+		format: FigmaImageFormat::try_from(args.format.as_str())?,
+		scale: args.scale,
+		output_dir: SPath::new(files::expand_path(&args.output)?),
+	};
+
+	let report = figma::export_frames(&client, &file_key, &options)?;
+
+	for file in &report.exported_files {
+		console::print_label("Exported", Style::Green, file);
+	}
+	for name in &report.failed {
+		console::eprint_warning(format!("Figma could not render '{name}'"));
+	}
+
+	Ok(())
+}
+
+fn file_key(file: &str) -> Result<String> {
+	figma::parse_file_key(file).ok_or_else(|| {
+		Error::custom(format!("Invalid Figma file '{file}'. Expected a file key or a figma.com file URL"))
+	})
+}
@@ -0,0 +1,34 @@
+use crate::Result;
+use crate::cli::cmd::{GenCommand, LogoPackArgs};
+use crate::handlers::logo::{self, LogoPackOptions};
+use crate::support::console::{self, Style};
+use crate::support::files;
+use simple_fs::SPath;
+
+pub fn exec_command(command: GenCommand) -> Result<()> {
+	match command {
+		GenCommand::LogoPack(args) => exec_logo_pack(args),
+	}
+}
+
+fn exec_logo_pack(args: LogoPackArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.input)?);
+	let output_dir = SPath::new(files::expand_path(&args.output)?);
+	let globs: Vec<&str> = args.glob.iter().map(String::as_str).collect();
+
+	let mut options =
+		LogoPackOptions { mono_color: args.mono_color, inverse_color: args.inverse_color, ..Default::default() };
+	if !args.png_widths.is_empty() {
+		options.png_widths = args.png_widths;
+	}
+
+	let report = logo::generate_logo_pack(&sketch_file, Some(&globs), &output_dir, &options)?;
+
+	console::print_label(
+		"Written",
+		Style::Green,
+		format!("{} ({} logo(s), {} file(s))", report.manifest_file, report.logo_count, report.files.len()),
+	);
+
+	Ok(())
+}
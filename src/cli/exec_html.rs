@@ -0,0 +1,52 @@
+use crate::cli::cmd::{HtmlCheckLinksArgs, HtmlCommand};
+use crate::handlers::html::{self, LinkCheckOptions};
+use crate::support::console::{self, Style};
+use crate::support::files;
+use crate::{Error, Result};
+use simple_fs::SPath;
+
+pub fn exec_command(command: HtmlCommand) -> Result<()> {
+	match command {
+		HtmlCommand::CheckLinks(args) => exec_check_links(args),
+	}
+}
+
+fn exec_check_links(args: HtmlCheckLinksArgs) -> Result<()> {
+	let root_dir = SPath::new(files::expand_path(&args.dir)?);
+	let options = LinkCheckOptions { external: args.external, concurrency: args.concurrency };
+	let report = html::check_links(&root_dir, &options)?;
+
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+	} else {
+		for link in &report.broken {
+			console::print_label(
+				"Broken",
+				Style::Red,
+				format!("{}:{} {}=\"{}\" ({})", link.file, link.line, link.attr, link.url, link.reason),
+			);
+		}
+		let external = if args.external {
+			format!("{} external URL(s) checked", report.external_count)
+		} else {
+			format!("{} external URL(s) not checked (use --external)", report.external_count)
+		};
+		let style = if report.broken.is_empty() { Style::Green } else { Style::Yellow };
+		console::print_label(
+			"Links",
+			style,
+			format!(
+				"{} broken of {} link(s) in {} file(s), {external}",
+				report.broken.len(),
+				report.link_count,
+				report.file_count
+			),
+		);
+	}
+
+	if !report.broken.is_empty() {
+		return Err(Error::ValidationFailed(format!("{} broken link(s) found", report.broken.len())));
+	}
+
+	Ok(())
+}
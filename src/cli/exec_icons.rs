@@ -0,0 +1,36 @@
+use crate::Result;
+use crate::cli::cmd::IconsCommand;
+use crate::handlers::icons::{self, ConflictPolicy};
+use crate::support::console::{self, Style};
+use crate::support::{files, http};
+use simple_fs::SPath;
+
+pub fn exec_command(command: IconsCommand) -> Result<()> {
+	match command {
+		IconsCommand::Merge(args) => exec_merge(&args.set_a, &args.set_b, &args.output, &args.on_conflict),
+	}
+}
+
+fn exec_merge(set_a: &str, set_b: &str, output: &str, on_conflict: &str) -> Result<()> {
+	let on_conflict = ConflictPolicy::try_from(on_conflict)?;
+
+	let report = icons::merge_icon_sets(
+		http::input_path(set_a)?,
+		http::input_path(set_b)?,
+		SPath::new(files::expand_path(output)?),
+		on_conflict,
+	)?;
+
+	for name in &report.conflicts {
+		let kept = if on_conflict == ConflictPolicy::PreferB { set_b } else { set_a };
+		console::print_label("Conflict", Style::Yellow, format!("{name} (kept from '{kept}')"));
+	}
+
+	for (name_b, name_a) in &report.duplicates {
+		console::print_label("Deduplicated", Style::Dim, format!("{name_b} (same content as {name_a})"));
+	}
+
+	console::print_label(&format!("Merged {} icons into", report.icon_count), Style::Green, output);
+
+	Ok(())
+}
@@ -0,0 +1,90 @@
+use crate::Result;
+use crate::cli::cmd::{ImgCommand, ImgModernizeArgs};
+use crate::handlers::img::{self, ModernFormat, ModernizeOptions};
+use crate::support::console::{self, Style};
+use crate::support::{files, strings};
+use simple_fs::SPath;
+
+pub fn exec_command(command: ImgCommand) -> Result<()> {
+	match command {
+		ImgCommand::Modernize(args) => exec_modernize(args),
+	}
+}
+
+fn exec_modernize(args: ImgModernizeArgs) -> Result<()> {
+	let mut formats: Vec<ModernFormat> = Vec::new();
+	for format in &args.format {
+		let format = ModernFormat::try_from(format.as_str())?;
+		if !formats.contains(&format) {
+			formats.push(format);
+		}
+	}
+	let out_dir = args.output.as_deref().map(files::expand_path).transpose()?.map(SPath::new);
+	let globs = args
+		.globs
+		.iter()
+		.map(|glob| files::expand_path(glob))
+		.collect::<Result<Vec<_>>>()?;
+	let report = img::modernize_images(&globs, &ModernizeOptions { formats, out_dir })?;
+
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+		return Ok(());
+	}
+
+	for skipped in &report.skipped {
+		console::print_label("Skipped", Style::Dim, format!("{} ({})", skipped.file, skipped.reason));
+	}
+	let mut total_bytes = 0;
+	let mut best_bytes = 0;
+	for image in &report.images {
+		let sizes: Vec<String> = image
+			.outputs
+			.iter()
+			.map(|output| {
+				format!(
+					"{} {} ({})",
+					output.format,
+					strings::format_bytes(output.bytes),
+					size_change(image.bytes, output.bytes)
+				)
+			})
+			.collect();
+		let smallest = image.outputs.iter().map(|output| output.bytes).min().unwrap_or(image.bytes);
+		// A conversion larger than the image (e.g., a tiny pixel art GIF) is not worth it
+		let style = if smallest < image.bytes { Style::Green } else { Style::Yellow };
+		console::print_label(
+			"Converted",
+			style,
+			format!("{} {} -> {}", image.file, strings::format_bytes(image.bytes), sizes.join(", ")),
+		);
+		total_bytes += image.bytes;
+		best_bytes += smallest;
+	}
+	console::print_label(
+		"Images",
+		Style::Green,
+		format!(
+			"{} converted, {} -> {} with the smallest format ({})",
+			report.images.len(),
+			strings::format_bytes(total_bytes),
+			strings::format_bytes(best_bytes),
+			size_change(total_bytes, best_bytes)
+		),
+	);
+
+	Ok(())
+}
+
+// region:    --- Support
+
+/// Returns the size change as a percentage of the original (e.g., "-82%", "+3%").
+fn size_change(original: u64, converted: u64) -> String {
+	if original == 0 {
+		return "n/a".to_string();
+	}
+	let change = (converted as f64 - original as f64) / original as f64 * 100.0;
+	format!("{change:+.0}%")
+}
+
+// endregion: --- Support
@@ -0,0 +1,68 @@
+use crate::cli::cmd::{LottieCheckArgs, LottieCommand};
+use crate::handlers::lottie::{self, LottieCheckOptions};
+use crate::support::console::{self, Style};
+use crate::support::{files, strings};
+use crate::{Error, Result};
+
+pub fn exec_command(command: LottieCommand) -> Result<()> {
+	match command {
+		LottieCommand::Check(args) => exec_check(args),
+	}
+}
+
+fn exec_check(args: LottieCheckArgs) -> Result<()> {
+	let max_bytes = args
+		.max_size
+		.as_deref()
+		.map(|size| {
+			strings::parse_bytes(size).ok_or_else(|| {
+				Error::custom(format!("Invalid max size '{size}'. Expected bytes or a size (e.g., 200KB)"))
+			})
+		})
+		.transpose()?;
+	let globs = args
+		.globs
+		.iter()
+		.map(|glob| files::expand_path(glob))
+		.collect::<Result<Vec<_>>>()?;
+	let options = LottieCheckOptions { max_bytes, optimize: args.optimize };
+	let report = lottie::check_lottie_files(&globs, &options)?;
+
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+	} else {
+		for file in report
+			.files
+			.iter()
+			.filter(|file| file.optimized_bytes.is_some_and(|bytes| bytes < file.bytes))
+		{
+			let optimized_bytes = file.optimized_bytes.unwrap_or(file.bytes);
+			console::print_label(
+				"Optimized",
+				Style::Cyan,
+				format!(
+					"{} {} -> {} ({} hidden layer(s) removed)",
+					file.file,
+					strings::format_bytes(file.bytes),
+					strings::format_bytes(optimized_bytes),
+					file.hidden_layers_removed
+				),
+			);
+		}
+		for issue in &report.issues {
+			console::print_label("Issue", Style::Red, format!("{} [{}] {}", issue.file, issue.code, issue.message));
+		}
+		let style = if report.issues.is_empty() { Style::Green } else { Style::Yellow };
+		console::print_label(
+			"Lottie",
+			style,
+			format!("{} issue(s) in {} file(s)", report.issues.len(), report.files.len()),
+		);
+	}
+
+	if !report.issues.is_empty() {
+		return Err(Error::ValidationFailed(format!("{} Lottie issue(s) found", report.issues.len())));
+	}
+
+	Ok(())
+}
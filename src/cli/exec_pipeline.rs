@@ -0,0 +1,39 @@
+use crate::Result;
+use crate::cli::cmd::{PipelineCommand, PipelineGraphArgs};
+use crate::handlers::config;
+use crate::handlers::run::{self, GraphFormat};
+use crate::support::console::{self, Style};
+use crate::support::files;
+use simple_fs::{SPath, ensure_dir};
+
+pub fn exec_command(command: PipelineCommand) -> Result<()> {
+	match command {
+		PipelineCommand::Graph(args) => exec_graph(args),
+	}
+}
+
+fn exec_graph(mut args: PipelineGraphArgs) -> Result<()> {
+	if let Some(config) = args.config.as_mut() {
+		*config = files::expand_path(config)?;
+	}
+	let projects = config::load_projects(args.config.as_deref(), args.all)?;
+	let format = GraphFormat::try_from(args.format.as_str())?;
+	let graphs = run::build_pipeline_graphs(&projects, &args.pipelines)?;
+	let content = run::format_pipeline_graph(&graphs, format);
+
+	match args.output {
+		Some(output) => {
+			let output = SPath::new(files::expand_path(&output)?);
+			if let Some(parent) = output.parent() {
+				ensure_dir(parent.as_std_path())
+					.map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+			}
+			std::fs::write(output.as_std_path(), &content)
+				.map_err(|e| format!("Failed to write graph '{}': {e}", output))?;
+			console::print_label("Written", Style::Green, output);
+		}
+		None => print!("{content}"),
+	}
+
+	Ok(())
+}
@@ -0,0 +1,343 @@
+use crate::cli::cmd::RunArgs;
+use crate::cli::exec_sketch::{print_export_plan, print_exported_files};
+use crate::cli::exec_source::porcelain_version;
+use crate::handlers::config::{self, ProjectConfig};
+use crate::handlers::notify::{self, NotifySettings};
+use crate::handlers::run::{self, JobRunReport, PipelineCache, PipelineRunReport, RunOptions, RunReport, StepStatus};
+use crate::support::console::{self, Stream, Style};
+use crate::support::files;
+use crate::support::porcelain::{self, PorcelainVersion};
+use crate::{Error, Result};
+use simple_fs::SPath;
+
+pub fn exec_run(mut args: RunArgs) -> Result<()> {
+	for path in [&mut args.config, &mut args.report].into_iter().flatten() {
+		*path = files::expand_path(path)?;
+	}
+	let projects = config::load_projects(args.config.as_deref(), args.all)?;
+
+	if args.plan {
+		return exec_run_plan(&projects, &args.jobs, args.json);
+	}
+
+	if !args.pipeline.is_empty() {
+		return exec_run_pipelines(&projects, &args);
+	}
+
+	if args.watch {
+		return exec_run_watch(&projects, &args);
+	}
+
+	let porcelain = porcelain_version(args.porcelain.as_deref())?;
+	let on_job_end = |project: &ProjectConfig, job: &JobRunReport| print_job_end(&projects, project, job);
+	let options =
+		RunOptions { on_job_end: if porcelain.is_none() { Some(&on_job_end) } else { None }, ..run_options(&args) };
+	let report = run::run_projects(&projects, &args.jobs, &options)?;
+	if let Some(version) = porcelain {
+		print_run_records(&report, version);
+	} else {
+		println!();
+		print_run_report(&report, args.list_files);
+	}
+
+	let job_count = report.jobs().count();
+	let failed_count = report.failed_count();
+	let warning_count = report.warning_count();
+
+	if let Some(report_file) = args.report {
+		let report_file = SPath::new(report_file);
+		report.write(&report_file)?;
+		if porcelain.is_none() {
+			console::print_label("Report", Style::Cyan, report_file);
+		}
+	}
+
+	if job_count == 0 {
+		return Err(Error::custom("No jobs to run"));
+	}
+
+	if failed_count > 0 {
+		let cause = format!("{failed_count} of {job_count} job(s) failed");
+		return if failed_count < job_count {
+			Err(Error::PartialFailure { cause, exported_files: report.exported_files() })
+		} else {
+			Err(Error::custom(cause))
+		};
+	}
+
+	if args.deny_warnings && warning_count > 0 {
+		return Err(Error::ValidationFailed(format!("{warning_count} warning(s) found")));
+	}
+
+	let duplicate_count = report.duplicate_count();
+	if args.deny_duplicates && duplicate_count > 0 {
+		return Err(Error::ValidationFailed(format!("{duplicate_count} set(s) of artboards render identically")));
+	}
+
+	Ok(())
+}
+
+/// Prints the export plans of the jobs (`run --plan`), without exporting.
+fn exec_run_plan(projects: &[ProjectConfig], job_names: &[String], json: bool) -> Result<()> {
+	let job_plans = run::plan_projects(projects, job_names)?;
+
+	if json {
+		println!("{}", serde_json::to_string_pretty(&job_plans)?);
+	} else {
+		let mut config_file = None;
+		for job_plan in &job_plans {
+			if config_file != Some(&job_plan.config_file) {
+				println!("{}", console::paint(format!("== {}", job_plan.config_file), Style::Bold, Stream::Stdout));
+				config_file = Some(&job_plan.config_file);
+			}
+			for export in &job_plan.exports {
+				println!("-- {} ({})", job_plan.name, export.label);
+				print_export_plan(&export.plan);
+				println!();
+			}
+		}
+	}
+
+	if job_plans.is_empty() {
+		return Err(Error::custom("No jobs to run"));
+	}
+
+	Ok(())
+}
+
+/// Runs the jobs, then re-runs them on each Sketch file save (`run --watch`), notifying the results.
+/// Failures are printed (and notified), the watch goes on.
+fn exec_run_watch(projects: &[ProjectConfig], args: &RunArgs) -> Result<()> {
+	let notify_section = projects.first().and_then(|project| project.config.notify.as_ref());
+	let mut settings = NotifySettings {
+		desktop: args.notify || notify_section.is_some_and(|notify| notify.desktop),
+		webhook: args
+			.webhook
+			.clone()
+			.or_else(|| notify_section.and_then(|notify| notify.webhook.clone())),
+	};
+	if let Some(webhook) = &settings.webhook
+		&& !(webhook.starts_with("https://") || webhook.starts_with("http://"))
+	{
+		return Err(Error::custom(format!("Webhook '{webhook}' is not an http(s) url")));
+	}
+	if settings.desktop && !notify::desktop_notifications_supported() {
+		console::eprint_warning("Desktop notifications are only supported on macOS (disabled)");
+		settings.desktop = false;
+	}
+
+	run::watch_projects(projects, &args.jobs, &run_options(args), |report, changed_files| {
+		for file in changed_files {
+			println!();
+			console::print_label("Changed", Style::Cyan, file);
+		}
+		print_run_report(report, args.list_files);
+
+		if let Some(report_file) = &args.report
+			&& let Err(err) = report.write(&SPath::new(report_file))
+		{
+			console::eprint_error(err);
+		}
+
+		if settings.is_enabled() {
+			let notification = notify::run_notification(report);
+			for error in notify::send_notification(&settings, &notification) {
+				console::eprint_warning(error);
+			}
+		}
+
+		println!("{}", console::paint("Watching for changes (Ctrl+C to stop)...", Style::Dim, Stream::Stdout));
+	})
+}
+
+/// Runs the pipelines (`run --pipeline`), once, or on each batch of changes with `--watch` (failures are printed,
+/// the watch goes on).
+fn exec_run_pipelines(projects: &[ProjectConfig], args: &RunArgs) -> Result<()> {
+	if args.watch {
+		return run::watch_pipelines(projects, &args.pipeline, &run_options(args), |reports| {
+			println!();
+			print_pipeline_reports(reports, args.list_files);
+			println!("{}", console::paint("Watching for changes (Ctrl+C to stop)...", Style::Dim, Stream::Stdout));
+		});
+	}
+
+	let reports = run::run_pipelines(projects, &args.pipeline, &mut PipelineCache::default(), &run_options(args))?;
+	print_pipeline_reports(&reports, args.list_files);
+
+	let failed_count: usize = reports.iter().map(|report| report.count(StepStatus::Failed)).sum();
+	if failed_count > 0 {
+		return Err(Error::custom(format!("{failed_count} pipeline step(s) failed")));
+	}
+
+	let warning_count: usize = reports
+		.iter()
+		.flat_map(|report| &report.steps)
+		.filter_map(|step| step.report.as_ref())
+		.map(|report| report.warnings.len())
+		.sum();
+	if args.deny_warnings && warning_count > 0 {
+		return Err(Error::ValidationFailed(format!("{warning_count} warning(s) found")));
+	}
+
+	Ok(())
+}
+
+/// Prints the steps of the pipelines (e.g., "[assets/export] ran, 24 file(s) in 3.1s"), the exported files and
+/// warnings of the job steps, then the summary line.
+fn print_pipeline_reports(reports: &[PipelineRunReport], list_files: bool) {
+	for report in reports {
+		for step in &report.steps {
+			let prefix = console::paint(format!("[{}/{}]", report.name, step.name), Style::Bold, Stream::Stdout);
+			let duration = format!("{:.1}s", step.duration_ms as f64 / 1000.0);
+			match step.status {
+				StepStatus::Ran => {
+					let files = step
+						.report
+						.as_ref()
+						.map(|report| format!(", {} file(s)", report.exported_files.len()))
+						.unwrap_or_default();
+					println!("{prefix} {}{files} in {duration}", console::paint("ran", Style::Green, Stream::Stdout));
+				}
+				StepStatus::Cached => println!("{prefix} {}", console::paint("cached", Style::Dim, Stream::Stdout)),
+				StepStatus::Skipped => {
+					println!("{prefix} {}", console::paint("skipped", Style::Yellow, Stream::Stdout))
+				}
+				StepStatus::Failed => {
+					println!("{prefix} {} in {duration}", console::paint("failed", Style::Red, Stream::Stdout));
+					console::eprint_error(step.error.as_deref().unwrap_or_default());
+				}
+			}
+			if let Some(job_report) = &step.report {
+				print_exported_files(job_report, list_files, "    ");
+				for warning in &job_report.warnings {
+					console::eprint_warning(warning);
+				}
+			}
+		}
+	}
+
+	let count = |status: StepStatus| reports.iter().map(|report| report.count(status)).sum::<usize>();
+	let failed_count = count(StepStatus::Failed);
+	let failed_style = if failed_count > 0 { Style::Red } else { Style::Green };
+	println!(
+		"\n{} pipeline(s), {} step(s) ran, {} cached, {}",
+		reports.len(),
+		count(StepStatus::Ran),
+		count(StepStatus::Cached),
+		console::paint(format!("{failed_count} failed"), failed_style, Stream::Stdout)
+	);
+}
+
+fn run_options(args: &RunArgs) -> RunOptions<'static> {
+	let parallel = args
+		.parallel
+		.map(|parallel| parallel as usize)
+		.unwrap_or_else(run::default_parallel_jobs);
+	RunOptions { parallel, on_job_end: None, symbols_cache: false }
+}
+
+/// Prints the end of a job as it happens, prefixed with the job (and its project directory, for several projects),
+/// e.g., "[icons] ok, 24 file(s) in 3.1s".
+fn print_job_end(projects: &[ProjectConfig], project: &ProjectConfig, job: &JobRunReport) {
+	let prefix = if projects.len() > 1 {
+		format!("[{}/{}]", project.base_dir.name(), job.name)
+	} else {
+		format!("[{}]", job.name)
+	};
+	let duration = format!("{:.1}s", job.duration_ms as f64 / 1000.0);
+	match (&job.report, &job.error) {
+		(Some(report), _) => println!(
+			"{} {}, {} file(s) in {duration}",
+			console::paint(prefix, Style::Bold, Stream::Stdout),
+			console::paint("ok", Style::Green, Stream::Stdout),
+			report.exported_files.len()
+		),
+		(None, error) => println!(
+			"{} {} in {duration}: {}",
+			console::paint(prefix, Style::Bold, Stream::Stdout),
+			console::paint("failed", Style::Red, Stream::Stdout),
+			error.as_deref().and_then(|error| error.lines().next()).unwrap_or_default()
+		),
+	}
+}
+
+/// Prints the exported files (see `print_exported_files`), warnings, and errors of the jobs, then the summary line.
+fn print_run_report(report: &RunReport, list_files: bool) {
+	for project in &report.projects {
+		if project.jobs.is_empty() {
+			continue;
+		}
+		println!("{}", console::paint(format!("== {}", project.config_file), Style::Bold, Stream::Stdout));
+		for job in &project.jobs {
+			if let Some(job_report) = &job.report {
+				let status = console::paint("ok", Style::Green, Stream::Stdout);
+				println!("-- {} ({status}, {} file(s))", job.name, job_report.exported_files.len());
+				print_exported_files(job_report, list_files, "    ");
+				for warning in &job_report.warnings {
+					console::eprint_warning(warning);
+				}
+			}
+			if let Some(error) = &job.error {
+				println!("-- {} ({})", job.name, console::paint("failed", Style::Red, Stream::Stdout));
+				console::eprint_error(error);
+			}
+		}
+	}
+
+	let job_count = report.jobs().count();
+	let failed_count = report.failed_count();
+	let warning_count = report.warning_count();
+	let failed_style = if failed_count > 0 { Style::Red } else { Style::Green };
+	let warning_style = if warning_count > 0 { Style::Yellow } else { Style::Green };
+	println!(
+		"\n{job_count} job(s), {}, {}",
+		console::paint(format!("{failed_count} failed"), failed_style, Stream::Stdout),
+		console::paint(format!("{warning_count} warning(s)"), warning_style, Stream::Stdout)
+	);
+
+	// The failures together, as the job outputs above can be long
+	for project in &report.projects {
+		for job in &project.jobs {
+			if let Some(error) = &job.error {
+				let first_line = error.lines().next().unwrap_or_default();
+				let name = if report.projects.len() > 1 {
+					format!("{} ({})", job.name, project.config_file)
+				} else {
+					job.name.clone()
+				};
+				console::print_label("Failed", Style::Red, format!("{name}: {first_line}"));
+			}
+		}
+	}
+}
+
+/// Prints the jobs of the run as porcelain records: `job <config file> <job> ok|failed <file count>`, followed by
+/// the `file`, `warning`, and `error` records of the job (`<config file> <job>`, then the path, the warning code,
+/// artboard, and message, or the error).
+fn print_run_records(report: &RunReport, version: PorcelainVersion) {
+	porcelain::print_header(version);
+	for project in &report.projects {
+		for job in &project.jobs {
+			let (config_file, name) = (project.config_file.as_str(), job.name.as_str());
+			let status = if job.error.is_some() { "failed" } else { "ok" };
+			let files = job
+				.report
+				.as_ref()
+				.map(|report| report.exported_files.as_slice())
+				.unwrap_or_default();
+			let file_count = files.len().to_string();
+			porcelain::print_record("job", &[config_file, name, status, &file_count]);
+			for file in files {
+				porcelain::print_record("file", &[config_file, name, file]);
+			}
+			for warning in job.report.iter().flat_map(|report| &report.warnings) {
+				let code = warning.code.to_string();
+				let artboard = warning.artboard.as_deref().unwrap_or_default();
+				porcelain::print_record("warning", &[config_file, name, &code, artboard, &warning.message]);
+			}
+			if let Some(error) = &job.error {
+				porcelain::print_record("error", &[config_file, name, error]);
+			}
+		}
+	}
+}
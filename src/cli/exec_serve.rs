@@ -0,0 +1,40 @@
+use crate::cli::cmd::ServeArgs;
+use crate::handlers::ingest::{self, DEFAULT_INGEST_ADDR, DEFAULT_INGEST_DIR, IngestOptions};
+use crate::support::console::{self, Style};
+use crate::support::files;
+use crate::{Error, Result};
+use simple_fs::SPath;
+
+pub fn exec_serve(args: ServeArgs) -> Result<()> {
+	let (true, Some(output)) = (args.ingest, args.output) else {
+		return Err(Error::custom("Nothing to serve (use --ingest with --output)"));
+	};
+
+	let options = IngestOptions {
+		ingest_dir: SPath::new(files::expand_path(args.ingest_dir.as_deref().unwrap_or(DEFAULT_INGEST_DIR))?),
+		output: SPath::new(files::expand_path(&output)?),
+		symbol_data_attrs: args.symbol_data_attrs,
+		strip_namespaces: args.strip_namespace,
+	};
+	let addr = args.addr.as_deref().unwrap_or(DEFAULT_INGEST_ADDR);
+
+	// Start from the SVGs ingested before (e.g., a previous session)
+	let symbol_ids = ingest::build_ingest_sprite(&options)?;
+	console::print_label("Sprite", Style::Cyan, format!("{} ({} symbol(s))", options.output, symbol_ids.len()));
+
+	ingest::serve_ingest(
+		addr,
+		&options,
+		|| println!("Listening on http://{addr} (POST /ingest?name=<artboard name> with the SVG as body)"),
+		|result| match result {
+			Ok(result) => {
+				let message = format!("{} -> #{} ({} symbol(s))", result.name, result.symbol_id, result.symbol_count);
+				console::print_label("Ingested", Style::Green, message);
+				for warning in &result.warnings {
+					console::eprint_warning(warning);
+				}
+			}
+			Err(message) => console::eprint_error(message),
+		},
+	)
+}
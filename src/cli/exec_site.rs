@@ -0,0 +1,48 @@
+use crate::Result;
+use crate::cli::cmd::{SiteCommand, SiteRobotsArgs, SiteSitemapArgs};
+use crate::handlers::site::{self, ROBOTS_FILE, RobotsOptions, SITEMAP_FILE, SitemapOptions};
+use crate::support::console::{self, Style};
+use crate::support::files;
+use simple_fs::SPath;
+
+pub fn exec_command(command: SiteCommand) -> Result<()> {
+	match command {
+		SiteCommand::Robots(args) => exec_robots(args),
+		SiteCommand::Sitemap(args) => exec_sitemap(args),
+	}
+}
+
+fn exec_robots(args: SiteRobotsArgs) -> Result<()> {
+	let root_dir = SPath::new(files::expand_path(&args.root)?);
+	let robots_file = match &args.output {
+		Some(output) => SPath::new(files::expand_path(output)?),
+		None => root_dir.join(ROBOTS_FILE),
+	};
+
+	let sitemap_url = args.sitemap_url.or_else(|| {
+		args.base_url
+			.map(|base_url| format!("{}/{SITEMAP_FILE}", base_url.trim_end_matches('/')))
+	});
+	let options =
+		RobotsOptions { user_agent: args.user_agent, allow: args.allow, disallow: args.disallow, sitemap_url };
+	site::generate_robots(&robots_file, &options)?;
+
+	console::print_label("Written", Style::Green, &robots_file);
+
+	Ok(())
+}
+
+fn exec_sitemap(args: SiteSitemapArgs) -> Result<()> {
+	let root_dir = SPath::new(files::expand_path(&args.root)?);
+	let sitemap_file = match &args.output {
+		Some(output) => SPath::new(files::expand_path(output)?),
+		None => root_dir.join(SITEMAP_FILE),
+	};
+
+	let options = SitemapOptions { ignore_globs: args.ignore, clean_urls: args.clean_urls };
+	let url_count = site::generate_sitemap(&root_dir, &args.base_url, &sitemap_file, &options)?;
+
+	console::print_label("Written", Style::Green, format!("{sitemap_file} ({url_count} URL(s))"));
+
+	Ok(())
+}
@@ -1,51 +1,772 @@
-use crate::Result;
-use crate::cli::cmd::SketchCommand;
-use crate::handlers::sketch;
+use crate::cli::cmd::{
+	ContactSheetArgs, CopyArgs, ExportArgs, FlowsArgs, FontsArgs, LintArgs, ListArtboardsArgs, PlanArgs, RenameArgs,
+	SetPresetsArgs, SketchCommand, SortArgs, SpecArgs, StatsArgs, SymbolsUsageArgs,
+};
+use crate::cli::exec_source::{self, porcelain_version};
+use crate::handlers::sketch::{
+	self, CopyFormat, ExportOptions, ExportPlan, ExportPreset, ExportReport, FailOn, FlowFormat, GlobScope, LintRule,
+	SketchSource, SortBy, SortOptions,
+};
+use crate::support::console::{self, Stream, Style};
+use crate::support::globs::GlobCase;
+use crate::support::{files, porcelain, strings};
+use crate::{Error, Result};
 use simple_fs::SPath;
+use std::collections::HashSet;
 
 pub fn exec_command(command: SketchCommand) -> Result<()> {
 	match command {
-		SketchCommand::ListArtboards(args) => exec_list_artboards(&args.sketch_file, args.glob),
-		SketchCommand::Export(args) => {
-			exec_export(&args.sketch_file, args.glob, args.format, &args.output, args.flatten, args.keep_raw_export)
+		SketchCommand::ListArtboards(args) => exec_list_artboards(args),
+		SketchCommand::Export(mut args) => {
+			expand_export_paths(&mut args)?;
+			exec_export(*args)
 		}
+		SketchCommand::Plan(mut args) => {
+			expand_export_paths(&mut args.export)?;
+			exec_plan(*args)
+		}
+		SketchCommand::SymbolsUsage(args) => exec_symbols_usage(args),
+		SketchCommand::Stats(args) => exec_stats(args),
+		SketchCommand::Lint(args) => exec_lint(args),
+		SketchCommand::Fonts(args) => exec_fonts(args),
+		SketchCommand::Copy(args) => exec_copy(args),
+		SketchCommand::Flows(args) => exec_flows(args),
+		SketchCommand::Spec(args) => exec_spec(args),
+		SketchCommand::ContactSheet(args) => exec_contact_sheet(args),
+		SketchCommand::Rename(args) => exec_rename(args),
+		SketchCommand::SetPresets(args) => exec_set_presets(args),
+		SketchCommand::Sort(args) => exec_sort(args),
 	}
 }
 
-fn exec_list_artboards(sketch_file: &str, globs: Vec<String>) -> Result<()> {
-	let sketch_file = SPath::new(sketch_file);
-	let glob_refs: Vec<&str> = globs.iter().map(|s| s.as_str()).collect();
+fn exec_list_artboards(args: ListArtboardsArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let glob_scope = args
+		.glob_scope
+		.as_deref()
+		.map(GlobScope::try_from)
+		.transpose()?
+		.unwrap_or_default();
+	let porcelain = porcelain_version(args.porcelain.as_deref())?;
+	let glob_case = match (args.ignore_case, args.smart_case) {
+		(true, _) => GlobCase::Insensitive,
+		(false, true) => GlobCase::Smart,
+		(false, false) => GlobCase::Sensitive,
+	};
+
+	if args.presets {
+		let glob_refs: Vec<&str> = args.glob.iter().map(String::as_str).collect();
+		let artboards = sketch::list_artboards(&sketch_file, Some(&glob_refs), glob_scope, glob_case)?;
+		let presets = sketch::load_export_presets(&sketch_file)?;
+		for artboard in artboards {
+			let id = console::paint(format!("{}:", artboard.uid), Style::Dim, Stream::Stdout);
+			let artboard_presets = match presets.get(&artboard.uid) {
+				Some(presets) => presets.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "),
+				None => "no presets".to_string(),
+			};
+			let artboard_presets = console::paint(format!("({artboard_presets})"), Style::Dim, Stream::Stdout);
+			println!("{id} {} {artboard_presets}", artboard.name);
+		}
+		return Ok(());
+	}
+
+	let sketch_source = SketchSource::new(sketch_file)
+		.with_glob_scope(glob_scope)
+		.with_glob_case(glob_case);
+	exec_source::print_items(&sketch_source, &args.glob, false, porcelain)
+}
+
+fn exec_export(args: ExportArgs) -> Result<()> {
+	let sketch_file = SPath::new(&args.sketch_file);
+	let output_dir = SPath::new(&args.output);
+
+	let glob_refs: Vec<&str> = args.glob.iter().map(|s| s.as_str()).collect();
 	let glob_arg = if glob_refs.is_empty() { None } else { Some(glob_refs.as_slice()) };
-	let artboards = sketch::list_artboards(&sketch_file, glob_arg)?;
 
-	for artboard in artboards {
-		println!("{}: {}", artboard.uid, artboard.name);
+	let format_refs = export_formats(&args);
+
+	let fail_on = fail_on_policy(&args)?;
+	let options = export_options(&args, fail_on);
+	let porcelain = porcelain_version(args.porcelain.as_deref())?;
+
+	let report = sketch::export_artboards(&sketch_file, glob_arg, &format_refs, &output_dir, &options)?;
+
+	if let Some(version) = porcelain {
+		porcelain::print_header(version);
+		for path in &report.exported_files {
+			porcelain::print_record("file", &[path]);
+		}
+		for warning in &report.warnings {
+			let code = warning.code.to_string();
+			porcelain::print_record(
+				"warning",
+				&[&code, warning.artboard.as_deref().unwrap_or_default(), &warning.message],
+			);
+		}
+	} else if args.quiet {
+		for path in &report.exported_files {
+			println!("{path}");
+		}
+	} else {
+		print_exported_files(&report, args.list_files, "");
+	}
+
+	if let Some(git_ref) = &args.changed_since
+		&& porcelain.is_none()
+	{
+		let message = format!(
+			"{} artboard(s) changed since '{git_ref}', {} unchanged (skipped)",
+			report.artboard_count, report.unchanged_count
+		);
+		if args.quiet {
+			eprintln!("Changed: {message}");
+		} else {
+			console::print_label("Changed", Style::Cyan, message);
+		}
+	}
+
+	if porcelain.is_none() {
+		for warning in &report.warnings {
+			console::eprint_warning(warning);
+		}
+	}
+
+	if let Some(report_file) = args.report {
+		let report_file = SPath::new(report_file);
+		report.write(&report_file)?;
+		if args.quiet {
+			eprintln!("Report: {report_file}");
+		} else if porcelain.is_none() {
+			console::print_label("Report", Style::Cyan, report_file);
+		}
+	}
+
+	report.check_fail_on(fail_on)?;
+	if args.deny_duplicates {
+		report.check_duplicates()?;
 	}
 
 	Ok(())
 }
 
-fn exec_export(
-	sketch_file: &str,
-	globs: Vec<String>,
-	formats: Vec<String>,
-	output: &str,
-	flatten: bool,
-	keep_raw_export: bool,
-) -> Result<()> {
-	let sketch_file = SPath::new(sketch_file);
-	let output_dir = SPath::new(output);
+fn exec_plan(args: PlanArgs) -> Result<()> {
+	let export_args = &args.export;
+	let sketch_file = SPath::new(&export_args.sketch_file);
+	let output_dir = SPath::new(&export_args.output);
 
-	let glob_refs: Vec<&str> = globs.iter().map(|s| s.as_str()).collect();
+	let glob_refs: Vec<&str> = export_args.glob.iter().map(|s| s.as_str()).collect();
 	let glob_arg = if glob_refs.is_empty() { None } else { Some(glob_refs.as_slice()) };
 
-	let format_refs: Vec<&str> = formats.iter().map(|s| s.as_str()).collect();
+	let format_refs = export_formats(export_args);
+
+	let options = export_options(export_args, fail_on_policy(export_args)?);
+	let porcelain = porcelain_version(export_args.porcelain.as_deref())?;
+
+	let plan = sketch::plan_export(&sketch_file, glob_arg, &format_refs, &output_dir, &options)?;
 
-	let exported =
-		sketch::export_artboards(&sketch_file, glob_arg, &format_refs, &output_dir, flatten, keep_raw_export)?;
+	if let Some(version) = porcelain {
+		porcelain::print_header(version);
+		let mut printed = HashSet::new();
+		for (_, item) in plan.items() {
+			if printed.insert(&item.target_file) {
+				porcelain::print_record("file", &[&item.target_file]);
+			}
+		}
+		for warning in &plan.warnings {
+			let code = warning.code.to_string();
+			porcelain::print_record(
+				"warning",
+				&[&code, warning.artboard.as_deref().unwrap_or_default(), &warning.message],
+			);
+		}
+	} else if args.json {
+		println!("{}", serde_json::to_string_pretty(&plan)?);
+	} else if export_args.quiet {
+		let mut printed = HashSet::new();
+		for (_, item) in plan.items() {
+			if printed.insert(&item.target_file) {
+				println!("{}", item.target_file);
+			}
+		}
+		for warning in &plan.warnings {
+			console::eprint_warning(warning);
+		}
+	} else {
+		print_export_plan(&plan);
+	}
+
+	Ok(())
+}
+
+/// Expands `~` and the environment variables of the path arguments (see `files::expand_path`).
+fn expand_export_paths(args: &mut ExportArgs) -> Result<()> {
+	args.sketch_file = files::expand_path(&args.sketch_file)?;
+	args.output = files::expand_path(&args.output)?;
+	for path in [
+		&mut args.ts_out,
+		&mut args.rust_out,
+		&mut args.paths_ts_out,
+		&mut args.template_dir,
+		&mut args.extract_images,
+		&mut args.report,
+	]
+	.into_iter()
+	.flatten()
+	{
+		*path = files::expand_path(path)?;
+	}
+	Ok(())
+}
+
+/// Returns the export formats (png for the email-safe exports without --format).
+fn export_formats(args: &ExportArgs) -> Vec<&str> {
+	if args.format.is_empty() && args.email_safe {
+		return vec!["png"];
+	}
+	args.format.iter().map(|s| s.as_str()).collect()
+}
+
+fn fail_on_policy(args: &ExportArgs) -> Result<FailOn> {
+	if args.deny_warnings { Ok(FailOn::Warning) } else { FailOn::try_from(args.fail_on.as_str()) }
+}
+
+fn export_options(args: &ExportArgs, fail_on: FailOn) -> ExportOptions {
+	ExportOptions {
+		flatten: args.flatten,
+		allow_empty: args.allow_empty || fail_on == FailOn::Never,
+		changed_since: args.changed_since.clone(),
+		exclude_globs: args.exclude.clone(),
+		glob_scope: args.glob_scope.clone(),
+		ignore_case: args.ignore_case,
+		smart_case: args.smart_case,
+		scales: args.scales.clone(),
+		use_design_presets: args.use_design_presets,
+		keep_raw_export: args.keep_raw_export,
+		symbol_data_attrs: args.symbol_data_attrs,
+		symbols_manifest: args.symbols_manifest,
+		ts_out: args.ts_out.clone(),
+		ts_type_name: args.ts_type_name.clone(),
+		rust_out: args.rust_out.clone(),
+		use_snippets_out: args.use_snippets_out.clone(),
+		usage_out: args.usage_out.clone(),
+		paths_ts_out: args.paths_ts_out.clone(),
+		template_dir: args.template_dir.clone(),
+		preserve_aspect_ratio: args.preserve_aspect_ratio.clone(),
+		symbol_size: args.symbol_size.clone(),
+		strip_namespaces: args.strip_namespace.clone(),
+		trim: args.trim,
+		group_contents_only: args.group_contents_only,
+		padding: args.padding,
+		background: args.background.clone(),
+		tile: args.tile,
+		tile_overlap: args.tile_overlap,
+		redline: args.redline,
+		email_safe: args.email_safe,
+		email_max_width: args.email_max_width,
+		email_snippet_out: args.email_snippet_out.clone(),
+		cdn_base: args.cdn_base.clone(),
+		cdn_ts_out: args.cdn_ts_out.clone(),
+		pdf_bookmarks: args.pdf_bookmarks,
+		non_scaling_stroke: args.non_scaling_stroke,
+		embed_images: args.embed_images,
+		extract_images: args.extract_images.clone(),
+		local_refs: args.local_refs.clone(),
+		checksums: args.checksums,
+		uid_map: args.uid_map,
+		preserve_mtime: args.preserve_mtime.clone(),
+		chmod: args.chmod.clone(),
+		tool_args: args.tool_arg.clone(),
+		items_batch_size: args.items_batch_size,
+		..Default::default()
+	}
+}
+
+/// Prints the exported files grouped by format (files, total size, directory),
+/// or each file with `list_files`.
+pub(super) fn print_exported_files(report: &ExportReport, list_files: bool, indent: &str) {
+	if list_files {
+		for path in &report.exported_files {
+			console::print_label(&format!("{indent}Exported"), Style::Green, path);
+		}
+		return;
+	}
+
+	let groups = report.file_groups();
+	if groups.is_empty() {
+		return;
+	}
+
+	let header = ["FORMAT", "FILES", "SIZE", "DIRECTORY"].map(|h| h.to_string()).to_vec();
+	let rows: Vec<Vec<String>> = std::iter::once(header)
+		.chain(groups.iter().map(|group| {
+			vec![
+				group.format.clone(),
+				group.file_count.to_string(),
+				strings::format_bytes(group.total_bytes),
+				if group.dir.is_empty() { ".".to_string() } else { group.dir.clone() },
+			]
+		}))
+		.collect();
+	console::print_table(&rows, indent);
+}
+
+/// Prints an export plan as a table (one row per output file), then its warnings.
+pub(super) fn print_export_plan(plan: &ExportPlan) {
+	let header = ["ARTBOARD", "FORMAT", "SCALE", "OUTPUT", "TRANSFORMS"]
+		.map(|h| h.to_string())
+		.to_vec();
+	let rows: Vec<Vec<String>> = std::iter::once(header)
+		.chain(plan.items().map(|(format, item)| {
+			vec![
+				item.artboard.name.clone(),
+				format.to_string(),
+				item.scale.clone().unwrap_or_else(|| "-".to_string()),
+				item.target_file.clone(),
+				item.transforms.join(", "),
+			]
+		}))
+		.collect();
+
+	console::print_table(&rows, "");
+
+	let file_count = rows.iter().skip(1).map(|row| &row[3]).collect::<HashSet<_>>().len();
+	println!(
+		"\n{} artboard(s), {} step(s), {file_count} file(s) -> {}",
+		plan.artboard_count,
+		plan.steps.len(),
+		plan.output_path
+	);
+	if let Some(git_ref) = &plan.options.changed_since {
+		println!("{} artboard(s) unchanged since '{git_ref}' (skipped)", plan.unchanged_count);
+	}
+
+	for warning in &plan.warnings {
+		console::eprint_warning(warning);
+	}
+}
+
+fn exec_symbols_usage(args: SymbolsUsageArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let report = sketch::symbols_usage(&sketch_file)?;
+
+	for symbol in &report.symbols {
+		let origin = if symbol.foreign { "library".to_string() } else { format!("page '{}'", symbol.page_name) };
+		println!(
+			"{} ({origin}): {} instance(s) in {} artboard(s)/symbol(s)",
+			symbol.name,
+			symbol.instance_count,
+			symbol.used_in.len()
+		);
+		for container in &symbol.used_in {
+			let kind = if container.is_symbol_master { "symbol" } else { "artboard" };
+			println!("    - {} / {} ({kind}, {})", container.page_name, container.name, container.instance_count);
+		}
+	}
+
+	let unused_count = report.symbols.iter().filter(|s| s.instance_count == 0).count();
+	println!("\n{} symbol(s), {unused_count} unused", report.symbols.len());
+
+	for (symbol_id, count) in &report.missing_symbols {
+		console::print_label("Missing symbol master", Style::Yellow, format!("{symbol_id} ({count} instance(s))"));
+	}
+
+	for dup in &report.duplicates {
+		println!(
+			"Artboard '{}' (page '{}') duplicates symbol master '{}'",
+			dup.artboard_name, dup.artboard_page_name, dup.symbol_name
+		);
+	}
+
+	Ok(())
+}
+
+fn exec_stats(args: StatsArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let stats = sketch::artboard_stats(&sketch_file, args.top)?;
+
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&stats)?);
+		return Ok(());
+	}
+
+	println!("{} artboard(s) in {} page(s)", stats.artboard_count, stats.page_count);
+
+	println!();
+	let mut rows = vec![vec!["PREFIX".to_string(), "COUNT".to_string()]];
+	for entry in &stats.prefixes {
+		let prefix = if entry.key.is_empty() { "(none)" } else { entry.key.as_str() };
+		rows.push(vec![prefix.to_string(), entry.count.to_string()]);
+	}
+	console::print_table(&rows, "    ");
+
+	println!();
+	let mut rows = vec![vec!["SIZE".to_string(), "COUNT".to_string()]];
+	for entry in &stats.sizes {
+		rows.push(vec![entry.key.clone(), entry.count.to_string()]);
+	}
+	console::print_table(&rows, "    ");
+
+	let compliance = stats.naming_compliance();
+	let style = if compliance >= 100.0 { Style::Green } else { Style::Yellow };
+	println!(
+		"\nNaming convention: {} ({} of {})",
+		console::paint(format!("{compliance:.0}%"), style, Stream::Stdout),
+		stats.conventional_count,
+		stats.artboard_count
+	);
+	for name in &stats.unconventional_names {
+		println!("    - {name}");
+	}
 
-	for path in exported {
-		println!("Exported: {path}");
+	println!();
+	let mut rows = vec![vec!["LARGEST".to_string(), "SIZE".to_string()]];
+	for frame in &stats.largest {
+		rows.push(vec![frame.artboard.name.clone(), format!("{}x{}", frame.width, frame.height)]);
+	}
+	console::print_table(&rows, "    ");
+
+	Ok(())
+}
+
+fn exec_lint(args: LintArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let globs: Vec<&str> = args.glob.iter().map(String::as_str).collect();
+	let ignored_rules = args
+		.ignore
+		.iter()
+		.map(|rule| LintRule::try_from(rule.as_str()))
+		.collect::<Result<Vec<_>>>()?;
+	let porcelain = porcelain_version(args.porcelain.as_deref())?;
+	let report = sketch::lint_sketch_file(&sketch_file, Some(&globs), &ignored_rules)?;
+
+	if let Some(version) = porcelain {
+		porcelain::print_header(version);
+		for violation in &report.violations {
+			let rule = violation.rule.to_string();
+			porcelain::print_record(
+				"violation",
+				&[
+					&rule,
+					&violation.page_name,
+					&violation.artboard_name,
+					&violation.layer_path,
+					&violation.message,
+				],
+			);
+		}
+	} else if args.json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+	} else {
+		if !report.violations.is_empty() {
+			let mut rows = vec![vec![
+				"RULE".to_string(),
+				"ARTBOARD".to_string(),
+				"LAYER".to_string(),
+				"DETAIL".to_string(),
+			]];
+			for violation in &report.violations {
+				rows.push(vec![
+					violation.rule.to_string(),
+					violation.artboard_name.clone(),
+					violation.layer_path.clone(),
+					violation.message.clone(),
+				]);
+			}
+			console::print_table(&rows, "    ");
+			println!();
+		}
+
+		let counts: Vec<String> = report
+			.counts_by_rule()
+			.iter()
+			.map(|(rule, count)| format!("{rule}: {count}"))
+			.collect();
+		let counts = if counts.is_empty() { String::new() } else { format!(" ({})", counts.join(", ")) };
+		let style = if report.violations.is_empty() { Style::Green } else { Style::Yellow };
+		console::print_label(
+			"Lint",
+			style,
+			format!(
+				"{} violation(s){counts} in {} layer(s) of {} artboard(s)",
+				report.violations.len(),
+				report.layer_count,
+				report.artboard_count
+			),
+		);
+	}
+
+	if !report.violations.is_empty() {
+		return Err(Error::ValidationFailed(format!("{} lint violation(s) found", report.violations.len())));
+	}
+
+	Ok(())
+}
+
+fn exec_fonts(args: FontsArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let brand_fonts: Vec<&str> = args.brand_font.iter().map(String::as_str).collect();
+	let report = sketch::fonts_report(&sketch_file, &brand_fonts)?;
+
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+		return Ok(());
+	}
+
+	let mut rows = vec![vec![
+		"FONT".to_string(),
+		"FAMILY".to_string(),
+		"LAYERS".to_string(),
+		"FLAG".to_string(),
+		"USED IN".to_string(),
+	]];
+	for font in &report.fonts {
+		let flag = if font.non_brand { "non-brand" } else { "" };
+		let used_in: Vec<&str> = font
+			.used_in
+			.iter()
+			.map(|name| if name.is_empty() { "(page)" } else { name.as_str() })
+			.collect();
+		rows.push(vec![
+			font.name.clone(),
+			font.family.clone(),
+			font.text_layer_count.to_string(),
+			flag.to_string(),
+			used_in.join(", "),
+		]);
+	}
+	console::print_table(&rows, "    ");
+
+	for layer in &report.missing_fonts {
+		let container = if layer.container_name.is_empty() { "(page)" } else { layer.container_name.as_str() };
+		console::print_label(
+			"Missing font",
+			Style::Yellow,
+			format!("{} / {container} / {}", layer.page_name, layer.layer_name),
+		);
+	}
+
+	println!(
+		"\n{} font(s) in {} text layer(s), {} non-brand, {} layer(s) without a font",
+		report.fonts.len(),
+		report.text_layer_count,
+		report.non_brand_count(),
+		report.missing_fonts.len()
+	);
+
+	Ok(())
+}
+
+fn exec_copy(args: CopyArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let globs: Vec<&str> = args.glob.iter().map(String::as_str).collect();
+	let items = sketch::extract_text_copy(&sketch_file, Some(&globs))?;
+	let content = sketch::format_text_copy(&items, CopyFormat::try_from(args.format.as_str())?)?;
+
+	let Some(output) = args.output else {
+		print!("{content}");
+		return Ok(());
+	};
+
+	let file = SPath::new(files::expand_path(&output)?);
+	std::fs::write(file.as_std_path(), content).map_err(|e| format!("Failed to write copy file '{file}': {e}"))?;
+	console::print_label("Written", Style::Green, format!("{file} ({} string(s))", items.len()));
+
+	Ok(())
+}
+
+fn exec_flows(args: FlowsArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let graph = sketch::flow_graph(&sketch_file)?;
+	let content = sketch::format_flow_graph(&graph, FlowFormat::try_from(args.format.as_str())?)?;
+
+	let Some(output) = args.output else {
+		print!("{content}");
+		return Ok(());
+	};
+
+	let file = SPath::new(files::expand_path(&output)?);
+	std::fs::write(file.as_std_path(), content).map_err(|e| format!("Failed to write flows file '{file}': {e}"))?;
+	console::print_label(
+		"Written",
+		Style::Green,
+		format!("{file} ({} artboard(s), {} link(s))", graph.artboards.len(), graph.links.len()),
+	);
+	for id in &graph.dead_ends {
+		let name = graph
+			.artboards
+			.iter()
+			.find(|a| &a.id == id)
+			.map(|a| a.name.as_str())
+			.unwrap_or(id);
+		console::print_label("Dead end", Style::Yellow, name);
+	}
+	for (source_id, target_id) in &graph.broken_links {
+		let name = graph
+			.artboards
+			.iter()
+			.find(|a| &a.id == source_id)
+			.map(|a| a.name.as_str())
+			.unwrap_or(source_id);
+		console::print_label("Broken link", Style::Yellow, format!("{name} -> {target_id} (not in the document)"));
+	}
+
+	Ok(())
+}
+
+fn exec_spec(args: SpecArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let output_dir = SPath::new(files::expand_path(&args.output)?);
+	let template_dir = args
+		.template_dir
+		.map(|dir| files::expand_path(&dir).map(SPath::new))
+		.transpose()?;
+	let globs: Vec<&str> = args.glob.iter().map(String::as_str).collect();
+
+	let report = sketch::generate_spec_sheets(&sketch_file, Some(&globs), &output_dir, template_dir.as_ref())?;
+
+	for file in &report.sheet_files {
+		console::print_label("Written", Style::Green, file);
+	}
+	console::print_label(
+		"Written",
+		Style::Green,
+		format!("{} ({} artboard(s), {} image(s))", report.index_file, report.artboard_count, report.image_files.len()),
+	);
+
+	Ok(())
+}
+
+fn exec_contact_sheet(args: ContactSheetArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let target_file = SPath::new(files::expand_path(&args.output)?);
+	let globs: Vec<&str> = args.glob.iter().map(String::as_str).collect();
+
+	let report = sketch::generate_contact_sheet(&sketch_file, Some(&globs), &target_file, args.columns)?;
+
+	console::print_label(
+		"Written",
+		Style::Green,
+		format!("{} ({} artboard(s), {} page(s))", report.file, report.artboard_count, report.page_count),
+	);
+
+	Ok(())
+}
+
+fn exec_rename(args: RenameArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let map_file = SPath::new(files::expand_path(&args.map)?);
+	let renames = sketch::load_rename_map(&map_file)?;
+	let report = sketch::rename_artboards(&sketch_file, &renames, args.backup, args.dry_run)?;
+
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+		return Ok(());
+	}
+
+	if !report.renamed.is_empty() {
+		let mut rows = vec![vec!["PAGE".to_string(), "FROM".to_string(), "TO".to_string()]];
+		for renamed in &report.renamed {
+			rows.push(vec![renamed.page.clone(), renamed.from.clone(), renamed.to.clone()]);
+		}
+		console::print_table(&rows, "");
+	}
+	for from in &report.unmatched {
+		console::eprint_warning(format!("No artboard named (or with the UID) '{from}'"));
+	}
+	for warning in &report.warnings {
+		console::eprint_warning(warning);
+	}
+
+	if let Some(backup_file) = &report.backup_file {
+		console::print_label("Backup", Style::Cyan, backup_file);
+	}
+	let message = format!("{sketch_file} ({} artboard(s) renamed)", report.renamed.len());
+	if args.dry_run {
+		console::print_label("Dry run", Style::Yellow, message);
+	} else if report.renamed.is_empty() {
+		console::print_label("Unchanged", Style::Yellow, message);
+	} else {
+		console::print_label("Written", Style::Green, message);
+	}
+
+	Ok(())
+}
+
+fn exec_set_presets(args: SetPresetsArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let globs: Vec<&str> = args.glob.iter().map(String::as_str).collect();
+	let presets = args
+		.preset
+		.iter()
+		.map(|preset| ExportPreset::try_from(preset.as_str()))
+		.collect::<Result<Vec<_>>>()?;
+	let report =
+		sketch::set_export_presets(&sketch_file, Some(&globs), &presets, args.append, args.backup, args.dry_run)?;
+
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+		return Ok(());
+	}
+
+	if !report.updated.is_empty() {
+		let mut rows = vec![vec![
+			"PAGE".to_string(),
+			"ARTBOARD".to_string(),
+			"BEFORE".to_string(),
+			"AFTER".to_string(),
+		]];
+		for update in &report.updated {
+			rows.push(vec![
+				update.page.clone(),
+				update.name.clone(),
+				update.before.join(", "),
+				update.after.join(", "),
+			]);
+		}
+		console::print_table(&rows, "");
+	}
+
+	if let Some(backup_file) = &report.backup_file {
+		console::print_label("Backup", Style::Cyan, backup_file);
+	}
+	let message =
+		format!("{sketch_file} ({} of {} matched artboard(s) updated)", report.updated.len(), report.artboard_count);
+	if args.dry_run {
+		console::print_label("Dry run", Style::Yellow, message);
+	} else if report.updated.is_empty() {
+		console::print_label("Unchanged", Style::Yellow, message);
+	} else {
+		console::print_label("Written", Style::Green, message);
+	}
+
+	Ok(())
+}
+
+fn exec_sort(args: SortArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let globs: Vec<&str> = args.glob.iter().map(String::as_str).collect();
+	let options = SortOptions {
+		by: SortBy::try_from(args.by.as_str())?,
+		grid: args.grid.unwrap_or(sketch::DEFAULT_SORT_GRID),
+		columns: args.columns,
+		backup: args.backup,
+		dry_run: args.dry_run,
+	};
+	let report = sketch::sort_artboards(&sketch_file, Some(&globs), &options)?;
+
+	if let Some(backup_file) = &report.backup_file {
+		console::print_label("Backup", Style::Cyan, backup_file);
+	}
+	let message = format!(
+		"{sketch_file} ({} of {} artboard(s) moved, {} page(s))",
+		report.moved_count, report.artboard_count, report.page_count
+	);
+	if args.dry_run {
+		console::print_label("Dry run", Style::Yellow, message);
+	} else if !report.changed {
+		console::print_label("Unchanged", Style::Yellow, message);
+	} else {
+		console::print_label("Written", Style::Green, message);
 	}
 
 	Ok(())
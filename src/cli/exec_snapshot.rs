@@ -0,0 +1,95 @@
+use crate::cli::cmd::{SnapshotArgs, SnapshotCheckArgs, SnapshotCommand};
+use crate::handlers::config::{self, ProjectConfig};
+use crate::handlers::snapshot::{self, ProjectSnapshot};
+use crate::support::console::{self, Stream, Style};
+use crate::support::files;
+use crate::{Error, Result};
+
+pub fn exec_command(command: SnapshotCommand) -> Result<()> {
+	match command {
+		SnapshotCommand::Record(args) => exec_record(args),
+		SnapshotCommand::Check(args) => exec_check(args),
+	}
+}
+
+fn exec_record(args: SnapshotArgs) -> Result<()> {
+	let projects = load_projects(&args)?;
+
+	for project_snapshot in snapshot::snapshot_projects(&projects, &args.jobs)? {
+		project_snapshot.accept(args.rasters)?;
+		console::print_label(
+			"Recorded",
+			Style::Green,
+			format!("{} ({} file(s))", project_snapshot.snapshot_dir, project_snapshot.actual.files.len()),
+		);
+	}
+
+	Ok(())
+}
+
+fn exec_check(args: SnapshotCheckArgs) -> Result<()> {
+	let SnapshotCheckArgs { snapshot: args, update } = args;
+	let projects = load_projects(&args)?;
+
+	let mut diff_count = 0;
+	for project_snapshot in snapshot::snapshot_projects(&projects, &args.jobs)? {
+		let file_count = project_snapshot.actual.files.len();
+		if project_snapshot.diff.is_empty() {
+			console::print_label(
+				"Match",
+				Style::Green,
+				format!("{} ({file_count} file(s))", project_snapshot.config_file),
+			);
+			continue;
+		}
+
+		print_snapshot_diff(&project_snapshot);
+
+		if update {
+			project_snapshot.accept(args.rasters)?;
+			console::print_label("Updated", Style::Green, &project_snapshot.snapshot_dir);
+		} else {
+			diff_count += project_snapshot.diff.len();
+			if args.rasters {
+				for file in project_snapshot.write_new_rasters()? {
+					console::print_label("Review", Style::Cyan, file);
+				}
+			}
+		}
+	}
+
+	if diff_count > 0 {
+		return Err(Error::ValidationFailed(format!(
+			"{diff_count} snapshot difference(s). Run `webtk snapshot check --update` to accept them"
+		)));
+	}
+
+	Ok(())
+}
+
+fn load_projects(args: &SnapshotArgs) -> Result<Vec<ProjectConfig>> {
+	let config_file = args.config.as_deref().map(files::expand_path).transpose()?;
+	config::load_projects(config_file.as_deref(), args.all)
+}
+
+/// Prints the added (`+`), removed (`-`), and changed (`~`) files of a project snapshot.
+fn print_snapshot_diff(project_snapshot: &ProjectSnapshot) {
+	let diff = &project_snapshot.diff;
+	let status = if project_snapshot.recorded.is_none() {
+		"no snapshot recorded (run `webtk snapshot record`)".to_string()
+	} else {
+		format!("{} difference(s)", diff.len())
+	};
+	console::print_label("Differ", Style::Red, format!("{}: {status}", project_snapshot.config_file));
+
+	let lines = [
+		("+", Style::Green, &diff.added),
+		("-", Style::Red, &diff.removed),
+		("~", Style::Yellow, &diff.changed),
+	];
+	for (mark, style, paths) in lines {
+		for path in paths {
+			println!("    {} {path}", console::paint(mark, style, Stream::Stdout));
+		}
+	}
+}
@@ -0,0 +1,86 @@
+use crate::Result;
+use crate::cli::cmd::{SourceExportArgs, SourceListArgs};
+use crate::handlers::source::{self, DesignSource, SourceKind};
+use crate::support::console::{self, Stream, Style};
+use crate::support::files;
+use crate::support::porcelain::{self, PorcelainVersion};
+use simple_fs::SPath;
+
+pub fn exec_list(args: SourceListArgs) -> Result<()> {
+	let design_source = open_source(&args.input, args.source.as_deref())?;
+	let porcelain = porcelain_version(args.porcelain.as_deref())?;
+	print_items(design_source.as_ref(), &args.glob, true, porcelain)
+}
+
+pub fn exec_export(args: SourceExportArgs) -> Result<()> {
+	let design_source = open_source(&args.input, args.source.as_deref())?;
+	let output_dir = SPath::new(files::expand_path(&args.output)?);
+
+	let porcelain = porcelain_version(args.porcelain.as_deref())?;
+
+	let export = design_source.export_items(&args.glob, &args.format, &output_dir)?;
+
+	if let Some(version) = porcelain {
+		porcelain::print_header(version);
+		for file in &export.exported_files {
+			porcelain::print_record("file", &[file]);
+		}
+		for warning in &export.warnings {
+			let code = warning.code.to_string();
+			porcelain::print_record(
+				"warning",
+				&[&code, warning.artboard.as_deref().unwrap_or_default(), &warning.message],
+			);
+		}
+		return Ok(());
+	}
+
+	for file in &export.exported_files {
+		console::print_label("Exported", Style::Green, file);
+	}
+	for warning in &export.warnings {
+		console::eprint_warning(warning);
+	}
+
+	Ok(())
+}
+
+/// Prints the items of a design source as `<id>: <name> (<page>)` (shared by `list`, `sketch list-artboards`, `figma list`),
+/// or as `item <id> <page> <name>` porcelain records.
+pub(super) fn print_items(
+	design_source: &dyn DesignSource,
+	globs: &[String],
+	show_pages: bool,
+	porcelain: Option<PorcelainVersion>,
+) -> Result<()> {
+	let items = design_source.list_items(globs)?;
+	if let Some(version) = porcelain {
+		porcelain::print_header(version);
+		for item in &items {
+			porcelain::print_record("item", &[&item.id, &item.page, &item.name]);
+		}
+		return Ok(());
+	}
+
+	for item in items {
+		let id = console::paint(format!("{}:", item.id), Style::Dim, Stream::Stdout);
+		if show_pages {
+			let page = console::paint(format!("({})", item.page), Style::Dim, Stream::Stdout);
+			println!("{id} {} {page}", item.name);
+		} else {
+			println!("{id} {}", item.name);
+		}
+	}
+
+	Ok(())
+}
+
+fn open_source(input: &str, source: Option<&str>) -> Result<Box<dyn DesignSource>> {
+	let kind = source.map(SourceKind::try_from).transpose()?;
+	source::open_source(input, kind)
+}
+
+/// Returns the porcelain version of a `--porcelain` value (None without the flag).
+pub(super) fn porcelain_version(value: Option<&str>) -> Result<Option<PorcelainVersion>> {
+	value.map(PorcelainVersion::try_from).transpose()
+}
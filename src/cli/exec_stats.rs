@@ -0,0 +1,88 @@
+use crate::cli::cmd::{StatsCommand, StatsShowArgs};
+use crate::handlers::stats::{self, UsageRecord};
+use crate::support::console::{self, Style};
+use crate::support::{dates, files};
+use crate::{Error, Result};
+use simple_fs::SPath;
+use std::time::Instant;
+
+pub fn exec_command(command: StatsCommand) -> Result<()> {
+	match command {
+		StatsCommand::Show(args) => exec_show(args),
+	}
+}
+
+fn exec_show(args: StatsShowArgs) -> Result<()> {
+	let section = stats::find_stats_section();
+	let stats_file = match &args.file {
+		Some(file) => SPath::new(files::expand_path(file)?),
+		None => stats::stats_file(section.as_ref())?,
+	};
+	if !stats_file.exists() {
+		let hint = if section.is_some_and(|section| section.enabled) {
+			"no command recorded yet"
+		} else {
+			"enable them with `[stats] enabled = true` in webtk.toml"
+		};
+		return Err(Error::custom(format!("No usage stats in '{stats_file}' ({hint})")));
+	}
+
+	let records = stats::read_usage_records(&stats_file)?;
+	let summary = stats::summarize_usage(&stats_file, &records);
+
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&summary)?);
+		return Ok(());
+	}
+
+	let period = match (summary.since, summary.until) {
+		(Some(since), Some(until)) => {
+			format!(", {} to {}", dates::format_utc_date(since, "%Y-%m-%d"), dates::format_utc_date(until, "%Y-%m-%d"))
+		}
+		_ => String::new(),
+	};
+	console::print_label("Stats", Style::Bold, format!("{} run(s){period} ({stats_file})", records.len()));
+
+	let header = ["COMMAND", "RUNS", "FAILURES", "AVG", "TOTAL", "ARTBOARDS"]
+		.map(|h| h.to_string())
+		.to_vec();
+	let rows: Vec<Vec<String>> = std::iter::once(header)
+		.chain(summary.commands.iter().map(|usage| {
+			vec![
+				usage.command.clone(),
+				usage.runs.to_string(),
+				usage.failures.to_string(),
+				format_duration(usage.avg_ms),
+				format_duration(usage.total_ms),
+				usage.artboards.to_string(),
+			]
+		}))
+		.collect();
+	console::print_table(&rows, "  ");
+
+	Ok(())
+}
+
+/// Appends the run of a command to the stats file when the `[stats]` of the config enable them.
+/// The stats never fail the command: a write error is a warning.
+pub fn record_usage(command: String, started: Instant, result: &Result<()>) {
+	let Some(section) = stats::find_stats_section().filter(|section| section.enabled) else {
+		return;
+	};
+
+	let record = UsageRecord {
+		time: dates::now_unix_secs(),
+		command,
+		duration_ms: started.elapsed().as_millis() as u64,
+		exit_code: result.as_ref().err().map(Error::exit_code).unwrap_or(0),
+		artboards: stats::counted_artboards(),
+	};
+	let res = stats::stats_file(Some(&section)).and_then(|file| stats::append_usage_record(&file, &record));
+	if let Err(err) = res {
+		console::eprint_warning(format!("Cannot record the usage stats. Cause: {err}"));
+	}
+}
+
+fn format_duration(ms: u64) -> String {
+	if ms < 1000 { format!("{ms}ms") } else { format!("{:.1}s", ms as f64 / 1000.0) }
+}
@@ -0,0 +1,60 @@
+use crate::Result;
+use crate::cli::cmd::{SvgBenchArgs, SvgCommand};
+use crate::handlers::svg;
+use crate::support::console::{self, Style};
+use crate::support::{files, http, strings};
+use simple_fs::SPath;
+
+pub fn exec_command(command: SvgCommand) -> Result<()> {
+	match command {
+		SvgCommand::Unsprite(args) => exec_unsprite(&args.sprite_file, &args.output),
+		SvgCommand::Bench(args) => exec_bench(args),
+	}
+}
+
+fn exec_unsprite(sprite_file: &str, output: &str) -> Result<()> {
+	let sprite_file = http::input_path(sprite_file)?;
+	let written = svg::unsprite(sprite_file, SPath::new(files::expand_path(output)?))?;
+
+	for path in written {
+		console::print_label("Extracted", Style::Green, path);
+	}
+
+	Ok(())
+}
+
+fn exec_bench(args: SvgBenchArgs) -> Result<()> {
+	let dir = args.dir.as_deref().map(files::expand_path).transpose()?.map(SPath::new);
+	let report = svg::bench_svg_transforms(dir.as_ref(), args.iterations)?;
+
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+		return Ok(());
+	}
+
+	console::print_label(
+		"Bench",
+		Style::Bold,
+		format!(
+			"{} file(s), {} x {} iteration(s) ({})",
+			report.file_count,
+			strings::format_bytes(report.bytes),
+			report.iterations,
+			report.dir.as_deref().unwrap_or("built-in fixtures")
+		),
+	);
+	let header = ["TRANSFORM", "TIME", "MB/S", "REJECTED"].map(|h| h.to_string()).to_vec();
+	let rows: Vec<Vec<String>> = std::iter::once(header)
+		.chain(report.results.iter().map(|result| {
+			vec![
+				result.transform.clone(),
+				format!("{:.1} ms", result.duration_ms),
+				format!("{:.1}", result.mb_per_sec),
+				result.failed_count.to_string(),
+			]
+		}))
+		.collect();
+	console::print_table(&rows, "  ");
+
+	Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::Result;
+use crate::cli::cmd::{SwCommand, SwPrecacheArgs};
+use crate::handlers::sw::{self, PrecacheOptions};
+use crate::support::console::{self, Style};
+use crate::support::files;
+use simple_fs::SPath;
+
+pub fn exec_command(command: SwCommand) -> Result<()> {
+	match command {
+		SwCommand::Precache(args) => exec_precache(args),
+	}
+}
+
+fn exec_precache(args: SwPrecacheArgs) -> Result<()> {
+	let dir = SPath::new(files::expand_path(&args.dir)?);
+	let manifest_file = SPath::new(files::expand_path(&args.output)?);
+
+	let options = PrecacheOptions { globs: args.glob, ignore_globs: args.ignore, url_prefix: args.url_prefix };
+	let entries = sw::build_precache_manifest(&dir, &options, Some(&manifest_file))?;
+	sw::write_precache_manifest(&entries, &manifest_file)?;
+
+	console::print_label("Written", Style::Green, format!("{manifest_file} ({} file(s))", entries.len()));
+
+	Ok(())
+}
@@ -0,0 +1,125 @@
+use crate::cli::cmd::{TokensCommand, TokensConvertArgs, TokensDiffArgs, TokensExportArgs};
+use crate::handlers::codegen;
+use crate::handlers::design_tokens::{self, DesignToken, TokenFormat, TokensDiff};
+use crate::support::console::{self, Stream, Style};
+use crate::support::{files, http};
+use crate::{Error, Result};
+use simple_fs::SPath;
+
+pub fn exec_command(command: TokensCommand) -> Result<()> {
+	match command {
+		TokensCommand::Export(args) => exec_export(args),
+		TokensCommand::Convert(args) => exec_convert(args),
+		TokensCommand::Diff(args) => exec_diff(args),
+	}
+}
+
+fn exec_export(args: TokensExportArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.sketch_file)?);
+	let tokens = design_tokens::extract_sketch_tokens(&sketch_file)?;
+
+	let (document, warnings) = design_tokens::tokens_to_w3c_json(&tokens);
+	for warning in &warnings {
+		console::eprint_warning(warning);
+	}
+
+	let content = format!("{}\n", serde_json::to_string_pretty(&document)?);
+	write_output(args.output.as_deref(), &content, format!("{} token(s)", tokens.len() - warnings.len()))
+}
+
+fn exec_convert(args: TokensConvertArgs) -> Result<()> {
+	let format = TokenFormat::try_from(args.to.as_str())?;
+	let template_dir = args
+		.template_dir
+		.as_deref()
+		.map(files::expand_path)
+		.transpose()?
+		.map(SPath::new);
+	let tokens = load_tokens_file(&args.tokens_file)?;
+
+	let content = design_tokens::convert_tokens(&tokens, format, template_dir.as_ref())?;
+	write_output(args.output.as_deref(), &content, format!("{} token(s)", tokens.len()))
+}
+
+fn exec_diff(args: TokensDiffArgs) -> Result<()> {
+	let sketch_file = SPath::new(files::expand_path(&args.design)?);
+	let design = design_tokens::extract_sketch_tokens(&sketch_file)?;
+	let code = load_tokens_file(&args.code)?;
+
+	let diff = design_tokens::diff_tokens(&design, &code);
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&diff)?);
+	} else if diff.is_empty() {
+		console::print_label("Match", Style::Green, format!("{} token(s)", design.len()));
+	} else {
+		print_tokens_diff(&diff);
+	}
+
+	if !diff.is_empty() {
+		return Err(Error::ValidationFailed(format!(
+			"{} token difference(s) between '{sketch_file}' and '{}'",
+			diff.len(),
+			args.code
+		)));
+	}
+
+	Ok(())
+}
+
+/// Loads the tokens of a W3C tokens file (or URL), printing the warnings of the skipped tokens.
+fn load_tokens_file(value: &str) -> Result<Vec<DesignToken>> {
+	let tokens_file = http::input_path(value)?;
+	files::check_file_exists(&tokens_file)?;
+
+	let content = std::fs::read_to_string(tokens_file.as_std_path())
+		.map_err(|e| format!("Failed to read tokens file '{tokens_file}': {e}"))?;
+	let document: serde_json::Value = serde_json::from_str(&content)
+		.map_err(|e| format!("Failed to parse tokens file '{tokens_file}' (expected JSON): {e}"))?;
+
+	let (tokens, warnings) = design_tokens::parse_w3c_tokens(&document)?;
+	for warning in &warnings {
+		console::eprint_warning(warning);
+	}
+
+	Ok(tokens)
+}
+
+/// Prints the added (`+`, only in the design), removed (`-`, only in the code), and changed (`~`) tokens.
+fn print_tokens_diff(diff: &TokensDiff) {
+	console::print_label("Differ", Style::Red, format!("{} difference(s)", diff.len()));
+
+	let lines = [
+		("+", Style::Green, &diff.added),
+		("-", Style::Red, &diff.removed),
+		("~", Style::Yellow, &diff.changed),
+	];
+	for (mark, style, changes) in lines {
+		for change in changes {
+			let values = match (&change.design, &change.code) {
+				(Some(design), Some(code)) => format!("{code} -> {design}"),
+				(Some(value), None) | (None, Some(value)) => value.clone(),
+				(None, None) => String::new(),
+			};
+			println!(
+				"    {} {} {}",
+				console::paint(mark, style, Stream::Stdout),
+				change.path,
+				console::paint(format!("({values})"), Style::Dim, Stream::Stdout)
+			);
+		}
+	}
+}
+
+/// Writes the content to the output file (unchanged files are not rewritten), or prints it to stdout.
+fn write_output(output: Option<&str>, content: &str, summary: String) -> Result<()> {
+	let Some(output) = output else {
+		print!("{content}");
+		return Ok(());
+	};
+
+	let file = SPath::new(files::expand_path(output)?);
+	codegen::write_codegen_file(&file, content)?;
+	console::print_label("Written", Style::Green, format!("{file} ({summary})"));
+
+	Ok(())
+}
@@ -0,0 +1,28 @@
+use crate::Result;
+use crate::cli::cmd::SelfUpdateArgs;
+use crate::handlers::update::{self, ReleaseChannel, SelfUpdateOptions, SelfUpdateOutcome};
+use crate::support::console::{self, Style};
+
+pub fn exec_self_update(args: SelfUpdateArgs) -> Result<()> {
+	let options = SelfUpdateOptions {
+		channel: ReleaseChannel::try_from(args.channel.as_str())?,
+		check_only: args.check,
+		force: args.force,
+		releases_url: args.releases_url,
+	};
+
+	match update::self_update(&options)? {
+		SelfUpdateOutcome::UpToDate { current } => {
+			console::print_label("Up to date", Style::Green, format!("webtk {current} ({} channel)", args.channel));
+		}
+		SelfUpdateOutcome::Available { current, latest } => {
+			console::print_label("Available", Style::Yellow, format!("webtk {latest} (current {current})"));
+			println!("Run `webtk self-update` to install it");
+		}
+		SelfUpdateOutcome::Updated { previous, installed, binary } => {
+			console::print_label("Updated", Style::Green, format!("webtk {previous} -> {installed} ({binary})"));
+		}
+	}
+
+	Ok(())
+}
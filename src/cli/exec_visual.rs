@@ -0,0 +1,65 @@
+use crate::cli::cmd::VisualTestArgs;
+use crate::handlers::raster::DEFAULT_PIXEL_THRESHOLD;
+use crate::handlers::visual::{self, DEFAULT_VISUAL_DIFF_DIR, VisualStatus, VisualTestOptions};
+use crate::support::console::{self, Style};
+use crate::support::{files, http, strings};
+use crate::{Error, Result};
+use simple_fs::SPath;
+
+pub fn exec_visual_test(args: VisualTestArgs) -> Result<()> {
+	let threshold = strings::parse_ratio(&args.threshold).ok_or_else(|| {
+		Error::custom(format!("Invalid threshold '{}'. Expected a percentage (e.g., 0.1%) or a ratio", args.threshold))
+	})?;
+	let pixel_threshold = args.pixel_threshold.unwrap_or(DEFAULT_PIXEL_THRESHOLD);
+	if !(0.0..=1.0).contains(&pixel_threshold) {
+		return Err(Error::custom(format!("Invalid pixel threshold '{pixel_threshold}'. Expected 0 to 1")));
+	}
+
+	let input = http::input_path(&args.input)?;
+	let options = VisualTestOptions {
+		baseline_dir: SPath::new(files::expand_path(&args.baseline)?),
+		diff_dir: SPath::new(files::expand_path(args.diff_dir.as_deref().unwrap_or(DEFAULT_VISUAL_DIFF_DIR))?),
+		threshold,
+		pixel_threshold,
+		scale: args.scale,
+		update: args.update,
+	};
+
+	let report = visual::visual_test(&input, &options)?;
+
+	for result in &report.results {
+		let detail = match (&result.message, result.diff_ratio) {
+			(Some(message), _) => format!(" ({message})"),
+			(None, Some(ratio)) if ratio > 0.0 => format!(" ({:.3}% pixels differ)", ratio * 100.0),
+			_ => String::new(),
+		};
+		let (label, style) = match result.status {
+			VisualStatus::Pass => ("Pass", Style::Green),
+			VisualStatus::Fail => ("Fail", Style::Red),
+			VisualStatus::Missing => ("Missing", Style::Red),
+			VisualStatus::Updated => ("Updated", Style::Yellow),
+		};
+		console::print_label(label, style, format!("{}{detail}", result.svg_file));
+		if let Some(diff_file) = &result.diff_file {
+			console::print_label("    Diff", Style::Cyan, diff_file);
+		}
+	}
+
+	if let Some(report_file) = args.report {
+		let report_file = SPath::new(files::expand_path(&report_file)?);
+		let content = serde_json::to_string_pretty(&report)?;
+		std::fs::write(report_file.as_std_path(), format!("{content}\n"))
+			.map_err(|e| format!("Failed to write visual test report '{report_file}': {e}"))?;
+		console::print_label("Report", Style::Cyan, report_file);
+	}
+
+	let failed_count = report.failed_count();
+	if failed_count > 0 {
+		return Err(Error::ValidationFailed(format!(
+			"{failed_count} of {} SVG(s) differ from their baseline (or have none). Use --update to accept them",
+			report.results.len()
+		)));
+	}
+
+	Ok(())
+}
@@ -1,21 +1,127 @@
-use crate::Result;
 use crate::cli::cmd::{CliCmd, CliSubCmd};
-use crate::cli::exec_sketch;
-use clap::Parser as _;
+use crate::cli::{
+	exec_auth, exec_checksums, exec_codegen, exec_config, exec_daemon, exec_export_server, exec_figma, exec_gen,
+	exec_html, exec_icons, exec_img, exec_lottie, exec_pipeline, exec_run, exec_serve, exec_site, exec_sketch,
+	exec_snapshot, exec_source, exec_stats, exec_svg, exec_sw, exec_tokens, exec_update, exec_visual,
+};
+use crate::handlers::config;
+use crate::handlers::sketch::{self, SshTarget, ToolRunner};
+use crate::support::console::{self, ColorChoice};
+use crate::support::http::{self, HttpOptions};
+use crate::support::{files, profile, sandbox};
+use crate::{Error, Result};
+use clap::{ArgMatches, CommandFactory as _, FromArgMatches as _};
+use simple_fs::SPath;
+use std::time::Instant;
 
 pub fn execute() -> Result<()> {
-	let cli_cmd = CliCmd::parse();
+	let matches = CliCmd::command().get_matches();
+	let cli_cmd = CliCmd::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+	let started = Instant::now();
+	console::set_color_choice(ColorChoice::try_from(cli_cmd.color.as_str())?);
+	let profile_output = cli_cmd.profile_output.as_deref().map(files::expand_path).transpose()?;
+	if profile_output.is_some() {
+		profile::enable();
+	}
+	let cacert = cli_cmd.cacert.as_deref().map(files::expand_path).transpose()?;
+	if let Some(cacert) = &cacert {
+		files::check_file_exists(&SPath::new(cacert))?;
+	}
+	http::set_http_options(HttpOptions {
+		timeout_secs: cli_cmd.http_timeout,
+		cache_dir: cli_cmd.http_cache_dir,
+		no_cache: cli_cmd.no_http_cache,
+		cacert,
+		insecure: cli_cmd.insecure,
+	});
+	sandbox::set_allowed_env(cli_cmd.allow_env)?;
+	if cli_cmd.offline || current_config_offline() {
+		http::set_offline();
+	}
+	if let Some(remote) = cli_cmd.remote {
+		let target = SshTarget::new(remote, cli_cmd.remote_shared.as_deref())?;
+		sketch::set_tool_runner(ToolRunner::Ssh(target));
+	}
+	if let Some(url) = cli_cmd.export_server {
+		if !http::is_url(&url) {
+			return Err(Error::custom(format!("Invalid export server URL '{url}'. Expected: http://host:port")));
+		}
+		sketch::set_tool_runner(ToolRunner::ExportServer(url.trim_end_matches('/').to_string()));
+	}
 
 	let Some(sub_cmd) = cli_cmd.command else {
 		println!("Hello webtk world! Use --help for available commands.");
 		return Ok(());
 	};
 
+	let command_name = command_name(&matches);
+	let command_span = profile::span("command", command_name.as_str());
+
 	let res: Result<()> = match sub_cmd {
+		CliSubCmd::List(args) => exec_source::exec_list(args),
+		CliSubCmd::Export(args) => exec_source::exec_export(args),
 		CliSubCmd::Sketch(command) => exec_sketch::exec_command(command),
+		CliSubCmd::Icons(command) => exec_icons::exec_command(command),
+		CliSubCmd::Svg(command) => exec_svg::exec_command(command),
+		CliSubCmd::Run(args) => exec_run::exec_run(args),
+		CliSubCmd::Config(command) => exec_config::exec_command(command),
+		CliSubCmd::Daemon(args) => exec_daemon::exec_daemon(args),
+		CliSubCmd::Serve(args) => exec_serve::exec_serve(args),
+		CliSubCmd::ExportServer(args) => exec_export_server::exec_export_server(args),
+		CliSubCmd::Snapshot(command) => exec_snapshot::exec_command(command),
+		CliSubCmd::VisualTest(args) => exec_visual::exec_visual_test(args),
+		CliSubCmd::Codegen(command) => exec_codegen::exec_command(command),
+		CliSubCmd::Auth(command) => exec_auth::exec_command(command),
+		CliSubCmd::Figma(command) => exec_figma::exec_command(command),
+		CliSubCmd::Tokens(command) => exec_tokens::exec_command(command),
+		CliSubCmd::VerifySums(args) => exec_checksums::exec_verify_sums(args),
+		CliSubCmd::SelfUpdate(args) => exec_update::exec_self_update(args),
+		CliSubCmd::Stats(command) => exec_stats::exec_command(command),
+		CliSubCmd::Gen(command) => exec_gen::exec_command(command),
+		CliSubCmd::Sw(command) => exec_sw::exec_command(command),
+		CliSubCmd::Site(command) => exec_site::exec_command(command),
+		CliSubCmd::Html(command) => exec_html::exec_command(command),
+		CliSubCmd::Lottie(command) => exec_lottie::exec_command(command),
+		CliSubCmd::Img(command) => exec_img::exec_command(command),
+		CliSubCmd::Pipeline(command) => exec_pipeline::exec_command(command),
 	};
 
+	drop(command_span);
+	// Written whatever the outcome, a failed run is worth profiling too
+	if let Some(profile_output) = profile_output
+		&& let Err(err) = profile::write_trace(&SPath::new(profile_output))
+	{
+		console::eprint_warning(format!("Profile not written. Cause: {err}"));
+	}
+
+	if !command_name.starts_with("stats") {
+		exec_stats::record_usage(command_name, started, &res);
+	}
+
 	res?;
 
 	Ok(())
 }
+
+/// Whether the `webtk.toml` of the current directory (or of its workspace root) sets `[network] offline = true`.
+/// An absent or invalid config is not offline (the commands loading it report the errors).
+fn current_config_offline() -> bool {
+	let Some(current_dir) = std::env::current_dir().ok().and_then(|dir| SPath::from_std_path(dir).ok()) else {
+		return false;
+	};
+	config::find_workspace_root(&current_dir)
+		.ok()
+		.flatten()
+		.is_some_and(|project| project.is_offline())
+}
+
+/// Returns the subcommand names of the command line, without any argument (e.g., "sketch export").
+fn command_name(matches: &ArgMatches) -> String {
+	let mut names = Vec::new();
+	let mut current = matches;
+	while let Some((name, sub_matches)) = current.subcommand() {
+		names.push(name);
+		current = sub_matches;
+	}
+	names.join(" ")
+}
@@ -1,7 +1,31 @@
 // region:    --- Modules
 
 mod cmd;
+mod exec_auth;
+mod exec_checksums;
+mod exec_codegen;
+mod exec_config;
+mod exec_daemon;
+mod exec_export_server;
+mod exec_figma;
+mod exec_gen;
+mod exec_html;
+mod exec_icons;
+mod exec_img;
+mod exec_lottie;
+mod exec_pipeline;
+mod exec_run;
+mod exec_serve;
+mod exec_site;
 mod exec_sketch;
+mod exec_snapshot;
+mod exec_source;
+mod exec_stats;
+mod exec_svg;
+mod exec_sw;
+mod exec_tokens;
+mod exec_update;
+mod exec_visual;
 mod executor;
 
 pub use executor::*;
@@ -11,6 +11,27 @@ pub enum Error {
 	#[display("File not found: '{_0}'")]
 	FileNotFound(SPath),
 
+	// -- Outcomes with dedicated exit codes
+	#[display("No artboards matched{}", no_match_display(globs, suggestions))]
+	NoArtboardsMatched {
+		globs: Vec<String>,
+		/// Near-miss artboard names (e.g., for a typo in a glob).
+		suggestions: Vec<String>,
+	},
+
+	#[display("Tool '{tool}' not found. Expected at: '{path}'")]
+	ToolMissing { tool: String, path: String },
+
+	#[display("Validation failed: {_0}")]
+	ValidationFailed(String),
+
+	#[display("Partial failure ({} file(s) exported): {cause}", exported_files.len())]
+	PartialFailure { cause: String, exported_files: Vec<String> },
+
+	/// A network access in offline mode, with the URL or host (see `http::set_offline`).
+	#[display("Network access disabled (offline mode): '{_0}'")]
+	NetworkDisabled(String),
+
 	// -- Externals
 	#[from]
 	SerdeJson(serde_json::Error),
@@ -26,10 +47,58 @@ impl Error {
 	pub fn custom(val: impl Into<String>) -> Self {
 		Self::Custom(val.into())
 	}
+
+	/// Error for an external tool that could not be executed.
+	/// A missing executable becomes `ToolMissing` (dedicated exit code).
+	pub fn tool_exec(tool: &str, path: &str, err: std::io::Error) -> Self {
+		if err.kind() == std::io::ErrorKind::NotFound {
+			Self::ToolMissing { tool: tool.to_string(), path: path.to_string() }
+		} else {
+			Self::Custom(format!("Failed to execute {tool}: {err}"))
+		}
+	}
 }
 
 // endregion: --- Custom
 
+// region:    --- Exit Codes
+
+impl Error {
+	/// The process exit code for this error, so scripts can branch on outcomes.
+	/// - 1: any other error
+	/// - 2: no artboards matched
+	/// - 3: external tool missing (e.g., sketchtool)
+	/// - 4: validation failed (e.g., denied warnings)
+	/// - 5: partial failure (some files were exported)
+	/// - 6: network access disabled (offline mode)
+	pub fn exit_code(&self) -> i32 {
+		match self {
+			Self::NoArtboardsMatched { .. } => 2,
+			Self::ToolMissing { .. } => 3,
+			Self::ValidationFailed(_) => 4,
+			Self::PartialFailure { .. } => 5,
+			Self::NetworkDisabled(_) => 6,
+			_ => 1,
+		}
+	}
+}
+
+fn no_match_display(globs: &[String], suggestions: &[String]) -> String {
+	let mut msg = String::new();
+	if !globs.is_empty() {
+		msg.push_str(&format!(" for glob(s): {}", globs.join(", ")));
+	}
+	if !suggestions.is_empty() {
+		msg.push_str("\nDid you mean:");
+		for name in suggestions {
+			msg.push_str(&format!("\n    {name}"));
+		}
+	}
+	msg
+}
+
+// endregion: --- Exit Codes
+
 // region:    --- Error Boilerplate
 
 impl std::error::Error for Error {}
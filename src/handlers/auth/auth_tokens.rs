@@ -0,0 +1,201 @@
+//! API tokens of the remote services (Figma, Sketch Cloud), stored in the macOS keychain (`security`),
+//! with an environment variable taking precedence (e.g., `WEBTK_FIGMA_TOKEN` in CI).
+//! The services read their token with `find_token`, so tokens never need to be passed on the command line.
+
+use crate::{Error, Result};
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+/// The keychain service name of the tokens (the account is the service id, e.g., "figma").
+const KEYCHAIN_SERVICE: &str = "webtk";
+
+/// `security` exit code when the item is not in the keychain.
+const SECURITY_ITEM_NOT_FOUND: i32 = 44;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthService {
+	Figma,
+	SketchCloud,
+}
+
+impl AuthService {
+	pub const ALL: [AuthService; 2] = [AuthService::Figma, AuthService::SketchCloud];
+
+	/// The id of the service on the command line and in the keychain (e.g., "sketch-cloud").
+	pub fn id(self) -> &'static str {
+		match self {
+			AuthService::Figma => "figma",
+			AuthService::SketchCloud => "sketch-cloud",
+		}
+	}
+
+	/// The environment variable of the token, which takes precedence over the keychain.
+	pub fn env_var(self) -> &'static str {
+		match self {
+			AuthService::Figma => "WEBTK_FIGMA_TOKEN",
+			AuthService::SketchCloud => "WEBTK_SKETCH_CLOUD_TOKEN",
+		}
+	}
+}
+
+impl TryFrom<&str> for AuthService {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		AuthService::ALL
+			.into_iter()
+			.find(|service| service.id() == value)
+			.ok_or_else(|| {
+				let ids: Vec<&str> = AuthService::ALL.iter().map(|service| service.id()).collect();
+				Error::custom(format!("Unknown auth service '{value}'. Expected: {}", ids.join(", ")))
+			})
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSource {
+	Env,
+	Keychain,
+}
+
+#[derive(Debug)]
+pub struct AuthToken {
+	pub token: String,
+	pub source: TokenSource,
+}
+
+impl AuthToken {
+	/// The token for display: its first 4 chars and its length (e.g., "figd... (40 chars)").
+	pub fn masked(&self) -> String {
+		let prefix: String = self.token.chars().take(4).collect();
+		format!("{prefix}... ({} chars)", self.token.chars().count())
+	}
+}
+
+/// The keychain is the macOS login keychain (`security`); elsewhere, only the environment variables are used.
+pub fn keychain_supported() -> bool {
+	cfg!(target_os = "macos")
+}
+
+/// Returns the token of a service: its environment variable when set, otherwise the keychain item.
+pub fn find_token(service: AuthService) -> Result<Option<AuthToken>> {
+	if let Ok(token) = std::env::var(service.env_var())
+		&& !token.trim().is_empty()
+	{
+		return Ok(Some(AuthToken { token: token.trim().to_string(), source: TokenSource::Env }));
+	}
+
+	if !keychain_supported() {
+		return Ok(None);
+	}
+
+	let output = Command::new("security")
+		.args(["find-generic-password", "-s", KEYCHAIN_SERVICE, "-a", service.id(), "-w"])
+		.output()
+		.map_err(|e| Error::tool_exec("security", "security", e))?;
+
+	if output.status.code() == Some(SECURITY_ITEM_NOT_FOUND) {
+		return Ok(None);
+	}
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!(
+			"Cannot read the {} token from the keychain: {}",
+			service.id(),
+			stderr.trim()
+		)));
+	}
+
+	let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+	Ok((!token.is_empty()).then_some(AuthToken { token, source: TokenSource::Keychain }))
+}
+
+/// Stores (or replaces) the token of a service in the keychain.
+pub fn set_token(service: AuthService, token: &str) -> Result<()> {
+	if !keychain_supported() {
+		return Err(Error::custom(format!(
+			"The keychain is only supported on macOS. Set {} instead",
+			service.env_var()
+		)));
+	}
+
+	let token = token.trim();
+	if token.is_empty() {
+		return Err(Error::custom(format!("The {} token is empty", service.id())));
+	}
+	if token.chars().any(char::is_control) {
+		return Err(Error::custom(format!("The {} token has control characters", service.id())));
+	}
+
+	// The token goes through the stdin of `security -i` (its interactive mode), not its arguments,
+	// which any local user can read (e.g., with `ps`) while it runs
+	let command_line = format!(
+		"add-generic-password -U -s {} -a {} -l {} -w {}\n",
+		security_quote(KEYCHAIN_SERVICE),
+		security_quote(service.id()),
+		security_quote(&format!("webtk {} token", service.id())),
+		security_quote(token)
+	);
+	let mut child = Command::new("security")
+		.arg("-i")
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.map_err(|e| Error::tool_exec("security", "security", e))?;
+	if let Some(mut stdin) = child.stdin.take() {
+		stdin
+			.write_all(command_line.as_bytes())
+			.map_err(|e| format!("Cannot write to security: {e}"))?;
+	}
+	let output = child
+		.wait_with_output()
+		.map_err(|e| Error::tool_exec("security", "security", e))?;
+
+	// Note: the interactive mode reports the failure of a command on stderr, not always in its exit code
+	if !output.status.success() || !output.stderr.trim_ascii().is_empty() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!(
+			"Cannot store the {} token in the keychain: {}",
+			service.id(),
+			stderr.trim()
+		)));
+	}
+
+	Ok(())
+}
+
+/// Removes the token of a service from the keychain. Returns false when there was none.
+pub fn remove_token(service: AuthService) -> Result<bool> {
+	if !keychain_supported() {
+		return Ok(false);
+	}
+
+	let output = Command::new("security")
+		.args(["delete-generic-password", "-s", KEYCHAIN_SERVICE, "-a", service.id()])
+		.output()
+		.map_err(|e| Error::tool_exec("security", "security", e))?;
+
+	if output.status.code() == Some(SECURITY_ITEM_NOT_FOUND) {
+		return Ok(false);
+	}
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!(
+			"Cannot remove the {} token from the keychain: {}",
+			service.id(),
+			stderr.trim()
+		)));
+	}
+
+	Ok(true)
+}
+
+// region:    --- Support
+
+/// Quotes an argument of a `security -i` command line (double quotes, with `"` and `\` escaped).
+fn security_quote(value: &str) -> String {
+	format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// endregion: --- Support
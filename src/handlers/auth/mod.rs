@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod auth_tokens;
+
+pub use auth_tokens::*;
+
+// endregion: --- Modules
@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod sha256_sums;
+
+pub use sha256_sums::*;
+
+// endregion: --- Modules
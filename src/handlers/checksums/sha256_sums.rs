@@ -0,0 +1,117 @@
+//! The `SHA256SUMS` files (the `sha256sum` format, "<hash>  <path>" per line), written next to the exported files
+//! and checked on delivery (`webtk verify-sums`, or `sha256sum -c SHA256SUMS`).
+
+use crate::support::hashes;
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::SPath;
+use std::collections::BTreeMap;
+
+/// The file name of the checksums file, in the directory of the files it covers.
+pub const SHA256SUMS_FILE: &str = "SHA256SUMS";
+
+/// A file of a checksums file, with its path relative to the checksums file directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumEntry {
+	pub path: String,
+	pub hash: String,
+}
+
+/// The result of a checksums file verification (the paths as in the checksums file).
+#[derive(Debug, Default, Serialize)]
+pub struct SumsVerification {
+	pub sums_file: String,
+	pub ok: Vec<String>,
+	/// Files whose content changed.
+	pub mismatched: Vec<String>,
+	pub missing: Vec<String>,
+}
+
+impl SumsVerification {
+	pub fn is_ok(&self) -> bool {
+		self.mismatched.is_empty() && self.missing.is_empty()
+	}
+}
+
+/// Writes (or updates) the `SHA256SUMS` file of a directory with the hashes of the files, sorted by path.
+/// The files outside of the directory are skipped. The entries of the other files of an existing `SHA256SUMS` are kept
+/// while their files exist (e.g., the outputs of the other jobs writing to the directory).
+/// Returns the checksums file.
+pub fn update_sha256_sums(dir: &SPath, files: &[String]) -> Result<SPath> {
+	let sums_file = dir.join(SHA256SUMS_FILE);
+
+	let mut hashes_by_path: BTreeMap<String, String> = BTreeMap::new();
+	if sums_file.exists() {
+		let content = std::fs::read_to_string(sums_file.as_std_path())
+			.map_err(|e| format!("Failed to read '{sums_file}': {e}"))?;
+		for entry in parse_sha256_sums(&content)? {
+			if dir.join(&entry.path).exists() {
+				hashes_by_path.insert(entry.path, entry.hash);
+			}
+		}
+	}
+
+	for file in files {
+		let file = SPath::new(file);
+		let Ok(rel_path) = file.as_std_path().strip_prefix(dir.as_std_path()) else {
+			continue;
+		};
+		let rel_path = rel_path.to_string_lossy().replace('\\', "/");
+		if rel_path == SHA256SUMS_FILE || !file.is_file() {
+			continue;
+		}
+		let content = std::fs::read(file.as_std_path()).map_err(|e| format!("Failed to read '{file}': {e}"))?;
+		hashes_by_path.insert(rel_path, hashes::sha256_hex(content));
+	}
+
+	let content: String = hashes_by_path.iter().map(|(path, hash)| format!("{hash}  {path}\n")).collect();
+	std::fs::write(sums_file.as_std_path(), content).map_err(|e| format!("Failed to write '{sums_file}': {e}"))?;
+
+	Ok(sums_file)
+}
+
+/// Parses the content of a checksums file: "<hash>  <path>" lines (or "<hash> *<path>", the binary mode).
+/// The empty lines are ignored.
+pub fn parse_sha256_sums(content: &str) -> Result<Vec<SumEntry>> {
+	let mut entries = Vec::new();
+	for (i, line) in content.lines().enumerate() {
+		if line.trim().is_empty() {
+			continue;
+		}
+		let entry = line.split_once(' ').and_then(|(hash, rest)| {
+			let path = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('*'))?;
+			let is_hash = hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+			(is_hash && !path.is_empty()).then(|| SumEntry { path: path.to_string(), hash: hash.to_lowercase() })
+		});
+		let entry = entry.ok_or_else(|| {
+			Error::custom(format!("Invalid checksum line {}: '{line}'. Expected '<sha256>  <path>'", i + 1))
+		})?;
+		entries.push(entry);
+	}
+	Ok(entries)
+}
+
+/// Verifies the files of a checksums file (a `SHA256SUMS` file, or a directory containing one),
+/// the paths relative to its directory.
+pub fn verify_sha256_sums(path: &SPath) -> Result<SumsVerification> {
+	let sums_file = if path.is_dir() { path.join(SHA256SUMS_FILE) } else { path.clone() };
+	if !sums_file.exists() {
+		return Err(Error::FileNotFound(sums_file));
+	}
+	let dir = sums_file.parent().unwrap_or_else(|| SPath::new(""));
+
+	let content =
+		std::fs::read_to_string(sums_file.as_std_path()).map_err(|e| format!("Failed to read '{sums_file}': {e}"))?;
+
+	let mut verification = SumsVerification { sums_file: sums_file.to_string(), ..Default::default() };
+	for entry in parse_sha256_sums(&content)? {
+		let file = dir.join(&entry.path);
+		match std::fs::read(file.as_std_path()) {
+			Ok(content) if hashes::sha256_hex(&content) == entry.hash => verification.ok.push(entry.path),
+			Ok(_) => verification.mismatched.push(entry.path),
+			Err(_) => verification.missing.push(entry.path),
+		}
+	}
+
+	Ok(verification)
+}
@@ -0,0 +1,36 @@
+//! The generated TypeScript module of the CDN URLs of the exported files (see `ExportOptions::cdn_base`),
+//! rendered from a template (see `render_codegen_template`).
+
+use crate::Result;
+use crate::handlers::codegen::{
+	ASSET_URLS_TS_TEMPLATE, GENERATED_NOTICE, codegen_string_literal, render_codegen_template,
+};
+use serde::Serialize;
+use simple_fs::SPath;
+use std::collections::BTreeMap;
+
+/// The context of the asset URLs template.
+#[derive(Debug, Serialize)]
+pub struct AssetUrlsContext {
+	pub notice: &'static str,
+	/// Sorted by name.
+	pub urls: Vec<AssetUrlItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssetUrlItem {
+	/// The logical name as a string literal (e.g., `"ico/user/fill.svg"`).
+	pub name: String,
+	/// The URL as a string literal.
+	pub url: String,
+}
+
+/// Generates the TypeScript module of the asset URLs (an `ASSET_URLS` const object by logical name, and its
+/// `AssetName` key type).
+pub fn asset_urls_ts(urls: &BTreeMap<String, String>, template_dir: Option<&SPath>) -> Result<String> {
+	let urls = urls
+		.iter()
+		.map(|(name, url)| AssetUrlItem { name: codegen_string_literal(name), url: codegen_string_literal(url) })
+		.collect();
+	render_codegen_template(ASSET_URLS_TS_TEMPLATE, template_dir, AssetUrlsContext { notice: GENERATED_NOTICE, urls })
+}
@@ -0,0 +1,38 @@
+//! Shared core of the generated source files (e.g., the icon names of a sprite).
+
+use crate::Result;
+use simple_fs::{SPath, ensure_dir};
+
+/// The first line of the generated files (after the language comment marker).
+pub const GENERATED_NOTICE: &str = "Generated by webtk. Do not edit.";
+
+/// Returns the names sorted and deduplicated, so the generated files are stable across exports.
+pub fn codegen_names(names: &[String]) -> Vec<&str> {
+	let mut names: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+	names.sort();
+	names.dedup();
+	names
+}
+
+/// Quotes a name as a string literal (JSON escaping, valid in TypeScript and Rust for the icon names).
+pub fn codegen_string_literal(name: &str) -> String {
+	serde_json::to_string(name).unwrap_or_else(|_| format!("\"{name}\""))
+}
+
+/// Writes a generated file, creating its parent directory.
+/// The file is not rewritten when unchanged (so watchers and incremental builds are not triggered).
+pub fn write_codegen_file(file: &SPath, content: &str) -> Result<()> {
+	if let Ok(existing) = std::fs::read_to_string(file.as_std_path())
+		&& existing == content
+	{
+		return Ok(());
+	}
+
+	if let Some(parent) = file.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+	}
+	std::fs::write(file.as_std_path(), content)
+		.map_err(|e| format!("Failed to write generated file '{}': {e}", file))?;
+
+	Ok(())
+}
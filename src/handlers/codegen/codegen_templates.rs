@@ -0,0 +1,112 @@
+//! The templates of the generated files (minijinja). The built-in templates are embedded in the binary,
+//! and a file of the same name in the template directory (`--template-dir`, `template_dir`) replaces one.
+
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The TypeScript module of the icon names (see `icon_names_ts`).
+pub const TS_ICON_NAMES_TEMPLATE: &str = "icon-names.ts.jinja";
+
+/// The Rust module of the icon names (see `icon_names_rust`).
+pub const RUST_ICON_NAMES_TEMPLATE: &str = "icon-names.rs.jinja";
+
+/// The TypeScript module of the icon paths (see `icon_paths_ts`).
+pub const TS_ICON_PATHS_TEMPLATE: &str = "icon-paths.ts.jinja";
+
+/// The HTML `<use>` snippets of a sprite (see `use_snippets_html`).
+pub const USE_SNIPPETS_TEMPLATE: &str = "use-snippets.html.jinja";
+
+/// The Markdown usage docs of the icons of a sprite (see `usage_docs`).
+pub const USAGE_MD_TEMPLATE: &str = "usage.md.jinja";
+
+/// The HTML usage docs of the icons of a sprite (see `usage_docs`).
+pub const USAGE_HTML_TEMPLATE: &str = "usage.html.jinja";
+
+/// The TypeScript module of the asset URLs (see `asset_urls_ts`).
+pub const ASSET_URLS_TS_TEMPLATE: &str = "asset-urls.ts.jinja";
+
+/// The HTML email snippet of the email-safe exports (see `email_snippet`).
+pub const EMAIL_HTML_TEMPLATE: &str = "email-snippet.html.jinja";
+
+/// The MJML email snippet of the email-safe exports (see `email_snippet`).
+pub const EMAIL_MJML_TEMPLATE: &str = "email-snippet.mjml.jinja";
+
+/// The spec sheet of an artboard (see `generate_spec_sheets`).
+pub const SPEC_SHEET_TEMPLATE: &str = "spec.html.jinja";
+
+/// The index of the spec sheets (see `generate_spec_sheets`).
+pub const SPEC_INDEX_TEMPLATE: &str = "spec-index.html.jinja";
+
+/// The design tokens as CSS custom properties (see `convert_tokens`).
+pub const CSS_TOKENS_TEMPLATE: &str = "tokens.css.jinja";
+
+/// The design tokens as SCSS variables.
+pub const SCSS_TOKENS_TEMPLATE: &str = "tokens.scss.jinja";
+
+/// The design tokens as TypeScript constants.
+pub const TS_TOKENS_TEMPLATE: &str = "tokens.ts.jinja";
+
+/// The design tokens as Android resources (`res/values/tokens.xml`).
+pub const ANDROID_TOKENS_TEMPLATE: &str = "tokens.android.xml.jinja";
+
+/// The design tokens as a SwiftUI enum of constants.
+pub const IOS_TOKENS_TEMPLATE: &str = "tokens.ios.swift.jinja";
+
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+	(TS_ICON_NAMES_TEMPLATE, include_str!("templates/icon-names.ts.jinja")),
+	(RUST_ICON_NAMES_TEMPLATE, include_str!("templates/icon-names.rs.jinja")),
+	(TS_ICON_PATHS_TEMPLATE, include_str!("templates/icon-paths.ts.jinja")),
+	(USE_SNIPPETS_TEMPLATE, include_str!("templates/use-snippets.html.jinja")),
+	(USAGE_MD_TEMPLATE, include_str!("templates/usage.md.jinja")),
+	(USAGE_HTML_TEMPLATE, include_str!("templates/usage.html.jinja")),
+	(ASSET_URLS_TS_TEMPLATE, include_str!("templates/asset-urls.ts.jinja")),
+	(EMAIL_HTML_TEMPLATE, include_str!("templates/email-snippet.html.jinja")),
+	(EMAIL_MJML_TEMPLATE, include_str!("templates/email-snippet.mjml.jinja")),
+	(SPEC_SHEET_TEMPLATE, include_str!("templates/spec.html.jinja")),
+	(SPEC_INDEX_TEMPLATE, include_str!("templates/spec-index.html.jinja")),
+	(CSS_TOKENS_TEMPLATE, include_str!("templates/tokens.css.jinja")),
+	(SCSS_TOKENS_TEMPLATE, include_str!("templates/tokens.scss.jinja")),
+	(TS_TOKENS_TEMPLATE, include_str!("templates/tokens.ts.jinja")),
+	(ANDROID_TOKENS_TEMPLATE, include_str!("templates/tokens.android.xml.jinja")),
+	(IOS_TOKENS_TEMPLATE, include_str!("templates/tokens.ios.swift.jinja")),
+];
+
+/// Returns the names and contents of the built-in templates.
+pub fn builtin_templates() -> &'static [(&'static str, &'static str)] {
+	BUILTIN_TEMPLATES
+}
+
+/// Renders a template with the context: `<template_dir>/<name>` when it exists, otherwise the built-in template.
+/// The output is not escaped (source code, not HTML), and keeps the trailing newline of the template.
+pub fn render_codegen_template(name: &str, template_dir: Option<&SPath>, context: impl Serialize) -> Result<String> {
+	if let Some(dir) = template_dir
+		&& !dir.is_dir()
+	{
+		return Err(Error::custom(format!("Template directory '{dir}' not found")));
+	}
+
+	let (source, origin) = match template_dir.map(|dir| dir.join(name)).filter(|file| file.exists()) {
+		Some(file) => {
+			let source = std::fs::read_to_string(file.as_std_path())
+				.map_err(|e| format!("Failed to read template '{file}': {e}"))?;
+			(source, file.to_string())
+		}
+		None => {
+			let source = BUILTIN_TEMPLATES
+				.iter()
+				.find(|(builtin_name, _)| *builtin_name == name)
+				.map(|(_, source)| source.to_string())
+				.ok_or_else(|| Error::custom(format!("Unknown codegen template '{name}'")))?;
+			(source, format!("built-in {name}"))
+		}
+	};
+
+	let mut env = minijinja::Environment::new();
+	env.set_keep_trailing_newline(true);
+	env.set_auto_escape_callback(|_| minijinja::AutoEscape::None);
+
+	env.add_template(name, &source)
+		.and_then(|_| env.get_template(name)?.render(context))
+		.map_err(|e| Error::custom(format!("Failed to render template '{origin}'. Cause: {e}")))
+}
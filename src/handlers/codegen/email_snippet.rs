@@ -0,0 +1,60 @@
+//! The email snippet of the email-safe exports (see `ExportOptions::email_safe`), rendered from templates
+//! (see `render_codegen_template`): one image per exported file, referenced by its `cid:` (the file name, for the
+//! inline attachments), with the display size in the attributes (Outlook ignores the CSS sizes).
+
+use crate::handlers::codegen::{EMAIL_HTML_TEMPLATE, EMAIL_MJML_TEMPLATE, GENERATED_NOTICE, render_codegen_template};
+use crate::{Error, Result};
+use quick_xml::escape::escape;
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The context of the email snippet templates.
+#[derive(Debug, Serialize)]
+pub struct EmailSnippetContext<'a> {
+	pub notice: &'static str,
+	/// In export order.
+	pub images: &'a [EmailSnippetItem],
+}
+
+/// An image of the snippet, with its attribute values escaped.
+#[derive(Debug, Default, Serialize)]
+pub struct EmailSnippetItem {
+	/// The file name, also its Content-ID (e.g., "hero-banner.png").
+	pub cid: String,
+	/// The artboard name.
+	pub alt: String,
+	/// The display size, in CSS px.
+	pub width: u32,
+	pub height: u32,
+}
+
+impl EmailSnippetItem {
+	pub fn new(cid: &str, alt: &str, width: u32, height: u32) -> Self {
+		Self { cid: escape(cid).into_owned(), alt: escape(alt).into_owned(), width, height }
+	}
+}
+
+/// Returns the template of an email snippet file, by its extension ("html" or "mjml").
+pub fn email_snippet_template(snippet_file: &SPath) -> Result<&'static str> {
+	match snippet_file.ext() {
+		"html" => Ok(EMAIL_HTML_TEMPLATE),
+		"mjml" => Ok(EMAIL_MJML_TEMPLATE),
+		other => Err(Error::custom(format!(
+			"Invalid email snippet file '{snippet_file}' (extension '{other}'). Expected a .html or .mjml file"
+		))),
+	}
+}
+
+/// Generates the email snippet of the images (HTML or MJML, see `email_snippet_template`), e.g.,
+///
+/// ```html
+/// <img src="cid:hero-banner.png" width="600" height="300" alt="hero/banner" style="display:block;border:0;...">
+/// ```
+pub fn email_snippet(
+	snippet_file: &SPath,
+	images: &[EmailSnippetItem],
+	template_dir: Option<&SPath>,
+) -> Result<String> {
+	let template = email_snippet_template(snippet_file)?;
+	render_codegen_template(template, template_dir, EmailSnippetContext { notice: GENERATED_NOTICE, images })
+}
@@ -0,0 +1,118 @@
+//! The generated modules of the icon names of a sprite (TypeScript, Rust), rendered from templates
+//! (see `render_codegen_template`). All the templates get the same context (`IconNamesContext`).
+
+use crate::Result;
+use crate::handlers::codegen::{
+	GENERATED_NOTICE, RUST_ICON_NAMES_TEMPLATE, TS_ICON_NAMES_TEMPLATE, codegen_names, codegen_string_literal,
+	render_codegen_template,
+};
+use crate::support::strings;
+use serde::Serialize;
+use simple_fs::SPath;
+use std::collections::HashSet;
+
+/// The default TypeScript type name of the icon names (`--ts-type-name`).
+pub const DEFAULT_TS_TYPE_NAME: &str = "IconName";
+
+/// The name of the slice of all the icon names in the generated Rust module.
+pub const RUST_ALL_ICONS_NAME: &str = "ALL_ICONS";
+
+/// The context of the icon names templates.
+#[derive(Debug, Serialize)]
+pub struct IconNamesContext {
+	pub notice: &'static str,
+	/// Sorted and deduplicated.
+	pub icons: Vec<IconNameItem>,
+	/// The TypeScript type name (e.g., "IconName").
+	pub type_name: String,
+	/// The TypeScript array name, after the type name (e.g., "iconNames").
+	pub array_name: String,
+	/// The Rust slice name ("ALL_ICONS").
+	pub all_icons_name: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IconNameItem {
+	/// The icon name (e.g., "ico-user-fill").
+	pub name: String,
+	/// The name as a string literal (e.g., `"ico-user-fill"`).
+	pub literal: String,
+	/// The Rust constant name, unique in the module (e.g., "ICO_USER_FILL").
+	pub const_name: String,
+}
+
+/// Returns the context of the icon names templates.
+pub fn icon_names_context(names: &[String], type_name: &str) -> IconNamesContext {
+	let mut taken: HashSet<String> = HashSet::new();
+	let icons = codegen_names(names)
+		.into_iter()
+		.map(|name| IconNameItem {
+			name: name.to_string(),
+			literal: codegen_string_literal(name),
+			const_name: unique_const_name(&rust_const_name(name), &mut taken),
+		})
+		.collect();
+
+	let array_name = {
+		let mut chars = type_name.chars();
+		let first = chars.next().map(|c| c.to_ascii_lowercase()).into_iter();
+		format!("{}s", first.chain(chars).collect::<String>())
+	};
+
+	IconNamesContext {
+		notice: GENERATED_NOTICE,
+		icons,
+		type_name: type_name.to_string(),
+		array_name,
+		all_icons_name: RUST_ALL_ICONS_NAME,
+	}
+}
+
+/// Generates a TypeScript module with the union type of the icon names and their const array, e.g.,
+///
+/// ```ts
+/// export type IconName = "ico-user-fill" | "ico-user-line";
+///
+/// export const iconNames: readonly IconName[] = ["ico-user-fill", "ico-user-line"];
+/// ```
+///
+/// The array is named after the type (e.g., "IconName" -> "iconNames").
+pub fn icon_names_ts(names: &[String], type_name: &str, template_dir: Option<&SPath>) -> Result<String> {
+	render_codegen_template(TS_ICON_NAMES_TEMPLATE, template_dir, icon_names_context(names, type_name))
+}
+
+/// Generates a Rust module with one constant per icon name and the slice of all of them, e.g.,
+///
+/// ```rust,ignore
+/// pub const ICO_USER_FILL: &str = "ico-user-fill";
+///
+/// pub const ALL_ICONS: &[&str] = &[ICO_USER_FILL];
+/// ```
+pub fn icon_names_rust(names: &[String], template_dir: Option<&SPath>) -> Result<String> {
+	render_codegen_template(RUST_ICON_NAMES_TEMPLATE, template_dir, icon_names_context(names, DEFAULT_TS_TYPE_NAME))
+}
+
+/// Returns the SCREAMING_SNAKE_CASE constant name of an icon name (e.g., "ico-user-fill" -> "ICO_USER_FILL").
+/// Names starting with a digit get an "ICON_" prefix.
+fn rust_const_name(name: &str) -> String {
+	let const_name = strings::canonicalize_name(name).replace('-', "_").to_ascii_uppercase();
+	let const_name = const_name.trim_matches('_');
+	if const_name.is_empty() || const_name.starts_with(|c: char| c.is_ascii_digit()) {
+		format!("ICON_{const_name}")
+	} else {
+		const_name.to_string()
+	}
+}
+
+/// Returns the constant name made unique among `taken` (e.g., "ICO_A", then "ICO_A_2").
+/// Different ids can collide once uppercased (e.g., "Ico-a" and "ico-a").
+fn unique_const_name(const_name: &str, taken: &mut HashSet<String>) -> String {
+	let mut candidate = const_name.to_string();
+	let mut n = 2;
+	while taken.contains(&candidate) {
+		candidate = format!("{const_name}_{n}");
+		n += 1;
+	}
+	taken.insert(candidate.clone());
+	candidate
+}
@@ -0,0 +1,49 @@
+//! The generated TypeScript module of the path data of the single-path icons of a sprite (see
+//! `svg::single_path_icon`), rendered from a template (see `render_codegen_template`).
+
+use crate::Result;
+use crate::handlers::codegen::{
+	GENERATED_NOTICE, TS_ICON_PATHS_TEMPLATE, codegen_string_literal, render_codegen_template,
+};
+use crate::handlers::svg::IconPath;
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The context of the icon paths template.
+#[derive(Debug, Serialize)]
+pub struct IconPathsContext {
+	pub notice: &'static str,
+	/// Sorted by name.
+	pub icons: Vec<IconPathItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IconPathItem {
+	/// The icon name as a string literal (e.g., `"ico-user-fill"`).
+	pub literal: String,
+	/// The viewBox as a string literal (e.g., `"0 0 24 24"`).
+	pub view_box: String,
+	/// The path data as a string literal.
+	pub path: String,
+	/// The fill rule as a string literal (`"evenodd"`), when not the default.
+	pub fill_rule: Option<String>,
+}
+
+/// Generates the TypeScript module of the icon paths (an `ICON_PATHS` const object of `{ viewBox, path }` by icon
+/// name, `fillRule` for the even-odd icons, and its `IconPathName` key type).
+pub fn icon_paths_ts(icons: &[(String, IconPath)], template_dir: Option<&SPath>) -> Result<String> {
+	let mut icons: Vec<&(String, IconPath)> = icons.iter().collect();
+	icons.sort_by(|a, b| a.0.cmp(&b.0));
+	icons.dedup_by(|a, b| a.0 == b.0);
+
+	let icons = icons
+		.into_iter()
+		.map(|(name, icon)| IconPathItem {
+			literal: codegen_string_literal(name),
+			view_box: codegen_string_literal(&icon.view_box),
+			path: codegen_string_literal(&icon.path),
+			fill_rule: icon.fill_rule.as_deref().map(codegen_string_literal),
+		})
+		.collect();
+	render_codegen_template(TS_ICON_PATHS_TEMPLATE, template_dir, IconPathsContext { notice: GENERATED_NOTICE, icons })
+}
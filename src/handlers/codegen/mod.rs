@@ -0,0 +1,19 @@
+// region:    --- Modules
+
+mod asset_urls;
+mod codegen_file;
+mod codegen_templates;
+mod email_snippet;
+mod icon_names;
+mod icon_paths;
+mod use_snippets;
+
+pub use asset_urls::*;
+pub use codegen_file::*;
+pub use codegen_templates::*;
+pub use email_snippet::*;
+pub use icon_names::*;
+pub use icon_paths::*;
+pub use use_snippets::*;
+
+// endregion: --- Modules
@@ -0,0 +1,113 @@
+//! The generated files of the `<use>` snippets of a sprite, rendered from templates (see `render_codegen_template`):
+//! the `<use>` snippets file (one `<svg>` per symbol, with the default size and preserveAspectRatio of the symbol,
+//! as the browsers differ on the intrinsic size of a `<use>` without them), and the usage docs (Markdown or HTML,
+//! with the copy-pasteable snippets of each icon). All the templates get the same context (`UseSnippetsContext`).
+
+use crate::handlers::codegen::{
+	GENERATED_NOTICE, USAGE_HTML_TEMPLATE, USAGE_MD_TEMPLATE, USE_SNIPPETS_TEMPLATE, render_codegen_template,
+};
+use crate::{Error, Result};
+use quick_xml::escape::escape;
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The prefix of the CSS class of an icon in the usage docs (e.g., "icon-ico-user-fill").
+pub const USAGE_CSS_CLASS_PREFIX: &str = "icon-";
+
+/// The context of the `<use>` snippets templates.
+#[derive(Debug, Serialize)]
+pub struct UseSnippetsContext<'a> {
+	pub notice: &'static str,
+	/// The sprite file, relative to the generated file (e.g., "symbols.svg").
+	pub sprite_href: String,
+	/// The sprite file as a JavaScript import path (e.g., "./symbols.svg").
+	pub sprite_import: String,
+	/// In sprite order.
+	pub symbols: &'a [UseSnippetItem],
+}
+
+/// A symbol of the snippets, with its attribute values escaped.
+#[derive(Debug, Default, Serialize)]
+pub struct UseSnippetItem {
+	pub id: String,
+	/// The artboard name.
+	pub name: String,
+	/// The CSS class of the icon (e.g., "icon-ico-user-fill").
+	pub css_class: String,
+	pub width: Option<String>,
+	pub height: Option<String>,
+	pub preserve_aspect_ratio: Option<String>,
+}
+
+impl UseSnippetItem {
+	pub fn new(id: &str, name: &str, size: Option<(&str, &str)>, preserve_aspect_ratio: Option<&str>) -> Self {
+		Self {
+			id: escape(id).into_owned(),
+			name: escape(name).into_owned(),
+			css_class: escape(format!("{USAGE_CSS_CLASS_PREFIX}{id}")).into_owned(),
+			width: size.map(|(width, _)| escape(width).into_owned()),
+			height: size.map(|(_, height)| escape(height).into_owned()),
+			preserve_aspect_ratio: preserve_aspect_ratio.map(|value| escape(value).into_owned()),
+		}
+	}
+}
+
+/// Returns the context of the snippets templates of a file generated for a sprite.
+pub fn use_snippets_context<'a>(
+	output_file: &SPath,
+	sprite_file: &SPath,
+	symbols: &'a [UseSnippetItem],
+) -> UseSnippetsContext<'a> {
+	let sprite_href = output_file
+		.parent()
+		.and_then(|dir| sprite_file.diff(dir))
+		.map(|path| path.to_string().replace('\\', "/"))
+		.unwrap_or_else(|| sprite_file.name().to_string());
+	let sprite_import = if sprite_href.starts_with("../") { sprite_href.clone() } else { format!("./{sprite_href}") };
+
+	UseSnippetsContext {
+		notice: GENERATED_NOTICE,
+		sprite_href: escape(&sprite_href).into_owned(),
+		sprite_import: escape(&sprite_import).into_owned(),
+		symbols,
+	}
+}
+
+/// Generates the `<use>` snippets of the symbols of a sprite, e.g.,
+///
+/// ```html
+/// <svg width="24" height="24" aria-hidden="true"><use href="symbols.svg#ico-user-fill"/></svg>
+/// ```
+pub fn use_snippets_html(
+	snippets_file: &SPath,
+	sprite_file: &SPath,
+	symbols: &[UseSnippetItem],
+	template_dir: Option<&SPath>,
+) -> Result<String> {
+	let context = use_snippets_context(snippets_file, sprite_file, symbols);
+	render_codegen_template(USE_SNIPPETS_TEMPLATE, template_dir, context)
+}
+
+/// Returns the template of a usage docs file, by its extension ("md" or "html").
+pub fn usage_docs_template(usage_file: &SPath) -> Result<&'static str> {
+	match usage_file.ext() {
+		"md" => Ok(USAGE_MD_TEMPLATE),
+		"html" => Ok(USAGE_HTML_TEMPLATE),
+		other => Err(Error::custom(format!(
+			"Invalid usage docs file '{usage_file}' (extension '{other}'). Expected a .md or .html file"
+		))),
+	}
+}
+
+/// Generates the usage docs of the icons of a sprite (Markdown or HTML, see `usage_docs_template`), with the
+/// copy-pasteable snippets of each icon: the `<svg><use/></svg>` markup, its React (JSX) form with the sprite
+/// import line, and its CSS class.
+pub fn usage_docs(
+	usage_file: &SPath,
+	sprite_file: &SPath,
+	symbols: &[UseSnippetItem],
+	template_dir: Option<&SPath>,
+) -> Result<String> {
+	let template = usage_docs_template(usage_file)?;
+	render_codegen_template(template, template_dir, use_snippets_context(usage_file, sprite_file, symbols))
+}
@@ -0,0 +1,92 @@
+//! Starter `webtk.toml` scaffolding (`webtk config init`): finds the Sketch files and guesses the output dirs.
+//! The questions are asked by the CLI, this module only inspects and renders.
+
+use crate::handlers::config::{DISCOVERY_EXCLUDE_GLOBS, parse_config};
+use crate::support::strings;
+use crate::{Error, Result};
+use simple_fs::{ListOptions, SPath};
+
+/// The directories commonly served as static assets, in guess order.
+const STATIC_DIR_CANDIDATES: &[&str] = &["public", "static", "assets", "src/assets"];
+
+/// The output base directory when no static directory is found.
+const DEFAULT_OUTPUT_BASE: &str = "dist";
+
+/// A job of the starter config.
+#[derive(Debug, Clone)]
+pub struct InitJob {
+	pub name: String,
+	/// Relative to the config directory.
+	pub sketch_file: String,
+	pub formats: Vec<String>,
+	/// Relative to the config directory.
+	pub output: String,
+}
+
+/// Returns the `.sketch` files below a directory (relative paths, sorted), ignoring `node_modules`, `.git`, `target`.
+pub fn find_sketch_files(dir: &SPath) -> Result<Vec<String>> {
+	let options = ListOptions::from_relative_glob(true).with_exclude_globs(DISCOVERY_EXCLUDE_GLOBS);
+	let files = simple_fs::list_files(dir.as_std_path(), Some(&["**/*.sketch"]), Some(options))
+		.map_err(Error::custom_from_err)?;
+
+	let mut sketch_files: Vec<String> = files
+		.iter()
+		.filter_map(|file| file.diff(dir))
+		.map(|file| file.to_string())
+		.collect();
+	sketch_files.sort();
+
+	Ok(sketch_files)
+}
+
+/// Returns the output base directory: the first existing static directory (e.g., "public"), or "dist".
+pub fn guess_output_base(dir: &SPath) -> String {
+	STATIC_DIR_CANDIDATES
+		.iter()
+		.find(|candidate| dir.join(candidate).is_dir())
+		.unwrap_or(&DEFAULT_OUTPUT_BASE)
+		.to_string()
+}
+
+/// Returns the default job of a Sketch file: named after the file (e.g., "design/Icons.sketch" -> "icons"),
+/// exported as an svg-symbols sprite into `<output_base>/<name>`.
+pub fn default_init_job(sketch_file: &str, output_base: &str) -> InitJob {
+	let stem = SPath::new(sketch_file).stem().to_string();
+	let name = strings::canonicalize_name(&stem).trim_matches('-').to_lowercase();
+	let name = if name.is_empty() { "icons".to_string() } else { name };
+
+	InitJob {
+		output: format!("{output_base}/{name}"),
+		name,
+		sketch_file: sketch_file.to_string(),
+		formats: vec!["svg-symbols".to_string()],
+	}
+}
+
+/// Renders the starter `webtk.toml` (validated like a loaded config).
+pub fn render_init_config(jobs: &[InitJob]) -> Result<String> {
+	let mut content = String::from(
+		"# webtk config (see `webtk config schema` for all the keys)\n\
+		 # Paths are relative to this file. Run with `webtk run`.\n",
+	);
+
+	for job in jobs {
+		let formats: Vec<String> = job.formats.iter().map(|f| toml_string(f)).collect();
+		content.push_str(&format!(
+			"\n[[jobs]]\nname = {}\nsketch_file = {}\nglobs = [\"**\"]\nformats = [{}]\noutput = {}\n# flatten = true\n# symbols_manifest = true\n",
+			toml_string(&job.name),
+			toml_string(&job.sketch_file),
+			formats.join(", "),
+			toml_string(&job.output),
+		));
+	}
+
+	parse_config(&content, "generated webtk.toml")?;
+
+	Ok(content)
+}
+
+/// Quotes a value as a TOML basic string.
+fn toml_string(value: &str) -> String {
+	toml::Value::String(value.to_string()).to_string()
+}
@@ -0,0 +1,156 @@
+//! The JSON Schema of `webtk.toml` (for editor tooling, e.g., Taplo or Even Better TOML).
+//! Note: Keep in sync with the `WebtkConfig` types (unknown keys are rejected by both).
+
+use crate::handlers::sketch::EXPORT_FORMATS;
+use serde_json::{Value, json};
+
+/// Returns the JSON Schema (draft-07) of `webtk.toml`.
+pub fn config_schema() -> Value {
+	let string_array =
+		|description: &str| json!({ "type": "array", "items": { "type": "string" }, "description": description });
+	let formats = json!({
+		"type": "array",
+		"items": { "type": "string", "enum": EXPORT_FORMATS },
+		"description": "Export formats"
+	});
+
+	let artboard_override = json!({
+		"type": "object",
+		"description": "The job artboards matching `glob` get an extra export with these fields (others inherited from the job)",
+		"additionalProperties": false,
+		"required": ["glob"],
+		"properties": {
+			"glob": { "type": "string", "description": "Glob on the artboard names (within the job globs)" },
+			"formats": formats,
+			"scales": string_array("Export scales, e.g., [\"1\", \"2\"]"),
+			"output": { "type": "string", "description": "Output directory or file" },
+			"name": { "type": "string", "description": "Output file name, without extension (single artboard)" },
+			"replace": { "type": "boolean", "default": false, "description": "Only export the matched artboards with this override" }
+		}
+	});
+
+	let job = json!({
+		"type": "object",
+		"additionalProperties": false,
+		"required": ["sketch_file", "formats", "output"],
+		"properties": {
+			"name": { "type": "string", "description": "Job name (defaults to job-1, job-2, ...)" },
+			"sketch_file": { "type": "string", "description": "Path to the Sketch file (relative to the webtk.toml)" },
+			"globs": string_array("Globs on the artboard names (all artboards when empty)"),
+			"exclude": string_array("Globs of artboards to exclude"),
+			"glob_scope": { "type": "string", "enum": ["name", "page-path"], "default": "name", "description": "What the globs match: the artboard name, or the page and artboard name (e.g., \"Icons/ico/*\")" },
+			"ignore_case": { "type": "boolean", "default": false, "description": "Match the globs case insensitively (ico/* matches Ico/User)" },
+			"smart_case": { "type": "boolean", "default": false, "description": "Match the globs case insensitively, unless they have an uppercase letter" },
+			"formats": formats,
+			"scales": string_array("Export scales for the regular formats, e.g., [\"1\", \"2\"]"),
+			"use_design_presets": { "type": "boolean", "default": false, "description": "Export the artboards with their Sketch export presets (formats and scales), the others with formats and scales" },
+			"output": { "type": "string", "description": "Output directory or file (supports tokens, e.g., {git_short_sha})" },
+			"overrides": { "type": "array", "items": artboard_override, "description": "Per-artboard overrides" },
+			"flatten": { "type": "boolean", "default": false, "description": "Flatten the file names (ico/user/fill -> ico-user-fill)" },
+			"allow_empty": { "type": "boolean", "default": false, "description": "Succeed when no artboards match" },
+			"keep_raw_export": { "type": "boolean", "default": false, "description": "Keep the raw export cache directory" },
+			"symbol_data_attrs": { "type": "boolean", "default": false, "description": "Add data-name and data-page to the symbols" },
+			"symbols_manifest": { "type": "boolean", "default": false, "description": "Write a symbols.json manifest next to the sprite" },
+			"ts_out": { "type": "string", "description": "TypeScript module of the symbol ids" },
+			"ts_type_name": { "type": "string", "default": "IconName", "description": "TypeScript type name of ts_out" },
+			"rust_out": { "type": "string", "description": "Rust module of the symbol ids" },
+			"use_snippets_out": { "type": "string", "description": "HTML file of the <use> snippets of the symbols (with their size and preserveAspectRatio)" },
+			"usage_out": { "type": "string", "description": "Usage docs of the icons (.md or .html), with the snippets of each icon" },
+			"paths_ts_out": { "type": "string", "description": "TypeScript module of the path data ({ viewBox, path }) of the single-path icons" },
+			"template_dir": { "type": "string", "description": "Codegen templates overriding the built-in ones by name (e.g., icon-names.ts.jinja)" },
+			"preserve_aspect_ratio": string_array("preserveAspectRatio rules, VALUE or GLOB=VALUE (first match wins)"),
+			"symbol_size": string_array("Default symbol width/height rules, VALUE or GLOB=VALUE with VALUE intrinsic, a length, or WIDTH,HEIGHT"),
+			"strip_namespaces": string_array("Namespace prefixes to strip from the symbols, e.g., [\"sketch\"]"),
+			"trim": { "type": "boolean", "default": false, "description": "Trim the transparent space around the exported images" },
+			"group_contents_only": { "type": "boolean", "default": false, "description": "Only export the layers within the artboard bounds (no background)" },
+			"padding": { "type": "integer", "minimum": 0, "description": "Transparent padding in px on each side (svg, png, svg-symbols)" },
+			"background": { "type": "string", "description": "Background of the raster exports: \"transparent\" or a hex color (e.g., \"#ffffff\")" },
+			"tile": { "type": "integer", "minimum": 1, "description": "Split the png exports into tiles of this size in px, with a <name>.tiles.json descriptor" },
+			"tile_overlap": { "type": "integer", "minimum": 0, "default": 0, "description": "The px each tile extends into its neighbors" },
+			"redline": { "type": "integer", "minimum": 1, "description": "Write a copy of the png exports with a grid of this size in px and the artboard size, in redlines/" },
+			"email_safe": { "type": "boolean", "default": false, "description": "Export email-safe images (png, jpg, jpeg), scaled down to email_max_width, with cid-friendly names" },
+			"email_max_width": { "type": "integer", "minimum": 1, "description": "The max display width in px of the email-safe images (default 600)" },
+			"email_snippet_out": { "type": "string", "description": "Write the email snippet of the email-safe images (.html or .mjml)" },
+			"cdn_base": { "type": "string", "description": "The CDN URL of the output, writes the urls.json of the exported files by logical name" },
+			"cdn_ts_out": { "type": "string", "description": "Write a TypeScript module of the cdn_base URLs" },
+			"pdf_bookmarks": { "type": "boolean", "default": false, "description": "Add a bookmark per page (artboard name) to the pdf-book file" },
+			"non_scaling_stroke": { "type": "boolean", "default": false, "description": "Set vector-effect=\"non-scaling-stroke\" on the stroked shapes (svg, svg-symbols)" },
+			"embed_images": { "type": "boolean", "default": false, "description": "Inline the raster images referenced by the svg and svg-symbols exports as base64 data: URIs" },
+			"extract_images": { "type": "string", "description": "Write the images inlined in the svg and svg-symbols exports (data: URIs) as files in this directory, referenced instead" },
+			"local_refs": { "type": "string", "enum": ["warn", "fail", "strip", "embed"], "default": "warn", "description": "What to do with the references to local files in the svg and svg-symbols exports (strip removes them, embed inlines them as data: URIs)" },
+			"checksums": { "type": "boolean", "default": false, "description": "Write a SHA256SUMS file covering the outputs in the output directory" },
+			"preserve_mtime": { "type": "string", "enum": ["source", "epoch", "now"], "default": "now", "description": "The modification time of the outputs: the Sketch file mtime, SOURCE_DATE_EPOCH (or the Unix epoch), or the write time" },
+			"chmod": { "type": "string", "pattern": "^0?[0-7]{3,4}$", "description": "The octal mode of the output files, e.g., \"644\" (default: 666 less the umask)" },
+			"uid_map": { "type": "boolean", "default": false, "description": "Write a uid-map.json relating each artboard UID to its name and output files" },
+			"tool_args": string_array("Raw sketchtool arguments, ARG or FORMAT:ARG, e.g., [\"png:--background=#ffffff\"]"),
+			"items_batch_size": { "type": "integer", "minimum": 1, "default": 500, "description": "The max artboards of a sketchtool export command (more are exported in several commands)" }
+		}
+	});
+
+	let pipeline_step = json!({
+		"type": "object",
+		"additionalProperties": false,
+		"description": "A job of the config, or a shell command (exactly one)",
+		"properties": {
+			"name": { "type": "string", "description": "Step name (defaults to the job name, or step-1, step-2, ...)" },
+			"job": { "type": "string", "description": "Name of a job of the config (its Sketch file is an input of the step)" },
+			"command": { "type": "string", "description": "Shell command, run from the webtk.toml directory" },
+			"inputs": string_array("Globs of the input files (relative to the webtk.toml); a step without inputs runs when a previous step ran"),
+			"outputs": string_array("Files or directories written by the command (relative to the webtk.toml), for `pipeline graph`")
+		}
+	});
+
+	let pipeline = json!({
+		"type": "object",
+		"additionalProperties": false,
+		"required": ["name", "steps"],
+		"description": "Steps run in order (run --pipeline), each only when its inputs changed since its last run",
+		"properties": {
+			"name": { "type": "string", "description": "Pipeline name" },
+			"steps": { "type": "array", "items": pipeline_step, "minItems": 1 }
+		}
+	});
+
+	json!({
+		"$schema": "http://json-schema.org/draft-07/schema#",
+		"title": "webtk.toml",
+		"type": "object",
+		"additionalProperties": false,
+		"properties": {
+			"workspace": {
+				"type": "object",
+				"additionalProperties": false,
+				"properties": {
+					"members": string_array("Member directories, as globs relative to the root config (e.g., packages/*)")
+				}
+			},
+			"notify": {
+				"type": "object",
+				"additionalProperties": false,
+				"description": "Where `run --watch` reports the rebuild results",
+				"properties": {
+					"desktop": { "type": "boolean", "default": false, "description": "macOS notification center" },
+					"webhook": { "type": "string", "description": "URL receiving a Slack-compatible JSON POST ({\"text\": ...})" }
+				}
+			},
+			"stats": {
+				"type": "object",
+				"additionalProperties": false,
+				"description": "Opt-in local usage stats (commands, durations, artboard counts; never file names, never sent)",
+				"properties": {
+					"enabled": { "type": "boolean", "default": false, "description": "Append a line per command run to the stats file" },
+					"file": { "type": "string", "default": "~/.webtk/stats.jsonl", "description": "The stats file" }
+				}
+			},
+			"network": {
+				"type": "object",
+				"additionalProperties": false,
+				"properties": {
+					"offline": { "type": "boolean", "default": false, "description": "Forbid all network access, like --offline (hermetic builds)" }
+				}
+			},
+			"jobs": { "type": "array", "items": job },
+			"pipelines": { "type": "array", "items": pipeline }
+		}
+	})
+}
@@ -0,0 +1,172 @@
+//! Strict `webtk.toml` parsing and validation, with error locations and "did you mean" hints.
+
+use crate::handlers::config::WebtkConfig;
+use crate::handlers::sketch::{EMAIL_SAFE_FORMATS, EXPORT_FORMATS, GlobScope, LocalRefs, MtimeMode};
+use crate::support::{files, http, strings};
+use crate::{Error, Result};
+use std::collections::HashSet;
+
+/// Parses and validates a `webtk.toml` content (`config_name` is used in the error messages).
+/// Errors have the line and column of the offending key or value, and a "did you mean" hint for unknown keys.
+pub fn parse_config(content: &str, config_name: &str) -> Result<WebtkConfig> {
+	let config: WebtkConfig = toml::from_str(content).map_err(|err| {
+		let location = err
+			.span()
+			.map(|span| {
+				let (line, column) = line_column(content, span.start);
+				format!(" at line {line}, column {column}")
+			})
+			.unwrap_or_default();
+		let message = err.message().trim();
+		let hint = unknown_field_hint(message).map(|hint| format!(". {hint}")).unwrap_or_default();
+		Error::custom(format!("Invalid config '{config_name}'{location}: {message}{hint}"))
+	})?;
+
+	validate_config(&config).map_err(|err| Error::custom(format!("Invalid config '{config_name}': {err}")))?;
+
+	Ok(config)
+}
+
+/// Validates what the config types cannot express (formats, job names, glob scopes, globs, webhook and CDN urls,
+/// pipeline steps).
+pub fn validate_config(config: &WebtkConfig) -> Result<()> {
+	if let Some(webhook) = config.notify.as_ref().and_then(|notify| notify.webhook.as_deref())
+		&& !(webhook.starts_with("https://") || webhook.starts_with("http://"))
+	{
+		return Err(Error::custom(format!("notify.webhook: '{webhook}' is not an http(s) url")));
+	}
+
+	let mut names: HashSet<String> = HashSet::new();
+
+	for (idx, job) in config.jobs.iter().enumerate() {
+		let path = format!("jobs[{idx}]");
+
+		let name = job.display_name(idx);
+		if !names.insert(name.clone()) {
+			return Err(Error::custom(format!("{path}.name: duplicate job name '{name}'")));
+		}
+
+		if job.formats.is_empty() {
+			return Err(Error::custom(format!("{path}.formats: at least one format is required")));
+		}
+		check_formats(&format!("{path}.formats"), &job.formats)?;
+
+		if job.email_safe
+			&& let Some(format) = job.formats.iter().find(|f| !EMAIL_SAFE_FORMATS.contains(&f.as_str()))
+		{
+			return Err(Error::custom(format!(
+				"{path}.formats: '{format}' is not email-safe (email_safe). Supported: {}",
+				EMAIL_SAFE_FORMATS.join(", ")
+			)));
+		}
+		if let Some(cdn_base) = &job.cdn_base
+			&& !http::is_url(cdn_base)
+		{
+			return Err(Error::custom(format!("{path}.cdn_base: '{cdn_base}' is not an http(s) url")));
+		}
+		if job.ignore_case && job.smart_case {
+			return Err(Error::custom(format!("{path}: ignore_case and smart_case cannot be both set")));
+		}
+		if let Some(glob_scope) = &job.glob_scope {
+			GlobScope::try_from(glob_scope.as_str())
+				.map_err(|err| Error::custom(format!("{path}.glob_scope: {err}")))?;
+		}
+		if let Some(preserve_mtime) = &job.preserve_mtime {
+			MtimeMode::try_from(preserve_mtime.as_str())
+				.map_err(|err| Error::custom(format!("{path}.preserve_mtime: {err}")))?;
+		}
+		if let Some(local_refs) = &job.local_refs {
+			LocalRefs::try_from(local_refs.as_str())
+				.map_err(|err| Error::custom(format!("{path}.local_refs: {err}")))?;
+		}
+		if let Some(chmod) = &job.chmod {
+			files::parse_file_mode(chmod).map_err(|err| Error::custom(format!("{path}.chmod: {err}")))?;
+		}
+
+		for (override_idx, artboard_override) in job.overrides.iter().enumerate() {
+			let override_path = format!("{path}.overrides[{override_idx}]");
+			if artboard_override.glob.trim().is_empty() {
+				return Err(Error::custom(format!("{override_path}.glob: the glob cannot be empty")));
+			}
+			if let Some(formats) = &artboard_override.formats {
+				check_formats(&format!("{override_path}.formats"), formats)?;
+			}
+		}
+	}
+
+	let mut pipeline_names: HashSet<&str> = HashSet::new();
+	for (idx, pipeline) in config.pipelines.iter().enumerate() {
+		let path = format!("pipelines[{idx}]");
+		if !pipeline_names.insert(pipeline.name.as_str()) {
+			return Err(Error::custom(format!("{path}.name: duplicate pipeline name '{}'", pipeline.name)));
+		}
+		if pipeline.steps.is_empty() {
+			return Err(Error::custom(format!("{path}.steps: at least one step is required")));
+		}
+
+		let mut step_names: HashSet<String> = HashSet::new();
+		for (step_idx, step) in pipeline.steps.iter().enumerate() {
+			let step_path = format!("{path}.steps[{step_idx}]");
+			let name = step.display_name(step_idx);
+			if !step_names.insert(name.clone()) {
+				return Err(Error::custom(format!("{step_path}.name: duplicate step name '{name}'")));
+			}
+			match (&step.job, &step.command) {
+				(Some(job), None) => {
+					if !names.contains(job) {
+						let job_names: Vec<&str> = names.iter().map(String::as_str).collect();
+						let hint = strings::closest_match(job, &job_names)
+							.map(|known| format!(". Did you mean '{known}'?"))
+							.unwrap_or_default();
+						return Err(Error::custom(format!("{step_path}.job: unknown job '{job}'{hint}")));
+					}
+					if !step.outputs.is_empty() {
+						return Err(Error::custom(format!(
+							"{step_path}.outputs: only for the command steps (the outputs of a job step are the job ones)"
+						)));
+					}
+				}
+				(None, Some(command)) if command.trim().is_empty() => {
+					return Err(Error::custom(format!("{step_path}.command: the command cannot be empty")));
+				}
+				(None, Some(_)) => {}
+				_ => return Err(Error::custom(format!("{step_path}: exactly one of job and command is required"))),
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn check_formats(path: &str, formats: &[String]) -> Result<()> {
+	for format in formats {
+		if !EXPORT_FORMATS.contains(&format.as_str()) {
+			let hint = strings::closest_match(format, EXPORT_FORMATS)
+				.map(|known| format!(". Did you mean '{known}'?"))
+				.unwrap_or_default();
+			return Err(Error::custom(format!(
+				"{path}: unknown format '{format}' (expected one of: {}){hint}",
+				EXPORT_FORMATS.join(", ")
+			)));
+		}
+	}
+	Ok(())
+}
+
+/// Returns the "did you mean" hint of a serde unknown field message
+/// (e.g., "unknown field `formts`, expected one of `name`, `formats`, ...").
+fn unknown_field_hint(message: &str) -> Option<String> {
+	let rest = message.strip_prefix("unknown field `")?;
+	let (field, expected) = rest.split_once('`')?;
+	let expected: Vec<&str> = expected.split('`').skip(1).step_by(2).collect();
+	let known = strings::closest_match(field, &expected)?;
+	Some(format!("Did you mean `{known}`?"))
+}
+
+/// Returns the 1-based line and column (in chars) of a byte offset.
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+	let before = &content[..offset.min(content.len())];
+	let line = before.matches('\n').count() + 1;
+	let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+	(line, before[line_start..].chars().count() + 1)
+}
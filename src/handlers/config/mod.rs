@@ -0,0 +1,15 @@
+// region:    --- Modules
+
+mod config_init;
+mod config_schema;
+mod config_validation;
+mod webtk_config;
+mod workspace;
+
+pub use config_init::*;
+pub use config_schema::*;
+pub use config_validation::*;
+pub use webtk_config::*;
+pub use workspace::*;
+
+// endregion: --- Modules
@@ -0,0 +1,436 @@
+use crate::handlers::config::parse_config;
+use crate::handlers::sketch::ExportOptions;
+use crate::support::files;
+use crate::{Error, Result};
+use serde::Deserialize;
+use simple_fs::{SPath, read_to_string};
+
+/// The project config file name.
+pub const CONFIG_FILE_NAME: &str = "webtk.toml";
+
+/// A `webtk.toml` file: optional `[workspace]`, `[notify]`, `[stats]` and `[network]` sections, the project `[[jobs]]`,
+/// and the `[[pipelines]]` chaining them with commands.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebtkConfig {
+	/// Only meaningful in the workspace root config.
+	#[serde(default)]
+	pub workspace: Option<WorkspaceSection>,
+
+	/// Watch-mode notifications (from the first project, i.e., the workspace root with `--all`).
+	#[serde(default)]
+	pub notify: Option<NotifySection>,
+
+	/// Local usage stats (from the config of the current directory, or of its workspace root).
+	#[serde(default)]
+	pub stats: Option<StatsSection>,
+
+	/// The network access of the commands (from the config of the current directory, or of its workspace root, and
+	/// from the loaded projects).
+	#[serde(default)]
+	pub network: Option<NetworkSection>,
+
+	#[serde(default)]
+	pub jobs: Vec<JobConfig>,
+
+	#[serde(default)]
+	pub pipelines: Vec<PipelineConfig>,
+}
+
+/// The `[workspace]` section of a root `webtk.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceSection {
+	/// Member directories, as globs relative to the root config (e.g., "packages/*").
+	/// When empty, all the `webtk.toml` files below the root are discovered.
+	#[serde(default)]
+	pub members: Vec<String>,
+}
+
+/// The `[notify]` section: where `run --watch` reports the rebuild results.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotifySection {
+	/// macOS notification center.
+	#[serde(default)]
+	pub desktop: bool,
+
+	/// URL receiving a Slack-compatible JSON POST (`{"text": "..."}`).
+	pub webhook: Option<String>,
+}
+
+/// The `[stats]` section: the opt-in local usage stats (see `handlers::stats`).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatsSection {
+	#[serde(default)]
+	pub enabled: bool,
+
+	/// The stats file (default `~/.webtk/stats.jsonl`), relative to the config directory (`~` and `$VAR` expanded).
+	pub file: Option<String>,
+}
+
+/// The `[network]` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkSection {
+	/// Forbid all network access, like `--offline` (see `http::set_offline`).
+	#[serde(default)]
+	pub offline: bool,
+}
+
+/// An export job (same options as `webtk sketch export`).
+/// Paths are relative to the directory of the `webtk.toml` declaring the job (`~` and `$VAR` expanded).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobConfig {
+	/// Optional job name (defaults to its index, e.g., "job-1").
+	pub name: Option<String>,
+
+	pub sketch_file: String,
+
+	#[serde(default)]
+	pub globs: Vec<String>,
+
+	/// Globs of artboards to exclude from the job.
+	#[serde(default)]
+	pub exclude: Vec<String>,
+
+	/// What the globs of the job match (see `ExportOptions::glob_scope`).
+	pub glob_scope: Option<String>,
+
+	/// Match the globs case insensitively (see `ExportOptions::ignore_case`).
+	#[serde(default)]
+	pub ignore_case: bool,
+
+	/// Match the globs without an uppercase letter case insensitively (see `ExportOptions::smart_case`).
+	#[serde(default)]
+	pub smart_case: bool,
+
+	pub formats: Vec<String>,
+
+	/// Export scales for the regular formats (e.g., ["1", "2"]).
+	#[serde(default)]
+	pub scales: Vec<String>,
+
+	/// Export the artboards with their Sketch export presets (see `ExportOptions::use_design_presets`).
+	#[serde(default)]
+	pub use_design_presets: bool,
+
+	pub output: String,
+
+	/// Per-artboard overrides (see `ArtboardOverride`).
+	#[serde(default)]
+	pub overrides: Vec<ArtboardOverride>,
+
+	#[serde(default)]
+	pub flatten: bool,
+
+	#[serde(default)]
+	pub allow_empty: bool,
+
+	#[serde(default)]
+	pub keep_raw_export: bool,
+
+	#[serde(default)]
+	pub symbol_data_attrs: bool,
+
+	#[serde(default)]
+	pub symbols_manifest: bool,
+
+	/// TypeScript module of the svg-symbols ids (see `ExportOptions::ts_out`).
+	pub ts_out: Option<String>,
+
+	pub ts_type_name: Option<String>,
+
+	/// Rust module of the svg-symbols ids (see `ExportOptions::rust_out`).
+	pub rust_out: Option<String>,
+
+	/// HTML `<use>` snippets of the svg-symbols (see `ExportOptions::use_snippets_out`).
+	pub use_snippets_out: Option<String>,
+
+	/// Usage docs of the svg-symbols icons (see `ExportOptions::usage_out`).
+	pub usage_out: Option<String>,
+
+	/// TypeScript module of the single-path icons path data (see `ExportOptions::paths_ts_out`).
+	pub paths_ts_out: Option<String>,
+
+	/// Codegen templates overriding the built-in ones (see `ExportOptions::template_dir`).
+	pub template_dir: Option<String>,
+
+	#[serde(default)]
+	pub preserve_aspect_ratio: Vec<String>,
+
+	/// Default sizes of the symbols (see `ExportOptions::symbol_size`).
+	#[serde(default)]
+	pub symbol_size: Vec<String>,
+
+	#[serde(default)]
+	pub strip_namespaces: Vec<String>,
+
+	#[serde(default)]
+	pub trim: bool,
+
+	#[serde(default)]
+	pub group_contents_only: bool,
+
+	/// Transparent padding in px (see `ExportOptions::padding`).
+	pub padding: Option<u32>,
+
+	/// The background of the raster exports (see `ExportOptions::background`).
+	pub background: Option<String>,
+
+	/// The tile size in px of the png exports (see `ExportOptions::tile`).
+	pub tile: Option<u32>,
+
+	#[serde(default)]
+	pub tile_overlap: u32,
+
+	/// The grid size in px of the redline copies of the png exports (see `ExportOptions::redline`).
+	pub redline: Option<u32>,
+
+	/// Export email-safe images (see `ExportOptions::email_safe`).
+	#[serde(default)]
+	pub email_safe: bool,
+
+	/// The max display width in px of the email-safe images (see `ExportOptions::email_max_width`).
+	pub email_max_width: Option<u32>,
+
+	/// The email snippet file of the email-safe images, .html or .mjml (see `ExportOptions::email_snippet_out`).
+	pub email_snippet_out: Option<String>,
+
+	/// The CDN URL of the output, for the `urls.json` of the exported files (see `ExportOptions::cdn_base`).
+	pub cdn_base: Option<String>,
+
+	/// The TypeScript module of the `cdn_base` URLs (see `ExportOptions::cdn_ts_out`).
+	pub cdn_ts_out: Option<String>,
+
+	/// Add a bookmark per page to the pdf-book file (see `ExportOptions::pdf_bookmarks`).
+	#[serde(default)]
+	pub pdf_bookmarks: bool,
+
+	/// Keep the stroke widths at any size (see `ExportOptions::non_scaling_stroke`).
+	#[serde(default)]
+	pub non_scaling_stroke: bool,
+
+	/// Inline the raster images of the SVGs as data: URIs (see `ExportOptions::embed_images`).
+	#[serde(default)]
+	pub embed_images: bool,
+
+	/// The directory of the images extracted from the SVGs (see `ExportOptions::extract_images`).
+	pub extract_images: Option<String>,
+
+	/// The local references policy: "warn", "fail", "strip" or "embed" (see `ExportOptions::local_refs`).
+	pub local_refs: Option<String>,
+
+	/// Write a SHA256SUMS file in the output directory (see `ExportOptions::checksums`).
+	#[serde(default)]
+	pub checksums: bool,
+
+	/// Write a uid-map.json in the output directory (see `ExportOptions::uid_map`).
+	#[serde(default)]
+	pub uid_map: bool,
+
+	/// The modification time of the outputs: "source", "epoch" or "now" (see `ExportOptions::preserve_mtime`).
+	pub preserve_mtime: Option<String>,
+
+	/// The octal mode of the outputs, e.g., "644" (see `ExportOptions::chmod`).
+	pub chmod: Option<String>,
+
+	/// Raw sketchtool arguments (see `ExportOptions::tool_args`).
+	#[serde(default)]
+	pub tool_args: Vec<String>,
+
+	/// The max artboards of a sketchtool export command (see `ExportOptions::items_batch_size`).
+	pub items_batch_size: Option<usize>,
+}
+
+/// A `[[jobs.overrides]]` entry: the job artboards matching `glob` get an extra export
+/// with the given fields, the others being inherited from the job (e.g., `logo/**` also as PDF at 3x into `brand/`).
+/// With `replace = true`, the matched artboards are only exported by the override.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArtboardOverride {
+	pub glob: String,
+
+	pub formats: Option<Vec<String>>,
+
+	pub scales: Option<Vec<String>>,
+
+	pub output: Option<String>,
+
+	/// Output file name, without extension (the glob must then match a single artboard).
+	pub name: Option<String>,
+
+	#[serde(default)]
+	pub replace: bool,
+}
+
+/// A `[[pipelines]]` entry: steps run in order (`run --pipeline`), each only when its inputs changed since its
+/// last run, so a change re-runs the steps from the first one reading it (e.g., export -> optimize -> sprite).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineConfig {
+	pub name: String,
+
+	pub steps: Vec<PipelineStepConfig>,
+}
+
+/// A `[[pipelines.steps]]` entry: a job of the config (`job`), or a shell command (`command`, run from the config
+/// directory).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineStepConfig {
+	/// Optional step name (defaults to the job name, or "step-<n>").
+	pub name: Option<String>,
+
+	/// The name of a job of the config (its Sketch file is an input of the step).
+	pub job: Option<String>,
+
+	pub command: Option<String>,
+
+	/// Globs of the input files, relative to the config directory (e.g., "dist/icons/**/*.svg").
+	/// A step without inputs runs when a previous step ran.
+	#[serde(default)]
+	pub inputs: Vec<String>,
+
+	/// The files or directories written by a command, relative to the config directory, for the dependencies
+	/// of `webtk pipeline graph` (the outputs of a job step are the ones of the job).
+	#[serde(default)]
+	pub outputs: Vec<String>,
+}
+
+/// A loaded `webtk.toml`, with the directory its relative paths resolve against.
+#[derive(Debug)]
+pub struct ProjectConfig {
+	pub config_file: SPath,
+	pub base_dir: SPath,
+	pub config: WebtkConfig,
+}
+
+impl ProjectConfig {
+	pub fn load(config_file: &SPath) -> Result<Self> {
+		files::check_file_exists(config_file)?;
+
+		let content = read_to_string(config_file.as_std_path()).map_err(Error::custom_from_err)?;
+		let mut config = parse_config(&content, config_file.as_str())?;
+		for (idx, job) in config.jobs.iter_mut().enumerate() {
+			job.expand_paths()
+				.map_err(|err| Error::custom(format!("Invalid config '{config_file}': jobs[{idx}]: {err}")))?;
+		}
+		if let Some(file) = config.stats.as_mut().and_then(|stats| stats.file.as_mut()) {
+			*file = files::expand_path(file)
+				.map_err(|err| Error::custom(format!("Invalid config '{config_file}': stats.file: {err}")))?;
+		}
+
+		// Absolute, so the job paths stay valid whatever the current dir is
+		let base_dir = std::path::absolute(config_file.as_std_path())
+			.ok()
+			.and_then(|file| file.parent().map(|dir| dir.to_path_buf()))
+			.and_then(|dir| SPath::from_std_path(dir).ok())
+			.ok_or_else(|| Error::custom(format!("Cannot resolve the directory of config '{config_file}'")))?;
+
+		Ok(Self { config_file: config_file.clone(), base_dir, config })
+	}
+
+	/// Whether the config sets `[network] offline = true`.
+	pub fn is_offline(&self) -> bool {
+		self.config.network.as_ref().is_some_and(|network| network.offline)
+	}
+
+	/// Resolves a path of this config (relative to its directory, unless absolute).
+	pub fn resolve_path(&self, path: &str) -> SPath {
+		if SPath::new(path).as_std_path().is_absolute() { SPath::new(path) } else { self.base_dir.join(path) }
+	}
+}
+
+impl PipelineStepConfig {
+	/// The step display name (its `name`, its job, or "step-<n>" from its 0-based index).
+	pub fn display_name(&self, idx: usize) -> String {
+		self.name
+			.clone()
+			.or_else(|| self.job.clone())
+			.unwrap_or_else(|| format!("step-{}", idx + 1))
+	}
+}
+
+impl JobConfig {
+	/// The job display name (its `name`, or "job-<n>" from its 0-based index).
+	pub fn display_name(&self, idx: usize) -> String {
+		self.name.clone().unwrap_or_else(|| format!("job-{}", idx + 1))
+	}
+
+	/// Expands `~` and the environment variables of the job paths (see `files::expand_path`).
+	pub fn expand_paths(&mut self) -> Result<()> {
+		self.sketch_file = files::expand_path(&self.sketch_file)?;
+		self.output = files::expand_path(&self.output)?;
+		for path in [
+			&mut self.ts_out,
+			&mut self.rust_out,
+			&mut self.use_snippets_out,
+			&mut self.usage_out,
+			&mut self.paths_ts_out,
+			&mut self.template_dir,
+			&mut self.extract_images,
+		]
+		.into_iter()
+		.flatten()
+		{
+			*path = files::expand_path(path)?;
+		}
+		for path in self.overrides.iter_mut().filter_map(|o| o.output.as_mut()) {
+			*path = files::expand_path(path)?;
+		}
+		Ok(())
+	}
+
+	pub fn export_options(&self) -> ExportOptions {
+		ExportOptions {
+			flatten: self.flatten,
+			allow_empty: self.allow_empty,
+			exclude_globs: self.exclude.clone(),
+			glob_scope: self.glob_scope.clone(),
+			ignore_case: self.ignore_case,
+			smart_case: self.smart_case,
+			scales: self.scales.clone(),
+			use_design_presets: self.use_design_presets,
+			keep_raw_export: self.keep_raw_export,
+			symbol_data_attrs: self.symbol_data_attrs,
+			symbols_manifest: self.symbols_manifest,
+			ts_out: self.ts_out.clone(),
+			ts_type_name: self.ts_type_name.clone(),
+			rust_out: self.rust_out.clone(),
+			use_snippets_out: self.use_snippets_out.clone(),
+			usage_out: self.usage_out.clone(),
+			paths_ts_out: self.paths_ts_out.clone(),
+			template_dir: self.template_dir.clone(),
+			preserve_aspect_ratio: self.preserve_aspect_ratio.clone(),
+			symbol_size: self.symbol_size.clone(),
+			strip_namespaces: self.strip_namespaces.clone(),
+			trim: self.trim,
+			group_contents_only: self.group_contents_only,
+			padding: self.padding,
+			background: self.background.clone(),
+			tile: self.tile,
+			tile_overlap: self.tile_overlap,
+			redline: self.redline,
+			email_safe: self.email_safe,
+			email_max_width: self.email_max_width,
+			email_snippet_out: self.email_snippet_out.clone(),
+			cdn_base: self.cdn_base.clone(),
+			cdn_ts_out: self.cdn_ts_out.clone(),
+			pdf_bookmarks: self.pdf_bookmarks,
+			non_scaling_stroke: self.non_scaling_stroke,
+			embed_images: self.embed_images,
+			extract_images: self.extract_images.clone(),
+			local_refs: self.local_refs.clone(),
+			checksums: self.checksums,
+			uid_map: self.uid_map,
+			preserve_mtime: self.preserve_mtime.clone(),
+			chmod: self.chmod.clone(),
+			tool_args: self.tool_args.clone(),
+			items_batch_size: self.items_batch_size,
+			..Default::default()
+		}
+	}
+}
@@ -0,0 +1,91 @@
+use crate::handlers::config::{CONFIG_FILE_NAME, ProjectConfig};
+use crate::support::http;
+use crate::{Error, Result};
+use simple_fs::{ListOptions, SPath};
+
+/// Directories never searched for `webtk.toml` files.
+pub const DISCOVERY_EXCLUDE_GLOBS: &[&str] = &["**/node_modules/**", "**/.git/**", "**/target/**", "**/.cache*/**"];
+
+/// Loads the projects to run: all the projects of the workspace of the current dir (`all`),
+/// otherwise the given config file (default: `./webtk.toml`).
+/// A project with `[network] offline = true` turns the offline mode on (see `http::set_offline`).
+pub fn load_projects(config_file: Option<&str>, all: bool) -> Result<Vec<ProjectConfig>> {
+	let projects = if all {
+		let current_dir = std::env::current_dir().map_err(Error::custom_from_err)?;
+		let current_dir = SPath::from_std_path(current_dir).map_err(Error::custom_from_err)?;
+		let root = find_workspace_root(&current_dir)?
+			.ok_or_else(|| Error::custom(format!("No '{CONFIG_FILE_NAME}' found in '{current_dir}' or its parents")))?;
+		discover_projects(root)?
+	} else {
+		let config_file = SPath::new(config_file.unwrap_or(CONFIG_FILE_NAME));
+		vec![ProjectConfig::load(&config_file)?]
+	};
+
+	if projects.iter().any(ProjectConfig::is_offline) {
+		http::set_offline();
+	}
+
+	Ok(projects)
+}
+
+/// Finds the workspace root config from a directory: the closest `webtk.toml` (in the directory or its parents)
+/// with a `[workspace]` section, otherwise the `webtk.toml` of the directory itself, if any.
+pub fn find_workspace_root(start_dir: &SPath) -> Result<Option<ProjectConfig>> {
+	let start_config = start_dir.join(CONFIG_FILE_NAME);
+
+	let mut dir = Some(start_dir.clone());
+	while let Some(current) = dir {
+		let config_file = current.join(CONFIG_FILE_NAME);
+		if config_file.exists() {
+			let project = ProjectConfig::load(&config_file)?;
+			if project.config.workspace.is_some() {
+				return Ok(Some(project));
+			}
+		}
+		dir = current.parent();
+	}
+
+	if start_config.exists() { Ok(Some(ProjectConfig::load(&start_config)?)) } else { Ok(None) }
+}
+
+/// Returns the projects of a workspace, root first:
+/// - With `[workspace] members`, the `webtk.toml` of each member directory (globs relative to the root)
+/// - Otherwise, all the `webtk.toml` files below the root (excluding `node_modules`, `.git`, `target`)
+pub fn discover_projects(root: ProjectConfig) -> Result<Vec<ProjectConfig>> {
+	let root_dir = root.base_dir.clone();
+	let members = root.config.workspace.as_ref().map(|ws| ws.members.clone()).unwrap_or_default();
+
+	let mut config_files: Vec<SPath> = if members.is_empty() {
+		let options = ListOptions::from_relative_glob(true).with_exclude_globs(DISCOVERY_EXCLUDE_GLOBS);
+		simple_fs::list_files(root_dir.as_std_path(), Some(&["**/webtk.toml"]), Some(options))
+			.map_err(Error::custom_from_err)?
+	} else {
+		let member_globs: Vec<&str> = members.iter().map(|m| m.trim_end_matches('/')).collect();
+		let options = ListOptions::from_relative_glob(true).with_exclude_globs(DISCOVERY_EXCLUDE_GLOBS);
+		let dirs = simple_fs::list_dirs(root_dir.as_std_path(), Some(&member_globs), Some(options))
+			.map_err(Error::custom_from_err)?;
+		let config_files: Vec<SPath> = dirs
+			.into_iter()
+			.map(|dir| dir.join(CONFIG_FILE_NAME))
+			.filter(|file| file.exists())
+			.collect();
+		if config_files.is_empty() {
+			return Err(Error::custom(format!(
+				"No '{CONFIG_FILE_NAME}' found in the workspace members {members:?} of '{}'",
+				root.config_file
+			)));
+		}
+		config_files
+	};
+
+	// Deterministic order, without the root (added first)
+	config_files.retain(|file| file.as_std_path() != root.config_file.as_std_path());
+	config_files.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+	let mut projects = vec![root];
+	for config_file in config_files {
+		projects.push(ProjectConfig::load(&config_file)?);
+	}
+
+	Ok(projects)
+}
@@ -0,0 +1,175 @@
+//! The JSON-RPC 2.0 methods of `webtk daemon` (one request per line, one response per line).
+//!
+//! Methods (params as objects, snake_case):
+//! - `ping` -> `{ "version": "..." }`
+//! - `list_artboards` `{ sketch_file, globs? }` -> `[Artboard]` (metadata cached until the file changes)
+//! - `run_jobs` `{ config?, all?, jobs? }` -> `RunReport` (same as `webtk run`, failures in the report)
+//! - `last_report` -> the `RunReport` of the last `run_jobs`, or `null`
+//! - `shutdown` -> `null`, then the daemon stops
+
+use crate::handlers::config;
+use crate::handlers::run::{self, RunOptions, RunReport};
+use crate::handlers::sketch::{self, Artboard, GlobScope};
+use crate::support::globs::{self, GlobCase};
+use crate::{Error, Result};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use simple_fs::SPath;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Application error (the webtk exit code is in the error `data`).
+const SERVER_ERROR: i64 = -32000;
+
+/// The state shared by the requests of a daemon.
+#[derive(Debug, Default)]
+pub struct DaemonState {
+	/// All the artboards of a Sketch file, with the file modification time they were read at.
+	metadata_cache: HashMap<String, (SystemTime, Vec<Artboard>)>,
+	last_report: Option<RunReport>,
+	/// Set by `shutdown`.
+	pub stopping: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+	jsonrpc: String,
+	#[serde(default)]
+	id: Value,
+	method: String,
+	#[serde(default)]
+	params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListArtboardsParams {
+	sketch_file: String,
+	#[serde(default)]
+	globs: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RunJobsParams {
+	config: Option<String>,
+	#[serde(default)]
+	all: bool,
+	#[serde(default)]
+	jobs: Vec<String>,
+}
+
+/// An error response (code, message), before the request id is known or attached.
+struct RpcError {
+	code: i64,
+	message: String,
+	data: Option<Value>,
+}
+
+impl From<Error> for RpcError {
+	fn from(err: Error) -> Self {
+		RpcError { code: SERVER_ERROR, message: err.to_string(), data: Some(json!({ "exit_code": err.exit_code() })) }
+	}
+}
+
+/// Handles one request line, returning the response line (`None` for a notification, i.e., a request without id).
+pub fn handle_rpc_line(state: &mut DaemonState, line: &str) -> Option<String> {
+	let (id, result) = match serde_json::from_str::<Value>(line) {
+		Err(err) => (Value::Null, Err(rpc_error(PARSE_ERROR, format!("Parse error: {err}")))),
+		Ok(value) => match serde_json::from_value::<RpcRequest>(value) {
+			Err(err) => (Value::Null, Err(rpc_error(INVALID_REQUEST, format!("Invalid request: {err}")))),
+			Ok(request) if request.jsonrpc != "2.0" => {
+				(request.id, Err(rpc_error(INVALID_REQUEST, "Invalid request: jsonrpc must be \"2.0\"")))
+			}
+			Ok(request) => {
+				let result = handle_request(state, &request.method, request.params);
+				if request.id.is_null() {
+					return None;
+				}
+				(request.id, result)
+			}
+		},
+	};
+
+	let response = match result {
+		Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+		Err(err) => {
+			let mut error = json!({ "code": err.code, "message": err.message });
+			if let Some(data) = err.data {
+				error["data"] = data;
+			}
+			json!({ "jsonrpc": "2.0", "id": id, "error": error })
+		}
+	};
+
+	Some(response.to_string())
+}
+
+fn handle_request(state: &mut DaemonState, method: &str, params: Value) -> core::result::Result<Value, RpcError> {
+	match method {
+		"ping" => Ok(json!({ "version": env!("CARGO_PKG_VERSION") })),
+		"list_artboards" => {
+			let params: ListArtboardsParams = parse_params(params)?;
+			let artboards = cached_artboards(state, &params.sketch_file)?;
+			let glob_refs: Vec<&str> = params.globs.iter().map(|s| s.as_str()).collect();
+			let glob_set = globs::build_glob_set(if glob_refs.is_empty() { None } else { Some(&glob_refs) })?;
+			let artboards: Vec<&Artboard> = artboards
+				.iter()
+				.filter(|ab| globs::matches_glob_set(glob_set.as_ref(), &ab.name))
+				.collect();
+			Ok(serde_json::to_value(artboards).map_err(Error::from)?)
+		}
+		"run_jobs" => {
+			let params: RunJobsParams = if params.is_null() { RunJobsParams::default() } else { parse_params(params)? };
+			let projects = config::load_projects(params.config.as_deref(), params.all)?;
+			let report = run::run_projects(&projects, &params.jobs, &RunOptions::default())?;
+			let result = serde_json::to_value(&report).map_err(Error::from)?;
+			state.last_report = Some(report);
+			Ok(result)
+		}
+		"last_report" => Ok(serde_json::to_value(&state.last_report).map_err(Error::from)?),
+		"shutdown" => {
+			state.stopping = true;
+			Ok(Value::Null)
+		}
+		_ => Err(rpc_error(METHOD_NOT_FOUND, format!("Method not found: '{method}'"))),
+	}
+}
+
+/// All the artboards of a Sketch file, read again only when the file modification time changed.
+fn cached_artboards<'a>(state: &'a mut DaemonState, sketch_file: &str) -> Result<&'a [Artboard]> {
+	let modified = std::fs::metadata(sketch_file)
+		.and_then(|meta| meta.modified())
+		.map_err(|_| Error::FileNotFound(SPath::new(sketch_file)))?;
+
+	let is_fresh = state
+		.metadata_cache
+		.get(sketch_file)
+		.is_some_and(|(cached, _)| *cached == modified);
+	if !is_fresh {
+		let artboards = sketch::list_artboards(SPath::new(sketch_file), None, GlobScope::Name, GlobCase::Sensitive)?;
+		state.metadata_cache.insert(sketch_file.to_string(), (modified, artboards));
+	}
+
+	Ok(state
+		.metadata_cache
+		.get(sketch_file)
+		.map(|(_, artboards)| artboards.as_slice())
+		.unwrap_or_default())
+}
+
+// region:    --- Support
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> core::result::Result<T, RpcError> {
+	serde_json::from_value(params).map_err(|err| rpc_error(INVALID_PARAMS, format!("Invalid params: {err}")))
+}
+
+fn rpc_error(code: i64, message: impl Into<String>) -> RpcError {
+	RpcError { code, message: message.into(), data: None }
+}
+
+// endregion: --- Support
@@ -0,0 +1,79 @@
+//! The local socket of `webtk daemon` (Unix domain socket, newline-delimited JSON-RPC, see `daemon_rpc`).
+
+use crate::handlers::daemon::{DaemonState, handle_rpc_line};
+use crate::support::console;
+use crate::{Error, Result};
+use simple_fs::SPath;
+
+/// The default socket path (relative to the current dir, i.e., the project the editor opened).
+pub const DEFAULT_DAEMON_SOCKET: &str = ".webtk-daemon.sock";
+
+/// Serves the JSON-RPC requests on a Unix socket until a `shutdown` request.
+/// Connections are served one at a time (the jobs change the current dir), and the metadata cache
+/// and last report are shared by all of them.
+///
+/// Note: A stale socket file (e.g., after a crash) is replaced, and the socket file is removed on shutdown.
+#[cfg(unix)]
+pub fn serve_daemon(socket_path: &SPath, mut on_listen: impl FnMut()) -> Result<()> {
+	use std::io::{BufRead as _, BufReader, Write as _};
+	use std::os::unix::net::{UnixListener, UnixStream};
+
+	if socket_path.exists() {
+		if UnixStream::connect(socket_path.as_std_path()).is_ok() {
+			return Err(Error::custom(format!("A daemon is already listening on '{socket_path}'")));
+		}
+		std::fs::remove_file(socket_path.as_std_path())
+			.map_err(|e| format!("Cannot remove stale socket '{socket_path}'. Cause: {e}"))?;
+	}
+
+	let listener = UnixListener::bind(socket_path.as_std_path())
+		.map_err(|e| format!("Cannot listen on '{socket_path}'. Cause: {e}"))?;
+	on_listen();
+
+	let mut state = DaemonState::default();
+	for stream in listener.incoming() {
+		let stream = match stream {
+			Ok(stream) => stream,
+			Err(err) => {
+				console::eprint_warning(format!("Connection failed. Cause: {err}"));
+				continue;
+			}
+		};
+
+		let mut writer = match stream.try_clone() {
+			Ok(writer) => writer,
+			Err(err) => {
+				console::eprint_warning(format!("Connection failed. Cause: {err}"));
+				continue;
+			}
+		};
+		for line in BufReader::new(stream).lines() {
+			let Ok(line) = line else { break };
+			if line.trim().is_empty() {
+				continue;
+			}
+			if let Some(response) = handle_rpc_line(&mut state, &line)
+				&& writeln!(writer, "{response}").is_err()
+			{
+				break;
+			}
+			if state.stopping {
+				break;
+			}
+		}
+
+		if state.stopping {
+			break;
+		}
+	}
+
+	std::fs::remove_file(socket_path.as_std_path())
+		.map_err(|e| format!("Cannot remove socket '{socket_path}'. Cause: {e}"))?;
+
+	Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve_daemon(_socket_path: &SPath, _on_listen: impl FnMut()) -> Result<()> {
+	Err(Error::custom("webtk daemon requires Unix domain sockets (macOS or Linux)"))
+}
@@ -0,0 +1,9 @@
+// region:    --- Modules
+
+mod daemon_rpc;
+mod daemon_server;
+
+pub use daemon_rpc::*;
+pub use daemon_server::*;
+
+// endregion: --- Modules
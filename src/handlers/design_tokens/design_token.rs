@@ -0,0 +1,428 @@
+//! The design tokens, and their W3C Design Tokens Community Group (DTCG) JSON format
+//! (https://www.designtokens.org/tr/drafts/format/).
+//!
+//! Tokens are emitted with the object values of the format (e.g., `{"colorSpace": "srgb", ...}` for a color),
+//! and parsed from both the object and the legacy string values (e.g., "#ff0000", "16px").
+
+use crate::support::colors::{self, Rgba};
+use crate::support::strings;
+use crate::{Error, Result, Warning, WarningCode};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+/// The maximum depth of the alias chains (e.g., `{color.primary}` -> `{color.red.500}` -> value).
+const MAX_ALIAS_DEPTH: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct DesignToken {
+	/// The group names and the token name (e.g., ["color", "brand", "primary"]).
+	pub path: Vec<String>,
+	pub value: TokenValue,
+}
+
+impl DesignToken {
+	/// Returns the dotted path of the token, as in the aliases (e.g., "color.brand.primary").
+	pub fn path_str(&self) -> String {
+		self.path.join(".")
+	}
+}
+
+#[derive(Debug, Clone)]
+pub enum TokenValue {
+	Color(Rgba),
+	Dimension(Dimension),
+	Number(f64),
+	FontFamily(Vec<String>),
+	FontWeight(f64),
+	Typography(Typography),
+	/// The value of the other simple types (e.g., "duration", "strokeStyle"), kept as written.
+	Other {
+		token_type: String,
+		value: String,
+	},
+}
+
+impl TokenValue {
+	/// Returns the DTCG `$type` of the value.
+	pub fn token_type(&self) -> &str {
+		match self {
+			TokenValue::Color(_) => "color",
+			TokenValue::Dimension(_) => "dimension",
+			TokenValue::Number(_) => "number",
+			TokenValue::FontFamily(_) => "fontFamily",
+			TokenValue::FontWeight(_) => "fontWeight",
+			TokenValue::Typography(_) => "typography",
+			TokenValue::Other { token_type, .. } => token_type,
+		}
+	}
+
+	/// Returns a short notation of the value, to compare and report (e.g., "#3366ff", "16px", "Inter 600 32px/1.25").
+	/// The colors are compared at 8 bits per channel, as in their hex notation.
+	pub fn summary(&self) -> String {
+		match self {
+			TokenValue::Color(color) => color.to_hex(),
+			TokenValue::Dimension(dimension) => dimension.to_css(),
+			TokenValue::Number(number) | TokenValue::FontWeight(number) => strings::format_number(*number, 4),
+			TokenValue::FontFamily(families) => families.join(", "),
+			TokenValue::Typography(typography) => {
+				let mut summary = format!(
+					"{} {} {}",
+					typography.font_family.join(", "),
+					strings::format_number(typography.font_weight, 0),
+					typography.font_size.to_css()
+				);
+				if let Some(line_height) = typography.line_height {
+					summary.push_str(&format!("/{}", strings::format_number(line_height, 4)));
+				}
+				if let Some(letter_spacing) = &typography.letter_spacing {
+					summary.push_str(&format!(" {}", letter_spacing.to_css()));
+				}
+				summary
+			}
+			TokenValue::Other { value, .. } => value.clone(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dimension {
+	pub value: f64,
+	/// "px", "rem", ...
+	pub unit: String,
+}
+
+impl Dimension {
+	pub fn px(value: f64) -> Self {
+		Self { value, unit: "px".to_string() }
+	}
+
+	/// Returns the CSS notation (e.g., "16px").
+	pub fn to_css(&self) -> String {
+		format!("{}{}", strings::format_number(self.value, 4), self.unit)
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct Typography {
+	pub font_family: Vec<String>,
+	pub font_size: Dimension,
+	pub font_weight: f64,
+	/// The ratio to the font size (e.g., 1.5).
+	pub line_height: Option<f64>,
+	pub letter_spacing: Option<Dimension>,
+}
+
+// region:    --- Emit
+
+/// Returns the DTCG JSON of the tokens, nested by path (keys sorted).
+/// A token whose path is taken by another token or group is skipped with a warning.
+pub fn tokens_to_w3c_json(tokens: &[DesignToken]) -> (Value, Vec<Warning>) {
+	let mut root = Map::new();
+	let mut warnings = Vec::new();
+
+	for token in tokens {
+		let Some((name, groups)) = token.path.split_last() else {
+			continue;
+		};
+
+		let group = token_group(&mut root, groups).filter(|group| !group.contains_key(name));
+		let Some(group) = group else {
+			warnings.push(Warning::new(
+				WarningCode::DuplicateToken,
+				format!("Token '{}' skipped (path already taken)", token.path_str()),
+			));
+			continue;
+		};
+
+		group.insert(name.clone(), json!({ "$type": token.value.token_type(), "$value": w3c_value(&token.value) }));
+	}
+
+	let mut root = Value::Object(root);
+	root.sort_all_objects();
+	(root, warnings)
+}
+
+/// Returns the group of the group names, created as needed (None when a token is in the way).
+fn token_group<'a>(root: &'a mut Map<String, Value>, groups: &[String]) -> Option<&'a mut Map<String, Value>> {
+	let mut group = root;
+	for group_name in groups {
+		let entry = group.entry(group_name.clone()).or_insert_with(|| Value::Object(Map::new()));
+		let Value::Object(child) = entry else {
+			return None;
+		};
+		if child.contains_key("$value") {
+			return None;
+		}
+		group = child;
+	}
+	Some(group)
+}
+
+fn w3c_value(value: &TokenValue) -> Value {
+	match value {
+		TokenValue::Color(color) => w3c_color(color),
+		TokenValue::Dimension(dimension) => w3c_dimension(dimension),
+		TokenValue::Number(number) | TokenValue::FontWeight(number) => json_number(*number),
+		TokenValue::FontFamily(families) => w3c_font_family(families),
+		TokenValue::Typography(typography) => {
+			let mut value = Map::new();
+			value.insert("fontFamily".to_string(), w3c_font_family(&typography.font_family));
+			value.insert("fontSize".to_string(), w3c_dimension(&typography.font_size));
+			value.insert("fontWeight".to_string(), json_number(typography.font_weight));
+			if let Some(line_height) = typography.line_height {
+				value.insert("lineHeight".to_string(), json_number(line_height));
+			}
+			if let Some(letter_spacing) = &typography.letter_spacing {
+				value.insert("letterSpacing".to_string(), w3c_dimension(letter_spacing));
+			}
+			Value::Object(value)
+		}
+		TokenValue::Other { value, .. } => Value::String(value.clone()),
+	}
+}
+
+fn w3c_color(color: &Rgba) -> Value {
+	let round = |c: f64| json_number((c * 10000.0).round() / 10000.0);
+	let hex = color.to_hex();
+	json!({
+		"colorSpace": "srgb",
+		"components": [round(color.red), round(color.green), round(color.blue)],
+		"alpha": round(color.alpha),
+		"hex": &hex[..7],
+	})
+}
+
+fn w3c_dimension(dimension: &Dimension) -> Value {
+	json!({ "value": json_number(dimension.value), "unit": dimension.unit })
+}
+
+fn w3c_font_family(families: &[String]) -> Value {
+	match families {
+		[family] => Value::String(family.clone()),
+		_ => json!(families),
+	}
+}
+
+/// Returns the number as a JSON integer when it has no fraction (e.g., 16 rather than 16.0).
+fn json_number(number: f64) -> Value {
+	if number.fract() == 0.0 && number.abs() < 1e15 { json!(number as i64) } else { json!(number) }
+}
+
+// endregion: --- Emit
+
+// region:    --- Parse
+
+/// Parses the tokens of a DTCG JSON document, in path order, with the aliases (e.g., `"{color.primary}"`)
+/// resolved and the `$type` inherited from the groups.
+/// The tokens of an unsupported type or value are skipped with a warning.
+pub fn parse_w3c_tokens(document: &Value) -> Result<(Vec<DesignToken>, Vec<Warning>)> {
+	let mut raw_tokens = Vec::new();
+	collect_raw_tokens(document, &mut Vec::new(), None, &mut raw_tokens)?;
+
+	let by_path: HashMap<String, &RawToken> = raw_tokens.iter().map(|t| (t.path.join("."), t)).collect();
+
+	let mut tokens = Vec::new();
+	let mut warnings = Vec::new();
+	for raw in &raw_tokens {
+		let path_str = raw.path.join(".");
+		let parsed = resolve_aliases(&raw.value, &by_path, 0).and_then(|value| {
+			let token_type = raw
+				.token_type
+				.clone()
+				.or_else(|| alias_target_type(&raw.value, &by_path))
+				.ok_or_else(|| Error::custom("no $type"))?;
+			parse_value(&token_type, &value)
+		});
+
+		match parsed {
+			Ok(value) => tokens.push(DesignToken { path: raw.path.clone(), value }),
+			Err(e) => {
+				warnings.push(Warning::new(WarningCode::UnsupportedToken, format!("Token '{path_str}' skipped: {e}")))
+			}
+		}
+	}
+
+	Ok((tokens, warnings))
+}
+
+struct RawToken {
+	path: Vec<String>,
+	token_type: Option<String>,
+	value: Value,
+}
+
+fn collect_raw_tokens(
+	group: &Value,
+	path: &mut Vec<String>,
+	inherited_type: Option<&str>,
+	raw_tokens: &mut Vec<RawToken>,
+) -> Result<()> {
+	let Value::Object(group) = group else {
+		return Err(Error::custom(format!("Invalid design tokens: '{}' is not a token or a group", path.join("."))));
+	};
+
+	let group_type = group.get("$type").and_then(Value::as_str).or(inherited_type);
+
+	if let Some(value) = group.get("$value") {
+		raw_tokens.push(RawToken {
+			path: path.clone(),
+			token_type: group_type.map(str::to_string),
+			value: value.clone(),
+		});
+		return Ok(());
+	}
+
+	for (name, child) in group.iter().filter(|(name, _)| !name.starts_with('$')) {
+		path.push(name.clone());
+		collect_raw_tokens(child, path, group_type, raw_tokens)?;
+		path.pop();
+	}
+
+	Ok(())
+}
+
+/// Returns the path of an alias value (e.g., `"{color.primary}"` -> "color.primary").
+fn alias_path(value: &Value) -> Option<&str> {
+	value.as_str()?.strip_prefix('{')?.strip_suffix('}')
+}
+
+/// Replaces the aliases of a value (whole value, or the fields of a composite value) with their values.
+fn resolve_aliases(value: &Value, by_path: &HashMap<String, &RawToken>, depth: usize) -> Result<Value> {
+	if depth > MAX_ALIAS_DEPTH {
+		return Err(Error::custom("alias chain too deep (circular reference?)"));
+	}
+
+	if let Some(path) = alias_path(value) {
+		let target = by_path
+			.get(path)
+			.ok_or_else(|| Error::custom(format!("unknown alias '{{{path}}}'")))?;
+		return resolve_aliases(&target.value, by_path, depth + 1);
+	}
+
+	match value {
+		Value::Object(fields) => {
+			let mut resolved = Map::new();
+			for (name, field) in fields {
+				resolved.insert(name.clone(), resolve_aliases(field, by_path, depth + 1)?);
+			}
+			Ok(Value::Object(resolved))
+		}
+		_ => Ok(value.clone()),
+	}
+}
+
+/// Returns the `$type` of the token an alias value refers to (following the alias chain).
+fn alias_target_type(value: &Value, by_path: &HashMap<String, &RawToken>) -> Option<String> {
+	let mut value = value;
+	for _ in 0..MAX_ALIAS_DEPTH {
+		let target = by_path.get(alias_path(value)?)?;
+		if let Some(token_type) = &target.token_type {
+			return Some(token_type.clone());
+		}
+		value = &target.value;
+	}
+	None
+}
+
+fn parse_value(token_type: &str, value: &Value) -> Result<TokenValue> {
+	let invalid = || Error::custom(format!("invalid {token_type} value {value}"));
+
+	let parsed = match token_type {
+		"color" => TokenValue::Color(parse_color(value).ok_or_else(invalid)?),
+		"dimension" => TokenValue::Dimension(parse_dimension(value).ok_or_else(invalid)?),
+		"number" => TokenValue::Number(value.as_f64().ok_or_else(invalid)?),
+		"fontFamily" => TokenValue::FontFamily(parse_font_family(value).ok_or_else(invalid)?),
+		"fontWeight" => TokenValue::FontWeight(parse_font_weight(value).ok_or_else(invalid)?),
+		"typography" => {
+			let field = |name: &str| value.get(name).ok_or_else(invalid);
+			TokenValue::Typography(Typography {
+				font_family: parse_font_family(field("fontFamily")?).ok_or_else(invalid)?,
+				font_size: parse_dimension(field("fontSize")?).ok_or_else(invalid)?,
+				font_weight: value
+					.get("fontWeight")
+					.map_or(Some(400.0), parse_font_weight)
+					.ok_or_else(invalid)?,
+				line_height: value.get("lineHeight").and_then(Value::as_f64),
+				letter_spacing: value.get("letterSpacing").and_then(parse_dimension),
+			})
+		}
+		_ => match value {
+			Value::String(text) => TokenValue::Other { token_type: token_type.to_string(), value: text.clone() },
+			Value::Number(number) => {
+				TokenValue::Other { token_type: token_type.to_string(), value: number.to_string() }
+			}
+			_ => return Err(Error::custom(format!("unsupported {token_type} value (composite)"))),
+		},
+	};
+
+	Ok(parsed)
+}
+
+/// Parses a color: a hex string, or an object with a "hex" or sRGB "components" (and an optional "alpha").
+fn parse_color(value: &Value) -> Option<Rgba> {
+	if let Some(hex) = value.as_str() {
+		return colors::parse_hex_color(hex);
+	}
+
+	let alpha = value.get("alpha").map_or(Some(1.0), Value::as_f64)?;
+	let components = value.get("components").and_then(Value::as_array);
+	let is_srgb = value.get("colorSpace").and_then(Value::as_str) == Some("srgb");
+
+	match components {
+		Some(components) if is_srgb && components.len() == 3 => {
+			let c: Vec<f64> = components.iter().map(Value::as_f64).collect::<Option<_>>()?;
+			Some(Rgba::new(c[0], c[1], c[2], alpha))
+		}
+		_ => {
+			let color = colors::parse_hex_color(value.get("hex")?.as_str()?)?;
+			Some(Rgba { alpha, ..color })
+		}
+	}
+}
+
+/// Parses a dimension: `{"value": 16, "unit": "px"}`, or a string (e.g., "16px", "1.5rem").
+fn parse_dimension(value: &Value) -> Option<Dimension> {
+	if let Some(text) = value.as_str() {
+		let text = text.trim();
+		let idx = text.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))?;
+		let (number, unit) = text.split_at(idx);
+		return Some(Dimension { value: number.parse().ok()?, unit: unit.trim().to_string() });
+	}
+	if let Some(number) = value.as_f64() {
+		return (number == 0.0).then(|| Dimension::px(0.0));
+	}
+
+	Some(Dimension { value: value.get("value")?.as_f64()?, unit: value.get("unit")?.as_str()?.to_string() })
+}
+
+fn parse_font_family(value: &Value) -> Option<Vec<String>> {
+	match value {
+		Value::String(family) => Some(vec![family.clone()]),
+		Value::Array(families) => families.iter().map(|f| f.as_str().map(str::to_string)).collect(),
+		_ => None,
+	}
+}
+
+/// Parses a font weight: a number (1 to 1000), or one of the names of the format (e.g., "bold").
+fn parse_font_weight(value: &Value) -> Option<f64> {
+	if let Some(weight) = value.as_f64() {
+		return (1.0..=1000.0).contains(&weight).then_some(weight);
+	}
+
+	let weight = match value.as_str()?.to_ascii_lowercase().replace(['-', ' '], "").as_str() {
+		"thin" | "hairline" => 100.0,
+		"extralight" | "ultralight" => 200.0,
+		"light" => 300.0,
+		"normal" | "regular" | "book" => 400.0,
+		"medium" => 500.0,
+		"semibold" | "demibold" => 600.0,
+		"bold" => 700.0,
+		"extrabold" | "ultrabold" => 800.0,
+		"black" | "heavy" => 900.0,
+		"extrablack" | "ultrablack" => 950.0,
+		_ => return None,
+	};
+	Some(weight)
+}
+
+// endregion: --- Parse
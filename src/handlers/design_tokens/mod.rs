@@ -0,0 +1,13 @@
+// region:    --- Modules
+
+mod design_token;
+mod tokens_convert;
+mod tokens_diff;
+mod tokens_sketch;
+
+pub use design_token::*;
+pub use tokens_convert::*;
+pub use tokens_diff::*;
+pub use tokens_sketch::*;
+
+// endregion: --- Modules
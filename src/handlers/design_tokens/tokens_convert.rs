@@ -0,0 +1,309 @@
+//! Conversion of the design tokens to the style and platform files (CSS, SCSS, TypeScript, Android, iOS),
+//! rendered from the codegen templates (see `render_codegen_template`).
+//!
+//! The typography tokens are expanded into one token per property (e.g., "typography.h1.font-size").
+
+use crate::handlers::codegen::{
+	ANDROID_TOKENS_TEMPLATE, CSS_TOKENS_TEMPLATE, GENERATED_NOTICE, IOS_TOKENS_TEMPLATE, SCSS_TOKENS_TEMPLATE,
+	TS_TOKENS_TEMPLATE, codegen_string_literal, render_codegen_template,
+};
+use crate::handlers::design_tokens::{DesignToken, Dimension, TokenValue};
+use crate::support::strings;
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::SPath;
+use std::collections::HashSet;
+
+/// The name of the Swift enum of the iOS tokens.
+pub const IOS_TOKENS_TYPE_NAME: &str = "DesignTokens";
+
+/// The px of 1rem, for the platforms without rem (Android, iOS).
+const REM_PX: f64 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenFormat {
+	Css,
+	Scss,
+	Ts,
+	Android,
+	Ios,
+}
+
+impl TokenFormat {
+	fn template_name(&self) -> &'static str {
+		match self {
+			TokenFormat::Css => CSS_TOKENS_TEMPLATE,
+			TokenFormat::Scss => SCSS_TOKENS_TEMPLATE,
+			TokenFormat::Ts => TS_TOKENS_TEMPLATE,
+			TokenFormat::Android => ANDROID_TOKENS_TEMPLATE,
+			TokenFormat::Ios => IOS_TOKENS_TEMPLATE,
+		}
+	}
+}
+
+impl TryFrom<&str> for TokenFormat {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"css" => Ok(Self::Css),
+			"scss" => Ok(Self::Scss),
+			"ts" => Ok(Self::Ts),
+			"android" => Ok(Self::Android),
+			"ios" => Ok(Self::Ios),
+			other => {
+				Err(Error::custom(format!("Invalid token format '{other}'. Expected: css, scss, ts, android, ios")))
+			}
+		}
+	}
+}
+
+/// The context of the tokens templates.
+#[derive(Debug, Serialize)]
+pub struct TokensContext {
+	pub notice: &'static str,
+	/// In the order of the tokens, with the typography tokens expanded.
+	pub tokens: Vec<TokenItem>,
+	/// The Swift enum name ("DesignTokens").
+	pub type_name: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenItem {
+	/// The dotted path (e.g., "color.brand.primary").
+	pub path: String,
+	/// The DTCG type (e.g., "color", "dimension").
+	pub token_type: String,
+	/// The kebab-case name, unique in the file (e.g., "color-brand-primary").
+	pub name: String,
+	/// e.g., "colorBrandPrimary"
+	pub camel_name: String,
+	/// e.g., "color_brand_primary"
+	pub snake_name: String,
+	/// The CSS value (e.g., "#ff0000", "16px", "\"SF Pro\", sans-serif").
+	pub css: String,
+	/// The TypeScript literal (e.g., `"#ff0000"`, `700`).
+	pub ts: String,
+	pub android: PlatformValue,
+	pub swift: PlatformValue,
+}
+
+/// The value of a token on a platform, with its kind (Android resource type, Swift type).
+#[derive(Debug, Serialize)]
+pub struct PlatformValue {
+	/// Android: "color", "dimen", "integer", "float", "string". Swift: "Color", "CGFloat", "Double", "Font.Weight", "String".
+	pub kind: &'static str,
+	pub value: String,
+}
+
+/// Converts the tokens to a format, from its template (`<template_dir>/<template name>` when present).
+pub fn convert_tokens(tokens: &[DesignToken], format: TokenFormat, template_dir: Option<&SPath>) -> Result<String> {
+	render_codegen_template(format.template_name(), template_dir, tokens_context(tokens))
+}
+
+/// Returns the context of the tokens templates.
+pub fn tokens_context(tokens: &[DesignToken]) -> TokensContext {
+	let mut taken = HashSet::new();
+	let items = expand_tokens(tokens)
+		.into_iter()
+		.map(|(path, value)| {
+			let name = strings::unique_name(&kebab_name(&path), &mut taken);
+			TokenItem {
+				path: path.join("."),
+				token_type: value.token_type().to_string(),
+				camel_name: camel_name(&name),
+				snake_name: name.replace('-', "_"),
+				name,
+				css: css_value(&value),
+				ts: ts_value(&value),
+				android: android_value(&path, &value),
+				swift: swift_value(&value),
+			}
+		})
+		.collect();
+
+	TokensContext { notice: GENERATED_NOTICE, tokens: items, type_name: IOS_TOKENS_TYPE_NAME }
+}
+
+/// Returns the tokens with the typography tokens expanded into their properties.
+fn expand_tokens(tokens: &[DesignToken]) -> Vec<(Vec<String>, TokenValue)> {
+	let mut expanded = Vec::new();
+	for token in tokens {
+		let TokenValue::Typography(typography) = &token.value else {
+			expanded.push((token.path.clone(), token.value.clone()));
+			continue;
+		};
+
+		let mut push = |property: &str, value: TokenValue| {
+			let mut path = token.path.clone();
+			path.push(property.to_string());
+			expanded.push((path, value));
+		};
+		push("font-family", TokenValue::FontFamily(typography.font_family.clone()));
+		push("font-size", TokenValue::Dimension(typography.font_size.clone()));
+		push("font-weight", TokenValue::FontWeight(typography.font_weight));
+		if let Some(line_height) = typography.line_height {
+			push("line-height", TokenValue::Number(line_height));
+		}
+		if let Some(letter_spacing) = &typography.letter_spacing {
+			push("letter-spacing", TokenValue::Dimension(letter_spacing.clone()));
+		}
+	}
+	expanded
+}
+
+// region:    --- Names
+
+/// Returns the kebab-case name of a path (e.g., ["color", "Brand", "primary_500"] -> "color-brand-primary-500").
+/// Names starting with a digit get a "token-" prefix (not valid identifiers on the platforms).
+fn kebab_name(path: &[String]) -> String {
+	let name = strings::canonicalize_name(&path.join("-")).to_lowercase();
+	if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) { format!("token-{name}") } else { name }
+}
+
+/// Returns the camelCase name of a kebab-case name (e.g., "color-brand-primary" -> "colorBrandPrimary").
+fn camel_name(kebab_name: &str) -> String {
+	kebab_name
+		.split('-')
+		.enumerate()
+		.map(|(i, part)| {
+			let mut chars = part.chars();
+			match chars.next() {
+				Some(first) if i > 0 => first.to_ascii_uppercase().to_string() + chars.as_str(),
+				_ => part.to_string(),
+			}
+		})
+		.collect()
+}
+
+// endregion: --- Names
+
+// region:    --- Values
+
+fn css_value(value: &TokenValue) -> String {
+	match value {
+		TokenValue::Color(color) => color.to_hex(),
+		TokenValue::Dimension(dimension) => dimension.to_css(),
+		TokenValue::Number(number) | TokenValue::FontWeight(number) => strings::format_number(*number, 4),
+		TokenValue::FontFamily(families) => css_font_family(families),
+		TokenValue::Typography(typography) => {
+			let line_height = typography
+				.line_height
+				.map(|line_height| format!("/{}", strings::format_number(line_height, 4)))
+				.unwrap_or_default();
+			format!(
+				"{} {}{line_height} {}",
+				strings::format_number(typography.font_weight, 0),
+				typography.font_size.to_css(),
+				css_font_family(&typography.font_family)
+			)
+		}
+		TokenValue::Other { value, .. } => value.clone(),
+	}
+}
+
+/// Returns the CSS font family list, with the names quoted when not plain identifiers (e.g., `"SF Pro", sans-serif`).
+fn css_font_family(families: &[String]) -> String {
+	families
+		.iter()
+		.map(|family| {
+			let is_ident = family.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+				&& !family.starts_with(|c: char| c.is_ascii_digit());
+			if is_ident { family.clone() } else { codegen_string_literal(family) }
+		})
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+fn ts_value(value: &TokenValue) -> String {
+	match value {
+		TokenValue::Number(number) | TokenValue::FontWeight(number) => strings::format_number(*number, 4),
+		_ => codegen_string_literal(&css_value(value)),
+	}
+}
+
+/// Returns the Android resource of a value. The dimensions are in dp, or sp for the font sizes
+/// (px and pt as is, rem as 16px).
+fn android_value(path: &[String], value: &TokenValue) -> PlatformValue {
+	let (kind, value) = match value {
+		TokenValue::Color(color) => ("color", color.to_argb_hex()),
+		TokenValue::Dimension(dimension) => match platform_points(dimension) {
+			Some(points) => {
+				let is_font_size = path.last().is_some_and(|name| name == "font-size");
+				let unit = if is_font_size { "sp" } else { "dp" };
+				("dimen", format!("{}{unit}", strings::format_number(points, 2)))
+			}
+			None => ("string", android_string_escape(&dimension.to_css())),
+		},
+		TokenValue::FontWeight(weight) => ("integer", strings::format_number(weight.round(), 0)),
+		TokenValue::Number(number) if number.fract() == 0.0 => ("integer", strings::format_number(*number, 0)),
+		TokenValue::Number(number) => ("float", strings::format_number(*number, 4)),
+		TokenValue::FontFamily(families) => {
+			("string", android_string_escape(families.first().map(String::as_str).unwrap_or_default()))
+		}
+		TokenValue::Typography(_) | TokenValue::Other { .. } => ("string", android_string_escape(&css_value(value))),
+	};
+	PlatformValue { kind, value }
+}
+
+/// Returns the Swift (SwiftUI) constant of a value. The dimensions are in points (px and pt as is, rem as 16px).
+fn swift_value(value: &TokenValue) -> PlatformValue {
+	let (kind, value) = match value {
+		TokenValue::Color(color) => (
+			"Color",
+			format!(
+				"Color(red: {}, green: {}, blue: {}, opacity: {})",
+				strings::format_number(color.red, 4),
+				strings::format_number(color.green, 4),
+				strings::format_number(color.blue, 4),
+				strings::format_number(color.alpha, 4)
+			),
+		),
+		TokenValue::Dimension(dimension) => match platform_points(dimension) {
+			Some(points) => ("CGFloat", strings::format_number(points, 2)),
+			None => ("String", codegen_string_literal(&dimension.to_css())),
+		},
+		TokenValue::FontWeight(weight) => ("Font.Weight", swift_font_weight(*weight).to_string()),
+		TokenValue::Number(number) => ("Double", strings::format_number(*number, 4)),
+		TokenValue::FontFamily(families) => {
+			("String", codegen_string_literal(families.first().map(String::as_str).unwrap_or_default()))
+		}
+		TokenValue::Typography(_) | TokenValue::Other { .. } => ("String", codegen_string_literal(&css_value(value))),
+	};
+	PlatformValue { kind, value }
+}
+
+/// Returns the points of a dimension for the native platforms (None for the relative units, e.g., "em", "%").
+fn platform_points(dimension: &Dimension) -> Option<f64> {
+	match dimension.unit.as_str() {
+		"px" | "pt" | "dp" => Some(dimension.value),
+		"rem" => Some(dimension.value * REM_PX),
+		_ => None,
+	}
+}
+
+fn swift_font_weight(weight: f64) -> &'static str {
+	match weight.round() as u32 {
+		0..=149 => ".ultraLight",
+		150..=249 => ".thin",
+		250..=349 => ".light",
+		350..=449 => ".regular",
+		450..=549 => ".medium",
+		550..=649 => ".semibold",
+		650..=749 => ".bold",
+		750..=849 => ".heavy",
+		_ => ".black",
+	}
+}
+
+/// Escapes a value for an Android string resource (XML text, and the `'` and `"` quotes).
+fn android_string_escape(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('\'', "\\'")
+		.replace('"', "\\\"")
+}
+
+// endregion: --- Values
@@ -0,0 +1,78 @@
+//! Comparison of the design tokens of the design source with the tokens of the code (`webtk tokens diff`),
+//! so CI can flag when the code drifts from the design.
+
+use crate::handlers::design_tokens::DesignToken;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// The differences of the code tokens with the design tokens, by token path (sorted).
+#[derive(Debug, Default, Serialize)]
+pub struct TokensDiff {
+	/// In the design, not in the code.
+	pub added: Vec<TokenChange>,
+	/// In the code, not in the design.
+	pub removed: Vec<TokenChange>,
+	/// In both, with a different type or value.
+	pub changed: Vec<TokenChange>,
+}
+
+impl TokensDiff {
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+	}
+
+	pub fn len(&self) -> usize {
+		self.added.len() + self.removed.len() + self.changed.len()
+	}
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenChange {
+	/// The dotted path (e.g., "color.brand.primary").
+	pub path: String,
+	/// The value in the design (see `TokenValue::summary`).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub design: Option<String>,
+	/// The value in the code.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub code: Option<String>,
+}
+
+/// Returns the differences of the code tokens with the design tokens.
+/// The values are compared by type and summary (e.g., "#3366ff"), so "#3366ff" and its sRGB components are equal.
+pub fn diff_tokens(design: &[DesignToken], code: &[DesignToken]) -> TokensDiff {
+	let design = tokens_by_path(design);
+	let code = tokens_by_path(code);
+
+	let mut diff = TokensDiff::default();
+	for (path, design_value) in &design {
+		match code.get(path) {
+			None => {
+				diff.added
+					.push(TokenChange { path: path.clone(), design: Some(design_value.1.clone()), code: None })
+			}
+			Some(code_value) if code_value != design_value => diff.changed.push(TokenChange {
+				path: path.clone(),
+				design: Some(design_value.1.clone()),
+				code: Some(code_value.1.clone()),
+			}),
+			Some(_) => {}
+		}
+	}
+	for (path, code_value) in &code {
+		if !design.contains_key(path) {
+			diff.removed
+				.push(TokenChange { path: path.clone(), design: None, code: Some(code_value.1.clone()) });
+		}
+	}
+
+	diff
+}
+
+/// Returns the (type, summary) of the tokens by dotted path.
+fn tokens_by_path(tokens: &[DesignToken]) -> BTreeMap<String, (String, String)> {
+	tokens
+		.iter()
+		.map(|token| (token.path_str(), (token.value.token_type().to_string(), token.value.summary())))
+		.collect()
+}
@@ -0,0 +1,135 @@
+//! Extraction of the design tokens of a Sketch document: the color variables (swatches) and the named document
+//! colors as "color" tokens, and the text styles as "typography" tokens.
+
+use crate::Result;
+use crate::handlers::design_tokens::{DesignToken, Dimension, TokenValue, Typography};
+use crate::handlers::sketch::{self, layer_str};
+use crate::support::colors::Rgba;
+use crate::support::strings;
+use serde_json::Value;
+use simple_fs::SPath;
+
+/// The group of the color tokens (e.g., swatch "Brand/Primary" -> "color.brand.primary").
+pub const COLOR_GROUP: &str = "color";
+
+/// The group of the typography tokens (e.g., text style "Heading/H1" -> "typography.heading.h1").
+pub const TYPOGRAPHY_GROUP: &str = "typography";
+
+/// Extracts the design tokens of a Sketch file (via `sketchtool dump`).
+pub fn extract_sketch_tokens(sketch_file: &SPath) -> Result<Vec<DesignToken>> {
+	let document = sketch::load_sketch_document(sketch_file)?;
+	Ok(sketch_document_tokens(&document))
+}
+
+/// Returns the tokens of a Sketch document JSON: colors (swatches, then named document colors),
+/// then typography (text styles), each in document order.
+pub fn sketch_document_tokens(document: &Value) -> Vec<DesignToken> {
+	let mut tokens = Vec::new();
+
+	for swatch in shared_objects(document, "sharedSwatches") {
+		if let (Some(name), Some(color)) = (layer_str(swatch, "name"), swatch.get("value").and_then(sketch_color)) {
+			tokens.push(DesignToken { path: token_path(COLOR_GROUP, name), value: TokenValue::Color(color) });
+		}
+	}
+
+	let color_assets = document.pointer("/assets/colorAssets").and_then(Value::as_array);
+	for asset in color_assets.into_iter().flatten() {
+		let name = layer_str(asset, "name").filter(|name| !name.trim().is_empty());
+		if let (Some(name), Some(color)) = (name, asset.get("color").and_then(sketch_color)) {
+			tokens.push(DesignToken { path: token_path(COLOR_GROUP, name), value: TokenValue::Color(color) });
+		}
+	}
+
+	for style in shared_objects(document, "layerTextStyles") {
+		let attributes = style.pointer("/value/textStyle/encodedAttributes");
+		if let (Some(name), Some(typography)) = (layer_str(style, "name"), attributes.and_then(sketch_typography)) {
+			tokens.push(DesignToken {
+				path: token_path(TYPOGRAPHY_GROUP, name),
+				value: TokenValue::Typography(typography),
+			});
+		}
+	}
+
+	tokens
+}
+
+/// Returns the objects of a shared collection of the document (e.g., "sharedSwatches", "layerTextStyles").
+fn shared_objects<'a>(document: &'a Value, collection: &str) -> &'a [Value] {
+	document
+		.get(collection)
+		.and_then(|c| c.get("objects"))
+		.and_then(Value::as_array)
+		.map(Vec::as_slice)
+		.unwrap_or(&[])
+}
+
+/// Returns the token path of a Sketch name, with the "/" levels as groups (e.g., "Brand/Primary 500"
+/// -> ["color", "brand", "primary-500"]).
+fn token_path(group: &str, name: &str) -> Vec<String> {
+	std::iter::once(group.to_string())
+		.chain(
+			name.split('/')
+				.map(|segment| strings::canonicalize_name(segment).to_lowercase())
+				.filter(|segment| !segment.is_empty()),
+		)
+		.collect()
+}
+
+/// Returns the color of a Sketch color object (`{"red": 1, "green": 0, "blue": 0, "alpha": 1}`).
+pub fn sketch_color(color: &Value) -> Option<Rgba> {
+	let component = |name: &str| color.get(name).and_then(Value::as_f64);
+	Some(Rgba::new(component("red")?, component("green")?, component("blue")?, component("alpha").unwrap_or(1.0)))
+}
+
+/// Returns the typography of the encoded attributes of a text style.
+/// The family and weight come from the PostScript name of the font (e.g., "Inter-SemiBold" -> "Inter", 600).
+fn sketch_typography(attributes: &Value) -> Option<Typography> {
+	let font = attributes.pointer("/MSAttributedStringFontAttribute/attributes")?;
+	let font_name = font.get("name")?.as_str()?;
+	let font_size = font.get("size")?.as_f64()?;
+	let (font_family, font_weight) = font_family_weight(font_name);
+
+	let line_height = attributes
+		.pointer("/paragraphStyle/maximumLineHeight")
+		.and_then(Value::as_f64)
+		.filter(|line_height| *line_height > 0.0 && font_size > 0.0)
+		.map(|line_height| (line_height / font_size * 1000.0).round() / 1000.0);
+
+	let letter_spacing = attributes
+		.get("kerning")
+		.and_then(Value::as_f64)
+		.filter(|kerning| *kerning != 0.0)
+		.map(Dimension::px);
+
+	Some(Typography {
+		font_family: vec![font_family],
+		font_size: Dimension::px(font_size),
+		font_weight,
+		line_height,
+		letter_spacing,
+	})
+}
+
+/// Returns the family and weight of a PostScript font name (e.g., "Inter-BoldItalic" -> ("Inter", 700)).
+/// Names without a known weight suffix are a regular (400) family.
+fn font_family_weight(font_name: &str) -> (String, f64) {
+	let Some((family, style)) = font_name.rsplit_once('-') else {
+		return (font_name.to_string(), 400.0);
+	};
+
+	let style = style.to_ascii_lowercase().replace("italic", "").replace("oblique", "");
+	let weight = match style.as_str() {
+		"thin" | "hairline" => 100.0,
+		"extralight" | "ultralight" => 200.0,
+		"light" => 300.0,
+		"" | "regular" | "normal" | "book" | "roman" => 400.0,
+		"medium" => 500.0,
+		"semibold" | "demibold" => 600.0,
+		"bold" => 700.0,
+		"extrabold" | "ultrabold" | "heavy" => 800.0,
+		"black" => 900.0,
+		_ => return (font_name.to_string(), 400.0),
+	};
+
+	(family.to_string(), weight)
+}
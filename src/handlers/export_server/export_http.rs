@@ -0,0 +1,150 @@
+//! The HTTP endpoints of `webtk export-server` (plain HTTP/1.1, one request per connection, a thread per connection).
+//!
+//! - `PUT /files/<hash>` with the Sketch file as body: stores it (the hash is its FNV-1a, see `support::hashes`)
+//! - `POST /jobs` with an `ExportJobRequest` JSON body: runs sketchtool (`ExportJobResponse` JSON), 404 when the
+//!   Sketch file was not uploaded
+//! - `GET /results/<id>`: the exported files of a job, as a tar archive (deleted once sent)
+//! - `GET /health`: `{"status": "ok"}`
+
+use crate::handlers::export_server::{
+	ExportJobLog, ExportJobRequest, ExportServerOptions, is_hash, is_result_id, run_export_job, store_sketch_file,
+};
+use crate::support::files;
+use crate::support::http_server::{HttpRequest, HttpResponse, error_response, read_request, write_response};
+use crate::{Error, Result};
+use serde_json::json;
+use std::io::Write as _;
+use std::net::{TcpListener, TcpStream};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Max size of an uploaded Sketch file.
+const MAX_SKETCH_FILE_BYTES: usize = 512 * 1024 * 1024;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Serves the export endpoints on `addr` until the process is interrupted.
+/// `on_job` is called with the log (or error message) of each job.
+pub fn serve_exports(
+	addr: &str,
+	options: &ExportServerOptions,
+	on_listen: impl FnOnce(),
+	on_job: impl Fn(core::result::Result<&ExportJobLog, &str>) + Sync,
+) -> Result<()> {
+	let listener = TcpListener::bind(addr).map_err(|e| format!("Cannot listen on '{addr}'. Cause: {e}"))?;
+	on_listen();
+
+	std::thread::scope(|scope| {
+		for stream in listener.incoming() {
+			let Ok(stream) = stream else { continue };
+			let on_job = &on_job;
+			scope.spawn(move || handle_connection(stream, options, on_job));
+		}
+	});
+
+	Ok(())
+}
+
+fn handle_connection(
+	mut stream: TcpStream,
+	options: &ExportServerOptions,
+	on_job: &impl Fn(core::result::Result<&ExportJobLog, &str>),
+) {
+	let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+	let request = match read_request(&mut stream, MAX_SKETCH_FILE_BYTES) {
+		Ok(request) => request,
+		Err(response) => {
+			let _ = write_response(&mut stream, &response);
+			return;
+		}
+	};
+
+	// The results are streamed, not JSON
+	if let ("GET", Some(id)) = (request.method.as_str(), request.path.strip_prefix("/results/")) {
+		if let Err(response) = send_result(&mut stream, options, id) {
+			let _ = write_response(&mut stream, &response);
+		}
+		return;
+	}
+
+	let response = handle_request(options, request, on_job);
+	let _ = write_response(&mut stream, &response);
+}
+
+fn handle_request(
+	options: &ExportServerOptions,
+	request: HttpRequest,
+	on_job: &impl Fn(core::result::Result<&ExportJobLog, &str>),
+) -> HttpResponse {
+	match (request.method.as_str(), request.path.as_str()) {
+		("GET", "/health") => HttpResponse { status: 200, body: json!({ "status": "ok" }) },
+		("PUT", path) if path.starts_with("/files/") => {
+			let hash = &path["/files/".len()..];
+			match store_sketch_file(options, hash, &request.body) {
+				Ok(()) => HttpResponse { status: 200, body: json!({ "file": hash }) },
+				Err(err) => error_response(400, &err.to_string()),
+			}
+		}
+		("POST", "/jobs") => {
+			let job: ExportJobRequest = match serde_json::from_slice(&request.body) {
+				Ok(job) => job,
+				Err(err) => return error_response(400, &format!("Invalid job request. Cause: {err}")),
+			};
+			if is_hash(&job.file) && !options.sketch_file(&job.file).exists() {
+				return error_response(404, &format!("Sketch file '{}' not uploaded", job.file));
+			}
+
+			match run_export_job(options, &job) {
+				Ok((response, log)) => {
+					on_job(Ok(&log));
+					HttpResponse { status: 200, body: serde_json::to_value(&response).unwrap_or_default() }
+				}
+				Err(err) => {
+					let message = format!("Cannot run the job '{}'. Cause: {err}", job.command.join(" "));
+					on_job(Err(&message));
+					error_response(400, &message)
+				}
+			}
+		}
+		(_, "/health" | "/jobs") => error_response(405, "Method not allowed"),
+		(_, path) if path.starts_with("/files/") || path.starts_with("/results/") => {
+			error_response(405, "Method not allowed")
+		}
+		_ => error_response(404, "Not found"),
+	}
+}
+
+/// Streams the files of a result as a tar archive (until the connection closes), then deletes them.
+fn send_result(
+	stream: &mut TcpStream,
+	options: &ExportServerOptions,
+	id: &str,
+) -> core::result::Result<(), HttpResponse> {
+	let result_dir = options.result_dir(id);
+	if !is_result_id(id) || !result_dir.is_dir() {
+		return Err(error_response(404, &format!("Result '{id}' not found")));
+	}
+
+	let mut tar = Command::new("tar")
+		.arg("-C")
+		.arg(result_dir.as_str())
+		.args(["-cf", "-", "."])
+		.stdout(Stdio::piped())
+		.spawn()
+		.map_err(|e| error_response(500, &Error::tool_exec("tar", "tar", e).to_string()))?;
+	let Some(mut archive) = tar.stdout.take() else {
+		return Err(error_response(500, "Cannot read the tar output"));
+	};
+
+	let head = "HTTP/1.1 200 OK\r\nContent-Type: application/x-tar\r\nConnection: close\r\n\r\n";
+	let sent = stream
+		.write_all(head.as_bytes())
+		.and_then(|_| std::io::copy(&mut archive, stream).map(|_| ()));
+	let _ = tar.wait();
+	if sent.is_ok() {
+		let _ = files::safer_delete_dir(&result_dir);
+	}
+
+	Ok(())
+}
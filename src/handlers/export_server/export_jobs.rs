@@ -0,0 +1,187 @@
+//! The export jobs of `webtk export-server`: a sketchtool command on an uploaded Sketch file, run on the server (a Mac),
+//! its exported files kept as a result until the client downloads them.
+
+use crate::handlers::sketch::run_sketchtool;
+use crate::support::{files, hashes};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use simple_fs::{SPath, ensure_dir};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_EXPORT_SERVER_ADDR: &str = "127.0.0.1:9000";
+pub const DEFAULT_EXPORT_SERVER_DIR: &str = ".cache-export-server";
+
+/// The sketchtool commands an export server runs.
+const ALLOWED_COMMANDS: &[&[&str]] = &[&["list", "artboards"], &["metadata"], &["dump"], &["export", "artboards"]];
+
+static RESULT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone)]
+pub struct ExportServerOptions {
+	/// The uploaded Sketch files (`files/<hash>.sketch`) and the results not downloaded yet (`results/<id>/`).
+	pub work_dir: SPath,
+}
+
+/// A `POST /jobs` request: `sketchtool <options> [--output=<result dir>] <command> <file>`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportJobRequest {
+	/// The FNV-1a hash of the Sketch file (uploaded with `PUT /files/<hash>`).
+	pub file: String,
+	/// e.g., ["export", "artboards"]
+	pub command: Vec<String>,
+	/// The sketchtool options (e.g., "--format=svg"), `--output` excluded.
+	pub options: Vec<String>,
+	/// Whether the command writes files (an `--output` result dir, to download as a tar archive).
+	pub output: bool,
+}
+
+/// The response of a `POST /jobs` request.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportJobResponse {
+	pub success: bool,
+	pub stdout: String,
+	pub stderr: String,
+	/// The path of the result archive (e.g., "/results/3f2a..."), for a successful job with an output.
+	pub result: Option<String>,
+}
+
+/// What the server logs of a job.
+#[derive(Debug)]
+pub struct ExportJobLog {
+	/// e.g., "export artboards"
+	pub command: String,
+	pub file: String,
+	pub success: bool,
+	pub duration_ms: u128,
+}
+
+impl ExportServerOptions {
+	/// The uploaded Sketch file of a hash.
+	pub fn sketch_file(&self, hash: &str) -> SPath {
+		self.work_dir.join("files").join(format!("{hash}.sketch"))
+	}
+
+	/// The files of a result.
+	pub fn result_dir(&self, id: &str) -> SPath {
+		self.work_dir.join("results").join(id)
+	}
+}
+
+/// Stores an uploaded Sketch file, after checking its content matches its hash.
+pub fn store_sketch_file(options: &ExportServerOptions, hash: &str, content: &[u8]) -> Result<()> {
+	if !is_hash(hash) {
+		return Err(Error::custom(format!("Invalid file hash '{hash}'")));
+	}
+	let actual = hashes::sha256_hex(content);
+	if actual != hash {
+		return Err(Error::custom(format!("The content hash is '{actual}', not '{hash}'")));
+	}
+
+	let file = options.sketch_file(hash);
+	if let Some(parent) = file.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create directory '{parent}': {e}"))?;
+	}
+	let part_file = SPath::new(format!("{file}.part"));
+	std::fs::write(part_file.as_std_path(), content).map_err(|e| format!("Failed to write '{part_file}': {e}"))?;
+	std::fs::rename(part_file.as_std_path(), file.as_std_path())
+		.map_err(|e| format!("Failed to write '{file}': {e}"))?;
+
+	Ok(())
+}
+
+/// Runs the sketchtool command of a job on its uploaded Sketch file (which must exist, see `store_sketch_file`).
+/// Only the known commands run, and the options must be flags (`--output` excluded, set by the server).
+pub fn run_export_job(
+	options: &ExportServerOptions,
+	request: &ExportJobRequest,
+) -> Result<(ExportJobResponse, ExportJobLog)> {
+	validate_job_request(request)?;
+
+	let sketch_file = options.sketch_file(&request.file);
+	let result_id = request.output.then(new_result_id);
+	let result_dir = result_id.as_deref().map(|id| options.result_dir(id));
+	if let Some(result_dir) = &result_dir {
+		ensure_dir(result_dir.as_std_path()).map_err(|e| format!("Failed to create directory '{result_dir}': {e}"))?;
+	}
+
+	let started = Instant::now();
+	let command: Vec<&str> = request.command.iter().map(String::as_str).collect();
+	let output = run_sketchtool(&command, &request.options, &sketch_file, result_dir.as_ref());
+	let duration_ms = started.elapsed().as_millis();
+
+	let output = match output {
+		Ok(output) => output,
+		Err(err) => {
+			if let Some(result_dir) = &result_dir {
+				let _ = files::safer_delete_dir(result_dir);
+			}
+			return Err(err);
+		}
+	};
+	let result = match (&result_id, &result_dir) {
+		(Some(id), _) if output.success => Some(format!("/results/{id}")),
+		(_, Some(result_dir)) => {
+			let _ = files::safer_delete_dir(result_dir);
+			None
+		}
+		_ => None,
+	};
+
+	let log = ExportJobLog {
+		command: request.command.join(" "),
+		file: request.file.clone(),
+		success: output.success,
+		duration_ms,
+	};
+	let response = ExportJobResponse {
+		success: output.success,
+		stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+		stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+		result,
+	};
+
+	Ok((response, log))
+}
+
+/// Returns true for a file hash (SHA-256, 64 lowercase hex chars), so it is safe in a path.
+/// The files are shared by the clients of the server, so their hash must be collision-resistant.
+pub fn is_hash(value: &str) -> bool {
+	is_lower_hex(value, 64)
+}
+
+/// Returns true for a result id (16 lowercase hex chars, see `new_result_id`), so it is safe in a path.
+pub fn is_result_id(value: &str) -> bool {
+	is_lower_hex(value, 16)
+}
+
+fn is_lower_hex(value: &str, len: usize) -> bool {
+	value.len() == len && value.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
+
+fn validate_job_request(request: &ExportJobRequest) -> Result<()> {
+	if !is_hash(&request.file) {
+		return Err(Error::custom(format!("Invalid file hash '{}'", request.file)));
+	}
+	if !ALLOWED_COMMANDS.iter().any(|allowed| allowed.iter().eq(request.command.iter())) {
+		return Err(Error::custom(format!("Unsupported sketchtool command '{}'", request.command.join(" "))));
+	}
+	for option in &request.options {
+		if !option.starts_with("--") || option.starts_with("--output") {
+			return Err(Error::custom(format!(
+				"Invalid sketchtool option '{option}'. Expected a flag (e.g., --format=svg), other than --output"
+			)));
+		}
+	}
+
+	Ok(())
+}
+
+fn new_result_id() -> String {
+	let nanos = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_nanos())
+		.unwrap_or_default();
+	let count = RESULT_COUNT.fetch_add(1, Ordering::Relaxed);
+	hashes::fnv1a_64_hex(format!("{}-{count}-{nanos}", std::process::id()))
+}
@@ -0,0 +1,9 @@
+// region:    --- Modules
+
+mod export_http;
+mod export_jobs;
+
+pub use export_http::*;
+pub use export_jobs::*;
+
+// endregion: --- Modules
@@ -0,0 +1,179 @@
+//! The Figma REST API client: typed responses, retries with backoff on rate limits (429) and server errors,
+//! and the node and image-render requests split in chunks (the API limits the ids per call).
+//! Requests go through `http::get` (curl, with the proxy and TLS settings), with the token from `webtk auth`.
+
+use crate::handlers::auth::{self, AuthService};
+use crate::handlers::figma::{FigmaErrorResponse, FigmaFile, FigmaImagesResponse, FigmaNode, FigmaNodesResponse};
+use crate::support::http::{self, HttpResponse};
+use crate::{Error, Result};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub const FIGMA_API_URL: &str = "https://api.figma.com";
+
+/// The API base URL can be overridden (e.g., a mock server in tests).
+const FIGMA_API_URL_ENV: &str = "WEBTK_FIGMA_API_URL";
+
+/// The maximum node ids per `/v1/files/:key/nodes` request.
+pub const MAX_NODE_IDS_PER_REQUEST: usize = 100;
+
+/// The maximum node ids per `/v1/images/:key` request (renders time out on large batches).
+pub const MAX_RENDER_IDS_PER_REQUEST: usize = 50;
+
+const MAX_RETRIES: u32 = 5;
+const MAX_RETRY_DELAY_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FigmaImageFormat {
+	Svg,
+	Png,
+	Jpg,
+	Pdf,
+}
+
+impl FigmaImageFormat {
+	pub fn ext(self) -> &'static str {
+		match self {
+			FigmaImageFormat::Svg => "svg",
+			FigmaImageFormat::Png => "png",
+			FigmaImageFormat::Jpg => "jpg",
+			FigmaImageFormat::Pdf => "pdf",
+		}
+	}
+}
+
+impl TryFrom<&str> for FigmaImageFormat {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"svg" => Ok(Self::Svg),
+			"png" => Ok(Self::Png),
+			"jpg" | "jpeg" => Ok(Self::Jpg),
+			"pdf" => Ok(Self::Pdf),
+			other => Err(Error::custom(format!("Invalid Figma format '{other}'. Expected: svg, png, jpg, pdf"))),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct FigmaClient {
+	token: String,
+	api_url: String,
+}
+
+impl FigmaClient {
+	pub fn new(token: impl Into<String>) -> Self {
+		let api_url = std::env::var(FIGMA_API_URL_ENV).unwrap_or_else(|_| FIGMA_API_URL.to_string());
+		Self { token: token.into(), api_url: api_url.trim_end_matches('/').to_string() }
+	}
+
+	/// A client with the token of `webtk auth` (keychain, or `WEBTK_FIGMA_TOKEN`).
+	pub fn from_auth() -> Result<Self> {
+		let service = AuthService::Figma;
+		match auth::find_token(service)? {
+			Some(auth_token) => Ok(Self::new(auth_token.token)),
+			None => {
+				Err(Error::custom(format!("No figma token. Run `webtk auth set figma` or set {}", service.env_var())))
+			}
+		}
+	}
+
+	/// Returns the document tree of a file, down to `depth` levels (1: pages, 2: pages and their frames).
+	pub fn get_file(&self, file_key: &str, depth: Option<u32>) -> Result<FigmaFile> {
+		let query = depth.map(|depth| format!("?depth={depth}")).unwrap_or_default();
+		self.get_json(&format!("/v1/files/{file_key}{query}"))
+	}
+
+	/// Returns the nodes of the ids (in chunks of `MAX_NODE_IDS_PER_REQUEST`), without the ones that do not exist.
+	pub fn get_nodes(&self, file_key: &str, ids: &[String]) -> Result<HashMap<String, FigmaNode>> {
+		let mut nodes = HashMap::new();
+		for chunk in ids.chunks(MAX_NODE_IDS_PER_REQUEST) {
+			let response: FigmaNodesResponse =
+				self.get_json(&format!("/v1/files/{file_key}/nodes?ids={}", chunk.join(",")))?;
+			nodes.extend(response.nodes.into_iter().filter_map(|(id, entry)| Some((id, entry?.document))));
+		}
+		Ok(nodes)
+	}
+
+	/// Renders the nodes (in chunks of `MAX_RENDER_IDS_PER_REQUEST`), returning the image URL of each id
+	/// (None when Figma could not render it). The URLs are temporary (about 30 days).
+	pub fn render_images(
+		&self,
+		file_key: &str,
+		ids: &[String],
+		format: FigmaImageFormat,
+		scale: f32,
+	) -> Result<HashMap<String, Option<String>>> {
+		let mut images = HashMap::new();
+		for chunk in ids.chunks(MAX_RENDER_IDS_PER_REQUEST) {
+			let response: FigmaImagesResponse = self.get_json(&format!(
+				"/v1/images/{file_key}?ids={}&format={}&scale={scale}",
+				chunk.join(","),
+				format.ext()
+			))?;
+			if let Some(err) = response.err {
+				return Err(Error::custom(format!("Figma render failed: {err}")));
+			}
+			images.extend(response.images);
+		}
+		Ok(images)
+	}
+
+	/// GETs an API path, retrying on 429 (after `Retry-After`) and 5xx (exponential backoff).
+	fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+		let url = format!("{}{path}", self.api_url);
+		let mut attempt = 0;
+		loop {
+			let response = http::get(&url, &[("X-Figma-Token", &self.token)])?;
+			if response.is_success() {
+				return serde_json::from_str(&response.body)
+					.map_err(|e| Error::custom(format!("Invalid Figma response for '{path}'. Cause: {e}")));
+			}
+
+			let retryable = response.status == 429 || response.status >= 500;
+			if !retryable || attempt >= MAX_RETRIES {
+				return Err(response_error(path, &response));
+			}
+
+			std::thread::sleep(Duration::from_secs(retry_delay_secs(response.header("Retry-After"), attempt)));
+			attempt += 1;
+		}
+	}
+}
+
+/// The wait before retrying: the `Retry-After` seconds when given, otherwise 1, 2, 4, ... seconds,
+/// capped at `MAX_RETRY_DELAY_SECS`.
+pub fn retry_delay_secs(retry_after: Option<&str>, attempt: u32) -> u64 {
+	let delay = retry_after
+		.and_then(|value| value.trim().parse::<u64>().ok())
+		.unwrap_or_else(|| 1u64 << attempt.min(16));
+	delay.min(MAX_RETRY_DELAY_SECS)
+}
+
+/// Returns the file key of a Figma file URL (`https://www.figma.com/file/<key>/...` or `/design/<key>/...`),
+/// or the value itself when it is already a key.
+pub fn parse_file_key(value: &str) -> Option<String> {
+	if !http::is_url(value) {
+		let is_key = !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric());
+		return is_key.then(|| value.to_string());
+	}
+
+	let path = value.split(['?', '#']).next().unwrap_or_default();
+	let mut segments = path.split('/').skip(3);
+	while let Some(segment) = segments.next() {
+		if matches!(segment, "file" | "design" | "proto" | "board") {
+			return segments.next().filter(|key| !key.is_empty()).map(|key| key.to_string());
+		}
+	}
+	None
+}
+
+fn response_error(path: &str, response: &HttpResponse) -> Error {
+	let detail = serde_json::from_str::<FigmaErrorResponse>(&response.body)
+		.ok()
+		.and_then(|error| error.err.or(error.message))
+		.unwrap_or_else(|| response.body.trim().chars().take(200).collect());
+	Error::custom(format!("Figma API error {} for '{path}': {detail}", response.status))
+}
@@ -0,0 +1,105 @@
+//! Export of the frames of a Figma file (`webtk figma list|export`), rendered by the Figma API.
+
+use crate::handlers::figma::{FigmaClient, FigmaFile, FigmaImageFormat};
+use crate::handlers::stats;
+use crate::support::{files, globs, http, strings};
+use crate::{Error, Result};
+use simple_fs::SPath;
+use std::collections::HashSet;
+
+/// The node types exported as frames (the direct children of the pages).
+const FRAME_NODE_TYPES: &[&str] = &["FRAME", "COMPONENT", "COMPONENT_SET", "SECTION"];
+
+/// A top-level frame of a page (like a Sketch artboard).
+#[derive(Debug, Clone)]
+pub struct FigmaFrame {
+	pub id: String,
+	/// The page name (empty when the frame was selected by id).
+	pub page: String,
+	pub name: String,
+}
+
+#[derive(Debug)]
+pub struct FigmaExportOptions {
+	/// Globs on the frame names (e.g., "ico/*"); all the frames when empty.
+	pub globs: Vec<String>,
+	/// Node ids to export instead of the frames (e.g., "12:34").
+	pub ids: Vec<String>,
+	pub format: FigmaImageFormat,
+	pub scale: f32,
+	pub output_dir: SPath,
+}
+
+#[derive(Debug, Default)]
+pub struct FigmaExportReport {
+	pub exported_files: Vec<String>,
+	/// The frames Figma could not render.
+	pub failed: Vec<String>,
+}
+
+/// Returns the frames of the pages (needs a file of depth 2 or more).
+pub fn list_frames(file: &FigmaFile) -> Vec<FigmaFrame> {
+	file.document
+		.children
+		.iter()
+		.flat_map(|page| {
+			page.children
+				.iter()
+				.filter(|node| FRAME_NODE_TYPES.contains(&node.node_type.as_str()))
+				.map(|node| FigmaFrame { id: node.id.clone(), page: page.name.clone(), name: node.name.clone() })
+		})
+		.collect()
+}
+
+/// Renders the selected frames and downloads them as `<output_dir>/<frame name>.<ext>`
+/// (names sanitized, `/` as sub directories, `-2`, `-3` suffixes for duplicates).
+pub fn export_frames(client: &FigmaClient, file_key: &str, options: &FigmaExportOptions) -> Result<FigmaExportReport> {
+	let frames = if options.ids.is_empty() {
+		let file = client.get_file(file_key, Some(2))?;
+		let glob_patterns: Vec<&str> = options.globs.iter().map(String::as_str).collect();
+		let glob_set = globs::build_glob_set(Some(&glob_patterns))?;
+		list_frames(&file)
+			.into_iter()
+			.filter(|frame| globs::matches_glob_set(glob_set.as_ref(), &frame.name))
+			.collect::<Vec<_>>()
+	} else {
+		let nodes = client.get_nodes(file_key, &options.ids)?;
+		options
+			.ids
+			.iter()
+			.filter_map(|id| nodes.get(id))
+			.map(|node| FigmaFrame { id: node.id.clone(), page: String::new(), name: node.name.clone() })
+			.collect()
+	};
+
+	if frames.is_empty() {
+		let globs = if options.ids.is_empty() { options.globs.clone() } else { options.ids.clone() };
+		return Err(Error::NoArtboardsMatched { globs, suggestions: Vec::new() });
+	}
+
+	stats::count_artboards(frames.len());
+
+	let ids: Vec<String> = frames.iter().map(|frame| frame.id.clone()).collect();
+	let images = client.render_images(file_key, &ids, options.format, options.scale)?;
+
+	let mut report = FigmaExportReport::default();
+	let mut taken = HashSet::new();
+	for frame in &frames {
+		let Some(Some(url)) = images.get(&frame.id) else {
+			report.failed.push(frame.name.clone());
+			continue;
+		};
+
+		let name = strings::unique_name(&files::sanitize_file_path(&frame.name), &mut taken);
+		let file = options.output_dir.join(format!("{name}.{}", options.format.ext()));
+		if let Err(err) = http::download(url, &file) {
+			if report.exported_files.is_empty() {
+				return Err(err);
+			}
+			return Err(Error::PartialFailure { cause: err.to_string(), exported_files: report.exported_files });
+		}
+		report.exported_files.push(file.to_string());
+	}
+
+	Ok(report)
+}
@@ -0,0 +1,65 @@
+//! The Figma implementation of `DesignSource` (the frames of the pages, rendered by the Figma API).
+
+use crate::handlers::figma::{self, FigmaClient, FigmaExportOptions, FigmaImageFormat};
+use crate::handlers::source::{DesignExport, DesignItem, DesignSource};
+use crate::support::globs;
+use crate::{Error, Result, Warning, WarningCode};
+use simple_fs::SPath;
+
+pub struct FigmaSource {
+	client: FigmaClient,
+	file_key: String,
+}
+
+impl FigmaSource {
+	pub fn new(client: FigmaClient, file_key: String) -> Self {
+		Self { client, file_key }
+	}
+}
+
+impl DesignSource for FigmaSource {
+	fn list_items(&self, globs: &[String]) -> Result<Vec<DesignItem>> {
+		let file = self.client.get_file(&self.file_key, Some(2))?;
+		let glob_refs: Vec<&str> = globs.iter().map(String::as_str).collect();
+		let glob_set = globs::build_glob_set(Some(&glob_refs))?;
+
+		let mut items: Vec<DesignItem> = figma::list_frames(&file)
+			.into_iter()
+			.filter(|frame| globs::matches_glob_set(glob_set.as_ref(), &frame.name))
+			.map(|frame| DesignItem { id: frame.id, name: frame.name, page: frame.page })
+			.collect();
+		items.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+
+		Ok(items)
+	}
+
+	fn export_items(&self, globs: &[String], formats: &[String], output_dir: &SPath) -> Result<DesignExport> {
+		// Checked first, so an unsupported format does not fail after other formats were exported
+		let formats = formats
+			.iter()
+			.map(|format| {
+				FigmaImageFormat::try_from(format.as_str()).map_err(|_| {
+					Error::custom(format!("Format '{format}' is not supported for Figma. Expected: svg, png, jpg, pdf"))
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		let mut export = DesignExport::default();
+		for format in formats {
+			let options = FigmaExportOptions {
+				globs: globs.to_vec(),
+				ids: Vec::new(),
+				format,
+				scale: 1.0,
+				output_dir: output_dir.clone(),
+			};
+			let report = figma::export_frames(&self.client, &self.file_key, &options)?;
+			export.exported_files.extend(report.exported_files);
+			export.warnings.extend(report.failed.into_iter().map(|name| {
+				Warning::for_artboard(WarningCode::RenderFailed, &name, format!("Figma could not render '{name}'"))
+			}));
+		}
+
+		Ok(export)
+	}
+}
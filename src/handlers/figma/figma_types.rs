@@ -0,0 +1,47 @@
+//! The typed responses of the Figma REST API (only the fields webtk uses).
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// `GET /v1/files/:key`
+#[derive(Debug, Deserialize)]
+pub struct FigmaFile {
+	pub document: FigmaNode,
+}
+
+/// A node of the document tree (document, canvas/page, frame, component, ...).
+#[derive(Debug, Clone, Deserialize)]
+pub struct FigmaNode {
+	pub id: String,
+	pub name: String,
+	#[serde(rename = "type")]
+	pub node_type: String,
+	#[serde(default)]
+	pub children: Vec<FigmaNode>,
+}
+
+/// `GET /v1/files/:key/nodes` (a requested node is null when it does not exist).
+#[derive(Debug, Deserialize)]
+pub struct FigmaNodesResponse {
+	pub nodes: HashMap<String, Option<FigmaNodeEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FigmaNodeEntry {
+	pub document: FigmaNode,
+}
+
+/// `GET /v1/images/:key` (an image URL is null when the node could not be rendered).
+#[derive(Debug, Deserialize)]
+pub struct FigmaImagesResponse {
+	pub err: Option<String>,
+	#[serde(default)]
+	pub images: HashMap<String, Option<String>>,
+}
+
+/// The error body of the API (e.g., `{"status": 403, "err": "Invalid token"}`).
+#[derive(Debug, Deserialize)]
+pub struct FigmaErrorResponse {
+	pub err: Option<String>,
+	pub message: Option<String>,
+}
@@ -0,0 +1,13 @@
+// region:    --- Modules
+
+mod figma_client;
+mod figma_export;
+mod figma_source;
+mod figma_types;
+
+pub use figma_client::*;
+pub use figma_export::*;
+pub use figma_source::*;
+pub use figma_types::*;
+
+// endregion: --- Modules
@@ -0,0 +1,221 @@
+//! The link check of a built site: the `href`, `src`, and `srcset` references of its HTML pages, the internal ones
+//! checked on disk, and optionally the external ones over HTTP.
+
+use crate::support::html::scan_attributes;
+use crate::support::threads::run_concurrently;
+use crate::support::{http, strings};
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::{ListOptions, SPath};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// The default max concurrent requests of the external link checks.
+pub const DEFAULT_LINK_CHECK_CONCURRENCY: usize = 8;
+
+/// The URL schemes not checked (e.g., "mailto:contact@example.com").
+const SKIPPED_SCHEMES: &[&str] = &["mailto:", "tel:", "data:", "javascript:", "sms:", "blob:"];
+
+/// Options for `check_links`.
+#[derive(Debug, Clone)]
+pub struct LinkCheckOptions {
+	/// Checks the external links (http, https) with requests, not only the internal ones.
+	pub external: bool,
+	/// The max concurrent requests of the external link checks.
+	pub concurrency: usize,
+}
+
+impl Default for LinkCheckOptions {
+	fn default() -> Self {
+		Self { external: false, concurrency: DEFAULT_LINK_CHECK_CONCURRENCY }
+	}
+}
+
+/// A broken reference of an HTML page.
+#[derive(Debug, Serialize)]
+pub struct BrokenLink {
+	/// The HTML file, relative to the root (e.g., "docs/index.html").
+	pub file: String,
+	/// The 1-based line of the attribute.
+	pub line: usize,
+	/// The attribute (e.g., "href", "srcset").
+	pub attr: String,
+	/// The URL as written in the page.
+	pub url: String,
+	/// Why the link is broken (e.g., "not found", "HTTP 404").
+	pub reason: String,
+}
+
+/// The result of `check_links`.
+#[derive(Debug, Serialize)]
+pub struct LinkCheckReport {
+	/// The HTML files checked.
+	pub file_count: usize,
+	/// The references checked (internal and external, the skipped schemes and fragments excluded).
+	pub link_count: usize,
+	/// The distinct external URLs (checked only with `LinkCheckOptions::external`).
+	pub external_count: usize,
+	/// Sorted by file and line.
+	pub broken: Vec<BrokenLink>,
+}
+
+/// A reference of a page, before its check.
+struct PageLink {
+	file: String,
+	line: usize,
+	attr: String,
+	url: String,
+}
+
+/// Checks the references (`href`, `src`, and the `srcset` candidates) of the HTML pages of a root directory.
+/// An internal reference must resolve to a file of the root: the absolute paths from the root, the relative ones from
+/// the page directory, a directory with an `index.html`, or an extensionless path with its `.html` file (the clean
+/// URLs). The external references (http, https, "//host") are checked once per URL when `options.external` is set,
+/// a status of 400 and above or a request failure being broken. The hidden files and directories are skipped.
+pub fn check_links(root_dir: &SPath, options: &LinkCheckOptions) -> Result<LinkCheckReport> {
+	if !root_dir.is_dir() {
+		return Err(Error::custom(format!("Site root directory '{root_dir}' not found")));
+	}
+	let root = root_dir.as_std_path();
+
+	// -- Collect the references
+	let list_options = ListOptions::from_relative_glob(true);
+	let pages = simple_fs::list_files(root, Some(&["**/*.html", "**/*.htm"]), Some(list_options))
+		.map_err(Error::custom_from_err)?;
+
+	let mut file_count = 0;
+	let mut internal_links = Vec::new();
+	let mut external_links = Vec::new();
+	for page in pages {
+		let Ok(rel_path) = page.as_std_path().strip_prefix(root) else {
+			continue;
+		};
+		let rel_path = rel_path.to_string_lossy().replace('\\', "/");
+		if rel_path.split('/').any(|segment| segment.starts_with('.')) {
+			continue;
+		}
+		file_count += 1;
+
+		let content =
+			std::fs::read_to_string(page.as_std_path()).map_err(|e| format!("Failed to read '{page}': {e}"))?;
+		for attr in scan_attributes(&content) {
+			let urls = match attr.name.as_str() {
+				"href" | "src" => vec![attr.value.trim().to_string()],
+				"srcset" => srcset_urls(&attr.value),
+				_ => continue,
+			};
+			for url in urls {
+				if url.is_empty() || url.starts_with('#') || is_skipped_scheme(&url) {
+					continue;
+				}
+				let link = PageLink { file: rel_path.clone(), line: attr.line, attr: attr.name.clone(), url };
+				if is_external(&link.url) {
+					external_links.push(link);
+				} else {
+					internal_links.push(link);
+				}
+			}
+		}
+	}
+
+	// -- Internal checks
+	let mut broken = Vec::new();
+	for link in &internal_links {
+		if let Some(reason) = check_internal_link(root, &link.file, &link.url) {
+			broken.push(broken_link(link, reason));
+		}
+	}
+
+	// -- External checks (once per URL)
+	let mut external_urls: BTreeMap<String, Option<String>> = BTreeMap::new();
+	for link in &external_links {
+		external_urls.insert(external_url(&link.url), None);
+	}
+	if options.external {
+		let urls: Vec<&String> = external_urls.keys().collect();
+		let reasons = run_concurrently(&urls, options.concurrency, |url| match http::url_status(url) {
+			Ok(status) if status >= 400 => Some(format!("HTTP {status}")),
+			Ok(_) => None,
+			Err(err) => Some(err.to_string()),
+		});
+		let checked: Vec<(String, Option<String>)> = urls.into_iter().cloned().zip(reasons).collect();
+		external_urls.extend(checked);
+
+		for link in &external_links {
+			if let Some(Some(reason)) = external_urls.get(&external_url(&link.url)) {
+				broken.push(broken_link(link, reason.clone()));
+			}
+		}
+	}
+
+	broken.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+
+	Ok(LinkCheckReport {
+		file_count,
+		link_count: internal_links.len() + external_links.len(),
+		external_count: external_urls.len(),
+		broken,
+	})
+}
+
+fn broken_link(link: &PageLink, reason: String) -> BrokenLink {
+	BrokenLink { file: link.file.clone(), line: link.line, attr: link.attr.clone(), url: link.url.clone(), reason }
+}
+
+/// Returns the URLs of a `srcset` value (e.g., "a.png 1x, b.png 2x" -> ["a.png", "b.png"]).
+fn srcset_urls(srcset: &str) -> Vec<String> {
+	srcset
+		.split(',')
+		.filter_map(|candidate| candidate.split_whitespace().next())
+		.map(str::to_string)
+		.collect()
+}
+
+/// The skipped schemes, and the "scheme://" URLs other than http and https (e.g., "ftp://").
+fn is_skipped_scheme(url: &str) -> bool {
+	let lower = url.to_ascii_lowercase();
+	SKIPPED_SCHEMES.iter().any(|scheme| lower.starts_with(scheme)) || (lower.contains("://") && !is_external(&lower))
+}
+
+fn is_external(url: &str) -> bool {
+	let lower = url.to_ascii_lowercase();
+	lower.starts_with("//") || lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Returns the requested URL of an external link: the protocol-relative URLs as https, without the fragment.
+fn external_url(url: &str) -> String {
+	let url = url.split('#').next().unwrap_or_default();
+	if url.starts_with("//") { format!("https:{url}") } else { url.to_string() }
+}
+
+/// Returns why an internal link of a page (relative to the root) is broken, or None when its target exists.
+fn check_internal_link(root: &Path, page: &str, url: &str) -> Option<String> {
+	let path = url.split(['?', '#']).next().unwrap_or_default();
+	if path.is_empty() {
+		// Only a query (e.g., "?page=2"), the page itself
+		return None;
+	}
+	// A `+` is a literal in a path, not a space
+	let Some(path) = strings::percent_decode(&path.replace('+', "%2B")) else {
+		return Some("invalid percent-encoding".to_string());
+	};
+
+	let base = match path.strip_prefix('/') {
+		Some(_) => PathBuf::new(),
+		None => Path::new(page).parent().map(Path::to_path_buf).unwrap_or_default(),
+	};
+	let mut target = PathBuf::new();
+	for component in base.join(path.trim_start_matches('/')).components() {
+		match component {
+			Component::Normal(segment) => target.push(segment),
+			Component::ParentDir if !target.pop() => return Some("outside the site root".to_string()),
+			_ => {}
+		}
+	}
+
+	let target = root.join(target);
+	let found = target.is_file()
+		|| (target.is_dir() && target.join("index.html").is_file())
+		|| (target.extension().is_none() && target.with_extension("html").is_file());
+	(!found).then(|| "not found".to_string())
+}
@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod html_links;
+
+pub use html_links::*;
+
+// endregion: --- Modules
@@ -0,0 +1,238 @@
+use crate::handlers::svg::{self, SpriteSymbol};
+use crate::support::{files, hashes, xmls};
+use crate::{Error, Result};
+use simple_fs::{SPath, ensure_dir, read_to_string};
+use std::collections::{BTreeMap, HashMap};
+
+/// Policy applied when both icon sets contain the same icon name with different content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+	PreferA,
+	PreferB,
+	Error,
+}
+
+impl TryFrom<&str> for ConflictPolicy {
+	type Error = Error;
+
+	fn try_from(val: &str) -> Result<Self> {
+		match val {
+			"prefer-a" => Ok(Self::PreferA),
+			"prefer-b" => Ok(Self::PreferB),
+			"error" => Ok(Self::Error),
+			other => Err(Error::custom(format!(
+				"Invalid conflict policy '{other}'. Expected one of: prefer-a, prefer-b, error"
+			))),
+		}
+	}
+}
+
+/// Result of an icon set merge.
+#[derive(Debug, Default)]
+pub struct MergeReport {
+	pub output_files: Vec<String>,
+	pub icon_count: usize,
+	/// Icon names present in both sets with different content.
+	pub conflicts: Vec<String>,
+	/// Icons of set B skipped because the merged set has the same content under another name,
+	/// as `(name_in_b, existing_name)`.
+	pub duplicates: Vec<(String, String)>,
+}
+
+/// Merges two icon sets into `output`.
+///
+/// Both sets must be of the same kind:
+/// - two directories of SVG files, merged into an output directory
+/// - two SVG sprite files, merged into an output sprite file
+///
+/// Icons are matched by name (relative path for directories, symbol id for sprites)
+/// and compared by normalized content hash.
+pub fn merge_icon_sets(
+	set_a: impl AsRef<SPath>,
+	set_b: impl AsRef<SPath>,
+	output: impl AsRef<SPath>,
+	on_conflict: ConflictPolicy,
+) -> Result<MergeReport> {
+	let (set_a, set_b, output) = (set_a.as_ref(), set_b.as_ref(), output.as_ref());
+
+	let icons_a = load_icon_set(set_a)?;
+	let icons_b = load_icon_set(set_b)?;
+
+	if icons_a.kind != icons_b.kind {
+		return Err(Error::custom(format!(
+			"Cannot merge icon sets of different kinds: '{set_a}' is a {} and '{set_b}' is a {}",
+			icons_a.kind.label(),
+			icons_b.kind.label()
+		)));
+	}
+	let kind = icons_a.kind;
+
+	let mut namespaces = icons_a.namespaces;
+	svg::merge_namespace_declarations(&mut namespaces, icons_b.namespaces);
+
+	let mut merged: BTreeMap<String, IconEntry> =
+		icons_a.entries.into_iter().map(|entry| (entry.name.clone(), entry)).collect();
+
+	let mut report = MergeReport::default();
+
+	// -- Resolve the icons present in both sets first
+	let (shared_b, new_b): (Vec<_>, Vec<_>) =
+		icons_b.entries.into_iter().partition(|entry| merged.contains_key(&entry.name));
+
+	for entry in shared_b {
+		let same_content = merged.get(&entry.name).is_some_and(|existing| existing.hash == entry.hash);
+		if same_content {
+			continue;
+		}
+		report.conflicts.push(entry.name.clone());
+		if on_conflict == ConflictPolicy::PreferB {
+			merged.insert(entry.name.clone(), entry);
+		}
+	}
+
+	// -- Then add the new icons of set B, skipping the ones with already merged content
+	let merged_hashes: HashMap<String, String> =
+		merged.values().map(|entry| (entry.hash.clone(), entry.name.clone())).collect();
+
+	for entry in new_b {
+		if let Some(existing_name) = merged_hashes.get(&entry.hash) {
+			report.duplicates.push((entry.name, existing_name.clone()));
+		} else {
+			merged.insert(entry.name.clone(), entry);
+		}
+	}
+
+	if on_conflict == ConflictPolicy::Error && !report.conflicts.is_empty() {
+		return Err(Error::custom(format!(
+			"{} icon(s) have the same name but different content: {}. Use --on-conflict prefer-a|prefer-b to resolve.",
+			report.conflicts.len(),
+			report.conflicts.join(", ")
+		)));
+	}
+
+	report.icon_count = merged.len();
+	report.output_files = match kind {
+		IconSetKind::Dir => write_icon_dir(output, merged.into_values())?,
+		IconSetKind::Sprite => write_icon_sprite(output, &namespaces, merged.into_values())?,
+	};
+
+	Ok(report)
+}
+
+// region:    --- Icon Set
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IconSetKind {
+	Dir,
+	Sprite,
+}
+
+impl IconSetKind {
+	fn label(&self) -> &'static str {
+		match self {
+			IconSetKind::Dir => "directory",
+			IconSetKind::Sprite => "sprite",
+		}
+	}
+}
+
+struct IconSet {
+	kind: IconSetKind,
+	/// The sprite root namespace declarations (empty for directories).
+	namespaces: Vec<(String, String)>,
+	entries: Vec<IconEntry>,
+}
+
+struct IconEntry {
+	name: String,
+	hash: String,
+	content: IconContent,
+}
+
+enum IconContent {
+	File(String),
+	Symbol(SpriteSymbol),
+}
+
+/// Loads an icon set from a directory of SVG files or from an SVG sprite file.
+fn load_icon_set(path: &SPath) -> Result<IconSet> {
+	if path.is_dir() {
+		let svg_files = simple_fs::list_files(path.as_std_path(), Some(&["**/*.svg"]), None)
+			.map_err(|e| format!("Failed to list SVG files in '{path}': {e}"))?;
+
+		let mut entries = Vec::new();
+		for svg_file in svg_files {
+			let rel_path = svg_file.diff(path).unwrap_or_else(|| svg_file.clone());
+			let name = rel_path.as_str().trim_end_matches(".svg").to_string();
+			let content = read_to_string(svg_file.as_std_path()).map_err(Error::custom_from_err)?;
+			let hash = hashes::fnv1a_64_hex(xmls::normalize_markup(&content));
+			entries.push(IconEntry { name, hash, content: IconContent::File(content) });
+		}
+		entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+		Ok(IconSet { kind: IconSetKind::Dir, namespaces: Vec::new(), entries })
+	} else {
+		files::check_file_exists(path)?;
+		let sprite = svg::load_sprite(path)?;
+		let entries = sprite
+			.symbols
+			.into_iter()
+			.map(|symbol| IconEntry {
+				name: symbol.id.clone(),
+				hash: symbol.content_hash(),
+				content: IconContent::Symbol(symbol),
+			})
+			.collect();
+
+		Ok(IconSet { kind: IconSetKind::Sprite, namespaces: sprite.namespaces, entries })
+	}
+}
+
+fn write_icon_dir(output_dir: &SPath, entries: impl Iterator<Item = IconEntry>) -> Result<Vec<String>> {
+	let mut written = Vec::new();
+	for entry in entries {
+		let IconContent::File(content) = entry.content else {
+			continue;
+		};
+		let target_path = output_dir.join(format!("{}.svg", entry.name));
+
+		if let Some(parent) = target_path.parent() {
+			ensure_dir(parent.as_std_path())
+				.map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+		}
+
+		std::fs::write(target_path.as_std_path(), content)
+			.map_err(|e| format!("Failed to write icon file '{}': {e}", target_path))?;
+
+		written.push(target_path.to_string());
+	}
+
+	Ok(written)
+}
+
+fn write_icon_sprite(
+	output_path: &SPath,
+	namespaces: &[(String, String)],
+	entries: impl Iterator<Item = IconEntry>,
+) -> Result<Vec<String>> {
+	let target_file =
+		if files::looks_like_file_path(output_path) { output_path.clone() } else { output_path.join("symbols.svg") };
+
+	let symbols: Vec<String> = entries
+		.filter_map(|entry| match entry.content {
+			IconContent::Symbol(symbol) => Some(symbol.to_markup()),
+			IconContent::File(_) => None,
+		})
+		.collect();
+
+	if let Some(parent) = target_file.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+	}
+
+	std::fs::write(target_file.as_std_path(), svg::build_svg_symbols_file(&symbols, namespaces))
+		.map_err(|e| format!("Failed to write symbols file '{}': {e}", target_file))?;
+
+	Ok(vec![target_file.to_string()])
+}
+
+// endregion: --- Icon Set
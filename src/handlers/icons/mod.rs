@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod icons_merge;
+
+pub use icons_merge::*;
+
+// endregion: --- Modules
@@ -0,0 +1,236 @@
+//! The conversion of the animated GIFs (and APNGs) to the modern formats, for `webtk img modernize`: an animated WebP
+//! or AVIF, or an MP4 video with its poster image, often a fraction of the GIF size. The conversions run ffmpeg (from
+//! the PATH, with the libwebp, libaom, and libx264 encoders), sandboxed (see `sandbox::sandboxed_command`).
+
+use crate::support::files::absolute_path;
+use crate::support::globs::list_glob_files;
+use crate::support::{pngs, sandbox};
+use crate::{Error, Result};
+use derive_more::Display;
+use serde::Serialize;
+use simple_fs::{SPath, ensure_dir};
+use std::collections::HashMap;
+
+/// The ffmpeg executable (from the PATH).
+pub const FFMPEG_PROGRAM: &str = "ffmpeg";
+
+/// The quality of the animated WebP (libwebp, 0 to 100).
+const WEBP_QUALITY: &str = "75";
+
+/// The constant rate factor of the AVIF (libaom, 0 to 63, lower is better).
+const AVIF_CRF: &str = "32";
+
+/// The constant rate factor of the MP4 (libx264, 0 to 51, lower is better).
+const MP4_CRF: &str = "23";
+
+/// The stderr lines kept in the error of a failed conversion.
+const FFMPEG_ERROR_MAX_LINES: usize = 10;
+
+/// A modern format of `modernize_images`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModernFormat {
+	/// An animated WebP.
+	#[display("webp")]
+	Webp,
+	/// An animated AVIF (ffmpeg 6 and above).
+	#[display("avif")]
+	Avif,
+	/// An H.264 MP4 video (no transparency), with a JPEG poster of its first frame.
+	#[display("mp4")]
+	Mp4,
+}
+
+impl TryFrom<&str> for ModernFormat {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"webp" => Ok(Self::Webp),
+			"avif" => Ok(Self::Avif),
+			"mp4" => Ok(Self::Mp4),
+			other => Err(Error::custom(format!("Invalid image format '{other}'. Expected: webp, avif, mp4"))),
+		}
+	}
+}
+
+/// Options for `modernize_images`.
+#[derive(Debug, Clone)]
+pub struct ModernizeOptions {
+	pub formats: Vec<ModernFormat>,
+	/// The directory of the converted files (default: the directory of each image).
+	pub out_dir: Option<SPath>,
+}
+
+/// A converted file of an image.
+#[derive(Debug, Serialize)]
+pub struct ModernizedFile {
+	pub format: ModernFormat,
+	pub file: String,
+	pub bytes: u64,
+}
+
+/// An image converted by `modernize_images`, with the sizes to compare.
+#[derive(Debug, Serialize)]
+pub struct ModernizedImage {
+	pub file: String,
+	pub bytes: u64,
+	pub outputs: Vec<ModernizedFile>,
+	/// The poster of the MP4 (first frame).
+	pub poster: Option<String>,
+}
+
+/// An image matched but not converted.
+#[derive(Debug, Serialize)]
+pub struct SkippedImage {
+	pub file: String,
+	/// E.g., "not animated" for a PNG.
+	pub reason: String,
+}
+
+/// The result of `modernize_images`.
+#[derive(Debug, Serialize)]
+pub struct ModernizeReport {
+	/// Sorted by file.
+	pub images: Vec<ModernizedImage>,
+	pub skipped: Vec<SkippedImage>,
+}
+
+/// Converts the GIFs and the animated PNGs (APNG) matching the glob patterns (or file paths) to each format, as
+/// "<name>.webp", "<name>.avif", and "<name>.mp4" plus "<name>-poster.jpg", next to the image or in
+/// `options.out_dir`. The other files (e.g., a static PNG) are skipped. Fails when no file matches, when two images
+/// would write the same files, or when a conversion fails.
+pub fn modernize_images(globs: &[String], options: &ModernizeOptions) -> Result<ModernizeReport> {
+	let files = list_glob_files(globs)?;
+	if files.is_empty() {
+		return Err(Error::custom(format!("No images matched '{}'", globs.join("', '"))));
+	}
+
+	// -- The animated images, and their output base paths (without extension)
+	let mut inputs: Vec<(SPath, bool, SPath)> = Vec::new();
+	let mut skipped = Vec::new();
+	let mut bases: HashMap<String, SPath> = HashMap::new();
+	for file in files {
+		let is_apng = match file.ext().to_ascii_lowercase().as_str() {
+			"gif" => false,
+			"png" => {
+				let content = std::fs::read(file.as_std_path()).map_err(|e| format!("Failed to read '{file}': {e}"))?;
+				if !pngs::is_apng(&content) {
+					skipped.push(SkippedImage { file: file.to_string(), reason: "not animated".to_string() });
+					continue;
+				}
+				true
+			}
+			_ => {
+				skipped.push(SkippedImage { file: file.to_string(), reason: "not a GIF or PNG".to_string() });
+				continue;
+			}
+		};
+		let dir = match &options.out_dir {
+			Some(out_dir) => out_dir.clone(),
+			None => file.parent().unwrap_or_else(|| SPath::new(".")),
+		};
+		let base = dir.join(file.stem());
+		if let Some(other) = bases.insert(base.to_string(), file.clone()) {
+			return Err(Error::custom(format!("'{other}' and '{file}' would write the same files ('{base}.*')")));
+		}
+		inputs.push((file, is_apng, base));
+	}
+
+	// -- The conversions
+	let mut images = Vec::new();
+	for (file, is_apng, base) in inputs {
+		if let Some(dir) = base.parent() {
+			ensure_dir(dir.as_std_path()).map_err(|e| format!("Failed to create directory '{dir}': {e}"))?;
+		}
+		let mut image =
+			ModernizedImage { file: file.to_string(), bytes: file_size(&file)?, outputs: Vec::new(), poster: None };
+		for format in &options.formats {
+			let output = SPath::new(format!("{base}.{format}"));
+			run_ffmpeg(&file, is_apng, format_args(*format), &output)?;
+			image.outputs.push(ModernizedFile {
+				format: *format,
+				file: output.to_string(),
+				bytes: file_size(&output)?,
+			});
+			if *format == ModernFormat::Mp4 {
+				let poster = SPath::new(format!("{base}-poster.jpg"));
+				run_ffmpeg(&file, is_apng, &["-frames:v", "1", "-q:v", "3"], &poster)?;
+				image.poster = Some(poster.to_string());
+			}
+		}
+		images.push(image);
+	}
+
+	Ok(ModernizeReport { images, skipped })
+}
+
+// region:    --- Support
+
+/// The ffmpeg arguments of the encoder of a format.
+fn format_args(format: ModernFormat) -> &'static [&'static str] {
+	match format {
+		ModernFormat::Webp => &["-c:v", "libwebp_anim", "-quality", WEBP_QUALITY, "-loop", "0", "-an"],
+		ModernFormat::Avif => &[
+			"-c:v",
+			"libaom-av1",
+			"-crf",
+			AVIF_CRF,
+			"-b:v",
+			"0",
+			"-pix_fmt",
+			"yuv420p",
+			"-an",
+		],
+		// Note: the H.264 yuv420p frames need even dimensions
+		ModernFormat::Mp4 => &[
+			"-c:v",
+			"libx264",
+			"-crf",
+			MP4_CRF,
+			"-pix_fmt",
+			"yuv420p",
+			"-vf",
+			"scale=trunc(iw/2)*2:trunc(ih/2)*2",
+			"-movflags",
+			"+faststart",
+			"-an",
+		],
+	}
+}
+
+/// Runs ffmpeg on an image (an APNG read with the apng demuxer, not as a still PNG), writing `output`.
+/// ffmpeg runs sandboxed in the output directory, so the paths are passed absolute.
+fn run_ffmpeg(input: &SPath, is_apng: bool, args: &[&str], output: &SPath) -> Result<()> {
+	let input_file = absolute_path(input)?;
+	let output_file = absolute_path(output)?;
+	let work_dir = output_file.parent().unwrap_or_else(|| SPath::new("/"));
+
+	let mut command = sandbox::sandboxed_command(FFMPEG_PROGRAM, &work_dir);
+	command.args(["-y", "-v", "error"]);
+	if is_apng {
+		command.args(["-f", "apng"]);
+	}
+	command.arg("-i").arg(input_file.as_str()).args(args).arg(output_file.as_str());
+
+	let output_res = command.output().map_err(|e| Error::tool_exec("ffmpeg", FFMPEG_PROGRAM, e))?;
+	if !output_res.status.success() {
+		let stderr = String::from_utf8_lossy(&output_res.stderr);
+		let lines: Vec<&str> = stderr.trim().lines().collect();
+		let tail = lines[lines.len().saturating_sub(FFMPEG_ERROR_MAX_LINES)..].join("\n");
+		return Err(Error::custom(format!(
+			"ffmpeg failed to convert '{input}' to '{output}' ({}):\n{tail}",
+			output_res.status
+		)));
+	}
+
+	Ok(())
+}
+
+fn file_size(file: &SPath) -> Result<u64> {
+	std::fs::metadata(file.as_std_path())
+		.map(|metadata| metadata.len())
+		.map_err(|e| Error::custom(format!("Failed to read '{file}': {e}")))
+}
+
+// endregion: --- Support
@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod img_modernize;
+
+pub use img_modernize::*;
+
+// endregion: --- Modules
@@ -0,0 +1,75 @@
+//! The HTTP endpoint of `webtk serve --ingest` (plain HTTP/1.1 on a local TCP port, one request per connection).
+//!
+//! - `POST /ingest?name=<artboard name>` with the SVG as body: stores it and rebuilds the sprite (`IngestResult` JSON)
+//! - `GET /health`: `{"status": "ok"}`
+
+use crate::Result;
+use crate::handlers::ingest::{IngestOptions, IngestResult, ingest_svg};
+use crate::support::http_server::{HttpRequest, HttpResponse, error_response, read_request, write_response};
+use serde_json::json;
+use std::net::TcpListener;
+use std::time::Duration;
+
+pub const DEFAULT_INGEST_ADDR: &str = "127.0.0.1:7331";
+
+/// Max size of an ingested SVG.
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serves the ingest endpoint on `addr` until the process is interrupted.
+/// `on_ingest` is called with the result (or error message) of each ingested SVG.
+pub fn serve_ingest(
+	addr: &str,
+	options: &IngestOptions,
+	on_listen: impl FnOnce(),
+	mut on_ingest: impl FnMut(core::result::Result<&IngestResult, &str>),
+) -> Result<()> {
+	let listener = TcpListener::bind(addr).map_err(|e| format!("Cannot listen on '{addr}'. Cause: {e}"))?;
+	on_listen();
+
+	for stream in listener.incoming() {
+		let Ok(mut stream) = stream else { continue };
+		let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+		let response = match read_request(&mut stream, MAX_BODY_BYTES) {
+			Ok(request) => handle_request(options, request, &mut on_ingest),
+			Err(response) => response,
+		};
+		let _ = write_response(&mut stream, &response);
+	}
+
+	Ok(())
+}
+
+fn handle_request(
+	options: &IngestOptions,
+	request: HttpRequest,
+	on_ingest: &mut impl FnMut(core::result::Result<&IngestResult, &str>),
+) -> HttpResponse {
+	match (request.method.as_str(), request.path.as_str()) {
+		("GET", "/health") => HttpResponse { status: 200, body: json!({ "status": "ok" }) },
+		("POST", "/ingest") => {
+			let Some(name) = request.query_value("name").map(str::to_string) else {
+				return error_response(400, "Missing 'name' query parameter (the artboard name)");
+			};
+			let Ok(svg_content) = String::from_utf8(request.body) else {
+				return error_response(400, "The SVG body is not UTF-8");
+			};
+
+			match ingest_svg(options, &name, &svg_content) {
+				Ok(result) => {
+					on_ingest(Ok(&result));
+					HttpResponse { status: 200, body: serde_json::to_value(&result).unwrap_or_default() }
+				}
+				Err(err) => {
+					let message = format!("Cannot ingest '{name}'. Cause: {err}");
+					on_ingest(Err(&message));
+					error_response(400, &message)
+				}
+			}
+		}
+		(_, "/health" | "/ingest") => error_response(405, "Method not allowed"),
+		_ => error_response(404, "Not found"),
+	}
+}
@@ -0,0 +1,139 @@
+//! The sprite pipeline of `webtk serve --ingest`: the SVGs pushed by a Sketch plugin are stored
+//! in the ingest directory (by artboard name), then the sprite is rebuilt from all of them.
+
+use crate::handlers::sketch::{Artboard, ExportChecker};
+use crate::handlers::svg;
+use crate::support::strings;
+use crate::warning::Warning;
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::{SPath, ensure_dir, read_to_string};
+use std::collections::HashSet;
+
+/// The default directory of the ingested SVGs (kept across restarts, so the sprite has all the icons).
+pub const DEFAULT_INGEST_DIR: &str = ".cache-ingest";
+
+#[derive(Debug, Clone)]
+pub struct IngestOptions {
+	pub ingest_dir: SPath,
+	/// The sprite file rebuilt on each ingest.
+	pub output: SPath,
+	/// Add the `data-name` attribute (artboard name) to the symbols.
+	pub symbol_data_attrs: bool,
+	pub strip_namespaces: Vec<String>,
+}
+
+/// The result of one ingested SVG.
+#[derive(Debug, Serialize)]
+pub struct IngestResult {
+	pub name: String,
+	pub symbol_id: String,
+	pub output: String,
+	pub symbol_count: usize,
+	pub warnings: Vec<Warning>,
+}
+
+/// Stores the SVG of an artboard (e.g., "ico/user/fill" -> "<ingest_dir>/ico/user/fill.svg"), then rebuilds the sprite.
+/// The SVG is checked like an exported one (warnings), and must convert to a symbol.
+pub fn ingest_svg(options: &IngestOptions, name: &str, svg_content: &str) -> Result<IngestResult> {
+	check_ingest_name(name)?;
+
+	let symbol_id = strings::canonicalize_name(name);
+	let strip_namespaces: Vec<&str> = options.strip_namespaces.iter().map(|s| s.as_str()).collect();
+	if svg::convert_svg_to_symbol(svg_content, &symbol_id, &[], &strip_namespaces).is_none() {
+		return Err(Error::custom(format!("Invalid SVG for '{name}' (expected an <svg> with a viewBox and content)")));
+	}
+
+	let artboard = Artboard { name: name.to_string(), uid: String::new(), page_name: String::new() };
+	let mut checker = ExportChecker::new(Vec::new());
+	checker.check_symbol_svg(&artboard, svg_content);
+
+	let svg_file = options.ingest_dir.join(format!("{name}.svg"));
+	if let Some(parent) = svg_file.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create directory '{parent}': {e}"))?;
+	}
+	std::fs::write(svg_file.as_std_path(), svg_content)
+		.map_err(|e| format!("Failed to write ingested SVG '{svg_file}': {e}"))?;
+
+	let symbol_ids = build_ingest_sprite(options)?;
+	let symbol_id = symbol_ids
+		.iter()
+		.find(|(artboard_name, _)| artboard_name == name)
+		.map(|(_, id)| id.clone())
+		.unwrap_or(symbol_id);
+
+	Ok(IngestResult {
+		name: name.to_string(),
+		symbol_id,
+		output: options.output.to_string(),
+		symbol_count: symbol_ids.len(),
+		warnings: checker.warnings,
+	})
+}
+
+/// Rebuilds the sprite from all the SVGs of the ingest directory (sorted by name).
+/// Returns the `(name, symbol_id)` of the symbols, ids made unique like in `sketch export`.
+pub fn build_ingest_sprite(options: &IngestOptions) -> Result<Vec<(String, String)>> {
+	let ingest_dir = &options.ingest_dir;
+	let svg_files = if ingest_dir.is_dir() {
+		simple_fs::list_files(ingest_dir.as_std_path(), Some(&["**/*.svg"]), None)
+			.map_err(|e| format!("Failed to list SVG files in '{ingest_dir}': {e}"))?
+	} else {
+		Vec::new()
+	};
+
+	let mut entries: Vec<(String, SPath)> = svg_files
+		.into_iter()
+		.map(|svg_file| {
+			let rel_path = svg_file.diff(ingest_dir).unwrap_or_else(|| svg_file.clone());
+			(rel_path.as_str().trim_end_matches(".svg").to_string(), svg_file)
+		})
+		.collect();
+	entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+	let strip_namespaces: Vec<&str> = options.strip_namespaces.iter().map(|s| s.as_str()).collect();
+	let mut taken: HashSet<String> = HashSet::new();
+	let mut symbols = Vec::new();
+	let mut namespaces: Vec<(String, String)> = Vec::new();
+	let mut symbol_ids = Vec::new();
+
+	for (name, svg_file) in entries {
+		let svg_content = read_to_string(svg_file.as_std_path()).map_err(Error::custom_from_err)?;
+		let symbol_id = strings::unique_name(&strings::canonicalize_name(&name), &mut taken);
+
+		let mut extra_attrs = Vec::new();
+		if options.symbol_data_attrs {
+			extra_attrs.push((svg::DATA_NAME_ATTR, name.as_str()));
+		}
+
+		let symbol = svg::convert_svg_to_symbol(&svg_content, &symbol_id, &extra_attrs, &strip_namespaces)
+			.ok_or_else(|| Error::custom(format!("Failed to convert ingested SVG '{svg_file}' to a symbol")))?;
+		svg::merge_namespace_declarations(&mut namespaces, symbol.namespaces);
+		symbols.push(symbol.markup);
+		symbol_ids.push((name, symbol_id));
+	}
+
+	let output = &options.output;
+	if let Some(parent) = output.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create parent directory '{parent}': {e}"))?;
+	}
+	std::fs::write(output.as_std_path(), svg::build_svg_symbols_file(&symbols, &namespaces))
+		.map_err(|e| format!("Failed to write symbols file '{output}': {e}"))?;
+
+	Ok(symbol_ids)
+}
+
+// region:    --- Support
+
+/// An artboard name usable as a relative file path (e.g., "ico/user/fill").
+fn check_ingest_name(name: &str) -> Result<()> {
+	let is_valid = !name.is_empty()
+		&& !name.contains('\\')
+		&& name
+			.split('/')
+			.all(|segment| !segment.trim().is_empty() && segment != "." && segment != "..");
+
+	if is_valid { Ok(()) } else { Err(Error::custom(format!("Invalid artboard name '{name}'"))) }
+}
+
+// endregion: --- Support
@@ -0,0 +1,9 @@
+// region:    --- Modules
+
+mod ingest_server;
+mod ingest_sprite;
+
+pub use ingest_server::*;
+pub use ingest_sprite::*;
+
+// endregion: --- Modules
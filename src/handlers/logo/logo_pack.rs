@@ -0,0 +1,258 @@
+//! The logo kit of artboards (the marketing "logo pack"): per logo, the SVG and PNG files of its color, monochrome,
+//! and inverse variants, and a favicon set, in a folder per logo, with a `manifest.json` of all the files.
+
+use crate::handlers::raster::{fill_background, render_svg, write_ico, write_png};
+use crate::handlers::sketch::{ExportOptions, execute_export_plan, plan_export};
+use crate::handlers::svg::recolor_svg;
+use crate::support::colors::{self, Rgba};
+use crate::support::{files, strings};
+use crate::{Error, Result};
+use resvg::tiny_skia::{Pixmap, PixmapPaint, Transform};
+use serde::Serialize;
+use simple_fs::{SPath, ensure_dir};
+use std::collections::HashSet;
+
+/// The manifest of the files, in the output directory.
+pub const LOGO_PACK_MANIFEST_FILE: &str = "manifest.json";
+
+/// The default widths of the PNG files, in px.
+pub const DEFAULT_LOGO_PNG_WIDTHS: &[u32] = &[256, 512, 1024, 2048];
+
+/// The directory of the SVG exports (deleted once the pack is written), in the output directory.
+const LOGO_PACK_CACHE_DIR: &str = ".cache-logo-pack";
+
+/// The sizes of the `favicon.ico` images, in px.
+const FAVICON_ICO_SIZES: &[u32] = &[16, 32, 48];
+
+/// The favicon PNG files: the file name, the size in px, and whether the background is opaque
+/// (iOS draws the transparent pixels of the touch icons black).
+const FAVICON_PNGS: &[(&str, u32, bool)] = &[
+	("favicon-16x16.png", 16, false),
+	("favicon-32x32.png", 32, false),
+	("apple-touch-icon.png", 180, true),
+	("android-chrome-192x192.png", 192, false),
+	("android-chrome-512x512.png", 512, false),
+];
+
+/// The background of the opaque favicons.
+const FAVICON_BACKGROUND: Rgba = Rgba { red: 1.0, green: 1.0, blue: 1.0, alpha: 1.0 };
+
+/// Options for `generate_logo_pack`.
+#[derive(Debug, Clone)]
+pub struct LogoPackOptions {
+	/// The widths of the PNG files of each variant, in px (the heights keep the logo ratio).
+	pub png_widths: Vec<u32>,
+	/// The hex color of the monochrome variant (e.g., "#000000").
+	pub mono_color: String,
+	/// The hex color of the inverse variant, for the dark backgrounds (e.g., "#ffffff").
+	pub inverse_color: String,
+}
+
+impl Default for LogoPackOptions {
+	fn default() -> Self {
+		Self {
+			png_widths: DEFAULT_LOGO_PNG_WIDTHS.to_vec(),
+			mono_color: "#000000".to_string(),
+			inverse_color: "#ffffff".to_string(),
+		}
+	}
+}
+
+/// The `manifest.json` of a logo pack.
+#[derive(Debug, Serialize)]
+pub struct LogoPackManifest {
+	/// The Sketch file name.
+	pub source: String,
+	/// In artboard name order.
+	pub logos: Vec<LogoPackLogo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogoPackLogo {
+	/// The artboard name (e.g., "logo/brand").
+	pub name: String,
+	/// The folder of the logo, relative to the output directory (e.g., "logo-brand").
+	pub dir: String,
+	pub files: Vec<LogoPackFile>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogoPackFile {
+	/// Relative to the output directory (e.g., "logo-brand/png/logo-brand-mono-512.png").
+	pub path: String,
+	/// "color", "mono", "inverse", or "favicon".
+	pub variant: &'static str,
+	/// "svg", "png", or "ico".
+	pub format: &'static str,
+	/// The px size (the largest image for the ICO file).
+	pub width: u32,
+	pub height: u32,
+}
+
+/// The files written by `generate_logo_pack`.
+#[derive(Debug)]
+pub struct LogoPackReport {
+	pub logo_count: usize,
+	pub manifest_file: String,
+	/// All the files, but the manifest.
+	pub files: Vec<String>,
+}
+
+/// Generates the logo pack of the artboards of a Sketch file matching the globs (all when None) in the output
+/// directory: per logo, a folder (its flattened name, e.g., "logo-brand") of `svg/` and `png/` files of the
+/// color, mono, and inverse variants (e.g., "logo-brand-inverse-512.png"), and a `favicon/` set (ICO, PNGs,
+/// apple-touch-icon) of the color variant fitted into a square. Then the `manifest.json` of the files.
+pub fn generate_logo_pack(
+	sketch_file: impl AsRef<SPath>,
+	glob_patterns: Option<&[&str]>,
+	output_dir: impl AsRef<SPath>,
+	options: &LogoPackOptions,
+) -> Result<LogoPackReport> {
+	let sketch_file = sketch_file.as_ref();
+	let output_dir = output_dir.as_ref();
+	if options.png_widths.contains(&0) {
+		return Err(Error::custom("Invalid logo PNG width of 0px"));
+	}
+	let mono_color = parse_color(&options.mono_color)?;
+	let inverse_color = parse_color(&options.inverse_color)?;
+
+	// -- Export the SVGs
+	let cache_dir = output_dir.join(LOGO_PACK_CACHE_DIR);
+	let export_options = ExportOptions { flatten: true, ..Default::default() };
+	let plan = plan_export(sketch_file, glob_patterns, &["svg"], &cache_dir, &export_options)?;
+	let result = execute_export_plan(&plan).and_then(|_| {
+		plan.items()
+			.map(|(_, item)| {
+				let content = simple_fs::read_to_string(SPath::new(&item.target_file).as_std_path())
+					.map_err(Error::custom_from_err)?;
+				Ok((item.artboard.name.clone(), content))
+			})
+			.collect::<Result<Vec<(String, String)>>>()
+	});
+	let _ = files::safer_delete_dir(&cache_dir);
+	let logos = result?;
+
+	// -- One folder per logo
+	let mut report = LogoPackReport { logo_count: logos.len(), manifest_file: String::new(), files: Vec::new() };
+	let mut manifest = LogoPackManifest { source: sketch_file.name().to_string(), logos: Vec::new() };
+	let mut taken_names: HashSet<String> = HashSet::from([LOGO_PACK_MANIFEST_FILE.to_string()]);
+	for (name, svg) in logos {
+		let dir_name = strings::unique_name(&strings::canonicalize_name(&name), &mut taken_names);
+		let mut writer = LogoPackWriter { output_dir, dir_name: &dir_name, files: Vec::new() };
+
+		let variants = [
+			("color", svg.clone()),
+			("mono", recolor(&svg, &mono_color, &name)?),
+			("inverse", recolor(&svg, &inverse_color, &name)?),
+		];
+		for (variant, content) in &variants {
+			writer.write_variant(variant, content, &options.png_widths)?;
+		}
+		writer.write_favicons(&svg)?;
+
+		report
+			.files
+			.extend(writer.files.iter().map(|file| output_dir.join(&file.path).to_string()));
+		manifest
+			.logos
+			.push(LogoPackLogo { name, dir: dir_name.clone(), files: writer.files });
+	}
+
+	// -- The manifest
+	let manifest_file = output_dir.join(LOGO_PACK_MANIFEST_FILE);
+	let content = serde_json::to_string_pretty(&manifest).map_err(Error::custom_from_err)?;
+	std::fs::write(manifest_file.as_std_path(), format!("{content}\n"))
+		.map_err(|e| Error::custom(format!("Cannot write '{manifest_file}'. Cause: {e}")))?;
+	report.manifest_file = manifest_file.to_string();
+
+	Ok(report)
+}
+
+/// Writes the files of a logo, collecting them for the manifest.
+struct LogoPackWriter<'a> {
+	output_dir: &'a SPath,
+	dir_name: &'a str,
+	files: Vec<LogoPackFile>,
+}
+
+impl LogoPackWriter<'_> {
+	/// Writes the SVG file of a variant, and its PNG files at the widths.
+	fn write_variant(&mut self, variant: &'static str, svg: &str, png_widths: &[u32]) -> Result<()> {
+		let suffix = if variant == "color" { String::new() } else { format!("-{variant}") };
+		let intrinsic = render_svg(svg, 1.0)?;
+
+		let path = format!("{}/svg/{}{suffix}.svg", self.dir_name, self.dir_name);
+		let file = self.output_dir.join(&path);
+		if let Some(parent) = file.parent() {
+			ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create directory '{parent}': {e}"))?;
+		}
+		std::fs::write(file.as_std_path(), svg)
+			.map_err(|e| Error::custom(format!("Cannot write '{file}'. Cause: {e}")))?;
+		self.push(path, variant, "svg", intrinsic.width(), intrinsic.height());
+
+		for &width in png_widths {
+			let image = render_svg(svg, width as f32 / intrinsic.width() as f32)?;
+			let path = format!("{}/png/{}{suffix}-{width}.png", self.dir_name, self.dir_name);
+			write_png(&image, &self.output_dir.join(&path))?;
+			self.push(path, variant, "png", image.width(), image.height());
+		}
+
+		Ok(())
+	}
+
+	/// Writes the favicon set of the logo (fitted into squares, centered).
+	fn write_favicons(&mut self, svg: &str) -> Result<()> {
+		let intrinsic = render_svg(svg, 1.0)?;
+		let intrinsic_size = intrinsic.width().max(intrinsic.height()) as f32;
+		let square = |size: u32| -> Result<Pixmap> {
+			let image = render_svg(svg, size as f32 / intrinsic_size)?;
+			let mut square = Pixmap::new(size, size)
+				.ok_or_else(|| Error::custom(format!("Cannot create an image of size {size}x{size}")))?;
+			let x = size.saturating_sub(image.width()) / 2;
+			let y = size.saturating_sub(image.height()) / 2;
+			square.draw_pixmap(
+				x as i32,
+				y as i32,
+				image.as_ref(),
+				&PixmapPaint::default(),
+				Transform::identity(),
+				None,
+			);
+			Ok(square)
+		};
+
+		let icons = FAVICON_ICO_SIZES.iter().map(|&size| square(size)).collect::<Result<Vec<_>>>()?;
+		let path = format!("{}/favicon/favicon.ico", self.dir_name);
+		write_ico(&icons, &self.output_dir.join(&path))?;
+		let largest = FAVICON_ICO_SIZES.iter().max().copied().unwrap_or_default();
+		self.push(path, "favicon", "ico", largest, largest);
+
+		for &(file_name, size, is_opaque) in FAVICON_PNGS {
+			let mut image = square(size)?;
+			if is_opaque {
+				image = fill_background(&image, FAVICON_BACKGROUND)?;
+			}
+			let path = format!("{}/favicon/{file_name}", self.dir_name);
+			write_png(&image, &self.output_dir.join(&path))?;
+			self.push(path, "favicon", "png", size, size);
+		}
+
+		Ok(())
+	}
+
+	fn push(&mut self, path: String, variant: &'static str, format: &'static str, width: u32, height: u32) {
+		self.files.push(LogoPackFile { path, variant, format, width, height });
+	}
+}
+
+/// Returns the hex notation of a color option (e.g., "#000" -> "#000000").
+fn parse_color(value: &str) -> Result<String> {
+	colors::parse_hex_color(value)
+		.map(Rgba::to_hex)
+		.ok_or_else(|| Error::custom(format!("Invalid color '{value}'. Expected a hex color (e.g., '#000000')")))
+}
+
+fn recolor(svg: &str, color: &str, name: &str) -> Result<String> {
+	recolor_svg(svg, color)
+		.ok_or_else(|| Error::custom(format!("Cannot recolor the SVG of logo '{name}' (invalid SVG)")))
+}
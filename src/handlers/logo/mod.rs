@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod logo_pack;
+
+pub use logo_pack::*;
+
+// endregion: --- Modules
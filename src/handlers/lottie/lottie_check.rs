@@ -0,0 +1,254 @@
+//! The check of Lottie animations (the JSON files of Bodymovin, LottieFiles): their structure, their image assets
+//! present on disk, and their size budget, optionally optimized first (see `optimize_lottie`).
+
+use crate::handlers::lottie::optimize_lottie;
+use crate::support::globs::list_glob_files;
+use crate::support::{http, strings};
+use crate::{Error, Result};
+use derive_more::Display;
+use serde::Serialize;
+use serde_json::Value;
+use simple_fs::SPath;
+use std::collections::HashSet;
+
+/// Options for `check_lottie_files`.
+#[derive(Debug, Clone, Default)]
+pub struct LottieCheckOptions {
+	/// The max file size (bytes), after the optimization with `optimize`.
+	pub max_bytes: Option<u64>,
+	/// Strips the hidden layers and the metadata, and writes the files minified (when smaller).
+	pub optimize: bool,
+}
+
+/// Machine-readable Lottie issue codes (serialized as kebab-case, e.g., "missing-asset").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LottieIssueCode {
+	#[display("invalid-json")]
+	InvalidJson,
+
+	/// A required property missing or of the wrong type (e.g., no frame rate).
+	#[display("invalid-schema")]
+	InvalidSchema,
+
+	/// An image asset whose file is not found.
+	#[display("missing-asset")]
+	MissingAsset,
+
+	/// A layer `refId` matching no asset.
+	#[display("unknown-asset-ref")]
+	UnknownAssetRef,
+
+	/// A file larger than `LottieCheckOptions::max_bytes`.
+	#[display("over-budget")]
+	OverBudget,
+}
+
+/// An issue of a Lottie file.
+#[derive(Debug, Serialize)]
+pub struct LottieIssue {
+	pub file: String,
+	pub code: LottieIssueCode,
+	pub message: String,
+}
+
+/// A Lottie file checked.
+#[derive(Debug, Serialize)]
+pub struct LottieFileReport {
+	pub file: String,
+	/// The file size, before the optimization.
+	pub bytes: u64,
+	/// The file size after the optimization (None without `LottieCheckOptions::optimize`, or when invalid).
+	pub optimized_bytes: Option<u64>,
+	/// The hidden layers removed by the optimization.
+	pub hidden_layers_removed: usize,
+	/// Whether the optimization removed the `meta` object.
+	pub metadata_removed: bool,
+}
+
+/// The result of `check_lottie_files`.
+#[derive(Debug, Serialize)]
+pub struct LottieCheckReport {
+	/// Sorted by file.
+	pub files: Vec<LottieFileReport>,
+	pub issues: Vec<LottieIssue>,
+}
+
+/// Checks the Lottie files matching the glob patterns (or file paths, e.g., "assets/**/*.json"): their structure
+/// (version, frame rate, frames, size, layers), the layer `refId` of the assets, the image asset files (relative to
+/// the JSON file, the embedded and URL ones excluded), and the size budget. With `options.optimize`, the files are
+/// optimized before the budget check. Fails when no file matches.
+pub fn check_lottie_files(globs: &[String], options: &LottieCheckOptions) -> Result<LottieCheckReport> {
+	let files = list_glob_files(globs)?;
+	if files.is_empty() {
+		return Err(Error::custom(format!("No Lottie files matched '{}'", globs.join("', '"))));
+	}
+
+	let mut reports = Vec::new();
+	let mut issues = Vec::new();
+	for file in files {
+		let content = std::fs::read(file.as_std_path()).map_err(|e| format!("Failed to read '{file}': {e}"))?;
+		let file_name = file.to_string();
+		let mut report = LottieFileReport {
+			file: file_name.clone(),
+			bytes: content.len() as u64,
+			optimized_bytes: None,
+			hidden_layers_removed: 0,
+			metadata_removed: false,
+		};
+		let mut issue = |code: LottieIssueCode, message: String| {
+			issues.push(LottieIssue { file: file_name.clone(), code, message });
+		};
+
+		let mut animation: Value = match serde_json::from_slice(&content) {
+			Ok(animation) => animation,
+			Err(err) => {
+				issue(LottieIssueCode::InvalidJson, err.to_string());
+				reports.push(report);
+				continue;
+			}
+		};
+
+		let base_dir = file.parent().unwrap_or_else(|| SPath::new("."));
+		for (code, message) in check_animation(&animation, &base_dir) {
+			issue(code, message);
+		}
+
+		let mut bytes = report.bytes;
+		if options.optimize && animation.is_object() {
+			let optimization = optimize_lottie(&mut animation);
+			let optimized = serde_json::to_vec(&animation)?;
+			if optimized.len() < content.len() {
+				std::fs::write(file.as_std_path(), &optimized).map_err(|e| format!("Failed to write '{file}': {e}"))?;
+				bytes = optimized.len() as u64;
+			}
+			report.optimized_bytes = Some(bytes);
+			report.hidden_layers_removed = optimization.hidden_layers;
+			report.metadata_removed = optimization.metadata;
+		}
+
+		if let Some(max_bytes) = options.max_bytes
+			&& bytes > max_bytes
+		{
+			issue(
+				LottieIssueCode::OverBudget,
+				format!("{} over the budget of {}", strings::format_bytes(bytes), strings::format_bytes(max_bytes)),
+			);
+		}
+		reports.push(report);
+	}
+
+	Ok(LottieCheckReport { files: reports, issues })
+}
+
+// region:    --- Support
+
+/// Returns the issues of an animation (its structure and its assets).
+fn check_animation(animation: &Value, base_dir: &SPath) -> Vec<(LottieIssueCode, String)> {
+	let mut issues = Vec::new();
+
+	let Some(root) = animation.as_object() else {
+		issues.push(invalid_schema("The root is not an object".to_string()));
+		return issues;
+	};
+
+	// -- The animation properties
+	if !root.get("v").is_some_and(Value::is_string) {
+		issues.push(invalid_schema("Missing the version 'v' (string)".to_string()));
+	}
+	for name in ["fr", "w", "h"] {
+		if !root.get(name).and_then(Value::as_f64).is_some_and(|value| value > 0.0) {
+			issues.push(invalid_schema(format!("Missing '{name}' (positive number)")));
+		}
+	}
+	match (root.get("ip").and_then(Value::as_f64), root.get("op").and_then(Value::as_f64)) {
+		(Some(ip), Some(op)) if op <= ip => {
+			issues.push(invalid_schema(format!("The out point 'op' ({op}) is not after 'ip' ({ip})")))
+		}
+		(Some(_), Some(_)) => (),
+		_ => issues.push(invalid_schema("Missing the frames 'ip' and 'op' (numbers)".to_string())),
+	}
+
+	// -- The assets (their ids, their files)
+	let empty = Vec::new();
+	let assets = match root.get("assets") {
+		None => &empty,
+		Some(Value::Array(assets)) => assets,
+		Some(_) => {
+			issues.push(invalid_schema("'assets' is not an array".to_string()));
+			&empty
+		}
+	};
+	let mut asset_ids = HashSet::new();
+	let mut layer_lists = Vec::new();
+	for (idx, asset) in assets.iter().enumerate() {
+		let Some(id) = asset.get("id").and_then(Value::as_str) else {
+			issues.push(invalid_schema(format!("assets[{idx}]: missing the 'id' (string)")));
+			continue;
+		};
+		asset_ids.insert(id);
+		if let Some(layers) = asset.get("layers") {
+			layer_lists.push((format!("assets[{idx}].layers"), layers));
+		} else if let Some(missing) = missing_image_file(asset, base_dir) {
+			issues.push((LottieIssueCode::MissingAsset, format!("Image asset '{id}': file '{missing}' not found")));
+		}
+	}
+
+	// -- The layers (of the root and of the precomps)
+	match root.get("layers") {
+		Some(layers) => layer_lists.insert(0, ("layers".to_string(), layers)),
+		None => issues.push(invalid_schema("Missing the 'layers' (array)".to_string())),
+	}
+	for (path, layers) in layer_lists {
+		let Some(layers) = layers.as_array() else {
+			issues.push(invalid_schema(format!("'{path}' is not an array")));
+			continue;
+		};
+		for (idx, layer) in layers.iter().enumerate() {
+			let name = match layer.get("nm").and_then(Value::as_str) {
+				Some(name) => format!("{path}[{idx}] ('{name}')"),
+				None => format!("{path}[{idx}]"),
+			};
+			if !layer.is_object() {
+				issues.push(invalid_schema(format!("{name}: not an object")));
+				continue;
+			}
+			if !layer.get("ty").is_some_and(Value::is_i64) {
+				issues.push(invalid_schema(format!("{name}: missing the type 'ty' (integer)")));
+			}
+			if !(layer.get("ip").is_some_and(Value::is_number) && layer.get("op").is_some_and(Value::is_number)) {
+				issues.push(invalid_schema(format!("{name}: missing the frames 'ip' and 'op'")));
+			}
+			if let Some(ref_id) = layer.get("refId").and_then(Value::as_str)
+				&& !asset_ids.contains(ref_id)
+			{
+				issues.push((LottieIssueCode::UnknownAssetRef, format!("{name}: no asset with the id '{ref_id}'")));
+			}
+		}
+	}
+
+	issues
+}
+
+fn invalid_schema(message: String) -> (LottieIssueCode, String) {
+	(LottieIssueCode::InvalidSchema, message)
+}
+
+/// Returns the path of the file of an image asset (`u` + `p`, relative to `base_dir`) when not found.
+/// The embedded images (`e` 1, or a `data:` URI) and the URLs are not checked.
+fn missing_image_file(asset: &Value, base_dir: &SPath) -> Option<String> {
+	let path = asset.get("p").and_then(Value::as_str)?;
+	let dir = asset.get("u").and_then(Value::as_str).unwrap_or_default();
+	let is_embedded = asset.get("e").and_then(Value::as_i64) == Some(1) || path.starts_with("data:");
+	if is_embedded || http::is_url(dir) || http::is_url(path) {
+		return None;
+	}
+
+	let path = match dir.trim_end_matches('/') {
+		"" => path.to_string(),
+		dir => format!("{dir}/{path}"),
+	};
+	(!base_dir.join(&path).exists()).then_some(path)
+}
+
+// endregion: --- Support
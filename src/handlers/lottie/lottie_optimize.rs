@@ -0,0 +1,67 @@
+//! The optimization of a Lottie animation: what does not render stripped (the hidden layers, the metadata of the
+//! exporter), for the `--optimize` of `webtk lottie check`, the file then written minified.
+
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// What `optimize_lottie` removed.
+#[derive(Debug, Clone, Default)]
+pub struct LottieOptimization {
+	/// The hidden layers removed (of the root and of the precomps).
+	pub hidden_layers: usize,
+	/// Whether the `meta` object (generator, author, keywords) was removed.
+	pub metadata: bool,
+}
+
+/// Removes the hidden layers (`"hd": true`) of the animation and of its precomp assets, and the top-level `meta`.
+/// A hidden layer still used by another layer, as its `parent` or its matte (`td`, `tp`), is kept, since removing it
+/// would change how the other layer renders.
+pub fn optimize_lottie(animation: &mut Value) -> LottieOptimization {
+	let mut optimization = LottieOptimization::default();
+	let Some(root) = animation.as_object_mut() else {
+		return optimization;
+	};
+
+	optimization.metadata = root.remove("meta").is_some();
+	if let Some(layers) = root.get_mut("layers") {
+		optimization.hidden_layers += remove_hidden_layers(layers);
+	}
+	if let Some(Value::Array(assets)) = root.get_mut("assets") {
+		for asset in assets.iter_mut() {
+			if let Some(layers) = asset.get_mut("layers") {
+				optimization.hidden_layers += remove_hidden_layers(layers);
+			}
+		}
+	}
+
+	optimization
+}
+
+// region:    --- Support
+
+/// Removes the hidden layers of a layer list not used by the other layers. Returns the count removed.
+fn remove_hidden_layers(layers: &mut Value) -> usize {
+	let Some(layers) = layers.as_array_mut() else {
+		return 0;
+	};
+
+	// The indexes (`ind`) used by the other layers, as parent or matte
+	let used: HashSet<i64> = layers
+		.iter()
+		.flat_map(|layer| [layer.get("parent"), layer.get("tp")])
+		.flatten()
+		.filter_map(Value::as_i64)
+		.collect();
+
+	let count = layers.len();
+	layers.retain(|layer| {
+		let is_hidden = layer.get("hd").and_then(Value::as_bool).unwrap_or(false);
+		let is_matte = layer.get("td").and_then(Value::as_i64).is_some_and(|td| td != 0);
+		let is_used = layer.get("ind").and_then(Value::as_i64).is_some_and(|ind| used.contains(&ind));
+		!is_hidden || is_matte || is_used
+	});
+
+	count - layers.len()
+}
+
+// endregion: --- Support
@@ -0,0 +1,9 @@
+// region:    --- Modules
+
+mod lottie_check;
+mod lottie_optimize;
+
+pub use lottie_check::*;
+pub use lottie_optimize::*;
+
+// endregion: --- Modules
@@ -1 +1,27 @@
+pub mod auth;
+pub mod checksums;
+pub mod codegen;
+pub mod config;
+pub mod daemon;
+pub mod design_tokens;
+pub mod export_server;
+pub mod figma;
+pub mod html;
+pub mod icons;
+pub mod img;
+pub mod ingest;
+pub mod logo;
+pub mod lottie;
+pub mod notify;
+pub mod pdf;
+pub mod raster;
+pub mod run;
+pub mod site;
 pub mod sketch;
+pub mod snapshot;
+pub mod source;
+pub mod stats;
+pub mod svg;
+pub mod sw;
+pub mod update;
+pub mod visual;
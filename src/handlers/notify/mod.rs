@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod run_notification;
+
+pub use run_notification::*;
+
+// endregion: --- Modules
@@ -0,0 +1,144 @@
+//! Rebuild notifications of `run --watch`: macOS notification center and Slack-compatible webhooks.
+
+use crate::handlers::run::RunReport;
+use crate::support::http;
+use crate::{Error, Result};
+use serde_json::json;
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+/// Where the rebuild results are notified (from `[notify]` and the `run` flags).
+#[derive(Debug, Default, Clone)]
+pub struct NotifySettings {
+	pub desktop: bool,
+	pub webhook: Option<String>,
+}
+
+/// The summary of one (re)build.
+#[derive(Debug)]
+pub struct RunNotification {
+	pub success: bool,
+	pub title: String,
+	pub message: String,
+}
+
+impl NotifySettings {
+	pub fn is_enabled(&self) -> bool {
+		self.desktop || self.webhook.is_some()
+	}
+}
+
+/// Desktop notifications use the macOS notification center (`osascript`).
+pub fn desktop_notifications_supported() -> bool {
+	cfg!(target_os = "macos")
+}
+
+/// Summarizes a run report, e.g., "icons, logos: 12 file(s) exported, 2 warning(s)"
+/// or "1 of 2 job(s) failed: icons (Cause: ...)".
+pub fn run_notification(report: &RunReport) -> RunNotification {
+	let job_count = report.jobs().count();
+	let failed: Vec<_> = report.jobs().filter(|job| job.error.is_some()).collect();
+
+	if failed.is_empty() {
+		let names: Vec<&str> = report.jobs().map(|job| job.name.as_str()).collect();
+		let file_count = report.exported_files().len();
+		let warning_count = report.warning_count();
+		RunNotification {
+			success: true,
+			title: "webtk: rebuild succeeded".to_string(),
+			message: format!("{}: {file_count} file(s) exported, {warning_count} warning(s)", names.join(", ")),
+		}
+	} else {
+		let details: Vec<String> = failed
+			.iter()
+			.map(|job| format!("{} ({})", job.name, job.error.as_deref().unwrap_or_default()))
+			.collect();
+		RunNotification {
+			success: false,
+			title: "webtk: rebuild failed".to_string(),
+			message: format!("{} of {job_count} job(s) failed: {}", failed.len(), details.join(", ")),
+		}
+	}
+}
+
+/// Sends the notification to the enabled targets.
+/// Returns the delivery errors (a failed notification must not stop the watch).
+pub fn send_notification(settings: &NotifySettings, notification: &RunNotification) -> Vec<String> {
+	let mut errors = Vec::new();
+
+	if settings.desktop
+		&& let Err(err) = send_desktop_notification(notification)
+	{
+		errors.push(format!("Desktop notification failed. Cause: {err}"));
+	}
+
+	if let Some(url) = &settings.webhook
+		&& let Err(err) = post_webhook(url, notification)
+	{
+		errors.push(format!("Webhook notification failed. Cause: {err}"));
+	}
+
+	errors
+}
+
+/// Shows the notification in the macOS notification center.
+pub fn send_desktop_notification(notification: &RunNotification) -> Result<()> {
+	if !desktop_notifications_supported() {
+		return Err(Error::custom("Desktop notifications are only supported on macOS"));
+	}
+
+	let script = format!(
+		"display notification {} with title {}",
+		applescript_string(&notification.message),
+		applescript_string(&notification.title)
+	);
+	let output = Command::new("osascript")
+		.args(["-e", &script])
+		.output()
+		.map_err(|e| Error::tool_exec("osascript", "osascript", e))?;
+
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!("osascript failed: {}", stderr.trim())));
+	}
+
+	Ok(())
+}
+
+/// POSTs the notification as Slack-compatible JSON (`{"text": "..."}`) with `curl`.
+pub fn post_webhook(url: &str, notification: &RunNotification) -> Result<()> {
+	let icon = if notification.success { ":white_check_mark:" } else { ":x:" };
+	let payload = json!({ "text": format!("{icon} *{}*\n{}", notification.title, notification.message) });
+
+	let mut child = http::curl_command(url)?
+		.args(["-sS", "--fail", "--max-time", "10", "-X", "POST"])
+		.args(["-H", "Content-Type: application/json", "--data-binary", "@-", url])
+		.stdin(Stdio::piped())
+		.stdout(Stdio::null())
+		.stderr(Stdio::piped())
+		.spawn()
+		.map_err(|e| Error::tool_exec("curl", "curl", e))?;
+
+	if let Some(mut stdin) = child.stdin.take() {
+		stdin
+			.write_all(payload.to_string().as_bytes())
+			.map_err(|e| format!("Cannot write the webhook payload. Cause: {e}"))?;
+	}
+
+	let output = child.wait_with_output().map_err(Error::custom_from_err)?;
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!("POST to '{url}' failed: {}", stderr.trim())));
+	}
+
+	Ok(())
+}
+
+// region:    --- Support
+
+/// Quotes a value as an AppleScript string literal.
+fn applescript_string(value: &str) -> String {
+	format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// endregion: --- Support
@@ -0,0 +1,9 @@
+// region:    --- Modules
+
+mod pdf_images;
+mod pdf_merge;
+
+pub use pdf_images::*;
+pub use pdf_merge::*;
+
+// endregion: --- Modules
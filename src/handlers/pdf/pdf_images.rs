@@ -0,0 +1,66 @@
+//! The writing of images as the pages of a PDF file (lopdf), e.g., rendered page layouts.
+
+use crate::{Error, Result};
+use lopdf::{Document, Object, Stream, dictionary};
+use resvg::tiny_skia::Pixmap;
+use simple_fs::{SPath, ensure_dir};
+
+/// Writes the images as the pages of a PDF file, each one filling a page of `page_width` x `page_height` pt
+/// (creating the parent directory). The images are expected opaque (the alpha is dropped). Returns the page count.
+pub fn write_image_pdf(images: &[Pixmap], page_width: f32, page_height: f32, target_file: &SPath) -> Result<usize> {
+	if images.is_empty() {
+		return Err(Error::custom(format!("No pages to write to PDF '{target_file}'")));
+	}
+
+	let mut document = Document::with_version("1.5");
+	let pages_id = document.new_object_id();
+	let mut kids: Vec<Object> = Vec::new();
+
+	for image in images {
+		// The pixels are premultiplied, so the RGB of the opaque pixels are the colors
+		let rgb: Vec<u8> = image
+			.data()
+			.chunks_exact(4)
+			.flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+			.collect();
+		let image_id = document.add_object(Stream::new(
+			dictionary! {
+				"Type" => "XObject",
+				"Subtype" => "Image",
+				"Width" => image.width() as i64,
+				"Height" => image.height() as i64,
+				"ColorSpace" => "DeviceRGB",
+				"BitsPerComponent" => 8,
+			},
+			rgb,
+		));
+		let content = format!("q {page_width} 0 0 {page_height} 0 0 cm /Im0 Do Q");
+		let content_id = document.add_object(Stream::new(dictionary! {}, content.into_bytes()));
+		let page_id = document.add_object(dictionary! {
+			"Type" => "Page",
+			"Parent" => pages_id,
+			"MediaBox" => vec![0.into(), 0.into(), page_width.into(), page_height.into()],
+			"Resources" => dictionary! { "XObject" => dictionary! { "Im0" => image_id } },
+			"Contents" => content_id,
+		});
+		kids.push(page_id.into());
+	}
+
+	let page_count = kids.len();
+	document.objects.insert(
+		pages_id,
+		Object::Dictionary(dictionary! { "Type" => "Pages", "Kids" => kids, "Count" => page_count as i64 }),
+	);
+	let catalog_id = document.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+	document.trailer.set("Root", catalog_id);
+	document.compress();
+
+	if let Some(parent) = target_file.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+	}
+	document
+		.save(target_file.as_std_path())
+		.map_err(|e| Error::custom(format!("Cannot write PDF '{target_file}'. Cause: {e}")))?;
+
+	Ok(page_count)
+}
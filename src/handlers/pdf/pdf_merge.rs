@@ -0,0 +1,106 @@
+//! The merging of PDF files into a single multi-page PDF (lopdf), with an optional bookmark per source file.
+
+use crate::{Error, Result};
+use lopdf::{Bookmark, Dictionary, Document, Object, ObjectId, dictionary};
+use simple_fs::{SPath, ensure_dir};
+
+/// The page attributes a page inherits from its `Pages` ancestors when not set on the page itself.
+const INHERITABLE_PAGE_ATTRIBUTES: &[&[u8]] = &[b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// The max depth of the page tree walked for the inherited attributes (a malformed tree can have cycles).
+const MAX_PAGE_TREE_DEPTH: usize = 32;
+
+/// A PDF file to merge, with the title of the bookmark to its first page.
+pub struct PdfSource {
+	pub file: SPath,
+	pub bookmark: Option<String>,
+}
+
+/// Merges the PDF files into one, their pages in the order of the sources (creating the parent directory).
+/// With bookmarks, the PDF opens with the outline panel. Returns the page count.
+pub fn merge_pdf_files(sources: &[PdfSource], target_file: &SPath) -> Result<usize> {
+	let mut document = Document::with_version("1.5");
+	let pages_id = document.new_object_id();
+	let mut page_ids: Vec<ObjectId> = Vec::new();
+
+	for source in sources {
+		let mut source_doc = Document::load(source.file.as_std_path())
+			.map_err(|e| Error::custom(format!("Cannot read PDF '{}'. Cause: {e}", source.file)))?;
+		source_doc.renumber_objects_with(document.max_id + 1);
+		document.max_id = source_doc.max_id;
+
+		let source_page_ids: Vec<ObjectId> = source_doc.get_pages().into_values().collect();
+		let Some(first_page_id) = source_page_ids.first() else {
+			return Err(Error::custom(format!("PDF '{}' has no pages", source.file)));
+		};
+		if let Some(title) = &source.bookmark {
+			document.add_bookmark(Bookmark::new(title.clone(), [0.0, 0.0, 0.0], 0, *first_page_id), None);
+		}
+
+		for page_id in &source_page_ids {
+			let mut page = page_with_inherited_attributes(&source_doc, *page_id)?;
+			page.set("Parent", pages_id);
+			document.objects.insert(*page_id, Object::Dictionary(page));
+		}
+
+		// The pages were moved above, and the catalog, page tree, and outline of the source are replaced
+		for (object_id, object) in source_doc.objects {
+			let type_name = object.type_name().unwrap_or_default();
+			if !matches!(type_name, b"Catalog" | b"Pages" | b"Page" | b"Outlines" | b"Outline") {
+				document.objects.insert(object_id, object);
+			}
+		}
+		page_ids.extend(source_page_ids);
+	}
+
+	let page_count = page_ids.len();
+	let kids: Vec<Object> = page_ids.into_iter().map(Object::Reference).collect();
+	document.objects.insert(
+		pages_id,
+		Object::Dictionary(dictionary! { "Type" => "Pages", "Kids" => kids, "Count" => page_count as i64 }),
+	);
+
+	let mut catalog = dictionary! { "Type" => "Catalog", "Pages" => pages_id };
+	if let Some(outline_id) = document.build_outline() {
+		catalog.set("Outlines", outline_id);
+		catalog.set("PageMode", "UseOutlines");
+	}
+	let catalog_id = document.add_object(catalog);
+	document.trailer.set("Root", catalog_id);
+	document.compress();
+
+	if let Some(parent) = target_file.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+	}
+	document
+		.save(target_file.as_std_path())
+		.map_err(|e| Error::custom(format!("Cannot write PDF '{target_file}'. Cause: {e}")))?;
+
+	Ok(page_count)
+}
+
+/// Returns the dictionary of a page with the attributes inherited from its `Pages` ancestors set on it
+/// (the page is moved to another page tree).
+fn page_with_inherited_attributes(doc: &Document, page_id: ObjectId) -> Result<Dictionary> {
+	let mut page = doc
+		.get_dictionary(page_id)
+		.map_err(|e| Error::custom(format!("Invalid PDF page {page_id:?}. Cause: {e}")))?
+		.clone();
+
+	let mut parent_id = page.get(b"Parent").and_then(Object::as_reference).ok();
+	for _ in 0..MAX_PAGE_TREE_DEPTH {
+		let Some(ancestor) = parent_id.and_then(|id| doc.get_dictionary(id).ok()) else {
+			break;
+		};
+		for attribute in INHERITABLE_PAGE_ATTRIBUTES {
+			if !page.has(attribute)
+				&& let Ok(value) = ancestor.get(attribute)
+			{
+				page.set(*attribute, value.clone());
+			}
+		}
+		parent_id = ancestor.get(b"Parent").and_then(Object::as_reference).ok();
+	}
+
+	Ok(page)
+}
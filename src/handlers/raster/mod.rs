@@ -0,0 +1,15 @@
+// region:    --- Modules
+
+mod raster_diff;
+mod raster_ico;
+mod raster_redline;
+mod raster_render;
+mod raster_tiles;
+
+pub use raster_diff::*;
+pub use raster_ico::*;
+pub use raster_redline::*;
+pub use raster_render::*;
+pub use raster_tiles::*;
+
+// endregion: --- Modules
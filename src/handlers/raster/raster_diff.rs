@@ -0,0 +1,91 @@
+//! Perceptual image comparison (the YIQ color distance of pixelmatch), with a diff image of the differences.
+
+use crate::{Error, Result};
+use resvg::tiny_skia::{Pixmap, PremultipliedColorU8};
+
+/// The default per-pixel threshold (0 to 1) of `diff_images`: the color distance under which pixels match.
+pub const DEFAULT_PIXEL_THRESHOLD: f64 = 0.1;
+
+/// The maximum YIQ color distance (black vs white).
+const MAX_YIQ_DELTA: f64 = 35215.0;
+
+/// The comparison of two images of the same size.
+#[derive(Debug)]
+pub struct ImageDiff {
+	pub width: u32,
+	pub height: u32,
+	/// The number of pixels differing (color distance above the pixel threshold).
+	pub diff_pixels: usize,
+	/// The faded expected image with the differing pixels in red.
+	pub diff_image: Pixmap,
+}
+
+impl ImageDiff {
+	/// The ratio of differing pixels (0 to 1).
+	pub fn diff_ratio(&self) -> f64 {
+		let total = self.width as f64 * self.height as f64;
+		if total == 0.0 { 0.0 } else { self.diff_pixels as f64 / total }
+	}
+}
+
+/// Compares two images pixel by pixel with a perceptual color distance (YIQ, colors blended on white).
+/// Pixels differ when their distance is above `pixel_threshold` (0 to 1) of the maximum distance.
+/// Fails when the images do not have the same size.
+pub fn diff_images(expected: &Pixmap, actual: &Pixmap, pixel_threshold: f64) -> Result<ImageDiff> {
+	if expected.width() != actual.width() || expected.height() != actual.height() {
+		return Err(Error::custom(format!(
+			"Image sizes differ: expected {}x{}, actual {}x{}",
+			expected.width(),
+			expected.height(),
+			actual.width(),
+			actual.height()
+		)));
+	}
+
+	let max_delta = MAX_YIQ_DELTA * pixel_threshold * pixel_threshold;
+	let mut diff_image =
+		Pixmap::new(expected.width(), expected.height()).ok_or_else(|| Error::custom("Cannot compare empty images"))?;
+
+	let mut diff_pixels = 0;
+	let pixels = expected.pixels().iter().zip(actual.pixels()).zip(diff_image.pixels_mut());
+	for ((expected_pixel, actual_pixel), diff_pixel) in pixels {
+		let expected_rgb = blend_on_white(*expected_pixel);
+		let delta = yiq_delta(expected_rgb, blend_on_white(*actual_pixel));
+		*diff_pixel = if delta > max_delta {
+			diff_pixels += 1;
+			opaque(255, 0, 0)
+		} else {
+			// The expected pixel, faded, for context
+			let gray = (255.0 + (yiq_y(expected_rgb) - 255.0) * 0.1) as u8;
+			opaque(gray, gray, gray)
+		};
+	}
+
+	Ok(ImageDiff { width: expected.width(), height: expected.height(), diff_pixels, diff_image })
+}
+
+/// Returns the RGB color of a pixel blended on a white background.
+fn blend_on_white(pixel: PremultipliedColorU8) -> [f64; 3] {
+	let color = pixel.demultiply();
+	let alpha = color.alpha() as f64 / 255.0;
+	[color.red(), color.green(), color.blue()].map(|c| 255.0 + (c as f64 - 255.0) * alpha)
+}
+
+fn yiq_y([r, g, b]: [f64; 3]) -> f64 {
+	r * 0.29889531 + g * 0.58662247 + b * 0.11448223
+}
+
+/// Returns the squared YIQ distance of two colors (0 to `MAX_YIQ_DELTA`).
+fn yiq_delta(a: [f64; 3], b: [f64; 3]) -> f64 {
+	let i = |[r, g, b]: [f64; 3]| r * 0.59597799 - g * 0.2741761 - b * 0.32180189;
+	let q = |[r, g, b]: [f64; 3]| r * 0.21147017 - g * 0.52261711 + b * 0.31114694;
+
+	let dy = yiq_y(a) - yiq_y(b);
+	let di = i(a) - i(b);
+	let dq = q(a) - q(b);
+	0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq
+}
+
+fn opaque(r: u8, g: u8, b: u8) -> PremultipliedColorU8 {
+	PremultipliedColorU8::from_rgba(r, g, b, 255).unwrap_or(PremultipliedColorU8::TRANSPARENT)
+}
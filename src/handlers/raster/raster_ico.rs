@@ -0,0 +1,60 @@
+//! The ICO files (e.g., `favicon.ico`): PNG compressed images of several sizes.
+
+use crate::{Error, Result};
+use resvg::tiny_skia::Pixmap;
+use simple_fs::{SPath, ensure_dir};
+
+/// The largest size of an ICO image, in px.
+const ICO_MAX_SIZE: u32 = 256;
+
+/// Writes the images (e.g., 16, 32, and 48 px) as an ICO file, PNG compressed (creating its parent directory).
+pub fn write_ico(images: &[Pixmap], ico_file: &SPath) -> Result<()> {
+	if images.is_empty() {
+		return Err(Error::custom(format!("No images to write to ICO '{ico_file}'")));
+	}
+
+	let pngs = images
+		.iter()
+		.map(|image| {
+			if image.width() > ICO_MAX_SIZE || image.height() > ICO_MAX_SIZE {
+				return Err(Error::custom(format!(
+					"Cannot write an image of {}x{} to ICO '{ico_file}' (max {ICO_MAX_SIZE}x{ICO_MAX_SIZE})",
+					image.width(),
+					image.height()
+				)));
+			}
+			image
+				.encode_png()
+				.map_err(|e| Error::custom(format!("Cannot encode the images of ICO '{ico_file}'. Cause: {e}")))
+		})
+		.collect::<Result<Vec<Vec<u8>>>>()?;
+
+	// -- The header, the directory (16 bytes per image), then the PNG data
+	let mut content: Vec<u8> = Vec::new();
+	content.extend_from_slice(&0u16.to_le_bytes());
+	content.extend_from_slice(&1u16.to_le_bytes()); // icon type
+	content.extend_from_slice(&(images.len() as u16).to_le_bytes());
+	let mut offset = 6 + 16 * images.len();
+	for (image, png) in images.iter().zip(&pngs) {
+		// A size of 0 is 256
+		content.push((image.width() % ICO_MAX_SIZE) as u8);
+		content.push((image.height() % ICO_MAX_SIZE) as u8);
+		content.extend_from_slice(&[0, 0]); // palette size, reserved
+		content.extend_from_slice(&1u16.to_le_bytes()); // color planes
+		content.extend_from_slice(&32u16.to_le_bytes()); // bits per pixel
+		content.extend_from_slice(&(png.len() as u32).to_le_bytes());
+		content.extend_from_slice(&(offset as u32).to_le_bytes());
+		offset += png.len();
+	}
+	for png in &pngs {
+		content.extend_from_slice(png);
+	}
+
+	if let Some(parent) = ico_file.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+	}
+	std::fs::write(ico_file.as_std_path(), content)
+		.map_err(|e| Error::custom(format!("Cannot write ICO '{ico_file}'. Cause: {e}")))?;
+
+	Ok(())
+}
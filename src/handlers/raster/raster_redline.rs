@@ -0,0 +1,54 @@
+//! Redline overlays for design reviews: a grid and a size label composited onto an image.
+
+use crate::handlers::raster::render_svg;
+use crate::{Error, Result};
+use quick_xml::escape::escape;
+use resvg::tiny_skia::{Pixmap, PixmapPaint, Transform};
+
+/// The color of the grid lines and of the label background.
+const REDLINE_COLOR: &str = "#ff0080";
+
+/// The opacity of the grid lines.
+const REDLINE_GRID_OPACITY: f64 = 0.35;
+
+/// The fonts of the label (the first installed one, the generic "sans-serif" being Arial for the rasterizer).
+const REDLINE_LABEL_FONTS: &str = "Helvetica, Arial, 'DejaVu Sans', sans-serif";
+
+/// The font size of the label, in px.
+const REDLINE_LABEL_FONT_SIZE: u32 = 12;
+
+/// Returns the image with grid lines every `grid` px and a label (e.g., "200 × 100") in its top left corner.
+/// The label is rendered with the system fonts (none drawn when there are no fonts).
+pub fn redline_image(image: &Pixmap, grid: u32, label: &str) -> Result<Pixmap> {
+	if grid == 0 {
+		return Err(Error::custom("Invalid redline grid of 0px"));
+	}
+	let (width, height) = (image.width(), image.height());
+
+	// -- The lines, on the pixel centers (so they are 1px wide and sharp)
+	let mut path = String::new();
+	for x in (grid..width).step_by(grid as usize) {
+		path.push_str(&format!("M{x}.5 0V{height}"));
+	}
+	for y in (grid..height).step_by(grid as usize) {
+		path.push_str(&format!("M0 {y}.5H{width}"));
+	}
+
+	// -- The label (its width estimated from the char count, the fonts are not measured)
+	let label_width = label.chars().count() as u32 * REDLINE_LABEL_FONT_SIZE * 6 / 10 + 8;
+	let label_height = REDLINE_LABEL_FONT_SIZE + 6;
+	let overlay = format!(
+		r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">
+<path d="{path}" fill="none" stroke="{REDLINE_COLOR}" stroke-opacity="{REDLINE_GRID_OPACITY}" stroke-width="1"/>
+<rect width="{label_width}" height="{label_height}" fill="{REDLINE_COLOR}"/>
+<text x="4" y="{baseline}" font-family="{REDLINE_LABEL_FONTS}" font-size="{REDLINE_LABEL_FONT_SIZE}" fill="#ffffff">{label}</text>
+</svg>"##,
+		baseline = REDLINE_LABEL_FONT_SIZE + 1,
+		label = escape(label),
+	);
+	let overlay = render_svg(&overlay, 1.0)?;
+
+	let mut redlined = image.clone();
+	redlined.draw_pixmap(0, 0, overlay.as_ref(), &PixmapPaint::default(), Transform::identity(), None);
+	Ok(redlined)
+}
@@ -0,0 +1,88 @@
+//! The native rasterizer (resvg): SVG rendering and PNG reading/writing, without sketchtool or a browser.
+
+use crate::support::colors::Rgba;
+use crate::{Error, Result};
+use resvg::tiny_skia::{Color, Pixmap, PixmapPaint, Transform};
+use resvg::usvg;
+use simple_fs::{SPath, ensure_dir};
+use std::sync::{Arc, OnceLock};
+
+/// Renders an SVG document to an image, at `scale` times its intrinsic size (width/height, or viewBox).
+/// Text is rendered with the system fonts (loaded once).
+pub fn render_svg(svg_content: &str, scale: f32) -> Result<Pixmap> {
+	let options = usvg::Options { fontdb: system_fonts(), ..Default::default() };
+
+	let tree = usvg::Tree::from_str(svg_content, &options).map_err(|e| format!("Invalid SVG. Cause: {e}"))?;
+
+	let size = tree.size();
+	let width = (size.width() * scale).ceil() as u32;
+	let height = (size.height() * scale).ceil() as u32;
+	let mut pixmap = Pixmap::new(width, height)
+		.ok_or_else(|| Error::custom(format!("Cannot render an SVG of size {width}x{height} (scale {scale})")))?;
+
+	resvg::render(&tree, Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+
+	Ok(pixmap)
+}
+
+/// Renders an SVG file (see `render_svg`).
+pub fn render_svg_file(svg_file: &SPath, scale: f32) -> Result<Pixmap> {
+	let content = simple_fs::read_to_string(svg_file.as_std_path()).map_err(Error::custom_from_err)?;
+	render_svg(&content, scale).map_err(|err| Error::custom(format!("Cannot render '{svg_file}'. Cause: {err}")))
+}
+
+/// Reads a PNG file.
+pub fn read_png(png_file: &SPath) -> Result<Pixmap> {
+	Pixmap::load_png(png_file.as_std_path())
+		.map_err(|e| Error::custom(format!("Cannot read PNG '{png_file}'. Cause: {e}")))
+}
+
+/// Writes an image as a PNG file (creating its parent directory).
+pub fn write_png(image: &Pixmap, png_file: &SPath) -> Result<()> {
+	if let Some(parent) = png_file.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+	}
+	image
+		.save_png(png_file.as_std_path())
+		.map_err(|e| Error::custom(format!("Cannot write PNG '{png_file}'. Cause: {e}")))
+}
+
+/// Returns the image with `padding` transparent pixels on each side.
+pub fn pad_image(image: &Pixmap, padding: u32) -> Result<Pixmap> {
+	let (width, height) = (image.width() + 2 * padding, image.height() + 2 * padding);
+	let mut padded = Pixmap::new(width, height)
+		.ok_or_else(|| Error::custom(format!("Cannot create an image of size {width}x{height}")))?;
+	padded.draw_pixmap(
+		padding as i32,
+		padding as i32,
+		image.as_ref(),
+		&PixmapPaint::default(),
+		Transform::identity(),
+		None,
+	);
+	Ok(padded)
+}
+
+/// Returns the image composited onto a solid color (e.g., a white background for the transparent pixels).
+pub fn fill_background(image: &Pixmap, color: Rgba) -> Result<Pixmap> {
+	let (width, height) = (image.width(), image.height());
+	let mut filled = Pixmap::new(width, height)
+		.ok_or_else(|| Error::custom(format!("Cannot create an image of size {width}x{height}")))?;
+	filled.fill(
+		Color::from_rgba(color.red as f32, color.green as f32, color.blue as f32, color.alpha as f32)
+			.unwrap_or(Color::WHITE),
+	);
+	filled.draw_pixmap(0, 0, image.as_ref(), &PixmapPaint::default(), Transform::identity(), None);
+	Ok(filled)
+}
+
+fn system_fonts() -> Arc<usvg::fontdb::Database> {
+	static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+	FONTS
+		.get_or_init(|| {
+			let mut fontdb = usvg::fontdb::Database::new();
+			fontdb.load_system_fonts();
+			Arc::new(fontdb)
+		})
+		.clone()
+}
@@ -0,0 +1,45 @@
+//! The slicing of large images into a grid of tiles (e.g., for the zoomable, map-like viewers).
+
+use crate::{Error, Result};
+use resvg::tiny_skia::{IntRect, Pixmap};
+
+/// A tile of an image, at its column and row of the grid.
+pub struct ImageTile {
+	pub column: u32,
+	pub row: u32,
+	/// The position of the tile in the image (including the overlap).
+	pub x: u32,
+	pub y: u32,
+	pub image: Pixmap,
+}
+
+/// Splits an image into a grid of `tile_size` px tiles, row by row (the last column and row may be smaller).
+/// Each tile extends `overlap` px into its neighbors (not beyond the image edges), as the Deep Zoom tiles do.
+pub fn tile_image(image: &Pixmap, tile_size: u32, overlap: u32) -> Result<Vec<ImageTile>> {
+	if tile_size == 0 {
+		return Err("The tile size must be greater than 0".into());
+	}
+
+	let (width, height) = (image.width(), image.height());
+	let columns = width.div_ceil(tile_size);
+	let rows = height.div_ceil(tile_size);
+
+	let mut tiles = Vec::new();
+	for row in 0..rows {
+		for column in 0..columns {
+			let x = (column * tile_size).saturating_sub(overlap);
+			let y = (row * tile_size).saturating_sub(overlap);
+			let right = ((column + 1) * tile_size + overlap).min(width);
+			let bottom = ((row + 1) * tile_size + overlap).min(height);
+
+			let tile = IntRect::from_xywh(x as i32, y as i32, right - x, bottom - y)
+				.and_then(|rect| image.clone_rect(rect))
+				.ok_or_else(|| {
+					Error::custom(format!("Cannot cut the tile {column},{row} of a {width}x{height} image"))
+				})?;
+			tiles.push(ImageTile { column, row, x, y, image: tile });
+		}
+	}
+
+	Ok(tiles)
+}
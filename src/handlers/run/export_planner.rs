@@ -0,0 +1,78 @@
+//! Resolves a job (defaults + per-artboard overrides) into the exports to run.
+
+use crate::handlers::config::JobConfig;
+use crate::handlers::sketch::{ExportOptions, ExportPlan};
+use serde::Serialize;
+
+/// One `export_artboards` call of a job.
+#[derive(Debug, Clone)]
+pub struct PlannedExport {
+	/// "default" for the job defaults, or "override:<glob>".
+	pub label: String,
+	pub globs: Vec<String>,
+	pub formats: Vec<String>,
+	/// Relative to the project directory (tokens not resolved yet).
+	pub output: String,
+	pub options: ExportOptions,
+}
+
+/// The resolved export plans of a job (see `plan_job`), for `run --plan`.
+#[derive(Debug, Serialize)]
+pub struct JobPlan {
+	pub config_file: String,
+	pub name: String,
+	pub exports: Vec<JobExportPlan>,
+}
+
+/// The resolved plan of a `PlannedExport`.
+#[derive(Debug, Serialize)]
+pub struct JobExportPlan {
+	/// Same as `PlannedExport::label`.
+	pub label: String,
+	pub plan: ExportPlan,
+}
+
+/// Resolves the exports of a job, layering each override over the job defaults:
+/// - The default export covers the job artboards, minus the ones of `replace` overrides
+/// - Each override exports the job artboards matching its glob, with its fields (others inherited)
+pub fn plan_job_exports(job: &JobConfig) -> Vec<PlannedExport> {
+	let defaults = job.export_options();
+
+	// -- Default export
+	let mut default_options = defaults.clone();
+	default_options
+		.exclude_globs
+		.extend(job.overrides.iter().filter(|o| o.replace).map(|o| o.glob.clone()));
+	let mut planned = vec![PlannedExport {
+		label: "default".to_string(),
+		globs: job.globs.clone(),
+		formats: job.formats.clone(),
+		output: job.output.clone(),
+		options: default_options,
+	}];
+
+	// -- Overrides (layered over the job defaults)
+	for artboard_override in &job.overrides {
+		let mut options = defaults.clone();
+		options.only_globs = vec![artboard_override.glob.clone()];
+		if let Some(scales) = &artboard_override.scales {
+			options.scales = scales.clone();
+		}
+		options.file_name = artboard_override.name.clone();
+		// The generated code is about the job sprite (the default export)
+		options.ts_out = None;
+		options.rust_out = None;
+		options.use_snippets_out = None;
+		options.usage_out = None;
+
+		planned.push(PlannedExport {
+			label: format!("override:{}", artboard_override.glob),
+			globs: job.globs.clone(),
+			formats: artboard_override.formats.clone().unwrap_or_else(|| job.formats.clone()),
+			output: artboard_override.output.clone().unwrap_or_else(|| job.output.clone()),
+			options,
+		});
+	}
+
+	planned
+}
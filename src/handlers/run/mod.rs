@@ -0,0 +1,17 @@
+// region:    --- Modules
+
+mod export_planner;
+mod pipeline_graph;
+mod run_jobs;
+mod run_pipeline;
+mod run_report;
+mod run_watch;
+
+pub use export_planner::*;
+pub use pipeline_graph::*;
+pub use run_jobs::*;
+pub use run_pipeline::*;
+pub use run_report::*;
+pub use run_watch::*;
+
+// endregion: --- Modules
@@ -0,0 +1,288 @@
+//! The dependency graph of the pipelines (`webtk pipeline graph`): the steps, their source inputs, and the steps
+//! feeding them (an input of a step overlapping an output of a previous step), rendered as Graphviz DOT or Mermaid.
+
+use crate::handlers::config::{JobConfig, PipelineStepConfig, ProjectConfig};
+use crate::handlers::run::{find_job, glob_base_dir, selected_pipelines};
+use crate::{Error, Result};
+
+/// The max chars of a command in a step label.
+const COMMAND_LABEL_MAX_CHARS: usize = 40;
+
+/// The output format of `format_pipeline_graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+	Dot,
+	Mermaid,
+}
+
+impl TryFrom<&str> for GraphFormat {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"dot" => Ok(Self::Dot),
+			"mermaid" => Ok(Self::Mermaid),
+			other => Err(Error::custom(format!("Invalid graph format '{other}'. Expected: dot, mermaid"))),
+		}
+	}
+}
+
+/// The graph of a pipeline.
+#[derive(Debug)]
+pub struct PipelineGraph {
+	pub config_file: String,
+	pub name: String,
+	/// In step order.
+	pub steps: Vec<GraphStep>,
+}
+
+#[derive(Debug)]
+pub struct GraphStep {
+	pub name: String,
+	/// "job: <name>" or "$ <command>".
+	pub label: String,
+	/// The job outputs, or the `outputs` of a command step.
+	pub outputs: Vec<String>,
+	/// The inputs not produced by a previous step (the files changed by hand).
+	pub sources: Vec<String>,
+	pub dependencies: Vec<GraphDependency>,
+}
+
+/// A previous step a step depends on.
+#[derive(Debug)]
+pub struct GraphDependency {
+	/// The index of the previous step.
+	pub step: usize,
+	/// The input of the step matching an output of the previous step, None for a step without inputs
+	/// (it runs when the previous step ran).
+	pub via: Option<String>,
+}
+
+/// Builds the graphs of the named pipelines of the projects (all when no names), in config order.
+pub fn build_pipeline_graphs(projects: &[ProjectConfig], pipeline_names: &[String]) -> Result<Vec<PipelineGraph>> {
+	let pipelines = if pipeline_names.is_empty() {
+		projects
+			.iter()
+			.flat_map(|project| project.config.pipelines.iter().map(move |pipeline| (project, pipeline)))
+			.collect()
+	} else {
+		selected_pipelines(projects, pipeline_names)?
+	};
+
+	let mut graphs = Vec::new();
+	for (project, pipeline) in pipelines {
+		let mut steps: Vec<GraphStep> = Vec::new();
+		for (idx, step) in pipeline.steps.iter().enumerate() {
+			let job = step.job.as_deref().map(|job_name| find_job(project, job_name)).transpose()?;
+			let mut inputs: Vec<String> = job.map(|job| job.sketch_file.clone()).into_iter().collect();
+			inputs.extend(step.inputs.iter().cloned());
+			let outputs = match job {
+				Some(job) => job_outputs(job),
+				None => step.outputs.clone(),
+			};
+
+			let mut dependencies = Vec::new();
+			let mut sources = Vec::new();
+			for input in &inputs {
+				let producers: Vec<usize> = steps
+					.iter()
+					.enumerate()
+					.filter(|(_, previous)| previous.outputs.iter().any(|output| paths_overlap(input, output)))
+					.map(|(previous_idx, _)| previous_idx)
+					.collect();
+				if producers.is_empty() {
+					sources.push(input.clone());
+				}
+				for producer in producers {
+					dependencies.push(GraphDependency { step: producer, via: Some(input.clone()) });
+				}
+			}
+			if inputs.is_empty() && idx > 0 {
+				dependencies.push(GraphDependency { step: idx - 1, via: None });
+			}
+
+			steps.push(GraphStep {
+				name: step.display_name(idx),
+				label: step_label(step),
+				outputs,
+				sources,
+				dependencies,
+			});
+		}
+		graphs.push(PipelineGraph { config_file: project.config_file.to_string(), name: pipeline.name.clone(), steps });
+	}
+
+	if graphs.is_empty() {
+		return Err(Error::custom("No pipelines in the config"));
+	}
+
+	Ok(graphs)
+}
+
+/// Renders the graphs: a cluster (DOT) or subgraph (Mermaid) of steps per pipeline, the source inputs as file
+/// nodes, and the dependencies as edges labeled with the input (dashed for a step without inputs).
+pub fn format_pipeline_graph(graphs: &[PipelineGraph], format: GraphFormat) -> String {
+	match format {
+		GraphFormat::Dot => format_dot(graphs),
+		GraphFormat::Mermaid => format_mermaid(graphs),
+	}
+}
+
+// region:    --- Formats
+
+fn format_dot(graphs: &[PipelineGraph]) -> String {
+	let mut lines = vec![
+		"digraph pipelines {".to_string(),
+		"\trankdir=LR;".to_string(),
+		"\tnode [fontname=\"Helvetica\", fontsize=11];".to_string(),
+	];
+
+	for (pipeline_idx, graph) in graphs.iter().enumerate() {
+		let step_id = |idx: usize| format!("p{pipeline_idx}s{idx}");
+		lines.push(format!("\tsubgraph cluster_p{pipeline_idx} {{"));
+		lines.push(format!("\t\tlabel=\"{}\";", dot_escape(&graph_label(graphs, graph))));
+		for (idx, step) in graph.steps.iter().enumerate() {
+			lines.push(format!(
+				"\t\t{} [shape=box, label=\"{}\\n{}\"];",
+				step_id(idx),
+				dot_escape(&step.name),
+				dot_escape(&step.label)
+			));
+		}
+		lines.push("\t}".to_string());
+
+		let mut source_idx = 0;
+		for (idx, step) in graph.steps.iter().enumerate() {
+			for source in &step.sources {
+				let source_id = format!("p{pipeline_idx}i{source_idx}");
+				source_idx += 1;
+				lines.push(format!("\t{source_id} [shape=note, label=\"{}\"];", dot_escape(source)));
+				lines.push(format!("\t{source_id} -> {};", step_id(idx)));
+			}
+			for dependency in &step.dependencies {
+				let attrs = match &dependency.via {
+					Some(via) => format!("label=\"{}\"", dot_escape(via)),
+					None => "style=dashed, label=\"after\"".to_string(),
+				};
+				lines.push(format!("\t{} -> {} [{attrs}];", step_id(dependency.step), step_id(idx)));
+			}
+		}
+	}
+
+	lines.push("}".to_string());
+	format!("{}\n", lines.join("\n"))
+}
+
+fn format_mermaid(graphs: &[PipelineGraph]) -> String {
+	let mut lines = vec!["flowchart LR".to_string()];
+
+	for (pipeline_idx, graph) in graphs.iter().enumerate() {
+		let step_id = |idx: usize| format!("p{pipeline_idx}s{idx}");
+		lines.push(format!("\tsubgraph p{pipeline_idx}[\"{}\"]", mermaid_escape(&graph_label(graphs, graph))));
+		for (idx, step) in graph.steps.iter().enumerate() {
+			lines.push(format!(
+				"\t\t{}[\"{}<br/>{}\"]",
+				step_id(idx),
+				mermaid_escape(&step.name),
+				mermaid_escape(&step.label)
+			));
+		}
+		lines.push("\tend".to_string());
+
+		let mut source_idx = 0;
+		for (idx, step) in graph.steps.iter().enumerate() {
+			for source in &step.sources {
+				let source_id = format!("p{pipeline_idx}i{source_idx}");
+				source_idx += 1;
+				lines.push(format!("\t{source_id}[/\"{}\"/] --> {}", mermaid_escape(source), step_id(idx)));
+			}
+			for dependency in &step.dependencies {
+				let edge = match &dependency.via {
+					Some(via) => format!("-->|\"{}\"|", mermaid_escape(via)),
+					None => "-.->|after|".to_string(),
+				};
+				lines.push(format!("\t{} {edge} {}", step_id(dependency.step), step_id(idx)));
+			}
+		}
+	}
+
+	format!("{}\n", lines.join("\n"))
+}
+
+/// The pipeline name, with its config file when the graphs are of several configs (`--all`).
+fn graph_label(graphs: &[PipelineGraph], graph: &PipelineGraph) -> String {
+	if graphs.iter().all(|other| other.config_file == graph.config_file) {
+		graph.name.clone()
+	} else {
+		format!("{} ({})", graph.name, graph.config_file)
+	}
+}
+
+fn dot_escape(value: &str) -> String {
+	value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_escape(value: &str) -> String {
+	value.replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;")
+}
+
+// endregion: --- Formats
+
+// region:    --- Support
+
+/// The outputs of a job: its output, the override outputs, and the generated files (codegen, snippets, URLs).
+fn job_outputs(job: &JobConfig) -> Vec<String> {
+	let mut outputs = vec![job.output.clone()];
+	outputs.extend(job.overrides.iter().filter_map(|o| o.output.clone()));
+	outputs.extend(
+		[
+			&job.ts_out,
+			&job.rust_out,
+			&job.use_snippets_out,
+			&job.usage_out,
+			&job.email_snippet_out,
+			&job.cdn_ts_out,
+		]
+		.into_iter()
+		.flatten()
+		.cloned(),
+	);
+	outputs
+}
+
+fn step_label(step: &PipelineStepConfig) -> String {
+	match (&step.job, &step.command) {
+		(Some(job), _) => format!("job: {job}"),
+		(None, Some(command)) if command.chars().count() > COMMAND_LABEL_MAX_CHARS => {
+			let truncated: String = command.chars().take(COMMAND_LABEL_MAX_CHARS).collect();
+			format!("$ {truncated}...")
+		}
+		(None, Some(command)) => format!("$ {command}"),
+		(None, None) => String::new(),
+	}
+}
+
+/// Whether an input (a path or glob) may read an output (a file or directory, with tokens): one of their base paths
+/// contains the other (e.g., "dist/icons/**/*.svg" and "dist/icons").
+fn paths_overlap(input: &str, output: &str) -> bool {
+	let input = normalize_path(input);
+	let input_base = if input.contains(['*', '?', '[', '{']) { glob_base_dir(&input) } else { input };
+	// The output path before its first token segment (e.g., "dist/{date}/icons" -> "dist")
+	let output_base = normalize_path(output)
+		.split('/')
+		.take_while(|segment| !segment.contains('{'))
+		.collect::<Vec<_>>()
+		.join("/");
+
+	is_path_prefix(&input_base, &output_base) || is_path_prefix(&output_base, &input_base)
+}
+
+fn normalize_path(path: &str) -> String {
+	path.trim_start_matches("./").trim_end_matches('/').to_string()
+}
+
+fn is_path_prefix(prefix: &str, path: &str) -> bool {
+	prefix.is_empty() || path == prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+}
+
+// endregion: --- Support
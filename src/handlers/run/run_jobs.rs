@@ -0,0 +1,217 @@
+use crate::handlers::config::{JobConfig, ProjectConfig};
+use crate::handlers::run::{JobExportPlan, JobPlan, JobRunReport, ProjectRunReport, RunReport, plan_job_exports};
+use crate::handlers::sketch::{self, ExportReport};
+use crate::support::threads::run_concurrently;
+use crate::support::{files, profile};
+use crate::{Error, Result};
+use simple_fs::SPath;
+use std::time::Instant;
+
+/// The max number of jobs running at once by default (each sketchtool export is CPU and memory heavy).
+const DEFAULT_MAX_PARALLEL_JOBS: usize = 4;
+
+/// A callback receiving each job report as the job ends (see `RunOptions::on_job_end`).
+pub type JobEndFn<'a> = dyn Fn(&ProjectConfig, &JobRunReport) + Sync + 'a;
+
+/// How the jobs of a run are executed.
+#[derive(Clone, Copy)]
+pub struct RunOptions<'a> {
+	/// The max number of jobs running at once (1 runs them one at a time, in order).
+	pub parallel: usize,
+	/// Called as each job ends, from the thread running it (in completion order), e.g., for the progress logs.
+	pub on_job_end: Option<&'a JobEndFn<'a>>,
+	/// Reuse the converted symbols of the unchanged artboards of the previous runs (see `ExportOptions::symbols_cache`).
+	pub symbols_cache: bool,
+}
+
+impl Default for RunOptions<'_> {
+	fn default() -> Self {
+		Self { parallel: default_parallel_jobs(), on_job_end: None, symbols_cache: false }
+	}
+}
+
+/// Returns the default max number of jobs running at once: the CPU count, up to `DEFAULT_MAX_PARALLEL_JOBS`.
+pub fn default_parallel_jobs() -> usize {
+	std::thread::available_parallelism()
+		.map(|count| count.get())
+		.unwrap_or(1)
+		.min(DEFAULT_MAX_PARALLEL_JOBS)
+}
+
+/// Runs the jobs of the projects, up to `options.parallel` at once, with the paths resolved against each project
+/// directory. A failing job does not stop the others; failures are recorded in the returned report,
+/// which lists the jobs in config order whatever their completion order.
+/// `job_names` filters the jobs to run (all when empty).
+///
+/// Note: Each job has its own raw export cache directory (`ExportOptions::cache_suffix`), and the jobs run from
+///       the common directory of the projects (current dir), to which the cache directory cleanup is restricted.
+pub fn run_projects(projects: &[ProjectConfig], job_names: &[String], options: &RunOptions) -> Result<RunReport> {
+	run_selected_jobs(projects, options, |_, _, name| job_names.is_empty() || job_names.iter().any(|n| n == name))
+}
+
+/// Runs the jobs of the projects selected by `is_selected(project, job, job_name)` (see `run_projects`).
+/// Projects without selected jobs are still reported (with no jobs).
+pub fn run_selected_jobs(
+	projects: &[ProjectConfig],
+	options: &RunOptions,
+	is_selected: impl Fn(&ProjectConfig, &JobConfig, &str) -> bool,
+) -> Result<RunReport> {
+	let Some(run_dir) = common_base_dir(projects) else {
+		return Ok(RunReport::default());
+	};
+
+	// The selected jobs, in config order: (project index, job, job name)
+	let mut selected: Vec<(usize, &JobConfig, String)> = Vec::new();
+	for (project_idx, project) in projects.iter().enumerate() {
+		for (idx, job) in project.config.jobs.iter().enumerate() {
+			let name = job.display_name(idx);
+			if is_selected(project, job, &name) {
+				selected.push((project_idx, job, name));
+			}
+		}
+	}
+
+	let job_reports = in_dir(&run_dir, || {
+		Ok(run_concurrently(&selected, options.parallel, |(project_idx, job, name)| {
+			let project = &projects[*project_idx];
+			let _span = profile::span("job", name.as_str()).arg("config", project.config_file.as_str());
+			let started = Instant::now();
+			let (report, error) = match run_job(project, job, name, options) {
+				Ok(report) => (Some(report), None),
+				Err(err) => (None, Some(err.to_string())),
+			};
+			let job_report =
+				JobRunReport { name: name.clone(), duration_ms: started.elapsed().as_millis() as u64, report, error };
+			if let Some(on_job_end) = options.on_job_end {
+				on_job_end(project, &job_report);
+			}
+			job_report
+		}))
+	})?;
+
+	let mut report = RunReport {
+		projects: projects
+			.iter()
+			.map(|project| ProjectRunReport { config_file: project.config_file.to_string(), jobs: Vec::new() })
+			.collect(),
+	};
+	for ((project_idx, _, _), job_report) in selected.iter().zip(job_reports) {
+		report.projects[*project_idx].jobs.push(job_report);
+	}
+
+	Ok(report)
+}
+
+/// Plans the jobs of the projects (see `plan_job`), without exporting.
+/// `job_names` filters the jobs (all when empty). Fails on the first job that cannot be planned.
+pub fn plan_projects(projects: &[ProjectConfig], job_names: &[String]) -> Result<Vec<JobPlan>> {
+	let mut job_plans = Vec::new();
+
+	for project in projects {
+		for (idx, job) in project.config.jobs.iter().enumerate() {
+			let name = job.display_name(idx);
+			if !job_names.is_empty() && !job_names.contains(&name) {
+				continue;
+			}
+
+			let exports = in_dir(&project.base_dir, || plan_job(project, job, &name)).map_err(|err| {
+				Error::custom(format!("Cannot plan job '{name}' of '{}'. Cause: {err}", project.config_file))
+			})?;
+			job_plans.push(JobPlan { config_file: project.config_file.to_string(), name, exports });
+		}
+	}
+
+	Ok(job_plans)
+}
+
+/// Resolves the export plans of a job (its default export and its overrides, see `plan_job_exports`),
+/// with the raw export cache directory of the job (e.g., ".cache-raw-export-icons" for the job "icons").
+pub fn plan_job(project: &ProjectConfig, job: &JobConfig, name: &str) -> Result<Vec<JobExportPlan>> {
+	if job.formats.is_empty() {
+		return Err(Error::custom("Job has no formats"));
+	}
+
+	let sketch_file = project.resolve_path(&job.sketch_file);
+	let mut exports = Vec::new();
+
+	for mut planned in plan_job_exports(job) {
+		let output = project.resolve_path(&planned.output);
+		let options = &mut planned.options;
+		for path in [
+			&mut options.ts_out,
+			&mut options.rust_out,
+			&mut options.use_snippets_out,
+			&mut options.usage_out,
+			&mut options.paths_ts_out,
+			&mut options.template_dir,
+			&mut options.extract_images,
+		]
+		.into_iter()
+		.flatten()
+		{
+			*path = project.resolve_path(path.as_str()).to_string();
+		}
+		planned.options.cache_suffix = Some(files::sanitize_file_name(name));
+		let glob_refs: Vec<&str> = planned.globs.iter().map(|s| s.as_str()).collect();
+		let glob_arg = if glob_refs.is_empty() { None } else { Some(glob_refs.as_slice()) };
+		let format_refs: Vec<&str> = planned.formats.iter().map(|s| s.as_str()).collect();
+
+		let plan = sketch::plan_export(&sketch_file, glob_arg, &format_refs, &output, &planned.options)
+			.map_err(|err| Error::custom(format!("{} export failed. Cause: {err}", planned.label)))?;
+		exports.push(JobExportPlan { label: planned.label, plan });
+	}
+
+	Ok(exports)
+}
+
+/// Runs one export job of a project (the plans of `plan_job`, executed in order).
+/// The reports of the planned exports are merged (duplicate warnings and duplicate sets removed).
+pub fn run_job(project: &ProjectConfig, job: &JobConfig, name: &str, options: &RunOptions) -> Result<ExportReport> {
+	let mut report = ExportReport::default();
+
+	for mut export in plan_job(project, job, name)? {
+		export.plan.options.symbols_cache = options.symbols_cache;
+		let planned_report = sketch::execute_export_plan(&export.plan)
+			.map_err(|err| Error::custom(format!("{} export failed. Cause: {err}", export.label)))?;
+
+		report.artboard_count = report.artboard_count.max(planned_report.artboard_count);
+		report.exported_files.extend(planned_report.exported_files);
+		for warning in planned_report.warnings {
+			let is_duplicate = report
+				.warnings
+				.iter()
+				.any(|w| w.code == warning.code && w.artboard == warning.artboard && w.message == warning.message);
+			if !is_duplicate {
+				report.warnings.push(warning);
+			}
+		}
+		for names in planned_report.duplicates {
+			if !report.duplicates.contains(&names) {
+				report.duplicates.push(names);
+			}
+		}
+	}
+
+	Ok(report)
+}
+
+/// Runs `f` with `dir` as the current directory (restored afterwards).
+pub(super) fn in_dir<T>(dir: &SPath, f: impl FnOnce() -> Result<T>) -> Result<T> {
+	let original_dir = std::env::current_dir().map_err(Error::custom_from_err)?;
+	std::env::set_current_dir(dir.as_std_path())
+		.map_err(|e| format!("Cannot change to project directory '{dir}'. Cause: {e}"))?;
+	let res = f();
+	std::env::set_current_dir(&original_dir).map_err(Error::custom_from_err)?;
+	res
+}
+
+/// Returns the deepest directory containing the directories of all the projects (None for no projects).
+fn common_base_dir(projects: &[ProjectConfig]) -> Option<SPath> {
+	let mut common = projects.first()?.base_dir.clone();
+	for project in projects.iter().skip(1) {
+		while !project.base_dir.as_std_path().starts_with(common.as_std_path()) {
+			common = common.parent()?;
+		}
+	}
+	Some(common)
+}
@@ -0,0 +1,355 @@
+//! The pipelines of `run --pipeline`: the steps of a `[[pipelines]]` (jobs and shell commands) run in order, each
+//! only when the fingerprint of its inputs changed since its last run (the step cache).
+
+use crate::handlers::config::{JobConfig, PipelineConfig, PipelineStepConfig, ProjectConfig};
+use crate::handlers::run::{RunOptions, in_dir, run_job};
+use crate::handlers::sketch::ExportReport;
+use crate::support::hashes;
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::{ListOptions, SEventKind, SPath};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The max lines of the output of a failed command kept in its error.
+const COMMAND_ERROR_MAX_LINES: usize = 20;
+
+/// The fingerprints of the inputs of the steps at their last run, by step.
+/// A step whose fingerprint is unchanged is not run again: cached, or failed with the same error.
+#[derive(Debug, Default)]
+pub struct PipelineCache {
+	fingerprints: HashMap<String, String>,
+	/// The fingerprint and error of the failed steps.
+	failures: HashMap<String, (String, String)>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PipelineRunReport {
+	pub config_file: String,
+	pub name: String,
+	/// In step order.
+	pub steps: Vec<PipelineStepReport>,
+	/// Whether a step ran (successfully or not), false when all were cached or failed on unchanged inputs.
+	#[serde(skip)]
+	pub has_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PipelineStepReport {
+	pub name: String,
+	pub status: StepStatus,
+	pub duration_ms: u64,
+	/// The export report of a job step that ran.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub report: Option<ExportReport>,
+	/// The error message of a failed step.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+	Ran,
+	/// The inputs did not change since the last run.
+	Cached,
+	Failed,
+	/// Not run, a previous step failed.
+	Skipped,
+}
+
+impl PipelineRunReport {
+	pub fn count(&self, status: StepStatus) -> usize {
+		self.steps.iter().filter(|step| step.status == status).count()
+	}
+}
+
+/// Runs the named pipelines of the projects (in config order), with the step cache of the previous runs.
+/// A failed step stops its pipeline (the next steps are skipped), and runs again once its inputs changed.
+/// Fails when a name matches no pipeline.
+pub fn run_pipelines(
+	projects: &[ProjectConfig],
+	pipeline_names: &[String],
+	cache: &mut PipelineCache,
+	options: &RunOptions,
+) -> Result<Vec<PipelineRunReport>> {
+	let selected = selected_pipelines(projects, pipeline_names)?;
+
+	Ok(selected
+		.into_iter()
+		.map(|(project, pipeline)| run_pipeline(project, pipeline, cache, options))
+		.collect())
+}
+
+/// Runs the pipelines (see `run_pipelines`), then runs them again on each batch of file changes in the directories
+/// of their inputs. `on_run` is called with the reports of the runs in which a step ran or failed (all steps cached,
+/// e.g., on the changes written by the steps themselves, is not reported). Runs until the process is interrupted.
+pub fn watch_pipelines(
+	projects: &[ProjectConfig],
+	pipeline_names: &[String],
+	options: &RunOptions,
+	mut on_run: impl FnMut(&[PipelineRunReport]),
+) -> Result<()> {
+	let options = &RunOptions { symbols_cache: true, ..*options };
+	let selected = selected_pipelines(projects, pipeline_names)?;
+
+	let dirs = watched_dirs(&selected);
+	let watchers = dirs
+		.iter()
+		.map(|dir| simple_fs::watch(dir).map_err(|e| format!("Cannot watch '{}'. Cause: {e}", dir.display())))
+		.collect::<core::result::Result<Vec<_>, _>>()?;
+
+	let mut cache = PipelineCache::default();
+	on_run(&run_pipelines(projects, pipeline_names, &mut cache, options)?);
+
+	loop {
+		let mut has_changes = false;
+		for watcher in &watchers {
+			while let Ok(events) = watcher.rx.try_recv() {
+				has_changes |= events.iter().any(|event| event.skind != SEventKind::Other);
+			}
+		}
+		if !has_changes {
+			std::thread::sleep(WATCH_POLL_INTERVAL);
+			continue;
+		}
+
+		let reports = run_pipelines(projects, pipeline_names, &mut cache, options)?;
+		if reports.iter().any(|report| report.has_run) {
+			on_run(&reports);
+		}
+	}
+}
+
+fn run_pipeline(
+	project: &ProjectConfig,
+	pipeline: &PipelineConfig,
+	cache: &mut PipelineCache,
+	options: &RunOptions,
+) -> PipelineRunReport {
+	let mut report = PipelineRunReport {
+		config_file: project.config_file.to_string(),
+		name: pipeline.name.clone(),
+		steps: Vec::new(),
+		has_run: false,
+	};
+	let mut has_failed = false;
+
+	for (idx, step) in pipeline.steps.iter().enumerate() {
+		let name = step.display_name(idx);
+		let key = format!("{}#{}#{name}", project.config_file, pipeline.name);
+		let mut step_report =
+			PipelineStepReport { name, status: StepStatus::Skipped, duration_ms: 0, report: None, error: None };
+		if has_failed {
+			report.steps.push(step_report);
+			continue;
+		}
+
+		let started = Instant::now();
+		let fingerprint = match step_fingerprint(project, step) {
+			// A step without inputs follows the previous steps
+			Ok(None) if report.has_run => None,
+			Ok(fingerprint) => Some(fingerprint.unwrap_or_default()),
+			Err(err) => {
+				step_report.status = StepStatus::Failed;
+				step_report.error = Some(err.to_string());
+				report.steps.push(step_report);
+				has_failed = true;
+				continue;
+			}
+		};
+
+		if let Some(fingerprint) = &fingerprint {
+			if cache.fingerprints.get(&key) == Some(fingerprint) {
+				step_report.status = StepStatus::Cached;
+				report.steps.push(step_report);
+				continue;
+			}
+			if let Some((_, error)) = cache.failures.get(&key).filter(|(failed, _)| failed == fingerprint) {
+				step_report.status = StepStatus::Failed;
+				step_report.error = Some(error.clone());
+				report.steps.push(step_report);
+				has_failed = true;
+				continue;
+			}
+		}
+
+		report.has_run = true;
+		let result = run_step(project, step, options);
+		step_report.duration_ms = started.elapsed().as_millis() as u64;
+		match result {
+			Ok(export_report) => {
+				step_report.status = StepStatus::Ran;
+				step_report.report = export_report;
+				// After the run, for the steps rewriting their inputs (e.g., an in-place optimizer)
+				let fingerprint = step_fingerprint(project, step).ok().flatten().unwrap_or_default();
+				cache.fingerprints.insert(key.clone(), fingerprint);
+				cache.failures.remove(&key);
+			}
+			Err(err) => {
+				step_report.status = StepStatus::Failed;
+				step_report.error = Some(err.to_string());
+				cache.fingerprints.remove(&key);
+				cache.failures.insert(key, (fingerprint.unwrap_or_default(), err.to_string()));
+				has_failed = true;
+			}
+		}
+		report.steps.push(step_report);
+	}
+
+	report
+}
+
+/// Runs a step: its job (returning the export report), or its shell command from the config directory.
+fn run_step(project: &ProjectConfig, step: &PipelineStepConfig, options: &RunOptions) -> Result<Option<ExportReport>> {
+	if let Some(job_name) = &step.job {
+		let job = find_job(project, job_name)?;
+		return in_dir(&project.base_dir, || run_job(project, job, job_name, options)).map(Some);
+	}
+
+	let command = step.command.as_deref().unwrap_or_default();
+	let output = shell_command(command)
+		.current_dir(project.base_dir.as_std_path())
+		.output()
+		.map_err(|e| Error::custom(format!("Cannot run command '{command}'. Cause: {e}")))?;
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		let text = if stderr.trim().is_empty() { stdout } else { stderr };
+		let lines: Vec<&str> = text.trim().lines().collect();
+		let tail = lines[lines.len().saturating_sub(COMMAND_ERROR_MAX_LINES)..].join("\n");
+		return Err(Error::custom(format!("Command '{command}' failed ({}):\n{tail}", output.status)));
+	}
+
+	Ok(None)
+}
+
+/// Returns the fingerprint of the input files of a step (its job Sketch file and its `inputs` globs), from their
+/// paths and contents. None for a step without inputs.
+fn step_fingerprint(project: &ProjectConfig, step: &PipelineStepConfig) -> Result<Option<String>> {
+	let mut files: Vec<SPath> = Vec::new();
+	if let Some(job_name) = &step.job {
+		files.push(project.resolve_path(&find_job(project, job_name)?.sketch_file));
+	}
+	if !step.inputs.is_empty() {
+		let globs: Vec<&str> = step.inputs.iter().map(String::as_str).collect();
+		let list_options = ListOptions::from_relative_glob(true);
+		let inputs = simple_fs::list_files(project.base_dir.as_std_path(), Some(&globs), Some(list_options))
+			.map_err(Error::custom_from_err)?;
+		files.extend(inputs);
+	}
+	if files.is_empty() && step.job.is_none() {
+		return Ok(None);
+	}
+
+	files.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+	let mut content = String::new();
+	for file in files {
+		// A missing file (e.g., a Sketch file being replaced) is part of the fingerprint
+		let hash = std::fs::read(file.as_std_path())
+			.map(hashes::fnv1a_64_hex)
+			.unwrap_or_else(|_| "missing".to_string());
+		content.push_str(&format!("{file}\t{hash}\n"));
+	}
+
+	Ok(Some(hashes::fnv1a_64_hex(content)))
+}
+
+// region:    --- Support
+
+/// The pipelines of the projects matching the names, in config order.
+pub(super) fn selected_pipelines<'a>(
+	projects: &'a [ProjectConfig],
+	pipeline_names: &[String],
+) -> Result<Vec<(&'a ProjectConfig, &'a PipelineConfig)>> {
+	let selected: Vec<(&ProjectConfig, &PipelineConfig)> = projects
+		.iter()
+		.flat_map(|project| project.config.pipelines.iter().map(move |pipeline| (project, pipeline)))
+		.filter(|(_, pipeline)| pipeline_names.contains(&pipeline.name))
+		.collect();
+
+	if let Some(name) = pipeline_names
+		.iter()
+		.find(|name| !selected.iter().any(|(_, p)| &&p.name == name))
+	{
+		return Err(Error::custom(format!("Unknown pipeline '{name}'")));
+	}
+
+	Ok(selected)
+}
+
+pub(super) fn find_job<'a>(project: &'a ProjectConfig, job_name: &str) -> Result<&'a JobConfig> {
+	project
+		.config
+		.jobs
+		.iter()
+		.enumerate()
+		.find(|(idx, job)| job.display_name(*idx) == job_name)
+		.map(|(_, job)| job)
+		.ok_or_else(|| Error::custom(format!("Unknown job '{job_name}' in '{}'", project.config_file)))
+}
+
+/// The directories to watch: the base directory of the input globs (their path before the first glob segment,
+/// e.g., "dist/icons" for "dist/icons/**/*.svg") and the directories of the job Sketch files, the nested ones
+/// removed (the watches are recursive).
+fn watched_dirs(selected: &[(&ProjectConfig, &PipelineConfig)]) -> Vec<PathBuf> {
+	let mut dirs: Vec<PathBuf> = Vec::new();
+
+	for (project, pipeline) in selected {
+		for step in &pipeline.steps {
+			let job_file = step
+				.job
+				.as_deref()
+				.and_then(|job_name| find_job(project, job_name).ok())
+				.and_then(|job| project.resolve_path(&job.sketch_file).parent());
+			let input_dirs = step.inputs.iter().map(|glob| project.base_dir.join(glob_base_dir(glob)));
+			for dir in job_file.into_iter().chain(input_dirs) {
+				if let Some(dir) = existing_dir(dir.as_std_path()) {
+					dirs.push(dir);
+				}
+			}
+		}
+	}
+
+	dirs.sort();
+	dirs.dedup();
+	let all_dirs = dirs.clone();
+	dirs.retain(|dir| !all_dirs.iter().any(|other| other != dir && dir.starts_with(other)));
+	dirs
+}
+
+/// Returns the path of a glob before its first segment with a glob character (e.g., "dist/icons/**/*.svg" -> "dist/icons").
+pub(super) fn glob_base_dir(glob: &str) -> String {
+	let mut segments: Vec<&str> = glob.split('/').collect();
+	// The last segment is a file name or a pattern
+	segments.pop();
+	segments
+		.into_iter()
+		.take_while(|segment| !segment.contains(['*', '?', '[', '{']))
+		.collect::<Vec<_>>()
+		.join("/")
+}
+
+/// Returns the canonical path of a directory, or of its nearest existing ancestor (a not yet created output).
+fn existing_dir(dir: &Path) -> Option<PathBuf> {
+	dir.ancestors()
+		.find_map(|dir| dir.canonicalize().ok().filter(|dir| dir.is_dir()))
+}
+
+fn shell_command(command: &str) -> Command {
+	if cfg!(windows) {
+		let mut shell = Command::new("cmd");
+		shell.args(["/C", command]);
+		shell
+	} else {
+		let mut shell = Command::new("sh");
+		shell.args(["-c", command]);
+		shell
+	}
+}
+
+// endregion: --- Support
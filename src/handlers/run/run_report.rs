@@ -0,0 +1,71 @@
+use crate::Result;
+use crate::handlers::sketch::ExportReport;
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The aggregated report of a `run` (one or more projects).
+#[derive(Debug, Default, Serialize)]
+pub struct RunReport {
+	pub projects: Vec<ProjectRunReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectRunReport {
+	pub config_file: String,
+	pub jobs: Vec<JobRunReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobRunReport {
+	pub name: String,
+	pub duration_ms: u64,
+	/// The export report, when the job succeeded.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub report: Option<ExportReport>,
+	/// The error message, when the job failed.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+impl RunReport {
+	pub fn jobs(&self) -> impl Iterator<Item = &JobRunReport> {
+		self.projects.iter().flat_map(|p| p.jobs.iter())
+	}
+
+	pub fn failed_count(&self) -> usize {
+		self.jobs().filter(|job| job.error.is_some()).count()
+	}
+
+	pub fn warning_count(&self) -> usize {
+		self.jobs()
+			.filter_map(|job| job.report.as_ref())
+			.map(|r| r.warnings.len())
+			.sum()
+	}
+
+	pub fn duplicate_count(&self) -> usize {
+		self.jobs()
+			.filter_map(|job| job.report.as_ref())
+			.map(|r| r.duplicates.len())
+			.sum()
+	}
+
+	pub fn exported_files(&self) -> Vec<String> {
+		self.jobs()
+			.filter_map(|job| job.report.as_ref())
+			.flat_map(|r| r.exported_files.iter().cloned())
+			.collect()
+	}
+
+	/// Writes the report as JSON (e.g., for CI).
+	pub fn write(&self, report_file: &SPath) -> Result<()> {
+		if let Some(parent) = report_file.parent() {
+			simple_fs::ensure_dir(parent.as_std_path())
+				.map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+		}
+		let content = serde_json::to_string_pretty(self)?;
+		std::fs::write(report_file.as_std_path(), format!("{content}\n"))
+			.map_err(|e| format!("Failed to write run report '{}': {e}", report_file))?;
+		Ok(())
+	}
+}
@@ -0,0 +1,112 @@
+//! Watch mode of `run`: re-runs the jobs whose Sketch file was saved.
+
+use crate::handlers::config::ProjectConfig;
+use crate::handlers::run::{RunOptions, RunReport, run_projects, run_selected_jobs};
+use crate::{Error, Result};
+use simple_fs::{SEventKind, SPath};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Runs the jobs of the projects (see `run_projects`), then re-runs the jobs of each Sketch file when it is saved.
+/// `on_run` is called with the report of each run and the changed Sketch files (none for the first run).
+/// Runs until the process is interrupted. The runs reuse the converted symbols of the unchanged artboards
+/// (`RunOptions::symbols_cache`), so a sprite rebuild only converts the changed ones.
+///
+/// Note: The directories of the Sketch files are watched, not the files, because Sketch saves by replacing the file.
+pub fn watch_projects(
+	projects: &[ProjectConfig],
+	job_names: &[String],
+	options: &RunOptions,
+	mut on_run: impl FnMut(&RunReport, &[SPath]),
+) -> Result<()> {
+	let options = &RunOptions { symbols_cache: true, ..*options };
+	let sketch_files = watched_sketch_files(projects, job_names);
+	if sketch_files.is_empty() {
+		return Err(Error::custom("No jobs to watch"));
+	}
+
+	let mut dirs: Vec<PathBuf> = sketch_files
+		.iter()
+		.filter_map(|file| file.parent().map(Path::to_path_buf))
+		.collect();
+	dirs.sort();
+	dirs.dedup();
+	let watchers = dirs
+		.iter()
+		.map(|dir| simple_fs::watch(dir).map_err(|e| format!("Cannot watch '{}'. Cause: {e}", dir.display())))
+		.collect::<core::result::Result<Vec<_>, _>>()?;
+
+	on_run(&run_projects(projects, job_names, options)?, &[]);
+
+	loop {
+		let mut changed: Vec<PathBuf> = Vec::new();
+		for watcher in &watchers {
+			while let Ok(events) = watcher.rx.try_recv() {
+				for event in events {
+					if !matches!(event.skind, SEventKind::Create | SEventKind::Modify) {
+						continue;
+					}
+					let path = canonical_path(event.spath.as_std_path());
+					if sketch_files.contains(&path) && !changed.contains(&path) {
+						changed.push(path);
+					}
+				}
+			}
+		}
+
+		if changed.is_empty() {
+			std::thread::sleep(WATCH_POLL_INTERVAL);
+			continue;
+		}
+
+		let report = run_selected_jobs(projects, options, |project, job, name| {
+			is_job_selected(job_names, name)
+				&& changed.contains(&canonical_path(project.resolve_path(&job.sketch_file).as_std_path()))
+		})?;
+		let changed_files: Vec<SPath> = changed
+			.into_iter()
+			.filter_map(|path| SPath::from_std_path_buf(path).ok())
+			.collect();
+		on_run(&report, &changed_files);
+	}
+}
+
+/// The Sketch files of the selected jobs (canonical paths, deduplicated).
+fn watched_sketch_files(projects: &[ProjectConfig], job_names: &[String]) -> Vec<PathBuf> {
+	let mut files: Vec<PathBuf> = Vec::new();
+
+	for project in projects {
+		for (idx, job) in project.config.jobs.iter().enumerate() {
+			if !is_job_selected(job_names, &job.display_name(idx)) {
+				continue;
+			}
+			let file = canonical_path(project.resolve_path(&job.sketch_file).as_std_path());
+			if !files.contains(&file) {
+				files.push(file);
+			}
+		}
+	}
+
+	files
+}
+
+// region:    --- Support
+
+fn is_job_selected(job_names: &[String], name: &str) -> bool {
+	job_names.is_empty() || job_names.iter().any(|n| n == name)
+}
+
+/// Canonical path, also for a file that does not exist (yet), e.g., while Sketch replaces it.
+fn canonical_path(path: &Path) -> PathBuf {
+	if let Ok(path) = path.canonicalize() {
+		return path;
+	}
+	match (path.parent().and_then(|dir| dir.canonicalize().ok()), path.file_name()) {
+		(Some(dir), Some(name)) => dir.join(name),
+		_ => path.to_path_buf(),
+	}
+}
+
+// endregion: --- Support
@@ -0,0 +1,9 @@
+// region:    --- Modules
+
+mod site_robots;
+mod site_sitemap;
+
+pub use site_robots::*;
+pub use site_sitemap::*;
+
+// endregion: --- Modules
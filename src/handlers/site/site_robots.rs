@@ -0,0 +1,75 @@
+//! The `robots.txt` of a site: one group of rules for a user agent, and the sitemap URL.
+
+use crate::support::http;
+use crate::{Error, Result};
+use simple_fs::SPath;
+
+/// The robots file, at the root of the site.
+pub const ROBOTS_FILE: &str = "robots.txt";
+
+/// Options for `format_robots`.
+#[derive(Debug, Clone)]
+pub struct RobotsOptions {
+	/// The user agent of the rules (e.g., "*" for all the crawlers).
+	pub user_agent: String,
+	/// The path prefixes allowed within the disallowed ones (e.g., "/admin/public/").
+	pub allow: Vec<String>,
+	/// The path prefixes the crawlers must not visit (e.g., "/admin/"), nothing when empty.
+	pub disallow: Vec<String>,
+	/// The absolute URL of the sitemap (e.g., "https://example.com/sitemap.xml").
+	pub sitemap_url: Option<String>,
+}
+
+impl Default for RobotsOptions {
+	fn default() -> Self {
+		Self { user_agent: "*".to_string(), allow: Vec::new(), disallow: Vec::new(), sitemap_url: None }
+	}
+}
+
+/// Returns the content of a `robots.txt`, e.g.,
+///
+/// ```text
+/// User-agent: *
+/// Disallow: /admin/
+///
+/// Sitemap: https://example.com/sitemap.xml
+/// ```
+///
+/// An empty `disallow` allows everything (an empty `Disallow:`). The paths must start with `/` (or `*`).
+pub fn format_robots(options: &RobotsOptions) -> Result<String> {
+	for path in options.allow.iter().chain(&options.disallow) {
+		if !path.starts_with('/') && !path.starts_with('*') {
+			return Err(Error::custom(format!("Invalid robots path '{path}'. Expected a path starting with '/'")));
+		}
+	}
+	if let Some(sitemap_url) = &options.sitemap_url
+		&& !http::is_url(sitemap_url)
+	{
+		return Err(Error::custom(format!("Invalid sitemap URL '{sitemap_url}'. Expected an absolute http(s) URL")));
+	}
+
+	let mut content = format!("User-agent: {}\n", options.user_agent);
+	// The Allow lines first, for the crawlers applying the first matching rule
+	for path in &options.allow {
+		content.push_str(&format!("Allow: {path}\n"));
+	}
+	if options.disallow.is_empty() {
+		content.push_str("Disallow:\n");
+	}
+	for path in &options.disallow {
+		content.push_str(&format!("Disallow: {path}\n"));
+	}
+	if let Some(sitemap_url) = &options.sitemap_url {
+		content.push_str(&format!("\nSitemap: {sitemap_url}\n"));
+	}
+
+	Ok(content)
+}
+
+/// Writes the `robots.txt` of the options to a file (see `format_robots`).
+pub fn generate_robots(robots_file: &SPath, options: &RobotsOptions) -> Result<()> {
+	let content = format_robots(options)?;
+	std::fs::write(robots_file.as_std_path(), content)
+		.map_err(|e| Error::custom(format!("Cannot write '{robots_file}'. Cause: {e}")))?;
+	Ok(())
+}
@@ -0,0 +1,165 @@
+//! The `sitemap.xml` of a built site: one URL per HTML page of the root directory, with its last modification date
+//! (the file mtime). The pages opting out of the indexing (`<meta name="robots" content="noindex">`) are skipped.
+
+use crate::support::{dates, globs, http, strings};
+use crate::{Error, Result};
+use quick_xml::escape::escape;
+use simple_fs::{ListOptions, SPath};
+use std::time::UNIX_EPOCH;
+
+/// The sitemap file, at the root of the site.
+pub const SITEMAP_FILE: &str = "sitemap.xml";
+
+/// The max URLs of a sitemap file (the sitemaps protocol limit).
+pub const SITEMAP_MAX_URLS: usize = 50_000;
+
+/// Options for `build_sitemap`.
+#[derive(Debug, Clone, Default)]
+pub struct SitemapOptions {
+	/// The globs of the pages not in the sitemap, relative to the root (e.g., "drafts/**", "404.html").
+	pub ignore_globs: Vec<String>,
+	/// The URLs of the pages without the `.html` extension (e.g., "/about" for "about.html"), as the hosts serving
+	/// the clean URLs.
+	pub clean_urls: bool,
+}
+
+/// A URL of the sitemap.
+#[derive(Debug)]
+pub struct SitemapEntry {
+	/// The absolute URL (e.g., "https://example.com/docs/").
+	pub loc: String,
+	/// The W3C date of the file mtime (e.g., "2025-01-31").
+	pub lastmod: String,
+}
+
+/// Returns the sitemap entries of the HTML pages of a root directory, sorted by URL. An `index.html` is the URL
+/// of its directory (e.g., "docs/index.html" -> "https://example.com/docs/"). The hidden files and directories are
+/// skipped.
+pub fn build_sitemap(root_dir: &SPath, base_url: &str, options: &SitemapOptions) -> Result<Vec<SitemapEntry>> {
+	if !http::is_url(base_url) {
+		return Err(Error::custom(format!(
+			"Invalid base URL '{base_url}'. Expected an absolute http(s) URL (e.g., https://example.com)"
+		)));
+	}
+	if !root_dir.is_dir() {
+		return Err(Error::custom(format!("Site root directory '{root_dir}' not found")));
+	}
+
+	let ignore_globs: Vec<&str> = options.ignore_globs.iter().map(String::as_str).collect();
+	let ignore_set = globs::build_glob_set(Some(&ignore_globs))?;
+	let list_options = ListOptions::from_relative_glob(true);
+	let pages = simple_fs::list_files(root_dir.as_std_path(), Some(&["**/*.html", "**/*.htm"]), Some(list_options))
+		.map_err(Error::custom_from_err)?;
+
+	let base_url = base_url.trim_end_matches('/');
+	let mut entries = Vec::new();
+	for page in pages {
+		let Ok(rel_path) = page.as_std_path().strip_prefix(root_dir.as_std_path()) else {
+			continue;
+		};
+		let rel_path = rel_path.to_string_lossy().replace('\\', "/");
+		if rel_path.split('/').any(|segment| segment.starts_with('.'))
+			|| ignore_set.as_ref().is_some_and(|set| set.is_match(&rel_path))
+		{
+			continue;
+		}
+
+		let content =
+			std::fs::read_to_string(page.as_std_path()).map_err(|e| format!("Failed to read '{page}': {e}"))?;
+		if is_noindex_page(&content) {
+			continue;
+		}
+
+		let modified = std::fs::metadata(page.as_std_path())
+			.and_then(|meta| meta.modified())
+			.map_err(|e| format!("Failed to read the modification time of '{page}': {e}"))?;
+		let mtime = modified
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_secs() as i64)
+			.unwrap_or_default();
+
+		entries.push(SitemapEntry {
+			loc: format!("{base_url}/{}", strings::percent_encode_path(&page_url_path(&rel_path, options.clean_urls))),
+			lastmod: dates::format_utc_date(mtime, "%Y-%m-%d"),
+		});
+	}
+	if entries.len() > SITEMAP_MAX_URLS {
+		return Err(Error::custom(format!(
+			"The sitemap has {} URLs, over the limit of {SITEMAP_MAX_URLS} of a sitemap file",
+			entries.len()
+		)));
+	}
+	entries.sort_by(|a, b| a.loc.cmp(&b.loc));
+
+	Ok(entries)
+}
+
+/// Returns the `sitemap.xml` content of the entries.
+pub fn format_sitemap(entries: &[SitemapEntry]) -> String {
+	let mut content = String::from(
+		"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+	);
+	for entry in entries {
+		content.push_str(&format!(
+			"  <url>\n    <loc>{}</loc>\n    <lastmod>{}</lastmod>\n  </url>\n",
+			escape(&entry.loc),
+			entry.lastmod
+		));
+	}
+	content.push_str("</urlset>\n");
+	content
+}
+
+/// Writes the `sitemap.xml` of the HTML pages of a root directory (see `build_sitemap`).
+/// Returns the number of URLs.
+pub fn generate_sitemap(
+	root_dir: &SPath,
+	base_url: &str,
+	sitemap_file: &SPath,
+	options: &SitemapOptions,
+) -> Result<usize> {
+	let entries = build_sitemap(root_dir, base_url, options)?;
+	std::fs::write(sitemap_file.as_std_path(), format_sitemap(&entries))
+		.map_err(|e| Error::custom(format!("Cannot write '{sitemap_file}'. Cause: {e}")))?;
+	Ok(entries.len())
+}
+
+/// Returns the URL path of a page, relative to the root (e.g., "docs/index.html" -> "docs/",
+/// "about.html" -> "about" with `clean_urls`).
+fn page_url_path(rel_path: &str, clean_urls: bool) -> String {
+	let (dir, file_name) = match rel_path.rsplit_once('/') {
+		Some((dir, file_name)) => (format!("{dir}/"), file_name),
+		None => (String::new(), rel_path),
+	};
+	if file_name == "index.html" || file_name == "index.htm" {
+		return dir;
+	}
+	if clean_urls && let Some(stem) = file_name.strip_suffix(".html").or_else(|| file_name.strip_suffix(".htm")) {
+		return format!("{dir}{stem}");
+	}
+	rel_path.to_string()
+}
+
+/// Returns true if the page has a `<meta name="robots">` with the `noindex` (or `none`) directive.
+fn is_noindex_page(content: &str) -> bool {
+	let content = content.to_ascii_lowercase();
+	content.split("<meta").skip(1).any(|tag| {
+		let tag = tag.split('>').next().unwrap_or_default();
+		let is_robots = tag.contains("name=\"robots\"") || tag.contains("name='robots'") || tag.contains("name=robots");
+		is_robots
+			&& meta_content(tag).is_some_and(|directives| {
+				directives
+					.split(',')
+					.any(|directive| matches!(directive.trim(), "noindex" | "none"))
+			})
+	})
+}
+
+/// Returns the `content` attribute value of a `<meta` tag (quoted or not).
+fn meta_content(tag: &str) -> Option<&str> {
+	let value = &tag[tag.find("content=")? + "content=".len()..];
+	match value.chars().next()? {
+		quote @ ('"' | '\'') => value[1..].split(quote).next(),
+		_ => value.split(|c: char| c.is_whitespace() || c == '/').next(),
+	}
+}
@@ -1,5 +1,49 @@
-#[derive(Debug, Clone)]
+use crate::{Error, Result};
+use serde::Serialize;
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Artboard {
 	pub name: String,
 	pub uid: String,
+	pub page_name: String,
+}
+
+impl Artboard {
+	/// Returns the path the globs match in a scope (e.g., "ico/add", or "Icons/ico/add" for `PagePath`).
+	pub fn glob_path(&self, glob_scope: GlobScope) -> Cow<'_, str> {
+		match glob_scope {
+			GlobScope::Name => Cow::Borrowed(&self.name),
+			GlobScope::PagePath => Cow::Owned(format!("{}/{}", self.page_name, self.name)),
+		}
+	}
+}
+
+/// What the artboard globs match: the artboard name (e.g., "ico/add"), or the page name then the artboard name
+/// (e.g., "Icons/ico/add"), for the artboard names used on several pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlobScope {
+	#[default]
+	Name,
+	PagePath,
+}
+
+impl TryFrom<&str> for GlobScope {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"name" => Ok(Self::Name),
+			"page-path" => Ok(Self::PagePath),
+			other => Err(Error::custom(format!("Invalid glob scope '{other}'. Expected: name, page-path"))),
+		}
+	}
+}
+
+/// An artboard with its size (from `sketchtool list artboards`).
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtboardFrame {
+	pub artboard: Artboard,
+	pub width: f64,
+	pub height: f64,
 }
@@ -0,0 +1,43 @@
+//! The CDN URLs of the exported files (see `ExportOptions::cdn_base`), by logical name: the artboard name with the
+//! scale suffix and format (e.g., "ico/user/fill@2x.png"), so the app code references the assets without
+//! hardcoding their output paths (flattened, sanitized, or under the resolved output path tokens).
+
+use crate::support::{files, strings, tokens};
+use simple_fs::SPath;
+
+/// The file of the URLs, in the output directory.
+pub const ASSET_URLS_FILE: &str = "urls.json";
+
+/// Returns the local directory mirrored at the CDN base: the output path up to its first segment with a token
+/// (e.g., "cdn" for "cdn/{git_short_sha}/icons", so the URLs keep the resolved sha), or the output directory.
+pub fn cdn_root_dir(output_dir: &str, output_path: &SPath) -> SPath {
+	if tokens::has_tokens(output_dir) {
+		let segments: Vec<&str> = output_dir
+			.split('/')
+			.take_while(|segment| !tokens::has_tokens(segment))
+			.collect();
+		return SPath::new(segments.join("/"));
+	}
+	if files::looks_like_file_path(output_path) {
+		output_path.parent().unwrap_or_else(|| SPath::new(""))
+	} else {
+		output_path.clone()
+	}
+}
+
+/// Returns the CDN URL of a file under the CDN root directory (the relative path percent-encoded),
+/// None when the file is not under it.
+pub fn cdn_url(cdn_base: &str, cdn_root: &SPath, file: &SPath) -> Option<String> {
+	let relative = if cdn_root.as_str().is_empty() || cdn_root.as_str() == "." {
+		file.to_string()
+	} else {
+		file.diff(cdn_root)?.to_string()
+	};
+	let relative = relative.replace('\\', "/");
+	let relative = relative.trim_start_matches("./");
+	if relative.split('/').any(|segment| segment == "..") {
+		return None;
+	}
+
+	Some(format!("{}/{}", cdn_base.trim_end_matches('/'), strings::percent_encode_path(relative)))
+}
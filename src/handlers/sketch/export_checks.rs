@@ -0,0 +1,315 @@
+//! Checks on the exported SVG of an artboard, reported as warnings.
+
+use crate::handlers::sketch::{Artboard, ExportReport, parse_svg_px_length, view_box_size};
+use crate::handlers::svg;
+use crate::support::{hashes, strings, xmls};
+use crate::{Warning, WarningCode};
+use std::collections::{BTreeMap, HashMap};
+use xmltree::{Element, XMLNode};
+
+/// The SVG elements that render something (an artboard without any of them is empty).
+const SHAPE_TAGS: &[&str] = &[
+	"path", "rect", "circle", "ellipse", "line", "polyline", "polygon", "use", "image", "text",
+];
+
+/// The SVG elements that do not render (ignored when comparing artboard contents).
+const NON_RENDERING_TAGS: &[&str] = &["title", "desc", "metadata"];
+
+/// Collects the checks of the exported SVGs along an export: the warnings,
+/// and the artboards rendering identically (likely copy-paste duplicates).
+/// An artboard exported several times (e.g., svg and svg-symbols, or several scales) is reported once.
+#[derive(Debug, Default)]
+pub struct ExportChecker {
+	pub warnings: Vec<Warning>,
+	/// Artboard names by rendered content hash (see `rendered_content_hash`).
+	artboards_by_hash: BTreeMap<String, Vec<String>>,
+}
+
+impl ExportChecker {
+	pub fn new(warnings: Vec<Warning>) -> Self {
+		Self { warnings, ..Default::default() }
+	}
+
+	/// Checks an exported SVG (see `check_exported_svg`) and records its rendered content.
+	/// Empty artboards are not compared (they are reported as empty already).
+	pub fn check_svg(&mut self, artboard: &Artboard, svg_content: &str) {
+		let warnings = check_exported_svg(artboard, svg_content);
+		let is_empty = warnings.iter().any(|w| w.code == WarningCode::EmptyArtboard);
+		self.add_warnings(warnings);
+
+		if !is_empty && let Some(hash) = rendered_content_hash(svg_content) {
+			let names = self.artboards_by_hash.entry(hash).or_default();
+			if !names.contains(&artboard.name) {
+				names.push(artboard.name.clone());
+			}
+		}
+	}
+
+	/// Checks an exported SVG converted to a sprite `<symbol>`: the `check_svg` checks,
+	/// plus the features that break in `<symbol>`/`<use>` contexts (see `check_symbol_compatibility`).
+	pub fn check_symbol_svg(&mut self, artboard: &Artboard, svg_content: &str) {
+		self.check_svg(artboard, svg_content);
+		self.add_warnings(check_symbol_compatibility(artboard, svg_content));
+	}
+
+	/// Reports the images inlined in the SVG of an artboard over `svg::EMBED_IMAGE_WARN_BYTES`.
+	pub fn check_embedded_images(&mut self, artboard: &Artboard, images: &[svg::EmbeddedImage]) {
+		let warnings = images
+			.iter()
+			.filter(|image| image.bytes > svg::EMBED_IMAGE_WARN_BYTES)
+			.map(|image| {
+				Warning::for_artboard(
+					WarningCode::LargeEmbeddedImage,
+					&artboard.name,
+					format!(
+						"Artboard '{}' embeds a large image '{}' ({})",
+						artboard.name,
+						image.reference,
+						strings::format_bytes(image.bytes)
+					),
+				)
+			});
+		self.add_warnings(warnings);
+	}
+
+	/// Adds the warnings not already reported (same code, artboard, and message).
+	fn add_warnings(&mut self, warnings: impl IntoIterator<Item = Warning>) {
+		for warning in warnings {
+			let is_reported = self
+				.warnings
+				.iter()
+				.any(|w| w.code == warning.code && w.artboard == warning.artboard && w.message == warning.message);
+			if !is_reported {
+				self.warnings.push(warning);
+			}
+		}
+	}
+
+	/// Returns the sets of artboards rendering identically (sorted by name).
+	pub fn duplicates(&self) -> Vec<Vec<String>> {
+		let mut duplicates: Vec<Vec<String>> = self
+			.artboards_by_hash
+			.values()
+			.filter(|names| names.len() > 1)
+			.map(|names| {
+				let mut names = names.clone();
+				names.sort();
+				names
+			})
+			.collect();
+		duplicates.sort();
+		duplicates
+	}
+
+	/// Sets the warnings and duplicates of the report,
+	/// with one `DuplicateContent` warning per set of identical artboards.
+	pub fn finish(self, report: &mut ExportReport) {
+		report.duplicates = self.duplicates();
+		report.warnings = self.warnings;
+		for names in &report.duplicates {
+			report.warnings.push(Warning::new(
+				WarningCode::DuplicateContent,
+				format!("Artboards render identically: {}", names.join(", ")),
+			));
+		}
+	}
+}
+
+/// Returns a stable hash of what an exported SVG renders: the root `viewBox` and the content,
+/// ignoring the non-rendering elements (e.g., `<title>` with the artboard name), the id names
+/// (renumbered in document order, with their references), and formatting.
+/// Returns None if the content cannot be parsed.
+pub fn rendered_content_hash(svg_content: &str) -> Option<String> {
+	let mut root = Element::parse(svg_content.as_bytes()).ok()?;
+	remove_elements(&mut root, NON_RENDERING_TAGS);
+
+	// Renumber the ids in document order (e.g., Sketch ids are named after the layers)
+	let mut ids: HashMap<String, String> = HashMap::new();
+	collect_ids(&root, &mut ids);
+	let nodes = xmls::transform_id_references(root.children, |id| ids.get(id).cloned().unwrap_or(id.to_string()));
+
+	let view_box = root.attributes.get("viewBox").map(String::as_str).unwrap_or_default();
+	let content = format!("{view_box}>{}", xmls::normalize_markup(&xmls::nodes_to_string(&nodes)));
+	Some(hashes::fnv1a_64_hex(content))
+}
+
+/// Removes the descendant elements with these tag names.
+fn remove_elements(element: &mut Element, tag_names: &[&str]) {
+	element
+		.children
+		.retain(|node| !matches!(node, XMLNode::Element(el) if tag_names.contains(&el.name.as_str())));
+	for node in element.children.iter_mut() {
+		if let XMLNode::Element(child) = node {
+			remove_elements(child, tag_names);
+		}
+	}
+}
+
+/// Maps the descendant ids to "id1", "id2", ... in document order.
+fn collect_ids(element: &Element, ids: &mut HashMap<String, String>) {
+	for child in element.children.iter().filter_map(|node| node.as_element()) {
+		if let Some(id) = child.attributes.get("id") {
+			let next = format!("id{}", ids.len() + 1);
+			ids.entry(id.clone()).or_insert(next);
+		}
+		collect_ids(child, ids);
+	}
+}
+
+/// Checks the SVG features with poor cross-browser support, or that break once the SVG is a `<symbol>`
+/// referenced with `<use>` (e.g., Safari clipping `objectBoundingBox` filters, gradients of external sprites).
+/// Returns one warning listing the features found, if any.
+pub fn check_symbol_compatibility(artboard: &Artboard, svg_content: &str) -> Option<Warning> {
+	let root = Element::parse(svg_content.as_bytes()).ok()?;
+
+	let has = |selector: &str| xmls::select_elements(&root, selector).is_ok_and(|els| !els.is_empty());
+
+	let mut features: Vec<&str> = Vec::new();
+	let bounding_box_filter = xmls::select_elements(&root, "filter").is_ok_and(|filters| {
+		filters.iter().any(|filter| {
+			filter
+				.attributes
+				.get("filterUnits")
+				.is_none_or(|units| units == "objectBoundingBox")
+		})
+	});
+	if bounding_box_filter {
+		features.push("filter with objectBoundingBox units");
+	}
+	if has("mask") {
+		features.push("mask");
+	}
+	if has("svg svg") {
+		features.push("nested svg");
+	}
+	if has("linearGradient") || has("radialGradient") {
+		features.push("gradient (not rendered from an external sprite in some browsers)");
+	}
+	if has("foreignObject") {
+		features.push("foreignObject");
+	}
+	if has("style") {
+		features.push("style element (applies to the whole page)");
+	}
+
+	if features.is_empty() {
+		return None;
+	}
+
+	let name = artboard.name.as_str();
+	Some(Warning::for_artboard(
+		WarningCode::CompatibilityRisk,
+		name,
+		format!("Artboard '{name}' uses features that may break in <symbol>/<use>: {}", features.join(", ")),
+	))
+}
+
+/// Checks the exported SVG of an artboard (non-square, empty, strokes, local references).
+/// Returns no warnings if the content cannot be parsed (conversion reports those errors).
+pub fn check_exported_svg(artboard: &Artboard, svg_content: &str) -> Vec<Warning> {
+	let Ok(root) = Element::parse(svg_content.as_bytes()) else {
+		return Vec::new();
+	};
+
+	let mut warnings = Vec::new();
+	let name = artboard.name.as_str();
+
+	// -- Non-square (from the pixel size, falling back to the viewBox)
+	let width = parse_svg_px_length(root.attributes.get("width").map(String::as_str));
+	let height = parse_svg_px_length(root.attributes.get("height").map(String::as_str));
+	let size = match (width, height) {
+		(Some(width), Some(height)) => Some((width, height)),
+		_ => root.attributes.get("viewBox").and_then(|vb| view_box_size(vb)),
+	};
+	if let Some((width, height)) = size
+		&& width.as_f64() != height.as_f64()
+	{
+		warnings.push(Warning::for_artboard(
+			WarningCode::NonSquareArtboard,
+			name,
+			format!("Artboard '{name}' is not square ({width}x{height})"),
+		));
+	}
+
+	// -- Empty (no rendering element)
+	let has_shape = SHAPE_TAGS
+		.iter()
+		.any(|tag| xmls::select_elements(&root, tag).is_ok_and(|els| !els.is_empty()));
+	if !has_shape {
+		warnings.push(Warning::for_artboard(
+			WarningCode::EmptyArtboard,
+			name,
+			format!("Artboard '{name}' has no visible shapes"),
+		));
+	}
+
+	// -- Strokes (do not scale with the icon size, and are often not meant to be themed)
+	let stroked_count = xmls::select_elements(&root, "[stroke]")
+		.map(|els| {
+			els.iter()
+				.filter(|el| el.attributes.get("stroke").is_some_and(|v| v != "none"))
+				.count()
+		})
+		.unwrap_or_default();
+	if stroked_count > 0 {
+		warnings.push(Warning::for_artboard(
+			WarningCode::StrokeDetected,
+			name,
+			format!("Artboard '{name}' has {stroked_count} stroked element(s)"),
+		));
+	}
+
+	// -- Local references (internal paths leaked into the export, not rendered anywhere else)
+	// Note: xmltree keys the attributes by local name, so `href` is also `xlink:href`
+	let mut local_refs: Vec<&str> = Vec::new();
+	for element in xmls::select_elements(&root, "[href]").unwrap_or_default() {
+		if let Some(href) = element.attributes.get("href").filter(|href| svg::is_local_reference(href))
+			&& !local_refs.contains(&href.as_str())
+		{
+			local_refs.push(href);
+		}
+	}
+	if !local_refs.is_empty() {
+		warnings.push(Warning::for_artboard(
+			WarningCode::LocalReference,
+			name,
+			format!("Artboard '{name}' references local files: {}", local_refs.join(", ")),
+		));
+	}
+
+	warnings
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_sketch_export_checks_finish_once_per_artboard() -> Result<()> {
+		// -- Setup & Fixtures
+		let artboard = Artboard { name: "ico/wide".to_string(), uid: "U1".to_string(), page_name: "Icons".to_string() };
+		let svg_content = r##"<svg width="24px" height="16px" viewBox="0 0 24 16" xmlns="http://www.w3.org/2000/svg"><g><path d="M0 0H4" stroke="#000"/></g></svg>"##;
+		let mut checker = ExportChecker::default();
+
+		// -- Exec
+		// The svg-symbols step, then the svg step at two scales
+		checker.check_symbol_svg(&artboard, svg_content);
+		checker.check_svg(&artboard, svg_content);
+		checker.check_svg(&artboard, svg_content);
+		let mut report = ExportReport::default();
+		checker.finish(&mut report);
+
+		// -- Check
+		let codes: Vec<WarningCode> = report.warnings.iter().map(|w| w.code).collect();
+		assert_eq!(codes, [WarningCode::NonSquareArtboard, WarningCode::StrokeDetected]);
+		assert!(report.duplicates.is_empty());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
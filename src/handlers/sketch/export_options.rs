@@ -0,0 +1,380 @@
+use crate::handlers::sketch::GlobScope;
+use crate::support::colors::{self, Rgba};
+use crate::support::files;
+use crate::support::globs::GlobCase;
+use crate::{Error, Result};
+
+/// The default max artboards of a sketchtool export command (see `ExportOptions::items_batch_size`).
+pub const DEFAULT_ITEMS_BATCH_SIZE: usize = 500;
+
+/// Options for `export_artboards`.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+	/// Flatten exported file names (e.g., "ico/user/fill" becomes "ico-user-fill").
+	pub flatten: bool,
+
+	/// Artboards matching these globs are not exported.
+	pub exclude_globs: Vec<String>,
+
+	/// When not empty, the artboards must also match one of these globs
+	/// (e.g., a per-artboard override within the job globs).
+	pub only_globs: Vec<String>,
+
+	/// What all the globs match (see `GlobScope`): "name" (the default) or "page-path" (e.g., "Icons/ico/*").
+	pub glob_scope: Option<String>,
+
+	/// Match all the globs case insensitively (e.g., "ico/*" matches "Ico/User").
+	pub ignore_case: bool,
+
+	/// Match the globs without an uppercase letter case insensitively (like ripgrep `--smart-case`).
+	pub smart_case: bool,
+
+	/// Export scales for the regular formats (e.g., "1", "2"), passed to sketchtool `--scales`.
+	/// Files of scales other than 1 get a `@<scale>x` suffix (e.g., "logo@2x.png").
+	pub scales: Vec<String>,
+
+	/// Export the artboards with their export presets (the formats and scales configured in the Sketch file, see
+	/// `load_export_presets`) instead of the regular formats and scales, which the artboards without presets keep.
+	pub use_design_presets: bool,
+
+	/// Output file name (without extension) for a single matched artboard (regular formats only).
+	pub file_name: Option<String>,
+
+	/// Only export the artboards changed since this git ref (e.g., "origin/main"), see `changed_artboard_uids`.
+	pub changed_since: Option<String>,
+
+	/// Return an empty report (instead of `Error::NoArtboardsMatched`) when the globs match no artboards.
+	pub allow_empty: bool,
+
+	/// Keep the raw export cache directory instead of deleting it after processing.
+	pub keep_raw_export: bool,
+
+	/// Annotate each generated `<symbol>` with the original artboard name and page
+	/// (`data-name` and `data-page`). Only applies to the `svg-symbols` format.
+	pub symbol_data_attrs: bool,
+
+	/// Also write a `symbols.json` manifest (ids, original names, viewBox, pixel width/height)
+	/// next to the sprite file. Only applies to the `svg-symbols` format.
+	pub symbols_manifest: bool,
+
+	/// Also write a TypeScript module of the symbol ids (union type and const array) to this file.
+	/// Only applies to the `svg-symbols` format.
+	pub ts_out: Option<String>,
+
+	/// The TypeScript type name of `ts_out` (default "IconName").
+	pub ts_type_name: Option<String>,
+
+	/// Also write a Rust module of the symbol ids (one constant per id and an `ALL_ICONS` slice) to this file.
+	/// Only applies to the `svg-symbols` format.
+	pub rust_out: Option<String>,
+
+	/// Also write an HTML file of the `<use>` snippets of the symbols (one `<svg>` per symbol, with the size
+	/// and preserveAspectRatio of the symbol), see `codegen::use_snippets_html`. Only applies to the `svg-symbols` format.
+	pub use_snippets_out: Option<String>,
+
+	/// Also write the usage docs of the icons to this Markdown (`.md`) or HTML (`.html`) file, with the
+	/// copy-pasteable snippets of each icon (`<use>` markup, React, CSS class), see `codegen::usage_docs`.
+	/// Only applies to the `svg-symbols` format.
+	pub usage_out: Option<String>,
+
+	/// Also write a TypeScript module of the path data of the single-path icons (`{ viewBox, path }` by symbol id,
+	/// see `svg::single_path_icon`) to this file, for the icon components rendering `<path d>` without a sprite.
+	/// The other icons are not in it. Only applies to the `svg-symbols` format.
+	pub paths_ts_out: Option<String>,
+
+	/// A directory of codegen templates replacing the built-in ones of the same name
+	/// (e.g., "icon-names.ts.jinja" for `ts_out`), see `codegen::render_codegen_template`.
+	pub template_dir: Option<String>,
+
+	/// `preserveAspectRatio` values for the generated `<symbol>` elements,
+	/// either `VALUE` (all symbols) or `GLOB=VALUE` (symbols whose artboard name matches the glob).
+	/// The first matching rule wins.
+	pub preserve_aspect_ratio: Vec<String>,
+
+	/// Default `width` and `height` of the generated `<symbol>` elements (used by the `<use>` without a size),
+	/// either `VALUE` (all symbols) or `GLOB=VALUE` (symbols whose artboard name matches the glob), see `SymbolSize`.
+	/// The first matching rule wins.
+	pub symbol_size: Vec<String>,
+
+	/// Namespace prefixes to strip from the generated `<symbol>` content (e.g., "sketch"),
+	/// removing their declarations, attributes, and elements.
+	pub strip_namespaces: Vec<String>,
+
+	/// Trim the transparent space around the exported images (sketchtool `--trimmed`).
+	pub trim: bool,
+
+	/// Only export the layers within the artboard bounds, without the artboard background
+	/// (sketchtool `--group-contents-only`).
+	pub group_contents_only: bool,
+
+	/// Transparent padding in px (at 1x) added on each side of the exported svg, png, and svg-symbols files,
+	/// by growing the SVG `viewBox` (and `width`/`height`) or the PNG canvas (scaled, e.g., 4px at 2x for 2).
+	pub padding: Option<u32>,
+
+	/// The background of the raster exports (png, jpg, jpeg, webp, tiff): "transparent" or a hex color
+	/// (e.g., "#ffffff"), see `Background`.
+	pub background: Option<String>,
+
+	/// Split the exported png files into a grid of tiles of this size in px (e.g., 1024), written in a
+	/// `<name>_tiles/` directory (`<column>_<row>.png`) with a `<name>.tiles.json` descriptor, see `TilesManifest`.
+	pub tile: Option<u32>,
+
+	/// The px each tile extends into its neighbors (with `tile`).
+	pub tile_overlap: u32,
+
+	/// Write a copy of the exported png files with a grid every this many px (in artboard px, e.g., 8) and the
+	/// artboard size label, in the `redlines/` directory of the output (same relative paths), see `redline_image`.
+	pub redline: Option<u32>,
+
+	/// Export email-safe images (see `plan_export`): png or jpeg files only, at most one scale, the artboards wider
+	/// than `email_max_width` scaled down to it, and lowercase flattened file names (also their `cid:` references).
+	pub email_safe: bool,
+
+	/// The max display width of the email-safe images, in CSS px (default `DEFAULT_EMAIL_MAX_WIDTH`).
+	/// The files are this width times the scale (e.g., 1200px for 600 at 2x).
+	pub email_max_width: Option<u32>,
+
+	/// Also write the email snippet of the email-safe images to this HTML (`.html`) or MJML (`.mjml`) file,
+	/// see `codegen::email_snippet`.
+	pub email_snippet_out: Option<String>,
+
+	/// The CDN URL of the output (e.g., "https://cdn.example.com/assets/"): writes the `urls.json` of the exported
+	/// files in the output directory, their URLs by logical name (see `ASSET_URLS_FILE`). The URL paths are relative to
+	/// the output path up to its first token, so the resolved tokens (e.g., `{git_short_sha}`) are in the URLs.
+	pub cdn_base: Option<String>,
+
+	/// Also write a TypeScript module of the `cdn_base` URLs to this file (see `codegen::asset_urls_ts`).
+	pub cdn_ts_out: Option<String>,
+
+	/// Add a bookmark per page (the artboard name) to the pdf-book file.
+	pub pdf_bookmarks: bool,
+
+	/// Set `vector-effect="non-scaling-stroke"` on the stroked shapes of the svg and svg-symbols exports,
+	/// so the strokes keep their width at any rendered size (see `svg::add_non_scaling_stroke`).
+	/// Only applies to the `svg` and `svg-symbols` formats.
+	pub non_scaling_stroke: bool,
+
+	/// Inline the raster images referenced by the svg and svg-symbols exports (files, local paths, and URLs) as
+	/// base64 `data:` URIs, so each SVG is self-contained (see `svg::embed_svg_images`).
+	/// Only applies to the `svg` and `svg-symbols` formats.
+	pub embed_images: bool,
+
+	/// Write the raster images inlined in the svg and svg-symbols exports (base64 `data:` URIs) as files in this
+	/// directory, the references rewritten to them (see `svg::extract_svg_images`). Cannot be used with `embed_images`.
+	pub extract_images: Option<String>,
+
+	/// What to do with the references to local files in the svg and svg-symbols exports (see `LocalRefs`):
+	/// "warn" (default), "fail", "strip", or "embed".
+	pub local_refs: Option<String>,
+
+	/// Write (or update) a `SHA256SUMS` file in the output directory, covering the exported files under it
+	/// (see `checksums::update_sha256_sums`).
+	pub checksums: bool,
+
+	/// Write a `uid-map.json` in the output directory, relating each artboard UID to its name and output files
+	/// (see `UidMap`).
+	pub uid_map: bool,
+
+	/// The modification time of the output files: "now" (default, the write time), "source" (the mtime of the Sketch
+	/// file) or "epoch" (`SOURCE_DATE_EPOCH`, or the Unix epoch), for the reproducible builds and the incremental deploy
+	/// tools comparing mtimes (see `MtimeMode`).
+	pub preserve_mtime: Option<String>,
+
+	/// The octal mode of the output files (e.g., "644"), for the exports run as root in a container and read by
+	/// non-root steps. Without it, the files get the default permissions (666 less the umask). Ignored on Windows.
+	pub chmod: Option<String>,
+
+	/// Keep the converted symbols of the svg-symbols sprite in a `.cache-symbols/` directory next to it,
+	/// and only convert the symbols whose exported SVG changed (see `svg::SymbolsCache`), e.g., for the watch rebuilds.
+	pub symbols_cache: bool,
+
+	/// Raw sketchtool arguments appended to the export commands, either `ARG` (all formats)
+	/// or `FORMAT:ARG` (e.g., "png:--background=#ffffff"), for the sketchtool flags without first-class support.
+	pub tool_args: Vec<String>,
+
+	/// The max artboards of a sketchtool export command (default `DEFAULT_ITEMS_BATCH_SIZE`): more are exported in
+	/// several commands, since the `--items` UIDs of thousands of artboards exceed the command line limit of the OS.
+	pub items_batch_size: Option<usize>,
+
+	/// A suffix of the raw export cache directory (e.g., "icons" for ".cache-raw-export-icons"), so the exports
+	/// running concurrently into the same parent directory do not share it (see `run::run_projects`).
+	pub cache_suffix: Option<String>,
+}
+
+impl ExportOptions {
+	/// Returns the parsed `glob_scope` (the artboard names by default).
+	pub fn glob_scope(&self) -> Result<GlobScope> {
+		Ok(self
+			.glob_scope
+			.as_deref()
+			.map(GlobScope::try_from)
+			.transpose()?
+			.unwrap_or_default())
+	}
+
+	/// Returns how the globs match the letter case (`ignore_case` first, then `smart_case`).
+	pub fn glob_case(&self) -> GlobCase {
+		if self.ignore_case {
+			GlobCase::Insensitive
+		} else if self.smart_case {
+			GlobCase::Smart
+		} else {
+			GlobCase::Sensitive
+		}
+	}
+
+	/// Returns the `items_batch_size`, or its default.
+	pub fn items_batch_size(&self) -> usize {
+		self.items_batch_size.unwrap_or(DEFAULT_ITEMS_BATCH_SIZE)
+	}
+
+	/// Returns the parsed `preserve_mtime` (the write time by default).
+	pub fn preserve_mtime(&self) -> Result<MtimeMode> {
+		Ok(self
+			.preserve_mtime
+			.as_deref()
+			.map(MtimeMode::try_from)
+			.transpose()?
+			.unwrap_or_default())
+	}
+
+	/// Returns the parsed `chmod` mode.
+	pub fn chmod(&self) -> Result<Option<u32>> {
+		self.chmod.as_deref().map(files::parse_file_mode).transpose()
+	}
+
+	/// Returns the parsed `local_refs` (a warning by default).
+	pub fn local_refs(&self) -> Result<LocalRefs> {
+		Ok(self
+			.local_refs
+			.as_deref()
+			.map(LocalRefs::try_from)
+			.transpose()?
+			.unwrap_or_default())
+	}
+
+	/// Returns the parsed `background`.
+	pub fn background(&self) -> Result<Option<Background>> {
+		self.background.as_deref().map(Background::try_from).transpose()
+	}
+}
+
+/// The modification time of the output files (see `ExportOptions::preserve_mtime`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MtimeMode {
+	/// The write time (unchanged).
+	#[default]
+	Now,
+	/// The mtime of the Sketch file.
+	Source,
+	/// `SOURCE_DATE_EPOCH` when set, the Unix epoch otherwise.
+	Epoch,
+}
+
+impl TryFrom<&str> for MtimeMode {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"now" => Ok(Self::Now),
+			"source" => Ok(Self::Source),
+			"epoch" => Ok(Self::Epoch),
+			other => Err(Error::custom(format!("Invalid mtime mode '{other}'. Expected: source, epoch, now"))),
+		}
+	}
+}
+
+/// What to do with the references to local files (e.g., an image fill at "/Users/jen/hero.png", see
+/// `svg::is_local_reference`) in the svg and svg-symbols exports (see `ExportOptions::local_refs`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LocalRefs {
+	/// A `local-reference` warning (failing with `--fail-on warning`).
+	#[default]
+	Warn,
+	/// Fail the export (`Error::ValidationFailed`).
+	Fail,
+	/// Remove the reference attributes.
+	Strip,
+	/// Replace the references with `data:` URIs of the files (failing when a file cannot be read).
+	Embed,
+}
+
+impl TryFrom<&str> for LocalRefs {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"warn" => Ok(Self::Warn),
+			"fail" => Ok(Self::Fail),
+			"strip" => Ok(Self::Strip),
+			"embed" => Ok(Self::Embed),
+			other => Err(Error::custom(format!(
+				"Invalid local references policy '{other}'. Expected: warn, fail, strip, embed"
+			))),
+		}
+	}
+}
+
+/// The background of the raster exports.
+/// A color is passed to sketchtool (`--background`), and the png files are also composited onto it
+/// (so the padding and the transparent pixels get the color too).
+/// Transparent keeps the alpha of the export (not supported by jpg/jpeg).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Background {
+	Transparent,
+	Color(Rgba),
+}
+
+impl TryFrom<&str> for Background {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		if value.trim().eq_ignore_ascii_case("transparent") {
+			return Ok(Self::Transparent);
+		}
+		colors::parse_hex_color(value).map(Self::Color).ok_or_else(|| {
+			Error::custom(format!(
+				"Invalid background '{value}'. Expected 'transparent' or a hex color (e.g., #ffffff)"
+			))
+		})
+	}
+}
+
+/// The default size of a generated `<symbol>` (see `ExportOptions::symbol_size`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolSize {
+	/// The px size of the artboard (the exported `width`/`height`, or the `viewBox` size).
+	Intrinsic,
+	/// The given lengths (e.g., "1em", "24").
+	Fixed { width: String, height: String },
+}
+
+impl TryFrom<&str> for SymbolSize {
+	type Error = Error;
+
+	/// Parses "intrinsic", a length for both the width and height (e.g., "1em"), or `WIDTH,HEIGHT` (e.g., "24,16").
+	fn try_from(value: &str) -> Result<Self> {
+		let value = value.trim();
+		if value.eq_ignore_ascii_case("intrinsic") {
+			return Ok(Self::Intrinsic);
+		}
+		let (width, height) = value.split_once(',').unwrap_or((value, value));
+		let (width, height) = (width.trim(), height.trim());
+		if !is_svg_length(width) || !is_svg_length(height) {
+			return Err(Error::custom(format!(
+				"Invalid symbol size '{value}'. Expected 'intrinsic', a length (e.g., 1em), or WIDTH,HEIGHT (e.g., 24,16)"
+			)));
+		}
+		Ok(Self::Fixed { width: width.to_string(), height: height.to_string() })
+	}
+}
+
+/// The units of the `SymbolSize` lengths (none is px).
+const LENGTH_UNITS: &[&str] = &["", "px", "em", "rem", "ex", "ch", "%", "vw", "vh", "pt", "pc", "cm", "mm", "in"];
+
+/// Returns true for a positive number with an optional unit (e.g., "24", "1.5em", "100%").
+fn is_svg_length(value: &str) -> bool {
+	let unit_start = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+	let (number, unit) = value.split_at(unit_start);
+	number.parse::<f64>().is_ok_and(|number| number > 0.0) && LENGTH_UNITS.contains(&unit)
+}
@@ -0,0 +1,1278 @@
+//! The fully resolved export plan (artboards × formats × output paths × transforms), computed without exporting.
+//! `export_artboards` plans, then executes the plan (see `execute_export_plan`).
+
+use crate::handlers::codegen;
+use crate::handlers::sketch::{
+	ASSET_URLS_FILE, Artboard, Background, ExportOptions, FILE_NAMES_MANIFEST_FILE, GlobScope, LocalRefs, SymbolSize,
+	SymbolsManifest, UID_MAP_FILE, cdn_root_dir, cdn_url, changed_artboard_uids, list_artboard_frames, list_artboards,
+	load_export_presets, resolve_output_tokens, suggest_artboard_names,
+};
+use crate::support::globs::GlobCase;
+use crate::support::{files, globs, http, strings};
+use crate::{Error, Result, Warning, WarningCode};
+use globset::GlobSet;
+use serde::Serialize;
+use simple_fs::SPath;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The sprite format (all the artboards as `<symbol>` elements of a single SVG file).
+pub const SVG_SYMBOLS_FORMAT: &str = "svg-symbols";
+
+/// The multi-page PDF format (all the artboards as the pages of a single PDF file, in artboard order).
+pub const PDF_BOOK_FORMAT: &str = "pdf-book";
+
+/// The export formats (the sketchtool ones, plus svg-symbols and pdf-book).
+pub const EXPORT_FORMATS: &[&str] = &[
+	"svg",
+	"png",
+	"jpg",
+	"jpeg",
+	"webp",
+	"tiff",
+	"pdf",
+	"eps",
+	SVG_SYMBOLS_FORMAT,
+	PDF_BOOK_FORMAT,
+];
+
+/// Returns the sketchtool `--format` of an export format (svg for svg-symbols, pdf for pdf-book).
+pub fn sketchtool_format(format: &str) -> &str {
+	match format {
+		SVG_SYMBOLS_FORMAT => "svg",
+		PDF_BOOK_FORMAT => "pdf",
+		format => format,
+	}
+}
+
+/// The default file name of the pdf-book output, when the output is a directory.
+const PDF_BOOK_FILE_NAME: &str = "book.pdf";
+
+/// The sketchtool arguments set from the plan, which `tool_args` cannot override.
+const RESERVED_TOOL_ARGS: &[&str] = &["--format", "--items", "--output", "--scales", "--use-id-for-name"];
+
+/// The formats `ExportOptions::padding` applies to.
+const PADDING_FORMATS: &[&str] = &["svg", "png", SVG_SYMBOLS_FORMAT];
+
+/// The raster formats, which `ExportOptions::background` applies to.
+pub const RASTER_FORMATS: &[&str] = &["png", "jpg", "jpeg", "webp", "tiff"];
+
+/// The formats `ExportOptions::tile` applies to.
+const TILE_FORMATS: &[&str] = &["png"];
+
+/// The format `ExportOptions::redline` applies to.
+const REDLINE_FORMAT: &str = "png";
+
+/// The directory of the redline copies, in the output directory (see `ExportOptions::redline`).
+pub const REDLINES_DIR: &str = "redlines";
+
+/// The raster formats without an alpha channel (no transparent background).
+const OPAQUE_FORMATS: &[&str] = &["jpg", "jpeg"];
+
+/// Cache directory name for raw exports before processing (see `cache_raw_export_dir`)
+const CACHE_RAW_EXPORT_DIR: &str = ".cache-raw-export";
+
+/// The transform of the files named after a sanitized artboard name.
+pub const SANITIZE_TRANSFORM: &str = "sanitize";
+
+/// The transform of the files named after the canonical artboard name (`--flatten`).
+pub const FLATTEN_TRANSFORM: &str = "flatten";
+
+/// The transform of the svg files with `ExportOptions::non_scaling_stroke`.
+pub const NON_SCALING_STROKE_TRANSFORM: &str = "non-scaling-stroke";
+
+/// The transform of the svg files with `ExportOptions::embed_images`.
+pub const EMBED_IMAGES_TRANSFORM: &str = "embed-images";
+
+/// The transform of the svg files with `ExportOptions::extract_images`.
+pub const EXTRACT_IMAGES_TRANSFORM: &str = "extract-images";
+
+/// The transforms of the svg files with `ExportOptions::local_refs` "strip" and "embed".
+pub const LOCAL_REFS_STRIP_TRANSFORM: &str = "local-refs:strip";
+pub const LOCAL_REFS_EMBED_TRANSFORM: &str = "local-refs:embed";
+
+/// The formats of the email-safe exports (see `ExportOptions::email_safe`).
+pub const EMAIL_SAFE_FORMATS: &[&str] = &["png", "jpg", "jpeg"];
+
+/// The default max display width of the email-safe images, in CSS px (the usual email body width).
+pub const DEFAULT_EMAIL_MAX_WIDTH: u32 = 600;
+
+/// What an export will do. Serialized as JSON for `sketch plan --json`.
+#[derive(Debug, Default, Serialize)]
+pub struct ExportPlan {
+	pub sketch_file: String,
+	/// The output path, with its tokens resolved.
+	pub output_path: String,
+	/// The number of artboards matched by the globs (and changed, with `changed_since`).
+	pub artboard_count: usize,
+	/// The number of artboards matched by the globs but unchanged since the `changed_since` ref (not exported).
+	pub unchanged_count: usize,
+	/// One sketchtool export per step (the svg-symbols step first).
+	pub steps: Vec<PlanStep>,
+	/// The warnings already known when planning (e.g., resolved name collisions).
+	pub warnings: Vec<Warning>,
+	/// The manifest mapping the flattened or sanitized file names back to their artboards.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub file_names_file: Option<String>,
+	/// The manifest relating each artboard UID to its name and output files (see `ExportOptions::uid_map`).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub uid_map_file: Option<String>,
+	/// The email snippet of the email-safe images (see `ExportOptions::email_snippet_out`).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub email_snippet_file: Option<String>,
+	/// The email-safe images, in export order.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub email_images: Vec<EmailImage>,
+	/// The CDN URLs of the exported files by logical name (see `ExportOptions::cdn_base`), written to `urls_file`.
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub asset_urls: BTreeMap<String, String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub urls_file: Option<String>,
+	/// The TypeScript module of the URLs.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub urls_ts_file: Option<String>,
+	#[serde(skip)]
+	pub options: ExportOptions,
+}
+
+/// One sketchtool export (one format) and what becomes of its files.
+#[derive(Debug, Serialize)]
+pub struct PlanStep {
+	pub format: String,
+	/// Where sketchtool writes the raw files.
+	pub export_dir: String,
+	/// The export dir is a cache directory (files are copied or converted from it, then it is deleted).
+	pub is_cache: bool,
+	/// Sketchtool names the files by artboard UID (`--use-id-for-name`), for artboard names not safe as file names.
+	pub use_id_for_name: bool,
+	/// The sketchtool `--scales` (empty for the sketchtool default).
+	pub scales: Vec<String>,
+	/// The raw sketchtool arguments of the step format (see `ExportOptions::tool_args`).
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub tool_args: Vec<String>,
+	/// The symbols manifest written next to the sprite (svg-symbols only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub manifest_file: Option<String>,
+	/// The TypeScript module of the symbol ids (svg-symbols only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub ts_file: Option<String>,
+	/// The Rust module of the symbol ids (svg-symbols only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub rust_file: Option<String>,
+	/// The TypeScript module of the path data of the single-path icons (svg-symbols only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub paths_ts_file: Option<String>,
+	/// The HTML file of the `<use>` snippets of the symbols (svg-symbols only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub use_snippets_file: Option<String>,
+	/// The usage docs of the icons, Markdown or HTML (svg-symbols only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub usage_file: Option<String>,
+	pub items: Vec<PlanItem>,
+}
+
+/// One artboard file of a step.
+#[derive(Debug, Serialize)]
+pub struct PlanItem {
+	pub artboard: Artboard,
+	/// The scale of the file (when `scales` are set).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub scale: Option<String>,
+	/// The file written by sketchtool (mirrors the artboard name, or its UID, in the export dir).
+	pub source_file: String,
+	/// The final file (the sprite file for svg-symbols).
+	pub target_file: String,
+	/// The `<symbol>` id (svg-symbols only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub symbol_id: Option<String>,
+	/// The `<symbol>` preserveAspectRatio (svg-symbols only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub preserve_aspect_ratio: Option<String>,
+	/// The `<symbol>` default size, a `SymbolSize` value (svg-symbols only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub symbol_size: Option<String>,
+	/// The redline copy of the file (png only, with `ExportOptions::redline`).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub redline_file: Option<String>,
+	/// The processing applied after the export, for display (e.g., "flatten", "sanitize", "symbol:#ico-user-fill", "check").
+	pub transforms: Vec<String>,
+}
+
+/// An email-safe image (see `ExportOptions::email_safe`).
+#[derive(Debug, Serialize)]
+pub struct EmailImage {
+	/// The file name, also its Content-ID (e.g., "hero-banner.png").
+	pub cid: String,
+	/// The artboard name.
+	pub alt: String,
+	/// The display size, in CSS px (the file size divided by the scale).
+	pub width: u32,
+	pub height: u32,
+}
+
+impl PlanItem {
+	/// Returns true if the file is not named after the artboard (flattened or sanitized name),
+	/// so it is recorded in the file names manifest.
+	pub fn has_mapped_file_name(&self) -> bool {
+		self.transforms
+			.iter()
+			.any(|t| t == FLATTEN_TRANSFORM || t == SANITIZE_TRANSFORM)
+	}
+}
+
+impl ExportPlan {
+	/// All the items, in execution order, with their format.
+	pub fn items(&self) -> impl Iterator<Item = (&str, &PlanItem)> {
+		self.steps
+			.iter()
+			.flat_map(|step| step.items.iter().map(move |item| (step.format.as_str(), item)))
+	}
+}
+
+/// Resolves an export without running it: the matched artboards, the output path tokens,
+/// and the output file of each artboard × format × scale.
+/// Fails like `export_artboards` would before exporting (e.g., no artboards matched, invalid single file output).
+pub fn plan_export(
+	sketch_file: impl AsRef<SPath>,
+	glob_patterns: Option<&[&str]>,
+	formats: &[&str],
+	output_dir: impl AsRef<SPath>,
+	options: &ExportOptions,
+) -> Result<ExportPlan> {
+	let sketch_file = sketch_file.as_ref();
+
+	files::check_file_exists(sketch_file)?;
+	let tool_arg_rules = parse_tool_args(&options.tool_args)?;
+	options.preserve_mtime()?;
+	options.chmod()?;
+	options.local_refs()?;
+	if options.padding.is_some()
+		&& let Some(format) = formats.iter().find(|format| !PADDING_FORMATS.contains(format))
+	{
+		return Err(Error::custom(format!(
+			"Padding is not supported for the '{format}' format. Supported: {}",
+			PADDING_FORMATS.join(", ")
+		)));
+	}
+	if let Some(tile) = options.tile {
+		if let Some(format) = formats.iter().find(|format| !TILE_FORMATS.contains(format)) {
+			return Err(Error::custom(format!(
+				"Tiles are not supported for the '{format}' format. Supported: {}",
+				TILE_FORMATS.join(", ")
+			)));
+		}
+		if tile == 0 || options.tile_overlap >= tile {
+			return Err(Error::custom(format!(
+				"Invalid tiles of {tile}px with an overlap of {}px. The overlap must be smaller than the tile size",
+				options.tile_overlap
+			)));
+		}
+	}
+	if options.embed_images && options.extract_images.is_some() {
+		return Err(Error::custom(
+			"The images cannot be both embedded and extracted (see --embed-images, --extract-images)",
+		));
+	}
+	if options.items_batch_size == Some(0) {
+		return Err(Error::custom("Invalid items batch size 0. It must be at least 1"));
+	}
+	if let Some(redline) = options.redline {
+		if !formats.contains(&REDLINE_FORMAT) {
+			return Err(Error::custom(format!("Redlines need the '{REDLINE_FORMAT}' format (see --redline)")));
+		}
+		if redline == 0 {
+			return Err(Error::custom("Invalid redline grid of 0px"));
+		}
+	}
+	if options.background()? == Some(Background::Transparent)
+		&& let Some(format) = formats.iter().find(|format| OPAQUE_FORMATS.contains(format))
+	{
+		return Err(Error::custom(format!(
+			"The '{format}' format has no transparency. Use a background color (e.g., --background '#ffffff')"
+		)));
+	}
+
+	// Resolve the output path tokens (e.g., "cdn/{date:%Y%m%d}-{git_short_sha}/")
+	let output_path = SPath::new(resolve_output_tokens(output_dir.as_ref().as_str(), sketch_file)?);
+
+	// Get artboards matching the glob patterns (all of them are kept for the near-miss suggestions)
+	let glob_scope = options.glob_scope()?;
+	let glob_case = options.glob_case();
+	let all_artboards = list_artboards(sketch_file, None, GlobScope::Name, GlobCase::Sensitive)?;
+	let glob_set = globs::build_glob_set_with_case(glob_patterns, glob_case)?;
+	let only_refs: Vec<&str> = options.only_globs.iter().map(|s| s.as_str()).collect();
+	let only_set = globs::build_glob_set_with_case(Some(&only_refs), glob_case)?;
+	let exclude_refs: Vec<&str> = options.exclude_globs.iter().map(|s| s.as_str()).collect();
+	let exclude_set = globs::build_glob_set_with_case(Some(&exclude_refs), glob_case)?;
+	let mut artboards: Vec<Artboard> = all_artboards
+		.iter()
+		.filter(|ab| globs::matches_glob_set(glob_set.as_ref(), &ab.glob_path(glob_scope)))
+		.filter(|ab| globs::matches_glob_set(only_set.as_ref(), &ab.glob_path(glob_scope)))
+		.filter(|ab| {
+			exclude_set
+				.as_ref()
+				.is_none_or(|set| !set.is_match(ab.glob_path(glob_scope).as_ref()))
+		})
+		.cloned()
+		.collect();
+
+	let mut plan = ExportPlan {
+		sketch_file: sketch_file.to_string(),
+		output_path: output_path.to_string(),
+		artboard_count: artboards.len(),
+		options: options.clone(),
+		..Default::default()
+	};
+
+	if artboards.is_empty() {
+		if options.allow_empty {
+			return Ok(plan);
+		}
+		let glob_patterns = glob_patterns.unwrap_or_default();
+		let paths: Vec<String> = all_artboards.iter().map(|ab| ab.glob_path(glob_scope).into_owned()).collect();
+		let names: Vec<&str> = paths.iter().map(String::as_str).collect();
+		return Err(Error::NoArtboardsMatched {
+			globs: glob_patterns.iter().map(|g| g.to_string()).collect(),
+			suggestions: suggest_artboard_names(glob_patterns, &names, 5),
+		});
+	}
+
+	// Only the artboards changed since the git ref (an empty plan when none changed is not a failure)
+	if let Some(git_ref) = &options.changed_since {
+		let changed_uids = changed_artboard_uids(sketch_file, git_ref)?;
+		artboards.retain(|artboard| changed_uids.contains(&artboard.uid));
+		plan.unchanged_count = plan.artboard_count - artboards.len();
+		plan.artboard_count = artboards.len();
+		if artboards.is_empty() {
+			return Ok(plan);
+		}
+	}
+
+	plan.warnings.extend(duplicate_name_warnings(&artboards));
+
+	// Separate svg-symbols and pdf-book from regular formats
+	let regular_formats: Vec<&str> = formats
+		.iter()
+		.filter(|f| **f != SVG_SYMBOLS_FORMAT && **f != PDF_BOOK_FORMAT)
+		.copied()
+		.collect();
+
+	if (options.ts_out.is_some() || options.rust_out.is_some() || options.paths_ts_out.is_some())
+		&& !formats.contains(&SVG_SYMBOLS_FORMAT)
+	{
+		return Err(Error::custom(format!(
+			"The TypeScript and Rust outputs require the '{SVG_SYMBOLS_FORMAT}' format (the names are the symbol ids)"
+		)));
+	}
+	if (options.use_snippets_out.is_some() || options.usage_out.is_some()) && !formats.contains(&SVG_SYMBOLS_FORMAT) {
+		return Err(Error::custom(format!(
+			"The <use> snippets and usage docs outputs require the '{SVG_SYMBOLS_FORMAT}' format"
+		)));
+	}
+	if let Some(usage_out) = &options.usage_out {
+		codegen::usage_docs_template(&SPath::new(usage_out))?;
+	}
+	check_email_safe(&regular_formats, formats, options)?;
+	if let Some(cdn_base) = &options.cdn_base
+		&& !http::is_url(cdn_base)
+	{
+		return Err(Error::custom(format!(
+			"Invalid CDN base '{cdn_base}'. Expected an http(s) URL (e.g., https://cdn.example.com/assets/)"
+		)));
+	}
+	if options.cdn_ts_out.is_some() && options.cdn_base.is_none() {
+		return Err(Error::custom("The TypeScript module of the URLs requires a CDN base (see --cdn-base)"));
+	}
+
+	if formats.contains(&SVG_SYMBOLS_FORMAT) {
+		let step = plan_svg_symbols(&artboards, &output_path, options, &mut plan.warnings)?;
+		plan.steps.push(step);
+	}
+
+	if formats.contains(&PDF_BOOK_FORMAT) {
+		plan.steps.push(plan_pdf_book(&artboards, &output_path, options));
+	}
+
+	if options.use_design_presets {
+		let steps =
+			plan_design_presets(sketch_file, &artboards, &regular_formats, &output_path, options, &mut plan.warnings)?;
+		plan.steps.extend(steps);
+	} else if options.email_safe {
+		let (steps, images) =
+			plan_email_formats(sketch_file, &artboards, &regular_formats, &output_path, options, &mut plan.warnings)?;
+		plan.steps.extend(steps);
+		plan.email_images = images;
+		plan.email_snippet_file = options.email_snippet_out.clone();
+	} else if !regular_formats.is_empty() {
+		let steps =
+			plan_regular_formats(&artboards, &regular_formats, &output_path, options, None, &mut plan.warnings)?;
+		plan.steps.extend(steps);
+	}
+
+	for step in &mut plan.steps {
+		step.tool_args = step_tool_args(&tool_arg_rules, &step.format);
+	}
+
+	if plan.items().any(|(_, item)| item.has_mapped_file_name()) {
+		plan.file_names_file = Some(output_path.join(FILE_NAMES_MANIFEST_FILE).to_string());
+	}
+
+	if options.uid_map {
+		let map_dir = if files::looks_like_file_path(&output_path) {
+			output_path.parent().unwrap_or_else(|| SPath::new(""))
+		} else {
+			output_path.clone()
+		};
+		plan.uid_map_file = Some(map_dir.join(UID_MAP_FILE).to_string());
+	}
+
+	if let Some(cdn_base) = &options.cdn_base {
+		let cdn_root = cdn_root_dir(output_dir.as_ref().as_str(), &output_path);
+		let mut url_warnings = Vec::new();
+		plan.asset_urls = plan_asset_urls(&plan, cdn_base, &cdn_root, &mut url_warnings)?;
+		plan.warnings.extend(url_warnings);
+		let urls_dir = if files::looks_like_file_path(&output_path) {
+			output_path.parent().unwrap_or_else(|| SPath::new(""))
+		} else {
+			output_path.clone()
+		};
+		plan.urls_file = Some(urls_dir.join(ASSET_URLS_FILE).to_string());
+		plan.urls_ts_file = options.cdn_ts_out.clone();
+	}
+
+	Ok(plan)
+}
+
+/// Plans the regular format steps from the export presets of the artboards (see `ExportOptions::use_design_presets`),
+/// one step per format and scales. The artboards without presets get the regular formats and scales.
+fn plan_design_presets(
+	sketch_file: &SPath,
+	artboards: &[Artboard],
+	formats: &[&str],
+	output_path: &SPath,
+	options: &ExportOptions,
+	warnings: &mut Vec<Warning>,
+) -> Result<Vec<PlanStep>> {
+	// A format and its scales (e.g., ("png", ["1", "2"]))
+	type FormatScales = (String, Vec<String>);
+	let presets = load_export_presets(sketch_file)?;
+
+	// The artboards by (format, scales), in the artboard order
+	let mut groups: Vec<(FormatScales, Vec<Artboard>)> = Vec::new();
+	for artboard in artboards {
+		let exports: Vec<FormatScales> = match presets.get(&artboard.uid) {
+			Some(artboard_presets) => {
+				let mut exports: Vec<FormatScales> = Vec::new();
+				for preset in artboard_presets {
+					let scale = preset.scale().filter(|_| EXPORT_FORMATS.contains(&preset.format.as_str()));
+					let Some(scale) = scale else {
+						warnings.push(Warning::for_artboard(
+							WarningCode::UnsupportedPreset,
+							&artboard.name,
+							format!(
+								"Export preset '{preset}' of artboard '{}' skipped (only the scale sizes of the sketchtool formats are supported)",
+								artboard.name
+							),
+						));
+						continue;
+					};
+					match exports.iter_mut().find(|(format, _)| *format == preset.format) {
+						Some((_, scales)) if !scales.contains(&scale) => scales.push(scale),
+						Some(_) => (),
+						None => exports.push((preset.format.clone(), vec![scale])),
+					}
+				}
+				exports
+			}
+			None => formats
+				.iter()
+				.map(|format| (format.to_string(), options.scales.clone()))
+				.collect(),
+		};
+		for export in exports {
+			match groups.iter_mut().find(|(group_export, _)| *group_export == export) {
+				Some((_, group)) => group.push(artboard.clone()),
+				None => groups.push((export, vec![artboard.clone()])),
+			}
+		}
+	}
+
+	let mut steps = Vec::new();
+	for ((format, scales), group) in groups {
+		let group_options = ExportOptions { scales, ..options.clone() };
+		steps.extend(plan_regular_formats(&group, &[format.as_str()], output_path, &group_options, None, warnings)?);
+	}
+	Ok(steps)
+}
+
+/// Returns the CDN URLs of the planned files by logical name: `<artboard name><scale suffix>.<format>`
+/// (e.g., "ico/user/fill@2x.png"), or the file name of the files of several artboards (svg-symbols, pdf-book).
+/// When artboards share a name, the first keeps the logical name, and a `duplicate-artboard-name` warning tells which
+/// files are not in the map.
+fn plan_asset_urls(
+	plan: &ExportPlan,
+	cdn_base: &str,
+	cdn_root: &SPath,
+	warnings: &mut Vec<Warning>,
+) -> Result<BTreeMap<String, String>> {
+	let mut urls = BTreeMap::new();
+	for (format, item) in plan.items() {
+		let target_file = SPath::new(&item.target_file);
+		let name = if format == SVG_SYMBOLS_FORMAT || format == PDF_BOOK_FORMAT {
+			target_file.name().to_string()
+		} else {
+			// The email-safe files have no scale suffix (their scale is fitted to the width)
+			let suffix = match &item.scale {
+				Some(scale) if !plan.options.email_safe => scale_suffix(scale),
+				_ => String::new(),
+			};
+			format!("{}{suffix}.{format}", item.artboard.name)
+		};
+		let url = cdn_url(cdn_base, cdn_root, &target_file).ok_or_else(|| {
+			Error::custom(format!("Cannot make the CDN URL of '{target_file}' (not under '{cdn_root}')"))
+		})?;
+		match urls.entry(name) {
+			Entry::Vacant(entry) => {
+				entry.insert(url);
+			}
+			Entry::Occupied(entry) => warnings.push(Warning::for_artboard(
+				WarningCode::DuplicateArtboardName,
+				&item.artboard.name,
+				format!(
+					"Artboards share the logical name '{}' of the CDN URL map: '{}' is mapped, '{url}' is not",
+					entry.key(),
+					entry.get()
+				),
+			)),
+		}
+	}
+	Ok(urls)
+}
+
+/// Checks the email-safe options (see `ExportOptions::email_safe`): the png and jpeg formats only, at most one scale,
+/// and not with the export presets. The email snippet requires the email-safe mode.
+fn check_email_safe(regular_formats: &[&str], formats: &[&str], options: &ExportOptions) -> Result<()> {
+	if !options.email_safe {
+		if options.email_snippet_out.is_some() || options.email_max_width.is_some() {
+			return Err(Error::custom(
+				"The email snippet and max width options require the email-safe mode (see --email-safe)",
+			));
+		}
+		return Ok(());
+	}
+	if let Some(format) = formats.iter().find(|format| !EMAIL_SAFE_FORMATS.contains(format)) {
+		return Err(Error::custom(format!(
+			"The '{format}' format is not email-safe. Supported: {}",
+			EMAIL_SAFE_FORMATS.join(", ")
+		)));
+	}
+	if regular_formats.is_empty() {
+		return Err(Error::custom(format!(
+			"The email-safe mode requires a raster format ({})",
+			EMAIL_SAFE_FORMATS.join(", ")
+		)));
+	}
+	if options.scales.len() > 1 {
+		return Err(Error::custom(format!(
+			"The email-safe mode exports a single scale, but {} scales specified",
+			options.scales.len()
+		)));
+	}
+	if options.use_design_presets {
+		return Err(Error::custom("The email-safe mode cannot use the design presets (see --use-design-presets)"));
+	}
+	if options.email_max_width == Some(0) {
+		return Err(Error::custom("Invalid email max width of 0px"));
+	}
+	if let Some(snippet_out) = &options.email_snippet_out {
+		codegen::email_snippet_template(&SPath::new(snippet_out))?;
+	}
+	Ok(())
+}
+
+/// Plans the email-safe format steps (see `ExportOptions::email_safe`), with the email images of the snippet.
+/// The artboards wider than the max width are exported at a scale fitting it (one step per format and scale),
+/// to flattened lowercase file names without scale suffix.
+fn plan_email_formats(
+	sketch_file: &SPath,
+	artboards: &[Artboard],
+	formats: &[&str],
+	output_path: &SPath,
+	options: &ExportOptions,
+	warnings: &mut Vec<Warning>,
+) -> Result<(Vec<PlanStep>, Vec<EmailImage>)> {
+	let max_width = options.email_max_width.unwrap_or(DEFAULT_EMAIL_MAX_WIDTH) as f64;
+	let scale = match options.scales.first() {
+		Some(scale) => scale
+			.trim()
+			.trim_end_matches('x')
+			.parse::<f64>()
+			.ok()
+			.filter(|scale| *scale > 0.0)
+			.ok_or_else(|| Error::custom(format!("Invalid email-safe scale '{scale}'. Expected a number (e.g., 2)")))?,
+		None => 1.0,
+	};
+	let frames: HashMap<String, (f64, f64)> = list_artboard_frames(sketch_file)?
+		.into_iter()
+		.map(|frame| (frame.artboard.uid, (frame.width, frame.height)))
+		.collect();
+
+	// The display sizes and the sketchtool scales, by artboard UID (the fit is floored to 2 decimals)
+	let mut display_sizes: HashMap<&str, (u32, u32)> = HashMap::new();
+	let mut fitted_scales: HashMap<String, String> = HashMap::new();
+	for artboard in artboards {
+		let (width, height) = frames.get(&artboard.uid).copied().unwrap_or((max_width, max_width));
+		let fit = if width > max_width { ((max_width / width * 100.0).floor() / 100.0).max(0.01) } else { 1.0 };
+		display_sizes.insert(&artboard.uid, ((width * fit).round() as u32, (height * fit).round() as u32));
+		fitted_scales.insert(artboard.uid.clone(), strings::format_number(scale * fit, 2));
+	}
+
+	let email_options = ExportOptions { flatten: true, ..options.clone() };
+	let steps = plan_regular_formats(artboards, formats, output_path, &email_options, Some(&fitted_scales), warnings)?;
+
+	let images = steps
+		.iter()
+		.flat_map(|step| &step.items)
+		.map(|item| {
+			let (width, height) = display_sizes.get(item.artboard.uid.as_str()).copied().unwrap_or_default();
+			EmailImage {
+				cid: SPath::new(&item.target_file).name().to_string(),
+				alt: item.artboard.name.clone(),
+				width,
+				height,
+			}
+		})
+		.collect();
+
+	Ok((steps, images))
+}
+
+/// Returns the raw export cache directory of an output path, in its parent directory
+/// (suffixed with `ExportOptions::cache_suffix`, e.g., ".cache-raw-export-icons").
+fn cache_raw_export_dir(output_path: &SPath, options: &ExportOptions) -> SPath {
+	let parent = output_path.parent().unwrap_or_else(|| SPath::new("."));
+	match &options.cache_suffix {
+		Some(suffix) => parent.join(format!("{CACHE_RAW_EXPORT_DIR}-{suffix}")),
+		None => parent.join(CACHE_RAW_EXPORT_DIR),
+	}
+}
+
+/// Plans the pdf-book step: each artboard exported to PDF, then merged as the pages of one file (in artboard order).
+fn plan_pdf_book(artboards: &[Artboard], output_path: &SPath, options: &ExportOptions) -> PlanStep {
+	let target_file = if files::looks_like_file_path(output_path) {
+		output_path.clone()
+	} else {
+		output_path.join(PDF_BOOK_FILE_NAME)
+	};
+
+	let export_dir = cache_raw_export_dir(&target_file, options);
+
+	let use_id_for_name = needs_id_for_name(artboards);
+
+	let items = artboards
+		.iter()
+		.enumerate()
+		.map(|(i, artboard)| {
+			let mut transforms = vec![format!("page:{}", i + 1)];
+			if options.pdf_bookmarks {
+				transforms.push("bookmark".to_string());
+			}
+			PlanItem {
+				artboard: artboard.clone(),
+				scale: None,
+				source_file: export_dir
+					.join(format!("{}.pdf", source_name(artboard, use_id_for_name)))
+					.to_string(),
+				target_file: target_file.to_string(),
+				symbol_id: None,
+				preserve_aspect_ratio: None,
+				symbol_size: None,
+				redline_file: None,
+				transforms,
+			}
+		})
+		.collect();
+
+	PlanStep {
+		format: PDF_BOOK_FORMAT.to_string(),
+		export_dir: export_dir.to_string(),
+		is_cache: true,
+		use_id_for_name,
+		scales: Vec::new(),
+		tool_args: Vec::new(),
+		manifest_file: None,
+		ts_file: None,
+		rust_file: None,
+		paths_ts_file: None,
+		use_snippets_file: None,
+		usage_file: None,
+		items,
+	}
+}
+
+/// Plans the svg-symbols step: all the artboards as symbols of one sprite file.
+fn plan_svg_symbols(
+	artboards: &[Artboard],
+	output_path: &SPath,
+	options: &ExportOptions,
+	warnings: &mut Vec<Warning>,
+) -> Result<PlanStep> {
+	let glob_case = options.glob_case();
+	let aspect_ratio_rules = parse_symbol_rules(&options.preserve_aspect_ratio, "preserveAspectRatio", glob_case)?;
+	let size_rules = parse_symbol_rules(&options.symbol_size, "symbol size", glob_case)?;
+	let glob_scope = options.glob_scope()?;
+	for (_, value) in &size_rules {
+		SymbolSize::try_from(value.as_str())?;
+	}
+
+	// Determine the target file path
+	let target_file = if files::looks_like_file_path(output_path) {
+		output_path.clone()
+	} else {
+		// It's a directory, use symbols.svg as filename
+		output_path.join("symbols.svg")
+	};
+
+	// Cache directory for the raw SVG exports
+	let export_dir = cache_raw_export_dir(&target_file, options);
+
+	let use_id_for_name = needs_id_for_name(artboards);
+
+	let mut taken_ids: HashSet<String> = HashSet::new();
+	let mut items = Vec::new();
+	for artboard in artboards {
+		let symbol_id = unique_artboard_name(artboard, &mut taken_ids, false, warnings);
+
+		let glob_path = artboard.glob_path(glob_scope);
+		let preserve_aspect_ratio = aspect_ratio_rules
+			.iter()
+			.find(|(glob_set, _)| globs::matches_glob_set(glob_set.as_ref(), &glob_path))
+			.map(|(_, value)| value.to_string());
+		let symbol_size = size_rules
+			.iter()
+			.find(|(glob_set, _)| globs::matches_glob_set(glob_set.as_ref(), &glob_path))
+			.map(|(_, value)| value.to_string());
+
+		let mut transforms: Vec<String> = options.padding.iter().map(|padding| format!("padding:{padding}")).collect();
+		if options.non_scaling_stroke {
+			transforms.push(NON_SCALING_STROKE_TRANSFORM.to_string());
+		}
+		if options.embed_images {
+			transforms.push(EMBED_IMAGES_TRANSFORM.to_string());
+		}
+		transforms.extend(local_refs_transform(options).map(str::to_string));
+		if options.extract_images.is_some() {
+			transforms.push(EXTRACT_IMAGES_TRANSFORM.to_string());
+		}
+		transforms.extend([
+			format!("symbol:#{symbol_id}"),
+			"scope-symbol-ids".to_string(),
+			"canonicalize-ids".to_string(),
+		]);
+		transforms.extend(
+			options
+				.strip_namespaces
+				.iter()
+				.map(|prefix| format!("strip-namespace:{prefix}")),
+		);
+		if let Some(value) = &preserve_aspect_ratio {
+			transforms.push(format!("preserve-aspect-ratio:{value}"));
+		}
+		if let Some(value) = &symbol_size {
+			transforms.push(format!("size:{value}"));
+		}
+		if options.symbol_data_attrs {
+			transforms.push("data-attrs".to_string());
+		}
+		transforms.push("check".to_string());
+
+		items.push(PlanItem {
+			artboard: artboard.clone(),
+			scale: None,
+			source_file: export_dir
+				.join(format!("{}.svg", source_name(artboard, use_id_for_name)))
+				.to_string(),
+			target_file: target_file.to_string(),
+			symbol_id: Some(symbol_id),
+			preserve_aspect_ratio,
+			symbol_size,
+			redline_file: None,
+			transforms,
+		});
+	}
+
+	let manifest_file = options
+		.symbols_manifest
+		.then(|| SymbolsManifest::path_for_sprite(&target_file).to_string());
+
+	if let Some(type_name) = &options.ts_type_name
+		&& !is_ts_identifier(type_name)
+	{
+		return Err(Error::custom(format!("Invalid TypeScript type name '{type_name}'")));
+	}
+
+	Ok(PlanStep {
+		format: SVG_SYMBOLS_FORMAT.to_string(),
+		export_dir: export_dir.to_string(),
+		is_cache: true,
+		use_id_for_name,
+		scales: Vec::new(),
+		tool_args: Vec::new(),
+		manifest_file,
+		ts_file: options.ts_out.clone(),
+		rust_file: options.rust_out.clone(),
+		paths_ts_file: options.paths_ts_out.clone(),
+		use_snippets_file: options.use_snippets_out.clone(),
+		usage_file: options.usage_out.clone(),
+		items,
+	})
+}
+
+/// Plans the regular format steps (svg, png, jpeg, ...), one per format.
+/// With `fitted_scales` (the scale of each artboard UID, see `plan_email_formats`), one per format and scale,
+/// and the target files have no scale suffix.
+fn plan_regular_formats(
+	artboards: &[Artboard],
+	formats: &[&str],
+	output_path: &SPath,
+	options: &ExportOptions,
+	fitted_scales: Option<&HashMap<String, String>>,
+	warnings: &mut Vec<Warning>,
+) -> Result<Vec<PlanStep>> {
+	let file_name = options.file_name.as_deref();
+	let background = options.background()?;
+
+	// Scales of the exported files, with their suffixes (e.g., "" for 1x, "@2x" for 2x)
+	let scales: Vec<(Option<&str>, String)> = if options.scales.is_empty() {
+		vec![(None, String::new())]
+	} else {
+		options
+			.scales
+			.iter()
+			.map(|scale| (Some(scale.as_str()), scale_suffix(scale)))
+			.collect()
+	};
+
+	// Determine if output is a single file target
+	let single_file_output = is_single_file_output(output_path, formats);
+
+	if file_name.is_some() && artboards.len() > 1 {
+		return Err(Error::custom(format!(
+			"An output file name is set, but {} artboards matched. It requires a single artboard.",
+			artboards.len()
+		)));
+	}
+
+	// Validate single file output constraints
+	if single_file_output {
+		if scales.len() > 1 {
+			return Err(Error::custom(format!(
+				"Output path '{}' is a file, but {} scales specified. Use a directory for multiple scales.",
+				output_path,
+				scales.len()
+			)));
+		}
+		if artboards.len() > 1 {
+			return Err(Error::custom(format!(
+				"Output path '{}' is a file, but {} artboards matched. Use a directory for multiple exports.",
+				output_path,
+				artboards.len()
+			)));
+		}
+		if formats.len() > 1 {
+			return Err(Error::custom(format!(
+				"Output path '{}' is a file, but {} formats specified. Use a directory for multiple formats.",
+				output_path,
+				formats.len()
+			)));
+		}
+	}
+
+	// Artboard names not safe as file names (e.g., "ico/a:b", emoji) or shared by several artboards are exported by UID,
+	// then copied to sanitized (and unique) names
+	let use_id_for_name = needs_id_for_name(artboards);
+
+	// Determine actual export directory (where sketchtool will write files)
+	// For single file output, flatten mode, or sanitized names, use a cache subdirectory to capture sketchtool's output
+	let is_cache = single_file_output || options.flatten || file_name.is_some() || use_id_for_name;
+	let export_dir = if is_cache { cache_raw_export_dir(output_path, options) } else { output_path.clone() };
+
+	// Target names are resolved once for all formats (so collisions are reported once)
+	let mut taken_names: HashSet<String> = HashSet::new();
+	let (target_names, rename_transform): (Vec<String>, Option<&str>) = if let Some(file_name) = file_name {
+		(vec![file_name.to_string()], Some("rename"))
+	} else if options.flatten && !single_file_output {
+		let names = artboards
+			.iter()
+			.map(|artboard| unique_artboard_name(artboard, &mut taken_names, options.email_safe, warnings))
+			.collect();
+		(names, Some(FLATTEN_TRANSFORM))
+	} else if use_id_for_name && !single_file_output {
+		// The safe names are taken first, so a sanitized name never takes the name of another artboard
+		taken_names.extend(
+			artboards
+				.iter()
+				.filter(|ab| !is_unsafe_file_name(&ab.name))
+				.map(|ab| ab.name.clone()),
+		);
+		// The first artboard of a shared name keeps it, the others get a suffix
+		let mut kept_names: HashSet<&str> = HashSet::new();
+		let names = artboards
+			.iter()
+			.map(|artboard| {
+				if !is_unsafe_file_name(&artboard.name) && kept_names.insert(&artboard.name) {
+					artboard.name.clone()
+				} else {
+					safe_artboard_file_name(artboard, &mut taken_names, warnings)
+				}
+			})
+			.collect();
+		(names, None)
+	} else {
+		(artboards.iter().map(|artboard| artboard.name.clone()).collect(), None)
+	};
+
+	let mut steps = Vec::new();
+	for format in formats {
+		let mut items = Vec::new();
+		for (artboard, target_name) in artboards.iter().zip(&target_names) {
+			let fitted_scale = fitted_scales.and_then(|fitted_scales| fitted_scales.get(&artboard.uid));
+			let artboard_scales = match fitted_scale {
+				Some(scale) => vec![(Some(scale.as_str()), scale_suffix(scale))],
+				None => scales.clone(),
+			};
+			for (scale, suffix) in &artboard_scales {
+				let source_file =
+					export_dir.join(format!("{}{suffix}.{format}", source_name(artboard, use_id_for_name)));
+				let target_suffix = if fitted_scale.is_some() { "" } else { suffix.as_str() };
+				let target_file = if single_file_output {
+					output_path.clone()
+				} else {
+					output_path.join(format!("{target_name}{target_suffix}.{format}"))
+				};
+
+				let mut transforms: Vec<String> = rename_transform.iter().map(|t| t.to_string()).collect();
+				if rename_transform.is_none() && !single_file_output && *target_name != artboard.name {
+					transforms.push(SANITIZE_TRANSFORM.to_string());
+				}
+				if let Some(padding) = options.padding {
+					transforms.push(format!("padding:{padding}"));
+				}
+				if *format == "svg" && options.non_scaling_stroke {
+					transforms.push(NON_SCALING_STROKE_TRANSFORM.to_string());
+				}
+				if *format == "svg" && options.embed_images {
+					transforms.push(EMBED_IMAGES_TRANSFORM.to_string());
+				}
+				if *format == "svg" {
+					transforms.extend(local_refs_transform(options).map(str::to_string));
+				}
+				if *format == "svg" && options.extract_images.is_some() {
+					transforms.push(EXTRACT_IMAGES_TRANSFORM.to_string());
+				}
+				if *format == "png"
+					&& let Some(Background::Color(color)) = background
+				{
+					transforms.push(format!("background:{}", color.to_hex()));
+				}
+				if let Some(tile) = options.tile {
+					transforms.push(format!("tile:{tile}+{}", options.tile_overlap));
+				}
+				let redline_file = options.redline.filter(|_| *format == REDLINE_FORMAT).map(|grid| {
+					transforms.push(format!("redline:{grid}"));
+					match output_path.parent().filter(|_| single_file_output) {
+						Some(parent) => parent.join(REDLINES_DIR).join(output_path.name()),
+						None => output_path
+							.join(REDLINES_DIR)
+							.join(format!("{target_name}{target_suffix}.{format}")),
+					}
+					.to_string()
+				});
+				if *format == "svg" {
+					transforms.push("check".to_string());
+				}
+
+				items.push(PlanItem {
+					artboard: artboard.clone(),
+					scale: scale.map(|s| s.to_string()),
+					source_file: source_file.to_string(),
+					target_file: target_file.to_string(),
+					symbol_id: None,
+					preserve_aspect_ratio: None,
+					symbol_size: None,
+					redline_file,
+					transforms,
+				});
+			}
+		}
+
+		// The fitted scales are exported by sketchtool one scale at a time
+		let mut step_items: Vec<(Vec<String>, Vec<PlanItem>)> = Vec::new();
+		if fitted_scales.is_some() {
+			for item in items {
+				let item_scales: Vec<String> = item.scale.iter().cloned().collect();
+				match step_items.iter_mut().find(|(scales, _)| *scales == item_scales) {
+					Some((_, group)) => group.push(item),
+					None => step_items.push((item_scales, vec![item])),
+				}
+			}
+		} else {
+			step_items.push((options.scales.clone(), items));
+		}
+
+		for (scales, items) in step_items {
+			steps.push(PlanStep {
+				format: format.to_string(),
+				export_dir: export_dir.to_string(),
+				is_cache,
+				use_id_for_name,
+				scales,
+				tool_args: Vec::new(),
+				manifest_file: None,
+				ts_file: None,
+				rust_file: None,
+				paths_ts_file: None,
+				use_snippets_file: None,
+				usage_file: None,
+				items,
+			});
+		}
+	}
+
+	Ok(steps)
+}
+
+/// Returns the canonical name of an artboard (symbol id or flattened file name), made unique among `taken`.
+/// A collision (e.g., "ico/user-fill" and "ico/user/fill") is resolved with a suffix and reported as a warning.
+/// An artboard name without ASCII letters or digits (e.g., "😀") falls back on its UID (e.g., "artboard-3f2a").
+/// With `lowercase`, the name is lowercased before the collisions are resolved (e.g., for the `cid:` references).
+fn unique_artboard_name(
+	artboard: &Artboard,
+	taken: &mut HashSet<String>,
+	lowercase: bool,
+	warnings: &mut Vec<Warning>,
+) -> String {
+	let mut canonical_name = strings::canonicalize_name(&artboard.name);
+	if lowercase {
+		canonical_name = canonical_name.to_lowercase();
+	}
+	if canonical_name.is_empty() {
+		canonical_name = format!("artboard-{}", strings::canonicalize_name(&artboard.uid).to_lowercase());
+	}
+	let name = strings::unique_name(&canonical_name, taken);
+	if name != canonical_name {
+		warnings.push(Warning::for_artboard(
+			WarningCode::IdCollisionResolved,
+			&artboard.name,
+			format!(
+				"Artboard '{}' collides with another artboard on '{canonical_name}', renamed to '{name}'",
+				artboard.name
+			),
+		));
+	}
+	name
+}
+
+/// Returns the file name of an artboard safe on all the platforms (see `files::sanitize_file_path`), made unique among `taken`.
+/// A collision (e.g., "ico/a:b" and "ico/a*b", or two artboards named "ico/a") is resolved with a suffix and reported
+/// as a warning.
+fn safe_artboard_file_name(artboard: &Artboard, taken: &mut HashSet<String>, warnings: &mut Vec<Warning>) -> String {
+	let safe_name = files::sanitize_file_path(&artboard.name);
+	let name = strings::unique_name(&safe_name, taken);
+	if name != safe_name {
+		warnings.push(Warning::for_artboard(
+			WarningCode::IdCollisionResolved,
+			&artboard.name,
+			format!(
+				"Artboard '{}' collides with another artboard on file name '{safe_name}', renamed to '{name}'",
+				artboard.name
+			),
+		));
+	}
+	name
+}
+
+/// Returns true if sketchtool must name the exported files by artboard UID: some artboard names are not safe as file
+/// names, or are shared by several artboards (their files would overwrite each other).
+fn needs_id_for_name(artboards: &[Artboard]) -> bool {
+	let mut names: HashSet<&str> = HashSet::new();
+	artboards
+		.iter()
+		.any(|artboard| is_unsafe_file_name(&artboard.name) || !names.insert(&artboard.name))
+}
+
+/// Returns a warning per artboard name shared by several artboards (with their UIDs), e.g., a copied artboard
+/// not renamed. Their exports are told apart by a suffix (see `safe_artboard_file_name` and `unique_artboard_name`).
+fn duplicate_name_warnings(artboards: &[Artboard]) -> Vec<Warning> {
+	let mut uids_by_name: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+	for artboard in artboards {
+		uids_by_name.entry(&artboard.name).or_default().push(&artboard.uid);
+	}
+
+	uids_by_name
+		.into_iter()
+		.filter(|(_, uids)| uids.len() > 1)
+		.map(|(name, uids)| {
+			Warning::for_artboard(
+				WarningCode::DuplicateArtboardName,
+				name,
+				format!(
+					"{} artboards are named '{name}' (UIDs: {}), the later ones are exported with a -2, -3, ... suffix",
+					uids.len(),
+					uids.join(", ")
+				),
+			)
+		})
+		.collect()
+}
+
+fn is_unsafe_file_name(name: &str) -> bool {
+	files::sanitize_file_path(name) != name
+}
+
+/// Returns the name of the file sketchtool writes for an artboard (its name, or its UID with `--use-id-for-name`).
+fn source_name(artboard: &Artboard, use_id_for_name: bool) -> &str {
+	if use_id_for_name { &artboard.uid } else { &artboard.name }
+}
+
+/// Parses the rules of a `<symbol>` attribute (`VALUE` or `GLOB=VALUE`), e.g., the `preserveAspectRatio` rules.
+fn parse_symbol_rules(specs: &[String], label: &str, glob_case: GlobCase) -> Result<Vec<(Option<GlobSet>, String)>> {
+	let mut rules = Vec::new();
+	for spec in specs {
+		let (glob, value) = match spec.rsplit_once('=') {
+			Some((glob, value)) => (Some(glob.trim()), value.trim()),
+			None => (None, spec.trim()),
+		};
+		if value.is_empty() {
+			return Err(Error::custom(format!("Invalid {label} rule '{spec}'. Expected VALUE or GLOB=VALUE")));
+		}
+		let glob_set = match glob {
+			Some(glob) => globs::build_glob_set_with_case(Some(&[glob]), glob_case)?,
+			None => None,
+		};
+		rules.push((glob_set, value.to_string()));
+	}
+	Ok(rules)
+}
+
+/// Parses the raw sketchtool arguments (`ARG` or `FORMAT:ARG`, e.g., "png:--background=#ffffff"),
+/// as (format, argument). The arguments must be flags, and not the ones set from the plan (e.g., `--output`).
+fn parse_tool_args(specs: &[String]) -> Result<Vec<(Option<String>, String)>> {
+	let mut rules = Vec::new();
+	for spec in specs {
+		let (format, arg) = match spec.split_once(':') {
+			Some((format, arg)) if EXPORT_FORMATS.contains(&format.trim()) => {
+				(Some(format.trim().to_string()), arg.trim())
+			}
+			_ => (None, spec.trim()),
+		};
+		if !arg.starts_with('-') {
+			return Err(Error::custom(format!(
+				"Invalid sketchtool argument '{spec}'. Expected ARG or FORMAT:ARG, with ARG a flag (e.g., --background=#ffffff)"
+			)));
+		}
+		let flag = arg.split('=').next().unwrap_or(arg);
+		if RESERVED_TOOL_ARGS.contains(&flag) {
+			return Err(Error::custom(format!(
+				"Invalid sketchtool argument '{spec}'. '{flag}' is set by webtk from the export options"
+			)));
+		}
+		rules.push((format, arg.to_string()));
+	}
+	Ok(rules)
+}
+
+/// Returns the arguments of the tool arg rules applying to a step: the unscoped ones, and the ones scoped to its format
+/// or to the sketchtool format it exports with (e.g., "svg:" also applies to svg-symbols, "pdf:" to pdf-book).
+fn step_tool_args(rules: &[(Option<String>, String)], step_format: &str) -> Vec<String> {
+	rules
+		.iter()
+		.filter(|(format, _)| {
+			format
+				.as_deref()
+				.is_none_or(|format| format == step_format || format == sketchtool_format(step_format))
+		})
+		.map(|(_, arg)| arg.clone())
+		.collect()
+}
+
+/// Returns true if the name is a valid TypeScript identifier (ASCII letters, digits, `_`, `$`, not starting with a digit).
+fn is_ts_identifier(name: &str) -> bool {
+	!name.is_empty()
+		&& !name.starts_with(|c: char| c.is_ascii_digit())
+		&& name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Returns the transform of the svg files for the `local_refs` policy (None for "warn" and "fail", which do not
+/// change the files). The policy is validated by `plan_export`.
+fn local_refs_transform(options: &ExportOptions) -> Option<&'static str> {
+	match options.local_refs().unwrap_or_default() {
+		LocalRefs::Strip => Some(LOCAL_REFS_STRIP_TRANSFORM),
+		LocalRefs::Embed => Some(LOCAL_REFS_EMBED_TRANSFORM),
+		LocalRefs::Warn | LocalRefs::Fail => None,
+	}
+}
+
+/// Returns the file name suffix sketchtool uses for a scale ("" for 1x, "@2x" for 2x).
+fn scale_suffix(scale: &str) -> String {
+	let scale = scale.trim().trim_end_matches('x');
+	match scale.parse::<f64>() {
+		Ok(1.0) => String::new(),
+		Ok(value) => format!("@{value}x"),
+		Err(_) => format!("@{scale}x"),
+	}
+}
+
+/// Checks if the output path appears to be a single file target.
+/// Returns true if the path ends with an extension matching one of the export formats.
+fn is_single_file_output(output_path: &SPath, formats: &[&str]) -> bool {
+	let ext = output_path.ext();
+	if ext.is_empty() {
+		return false;
+	}
+
+	// Check if the extension matches any of the export formats
+	let ext_lower = ext.to_lowercase();
+	formats.iter().any(|f| f.to_lowercase() == ext_lower)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_sketch_export_plan_step_tool_args_sketchtool_format() -> Result<()> {
+		// -- Setup & Fixtures
+		let specs = ["svg:--background=#fff", "pdf:--compact", "--save-for-web"].map(String::from);
+		let rules = parse_tool_args(&specs)?;
+
+		// -- Exec & Check
+		// svg-symbols exports with --format=svg, pdf-book with --format=pdf
+		assert_eq!(step_tool_args(&rules, SVG_SYMBOLS_FORMAT), ["--background=#fff", "--save-for-web"]);
+		assert_eq!(step_tool_args(&rules, PDF_BOOK_FORMAT), ["--compact", "--save-for-web"]);
+		assert_eq!(step_tool_args(&rules, "png"), ["--save-for-web"]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_sketch_export_plan_step_tool_args_step_format() -> Result<()> {
+		// -- Setup & Fixtures
+		let specs = ["svg-symbols:--background=#fff", "pdf-book:--compact"].map(String::from);
+		let rules = parse_tool_args(&specs)?;
+
+		// -- Exec & Check
+		assert_eq!(step_tool_args(&rules, SVG_SYMBOLS_FORMAT), ["--background=#fff"]);
+		assert_eq!(step_tool_args(&rules, PDF_BOOK_FORMAT), ["--compact"]);
+		assert!(step_tool_args(&rules, "svg").is_empty());
+		assert!(step_tool_args(&rules, "pdf").is_empty());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
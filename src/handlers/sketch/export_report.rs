@@ -0,0 +1,115 @@
+use crate::support::files;
+use crate::{Error, Result, Warning};
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The result of `export_artboards`: the written files and the warnings found along the way.
+#[derive(Debug, Default, Serialize)]
+pub struct ExportReport {
+	/// The number of artboards matched by the globs (and changed, with `ExportOptions::changed_since`).
+	pub artboard_count: usize,
+	/// The number of artboards skipped as unchanged since the `ExportOptions::changed_since` ref.
+	pub unchanged_count: usize,
+	pub exported_files: Vec<String>,
+	pub warnings: Vec<Warning>,
+	/// The sets of artboards rendering identically (from the exported SVGs).
+	pub duplicates: Vec<Vec<String>>,
+}
+
+/// The exported files of one format (extension), for the export summary.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportFileGroup {
+	/// The file extension (e.g., "svg", "png", "json").
+	pub format: String,
+	pub file_count: usize,
+	pub total_bytes: u64,
+	/// The deepest directory containing all the files of the group.
+	pub dir: String,
+}
+
+/// When an export outcome should fail (`--fail-on`).
+/// - `Empty`: fail when no artboards matched (see `ExportOptions::allow_empty`)
+/// - `Warning`: fail when no artboards matched or when there are warnings
+/// - `Never`: never fail on those outcomes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailOn {
+	Empty,
+	Warning,
+	Never,
+}
+
+impl TryFrom<&str> for FailOn {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"empty" => Ok(Self::Empty),
+			"warning" => Ok(Self::Warning),
+			"never" => Ok(Self::Never),
+			other => Err(Error::custom(format!("Invalid fail-on policy '{other}'. Expected: empty, warning, never"))),
+		}
+	}
+}
+
+impl ExportReport {
+	/// Groups the exported files by format (extension, in order of first appearance), with their size on disk.
+	pub fn file_groups(&self) -> Vec<ExportFileGroup> {
+		let mut groups: Vec<(String, Vec<&str>)> = Vec::new();
+		for file in &self.exported_files {
+			let format = SPath::new(file).ext().to_lowercase();
+			let format = if format.is_empty() { "(none)".to_string() } else { format };
+			match groups.iter_mut().find(|(group_format, _)| *group_format == format) {
+				Some((_, files)) => files.push(file),
+				None => groups.push((format, vec![file])),
+			}
+		}
+
+		groups
+			.into_iter()
+			.map(|(format, files)| ExportFileGroup {
+				file_count: files.len(),
+				total_bytes: files
+					.iter()
+					.filter_map(|file| std::fs::metadata(file).ok())
+					.map(|meta| meta.len())
+					.sum(),
+				dir: files::common_dir(&files),
+				format,
+			})
+			.collect()
+	}
+
+	/// Returns `Error::ValidationFailed` if the report has warnings and `fail_on` is `Warning`.
+	/// (No artboards matched is reported by `export_artboards` itself, unless `allow_empty`.)
+	pub fn check_fail_on(&self, fail_on: FailOn) -> Result<()> {
+		if fail_on == FailOn::Warning && !self.warnings.is_empty() {
+			return Err(Error::ValidationFailed(format!("{} warning(s) found", self.warnings.len())));
+		}
+
+		Ok(())
+	}
+
+	/// Returns `Error::ValidationFailed` if artboards render identically (`--deny-duplicates`).
+	pub fn check_duplicates(&self) -> Result<()> {
+		if !self.duplicates.is_empty() {
+			return Err(Error::ValidationFailed(format!(
+				"{} set(s) of artboards render identically",
+				self.duplicates.len()
+			)));
+		}
+
+		Ok(())
+	}
+
+	/// Writes the report as JSON (e.g., for CI).
+	pub fn write(&self, report_file: &SPath) -> Result<()> {
+		if let Some(parent) = report_file.parent() {
+			simple_fs::ensure_dir(parent.as_std_path())
+				.map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+		}
+		let content = serde_json::to_string_pretty(self)?;
+		std::fs::write(report_file.as_std_path(), format!("{content}\n"))
+			.map_err(|e| format!("Failed to write export report '{}': {e}", report_file))?;
+		Ok(())
+	}
+}
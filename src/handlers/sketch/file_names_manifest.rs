@@ -0,0 +1,33 @@
+use crate::Result;
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The file name of the manifest written in the output directory when files are not named after their artboards
+/// (flattened or sanitized names).
+pub const FILE_NAMES_MANIFEST_FILE: &str = "file-names.json";
+
+/// The `file-names.json` manifest, mapping the file names back to their artboards (e.g., "ico/a_b.svg" -> "ico/a:b",
+/// "a-b-c-2.svg" -> "a/b-c"), since neither the sanitization nor the flattening can be reversed from the file name alone.
+#[derive(Debug, Default, Serialize)]
+pub struct FileNamesManifest {
+	pub files: Vec<FileNameEntry>,
+}
+
+/// An exported file with a flattened or sanitized name.
+#[derive(Debug, Serialize)]
+pub struct FileNameEntry {
+	/// The file path, relative to the manifest directory.
+	pub file: String,
+	/// The artboard name.
+	pub name: String,
+	pub uid: String,
+}
+
+impl FileNamesManifest {
+	pub fn write(&self, manifest_file: &SPath) -> Result<()> {
+		let content = serde_json::to_string_pretty(self)?;
+		std::fs::write(manifest_file.as_std_path(), format!("{content}\n"))
+			.map_err(|e| format!("Failed to write file names manifest '{}': {e}", manifest_file))?;
+		Ok(())
+	}
+}
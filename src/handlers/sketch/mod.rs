@@ -1,11 +1,63 @@
 // region:    --- Modules
 
 mod artboard;
+mod asset_urls;
+mod export_checks;
+mod export_options;
+mod export_plan;
+mod export_report;
+mod file_names_manifest;
+mod output_tokens;
+mod sketch_archive;
+mod sketch_changes;
+mod sketch_contact_sheet;
+mod sketch_copy;
+mod sketch_document;
 mod sketch_export;
+mod sketch_flows;
+mod sketch_fonts;
+mod sketch_lint;
 mod sketch_list;
+mod sketch_presets;
+mod sketch_rename;
+mod sketch_sort;
+mod sketch_source;
+mod sketch_spec;
+mod sketch_stats;
+mod symbols_manifest;
+mod symbols_usage;
+mod tiles_manifest;
+mod tool_runner;
+mod uid_map;
 
 pub use artboard::*;
+pub use asset_urls::*;
+pub use export_checks::*;
+pub use export_options::*;
+pub use export_plan::*;
+pub use export_report::*;
+pub use file_names_manifest::*;
+pub use output_tokens::*;
+pub use sketch_archive::*;
+pub use sketch_changes::*;
+pub use sketch_contact_sheet::*;
+pub use sketch_copy::*;
+pub use sketch_document::*;
 pub use sketch_export::*;
+pub use sketch_flows::*;
+pub use sketch_fonts::*;
+pub use sketch_lint::*;
 pub use sketch_list::*;
+pub use sketch_presets::*;
+pub use sketch_rename::*;
+pub use sketch_sort::*;
+pub use sketch_source::*;
+pub use sketch_spec::*;
+pub use sketch_stats::*;
+pub use symbols_manifest::*;
+pub use symbols_usage::*;
+pub use tiles_manifest::*;
+pub use tool_runner::*;
+pub use uid_map::*;
 
 // endregion: --- Modules
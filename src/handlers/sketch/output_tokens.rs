@@ -0,0 +1,62 @@
+//! Output path tokens resolved at run time (e.g., "cdn/{date:%Y-%m-%d}-{git_short_sha}/").
+
+use crate::handlers::sketch::sketch_app_version;
+use crate::support::{dates, tokens};
+use crate::{Error, Result};
+use simple_fs::SPath;
+use std::process::Command;
+
+/// Resolves the tokens of an output path:
+/// - `{git_sha}`, `{git_short_sha}`: the HEAD commit of the git repository of the Sketch file
+/// - `{date}` (as `%Y-%m-%d`) or `{date:FORMAT}` (`%Y %m %d %H %M %S`): the current UTC date
+///   (or `SOURCE_DATE_EPOCH` when set)
+/// - `{sketch_version}`: the Sketch version that saved the file
+/// - `{sketch_name}`: the Sketch file name without extension
+///
+/// Values are resolved once per path, and only when used.
+pub fn resolve_output_tokens(path: &str, sketch_file: &SPath) -> Result<String> {
+	if !tokens::has_tokens(path) {
+		return Ok(path.to_string());
+	}
+
+	let now = dates::now_unix_secs();
+	let repo_dir = sketch_file.parent().unwrap_or_else(|| SPath::new("."));
+
+	tokens::render_tokens(path, |name, arg| {
+		let value = match (name, arg) {
+			("git_sha", None) => git_rev_parse(&repo_dir, false)?,
+			("git_short_sha", None) => git_rev_parse(&repo_dir, true)?,
+			("date", None) => dates::format_utc_date(now, "%Y-%m-%d"),
+			("date", Some(format)) => dates::format_utc_date(now, format),
+			("sketch_version", None) => sketch_app_version(sketch_file)?,
+			("sketch_name", None) => sketch_file.stem().to_string(),
+			_ => return Ok(None),
+		};
+		Ok(Some(value))
+	})
+}
+
+/// Returns the HEAD commit sha (short or full) of the git repository containing `dir`.
+fn git_rev_parse(dir: &SPath, short: bool) -> Result<String> {
+	let dir = if dir.as_str().is_empty() { "." } else { dir.as_str() };
+	let mut args = vec!["-C", dir, "rev-parse"];
+	if short {
+		args.push("--short");
+	}
+	args.push("HEAD");
+
+	let output = Command::new("git")
+		.args(&args)
+		.output()
+		.map_err(|e| Error::tool_exec("git", "git", e))?;
+
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!(
+			"Cannot resolve the git sha for '{dir}' (output path token). Cause: {}",
+			stderr.trim()
+		)));
+	}
+
+	Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,84 @@
+//! Native reading and writing of a Sketch file, without sketchtool: a zip of JSON files (`document.json`,
+//! `meta.json`, `user.json`, `pages/<page id>.json`) and images, to edit the document JSON in place.
+
+use crate::support::zips::{self, ZipEntry};
+use crate::{Error, Result};
+use serde_json::Value;
+use simple_fs::SPath;
+
+/// The Sketch file metadata, with the page and artboard names (`pagesAndArtboards`).
+pub const SKETCH_META_FILE: &str = "meta.json";
+
+const SKETCH_PAGES_DIR: &str = "pages/";
+
+/// The files of a Sketch file. The files not written back are saved unchanged.
+#[derive(Debug)]
+pub struct SketchArchive {
+	entries: Vec<ZipEntry>,
+}
+
+impl SketchArchive {
+	pub fn open(sketch_file: &SPath) -> Result<Self> {
+		let bytes =
+			std::fs::read(sketch_file.as_std_path()).map_err(|e| format!("Failed to read '{sketch_file}': {e}"))?;
+		let entries = zips::read_zip(&bytes).map_err(|err| {
+			Error::custom(format!("Cannot read '{sketch_file}' (a Sketch file is a zip archive). Cause: {err}"))
+		})?;
+		Ok(Self { entries })
+	}
+
+	/// The page files (e.g., "pages/3F0A....json"), in archive order.
+	pub fn page_files(&self) -> Vec<String> {
+		self.entries
+			.iter()
+			.filter(|entry| entry.name.starts_with(SKETCH_PAGES_DIR) && entry.name.ends_with(".json"))
+			.map(|entry| entry.name.clone())
+			.collect()
+	}
+
+	pub fn read_json(&self, name: &str) -> Result<Value> {
+		let entry = self
+			.entries
+			.iter()
+			.find(|entry| entry.name == name)
+			.ok_or_else(|| Error::custom(format!("File '{name}' not found in the Sketch file")))?;
+		serde_json::from_slice(&entry.content()?).map_err(|e| Error::custom(format!("Invalid JSON of '{name}': {e}")))
+	}
+
+	/// Replaces the content of an existing file.
+	pub fn write_json(&mut self, name: &str, value: &Value) -> Result<()> {
+		let entry = self
+			.entries
+			.iter_mut()
+			.find(|entry| entry.name == name)
+			.ok_or_else(|| Error::custom(format!("File '{name}' not found in the Sketch file")))?;
+		entry.set_content(&serde_json::to_vec(value)?)
+	}
+
+	/// Writes the Sketch file (to a `.part` file first, then renamed over the file).
+	pub fn save(&self, sketch_file: &SPath) -> Result<()> {
+		let bytes = zips::write_zip(&self.entries)?;
+		let part_file = SPath::new(format!("{sketch_file}.part"));
+		std::fs::write(part_file.as_std_path(), bytes).map_err(|e| format!("Failed to write '{part_file}': {e}"))?;
+		std::fs::rename(part_file.as_std_path(), sketch_file.as_std_path())
+			.map_err(|e| format!("Failed to write '{sketch_file}': {e}"))?;
+		Ok(())
+	}
+}
+
+/// Copies a Sketch file to `<stem>.backup.sketch` next to it (`<stem>.backup-2.sketch`, ... when taken), before an
+/// in-place edit. Returns the backup file.
+pub fn backup_sketch_file(sketch_file: &SPath) -> Result<SPath> {
+	let dir = sketch_file.parent().unwrap_or_else(|| SPath::new(""));
+	let (stem, ext) = (sketch_file.stem(), sketch_file.ext());
+	let mut backup_file = dir.join(format!("{stem}.backup.{ext}"));
+	let mut n = 2;
+	while backup_file.exists() {
+		backup_file = dir.join(format!("{stem}.backup-{n}.{ext}"));
+		n += 1;
+	}
+
+	std::fs::copy(sketch_file.as_std_path(), backup_file.as_std_path())
+		.map_err(|e| format!("Failed to write the backup '{backup_file}': {e}"))?;
+	Ok(backup_file)
+}
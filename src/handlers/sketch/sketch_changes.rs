@@ -0,0 +1,159 @@
+//! The artboards changed since a git ref (`--changed-since origin/main`), by comparing the document JSON
+//! of the working Sketch file with the one of the file at the ref (from `git show`).
+//!
+//! An artboard is changed when its layers changed, or the layers of a symbol it uses (transitively).
+//! Changes of shared styles or library files are not detected.
+
+use crate::handlers::sketch::{
+	CLASS_ARTBOARD, CLASS_SYMBOL_INSTANCE, CLASS_SYMBOL_MASTER, child_layers, document_pages, layer_class, layer_str,
+	load_sketch_document, walk_layers,
+};
+use crate::support::hashes;
+use crate::{Error, Result};
+use serde_json::Value;
+use simple_fs::SPath;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::process::Command;
+
+/// Returns the UIDs of the artboards (and symbol masters) of the Sketch file that are new or changed since `git_ref`.
+/// All of them when the file is not in the ref (e.g., a new file).
+pub fn changed_artboard_uids(sketch_file: impl AsRef<SPath>, git_ref: &str) -> Result<HashSet<String>> {
+	let sketch_file = sketch_file.as_ref();
+
+	let current = artboard_fingerprints(&load_sketch_document(sketch_file)?);
+	let previous = match load_sketch_document_at_ref(sketch_file, git_ref)? {
+		Some(document) => artboard_fingerprints(&document),
+		None => HashMap::new(),
+	};
+
+	let changed = current
+		.into_iter()
+		.filter(|(uid, fingerprint)| previous.get(uid) != Some(fingerprint))
+		.map(|(uid, _)| uid)
+		.collect();
+
+	Ok(changed)
+}
+
+/// Returns the fingerprint of each artboard and symbol master of a document, by UID (`do_objectID`).
+/// The fingerprint covers the layers of the artboard and of the symbols it uses (transitively).
+pub fn artboard_fingerprints(document: &Value) -> HashMap<String, String> {
+	// -- The symbol masters by symbol id (document and library symbols)
+	let mut masters: HashMap<&str, &Value> = HashMap::new();
+	for page in document_pages(document) {
+		for layer in child_layers(page) {
+			if layer_class(layer) == Some(CLASS_SYMBOL_MASTER)
+				&& let Some(symbol_id) = layer_str(layer, "symbolID")
+			{
+				masters.insert(symbol_id, layer);
+			}
+		}
+	}
+	let foreign_symbols = document.pointer("/document/foreignSymbols").and_then(Value::as_array);
+	for master in foreign_symbols
+		.into_iter()
+		.flatten()
+		.filter_map(|foreign| foreign.get("symbolMaster"))
+	{
+		if let Some(symbol_id) = layer_str(master, "symbolID") {
+			masters.entry(symbol_id).or_insert(master);
+		}
+	}
+
+	// -- The fingerprint of each artboard
+	let mut fingerprints = HashMap::new();
+	for page in document_pages(document) {
+		for layer in child_layers(page) {
+			let class = layer_class(layer);
+			if class != Some(CLASS_ARTBOARD) && class != Some(CLASS_SYMBOL_MASTER) {
+				continue;
+			}
+			let Some(uid) = layer_str(layer, "do_objectID") else {
+				continue;
+			};
+
+			let mut symbol_ids = BTreeSet::new();
+			collect_symbol_ids(layer, &masters, &mut symbol_ids);
+
+			let mut content = sorted_json(layer);
+			for symbol_id in symbol_ids {
+				if let Some(master) = masters.get(symbol_id) {
+					content.push_str(&sorted_json(master));
+				}
+			}
+			fingerprints.insert(uid.to_string(), hashes::fnv1a_64_hex(content));
+		}
+	}
+
+	fingerprints
+}
+
+/// Collects the ids of the symbols used by a layer, and by those symbols (transitively).
+fn collect_symbol_ids<'a>(layer: &'a Value, masters: &HashMap<&str, &'a Value>, symbol_ids: &mut BTreeSet<&'a str>) {
+	walk_layers(layer, &mut |child| {
+		if layer_class(child) == Some(CLASS_SYMBOL_INSTANCE)
+			&& let Some(symbol_id) = layer_str(child, "symbolID")
+			&& symbol_ids.insert(symbol_id)
+			&& let Some(master) = masters.get(symbol_id)
+		{
+			collect_symbol_ids(master, masters, symbol_ids);
+		}
+	});
+}
+
+/// Returns the JSON of a value with its keys sorted, so a key order change is not a change.
+fn sorted_json(value: &Value) -> String {
+	let mut value = value.clone();
+	value.sort_all_objects();
+	value.to_string()
+}
+
+/// Loads the document JSON of the Sketch file as it is at `git_ref` (None if the file is not in the ref).
+fn load_sketch_document_at_ref(sketch_file: &SPath, git_ref: &str) -> Result<Option<Value>> {
+	let repo_dir = sketch_file.parent().unwrap_or_else(|| SPath::new("."));
+	let file_name = sketch_file.name();
+
+	// -- Fail on an unknown ref (rather than treating the file as new)
+	let output = git_command(&repo_dir)
+		.args(["rev-parse", "--verify", "--quiet", &format!("{git_ref}^{{commit}}")])
+		.output()
+		.map_err(|e| Error::tool_exec("git", "git", e))?;
+	if !output.status.success() {
+		return Err(Error::custom(format!(
+			"Unknown git ref '{git_ref}' (--changed-since) in the repository of '{sketch_file}'"
+		)));
+	}
+
+	// -- The file content at the ref (`./` for a path relative to the sketch file directory)
+	let output = git_command(&repo_dir)
+		.args(["show", &format!("{git_ref}:./{file_name}")])
+		.output()
+		.map_err(|e| Error::tool_exec("git", "git", e))?;
+	if !output.status.success() {
+		return Ok(None);
+	}
+	if output.stdout.starts_with(b"version https://git-lfs") {
+		return Err(Error::custom(format!(
+			"'{sketch_file}' is a Git LFS pointer at '{git_ref}', --changed-since requires the file content in git"
+		)));
+	}
+
+	// -- sketchtool reads a file, so the content is written to a temporary one
+	let tmp_file = SPath::from_std_path_buf(
+		std::env::temp_dir().join(format!("webtk-changed-since-{}-{file_name}", std::process::id())),
+	)
+	.map_err(Error::custom_from_err)?;
+	std::fs::write(tmp_file.as_std_path(), &output.stdout)
+		.map_err(|e| format!("Failed to write temporary file '{tmp_file}': {e}"))?;
+	let document = load_sketch_document(&tmp_file);
+	let _ = std::fs::remove_file(tmp_file.as_std_path());
+
+	document.map(Some)
+}
+
+fn git_command(repo_dir: &SPath) -> Command {
+	let repo_dir = if repo_dir.as_str().is_empty() { "." } else { repo_dir.as_str() };
+	let mut command = Command::new("git");
+	command.args(["-C", repo_dir]);
+	command
+}
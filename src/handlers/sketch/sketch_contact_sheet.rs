@@ -0,0 +1,162 @@
+//! The contact sheet of artboards (for offline review and printing): their thumbnails (png exports) with their
+//! names, laid out in a grid on the A4 pages of a PDF file.
+
+use crate::handlers::pdf;
+use crate::handlers::raster::{read_png, render_svg};
+use crate::handlers::sketch::{ExportOptions, execute_export_plan, plan_export};
+use crate::support::files;
+use crate::{Error, Result};
+use quick_xml::escape::escape;
+use resvg::tiny_skia::{FilterQuality, Pixmap, PixmapPaint, Transform};
+use simple_fs::SPath;
+
+/// The directory of the thumbnails (deleted once the sheet is written), next to the PDF file.
+const CONTACT_SHEET_CACHE_DIR: &str = ".cache-contact-sheet";
+
+/// The page size (A4 portrait), in pt.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+
+/// The page margin, and the space between the cells, in pt.
+const PAGE_MARGIN: f32 = 36.0;
+const CELL_GAP: f32 = 12.0;
+
+/// The ratio of the thumbnail box height to the cell width.
+const THUMBNAIL_BOX_RATIO: f32 = 0.75;
+
+/// The height of the name below a thumbnail, and its font size, in pt.
+const LABEL_HEIGHT: f32 = 16.0;
+const LABEL_FONT_SIZE: f32 = 8.0;
+
+/// The fonts of the names (the first installed one, the generic "sans-serif" being Arial for the rasterizer).
+const LABEL_FONTS: &str = "Helvetica, Arial, 'DejaVu Sans', sans-serif";
+
+/// The resolution of the pages, in px per pt (144 dpi), also the scale of the thumbnail exports.
+const RENDER_SCALE: f32 = 2.0;
+
+/// The file written by `generate_contact_sheet`.
+#[derive(Debug)]
+pub struct ContactSheetReport {
+	pub file: String,
+	pub artboard_count: usize,
+	pub page_count: usize,
+}
+
+/// Writes the contact sheet PDF of the artboards of a Sketch file matching the globs (all when None), in export
+/// order, `columns` thumbnails per row. The thumbnails are scaled down to fit their cell (never up).
+pub fn generate_contact_sheet(
+	sketch_file: impl AsRef<SPath>,
+	glob_patterns: Option<&[&str]>,
+	target_file: impl AsRef<SPath>,
+	columns: u32,
+) -> Result<ContactSheetReport> {
+	let sketch_file = sketch_file.as_ref();
+	let target_file = target_file.as_ref();
+	if columns == 0 {
+		return Err(Error::custom("Invalid contact sheet of 0 columns"));
+	}
+
+	// -- Render the thumbnails
+	let cache_dir = target_file
+		.parent()
+		.unwrap_or_else(|| SPath::new("."))
+		.join(CONTACT_SHEET_CACHE_DIR);
+	let options = ExportOptions { flatten: true, scales: vec![RENDER_SCALE.to_string()], ..Default::default() };
+	let plan = plan_export(sketch_file, glob_patterns, &["png"], &cache_dir, &options)?;
+	let result = execute_export_plan(&plan).and_then(|_| {
+		let thumbnails = plan
+			.items()
+			.map(|(_, item)| Ok((item.artboard.name.clone(), read_png(&SPath::new(&item.target_file))?)))
+			.collect::<Result<Vec<(String, Pixmap)>>>()?;
+		render_contact_sheet_pages(&thumbnails, columns, sketch_file.name()).map(|pages| (thumbnails.len(), pages))
+	});
+	let _ = files::safer_delete_dir(&cache_dir);
+	let (artboard_count, pages) = result?;
+
+	// -- Write the PDF
+	let page_count = pdf::write_image_pdf(&pages, PAGE_WIDTH, PAGE_HEIGHT, target_file)?;
+
+	Ok(ContactSheetReport { file: target_file.to_string(), artboard_count, page_count })
+}
+
+/// Renders the pages of the thumbnails (name, image at `RENDER_SCALE`), with a footer of the title and page number.
+fn render_contact_sheet_pages(thumbnails: &[(String, Pixmap)], columns: u32, title: &str) -> Result<Vec<Pixmap>> {
+	let cell_width = (PAGE_WIDTH - 2.0 * PAGE_MARGIN - (columns - 1) as f32 * CELL_GAP) / columns as f32;
+	if cell_width < LABEL_HEIGHT {
+		return Err(Error::custom(format!("Too many contact sheet columns ({columns}) for the page width")));
+	}
+	let box_height = cell_width * THUMBNAIL_BOX_RATIO;
+	let cell_height = box_height + LABEL_HEIGHT;
+	// The footer is in the bottom margin
+	let rows = (((PAGE_HEIGHT - 2.0 * PAGE_MARGIN + CELL_GAP) / (cell_height + CELL_GAP)) as usize).max(1);
+	let per_page = rows * columns as usize;
+	let page_count = thumbnails.len().div_ceil(per_page);
+	// The label width is estimated from the char count (the fonts are not measured)
+	let max_label_chars = (cell_width / (LABEL_FONT_SIZE * 0.55)) as usize;
+
+	let mut pages = Vec::new();
+	for (page_idx, page_thumbnails) in thumbnails.chunks(per_page).enumerate() {
+		let cells: Vec<(f32, f32)> = (0..page_thumbnails.len())
+			.map(|idx| {
+				let (row, column) = (idx / columns as usize, idx % columns as usize);
+				let x = PAGE_MARGIN + column as f32 * (cell_width + CELL_GAP);
+				let y = PAGE_MARGIN + row as f32 * (cell_height + CELL_GAP);
+				(x, y)
+			})
+			.collect();
+
+		// -- The layout (boxes, names, and footer)
+		let mut svg = format!(
+			r##"<svg xmlns="http://www.w3.org/2000/svg" width="{PAGE_WIDTH}" height="{PAGE_HEIGHT}" viewBox="0 0 {PAGE_WIDTH} {PAGE_HEIGHT}">
+<rect width="{PAGE_WIDTH}" height="{PAGE_HEIGHT}" fill="#ffffff"/>
+<g font-family="{LABEL_FONTS}" font-size="{LABEL_FONT_SIZE}" fill="#333333">
+"##
+		);
+		for ((name, _), (x, y)) in page_thumbnails.iter().zip(&cells) {
+			svg.push_str(&format!(
+				r##"<rect x="{x}" y="{y}" width="{cell_width}" height="{box_height}" fill="#f4f4f4" stroke="#dddddd" stroke-width="0.5"/>
+<text x="{x}" y="{baseline}">{label}</text>
+"##,
+				baseline = y + box_height + LABEL_FONT_SIZE + 3.0,
+				label = escape(truncate_label(name, max_label_chars)),
+			));
+		}
+		svg.push_str(&format!(
+			r##"<text x="{PAGE_MARGIN}" y="{footer_y}" fill="#999999">{title}</text>
+<text x="{footer_x}" y="{footer_y}" fill="#999999" text-anchor="end">{page} / {page_count}</text>
+</g>
+</svg>"##,
+			footer_x = PAGE_WIDTH - PAGE_MARGIN,
+			footer_y = PAGE_HEIGHT - PAGE_MARGIN / 2.0,
+			title = escape(title),
+			page = page_idx + 1,
+		));
+		let mut page = render_svg(&svg, RENDER_SCALE)?;
+
+		// -- The thumbnails, centered in their box
+		let paint = PixmapPaint { quality: FilterQuality::Bicubic, ..Default::default() };
+		for ((_, thumbnail), (x, y)) in page_thumbnails.iter().zip(&cells) {
+			let (width, height) = (thumbnail.width() as f32 / RENDER_SCALE, thumbnail.height() as f32 / RENDER_SCALE);
+			let fit = (cell_width / width).min(box_height / height).min(1.0);
+			let offset_x = x + (cell_width - width * fit) / 2.0;
+			let offset_y = y + (box_height - height * fit) / 2.0;
+			// The thumbnail px are page px at fit 1
+			let transform = Transform::from_row(fit, 0.0, 0.0, fit, offset_x * RENDER_SCALE, offset_y * RENDER_SCALE);
+			page.draw_pixmap(0, 0, thumbnail.as_ref(), &paint, transform, None);
+		}
+
+		pages.push(page);
+	}
+
+	Ok(pages)
+}
+
+/// Returns the name, truncated with an ellipsis when longer than `max_chars`.
+fn truncate_label(name: &str, max_chars: usize) -> String {
+	if name.chars().count() <= max_chars {
+		return name.to_string();
+	}
+	let mut label: String = name.chars().take(max_chars.saturating_sub(1)).collect();
+	label.push('…');
+	label
+}
@@ -0,0 +1,226 @@
+//! The text content of the artboards of a Sketch document (for copy review and localization), from the document JSON:
+//! the strings of the text layers, and of the symbol instances (the texts of their master, or their overrides).
+
+use crate::handlers::sketch::{
+	CLASS_ARTBOARD, CLASS_SYMBOL_INSTANCE, CLASS_SYMBOL_MASTER, child_layers, document_pages, layer_class, layer_str,
+	load_sketch_document, walk_layers,
+};
+use crate::support::{globs, strings};
+use crate::{Error, Result};
+use serde::Serialize;
+use serde_json::Value;
+use simple_fs::SPath;
+use std::collections::HashMap;
+
+/// The layer class of the text layers.
+const CLASS_TEXT: &str = "text";
+
+/// The suffix of the override names of the text overrides (e.g., "<layer id>_stringValue").
+const STRING_OVERRIDE_SUFFIX: &str = "_stringValue";
+
+/// The output formats of `sketch copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyFormat {
+	#[default]
+	Csv,
+	Json,
+}
+
+impl TryFrom<&str> for CopyFormat {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"csv" => Ok(Self::Csv),
+			"json" => Ok(Self::Json),
+			other => Err(Error::custom(format!("Invalid copy format '{other}'. Expected: csv, json"))),
+		}
+	}
+}
+
+/// A string of an artboard.
+#[derive(Debug, Clone, Serialize)]
+pub struct TextCopy {
+	pub page_name: String,
+	pub artboard_name: String,
+	/// The layer names from the artboard, joined by " > " (for a symbol instance, its path then the name of the
+	/// text layer in the master).
+	pub layer_path: String,
+	/// The UID of the text layer (`do_objectID`, of the text layer in the master for a symbol instance).
+	pub layer_id: String,
+	/// True when the text is an override of a symbol instance.
+	pub is_override: bool,
+	pub text: String,
+}
+
+/// Returns the strings of the artboards of a Sketch file matching the globs (all when None), in document order.
+/// The hidden layers (and their layers) are skipped.
+pub fn extract_text_copy(sketch_file: impl AsRef<SPath>, glob_patterns: Option<&[&str]>) -> Result<Vec<TextCopy>> {
+	let document = load_sketch_document(sketch_file)?;
+	build_text_copy(&document, glob_patterns)
+}
+
+/// Builds the strings of the artboards from a document JSON (see `extract_text_copy`).
+pub fn build_text_copy(document: &Value, glob_patterns: Option<&[&str]>) -> Result<Vec<TextCopy>> {
+	let glob_set = globs::build_glob_set(glob_patterns)?;
+
+	// The symbol masters by symbol id, and the names of their layers (for the text overrides)
+	let mut masters: HashMap<&str, &Value> = HashMap::new();
+	for page in document_pages(document) {
+		for master in child_layers(page)
+			.iter()
+			.filter(|l| layer_class(l) == Some(CLASS_SYMBOL_MASTER))
+		{
+			masters.extend(layer_str(master, "symbolID").map(|id| (id, master)));
+		}
+	}
+	let foreign_symbols = document.pointer("/document/foreignSymbols").and_then(Value::as_array);
+	for master in foreign_symbols
+		.into_iter()
+		.flatten()
+		.filter_map(|foreign| foreign.get("symbolMaster"))
+	{
+		if let Some(id) = layer_str(master, "symbolID") {
+			masters.entry(id).or_insert(master);
+		}
+	}
+	let mut layer_names: HashMap<&str, &str> = HashMap::new();
+	for master in masters.values() {
+		walk_layers(master, &mut |layer| {
+			if let (Some(id), Some(name)) = (layer_str(layer, "do_objectID"), layer_str(layer, "name")) {
+				layer_names.insert(id, name);
+			}
+		});
+	}
+
+	let mut items = Vec::new();
+	for page in document_pages(document) {
+		let page_name = layer_str(page, "name").unwrap_or_default();
+		for artboard in child_layers(page).iter().filter(|l| layer_class(l) == Some(CLASS_ARTBOARD)) {
+			let artboard_name = layer_str(artboard, "name").unwrap_or_default();
+			if !globs::matches_glob_set(glob_set.as_ref(), artboard_name) {
+				continue;
+			}
+			let mut collector = CopyCollector {
+				page_name,
+				artboard_name,
+				masters: &masters,
+				layer_names: &layer_names,
+				items: &mut items,
+			};
+			collector.collect_children(artboard, &mut Vec::new());
+		}
+	}
+
+	Ok(items)
+}
+
+/// Formats the strings as CSV (with a header row) or JSON.
+pub fn format_text_copy(items: &[TextCopy], format: CopyFormat) -> Result<String> {
+	match format {
+		CopyFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(items)?)),
+		CopyFormat::Csv => {
+			let mut content = String::from("page,artboard,layer_path,layer_id,override,text\n");
+			for item in items {
+				let fields = [
+					strings::csv_field(&item.page_name),
+					strings::csv_field(&item.artboard_name),
+					strings::csv_field(&item.layer_path),
+					strings::csv_field(&item.layer_id),
+					item.is_override.to_string(),
+					strings::csv_field(&item.text),
+				];
+				content.push_str(&fields.join(","));
+				content.push('\n');
+			}
+			Ok(content)
+		}
+	}
+}
+
+/// Walks the layers of an artboard, collecting its strings.
+struct CopyCollector<'a> {
+	page_name: &'a str,
+	artboard_name: &'a str,
+	masters: &'a HashMap<&'a str, &'a Value>,
+	layer_names: &'a HashMap<&'a str, &'a str>,
+	items: &'a mut Vec<TextCopy>,
+}
+
+impl CopyCollector<'_> {
+	fn collect_children(&mut self, parent: &Value, path: &mut Vec<String>) {
+		for layer in child_layers(parent) {
+			if layer.get("isVisible").and_then(Value::as_bool) == Some(false) {
+				continue;
+			}
+			path.push(layer_str(layer, "name").unwrap_or_default().to_string());
+
+			match layer_class(layer) {
+				Some(CLASS_TEXT) => {
+					let text = layer
+						.pointer("/attributedString/string")
+						.and_then(Value::as_str)
+						.unwrap_or_default();
+					self.push(path.join(" > "), layer_str(layer, "do_objectID").unwrap_or_default(), false, text);
+				}
+				Some(CLASS_SYMBOL_INSTANCE) => self.collect_instance(layer, path),
+				_ => self.collect_children(layer, path),
+			}
+
+			path.pop();
+		}
+	}
+
+	/// Collects the strings of a symbol instance: the text layers of its master (with their overrides), and the
+	/// text overrides of its nested symbols (`overrideValues`, e.g., "<layer id>_stringValue", or
+	/// "<nested instance id>/<layer id>_stringValue").
+	fn collect_instance(&mut self, instance: &Value, path: &[String]) {
+		let mut overrides: Vec<(&str, &str)> = Vec::new();
+		for override_value in instance.get("overrideValues").and_then(Value::as_array).into_iter().flatten() {
+			let override_name = layer_str(override_value, "overrideName").unwrap_or_default();
+			if let Some(layer_id) = override_name.strip_suffix(STRING_OVERRIDE_SUFFIX) {
+				overrides.push((layer_id, layer_str(override_value, "value").unwrap_or_default()));
+			}
+		}
+		let instance_path = path.join(" > ");
+
+		// -- The text layers of the master
+		let master = layer_str(instance, "symbolID").and_then(|id| self.masters.get(id).copied());
+		let mut master_texts: Vec<(&str, &str, &str)> = Vec::new();
+		if let Some(master) = master {
+			walk_layers(master, &mut |layer| {
+				if layer_class(layer) == Some(CLASS_TEXT) {
+					let text = layer
+						.pointer("/attributedString/string")
+						.and_then(Value::as_str)
+						.unwrap_or_default();
+					let id = layer_str(layer, "do_objectID").unwrap_or_default();
+					master_texts.push((id, layer_str(layer, "name").unwrap_or_default(), text));
+				}
+			});
+		}
+		for (layer_id, layer_name, text) in master_texts {
+			let override_text = overrides.iter().find(|(id, _)| *id == layer_id).map(|(_, value)| *value);
+			let layer_path = format!("{instance_path} > {layer_name}");
+			self.push(layer_path, layer_id, override_text.is_some(), override_text.unwrap_or(text));
+		}
+
+		// -- The overrides of the nested symbols
+		for (override_id, text) in overrides.into_iter().filter(|(id, _)| id.contains('/')) {
+			let layer_id = override_id.rsplit('/').next().unwrap_or(override_id);
+			let layer_name = self.layer_names.get(layer_id).copied().unwrap_or(layer_id);
+			self.push(format!("{instance_path} > {layer_name}"), layer_id, true, text);
+		}
+	}
+
+	fn push(&mut self, layer_path: String, layer_id: &str, is_override: bool, text: &str) {
+		self.items.push(TextCopy {
+			page_name: self.page_name.to_string(),
+			artboard_name: self.artboard_name.to_string(),
+			layer_path,
+			layer_id: layer_id.to_string(),
+			is_override,
+			text: text.to_string(),
+		});
+	}
+}
@@ -0,0 +1,70 @@
+//! Access to the Sketch document JSON (via `sketchtool dump`).
+//!
+//! The document is kept as a `serde_json::Value`, and walked by layer `_class`,
+//! since only a few fields are needed for the analyses.
+
+use crate::Result;
+use crate::handlers::sketch::run_sketchtool;
+use crate::support::files;
+use serde_json::Value;
+use simple_fs::SPath;
+
+/// Layer classes of the Sketch document JSON.
+pub const CLASS_ARTBOARD: &str = "artboard";
+pub const CLASS_SYMBOL_MASTER: &str = "symbolMaster";
+pub const CLASS_SYMBOL_INSTANCE: &str = "symbolInstance";
+
+/// Loads the document JSON of a Sketch file (pages with their full layer trees).
+pub fn load_sketch_document(sketch_file: impl AsRef<SPath>) -> Result<Value> {
+	let sketch_file = sketch_file.as_ref();
+
+	files::check_file_exists(sketch_file)?;
+
+	let output = run_sketchtool(&["dump"], &[], sketch_file, None)?;
+
+	if !output.success {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(format!("sketchtool dump failed: {stderr}").into());
+	}
+
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	let document: Value =
+		serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse sketchtool dump output: {e}"))?;
+
+	Ok(document)
+}
+
+/// Returns the `_class` of a layer (e.g., "artboard", "symbolInstance").
+pub fn layer_class(layer: &Value) -> Option<&str> {
+	layer.get("_class").and_then(Value::as_str)
+}
+
+/// Returns a string field of a layer (e.g., "name", "do_objectID", "symbolID").
+pub fn layer_str<'a>(layer: &'a Value, field: &str) -> Option<&'a str> {
+	layer.get(field).and_then(Value::as_str)
+}
+
+/// Returns the direct child layers of a layer (empty if none).
+pub fn child_layers(layer: &Value) -> &[Value] {
+	layer.get("layers").and_then(Value::as_array).map(Vec::as_slice).unwrap_or(&[])
+}
+
+/// Returns the pages of the document (empty if none).
+pub fn document_pages(document: &Value) -> &[Value] {
+	document
+		.get("pages")
+		.and_then(Value::as_array)
+		.map(Vec::as_slice)
+		.unwrap_or(&[])
+}
+
+/// Calls `visit_fn` on all the descendant layers of a layer (depth first, document order).
+pub fn walk_layers<'a, F>(layer: &'a Value, visit_fn: &mut F)
+where
+	F: FnMut(&'a Value),
+{
+	for child in child_layers(layer) {
+		visit_fn(child);
+		walk_layers(child, visit_fn);
+	}
+}
@@ -1,114 +1,213 @@
-use crate::handlers::sketch::{Artboard, list_artboards};
-use crate::support::{files, strings, xmls};
+use crate::handlers::pdf::{self, PdfSource};
+use crate::handlers::sketch::{
+	Artboard, Background, ExportChecker, ExportOptions, ExportPlan, ExportReport, FileNameEntry, FileNamesManifest,
+	LocalRefs, MtimeMode, PDF_BOOK_FORMAT, PlanItem, PlanStep, RASTER_FORMATS, SVG_SYMBOLS_FORMAT, SymbolEntry,
+	SymbolSize, SymbolsManifest, TILES_DIR_SUFFIX, TILES_MANIFEST_EXT, TileEntry, TilesManifest, UidMap, UidMapEntry,
+	plan_export, run_sketchtool, sketchtool_format, svg_px_size,
+};
+use crate::handlers::{checksums, codegen, raster, stats, svg};
+use crate::support::{dates, files, profile, strings, xmls_stream};
 use crate::{Error, Result};
 use simple_fs::{SPath, ensure_dir, read_to_string};
-use std::process::Command;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, UNIX_EPOCH};
 
-const SKETCHTOOL_PATH: &str = "/Applications/Sketch.app/Contents/Resources/sketchtool/bin/sketchtool";
-
-/// Cache directory name for raw exports before processing
-const CACHE_RAW_EXPORT_DIR: &str = ".cache-raw-export";
-
-/// Exports artboards from a Sketch file to the specified formats.
-/// Returns a list of exported file paths.
+/// Exports artboards from a Sketch file to the specified formats (see `plan_export`, then `execute_export_plan`).
+/// Returns the exported file paths, the warnings (e.g., non-square or empty artboards),
+/// and the artboards rendering identically.
 pub fn export_artboards(
 	sketch_file: impl AsRef<SPath>,
 	glob_patterns: Option<&[&str]>,
 	formats: &[&str],
 	output_dir: impl AsRef<SPath>,
-	flatten: bool,
-	keep_raw_export: bool,
-) -> Result<Vec<String>> {
-	let sketch_file = sketch_file.as_ref();
-	let output_path = output_dir.as_ref();
+	options: &ExportOptions,
+) -> Result<ExportReport> {
+	let plan = plan_export(sketch_file, glob_patterns, formats, output_dir, options)?;
+	execute_export_plan(&plan)
+}
 
-	files::check_file_exists(sketch_file)?;
+/// Executes an export plan, step by step (one sketchtool export per step).
+/// A step failing after others wrote their files is an `Error::PartialFailure`.
+pub fn execute_export_plan(plan: &ExportPlan) -> Result<ExportReport> {
+	let sketch_file = SPath::new(&plan.sketch_file);
 
-	// Get artboards matching the glob patterns
-	let artboards = list_artboards(sketch_file, glob_patterns)?;
+	let mut report = ExportReport {
+		artboard_count: plan.artboard_count,
+		unchanged_count: plan.unchanged_count,
+		..Default::default()
+	};
+	let mut checker = ExportChecker::new(plan.warnings.clone());
+	stats::count_artboards(plan.artboard_count);
+
+	for step in &plan.steps {
+		let _span = profile::span("export", format!("export {}", step.format)).arg("items", step.items.len());
+		let step_files = if step.format == SVG_SYMBOLS_FORMAT {
+			export_svg_symbols(&sketch_file, step, &plan.options, &mut checker)
+		} else if step.format == PDF_BOOK_FORMAT {
+			export_pdf_book(&sketch_file, step, &plan.options)
+		} else {
+			export_regular_format(&sketch_file, step, &plan.options, &mut checker)
+		}
+		.map_err(|err| match err {
+			err if !report.exported_files.is_empty() => {
+				Error::PartialFailure { cause: err.to_string(), exported_files: report.exported_files.clone() }
+			}
+			err => err,
+		})?;
+		report.exported_files.extend(step_files);
+	}
+	// The extracted images can be shared by the steps (see `ExportOptions::extract_images`)
+	let mut seen = HashSet::new();
+	report.exported_files.retain(|file| seen.insert(file.clone()));
 
-	if artboards.is_empty() {
-		return Ok(vec![]);
+	if let Some(file_names_file) = &plan.file_names_file {
+		write_file_names_manifest(plan, &SPath::new(file_names_file))?;
+		report.exported_files.push(file_names_file.to_string());
 	}
 
-	// Check if svg-symbols format is requested
-	let has_svg_symbols = formats.contains(&"svg-symbols");
+	if let Some(uid_map_file) = &plan.uid_map_file {
+		write_uid_map(plan, &SPath::new(uid_map_file))?;
+		report.exported_files.push(uid_map_file.to_string());
+	}
 
-	// Separate svg-symbols from regular formats
-	let regular_formats: Vec<&str> = formats.iter().filter(|f| **f != "svg-symbols").copied().collect();
+	if let Some(snippet_file) = &plan.email_snippet_file {
+		let snippet_file = SPath::new(snippet_file);
+		let images: Vec<codegen::EmailSnippetItem> = plan
+			.email_images
+			.iter()
+			.map(|image| codegen::EmailSnippetItem::new(&image.cid, &image.alt, image.width, image.height))
+			.collect();
+		let template_dir = plan.options.template_dir.as_deref().map(SPath::new);
+		let content = codegen::email_snippet(&snippet_file, &images, template_dir.as_ref())?;
+		codegen::write_codegen_file(&snippet_file, &content)?;
+		report.exported_files.push(snippet_file.to_string());
+	}
 
-	let mut exported_files = Vec::new();
+	if let Some(urls_file) = &plan.urls_file {
+		let urls_file = SPath::new(urls_file);
+		let content = serde_json::to_string_pretty(&plan.asset_urls)?;
+		codegen::write_codegen_file(&urls_file, &format!("{content}\n"))?;
+		report.exported_files.push(urls_file.to_string());
+	}
 
-	// Handle svg-symbols export
-	if has_svg_symbols {
-		let symbols_files = export_svg_symbols(sketch_file, &artboards, output_path, keep_raw_export)?;
-		exported_files.extend(symbols_files);
+	if let Some(urls_ts_file) = &plan.urls_ts_file {
+		let urls_ts_file = SPath::new(urls_ts_file);
+		let template_dir = plan.options.template_dir.as_deref().map(SPath::new);
+		codegen::write_codegen_file(&urls_ts_file, &codegen::asset_urls_ts(&plan.asset_urls, template_dir.as_ref())?)?;
+		report.exported_files.push(urls_ts_file.to_string());
 	}
 
-	// Handle regular formats
-	if !regular_formats.is_empty() {
-		let regular_files =
-			export_regular_formats(sketch_file, &artboards, &regular_formats, output_path, flatten, keep_raw_export)?;
-		exported_files.extend(regular_files);
+	if plan.options.checksums && !report.exported_files.is_empty() {
+		let output_path = SPath::new(&plan.output_path);
+		let sums_dir = if files::looks_like_file_path(&output_path) {
+			output_path.parent().unwrap_or_else(|| SPath::new(""))
+		} else {
+			output_path
+		};
+		let sums_file = checksums::update_sha256_sums(&sums_dir, &report.exported_files)?;
+		report.exported_files.push(sums_file.to_string());
 	}
 
-	Ok(exported_files)
+	if let Some(mode) = plan.options.chmod()? {
+		for file in &report.exported_files {
+			files::set_file_mode(&SPath::new(file), mode)?;
+		}
+	}
+	set_output_mtimes(&sketch_file, plan.options.preserve_mtime()?, &report.exported_files)?;
+
+	checker.finish(&mut report);
+
+	Ok(report)
+}
+
+/// Exports the artboards of a pdf-book step to PDF, then merges them as the pages of a single PDF file.
+fn export_pdf_book(sketch_file: &SPath, step: &PlanStep, options: &ExportOptions) -> Result<Vec<String>> {
+	let Some(target_file) = step.items.first().map(|item| SPath::new(&item.target_file)) else {
+		return Ok(Vec::new());
+	};
+
+	let cache_dir = SPath::new(&step.export_dir);
+	ensure_dir(cache_dir.as_std_path())
+		.map_err(|e| format!("Failed to create cache directory '{}': {e}", cache_dir))?;
+
+	if let Some(stderr) = run_step_export(sketch_file, step, options, sketchtool_format(PDF_BOOK_FORMAT), &cache_dir)? {
+		let _ = files::safer_delete_dir(&cache_dir);
+		return Err(format!("sketchtool export failed for {PDF_BOOK_FORMAT}: {stderr}").into());
+	}
+
+	let mut sources = Vec::new();
+	for item in &step.items {
+		let pdf_file = SPath::new(&item.source_file);
+		if !pdf_file.exists() {
+			return Err(Error::custom(format!(
+				"PDF file not found for artboard '{}'. Expected path: '{}' does not exist.",
+				item.artboard.name, pdf_file
+			)));
+		}
+		sources.push(PdfSource { file: pdf_file, bookmark: options.pdf_bookmarks.then(|| item.artboard.name.clone()) });
+	}
+
+	pdf::merge_pdf_files(&sources, &target_file)?;
+
+	if !options.keep_raw_export {
+		let _ = files::safer_delete_dir(&cache_dir);
+	}
+
+	Ok(vec![target_file.to_string()])
 }
 
-/// Exports artboards as SVG symbols into a single SVG file.
+/// Exports the artboards of an svg-symbols step as symbols of a single SVG file.
 fn export_svg_symbols(
 	sketch_file: &SPath,
-	artboards: &[Artboard],
-	output_path: &SPath,
-	keep_raw_export: bool,
+	step: &PlanStep,
+	options: &ExportOptions,
+	checker: &mut ExportChecker,
 ) -> Result<Vec<String>> {
-	// Determine the target file path
-	let target_file = if files::looks_like_file_path(output_path) {
-		output_path.clone()
-	} else {
-		// It's a directory, use symbols.svg as filename
-		output_path.join("symbols.svg")
+	let keep_raw_export = options.keep_raw_export;
+
+	let Some(target_file) = step.items.first().map(|item| SPath::new(&item.target_file)) else {
+		return Ok(Vec::new());
 	};
 
 	// Create a cache directory for temporary SVG exports
-	let cache_dir = target_file
-		.parent()
-		.unwrap_or_else(|| SPath::new("."))
-		.join(CACHE_RAW_EXPORT_DIR);
+	let cache_dir = SPath::new(&step.export_dir);
 
 	ensure_dir(cache_dir.as_std_path())
 		.map_err(|e| format!("Failed to create cache directory '{}': {e}", cache_dir))?;
 
-	// Build the items argument (comma-separated UIDs)
-	let item_ids: Vec<&str> = artboards.iter().map(|ab| ab.uid.as_str()).collect();
-	let items_arg = item_ids.join(",");
-
 	// Export SVGs to cache directory
-	let output = Command::new(SKETCHTOOL_PATH)
-		.arg("--format=svg")
-		.arg("--include-symbols=YES")
-		.arg(format!("--items={items_arg}"))
-		.arg(format!("--output={}", cache_dir.as_str()))
-		.arg("export")
-		.arg("artboards")
-		.arg(sketch_file.as_str())
-		.output()
-		.map_err(|e| format!("Failed to execute sketchtool: {e}"))?;
-
-	if !output.status.success() {
-		let stderr = String::from_utf8_lossy(&output.stderr);
+	if let Some(stderr) =
+		run_step_export(sketch_file, step, options, sketchtool_format(SVG_SYMBOLS_FORMAT), &cache_dir)?
+	{
 		let _ = files::safer_delete_dir(&cache_dir);
 		return Err(format!("sketchtool export failed for svg-symbols: {stderr}").into());
 	}
 
-	// Build symbols from exported SVGs, matching by artboard name
-	let mut symbols = Vec::new();
-	for artboard in artboards {
-		let symbol_id = strings::canonicalize_name(&artboard.name);
-
-		// Find the corresponding SVG file by matching the artboard name pattern
-		// sketchtool exports files with names like "artboard-name.svg" where slashes become "/"
-		let svg_file = find_svg_file_for_artboard(&cache_dir, &artboard.name)?;
+	// Build symbols from exported SVGs, matching by artboard name, streamed to the symbols file one at a time
+	let _span = profile::span("transform", "build sprite").arg("file", target_file.as_str());
+	let mut sprite_writer = svg::SpriteWriter::create(&target_file)?;
+	let mut symbols_cache = if options.symbols_cache { Some(svg::SymbolsCache::open(&target_file)?) } else { None };
+	let mut symbol_ids = Vec::new();
+	let sprite_ids: HashSet<String> = step.items.iter().map(item_symbol_id).collect();
+	let strip_namespaces: Vec<&str> = options.strip_namespaces.iter().map(|s| s.as_str()).collect();
+	let mut manifest = SymbolsManifest::default();
+	let mut use_snippets = Vec::new();
+	let mut icon_paths = Vec::new();
+	let local_refs = options.local_refs()?;
+	let image_dir = options.extract_images.as_deref().map(SPath::new);
+	let mut image_files: Vec<String> = Vec::new();
+	for item in &step.items {
+		let artboard = &item.artboard;
+		let symbol_id = item_symbol_id(item);
+
+		// The exported SVG file, named like the artboard (e.g., "ico/user/fill" -> "ico/user/fill.svg"), or by its UID
+		let svg_file = SPath::new(&item.source_file);
+		if !svg_file.exists() {
+			return Err(Error::custom(format!(
+				"SVG file not found for artboard '{}'. Expected path: '{}' does not exist.",
+				artboard.name, svg_file
+			)));
+		}
 
 		let svg_content = read_to_string(svg_file.path()).map_err(Error::custom_from_err)?;
 
@@ -117,297 +216,704 @@ fn export_svg_symbols(
 			let _ = files::safer_delete_dir(&cache_dir);
 		}
 
-		let symbol = convert_svg_to_symbol(&svg_content, &symbol_id).ok_or_else(|| {
-			// Clean up before returning error (unless keep_raw_export is set)
-			if !keep_raw_export {
-				let _ = files::safer_delete_dir(&cache_dir);
+		let svg_content = match options.padding {
+			Some(padding) => svg::pad_svg(&svg_content, padding as f64).ok_or_else(|| {
+				Error::custom(format!("Cannot pad the SVG of artboard '{}' (no valid viewBox)", artboard.name))
+			})?,
+			None => svg_content,
+		};
+		let svg_content = if options.non_scaling_stroke {
+			svg::add_non_scaling_stroke(&svg_content).ok_or_else(|| {
+				Error::custom(format!(
+					"Cannot set the non-scaling strokes of artboard '{}' (invalid SVG)",
+					artboard.name
+				))
+			})?
+		} else {
+			svg_content
+		};
+		// Before the local references policy, which then only sees the images that are not inlined
+		let svg_content = if options.embed_images {
+			let base_dir = svg_file.parent().unwrap_or_else(|| SPath::new("."));
+			let (svg_content, images) = embed_images(&svg_content, &base_dir, &artboard.name)?;
+			checker.check_embedded_images(artboard, &images);
+			svg_content
+		} else {
+			svg_content
+		};
+		let svg_content = resolve_local_refs(svg_content, local_refs, &artboard.name)?;
+		// The references relative to the sprite
+		let svg_content = match &image_dir {
+			Some(image_dir) => {
+				let (svg_content, files) = extract_images(&svg_content, image_dir, &target_file, &artboard.name)?;
+				push_new_files(&mut image_files, files);
+				svg_content
 			}
-			Error::custom(format!(
-				"Failed to convert SVG to symbol for artboard '{}': invalid SVG content. File: '{}', Content length: {} bytes",
-				artboard.name,
-				svg_file.path(),
-				svg_content.len()
-			))
+			None => svg_content,
+		};
+
+		// The inner ids named like a symbol (e.g., the artboard group) would duplicate its id in the sprite
+		let svg_content = svg::scope_symbol_ids(&svg_content, &symbol_id, &sprite_ids).ok_or_else(|| {
+			Error::custom(format!("Cannot scope the ids of artboard '{}' (invalid SVG)", artboard.name))
 		})?;
 
-		// Validate that the symbol actually has content beyond just the opening/closing tags
-		if !symbol.contains('<') || symbol.matches('<').count() <= 2 {
-			if !keep_raw_export {
-				let _ = files::safer_delete_dir(&cache_dir);
+		checker.check_symbol_svg(artboard, &svg_content);
+
+		let preserve_aspect_ratio = item.preserve_aspect_ratio.as_deref();
+		let symbol_size = match item.symbol_size.as_deref().map(SymbolSize::try_from).transpose()? {
+			Some(SymbolSize::Intrinsic) => {
+				let (width, height) = svg_px_size(&svg_content).ok_or_else(|| {
+					Error::custom(format!(
+						"Cannot get the intrinsic size of artboard '{}' (no width/height or viewBox)",
+						artboard.name
+					))
+				})?;
+				let number =
+					|value: serde_json::Number| strings::format_svg_number(value.as_f64().unwrap_or_default(), None);
+				Some((number(width), number(height)))
 			}
-			return Err(Error::custom(format!(
-				"Generated symbol for artboard '{}' appears to have no inner content. SVG file: '{}'",
-				artboard.name,
-				svg_file.path()
-			)));
+			Some(SymbolSize::Fixed { width, height }) => Some((width, height)),
+			None => None,
+		};
+		let symbol_size = symbol_size.as_ref().map(|(width, height)| (width.as_str(), height.as_str()));
+
+		let mut extra_attrs = Vec::new();
+		if let Some(value) = preserve_aspect_ratio {
+			extra_attrs.push(("preserveAspectRatio", value));
+		}
+		if let Some((width, height)) = symbol_size {
+			extra_attrs.extend([("width", width), ("height", height)]);
 		}
+		if options.symbol_data_attrs {
+			extra_attrs.push((svg::DATA_NAME_ATTR, artboard.name.as_str()));
+			extra_attrs.push((svg::DATA_PAGE_ATTR, artboard.page_name.as_str()));
+		}
+
+		let convert = || {
+			let symbol = svg::convert_svg_to_symbol(&svg_content, &symbol_id, &extra_attrs, &strip_namespaces)
+				.ok_or_else(|| {
+					// Clean up before returning error (unless keep_raw_export is set)
+					if !keep_raw_export {
+						let _ = files::safer_delete_dir(&cache_dir);
+					}
+					Error::custom(format!(
+						"Failed to convert SVG to symbol for artboard '{}': invalid SVG content. File: '{}', Content length: {} bytes",
+						artboard.name,
+						svg_file.path(),
+						svg_content.len()
+					))
+				})?;
+
+			// Validate that the symbol actually has content beyond just the opening/closing tags
+			if !symbol.markup.contains('<') || symbol.markup.matches('<').count() <= 2 {
+				if !keep_raw_export {
+					let _ = files::safer_delete_dir(&cache_dir);
+				}
+				return Err(Error::custom(format!(
+					"Generated symbol for artboard '{}' appears to have no inner content. SVG file: '{}'",
+					artboard.name,
+					svg_file.path()
+				)));
+			}
+
+			Ok(symbol)
+		};
 
-		symbols.push(symbol);
+		// With the symbols cache, only the symbols of the changed SVGs are converted
+		let symbol = match &mut symbols_cache {
+			Some(symbols_cache) => {
+				let key = svg::symbol_cache_key(&svg_content, &symbol_id, &extra_attrs, &strip_namespaces);
+				symbols_cache.get_or_convert(&key, convert)?
+			}
+			None => convert()?,
+		};
+
+		if step.manifest_file.is_some() {
+			manifest
+				.symbols
+				.push(build_symbol_entry(&svg_content, symbol_id.clone(), artboard, preserve_aspect_ratio));
+		}
+
+		// The icons that are not single-path are left out
+		if step.paths_ts_file.is_some()
+			&& let Some(icon_path) = svg::single_path_icon(&svg_content)
+		{
+			icon_paths.push((symbol_id.clone(), icon_path));
+		}
+
+		if step.use_snippets_file.is_some() || step.usage_file.is_some() {
+			use_snippets.push(codegen::UseSnippetItem::new(
+				&symbol_id,
+				&artboard.name,
+				symbol_size,
+				preserve_aspect_ratio,
+			));
+		}
+
+		sprite_writer.write_symbol(&symbol.markup, symbol.namespaces)?;
+		symbol_ids.push(symbol_id);
 	}
 
-	// Build the combined SVG symbols file
-	let symbols_content = build_svg_symbols_file(&symbols);
+	// Write the symbols file (the root with the namespaces of all the symbols, then the symbols)
+	sprite_writer.finish()?;
+	if let Some(symbols_cache) = &symbols_cache {
+		symbols_cache.prune()?;
+	}
 
-	// Ensure target parent directory exists
-	if let Some(parent) = target_file.parent() {
-		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+	let mut written = vec![target_file.to_string()];
+	written.extend(image_files);
+
+	// Write the symbols manifest next to the symbols file
+	if let Some(manifest_file) = &step.manifest_file {
+		let manifest_file = SPath::new(manifest_file);
+		manifest.write(&manifest_file)?;
+		written.push(manifest_file.to_string());
 	}
 
-	// Write the symbols file
-	std::fs::write(target_file.as_std_path(), symbols_content)
-		.map_err(|e| format!("Failed to write symbols file '{}': {e}", target_file))?;
+	let template_dir = options.template_dir.as_deref().map(SPath::new);
+
+	// Write the TypeScript module of the symbol ids
+	if let Some(ts_file) = &step.ts_file {
+		let ts_file = SPath::new(ts_file);
+		let type_name = options.ts_type_name.as_deref().unwrap_or(codegen::DEFAULT_TS_TYPE_NAME);
+		let content = codegen::icon_names_ts(&symbol_ids, type_name, template_dir.as_ref())?;
+		codegen::write_codegen_file(&ts_file, &content)?;
+		written.push(ts_file.to_string());
+	}
+
+	// Write the Rust module of the symbol ids
+	if let Some(rust_file) = &step.rust_file {
+		let rust_file = SPath::new(rust_file);
+		codegen::write_codegen_file(&rust_file, &codegen::icon_names_rust(&symbol_ids, template_dir.as_ref())?)?;
+		written.push(rust_file.to_string());
+	}
+
+	// Write the TypeScript module of the single-path icons
+	if let Some(paths_ts_file) = &step.paths_ts_file {
+		let paths_ts_file = SPath::new(paths_ts_file);
+		codegen::write_codegen_file(&paths_ts_file, &codegen::icon_paths_ts(&icon_paths, template_dir.as_ref())?)?;
+		written.push(paths_ts_file.to_string());
+	}
+
+	// Write the <use> snippets of the symbols
+	if let Some(snippets_file) = &step.use_snippets_file {
+		let snippets_file = SPath::new(snippets_file);
+		let content = codegen::use_snippets_html(&snippets_file, &target_file, &use_snippets, template_dir.as_ref())?;
+		codegen::write_codegen_file(&snippets_file, &content)?;
+		written.push(snippets_file.to_string());
+	}
+
+	// Write the usage docs of the icons
+	if let Some(usage_file) = &step.usage_file {
+		let usage_file = SPath::new(usage_file);
+		let content = codegen::usage_docs(&usage_file, &target_file, &use_snippets, template_dir.as_ref())?;
+		codegen::write_codegen_file(&usage_file, &content)?;
+		written.push(usage_file.to_string());
+	}
 
 	// Clean up cache directory (unless keep_raw_export is set)
 	if !keep_raw_export {
 		let _ = files::safer_delete_dir(&cache_dir);
 	}
 
-	Ok(vec![target_file.to_string()])
+	Ok(written)
 }
 
-/// Finds the SVG file corresponding to an artboard in the cache directory.
-/// The file path structure mirrors the artboard name (e.g., "ico/user/fill" -> "ico/user/fill.svg").
-fn find_svg_file_for_artboard(cache_dir: &SPath, artboard_name: &str) -> Result<SPath> {
-	find_svg_file_for_artboard_with_ext(cache_dir, artboard_name, "svg")
+/// The sprite symbol id of an item (planned, or the canonical artboard name).
+fn item_symbol_id(item: &PlanItem) -> String {
+	item.symbol_id
+		.clone()
+		.unwrap_or_else(|| strings::canonicalize_name(&item.artboard.name))
 }
 
-/// Finds a file corresponding to an artboard in the cache directory with a specific extension.
-/// The file path structure mirrors the artboard name (e.g., "ico/user/fill" -> "ico/user/fill.{ext}").
-fn find_svg_file_for_artboard_with_ext(cache_dir: &SPath, artboard_name: &str, ext: &str) -> Result<SPath> {
-	// sketchtool exports files preserving the artboard name path structure
-	// e.g., artboard "ico/user/fill" becomes "cache_dir/ico/user/fill.{ext}"
-	let expected_path = cache_dir.join(format!("{artboard_name}.{ext}"));
-
-	if expected_path.exists() {
-		return Ok(expected_path);
-	}
-
-	// If the expected path doesn't exist, fail immediately with a clear error
-	// Don't do fuzzy matching as it leads to returning wrong files
-	Err(Error::custom(format!(
-		"{} file not found for artboard '{}'. Expected path: '{}' does not exist.",
-		ext.to_uppercase(),
-		artboard_name,
-		expected_path
-	)))
+/// Builds the manifest entry of a symbol from its exported SVG.
+/// The pixel size comes from the root `width`/`height` attributes, falling back to the `viewBox` size.
+fn build_symbol_entry(
+	svg_content: &str,
+	symbol_id: String,
+	artboard: &Artboard,
+	preserve_aspect_ratio: Option<&str>,
+) -> SymbolEntry {
+	let view_box = xmls_stream::extract_root_attribute(svg_content, "viewBox").unwrap_or_default();
+	let (width, height) = svg_px_size(svg_content).unzip();
+
+	SymbolEntry {
+		id: symbol_id,
+		name: artboard.name.clone(),
+		page: artboard.page_name.clone(),
+		view_box,
+		width,
+		height,
+		preserve_aspect_ratio: preserve_aspect_ratio.map(|v| v.to_string()),
+	}
 }
 
-/// Converts an SVG file content to a symbol element.
-fn convert_svg_to_symbol(svg_content: &str, symbol_id: &str) -> Option<String> {
-	// Extract viewBox from the SVG
-	let viewbox = xmls::extract_root_attribute(svg_content, "viewBox")?;
+/// Writes the manifest mapping the flattened or sanitized file names back to their artboards (see `FileNamesManifest`).
+fn write_file_names_manifest(plan: &ExportPlan, manifest_file: &SPath) -> Result<()> {
+	let base_dir = manifest_file.parent().unwrap_or_else(|| SPath::new("."));
+	let mut manifest = FileNamesManifest::default();
+	for (_, item) in plan.items() {
+		if !item.has_mapped_file_name() {
+			continue;
+		}
+		let file = item
+			.target_file
+			.strip_prefix(&format!("{base_dir}/"))
+			.unwrap_or(&item.target_file)
+			.to_string();
+		manifest
+			.files
+			.push(FileNameEntry { file, name: item.artboard.name.clone(), uid: item.artboard.uid.clone() });
+	}
+	manifest.write(manifest_file)
+}
 
-	// Extract the inner nodes (everything between <svg ...> and </svg>)
-	let inner_nodes = xmls::extract_root_inner_nodes(svg_content)?;
+/// Sets the modification time of the exported files (see `ExportOptions::preserve_mtime`).
+fn set_output_mtimes(sketch_file: &SPath, mode: MtimeMode, exported_files: &[String]) -> Result<()> {
+	let mtime = match mode {
+		MtimeMode::Now => return Ok(()),
+		MtimeMode::Source => std::fs::metadata(sketch_file.as_std_path())
+			.and_then(|meta| meta.modified())
+			.map_err(|e| format!("Cannot read the modification time of '{sketch_file}': {e}"))?,
+		MtimeMode::Epoch => {
+			let secs = dates::source_date_epoch()
+				.and_then(|secs| u64::try_from(secs).ok())
+				.unwrap_or(0);
+			UNIX_EPOCH + Duration::from_secs(secs)
+		}
+	};
 
-	// If no inner nodes, return None to signal an error
-	if inner_nodes.is_empty() {
-		return None;
+	for file in exported_files {
+		files::set_file_mtime(&SPath::new(file), mtime)?;
 	}
 
-	// Canonicalize all id attributes within the inner nodes
-	let transformed_nodes = xmls::transform_nodes_id_attributes(inner_nodes, strings::canonicalize_name);
-
-	// Convert nodes back to string
-	let inner_content = xmls::nodes_to_string(&transformed_nodes);
+	Ok(())
+}
 
-	// If inner content is empty after transformation, return None
-	if inner_content.trim().is_empty() {
-		return None;
+/// Writes the map relating each artboard UID to its name and output files (see `UidMap`).
+fn write_uid_map(plan: &ExportPlan, map_file: &SPath) -> Result<()> {
+	let base_dir = map_file.parent().unwrap_or_else(|| SPath::new("."));
+	let relative = |file: &str| file.strip_prefix(&format!("{base_dir}/")).unwrap_or(file).to_string();
+
+	let mut map = UidMap::default();
+	let mut uid_idxs: HashMap<&str, usize> = HashMap::new();
+	for (_, item) in plan.items() {
+		let idx = *uid_idxs.entry(&item.artboard.uid).or_insert_with(|| {
+			map.artboards.push(UidMapEntry {
+				uid: item.artboard.uid.clone(),
+				name: item.artboard.name.clone(),
+				page: item.artboard.page_name.clone(),
+				files: Vec::new(),
+				symbol_id: None,
+			});
+			map.artboards.len() - 1
+		});
+		let entry = &mut map.artboards[idx];
+		for file in std::iter::once(&item.target_file).chain(item.redline_file.as_ref()) {
+			let file = relative(file);
+			if !entry.files.contains(&file) {
+				entry.files.push(file);
+			}
+		}
+		if item.symbol_id.is_some() {
+			entry.symbol_id.clone_from(&item.symbol_id);
+		}
 	}
+	map.write(map_file)
+}
 
-	// Indent the inner content for proper formatting
-	let indented_content = indent_content(&inner_content, 4);
-
-	// Final check: if indented content is empty, something went wrong
-	if indented_content.trim().is_empty() {
-		return None;
+/// Exports the artboards of a regular format step (svg, png, jpeg) with sketchtool,
+/// then copies them from the cache to their target files (when the step uses a cache).
+fn export_regular_format(
+	sketch_file: &SPath,
+	step: &PlanStep,
+	options: &ExportOptions,
+	checker: &mut ExportChecker,
+) -> Result<Vec<String>> {
+	let format = step.format.as_str();
+	let export_dir = SPath::new(&step.export_dir);
+	let background = options.background()?;
+	let local_refs = options.local_refs()?;
+	let image_dir = options.extract_images.as_deref().map(SPath::new);
+
+	// Ensure export directory exists
+	ensure_dir(export_dir.as_std_path())
+		.map_err(|e| format!("Failed to create output directory '{}': {e}", export_dir))?;
+
+	if let Some(stderr) = run_step_export(sketch_file, step, options, format, &export_dir)? {
+		return Err(format!("sketchtool export failed for format '{format}': {stderr}").into());
 	}
 
-	Some(format!(
-		r#"  <symbol id="{symbol_id}" viewBox="{viewbox}">
-{indented_content}
-  </symbol>"#
-	))
-}
+	let mut exported_files = Vec::new();
 
-/// Indents each line of content by the specified number of spaces.
-/// First removes common leading whitespace, then applies the new base indentation
-/// while preserving relative indentation between lines.
-fn indent_content(content: &str, base_spaces: usize) -> String {
-	if content.is_empty() {
-		return String::new();
-	}
-
-	// Find the minimum indentation among non-empty lines
-	let min_indent = content
-		.lines()
-		.filter(|line| !line.trim().is_empty())
-		.map(|line| line.len() - line.trim_start().len())
-		.min()
-		.unwrap_or(0);
-
-	let base_indent = " ".repeat(base_spaces);
-	content
-		.lines()
-		.map(|line| {
-			if line.trim().is_empty() {
-				String::new()
-			} else {
-				// Calculate this line's indentation relative to min_indent
-				let line_indent = line.len() - line.trim_start().len();
-				let relative_indent = line_indent.saturating_sub(min_indent);
-				let extra_indent = " ".repeat(relative_indent);
-				let trimmed = line.trim_start();
-				format!("{base_indent}{extra_indent}{trimmed}")
+	for item in &step.items {
+		let target_path = SPath::new(&item.target_file);
+		let _span = profile::span("transform", format!("process {}", item.artboard.name));
+
+		// Using a cache (single file output, flatten, file name, or sanitized names): copy the file to its target
+		if step.is_cache {
+			let mut src_path = SPath::new(&item.source_file);
+			// Single file output: fall back on the one exported file
+			if !src_path.exists()
+				&& step.items.len() == 1
+				&& let Some(exported_path) = find_exported_file_in_cache(&export_dir, format)
+			{
+				src_path = exported_path;
+			}
+			if !src_path.exists() {
+				return Err(Error::custom(format!(
+					"{} file not found for artboard '{}'. Expected path: '{}' does not exist.",
+					format.to_uppercase(),
+					item.artboard.name,
+					src_path
+				)));
+			}
+
+			// Ensure target parent directory exists
+			if let Some(parent) = target_path.parent() {
+				ensure_dir(parent.as_std_path())
+					.map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
 			}
-		})
-		.collect::<Vec<_>>()
-		.join("\n")
-}
 
-/// Builds the combined SVG symbols file.
-fn build_svg_symbols_file(symbols: &[String]) -> String {
-	let mut result = String::new();
-	result.push_str(r#"<svg width="0" height="0" style="position:absolute">"#);
-	result.push('\n');
+			// Copy the file first (more reliable across filesystems), the cache is removed afterwards
+			files::copy_file(&src_path, &target_path)?;
+		}
 
-	for (idx, symbol) in symbols.iter().enumerate() {
-		// Add empty line before symbols, except for the first one
-		if idx > 0 {
-			result.push('\n');
+		if let Some(padding) = options.padding {
+			pad_exported_file(&target_path, format, padding, item.scale.as_deref()).map_err(|err| {
+				Error::custom(format!("Cannot pad '{target_path}' ({}). Cause: {err}", item.artboard.name))
+			})?;
 		}
-		result.push_str(symbol);
-		result.push('\n');
+
+		if format == "svg" && options.non_scaling_stroke {
+			set_non_scaling_stroke(&target_path).map_err(|err| {
+				Error::custom(format!(
+					"Cannot set the non-scaling strokes of '{target_path}' ({}). Cause: {err}",
+					item.artboard.name
+				))
+			})?;
+		}
+
+		if format == "svg" && options.embed_images {
+			// The relative references are resolved from the exported file (in the cache when copied)
+			let source_file = if step.is_cache { SPath::new(&item.source_file) } else { target_path.clone() };
+			let base_dir = source_file.parent().unwrap_or_else(|| SPath::new("."));
+			let images = embed_file_images(&target_path, &base_dir, &item.artboard.name)?;
+			checker.check_embedded_images(&item.artboard, &images);
+		}
+
+		if format == "svg" && local_refs != LocalRefs::Warn {
+			resolve_file_local_refs(&target_path, local_refs, &item.artboard.name)?;
+		}
+
+		let mut image_files = Vec::new();
+		if format == "svg"
+			&& let Some(image_dir) = &image_dir
+		{
+			image_files = extract_file_images(&target_path, image_dir, &item.artboard.name)?;
+		}
+
+		// After the padding, so the padding gets the color too
+		if format == "png"
+			&& let Some(Background::Color(color)) = background
+		{
+			let image = raster::read_png(&target_path)?;
+			raster::write_png(&raster::fill_background(&image, color)?, &target_path)?;
+		}
+
+		// After the background, so the review copy matches the exported file
+		let redline_file = match (options.redline, &item.redline_file) {
+			(Some(grid), Some(redline_file)) => {
+				let redline_file = SPath::new(redline_file);
+				redline_exported_file(&target_path, &redline_file, grid, item.scale.as_deref()).map_err(|err| {
+					Error::custom(format!("Cannot redline '{target_path}' ({}). Cause: {err}", item.artboard.name))
+				})?;
+				Some(redline_file.to_string())
+			}
+			_ => None,
+		};
+
+		let tile_files = match options.tile {
+			Some(tile_size) => tile_exported_file(&target_path, tile_size, options.tile_overlap).map_err(|err| {
+				Error::custom(format!("Cannot tile '{target_path}' ({}). Cause: {err}", item.artboard.name))
+			})?,
+			None => Vec::new(),
+		};
+
+		// Only check what can be read (a missing file is not this check's concern)
+		if format == "svg"
+			&& let Ok(svg_content) = read_to_string(target_path.as_std_path())
+		{
+			checker.check_svg(&item.artboard, &svg_content);
+		}
+
+		exported_files.push(target_path.to_string());
+		exported_files.extend(redline_file);
+		exported_files.extend(tile_files);
+		push_new_files(&mut exported_files, image_files);
+	}
+
+	// Clean up the cache directory (unless keep_raw_export is set)
+	if step.is_cache && !options.keep_raw_export {
+		let _ = files::safer_delete_dir(&export_dir);
 	}
 
-	result.push_str("</svg>\n");
-	result
+	Ok(exported_files)
 }
 
-/// Exports artboards using regular sketchtool formats (svg, png, jpeg).
-fn export_regular_formats(
-	sketch_file: &SPath,
-	artboards: &[Artboard],
-	formats: &[&str],
-	output_path: &SPath,
-	flatten: bool,
-	keep_raw_export: bool,
-) -> Result<Vec<String>> {
-	// Determine if output is a single file target
-	let single_file_output = is_single_file_output(output_path, formats);
+/// Returns the sketchtool arguments of the export options of a format (`trim`, `group_contents_only`,
+/// and the `background` color of the raster formats).
+fn sketchtool_option_args(options: &ExportOptions, format: &str) -> Result<Vec<String>> {
+	let mut args = Vec::new();
+	if options.trim {
+		args.push("--trimmed=YES".to_string());
+	}
+	if options.group_contents_only {
+		args.push("--group-contents-only=YES".to_string());
+	}
+	if RASTER_FORMATS.contains(&format)
+		&& let Some(Background::Color(color)) = options.background()?
+	{
+		args.push(format!("--background={}", color.to_hex()));
+	}
+	Ok(args)
+}
 
-	// Validate single file output constraints
-	if single_file_output {
-		if artboards.len() > 1 {
-			return Err(Error::custom(format!(
-				"Output path '{}' is a file, but {} artboards matched. Use a directory for multiple exports.",
-				output_path,
-				artboards.len()
-			)));
+/// Pads an exported svg or png file in place (see `ExportOptions::padding`), the png by the padding times its scale.
+fn pad_exported_file(file: &SPath, format: &str, padding: u32, scale: Option<&str>) -> Result<()> {
+	match format {
+		"svg" => {
+			let content = read_to_string(file.as_std_path()).map_err(Error::custom_from_err)?;
+			let padded = svg::pad_svg(&content, padding as f64).ok_or("no valid viewBox")?;
+			std::fs::write(file.as_std_path(), padded).map_err(|e| format!("Failed to write '{file}': {e}"))?;
 		}
-		if formats.len() > 1 {
-			return Err(Error::custom(format!(
-				"Output path '{}' is a file, but {} formats specified. Use a directory for multiple formats.",
-				output_path,
-				formats.len()
-			)));
+		"png" => {
+			let scale: f64 = scale.and_then(|s| s.trim().trim_end_matches('x').parse().ok()).unwrap_or(1.0);
+			let image = raster::read_png(file)?;
+			raster::write_png(&raster::pad_image(&image, (padding as f64 * scale).round() as u32)?, file)?;
 		}
+		other => return Err(Error::custom(format!("padding not supported for '{other}'"))),
 	}
+	Ok(())
+}
 
-	// Determine actual output directory (where sketchtool will write files)
-	// For single file output or flatten mode, use a cache subdirectory to capture sketchtool's output
-	let use_cache = single_file_output || flatten;
-	let (output_dir, cache_dir) = if use_cache {
-		let parent = output_path.parent().unwrap_or_else(|| SPath::new("."));
-		let cache = parent.join(CACHE_RAW_EXPORT_DIR);
-		(cache.clone(), Some(cache))
-	} else {
-		(output_path.clone(), None)
-	};
+/// Sets the non-scaling strokes of an exported svg file in place (see `ExportOptions::non_scaling_stroke`).
+fn set_non_scaling_stroke(file: &SPath) -> Result<()> {
+	let content = read_to_string(file.as_std_path()).map_err(Error::custom_from_err)?;
+	let content = svg::add_non_scaling_stroke(&content).ok_or("invalid SVG content")?;
+	std::fs::write(file.as_std_path(), content).map_err(|e| format!("Failed to write '{file}': {e}"))?;
+	Ok(())
+}
+
+/// Inlines the raster images of an exported SVG (see `ExportOptions::embed_images`).
+fn embed_images(svg_content: &str, base_dir: &SPath, artboard_name: &str) -> Result<(String, Vec<svg::EmbeddedImage>)> {
+	svg::embed_svg_images(svg_content, base_dir)
+		.map_err(|err| Error::custom(format!("Cannot embed the images of artboard '{artboard_name}'. Cause: {err}")))
+}
+
+/// Inlines the raster images of an exported svg file in place (see `embed_images`).
+fn embed_file_images(file: &SPath, base_dir: &SPath, artboard_name: &str) -> Result<Vec<svg::EmbeddedImage>> {
+	let content = read_to_string(file.as_std_path()).map_err(Error::custom_from_err)?;
+	let (embedded, images) = embed_images(&content, base_dir, artboard_name)?;
+	if !images.is_empty() {
+		std::fs::write(file.as_std_path(), embedded).map_err(|e| format!("Failed to write '{file}': {e}"))?;
+	}
+	Ok(images)
+}
 
-	// Ensure output directory exists
-	ensure_dir(output_dir.as_std_path())
-		.map_err(|e| format!("Failed to create output directory '{}': {e}", output_dir))?;
+/// Writes the inlined images of an exported SVG to `image_dir` (see `ExportOptions::extract_images`), the references
+/// relative to `svg_file` (the file the SVG content is written to).
+fn extract_images(
+	svg_content: &str,
+	image_dir: &SPath,
+	svg_file: &SPath,
+	artboard_name: &str,
+) -> Result<(String, Vec<SPath>)> {
+	// Note: absolute, so an absolute image directory is relative to a relative output (and conversely)
+	let absolute = |path: &SPath| {
+		std::path::absolute(path.as_std_path())
+			.ok()
+			.and_then(|p| SPath::from_std_path(p).ok())
+	};
+	let href_dir = absolute(svg_file)
+		.and_then(|svg_file| svg_file.parent())
+		.zip(absolute(image_dir))
+		.and_then(|(dir, image_dir)| image_dir.diff(dir))
+		.map(|path| path.to_string().replace('\\', "/"))
+		.unwrap_or_else(|| image_dir.to_string());
+	svg::extract_svg_images(svg_content, image_dir, &href_dir)
+		.map_err(|err| Error::custom(format!("Cannot extract the images of artboard '{artboard_name}'. Cause: {err}")))
+}
 
-	// Build the items argument (comma-separated UIDs)
-	let item_ids: Vec<&str> = artboards.iter().map(|ab| ab.uid.as_str()).collect();
-	let items_arg = item_ids.join(",");
+/// Writes the inlined images of an exported svg file in place (see `extract_images`).
+fn extract_file_images(file: &SPath, image_dir: &SPath, artboard_name: &str) -> Result<Vec<SPath>> {
+	let content = read_to_string(file.as_std_path()).map_err(Error::custom_from_err)?;
+	let (extracted, image_files) = extract_images(&content, image_dir, file, artboard_name)?;
+	if !image_files.is_empty() {
+		std::fs::write(file.as_std_path(), extracted).map_err(|e| format!("Failed to write '{file}': {e}"))?;
+	}
+	Ok(image_files)
+}
 
-	let mut exported_files = Vec::new();
+/// Appends the files not already in `files` (e.g., the extracted images shared by several SVGs).
+fn push_new_files(files: &mut Vec<String>, new_files: Vec<SPath>) {
+	for file in new_files {
+		let file = file.to_string();
+		if !files.contains(&file) {
+			files.push(file);
+		}
+	}
+}
 
-	// Export for each format
-	for format in formats {
-		let output = Command::new(SKETCHTOOL_PATH)
-			.arg(format!("--format={format}"))
-			.arg("--include-symbols=YES")
-			.arg(format!("--items={items_arg}"))
-			.arg(format!("--output={}", output_dir.as_str()))
-			.arg("export")
-			.arg("artboards")
-			.arg(sketch_file.as_str())
-			.output()
-			.map_err(|e| format!("Failed to execute sketchtool: {e}"))?;
-
-		if !output.status.success() {
-			let stderr = String::from_utf8_lossy(&output.stderr);
-			return Err(format!("sketchtool export failed for format '{format}': {stderr}").into());
+/// Applies the local references policy to an exported SVG (see `ExportOptions::local_refs`),
+/// the warning being left to the export checks.
+fn resolve_local_refs(svg_content: String, local_refs: LocalRefs, artboard_name: &str) -> Result<String> {
+	let invalid =
+		|| Error::custom(format!("Cannot check the local references of artboard '{artboard_name}' (invalid SVG)"));
+	match local_refs {
+		LocalRefs::Warn => Ok(svg_content),
+		LocalRefs::Fail => {
+			let references = svg::find_local_references(&svg_content).ok_or_else(invalid)?;
+			if references.is_empty() {
+				return Ok(svg_content);
+			}
+			Err(Error::ValidationFailed(format!(
+				"Artboard '{artboard_name}' references local files: {}",
+				references.join(", ")
+			)))
 		}
+		LocalRefs::Strip => svg::strip_local_references(&svg_content).ok_or_else(invalid),
+		LocalRefs::Embed => svg::embed_local_references(&svg_content).map_err(|err| {
+			Error::custom(format!("Cannot embed the local references of artboard '{artboard_name}'. Cause: {err}"))
+		}),
+	}
+}
 
-		// If using cache (single file output or flatten mode), move files from cache to target
-		if let Some(ref cache) = cache_dir {
-			if single_file_output {
-				// Single file output: find and move the one exported file
-				let exported_path = find_exported_file_in_cache(cache, format).ok_or("Cannot find exported")?;
-				let target_path = output_path;
-
-				// Ensure target parent directory exists
-				if let Some(parent) = target_path.parent() {
-					ensure_dir(parent.as_std_path())
-						.map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
-				}
+/// Applies the local references policy to an exported svg file in place (see `resolve_local_refs`).
+fn resolve_file_local_refs(file: &SPath, local_refs: LocalRefs, artboard_name: &str) -> Result<()> {
+	let content = read_to_string(file.as_std_path()).map_err(Error::custom_from_err)?;
+	let resolved = resolve_local_refs(content.clone(), local_refs, artboard_name)?;
+	if resolved != content {
+		std::fs::write(file.as_std_path(), resolved).map_err(|e| format!("Failed to write '{file}': {e}"))?;
+	}
+	Ok(())
+}
 
-				// Copy the file first (more reliable across filesystems), then remove source
-				std::fs::copy(exported_path.as_std_path(), target_path.as_std_path())
-					.map_err(|e| format!("Failed to copy exported file to '{}': {e}", target_path))?;
+/// Writes the redline copy of an exported png file (see `ExportOptions::redline`): the grid and the size label
+/// in artboard px (the image px divided by the scale).
+fn redline_exported_file(file: &SPath, redline_file: &SPath, grid: u32, scale: Option<&str>) -> Result<()> {
+	let scale: f64 = scale.and_then(|s| s.trim().trim_end_matches('x').parse().ok()).unwrap_or(1.0);
+	let image = raster::read_png(file)?;
+	let label = format!(
+		"{} × {}",
+		strings::format_number(image.width() as f64 / scale, 2),
+		strings::format_number(image.height() as f64 / scale, 2)
+	);
+	let redlined = raster::redline_image(&image, (grid as f64 * scale).round().max(1.0) as u32, &label)?;
+	raster::write_png(&redlined, redline_file)
+}
 
-				exported_files.push(target_path.to_string());
-			} else {
-				// Flatten mode: move all exported files with canonicalized names
-				for artboard in artboards {
-					let src_path = find_svg_file_for_artboard_with_ext(cache, &artboard.name, format)?;
-					let flattened_name = strings::canonicalize_name(&artboard.name);
-					let target_path = output_path.join(format!("{flattened_name}.{format}"));
-
-					// Ensure target parent directory exists
-					if let Some(parent) = target_path.parent() {
-						ensure_dir(parent.as_std_path())
-							.map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
-					}
+/// Splits an exported png file into tiles (see `ExportOptions::tile`): `<stem>_tiles/<column>_<row>.png`
+/// and the `<stem>.tiles.json` descriptor, next to the file (the previous tiles are removed).
+/// Returns the written files, the descriptor first.
+fn tile_exported_file(file: &SPath, tile_size: u32, overlap: u32) -> Result<Vec<String>> {
+	let image = raster::read_png(file)?;
+	let tiles = raster::tile_image(&image, tile_size, overlap)?;
+
+	let dir = file.parent().unwrap_or_else(|| SPath::new(""));
+	let stem = file.stem();
+	let tiles_dir_name = format!("{stem}{TILES_DIR_SUFFIX}");
+	let tiles_dir = dir.join(&tiles_dir_name);
+	if tiles_dir.exists() {
+		files::safer_delete_dir(&tiles_dir)?;
+	}
 
-					// Copy the file
-					std::fs::copy(src_path.as_std_path(), target_path.as_std_path())
-						.map_err(|e| format!("Failed to copy exported file to '{}': {e}", target_path))?;
+	let mut manifest = TilesManifest {
+		image: file.name().to_string(),
+		width: image.width(),
+		height: image.height(),
+		tile_size,
+		overlap,
+		columns: image.width().div_ceil(tile_size),
+		rows: image.height().div_ceil(tile_size),
+		tiles: Vec::new(),
+	};
+	let mut tile_files = Vec::new();
+	for tile in &tiles {
+		let tile_name = format!("{}_{}.png", tile.column, tile.row);
+		let tile_file = tiles_dir.join(&tile_name);
+		raster::write_png(&tile.image, &tile_file)?;
+		tile_files.push(tile_file.to_string());
+		manifest.tiles.push(TileEntry {
+			file: format!("{tiles_dir_name}/{tile_name}"),
+			column: tile.column,
+			row: tile.row,
+			x: tile.x,
+			y: tile.y,
+			width: tile.image.width(),
+			height: tile.image.height(),
+		});
+	}
 
-					exported_files.push(target_path.to_string());
-				}
-			}
+	let manifest_file = dir.join(format!("{stem}.{TILES_MANIFEST_EXT}"));
+	manifest.write(&manifest_file)?;
+	tile_files.insert(0, manifest_file.to_string());
 
-			// Clean up the cache directory (unless keep_raw_export is set)
-			if !keep_raw_export {
-				let _ = files::safer_delete_dir(cache);
-			}
-		} else {
-			// For multi-file output without flatten, build paths based on artboard names
-			// sketchtool exports files with paths matching artboard names (e.g., "ico/user/fill.svg")
-			for artboard in artboards {
-				let file_path = output_path.join(format!("{}.{format}", artboard.name));
-				exported_files.push(file_path.to_string());
-			}
+	Ok(tile_files)
+}
+
+/// Runs the sketchtool export of the artboards of a step into a directory, in batches of at most
+/// `ExportOptions::items_batch_size` artboards (a single `--items` of thousands of UIDs exceeds the command line
+/// limit of the OS). Returns the stderr of the first failed batch (with the batch position when there are several).
+fn run_step_export(
+	sketch_file: &SPath,
+	step: &PlanStep,
+	options: &ExportOptions,
+	format: &str,
+	export_dir: &SPath,
+) -> Result<Option<String>> {
+	let batches = step_items_batches(step, options.items_batch_size());
+	let option_args = sketchtool_option_args(options, &step.format)?;
+
+	for (idx, batch) in batches.iter().enumerate() {
+		let mut args = vec![
+			format!("--format={format}"),
+			"--include-symbols=YES".to_string(),
+			format!("--items={batch}"),
+		];
+		if !step.scales.is_empty() {
+			args.push(format!("--scales={}", step.scales.join(",")));
+		}
+		if step.use_id_for_name {
+			args.push("--use-id-for-name=YES".to_string());
+		}
+		args.extend(option_args.iter().cloned());
+		args.extend(step.tool_args.iter().cloned());
+
+		let output = run_sketchtool(&["export", "artboards"], &args, sketch_file, Some(export_dir))?;
+		if !output.success {
+			let stderr = String::from_utf8_lossy(&output.stderr);
+			return Ok(Some(if batches.len() > 1 {
+				format!("(batch {} of {}) {stderr}", idx + 1, batches.len())
+			} else {
+				stderr.to_string()
+			}));
 		}
 	}
 
-	Ok(exported_files)
+	Ok(None)
+}
+
+/// Returns the sketchtool `--items` arguments of a step (comma-separated UIDs, each artboard once),
+/// at most `batch_size` UIDs each.
+fn step_items_batches(step: &PlanStep, batch_size: usize) -> Vec<String> {
+	let mut item_ids: Vec<&str> = Vec::new();
+	for item in &step.items {
+		if !item_ids.contains(&item.artboard.uid.as_str()) {
+			item_ids.push(&item.artboard.uid);
+		}
+	}
+	item_ids.chunks(batch_size.max(1)).map(|batch| batch.join(",")).collect()
 }
 
 /// Finds the first file with the given extension in the cache directory (recursively).
@@ -416,16 +922,3 @@ fn find_exported_file_in_cache(cache_dir: &SPath, format: &str) -> Option<SPath>
 	let files = simple_fs::list_files(cache_dir.as_std_path(), Some(&[pattern.as_str()]), None).ok()?;
 	files.into_iter().next()
 }
-
-/// Checks if the output path appears to be a single file target.
-/// Returns true if the path ends with an extension matching one of the export formats.
-fn is_single_file_output(output_path: &SPath, formats: &[&str]) -> bool {
-	let ext = output_path.ext();
-	if ext.is_empty() {
-		return false;
-	}
-
-	// Check if the extension matches any of the export formats
-	let ext_lower = ext.to_lowercase();
-	formats.iter().any(|f| f.to_lowercase() == ext_lower)
-}
@@ -0,0 +1,256 @@
+//! The prototyping flows of a Sketch document (the links between its artboards), from the document JSON,
+//! as a graph (DOT, JSON, or Mermaid) with the dead-end screens (linked to, but without links).
+
+use crate::handlers::sketch::{
+	CLASS_ARTBOARD, CLASS_SYMBOL_INSTANCE, CLASS_SYMBOL_MASTER, child_layers, document_pages, layer_class, layer_str,
+	load_sketch_document, walk_layers,
+};
+use crate::{Error, Result};
+use serde::Serialize;
+use serde_json::Value;
+use simple_fs::SPath;
+use std::collections::{HashMap, HashSet};
+
+/// The `destinationArtboardID` of the "back" links (to the previous artboard).
+const BACK_DESTINATION: &str = "back";
+
+/// The output formats of `sketch flows`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlowFormat {
+	#[default]
+	Dot,
+	Json,
+	Mermaid,
+}
+
+impl TryFrom<&str> for FlowFormat {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"dot" => Ok(Self::Dot),
+			"json" => Ok(Self::Json),
+			"mermaid" => Ok(Self::Mermaid),
+			other => Err(Error::custom(format!("Invalid flow format '{other}'. Expected: dot, json, mermaid"))),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowArtboard {
+	/// The UID (`do_objectID`).
+	pub id: String,
+	pub name: String,
+	pub page_name: String,
+	/// True for a start point of the prototype (`isFlowHome`).
+	pub is_home: bool,
+}
+
+/// A link from a layer of an artboard (or of a symbol instance on it) to an artboard.
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowLink {
+	pub source_id: String,
+	/// The name of the layer with the link.
+	pub layer_name: String,
+	/// The target artboard UID, None for a "back" link (to the previous artboard).
+	pub target_id: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FlowGraph {
+	/// In document order.
+	pub artboards: Vec<FlowArtboard>,
+	/// In document order.
+	pub links: Vec<FlowLink>,
+	/// The artboards linked to, without links (or "back" links) to leave them.
+	pub dead_ends: Vec<String>,
+	/// The links to an artboard not in the document (e.g., deleted), as `(source id, target id)`.
+	pub broken_links: Vec<(String, String)>,
+}
+
+/// Returns the flow graph of a Sketch file (from the document JSON).
+pub fn flow_graph(sketch_file: impl AsRef<SPath>) -> Result<FlowGraph> {
+	let document = load_sketch_document(sketch_file)?;
+	Ok(build_flow_graph(&document))
+}
+
+/// Builds the flow graph from a document JSON. The links of the symbol instances are the links of their master.
+pub fn build_flow_graph(document: &Value) -> FlowGraph {
+	let mut masters: HashMap<&str, &Value> = HashMap::new();
+	for page in document_pages(document) {
+		for master in child_layers(page)
+			.iter()
+			.filter(|l| layer_class(l) == Some(CLASS_SYMBOL_MASTER))
+		{
+			masters.extend(layer_str(master, "symbolID").map(|id| (id, master)));
+		}
+	}
+	let foreign_symbols = document.pointer("/document/foreignSymbols").and_then(Value::as_array);
+	for master in foreign_symbols
+		.into_iter()
+		.flatten()
+		.filter_map(|foreign| foreign.get("symbolMaster"))
+	{
+		if let Some(id) = layer_str(master, "symbolID") {
+			masters.entry(id).or_insert(master);
+		}
+	}
+
+	let mut graph = FlowGraph::default();
+	for page in document_pages(document) {
+		let page_name = layer_str(page, "name").unwrap_or_default();
+		for artboard in child_layers(page).iter().filter(|l| layer_class(l) == Some(CLASS_ARTBOARD)) {
+			let Some(id) = layer_str(artboard, "do_objectID") else {
+				continue;
+			};
+			graph.artboards.push(FlowArtboard {
+				id: id.to_string(),
+				name: layer_str(artboard, "name").unwrap_or_default().to_string(),
+				page_name: page_name.to_string(),
+				is_home: artboard.get("isFlowHome").and_then(Value::as_bool).unwrap_or(false),
+			});
+			collect_links(artboard, id, &masters, &mut HashSet::new(), &mut graph.links);
+		}
+	}
+
+	// -- Dead ends and broken links
+	let artboard_ids: HashSet<&str> = graph.artboards.iter().map(|artboard| artboard.id.as_str()).collect();
+	let sources: HashSet<&str> = graph.links.iter().map(|link| link.source_id.as_str()).collect();
+	let mut dead_ends: Vec<String> = Vec::new();
+	let mut broken_links = Vec::new();
+	for link in &graph.links {
+		let Some(target_id) = &link.target_id else {
+			continue;
+		};
+		if !artboard_ids.contains(target_id.as_str()) {
+			broken_links.push((link.source_id.clone(), target_id.clone()));
+		} else if !sources.contains(target_id.as_str()) && !dead_ends.contains(target_id) {
+			dead_ends.push(target_id.clone());
+		}
+	}
+	graph.dead_ends = dead_ends;
+	graph.broken_links = broken_links;
+
+	graph
+}
+
+/// Collects the links of the layers of a layer (and of the masters of its symbol instances).
+/// `visited_masters` guards against the symbols nested in themselves.
+fn collect_links<'a>(
+	layer: &'a Value,
+	source_id: &str,
+	masters: &HashMap<&str, &'a Value>,
+	visited_masters: &mut HashSet<&'a str>,
+	links: &mut Vec<FlowLink>,
+) {
+	walk_layers(layer, &mut |child| {
+		if let Some(target) = child.pointer("/flow/destinationArtboardID").and_then(Value::as_str) {
+			links.push(FlowLink {
+				source_id: source_id.to_string(),
+				layer_name: layer_str(child, "name").unwrap_or_default().to_string(),
+				target_id: (target != BACK_DESTINATION).then(|| target.to_string()),
+			});
+		}
+		if layer_class(child) == Some(CLASS_SYMBOL_INSTANCE)
+			&& let Some(symbol_id) = layer_str(child, "symbolID")
+			&& let Some(master) = masters.get(symbol_id)
+			&& visited_masters.insert(symbol_id)
+		{
+			collect_links(master, source_id, masters, visited_masters, links);
+			visited_masters.remove(symbol_id);
+		}
+	});
+}
+
+/// Formats the flow graph as DOT (Graphviz), JSON, or a Mermaid flowchart.
+/// The start points are double-bordered (DOT) or rounded (Mermaid), the dead ends are red.
+pub fn format_flow_graph(graph: &FlowGraph, format: FlowFormat) -> Result<String> {
+	match format {
+		FlowFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(graph)?)),
+		FlowFormat::Dot => Ok(flow_graph_dot(graph)),
+		FlowFormat::Mermaid => Ok(flow_graph_mermaid(graph)),
+	}
+}
+
+fn flow_graph_dot(graph: &FlowGraph) -> String {
+	let quote = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+
+	let mut content = String::from("digraph flows {\n\trankdir=LR;\n\tnode [shape=box];\n");
+	for artboard in &graph.artboards {
+		let mut attributes = vec![format!("label={}", quote(&artboard.name))];
+		if artboard.is_home {
+			attributes.push("peripheries=2".to_string());
+		}
+		if graph.dead_ends.contains(&artboard.id) {
+			attributes.push("color=red".to_string());
+		}
+		content.push_str(&format!("\t{} [{}];\n", quote(&artboard.id), attributes.join(", ")));
+	}
+	if graph.links.iter().any(|link| link.target_id.is_none()) {
+		content.push_str("\t\"back\" [label=\"(back)\", shape=plaintext];\n");
+	}
+	for link in &graph.links {
+		let target = link.target_id.as_deref().unwrap_or(BACK_DESTINATION);
+		if graph
+			.broken_links
+			.iter()
+			.any(|(source, broken)| *source == link.source_id && broken == target)
+		{
+			continue;
+		}
+		let style = if link.target_id.is_none() { ", style=dashed" } else { "" };
+		content.push_str(&format!(
+			"\t{} -> {} [label={}{style}];\n",
+			quote(&link.source_id),
+			quote(target),
+			quote(&link.layer_name)
+		));
+	}
+	content.push_str("}\n");
+	content
+}
+
+fn flow_graph_mermaid(graph: &FlowGraph) -> String {
+	// Mermaid ids are the artboard indexes ("n0", "n1", ...), the labels are quoted (quotes as entities)
+	let quote = |value: &str| format!("\"{}\"", value.replace('"', "#quot;"));
+	let node_ids: HashMap<&str, String> = graph
+		.artboards
+		.iter()
+		.enumerate()
+		.map(|(idx, artboard)| (artboard.id.as_str(), format!("n{idx}")))
+		.collect();
+
+	let mut content = String::from("flowchart LR\n");
+	for artboard in &graph.artboards {
+		let label = quote(&artboard.name);
+		let node = if artboard.is_home { format!("([{label}])") } else { format!("[{label}]") };
+		content.push_str(&format!("\t{}{node}\n", node_ids[artboard.id.as_str()]));
+	}
+	if graph.links.iter().any(|link| link.target_id.is_none()) {
+		content.push_str("\tback{{\"(back)\"}}\n");
+	}
+	for link in &graph.links {
+		let Some(source) = node_ids.get(link.source_id.as_str()) else {
+			continue;
+		};
+		let (target, arrow) = match &link.target_id {
+			Some(target_id) => match node_ids.get(target_id.as_str()) {
+				Some(target) => (target.as_str(), "-->"),
+				None => continue, // broken link
+			},
+			None => (BACK_DESTINATION, "-.->"),
+		};
+		content.push_str(&format!("\t{source} {arrow}|{}| {target}\n", quote(&link.layer_name)));
+	}
+	let dead_end_ids: Vec<&str> = graph
+		.dead_ends
+		.iter()
+		.filter_map(|id| node_ids.get(id.as_str()))
+		.map(String::as_str)
+		.collect();
+	if !dead_end_ids.is_empty() {
+		content.push_str("\tclassDef deadEnd stroke:#d33,stroke-width:2px\n");
+		content.push_str(&format!("\tclass {} deadEnd\n", dead_end_ids.join(",")));
+	}
+	content
+}
@@ -0,0 +1,149 @@
+//! Sketch font usage analysis (the fonts of the text layers, from the document JSON).
+
+use crate::Result;
+use crate::handlers::sketch::{
+	CLASS_ARTBOARD, CLASS_SYMBOL_MASTER, child_layers, document_pages, layer_class, layer_str, load_sketch_document,
+	walk_layers,
+};
+use serde::Serialize;
+use serde_json::Value;
+use simple_fs::SPath;
+use std::collections::BTreeMap;
+
+/// The layer class of the text layers.
+const CLASS_TEXT: &str = "text";
+
+/// The font name of a text style or of an attributed string run (the PostScript name, e.g., "Inter-Bold").
+const FONT_NAME_POINTER: &str = "/MSAttributedStringFontAttribute/attributes/name";
+
+/// A font referenced by text layers.
+#[derive(Debug, Clone, Serialize)]
+pub struct FontUsage {
+	/// The PostScript name (e.g., "Inter-Bold").
+	pub name: String,
+	/// The family part of the name (e.g., "Inter").
+	pub family: String,
+	pub text_layer_count: usize,
+	/// The artboards and symbol masters using the font (sorted, "" for the layers outside of them).
+	pub used_in: Vec<String>,
+	/// True when brand fonts are given and the font is not one of them.
+	pub non_brand: bool,
+}
+
+/// A text layer without a font (no font attribute, or an empty font name).
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingFontLayer {
+	pub page_name: String,
+	/// The artboard or symbol master of the layer ("" when outside of them).
+	pub container_name: String,
+	pub layer_name: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FontsReport {
+	pub text_layer_count: usize,
+	/// Sorted by name.
+	pub fonts: Vec<FontUsage>,
+	/// In document order.
+	pub missing_fonts: Vec<MissingFontLayer>,
+}
+
+impl FontsReport {
+	pub fn non_brand_count(&self) -> usize {
+		self.fonts.iter().filter(|font| font.non_brand).count()
+	}
+}
+
+/// Reports the fonts of the text layers of a Sketch file (from the document JSON), flagging the fonts
+/// not in `brand_fonts` (family or PostScript names, none flagged when empty).
+pub fn fonts_report(sketch_file: impl AsRef<SPath>, brand_fonts: &[&str]) -> Result<FontsReport> {
+	let document = load_sketch_document(sketch_file)?;
+	Ok(build_fonts_report(&document, brand_fonts))
+}
+
+/// Builds the fonts report from a document JSON (see `fonts_report`).
+pub fn build_fonts_report(document: &Value, brand_fonts: &[&str]) -> FontsReport {
+	let mut report = FontsReport::default();
+	let mut fonts: BTreeMap<String, FontUsage> = BTreeMap::new();
+
+	for page in document_pages(document) {
+		let page_name = layer_str(page, "name").unwrap_or_default();
+		for layer in child_layers(page) {
+			let is_container = matches!(layer_class(layer), Some(CLASS_ARTBOARD | CLASS_SYMBOL_MASTER));
+			let container_name = if is_container { layer_str(layer, "name").unwrap_or_default() } else { "" };
+
+			let mut visit = |text_layer: &Value| {
+				if layer_class(text_layer) != Some(CLASS_TEXT) {
+					return;
+				}
+				report.text_layer_count += 1;
+
+				let font_names = text_layer_font_names(text_layer);
+				if font_names.is_empty() {
+					report.missing_fonts.push(MissingFontLayer {
+						page_name: page_name.to_string(),
+						container_name: container_name.to_string(),
+						layer_name: layer_str(text_layer, "name").unwrap_or_default().to_string(),
+					});
+				}
+				for name in font_names {
+					let font = fonts.entry(name.to_string()).or_insert_with(|| FontUsage {
+						name: name.to_string(),
+						family: font_family(name).to_string(),
+						text_layer_count: 0,
+						used_in: Vec::new(),
+						non_brand: !brand_fonts.is_empty()
+							&& !brand_fonts.iter().any(|brand| is_brand_font(name, brand)),
+					});
+					font.text_layer_count += 1;
+					if !font.used_in.iter().any(|used_in| used_in == container_name) {
+						font.used_in.push(container_name.to_string());
+					}
+				}
+			};
+			visit(layer);
+			walk_layers(layer, &mut visit);
+		}
+	}
+
+	report.fonts = fonts.into_values().collect();
+	for font in &mut report.fonts {
+		font.used_in.sort();
+	}
+
+	report
+}
+
+/// Returns the distinct font names of a text layer: of its text style, and of the runs of its attributed string.
+fn text_layer_font_names(text_layer: &Value) -> Vec<&str> {
+	let style_attributes = text_layer.pointer("/style/textStyle/encodedAttributes");
+	let run_attributes = text_layer
+		.pointer("/attributedString/attributes")
+		.and_then(Value::as_array)
+		.into_iter()
+		.flatten()
+		.filter_map(|run| run.get("attributes"));
+
+	let mut names: Vec<&str> = Vec::new();
+	for attributes in style_attributes.into_iter().chain(run_attributes) {
+		let name = attributes.pointer(FONT_NAME_POINTER).and_then(Value::as_str).map(str::trim);
+		if let Some(name) = name.filter(|name| !name.is_empty())
+			&& !names.contains(&name)
+		{
+			names.push(name);
+		}
+	}
+	names
+}
+
+/// Returns the family part of a PostScript name (e.g., "Inter-Bold" -> "Inter").
+fn font_family(name: &str) -> &str {
+	name.split_once('-').map(|(family, _)| family).unwrap_or(name)
+}
+
+/// Returns true when a PostScript name is of a brand font, given as a PostScript name or a family name
+/// (case insensitive, spaces ignored, e.g., "SF Pro Text" matches "SFProText-Regular").
+fn is_brand_font(name: &str, brand_font: &str) -> bool {
+	let brand_font: String = brand_font.chars().filter(|c| !c.is_whitespace()).collect();
+	name.eq_ignore_ascii_case(&brand_font) || font_family(name).eq_ignore_ascii_case(&brand_font)
+}
@@ -0,0 +1,249 @@
+//! Layer hygiene checks of a Sketch document (default layer names, hidden layers, clipping masks, detached symbols),
+//! on the layers of its artboards (from the document JSON, see `load_sketch_document`).
+
+use crate::handlers::sketch::{
+	CLASS_ARTBOARD, CLASS_SYMBOL_MASTER, child_layers, document_pages, layer_class, layer_str, load_sketch_document,
+};
+use crate::support::globs;
+use crate::{Error, Result};
+use derive_more::Display;
+use serde::Serialize;
+use serde_json::Value;
+use simple_fs::SPath;
+use std::collections::{BTreeMap, HashSet};
+
+/// The names Sketch gives to the new layers (followed by a number or "Copy", e.g., "Rectangle 3", "Oval Copy 2").
+const DEFAULT_LAYER_NAMES: &[&str] = &[
+	"Rectangle",
+	"Oval",
+	"Line",
+	"Arrow",
+	"Triangle",
+	"Star",
+	"Polygon",
+	"Path",
+	"Shape",
+	"Combined Shape",
+	"Vector",
+	"Group",
+	"Image",
+	"Bitmap",
+	"Slice",
+	"Hotspot",
+	"Mask",
+];
+
+/// The layer class of the groups.
+const CLASS_GROUP: &str = "group";
+
+/// The lint rules (serialized as kebab-case, e.g., "default-layer-name").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Display, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+	/// A layer keeps the name Sketch gave it (e.g., "Rectangle 3").
+	#[display("default-layer-name")]
+	DefaultLayerName,
+
+	/// A hidden layer (not exported, but still in the file).
+	#[display("hidden-layer")]
+	HiddenLayer,
+
+	/// A group clipped by a mask layer (often exported as a `<mask>` or `<clipPath>`).
+	#[display("clipping-mask")]
+	ClippingMask,
+
+	/// A group named like a symbol master, likely a detached symbol instance.
+	#[display("detached-symbol")]
+	DetachedSymbol,
+}
+
+impl LintRule {
+	pub const ALL: [LintRule; 4] = [
+		Self::DefaultLayerName,
+		Self::HiddenLayer,
+		Self::ClippingMask,
+		Self::DetachedSymbol,
+	];
+}
+
+impl TryFrom<&str> for LintRule {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		Self::ALL
+			.into_iter()
+			.find(|rule| rule.to_string() == value.trim())
+			.ok_or_else(|| {
+				let names: Vec<String> = Self::ALL.iter().map(|rule| rule.to_string()).collect();
+				Error::custom(format!("Invalid lint rule '{value}'. Expected: {}", names.join(", ")))
+			})
+	}
+}
+
+/// A layer breaking a lint rule.
+#[derive(Debug, Clone, Serialize)]
+pub struct LintViolation {
+	pub rule: LintRule,
+	pub page_name: String,
+	pub artboard_name: String,
+	/// The layer names from the artboard, joined by " > " (empty for the artboard itself).
+	pub layer_path: String,
+	pub message: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct LintReport {
+	/// The artboards checked (matched by the globs).
+	pub artboard_count: usize,
+	/// The layers checked, in these artboards.
+	pub layer_count: usize,
+	/// In document order.
+	pub violations: Vec<LintViolation>,
+}
+
+impl LintReport {
+	/// Returns the violation counts by rule (rules without violations omitted).
+	pub fn counts_by_rule(&self) -> BTreeMap<LintRule, usize> {
+		let mut counts = BTreeMap::new();
+		for violation in &self.violations {
+			*counts.entry(violation.rule).or_default() += 1;
+		}
+		counts
+	}
+}
+
+/// Checks the layers of the artboards of a Sketch file matching the globs (all when None),
+/// with all the rules but the ignored ones.
+pub fn lint_sketch_file(
+	sketch_file: impl AsRef<SPath>,
+	glob_patterns: Option<&[&str]>,
+	ignored_rules: &[LintRule],
+) -> Result<LintReport> {
+	let document = load_sketch_document(sketch_file)?;
+	build_lint_report(&document, glob_patterns, ignored_rules)
+}
+
+/// Builds the lint report from a document JSON (see `lint_sketch_file`).
+pub fn build_lint_report(
+	document: &Value,
+	glob_patterns: Option<&[&str]>,
+	ignored_rules: &[LintRule],
+) -> Result<LintReport> {
+	let glob_set = globs::build_glob_set(glob_patterns)?;
+
+	// The names of the local and library symbol masters (for the detached symbols)
+	let mut symbol_names: HashSet<&str> = HashSet::new();
+	for page in document_pages(document) {
+		for layer in child_layers(page)
+			.iter()
+			.filter(|l| layer_class(l) == Some(CLASS_SYMBOL_MASTER))
+		{
+			symbol_names.extend(layer_str(layer, "name"));
+		}
+	}
+	let foreign_symbols = document.pointer("/document/foreignSymbols").and_then(Value::as_array);
+	for foreign in foreign_symbols.into_iter().flatten() {
+		symbol_names.extend(foreign.get("symbolMaster").and_then(|master| layer_str(master, "name")));
+	}
+
+	let mut report = LintReport::default();
+	for page in document_pages(document) {
+		let page_name = layer_str(page, "name").unwrap_or_default();
+		for artboard in child_layers(page).iter().filter(|l| layer_class(l) == Some(CLASS_ARTBOARD)) {
+			let artboard_name = layer_str(artboard, "name").unwrap_or_default();
+			if !globs::matches_glob_set(glob_set.as_ref(), artboard_name) {
+				continue;
+			}
+			report.artboard_count += 1;
+
+			let mut linter = ArtboardLinter {
+				page_name,
+				artboard_name,
+				symbol_names: &symbol_names,
+				ignored_rules,
+				report: &mut report,
+			};
+			linter.lint_children(artboard, &mut Vec::new());
+		}
+	}
+
+	Ok(report)
+}
+
+/// Walks the layers of an artboard, adding the violations to the report.
+struct ArtboardLinter<'a> {
+	page_name: &'a str,
+	artboard_name: &'a str,
+	symbol_names: &'a HashSet<&'a str>,
+	ignored_rules: &'a [LintRule],
+	report: &'a mut LintReport,
+}
+
+impl ArtboardLinter<'_> {
+	fn lint_children(&mut self, parent: &Value, path: &mut Vec<String>) {
+		let children = child_layers(parent);
+
+		if let Some(mask) = children.iter().find(|layer| is_true(layer, "hasClippingMask")) {
+			let mask_name = layer_str(mask, "name").unwrap_or_default();
+			let target = path
+				.last()
+				.map(|name| format!("Group '{name}'"))
+				.unwrap_or_else(|| "The artboard".to_string());
+			self.add(LintRule::ClippingMask, path, format!("{target} is clipped by the mask layer '{mask_name}'"));
+		}
+
+		for layer in children {
+			let name = layer_str(layer, "name").unwrap_or_default();
+			path.push(name.to_string());
+			self.report.layer_count += 1;
+
+			if is_default_layer_name(name) {
+				self.add(LintRule::DefaultLayerName, path, format!("Layer '{name}' has a default name"));
+			}
+			if layer_class(layer) == Some(CLASS_GROUP) && self.symbol_names.contains(name) {
+				self.add(LintRule::DetachedSymbol, path, format!("Group '{name}' is named like a symbol (detached?)"));
+			}
+
+			// The layers of a hidden layer are hidden too, reported once
+			if layer.get("isVisible").and_then(Value::as_bool) == Some(false) {
+				self.add(LintRule::HiddenLayer, path, format!("Layer '{name}' is hidden"));
+			} else {
+				self.lint_children(layer, path);
+			}
+
+			path.pop();
+		}
+	}
+
+	fn add(&mut self, rule: LintRule, path: &[String], message: String) {
+		if self.ignored_rules.contains(&rule) {
+			return;
+		}
+		self.report.violations.push(LintViolation {
+			rule,
+			page_name: self.page_name.to_string(),
+			artboard_name: self.artboard_name.to_string(),
+			layer_path: path.join(" > "),
+			message,
+		});
+	}
+}
+
+/// Returns true for a name Sketch gives to the new layers (e.g., "Rectangle", "Rectangle 3", "Oval Copy 2").
+fn is_default_layer_name(name: &str) -> bool {
+	DEFAULT_LAYER_NAMES.iter().any(|default_name| {
+		let Some(rest) = name.strip_prefix(default_name) else {
+			return false;
+		};
+		let rest = rest.strip_prefix(" Copy").unwrap_or(rest);
+		rest.is_empty()
+			|| rest
+				.strip_prefix(' ')
+				.is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+	})
+}
+
+/// Returns true when a boolean field of a layer is true.
+fn is_true(layer: &Value, field: &str) -> bool {
+	layer.get(field).and_then(Value::as_bool) == Some(true)
+}
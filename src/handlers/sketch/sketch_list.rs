@@ -1,12 +1,10 @@
-use crate::Result;
-use crate::handlers::sketch::Artboard;
-use crate::support::{files, globs};
+use crate::handlers::sketch::{Artboard, ArtboardFrame, GlobScope, run_sketchtool};
+use crate::support::globs::GlobCase;
+use crate::support::{files, globs, strings};
+use crate::{Error, Result};
 use serde::Deserialize;
 use simple_fs::SPath;
 use std::collections::HashMap;
-use std::process::Command;
-
-const SKETCHTOOL_PATH: &str = "/Applications/Sketch.app/Contents/Resources/sketchtool/bin/sketchtool";
 
 // region:    --- Sketchtool JSON Response Types
 
@@ -14,10 +12,15 @@ const SKETCHTOOL_PATH: &str = "/Applications/Sketch.app/Contents/Resources/sketc
 #[serde(rename_all = "camelCase")]
 struct SketchMetadataResponse {
 	pages_and_artboards: HashMap<String, SketchPage>,
+	/// The Sketch version that saved the file (e.g., "100.3").
+	#[serde(default)]
+	app_version: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct SketchPage {
+	#[serde(default)]
+	name: String,
 	artboards: HashMap<String, SketchArtboard>,
 }
 
@@ -26,36 +29,180 @@ struct SketchArtboard {
 	name: String,
 }
 
+#[derive(Deserialize)]
+struct SketchListResponse {
+	pages: Vec<SketchListPage>,
+}
+
+#[derive(Deserialize)]
+struct SketchListPage {
+	#[serde(default)]
+	name: String,
+	#[serde(default)]
+	artboards: Vec<SketchListArtboard>,
+}
+
+#[derive(Deserialize)]
+struct SketchListArtboard {
+	id: String,
+	name: String,
+	rect: SketchRect,
+}
+
+#[derive(Deserialize)]
+struct SketchRect {
+	width: f64,
+	height: f64,
+}
+
 // endregion: --- Sketchtool JSON Response Types
 
-pub fn list_artboards(sketch_file: impl AsRef<SPath>, glob_patterns: Option<&[&str]>) -> Result<Vec<Artboard>> {
+/// Lists the artboards matching the globs (all when None), sorted by name.
+/// The globs match the artboard names, or their page and name with `GlobScope::PagePath` (e.g., "Icons/ico/*"),
+/// with the letter case of `glob_case`.
+pub fn list_artboards(
+	sketch_file: impl AsRef<SPath>,
+	glob_patterns: Option<&[&str]>,
+	glob_scope: GlobScope,
+	glob_case: GlobCase,
+) -> Result<Vec<Artboard>> {
 	let sketch_file = sketch_file.as_ref();
 
-	let glob_set = globs::build_glob_set(glob_patterns)?;
+	let glob_set = globs::build_glob_set_with_case(glob_patterns, glob_case)?;
+
+	let response = read_metadata(sketch_file)?;
+
+	let mut artboards: Vec<Artboard> = response
+		.pages_and_artboards
+		.into_values()
+		.flat_map(|page| {
+			let page_name = page.name;
+			page.artboards.into_iter().map(move |(uid, ab)| Artboard {
+				uid,
+				name: ab.name,
+				page_name: page_name.clone(),
+			})
+		})
+		.filter(|ab| globs::matches_glob_set(glob_set.as_ref(), &ab.glob_path(glob_scope)))
+		.collect();
+
+	// Sort for deterministic outputs (the sketchtool metadata is keyed by uid, in no particular order)
+	artboards.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.uid.cmp(&b.uid)));
+
+	Ok(artboards)
+}
+
+/// Lists all the artboards with their size (from `sketchtool list artboards`), sorted by name.
+pub fn list_artboard_frames(sketch_file: impl AsRef<SPath>) -> Result<Vec<ArtboardFrame>> {
+	let sketch_file = sketch_file.as_ref();
 
 	files::check_file_exists(sketch_file)?;
 
-	let output = Command::new(SKETCHTOOL_PATH)
-		.args(["metadata", sketch_file.as_str()])
-		.output()
-		.map_err(|e| format!("Failed to execute sketchtool: {e}"))?;
+	let output = run_sketchtool(&["list", "artboards"], &[], sketch_file, None)?;
 
-	if !output.status.success() {
+	if !output.success {
 		let stderr = String::from_utf8_lossy(&output.stderr);
 		return Err(format!("sketchtool failed: {stderr}").into());
 	}
 
 	let stdout = String::from_utf8_lossy(&output.stdout);
-	let response: SketchMetadataResponse =
+	let response: SketchListResponse =
 		serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse sketchtool output: {e}"))?;
 
-	let artboards = response
-		.pages_and_artboards
-		.into_values()
-		.flat_map(|page| page.artboards)
-		.map(|(uid, ab)| Artboard { uid, name: ab.name })
-		.filter(|ab| globs::matches_glob_set(glob_set.as_ref(), &ab.name))
+	let mut frames: Vec<ArtboardFrame> = response
+		.pages
+		.into_iter()
+		.flat_map(|page| {
+			let page_name = page.name;
+			page.artboards.into_iter().map(move |ab| ArtboardFrame {
+				artboard: Artboard { uid: ab.id, name: ab.name, page_name: page_name.clone() },
+				width: ab.rect.width,
+				height: ab.rect.height,
+			})
+		})
 		.collect();
 
-	Ok(artboards)
+	frames.sort_by(|a, b| {
+		a.artboard
+			.name
+			.cmp(&b.artboard.name)
+			.then_with(|| a.artboard.uid.cmp(&b.artboard.uid))
+	});
+
+	Ok(frames)
+}
+
+/// Returns the version of Sketch that saved the file (e.g., "100.3"), from the sketchtool metadata.
+pub fn sketch_app_version(sketch_file: impl AsRef<SPath>) -> Result<String> {
+	let sketch_file = sketch_file.as_ref();
+	read_metadata(sketch_file)?
+		.app_version
+		.ok_or_else(|| Error::custom(format!("No app version in the metadata of '{sketch_file}'")))
+}
+
+fn read_metadata(sketch_file: &SPath) -> Result<SketchMetadataResponse> {
+	files::check_file_exists(sketch_file)?;
+
+	let output = run_sketchtool(&["metadata"], &[], sketch_file, None)?;
+
+	if !output.success {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(format!("sketchtool failed: {stderr}").into());
+	}
+
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	let response: SketchMetadataResponse =
+		serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse sketchtool output: {e}"))?;
+
+	Ok(response)
+}
+
+/// Returns the artboard names closest to the glob patterns (near misses, by edit distance),
+/// for "did you mean" hints when nothing matched. At most `max` names, closest first.
+pub fn suggest_artboard_names(glob_patterns: &[&str], artboard_names: &[&str], max: usize) -> Vec<String> {
+	let mut scored: Vec<(usize, &str)> = Vec::new();
+
+	for glob in glob_patterns {
+		// Only the literal part before the first wildcard can be compared
+		let literal_end = glob.find(['*', '?', '[', '{']).unwrap_or(glob.len());
+		let literal = &glob[..literal_end];
+		let has_wildcard = literal_end < glob.len();
+		let literal_len = literal.chars().count();
+		if literal_len == 0 {
+			continue;
+		}
+		let threshold = (literal_len / 3).max(1);
+
+		for name in artboard_names {
+			let distance = if has_wildcard {
+				// Compare with the name prefixes of about the literal length (e.g., "ico/usr/" vs "ico/user/")
+				let name_chars: Vec<char> = name.chars().collect();
+				let min_len = literal_len.saturating_sub(threshold);
+				let max_len = (literal_len + threshold).min(name_chars.len());
+				(min_len..=max_len)
+					.map(|len| strings::edit_distance(literal, &name_chars[..len].iter().collect::<String>()))
+					.min()
+			} else {
+				Some(strings::edit_distance(literal, name))
+			};
+
+			if let Some(distance) = distance
+				&& distance <= threshold
+			{
+				scored.push((distance, name));
+			}
+		}
+	}
+
+	scored.sort();
+	let mut suggestions: Vec<String> = Vec::new();
+	for (_, name) in scored {
+		if !suggestions.iter().any(|s| s == name) {
+			suggestions.push(name.to_string());
+		}
+		if suggestions.len() == max {
+			break;
+		}
+	}
+	suggestions
 }
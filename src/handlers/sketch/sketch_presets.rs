@@ -0,0 +1,266 @@
+//! The export presets of the artboards (the formats and sizes the designers configured in the Sketch file,
+//! the `exportOptions` of the artboard layers in the document JSON), read with sketchtool, and written back in place
+//! (`webtk sketch set-presets`, see `SketchArchive`).
+
+use crate::handlers::sketch::{
+	CLASS_ARTBOARD, SketchArchive, backup_sketch_file, child_layers, document_pages, layer_class, layer_str,
+	load_sketch_document,
+};
+use crate::support::{files, globs, strings};
+use crate::{Error, Result};
+use derive_more::Display;
+use serde::Serialize;
+use serde_json::{Value, json};
+use simple_fs::SPath;
+use std::collections::HashMap;
+
+/// The file formats of the Sketch export presets.
+pub const PRESET_FORMATS: &[&str] = &["png", "jpg", "tiff", "webp", "pdf", "eps", "svg"];
+
+/// An export preset of an artboard (e.g., "png@2x", "svg", "png@512w").
+#[derive(Debug, Clone, PartialEq, Display, Serialize)]
+#[display("{format}@{size}")]
+pub struct ExportPreset {
+	/// The Sketch file format (e.g., "png", "jpg", "svg", "pdf").
+	pub format: String,
+	pub size: PresetSize,
+}
+
+/// The size of an export preset.
+#[derive(Debug, Clone, Copy, PartialEq, Display, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PresetSize {
+	/// A scale of the artboard (e.g., 2 for "2x").
+	#[display("{}x", strings::format_number(*_0, 2))]
+	Scale(f64),
+	/// A width in px, the height scaled proportionally (e.g., "512w").
+	#[display("{}w", strings::format_number(*_0, 2))]
+	Width(f64),
+	/// A height in px, the width scaled proportionally (e.g., "512h").
+	#[display("{}h", strings::format_number(*_0, 2))]
+	Height(f64),
+}
+
+impl TryFrom<&str> for ExportPreset {
+	type Error = Error;
+
+	/// Parses "FORMAT[@SIZE]" (e.g., "svg", "png@2x", "png@512w", "jpg@256h"), the size 1x by default.
+	fn try_from(value: &str) -> Result<Self> {
+		let invalid = || {
+			Error::custom(format!(
+				"Invalid preset '{value}'. Expected FORMAT[@SIZE] (e.g., svg, png@2x, png@512w), the format one of: {}",
+				PRESET_FORMATS.join(", ")
+			))
+		};
+
+		let (format, size) = value.trim().split_once('@').unwrap_or((value.trim(), "1x"));
+		let format = format.to_lowercase();
+		if !PRESET_FORMATS.contains(&format.as_str()) {
+			return Err(invalid());
+		}
+		let Some(kind) = size.chars().last() else {
+			return Err(invalid());
+		};
+		let number: f64 = size[..size.len() - kind.len_utf8()].parse().map_err(|_| invalid())?;
+		if !(number.is_finite() && number > 0.0) {
+			return Err(invalid());
+		}
+		let size = match kind {
+			'x' => PresetSize::Scale(number),
+			'w' => PresetSize::Width(number),
+			'h' => PresetSize::Height(number),
+			_ => return Err(invalid()),
+		};
+
+		Ok(Self { format, size })
+	}
+}
+
+impl ExportPreset {
+	/// Returns the sketchtool `--scales` value of the preset (None for the width and height sizes).
+	pub fn scale(&self) -> Option<String> {
+		match self.size {
+			PresetSize::Scale(scale) => Some(strings::format_number(scale, 2)),
+			PresetSize::Width(_) | PresetSize::Height(_) => None,
+		}
+	}
+}
+
+/// Loads the export presets of the artboards of a Sketch file, by artboard UID (the artboards without presets
+/// are not in the map).
+pub fn load_export_presets(sketch_file: impl AsRef<SPath>) -> Result<HashMap<String, Vec<ExportPreset>>> {
+	let document = load_sketch_document(sketch_file)?;
+
+	Ok(document_pages(&document)
+		.iter()
+		.flat_map(|page| child_layers(page).iter().filter(|l| layer_class(l) == Some(CLASS_ARTBOARD)))
+		.filter_map(|artboard| {
+			let uid = layer_str(artboard, "do_objectID")?;
+			let presets = artboard_export_presets(artboard);
+			(!presets.is_empty()).then(|| (uid.to_string(), presets))
+		})
+		.collect())
+}
+
+/// Returns the export presets of an artboard layer of the document JSON, in the Sketch order.
+/// The presets without a file format are skipped.
+pub fn artboard_export_presets(artboard: &Value) -> Vec<ExportPreset> {
+	let formats = artboard.pointer("/exportOptions/exportFormats").and_then(Value::as_array);
+
+	formats
+		.into_iter()
+		.flatten()
+		.filter_map(|preset| {
+			let format = preset.get("fileFormat").and_then(Value::as_str)?.trim().to_lowercase();
+			if format.is_empty() {
+				return None;
+			}
+			// The absolute size is set for the width and height sizes (visibleScaleType 1 and 2)
+			let absolute_size = preset.get("absoluteSize").and_then(Value::as_f64).unwrap_or(0.0);
+			let size = match preset.get("visibleScaleType").and_then(Value::as_u64) {
+				Some(1) if absolute_size > 0.0 => PresetSize::Width(absolute_size),
+				Some(2) if absolute_size > 0.0 => PresetSize::Height(absolute_size),
+				_ => PresetSize::Scale(preset.get("scale").and_then(Value::as_f64).unwrap_or(1.0)),
+			};
+			Some(ExportPreset { format, size })
+		})
+		.collect()
+}
+
+// region:    --- Write Back
+
+/// What `set_export_presets` changed.
+#[derive(Debug, Default, Serialize)]
+pub struct SetPresetsReport {
+	/// The artboards matched by the globs.
+	pub artboard_count: usize,
+	/// The matched artboards whose presets changed.
+	pub updated: Vec<PresetsUpdate>,
+	/// The copy of the Sketch file before the changes (with `backup`).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub backup_file: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PresetsUpdate {
+	pub uid: String,
+	pub page: String,
+	pub name: String,
+	/// e.g., ["png@1x"]
+	pub before: Vec<String>,
+	pub after: Vec<String>,
+}
+
+/// Sets the export presets of the artboards matching the globs (all when None), replacing their presets, or adding
+/// the missing ones with `append`. Nothing is written with `dry_run`, or when no presets changed.
+pub fn set_export_presets(
+	sketch_file: &SPath,
+	glob_patterns: Option<&[&str]>,
+	presets: &[ExportPreset],
+	append: bool,
+	backup: bool,
+	dry_run: bool,
+) -> Result<SetPresetsReport> {
+	files::check_file_exists(sketch_file)?;
+	let glob_set = globs::build_glob_set(glob_patterns)?;
+	let mut archive = SketchArchive::open(sketch_file)?;
+	let mut report = SetPresetsReport::default();
+
+	for page_file in archive.page_files() {
+		let mut page = archive.read_json(&page_file)?;
+		let page_name = layer_str(&page, "name").unwrap_or_default().to_string();
+		let Some(layers) = page.get_mut("layers").and_then(Value::as_array_mut) else {
+			continue;
+		};
+
+		let mut page_changed = false;
+		for artboard in layers.iter_mut().filter(|layer| layer_class(layer) == Some(CLASS_ARTBOARD)) {
+			let name = layer_str(artboard, "name").unwrap_or_default().to_string();
+			if !globs::matches_glob_set(glob_set.as_ref(), &name) {
+				continue;
+			}
+			report.artboard_count += 1;
+
+			let before = artboard_export_presets(artboard);
+			let mut after = if append { before.clone() } else { Vec::new() };
+			for preset in presets {
+				if !after.contains(preset) {
+					after.push(preset.clone());
+				}
+			}
+			if after == before {
+				continue;
+			}
+
+			set_artboard_export_formats(artboard, &after);
+			page_changed = true;
+			report.updated.push(PresetsUpdate {
+				uid: layer_str(artboard, "do_objectID").unwrap_or_default().to_string(),
+				page: page_name.clone(),
+				name,
+				before: before.iter().map(ExportPreset::to_string).collect(),
+				after: after.iter().map(ExportPreset::to_string).collect(),
+			});
+		}
+
+		if page_changed {
+			archive.write_json(&page_file, &page)?;
+		}
+	}
+
+	if report.updated.is_empty() || dry_run {
+		return Ok(report);
+	}
+
+	if backup {
+		report.backup_file = Some(backup_sketch_file(sketch_file)?.to_string());
+	}
+	archive.save(sketch_file)?;
+
+	Ok(report)
+}
+
+/// Replaces the `exportFormats` of an artboard layer (creating its `exportOptions` when missing).
+fn set_artboard_export_formats(artboard: &mut Value, presets: &[ExportPreset]) {
+	let formats: Vec<Value> = presets.iter().map(export_format_json).collect();
+
+	match artboard.get_mut("exportOptions").and_then(Value::as_object_mut) {
+		Some(options) => {
+			options.insert("exportFormats".to_string(), Value::Array(formats));
+		}
+		None => {
+			artboard["exportOptions"] = json!({
+				"_class": "exportOptions",
+				"exportFormats": formats,
+				"includedLayerIds": [],
+				"layerOptions": 0,
+				"shouldTrim": false
+			});
+		}
+	}
+}
+
+/// The `exportFormat` object of a preset, named with its size suffix like Sketch (e.g., "@2x", none for 1x).
+fn export_format_json(preset: &ExportPreset) -> Value {
+	let (scale, absolute_size, visible_scale_type) = match preset.size {
+		PresetSize::Scale(scale) => (scale, 0.0, 0),
+		PresetSize::Width(width) => (1.0, width, 1),
+		PresetSize::Height(height) => (1.0, height, 2),
+	};
+	let name = match preset.size {
+		PresetSize::Scale(1.0) => String::new(),
+		size => format!("@{size}"),
+	};
+
+	json!({
+		"_class": "exportFormat",
+		"absoluteSize": absolute_size,
+		"fileFormat": preset.format,
+		"name": name,
+		"namingScheme": 0,
+		"scale": scale,
+		"visibleScaleType": visible_scale_type
+	})
+}
+
+// endregion: --- Write Back
@@ -0,0 +1,186 @@
+//! In-place renaming of the artboards of a Sketch file (`webtk sketch rename`), from a CSV map of the current names
+//! (or UIDs) to the new names: the artboard layers of the pages and their names in `meta.json` are rewritten
+//! (see `SketchArchive`), e.g., to enforce naming conventions from a script.
+
+use crate::handlers::sketch::{
+	CLASS_ARTBOARD, SKETCH_META_FILE, SketchArchive, backup_sketch_file, layer_class, layer_str,
+};
+use crate::support::{files, strings};
+use crate::warning::{Warning, WarningCode};
+use crate::{Error, Result};
+use serde::Serialize;
+use serde_json::Value;
+use simple_fs::SPath;
+use std::collections::{BTreeMap, HashSet};
+
+/// A row of the rename map: the current artboard name (all the artboards with this name) or UID, and the new name.
+#[derive(Debug, Clone)]
+pub struct ArtboardRename {
+	pub from: String,
+	pub to: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RenameReport {
+	pub renamed: Vec<RenamedArtboard>,
+	/// The `from` of the rows matching no artboard.
+	pub unmatched: Vec<String>,
+	/// The names shared by several artboards after the renames.
+	pub warnings: Vec<Warning>,
+	/// The copy of the Sketch file before the renames (with `backup`).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub backup_file: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenamedArtboard {
+	pub uid: String,
+	pub page: String,
+	pub from: String,
+	pub to: String,
+}
+
+/// Loads a rename map CSV: `from,to` rows (the `from,to` header row, the empty lines, and the `#` comments skipped).
+pub fn load_rename_map(map_file: &SPath) -> Result<Vec<ArtboardRename>> {
+	files::check_file_exists(map_file)?;
+	let content =
+		std::fs::read_to_string(map_file.as_std_path()).map_err(|e| format!("Failed to read '{map_file}': {e}"))?;
+
+	let mut renames: Vec<ArtboardRename> = Vec::new();
+	for (idx, line) in content.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') || (idx == 0 && line.eq_ignore_ascii_case("from,to")) {
+			continue;
+		}
+		let fields = strings::parse_csv_line(line);
+		let [from, to] = fields.as_slice() else {
+			return Err(Error::custom(format!(
+				"{map_file}:{}: expected 2 fields (from,to), found {}",
+				idx + 1,
+				fields.len()
+			)));
+		};
+		let (from, to) = (from.trim(), to.trim());
+		if from.is_empty() || to.is_empty() {
+			return Err(Error::custom(format!("{map_file}:{}: the names cannot be empty", idx + 1)));
+		}
+		if renames.iter().any(|rename| rename.from == from) {
+			return Err(Error::custom(format!("{map_file}:{}: '{from}' is renamed twice", idx + 1)));
+		}
+		renames.push(ArtboardRename { from: from.to_string(), to: to.to_string() });
+	}
+
+	Ok(renames)
+}
+
+/// Renames the artboards of a Sketch file. A row matches the artboards by UID first, then by name, and the rows apply
+/// to the names before the renames (so "a,b" and "b,a" swap the names).
+/// Nothing is written with `dry_run`, or when nothing is renamed.
+pub fn rename_artboards(
+	sketch_file: &SPath,
+	renames: &[ArtboardRename],
+	backup: bool,
+	dry_run: bool,
+) -> Result<RenameReport> {
+	files::check_file_exists(sketch_file)?;
+	let mut archive = SketchArchive::open(sketch_file)?;
+	let mut report = RenameReport::default();
+	let mut matched: HashSet<&str> = HashSet::new();
+	// All the artboard UIDs by name, after the renames
+	let mut uids_by_name: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+	for page_file in archive.page_files() {
+		let mut page = archive.read_json(&page_file)?;
+		let page_name = layer_str(&page, "name").unwrap_or_default().to_string();
+		let Some(layers) = page.get_mut("layers").and_then(Value::as_array_mut) else {
+			continue;
+		};
+
+		let mut page_renamed = false;
+		for layer in layers.iter_mut().filter(|layer| layer_class(layer) == Some(CLASS_ARTBOARD)) {
+			let uid = layer_str(layer, "do_objectID").unwrap_or_default().to_string();
+			let name = layer_str(layer, "name").unwrap_or_default().to_string();
+			let rename = renames
+				.iter()
+				.find(|rename| rename.from == uid)
+				.or_else(|| renames.iter().find(|rename| rename.from == name));
+
+			let new_name = match rename {
+				Some(rename) => {
+					matched.insert(&rename.from);
+					if rename.to != name {
+						layer["name"] = Value::String(rename.to.clone());
+						page_renamed = true;
+						report.renamed.push(RenamedArtboard {
+							uid: uid.clone(),
+							page: page_name.clone(),
+							from: name.clone(),
+							to: rename.to.clone(),
+						});
+					}
+					rename.to.clone()
+				}
+				None => name,
+			};
+			uids_by_name.entry(new_name).or_default().push(uid);
+		}
+
+		if page_renamed {
+			archive.write_json(&page_file, &page)?;
+		}
+	}
+
+	report.unmatched = renames
+		.iter()
+		.filter(|rename| !matched.contains(rename.from.as_str()))
+		.map(|rename| rename.from.clone())
+		.collect();
+	report.warnings = uids_by_name
+		.iter()
+		.filter(|(name, uids)| uids.len() > 1 && report.renamed.iter().any(|renamed| &renamed.to == *name))
+		.map(|(name, uids)| {
+			Warning::for_artboard(
+				WarningCode::DuplicateArtboardName,
+				name,
+				format!("{} artboards are named '{name}' after the renames (UIDs: {})", uids.len(), uids.join(", ")),
+			)
+		})
+		.collect();
+
+	if report.renamed.is_empty() || dry_run {
+		return Ok(report);
+	}
+
+	rename_meta_artboards(&mut archive, &report.renamed)?;
+	if backup {
+		report.backup_file = Some(backup_sketch_file(sketch_file)?.to_string());
+	}
+	archive.save(sketch_file)?;
+
+	Ok(report)
+}
+
+// region:    --- Support
+
+/// Renames the artboards in the `pagesAndArtboards` of `meta.json` (read by `sketchtool metadata`).
+fn rename_meta_artboards(archive: &mut SketchArchive, renamed: &[RenamedArtboard]) -> Result<()> {
+	let mut meta = archive.read_json(SKETCH_META_FILE)?;
+	let Some(pages) = meta.get_mut("pagesAndArtboards").and_then(Value::as_object_mut) else {
+		return Ok(());
+	};
+
+	for page in pages.values_mut() {
+		let Some(artboards) = page.get_mut("artboards").and_then(Value::as_object_mut) else {
+			continue;
+		};
+		for renamed in renamed {
+			if let Some(artboard) = artboards.get_mut(&renamed.uid) {
+				artboard["name"] = Value::String(renamed.to.clone());
+			}
+		}
+	}
+
+	archive.write_json(SKETCH_META_FILE, &meta)
+}
+
+// endregion: --- Support
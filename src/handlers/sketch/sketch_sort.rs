@@ -0,0 +1,226 @@
+//! In-place sorting of the artboards of the pages of a Sketch file (`webtk sketch sort`): the artboards matching the
+//! globs are repositioned in a tidy grid, in sort order, from the top left corner of their current bounds, and
+//! their layers reordered so the layer list reads in the same order (see `SketchArchive`).
+
+use crate::handlers::sketch::{CLASS_ARTBOARD, SketchArchive, backup_sketch_file, layer_class, layer_str};
+use crate::support::{files, globs};
+use crate::{Error, Result};
+use serde::Serialize;
+use serde_json::Value;
+use simple_fs::SPath;
+use std::cmp::Ordering;
+
+/// The default grid of `sort_artboards`, in px.
+pub const DEFAULT_SORT_GRID: u32 = 8;
+
+/// The sort order of the artboards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortBy {
+	/// By name (case insensitive), then by UID.
+	#[default]
+	Name,
+	/// By area, the largest first, then by name.
+	Size,
+}
+
+impl TryFrom<&str> for SortBy {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"name" => Ok(Self::Name),
+			"size" => Ok(Self::Size),
+			other => Err(Error::custom(format!("Invalid sort order '{other}'. Expected: name, size"))),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct SortOptions {
+	pub by: SortBy,
+	/// The gap between the artboards, in px, also the unit of their positions and cell sizes.
+	pub grid: u32,
+	/// The artboards per row (the ceiling of the square root of the artboard count by default).
+	pub columns: Option<u32>,
+	pub backup: bool,
+	pub dry_run: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SortReport {
+	/// The artboards matched by the globs.
+	pub artboard_count: usize,
+	/// The pages with matched artboards.
+	pub page_count: usize,
+	/// The artboards whose position changed.
+	pub moved_count: usize,
+	/// Whether the positions or the layer order changed.
+	pub changed: bool,
+	/// The copy of the Sketch file before the changes (with `backup`).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub backup_file: Option<String>,
+}
+
+/// An artboard of a page to place.
+struct GridItem {
+	/// The index in the page layers.
+	layer_idx: usize,
+	name: String,
+	uid: String,
+	x: f64,
+	y: f64,
+	width: f64,
+	height: f64,
+}
+
+/// Sorts the artboards matching the globs (all when None) of each page in a grid.
+/// Nothing is written with `dry_run`, or when nothing changed.
+pub fn sort_artboards(
+	sketch_file: &SPath,
+	glob_patterns: Option<&[&str]>,
+	options: &SortOptions,
+) -> Result<SortReport> {
+	if options.grid == 0 || options.columns == Some(0) {
+		return Err(Error::custom("The grid and the columns must be greater than 0"));
+	}
+	files::check_file_exists(sketch_file)?;
+	let glob_set = globs::build_glob_set(glob_patterns)?;
+	let mut archive = SketchArchive::open(sketch_file)?;
+	let mut report = SortReport::default();
+
+	for page_file in archive.page_files() {
+		let mut page = archive.read_json(&page_file)?;
+		let Some(layers) = page.get_mut("layers").and_then(Value::as_array_mut) else {
+			continue;
+		};
+
+		let mut items: Vec<GridItem> = layers
+			.iter()
+			.enumerate()
+			.filter(|(_, layer)| layer_class(layer) == Some(CLASS_ARTBOARD))
+			.filter_map(|(layer_idx, layer)| {
+				let name = layer_str(layer, "name").unwrap_or_default();
+				if !globs::matches_glob_set(glob_set.as_ref(), name) {
+					return None;
+				}
+				let frame = |field: &str| layer.pointer(&format!("/frame/{field}")).and_then(Value::as_f64);
+				Some(GridItem {
+					layer_idx,
+					name: name.to_string(),
+					uid: layer_str(layer, "do_objectID").unwrap_or_default().to_string(),
+					x: frame("x")?,
+					y: frame("y")?,
+					width: frame("width")?,
+					height: frame("height")?,
+				})
+			})
+			.collect();
+		if items.is_empty() {
+			continue;
+		}
+		report.page_count += 1;
+		report.artboard_count += items.len();
+
+		items.sort_by(|a, b| compare_items(a, b, options.by));
+		let positions = grid_positions(&items, options);
+		let mut page_changed = false;
+		for (item, (x, y)) in items.iter().zip(&positions) {
+			if item.x != *x || item.y != *y {
+				let frame = &mut layers[item.layer_idx]["frame"];
+				frame["x"] = Value::from(*x);
+				frame["y"] = Value::from(*y);
+				report.moved_count += 1;
+				page_changed = true;
+			}
+		}
+
+		// The sorted artboards take the layer slots of the matched artboards, the last layer at the top of the list
+		let mut slots: Vec<usize> = items.iter().map(|item| item.layer_idx).collect();
+		slots.sort_unstable();
+		let sorted: Vec<Value> = items.iter().rev().map(|item| layers[item.layer_idx].clone()).collect();
+		for (slot, layer) in slots.into_iter().zip(sorted) {
+			if layers[slot] != layer {
+				layers[slot] = layer;
+				page_changed = true;
+			}
+		}
+
+		if page_changed {
+			archive.write_json(&page_file, &page)?;
+			report.changed = true;
+		}
+	}
+
+	if !report.changed || options.dry_run {
+		return Ok(report);
+	}
+
+	if options.backup {
+		report.backup_file = Some(backup_sketch_file(sketch_file)?.to_string());
+	}
+	archive.save(sketch_file)?;
+
+	Ok(report)
+}
+
+// region:    --- Support
+
+fn compare_items(a: &GridItem, b: &GridItem, by: SortBy) -> Ordering {
+	let by_name = || {
+		a.name
+			.to_lowercase()
+			.cmp(&b.name.to_lowercase())
+			.then_with(|| a.name.cmp(&b.name))
+			.then_with(|| a.uid.cmp(&b.uid))
+	};
+	match by {
+		SortBy::Name => by_name(),
+		SortBy::Size => (b.width * b.height).total_cmp(&(a.width * a.height)).then_with(by_name),
+	}
+}
+
+/// The top left positions of the sorted items: rows of `columns` cells from the top left corner of the items
+/// (snapped to the grid), the column widths and row heights the largest of their items rounded up to the grid,
+/// and a grid gap between the cells.
+fn grid_positions(items: &[GridItem], options: &SortOptions) -> Vec<(f64, f64)> {
+	let grid = options.grid as f64;
+	let snap_up = |value: f64| (value / grid).ceil() * grid;
+	let columns = options
+		.columns
+		.map(|columns| columns as usize)
+		.unwrap_or_else(|| (items.len() as f64).sqrt().ceil() as usize)
+		.clamp(1, items.len().max(1));
+
+	let origin_x = (items.iter().map(|item| item.x).fold(f64::INFINITY, f64::min) / grid).floor() * grid;
+	let origin_y = (items.iter().map(|item| item.y).fold(f64::INFINITY, f64::min) / grid).floor() * grid;
+
+	let mut column_widths = vec![0.0_f64; columns];
+	let mut row_heights = vec![0.0_f64; items.len().div_ceil(columns)];
+	for (idx, item) in items.iter().enumerate() {
+		column_widths[idx % columns] = column_widths[idx % columns].max(snap_up(item.width));
+		row_heights[idx / columns] = row_heights[idx / columns].max(snap_up(item.height));
+	}
+
+	let column_xs: Vec<f64> = column_widths
+		.iter()
+		.scan(origin_x, |x, width| {
+			let column_x = *x;
+			*x += width + grid;
+			Some(column_x)
+		})
+		.collect();
+	let row_ys: Vec<f64> = row_heights
+		.iter()
+		.scan(origin_y, |y, height| {
+			let row_y = *y;
+			*y += height + grid;
+			Some(row_y)
+		})
+		.collect();
+
+	(0..items.len())
+		.map(|idx| (column_xs[idx % columns], row_ys[idx / columns]))
+		.collect()
+}
+
+// endregion: --- Support
@@ -0,0 +1,57 @@
+//! The Sketch implementation of `DesignSource` (artboards, exported with sketchtool).
+
+use crate::Result;
+use crate::handlers::sketch::{self, ExportOptions, GlobScope};
+use crate::handlers::source::{DesignExport, DesignItem, DesignSource};
+use crate::support::globs::GlobCase;
+use simple_fs::SPath;
+
+pub struct SketchSource {
+	sketch_file: SPath,
+	glob_scope: GlobScope,
+	glob_case: GlobCase,
+}
+
+impl SketchSource {
+	pub fn new(sketch_file: SPath) -> Self {
+		Self { sketch_file, glob_scope: GlobScope::default(), glob_case: GlobCase::default() }
+	}
+
+	/// Sets what the globs of `list_items` match (the artboard names by default).
+	pub fn with_glob_scope(mut self, glob_scope: GlobScope) -> Self {
+		self.glob_scope = glob_scope;
+		self
+	}
+
+	/// Sets how the globs of `list_items` match the letter case (case sensitive by default).
+	pub fn with_glob_case(mut self, glob_case: GlobCase) -> Self {
+		self.glob_case = glob_case;
+		self
+	}
+}
+
+impl DesignSource for SketchSource {
+	fn list_items(&self, globs: &[String]) -> Result<Vec<DesignItem>> {
+		let glob_refs: Vec<&str> = globs.iter().map(String::as_str).collect();
+		let artboards = sketch::list_artboards(&self.sketch_file, Some(&glob_refs), self.glob_scope, self.glob_case)?;
+
+		Ok(artboards
+			.into_iter()
+			.map(|artboard| DesignItem { id: artboard.uid, name: artboard.name, page: artboard.page_name })
+			.collect())
+	}
+
+	fn export_items(&self, globs: &[String], formats: &[String], output_dir: &SPath) -> Result<DesignExport> {
+		let glob_refs: Vec<&str> = globs.iter().map(String::as_str).collect();
+		let format_refs: Vec<&str> = formats.iter().map(String::as_str).collect();
+		let report = sketch::export_artboards(
+			&self.sketch_file,
+			Some(&glob_refs),
+			&format_refs,
+			output_dir,
+			&ExportOptions::default(),
+		)?;
+
+		Ok(DesignExport { exported_files: report.exported_files, warnings: report.warnings })
+	}
+}
@@ -0,0 +1,309 @@
+//! The HTML spec sheets of artboards (a local handoff doc): one page per artboard with its rendered image
+//! (a png export), its size, the colors and text styles it uses, and the measurements of its layers
+//! (from the document JSON), plus an index page. Rendered from the codegen templates (see `render_codegen_template`).
+
+use crate::handlers::codegen::{
+	GENERATED_NOTICE, SPEC_INDEX_TEMPLATE, SPEC_SHEET_TEMPLATE, render_codegen_template, write_codegen_file,
+};
+use crate::handlers::design_tokens::sketch_color;
+use crate::handlers::sketch::{
+	CLASS_ARTBOARD, ExportOptions, child_layers, document_pages, execute_export_plan, layer_class, layer_str,
+	load_sketch_document, plan_export,
+};
+use crate::support::strings;
+use crate::{Error, Result};
+use quick_xml::escape::escape;
+use serde::Serialize;
+use serde_json::Value;
+use simple_fs::SPath;
+use std::collections::{HashMap, HashSet};
+
+/// The directory of the rendered images, in the output directory.
+pub const SPEC_IMAGES_DIR: &str = "images";
+
+/// The index page, in the output directory.
+pub const SPEC_INDEX_FILE: &str = "index.html";
+
+/// The layer classes measured with their layers (the other layers are measured as a whole).
+const CONTAINER_CLASSES: &[&str] = &["group"];
+
+/// The layer class of the text layers.
+const CLASS_TEXT: &str = "text";
+
+/// The files written by `generate_spec_sheets`.
+#[derive(Debug, Default)]
+pub struct SpecReport {
+	pub artboard_count: usize,
+	pub index_file: String,
+	/// The spec sheets, one per artboard.
+	pub sheet_files: Vec<String>,
+	/// The rendered images, one per artboard.
+	pub image_files: Vec<String>,
+}
+
+/// The context of the spec sheet template (the strings HTML escaped).
+#[derive(Debug, Serialize)]
+pub struct SpecSheetContext {
+	pub notice: &'static str,
+	pub name: String,
+	pub page_name: String,
+	pub width: String,
+	pub height: String,
+	/// The image, relative to the spec sheet.
+	pub image_href: String,
+	pub index_href: &'static str,
+	/// By use count (descending), then hex.
+	pub colors: Vec<SpecColor>,
+	/// By use count (descending), then font.
+	pub text_styles: Vec<SpecTextStyle>,
+	/// In document order, positioned from the artboard top left corner.
+	pub layers: Vec<SpecLayer>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpecColor {
+	/// The CSS hex notation (e.g., "#1a73e8", "#00000080").
+	pub hex: String,
+	pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpecTextStyle {
+	/// The PostScript name (e.g., "Inter-Bold").
+	pub font: String,
+	pub size: String,
+	pub color: Option<String>,
+	pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpecLayer {
+	/// The layer names from the artboard, joined by " > ".
+	pub path: String,
+	/// The layer class (e.g., "rectangle", "text", "symbolInstance").
+	pub class: String,
+	pub x: String,
+	pub y: String,
+	pub width: String,
+	pub height: String,
+}
+
+/// The context of the spec index template (the strings HTML escaped).
+#[derive(Debug, Serialize)]
+pub struct SpecIndexContext {
+	pub notice: &'static str,
+	/// The Sketch file name.
+	pub source_name: String,
+	/// In export order.
+	pub sheets: Vec<SpecIndexItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpecIndexItem {
+	pub name: String,
+	pub page_name: String,
+	pub href: String,
+	pub image_href: String,
+	pub width: String,
+	pub height: String,
+}
+
+/// Generates the spec sheets of the artboards of a Sketch file matching the globs (all when None) in the output
+/// directory: `index.html`, one `<artboard name>.html` per artboard (flattened, e.g., "screens-home.html"),
+/// and their images in `images/` (png, flattened names).
+pub fn generate_spec_sheets(
+	sketch_file: impl AsRef<SPath>,
+	glob_patterns: Option<&[&str]>,
+	output_dir: impl AsRef<SPath>,
+	template_dir: Option<&SPath>,
+) -> Result<SpecReport> {
+	let sketch_file = sketch_file.as_ref();
+	let output_dir = output_dir.as_ref();
+
+	// -- Render the images
+	let options = ExportOptions { flatten: true, ..Default::default() };
+	let plan = plan_export(sketch_file, glob_patterns, &["png"], output_dir.join(SPEC_IMAGES_DIR), &options)?;
+	execute_export_plan(&plan)?;
+
+	// -- The artboards of the document, by UID
+	let document = load_sketch_document(sketch_file)?;
+	let artboards: HashMap<&str, &Value> = document_pages(&document)
+		.iter()
+		.flat_map(|page| child_layers(page).iter().filter(|l| layer_class(l) == Some(CLASS_ARTBOARD)))
+		.filter_map(|artboard| layer_str(artboard, "do_objectID").map(|uid| (uid, artboard)))
+		.collect();
+
+	// -- One sheet per artboard
+	let mut report = SpecReport::default();
+	let mut taken_names: HashSet<String> = HashSet::from([SPEC_INDEX_FILE.trim_end_matches(".html").to_string()]);
+	let mut index_items = Vec::new();
+	for (_, item) in plan.items() {
+		let artboard = artboards.get(item.artboard.uid.as_str()).ok_or_else(|| {
+			Error::custom(format!("Artboard '{}' not found in the document of '{sketch_file}'", item.artboard.name))
+		})?;
+
+		let file_stem = strings::unique_name(&strings::canonicalize_name(&item.artboard.name), &mut taken_names);
+		let sheet_file = output_dir.join(format!("{file_stem}.html"));
+		let image_href = SPath::new(&item.target_file)
+			.diff(output_dir)
+			.map(|path| path.to_string().replace('\\', "/"))
+			.unwrap_or_else(|| item.target_file.clone());
+
+		let context = spec_sheet_context(artboard, &item.artboard.page_name, &image_href);
+		let content = render_codegen_template(SPEC_SHEET_TEMPLATE, template_dir, &context)?;
+		write_codegen_file(&sheet_file, &content)?;
+
+		index_items.push(SpecIndexItem {
+			name: context.name,
+			page_name: context.page_name,
+			href: escape(format!("{file_stem}.html")).into_owned(),
+			image_href: context.image_href,
+			width: context.width,
+			height: context.height,
+		});
+		report.sheet_files.push(sheet_file.to_string());
+		report.image_files.push(item.target_file.clone());
+	}
+	report.artboard_count = index_items.len();
+
+	// -- The index
+	let index_file = output_dir.join(SPEC_INDEX_FILE);
+	let context = SpecIndexContext {
+		notice: GENERATED_NOTICE,
+		source_name: escape(sketch_file.name()).into_owned(),
+		sheets: index_items,
+	};
+	let content = render_codegen_template(SPEC_INDEX_TEMPLATE, template_dir, context)?;
+	write_codegen_file(&index_file, &content)?;
+	report.index_file = index_file.to_string();
+
+	Ok(report)
+}
+
+/// Builds the spec sheet context of an artboard of the document JSON. The hidden layers are skipped.
+pub fn spec_sheet_context(artboard: &Value, page_name: &str, image_href: &str) -> SpecSheetContext {
+	let (_, _, width, height) = layer_frame(artboard);
+	let mut collector = SpecCollector::default();
+	collector.collect_children(artboard, 0.0, 0.0, &mut Vec::new());
+
+	let mut colors: Vec<SpecColor> = collector
+		.colors
+		.into_iter()
+		.map(|(hex, count)| SpecColor { hex: escape(hex).into_owned(), count })
+		.collect();
+	colors.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.hex.cmp(&b.hex)));
+
+	let mut text_styles: Vec<SpecTextStyle> = collector
+		.text_styles
+		.into_iter()
+		.map(|((font, size, color), count)| SpecTextStyle {
+			font: escape(font).into_owned(),
+			size,
+			color: color.map(|color| escape(color).into_owned()),
+			count,
+		})
+		.collect();
+	text_styles.sort_by(|a, b| {
+		b.count
+			.cmp(&a.count)
+			.then_with(|| a.font.cmp(&b.font))
+			.then_with(|| a.size.cmp(&b.size))
+	});
+
+	SpecSheetContext {
+		notice: GENERATED_NOTICE,
+		name: escape(layer_str(artboard, "name").unwrap_or_default()).into_owned(),
+		page_name: escape(page_name).into_owned(),
+		width: strings::format_number(width, 2),
+		height: strings::format_number(height, 2),
+		image_href: escape(image_href).into_owned(),
+		index_href: SPEC_INDEX_FILE,
+		colors,
+		text_styles,
+		layers: collector.layers,
+	}
+}
+
+/// Walks the layers of an artboard, collecting the measurements, colors, and text styles.
+#[derive(Default)]
+struct SpecCollector {
+	layers: Vec<SpecLayer>,
+	/// The use count by hex color.
+	colors: HashMap<String, usize>,
+	/// The use count by (font, size, color).
+	text_styles: HashMap<(String, String, Option<String>), usize>,
+}
+
+impl SpecCollector {
+	/// Collects the children of a layer positioned at `(origin_x, origin_y)` in the artboard.
+	fn collect_children(&mut self, parent: &Value, origin_x: f64, origin_y: f64, path: &mut Vec<String>) {
+		for layer in child_layers(parent) {
+			if layer.get("isVisible").and_then(Value::as_bool) == Some(false) {
+				continue;
+			}
+			let class = layer_class(layer).unwrap_or_default();
+			let (x, y, width, height) = layer_frame(layer);
+			let (x, y) = (origin_x + x, origin_y + y);
+			path.push(layer_str(layer, "name").unwrap_or_default().to_string());
+
+			self.layers.push(SpecLayer {
+				path: escape(path.join(" > ")).into_owned(),
+				class: escape(class).into_owned(),
+				x: strings::format_number(x, 2),
+				y: strings::format_number(y, 2),
+				width: strings::format_number(width, 2),
+				height: strings::format_number(height, 2),
+			});
+			self.collect_style(layer, class);
+
+			if CONTAINER_CLASSES.contains(&class) {
+				self.collect_children(layer, x, y, path);
+			}
+			path.pop();
+		}
+	}
+
+	/// Counts the colors of the enabled solid fills and borders of a layer, and the text style of a text layer.
+	fn collect_style(&mut self, layer: &Value, class: &str) {
+		for collection in ["/style/fills", "/style/borders"] {
+			let paints = layer.pointer(collection).and_then(Value::as_array);
+			for paint in paints.into_iter().flatten() {
+				let is_enabled = paint.get("isEnabled").and_then(Value::as_bool).unwrap_or(true);
+				let is_solid = paint.get("fillType").and_then(Value::as_u64).unwrap_or(0) == 0;
+				if let Some(color) = paint.get("color").and_then(sketch_color).filter(|_| is_enabled && is_solid) {
+					*self.colors.entry(color.to_hex()).or_default() += 1;
+				}
+			}
+		}
+
+		if class != CLASS_TEXT {
+			return;
+		}
+		let Some(attributes) = layer.pointer("/style/textStyle/encodedAttributes") else {
+			return;
+		};
+		let font = attributes
+			.pointer("/MSAttributedStringFontAttribute/attributes/name")
+			.and_then(Value::as_str);
+		let size = attributes
+			.pointer("/MSAttributedStringFontAttribute/attributes/size")
+			.and_then(Value::as_f64);
+		let color = attributes
+			.get("MSAttributedStringColorAttribute")
+			.and_then(sketch_color)
+			.map(|c| c.to_hex());
+		if let Some(color) = &color {
+			*self.colors.entry(color.clone()).or_default() += 1;
+		}
+		if let (Some(font), Some(size)) = (font, size) {
+			let key = (font.to_string(), strings::format_number(size, 2), color);
+			*self.text_styles.entry(key).or_default() += 1;
+		}
+	}
+}
+
+/// Returns the frame of a layer (`x`, `y`, `width`, `height`, relative to its parent).
+fn layer_frame(layer: &Value) -> (f64, f64, f64, f64) {
+	let value = |name: &str| layer.pointer(&format!("/frame/{name}")).and_then(Value::as_f64).unwrap_or(0.0);
+	(value("x"), value("y"), value("width"), value("height"))
+}
@@ -0,0 +1,86 @@
+//! A quick health overview of a design file (artboard groups, sizes, naming, largest artboards).
+
+use crate::Result;
+use crate::handlers::sketch::{ArtboardFrame, list_artboard_frames};
+use crate::support::strings;
+use serde::Serialize;
+use simple_fs::SPath;
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Debug, Default, Serialize)]
+pub struct ArtboardStats {
+	pub artboard_count: usize,
+	pub page_count: usize,
+	/// Artboard counts by top-level prefix (e.g., "ico/"), most first. Names without a "/" are under "".
+	pub prefixes: Vec<StatCount>,
+	/// Artboard counts by size (e.g., "24x24"), most first.
+	pub sizes: Vec<StatCount>,
+	/// The artboards following the naming convention (lowercase kebab-case segments, e.g., "ico/user-fill").
+	pub conventional_count: usize,
+	pub unconventional_names: Vec<String>,
+	/// The largest artboards by area, largest first.
+	pub largest: Vec<ArtboardFrame>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatCount {
+	pub key: String,
+	pub count: usize,
+}
+
+impl ArtboardStats {
+	/// The naming convention compliance, in percent (100 when there are no artboards).
+	pub fn naming_compliance(&self) -> f64 {
+		if self.artboard_count == 0 {
+			return 100.0;
+		}
+		self.conventional_count as f64 * 100.0 / self.artboard_count as f64
+	}
+}
+
+/// Computes the artboard stats of a Sketch file, keeping the `top` largest artboards.
+pub fn artboard_stats(sketch_file: impl AsRef<SPath>, top: usize) -> Result<ArtboardStats> {
+	let frames = list_artboard_frames(sketch_file)?;
+	Ok(build_artboard_stats(&frames, top))
+}
+
+/// Computes the artboard stats from the artboard frames (see `artboard_stats`).
+pub fn build_artboard_stats(frames: &[ArtboardFrame], top: usize) -> ArtboardStats {
+	let mut prefixes: BTreeMap<String, usize> = BTreeMap::new();
+	let mut sizes: BTreeMap<String, usize> = BTreeMap::new();
+	let mut pages: HashSet<&str> = HashSet::new();
+	let mut unconventional_names = Vec::new();
+
+	for frame in frames {
+		let name = &frame.artboard.name;
+		let prefix = name.split_once('/').map(|(prefix, _)| format!("{prefix}/")).unwrap_or_default();
+		*prefixes.entry(prefix).or_default() += 1;
+		*sizes.entry(format!("{}x{}", frame.width, frame.height)).or_default() += 1;
+		pages.insert(&frame.artboard.page_name);
+		if !strings::is_kebab_path(name) {
+			unconventional_names.push(name.clone());
+		}
+	}
+
+	let mut largest: Vec<ArtboardFrame> = frames.to_vec();
+	largest.sort_by(|a, b| (b.width * b.height).total_cmp(&(a.width * a.height)));
+	largest.truncate(top);
+
+	ArtboardStats {
+		artboard_count: frames.len(),
+		page_count: pages.len(),
+		prefixes: sorted_counts(prefixes),
+		sizes: sorted_counts(sizes),
+		conventional_count: frames.len() - unconventional_names.len(),
+		unconventional_names,
+		largest,
+	}
+}
+
+/// Sorts the counts, most first (then by key).
+fn sorted_counts(counts: BTreeMap<String, usize>) -> Vec<StatCount> {
+	let mut counts: Vec<StatCount> = counts.into_iter().map(|(key, count)| StatCount { key, count }).collect();
+	// stable sort, so equal counts stay sorted by key
+	counts.sort_by_key(|entry| std::cmp::Reverse(entry.count));
+	counts
+}
@@ -0,0 +1,73 @@
+use crate::Result;
+use crate::support::xmls_stream;
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The `symbols.json` manifest written next to an `svg-symbols` sprite.
+#[derive(Debug, Default, Serialize)]
+pub struct SymbolsManifest {
+	pub symbols: Vec<SymbolEntry>,
+}
+
+/// A symbol of the sprite, with the intrinsic size of its artboard.
+#[derive(Debug, Serialize)]
+pub struct SymbolEntry {
+	pub id: String,
+	pub name: String,
+	pub page: String,
+	#[serde(rename = "viewBox")]
+	pub view_box: String,
+	pub width: Option<serde_json::Number>,
+	pub height: Option<serde_json::Number>,
+	#[serde(rename = "preserveAspectRatio", skip_serializing_if = "Option::is_none")]
+	pub preserve_aspect_ratio: Option<String>,
+}
+
+impl SymbolsManifest {
+	/// Returns the manifest path for a sprite file (e.g., "icons/symbols.svg" -> "icons/symbols.json").
+	pub fn path_for_sprite(sprite_file: &SPath) -> SPath {
+		sprite_file.new_sibling(format!("{}.json", sprite_file.stem()))
+	}
+
+	pub fn write(&self, manifest_file: &SPath) -> Result<()> {
+		let content = serde_json::to_string_pretty(self)?;
+		std::fs::write(manifest_file.as_std_path(), format!("{content}\n"))
+			.map_err(|e| format!("Failed to write symbols manifest '{}': {e}", manifest_file))?;
+		Ok(())
+	}
+}
+
+/// Parses an SVG length (e.g., "16px", "16", "12.5") into a pixel number.
+/// Returns None for missing values, percentages, or other units.
+pub fn parse_svg_px_length(value: Option<&str>) -> Option<serde_json::Number> {
+	let value = value?.trim();
+	let value = value.strip_suffix("px").unwrap_or(value);
+	let num: f64 = value.parse().ok()?;
+	if num.fract() == 0.0 && num.abs() < i64::MAX as f64 {
+		Some(serde_json::Number::from(num as i64))
+	} else {
+		serde_json::Number::from_f64(num)
+	}
+}
+
+/// Returns the px size of an SVG document, from the root `width`/`height` attributes, falling back to the `viewBox` size.
+pub fn svg_px_size(svg_content: &str) -> Option<(serde_json::Number, serde_json::Number)> {
+	let width = parse_svg_px_length(xmls_stream::extract_root_attribute(svg_content, "width").as_deref());
+	let height = parse_svg_px_length(xmls_stream::extract_root_attribute(svg_content, "height").as_deref());
+	match (width, height) {
+		(Some(width), Some(height)) => Some((width, height)),
+		_ => view_box_size(&xmls_stream::extract_root_attribute(svg_content, "viewBox")?),
+	}
+}
+
+/// Returns the width and height from a `viewBox` value (`min-x min-y width height`).
+pub fn view_box_size(view_box: &str) -> Option<(serde_json::Number, serde_json::Number)> {
+	let parts: Vec<&str> = view_box
+		.split(|c: char| c.is_whitespace() || c == ',')
+		.filter(|s| !s.is_empty())
+		.collect();
+	let [_, _, width, height] = parts.as_slice() else {
+		return None;
+	};
+	Some((parse_svg_px_length(Some(width))?, parse_svg_px_length(Some(height))?))
+}
@@ -0,0 +1,169 @@
+//! Sketch symbols usage analysis (which symbols are used on which artboards).
+
+use crate::Result;
+use crate::handlers::sketch::{
+	CLASS_ARTBOARD, CLASS_SYMBOL_INSTANCE, CLASS_SYMBOL_MASTER, child_layers, document_pages, layer_class, layer_str,
+	load_sketch_document, walk_layers,
+};
+use crate::support::strings;
+use serde_json::Value;
+use simple_fs::SPath;
+use std::collections::HashMap;
+
+/// A symbol master with the artboards (or other symbol masters) using it.
+#[derive(Debug, Clone)]
+pub struct SymbolUsage {
+	pub symbol_id: String,
+	pub name: String,
+	/// The page of the symbol master ("" for library symbols).
+	pub page_name: String,
+	/// True when the master comes from a library (`foreignSymbols`).
+	pub foreign: bool,
+	pub instance_count: usize,
+	/// The containers using the symbol, sorted by page and name.
+	pub used_in: Vec<SymbolContainer>,
+}
+
+/// An artboard or symbol master containing symbol instances.
+#[derive(Debug, Clone)]
+pub struct SymbolContainer {
+	pub name: String,
+	pub page_name: String,
+	pub is_symbol_master: bool,
+	pub instance_count: usize,
+}
+
+/// An artboard with the same (canonical) name as a symbol master, likely a duplicate export of it.
+#[derive(Debug, Clone)]
+pub struct ArtboardSymbolDuplicate {
+	pub artboard_name: String,
+	pub artboard_page_name: String,
+	pub symbol_name: String,
+}
+
+#[derive(Debug, Default)]
+pub struct SymbolsUsageReport {
+	/// All the symbol masters (used or not), sorted by name.
+	pub symbols: Vec<SymbolUsage>,
+	/// Instances referencing a symbol id not found in the document, as `(symbol_id, count)`.
+	pub missing_symbols: Vec<(String, usize)>,
+	pub duplicates: Vec<ArtboardSymbolDuplicate>,
+}
+
+/// Reports which symbols are used on which artboards of a Sketch file (from the document JSON).
+pub fn symbols_usage(sketch_file: impl AsRef<SPath>) -> Result<SymbolsUsageReport> {
+	let document = load_sketch_document(sketch_file)?;
+	Ok(build_symbols_usage(&document))
+}
+
+/// Builds the symbols usage report from a document JSON.
+pub fn build_symbols_usage(document: &Value) -> SymbolsUsageReport {
+	let mut symbols: Vec<SymbolUsage> = Vec::new();
+	let mut symbol_idx_by_id: HashMap<String, usize> = HashMap::new();
+	let mut artboards: Vec<(String, String)> = Vec::new();
+	// (container_name, page_name, is_symbol_master, symbol_id)
+	let mut instances: Vec<(&str, &str, bool, &str)> = Vec::new();
+
+	let mut add_master = |master: &Value, page_name: &str, foreign: bool, symbols: &mut Vec<SymbolUsage>| {
+		let Some(symbol_id) = layer_str(master, "symbolID") else {
+			return;
+		};
+		if symbol_idx_by_id.contains_key(symbol_id) {
+			return;
+		}
+		symbol_idx_by_id.insert(symbol_id.to_string(), symbols.len());
+		symbols.push(SymbolUsage {
+			symbol_id: symbol_id.to_string(),
+			name: layer_str(master, "name").unwrap_or_default().to_string(),
+			page_name: page_name.to_string(),
+			foreign,
+			instance_count: 0,
+			used_in: Vec::new(),
+		});
+	};
+
+	// -- Collect the masters, artboards, and instances of each page
+	for page in document_pages(document) {
+		let page_name = layer_str(page, "name").unwrap_or_default();
+		for layer in child_layers(page) {
+			let class = layer_class(layer);
+			let is_master = class == Some(CLASS_SYMBOL_MASTER);
+			if !is_master && class != Some(CLASS_ARTBOARD) {
+				continue;
+			}
+
+			let container_name = layer_str(layer, "name").unwrap_or_default();
+			if is_master {
+				add_master(layer, page_name, false, &mut symbols);
+			} else {
+				artboards.push((container_name.to_string(), page_name.to_string()));
+			}
+
+			walk_layers(layer, &mut |child| {
+				if layer_class(child) == Some(CLASS_SYMBOL_INSTANCE)
+					&& let Some(symbol_id) = layer_str(child, "symbolID")
+				{
+					instances.push((container_name, page_name, is_master, symbol_id));
+				}
+			});
+		}
+	}
+
+	// -- Library symbols (`foreignSymbols[].symbolMaster`)
+	let foreign_symbols = document.pointer("/document/foreignSymbols").and_then(Value::as_array);
+	for foreign in foreign_symbols.into_iter().flatten() {
+		if let Some(master) = foreign.get("symbolMaster") {
+			add_master(master, "", true, &mut symbols);
+		}
+	}
+
+	// -- Attach the instances to their symbols
+	let mut missing: HashMap<&str, usize> = HashMap::new();
+	for (container_name, page_name, is_symbol_master, symbol_id) in instances {
+		let Some(&idx) = symbol_idx_by_id.get(symbol_id) else {
+			*missing.entry(symbol_id).or_default() += 1;
+			continue;
+		};
+		let symbol = &mut symbols[idx];
+		symbol.instance_count += 1;
+		match symbol
+			.used_in
+			.iter_mut()
+			.find(|c| c.name == container_name && c.page_name == page_name && c.is_symbol_master == is_symbol_master)
+		{
+			Some(container) => container.instance_count += 1,
+			None => symbol.used_in.push(SymbolContainer {
+				name: container_name.to_string(),
+				page_name: page_name.to_string(),
+				is_symbol_master,
+				instance_count: 1,
+			}),
+		}
+	}
+
+	// -- Artboards duplicating a symbol master (same canonical name)
+	let mut duplicates: Vec<ArtboardSymbolDuplicate> = Vec::new();
+	for (artboard_name, artboard_page_name) in &artboards {
+		let canonical_name = strings::canonicalize_name(artboard_name);
+		if let Some(symbol) = symbols.iter().find(|s| strings::canonicalize_name(&s.name) == canonical_name) {
+			duplicates.push(ArtboardSymbolDuplicate {
+				artboard_name: artboard_name.clone(),
+				artboard_page_name: artboard_page_name.clone(),
+				symbol_name: symbol.name.clone(),
+			});
+		}
+	}
+
+	// -- Sort for deterministic outputs
+	for symbol in &mut symbols {
+		symbol
+			.used_in
+			.sort_by(|a, b| a.page_name.cmp(&b.page_name).then_with(|| a.name.cmp(&b.name)));
+	}
+	symbols.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.symbol_id.cmp(&b.symbol_id)));
+	duplicates.sort_by(|a, b| a.artboard_name.cmp(&b.artboard_name));
+	let mut missing_symbols: Vec<(String, usize)> = missing.into_iter().map(|(id, n)| (id.to_string(), n)).collect();
+	missing_symbols.sort();
+
+	SymbolsUsageReport { symbols, missing_symbols, duplicates }
+}
@@ -0,0 +1,46 @@
+use crate::Result;
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The suffix of the directory of the tiles of an image (e.g., "illus/hero.png" -> "illus/hero_tiles/").
+pub const TILES_DIR_SUFFIX: &str = "_tiles";
+
+/// The extension of the tiles descriptor of an image (e.g., "illus/hero.png" -> "illus/hero.tiles.json").
+pub const TILES_MANIFEST_EXT: &str = "tiles.json";
+
+/// The descriptor of a tiled image (see `ExportOptions::tile`), for the zoomable viewers.
+#[derive(Debug, Serialize)]
+pub struct TilesManifest {
+	/// The image file name (next to the descriptor).
+	pub image: String,
+	pub width: u32,
+	pub height: u32,
+	pub tile_size: u32,
+	pub overlap: u32,
+	pub columns: u32,
+	pub rows: u32,
+	/// Row by row.
+	pub tiles: Vec<TileEntry>,
+}
+
+/// A tile, with its position and size in the image (including the overlap).
+#[derive(Debug, Serialize)]
+pub struct TileEntry {
+	/// The tile path, relative to the descriptor directory (e.g., "hero_tiles/0_0.png").
+	pub file: String,
+	pub column: u32,
+	pub row: u32,
+	pub x: u32,
+	pub y: u32,
+	pub width: u32,
+	pub height: u32,
+}
+
+impl TilesManifest {
+	pub fn write(&self, manifest_file: &SPath) -> Result<()> {
+		let content = serde_json::to_string_pretty(self)?;
+		std::fs::write(manifest_file.as_std_path(), format!("{content}\n"))
+			.map_err(|e| format!("Failed to write tiles descriptor '{}': {e}", manifest_file))?;
+		Ok(())
+	}
+}
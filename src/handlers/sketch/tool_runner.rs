@@ -0,0 +1,420 @@
+//! How sketchtool runs: locally (macOS with Sketch installed), or on a remote macOS host, over SSH (`--remote`) or
+//! through a `webtk export-server` (`--export-server`), so a Linux CI can still drive the real Sketch renders.
+//! A local sketchtool runs sandboxed (see `sandbox::sandboxed_command`).
+//!
+//! On a remote host, the Sketch file is copied up (once per content, as `~/.webtk-remote/<hash>.sketch`) and the exported
+//! files are copied back with `tar` over `ssh`, unless both machines see the files on a shared volume
+//! (`--remote-shared LOCAL=REMOTE`), in which case only the paths are mapped. An export server gets the Sketch file
+//! uploaded when it does not have it yet, and streams the exported files back as a tar archive.
+
+use crate::handlers::export_server::{ExportJobRequest, ExportJobResponse};
+use crate::support::files::absolute_path;
+use crate::support::{hashes, http, profile, sandbox};
+use crate::{Error, Result};
+use simple_fs::SPath;
+use std::collections::HashSet;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+pub const SKETCHTOOL_PATH: &str = "/Applications/Sketch.app/Contents/Resources/sketchtool/bin/sketchtool";
+
+/// The directory of the uploaded files and exports on the remote host (relative to the SSH user home).
+const REMOTE_WORK_DIR: &str = ".webtk-remote";
+
+static TOOL_RUNNER: OnceLock<ToolRunner> = OnceLock::new();
+
+/// The Sketch files already on the remote host (their remote paths), to upload each one once per process.
+static UPLOADED_FILES: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// The exit code of ssh on its own errors (e.g., connection refused, authentication failed).
+const SSH_ERROR_CODE: i32 = 255;
+
+static REMOTE_EXPORT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// The backend running sketchtool.
+#[derive(Debug, Clone, Default)]
+pub enum ToolRunner {
+	#[default]
+	Local,
+	Ssh(SshTarget),
+	/// The base URL of a `webtk export-server` (e.g., "http://mac-runner:9000").
+	ExportServer(String),
+}
+
+/// The outcome of a sketchtool command, whatever the backend.
+#[derive(Debug)]
+pub struct ToolOutput {
+	pub success: bool,
+	pub stdout: Vec<u8>,
+	pub stderr: Vec<u8>,
+}
+
+impl From<Output> for ToolOutput {
+	fn from(output: Output) -> Self {
+		Self { success: output.status.success(), stdout: output.stdout, stderr: output.stderr }
+	}
+}
+
+/// A remote macOS host with Sketch installed.
+#[derive(Debug, Clone)]
+pub struct SshTarget {
+	/// The SSH destination (e.g., "ci@mac-mini.local", or a `~/.ssh/config` host alias).
+	pub host: String,
+	/// A volume mounted on both machines, as (local dir, remote dir): the paths under the local dir are mapped
+	/// instead of copying the files.
+	pub shared_volume: Option<(String, String)>,
+}
+
+impl SshTarget {
+	/// Parses the `--remote` destination and the optional `--remote-shared LOCAL=REMOTE` mapping.
+	pub fn new(host: impl Into<String>, shared: Option<&str>) -> Result<Self> {
+		let host = host.into();
+		if host.trim().is_empty() || host.starts_with('-') {
+			return Err(Error::custom(format!("Invalid remote host '{host}'. Expected: user@host")));
+		}
+		let shared_volume = shared
+			.map(|shared| match shared.split_once('=') {
+				Some((local, remote)) if !local.is_empty() && !remote.is_empty() => {
+					let local = std::path::absolute(local)
+						.map_err(|e| format!("Invalid shared volume '{local}': {e}"))?
+						.to_string_lossy()
+						.to_string();
+					Ok((local, remote.trim_end_matches('/').to_string()))
+				}
+				_ => Err(Error::custom(format!(
+					"Invalid shared volume '{shared}'. Expected: LOCAL_DIR=REMOTE_DIR (e.g., /mnt/design=/Volumes/design)"
+				))),
+			})
+			.transpose()?;
+
+		Ok(Self { host, shared_volume })
+	}
+}
+
+/// Sets the sketchtool backend of the process (from the global CLI options). Only the first call applies.
+pub fn set_tool_runner(runner: ToolRunner) {
+	let _ = TOOL_RUNNER.set(runner);
+}
+
+pub fn tool_runner() -> &'static ToolRunner {
+	TOOL_RUNNER.get_or_init(ToolRunner::default)
+}
+
+/// Runs a sketchtool command on a Sketch file with the backend of the process, as
+/// `sketchtool <options> [--output=<output_dir>] <command> <sketch_file>` (e.g., command `["list", "artboards"]`).
+/// With an output dir, the exported files are in it on return, whatever the backend.
+pub fn run_sketchtool(
+	command: &[&str],
+	options: &[String],
+	sketch_file: &SPath,
+	output_dir: Option<&SPath>,
+) -> Result<ToolOutput> {
+	let _span = profile::span("tool", format!("sketchtool {}", command.join(" "))).arg("file", sketch_file.as_str());
+	match tool_runner() {
+		ToolRunner::Local => {
+			// The working dir is the output dir (or the Sketch file dir), so the paths are passed absolute
+			let sketch_file = &absolute_path(sketch_file)?;
+			let output_dir = output_dir.map(absolute_path).transpose()?;
+			let work_dir = output_dir
+				.clone()
+				.filter(|dir| dir.exists())
+				.or_else(|| sketch_file.parent())
+				.unwrap_or_else(|| SPath::new("/"));
+
+			let mut cmd = sandbox::sandboxed_command(SKETCHTOOL_PATH, &work_dir);
+			cmd.args(options);
+			if let Some(output_dir) = output_dir {
+				cmd.arg(format!("--output={output_dir}"));
+			}
+			cmd.args(command)
+				.arg(sketch_file.as_str())
+				.output()
+				.map(ToolOutput::from)
+				.map_err(|e| Error::tool_exec("sketchtool", SKETCHTOOL_PATH, e))
+		}
+		ToolRunner::Ssh(target) => {
+			http::check_network(&target.host)?;
+			match &target.shared_volume {
+				Some((local_dir, remote_dir)) => {
+					let remote_file = shared_path(sketch_file, local_dir, remote_dir)?;
+					let remote_output = output_dir.map(|dir| shared_path(dir, local_dir, remote_dir)).transpose()?;
+					let script = sketchtool_script(command, options, &remote_file, remote_output.as_deref());
+					ssh(&target.host, &script).map(ToolOutput::from)
+				}
+				None => run_remote_copy(target, command, options, sketch_file, output_dir),
+			}
+		}
+		ToolRunner::ExportServer(url) => run_on_export_server(url, command, options, sketch_file, output_dir),
+	}
+}
+
+// region:    --- Remote
+
+/// Runs sketchtool on the remote host with copies: the Sketch file up, the exported files back.
+fn run_remote_copy(
+	target: &SshTarget,
+	command: &[&str],
+	options: &[String],
+	sketch_file: &SPath,
+	output_dir: Option<&SPath>,
+) -> Result<ToolOutput> {
+	let remote_file = upload_file(&target.host, sketch_file)?;
+
+	let Some(output_dir) = output_dir else {
+		return ssh(&target.host, &sketchtool_script(command, options, &remote_file, None)).map(ToolOutput::from);
+	};
+
+	let remote_output =
+		format!("{REMOTE_WORK_DIR}/out-{}-{}", std::process::id(), REMOTE_EXPORT_COUNT.fetch_add(1, Ordering::Relaxed));
+	let script = format!(
+		"mkdir -p {} && {}",
+		shell_quote(&remote_output),
+		sketchtool_script(command, options, &remote_file, Some(&remote_output))
+	);
+	let output = ssh(&target.host, &script)?;
+	if output.status.success() {
+		download_dir(&target.host, &remote_output, output_dir)?;
+	} else {
+		let _ = ssh(&target.host, &format!("rm -rf {}", shell_quote(&remote_output)));
+	}
+
+	Ok(output.into())
+}
+
+/// Copies a Sketch file to the remote host, unless it is already there (same content), and returns its remote path.
+fn upload_file(host: &str, sketch_file: &SPath) -> Result<String> {
+	let content =
+		std::fs::read(sketch_file.as_std_path()).map_err(|e| format!("Failed to read '{sketch_file}': {e}"))?;
+	// SHA-256, since a file already on a shared host is used as is
+	let remote_file = format!("{REMOTE_WORK_DIR}/{}.sketch", hashes::sha256_hex(&content));
+
+	let mut uploaded = UPLOADED_FILES
+		.lock()
+		.map_err(|_| Error::custom("Remote uploads lock poisoned"))?;
+	let uploaded = uploaded.get_or_insert_with(HashSet::new);
+	if uploaded.contains(&remote_file) {
+		return Ok(remote_file);
+	}
+
+	// `test -f` exits with 1 when the file is missing, ssh with 255 on a connection error
+	let check = ssh(host, &format!("mkdir -p {REMOTE_WORK_DIR} && test -f {}", shell_quote(&remote_file)))?;
+	if check.status.code() == Some(SSH_ERROR_CODE) {
+		return Err(ssh_error(host, &check));
+	}
+	let exists = check.status.success();
+	if !exists {
+		// Note: absolute, so a relative path with a ':' is not read as a remote host by scp
+		let local_file = absolute_path(sketch_file)?;
+		let output = Command::new("scp")
+			.args(["-q", "-o", "BatchMode=yes"])
+			.arg(local_file.as_str())
+			.arg(format!("{host}:{remote_file}"))
+			.output()
+			.map_err(|e| Error::tool_exec("scp", "scp", e))?;
+		if !output.status.success() {
+			let stderr = String::from_utf8_lossy(&output.stderr);
+			return Err(format!("Failed to copy '{sketch_file}' to '{host}': {}", stderr.trim()).into());
+		}
+	}
+
+	uploaded.insert(remote_file.clone());
+	Ok(remote_file)
+}
+
+/// Copies the content of a remote directory into a local one (`tar` over `ssh`), then deletes the remote directory.
+fn download_dir(host: &str, remote_dir: &str, local_dir: &SPath) -> Result<()> {
+	let quoted = shell_quote(remote_dir);
+	let mut source = Command::new("ssh");
+	source
+		.args(["-o", "BatchMode=yes", host])
+		.arg(format!("tar -C {quoted} -cf - . && rm -rf {quoted}"));
+	extract_tar(source, host, local_dir)
+}
+
+/// Extracts the tar archive written on the stdout of a command (e.g., `ssh`, `curl`) into a local directory.
+fn extract_tar(mut source: Command, origin: &str, local_dir: &SPath) -> Result<()> {
+	let program = source.get_program().to_string_lossy().to_string();
+	let mut source = source
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.map_err(|e| Error::tool_exec(&program, &program, e))?;
+	let archive = source
+		.stdout
+		.take()
+		.ok_or_else(|| format!("Failed to read the {program} output"))?;
+
+	let local = Command::new("tar")
+		.arg("-C")
+		.arg(local_dir.as_str())
+		// The files get the local user and default permissions, not the ones of the remote exports
+		.args(["--no-same-owner", "--no-same-permissions", "-xf", "-"])
+		.stdin(Stdio::from(archive))
+		.output()
+		.map_err(|e| Error::tool_exec("tar", "tar", e))?;
+	let source = source
+		.wait_with_output()
+		.map_err(|e| format!("Failed to wait for {program}: {e}"))?;
+
+	if !source.status.success() {
+		let stderr = String::from_utf8_lossy(&source.stderr);
+		return Err(format!("Failed to copy the exported files from '{origin}': {}", stderr.trim()).into());
+	}
+	if !local.status.success() {
+		let stderr = String::from_utf8_lossy(&local.stderr);
+		return Err(format!("Failed to extract the exported files into '{local_dir}': {}", stderr.trim()).into());
+	}
+
+	Ok(())
+}
+
+/// Runs a shell script on the remote host, its output captured.
+fn ssh(host: &str, script: &str) -> Result<Output> {
+	Command::new("ssh")
+		.args(["-o", "BatchMode=yes", host])
+		.arg(script)
+		.output()
+		.map_err(|e| Error::tool_exec("ssh", "ssh", e))
+}
+
+fn ssh_error(host: &str, output: &Output) -> Error {
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	Error::custom(format!("Failed to connect to remote host '{host}': {}", stderr.trim()))
+}
+
+/// The remote shell command line of a sketchtool run (see `run_sketchtool`).
+fn sketchtool_script(command: &[&str], options: &[String], sketch_file: &str, output_dir: Option<&str>) -> String {
+	let mut args: Vec<String> = vec![SKETCHTOOL_PATH.to_string()];
+	args.extend(options.iter().cloned());
+	if let Some(output_dir) = output_dir {
+		args.push(format!("--output={output_dir}"));
+	}
+	args.extend(command.iter().map(|arg| arg.to_string()));
+	args.push(sketch_file.to_string());
+
+	args.iter().map(|arg| shell_quote(arg)).collect::<Vec<_>>().join(" ")
+}
+
+/// Maps a local path under the shared volume to its remote path.
+fn shared_path(path: &SPath, local_dir: &str, remote_dir: &str) -> Result<String> {
+	let absolute = std::path::absolute(path.as_std_path()).map_err(|e| format!("Invalid path '{path}': {e}"))?;
+	let rel_path = absolute
+		.strip_prefix(Path::new(local_dir))
+		.map_err(|_| format!("'{path}' is not on the shared volume '{local_dir}'"))?;
+	let rel_path = rel_path.to_string_lossy().replace('\\', "/");
+
+	if rel_path.is_empty() { Ok(remote_dir.to_string()) } else { Ok(format!("{remote_dir}/{rel_path}")) }
+}
+
+/// Quotes an argument for a POSIX shell (e.g., "it's" -> "'it'\\''s'").
+fn shell_quote(value: &str) -> String {
+	format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+// endregion: --- Remote
+
+// region:    --- Export Server
+
+/// Runs sketchtool through an export server: the job is posted, and the Sketch file uploaded first when the server
+/// does not have it (404), then the result archive is extracted into the output dir.
+fn run_on_export_server(
+	url: &str,
+	command: &[&str],
+	options: &[String],
+	sketch_file: &SPath,
+	output_dir: Option<&SPath>,
+) -> Result<ToolOutput> {
+	let content =
+		std::fs::read(sketch_file.as_std_path()).map_err(|e| format!("Failed to read '{sketch_file}': {e}"))?;
+	let hash = hashes::sha256_hex(&content);
+	let request = ExportJobRequest {
+		file: hash.clone(),
+		command: command.iter().map(|arg| arg.to_string()).collect(),
+		options: options.to_vec(),
+		output: output_dir.is_some(),
+	};
+	let body = serde_json::to_string(&request).map_err(Error::custom_from_err)?;
+
+	let mut response = post_job(url, &body)?;
+	if response.status == 404 {
+		upload_to_export_server(url, sketch_file, &hash)?;
+		response = post_job(url, &body)?;
+	}
+	if !response.is_success() {
+		let message = serde_json::from_str::<serde_json::Value>(&response.body)
+			.ok()
+			.and_then(|body| body.get("error").and_then(|e| e.as_str()).map(str::to_string))
+			.unwrap_or_else(|| format!("HTTP {}", response.status));
+		return Err(Error::custom(format!("Export server '{url}' failed: {message}")));
+	}
+	let job: ExportJobResponse = serde_json::from_str(&response.body)
+		.map_err(|e| format!("Invalid response of the export server '{url}': {e}"))?;
+
+	if let (Some(result), Some(output_dir)) = (&job.result, output_dir) {
+		let result_url = format!("{url}{result}");
+		let mut source = http::curl_command(&result_url)?;
+		source.args(["-sS", "--fail", "--proto", "=http,https", &result_url]);
+		extract_tar(source, url, output_dir)?;
+	}
+
+	Ok(ToolOutput { success: job.success, stdout: job.stdout.into_bytes(), stderr: job.stderr.into_bytes() })
+}
+
+/// Posts a job (`POST /jobs`), returning the response whatever its status. No timeout: an export can take minutes.
+fn post_job(url: &str, body: &str) -> Result<http::HttpResponse> {
+	let jobs_url = format!("{url}/jobs");
+	let mut child = http::curl_command(&jobs_url)?
+		.args(["-sS", "--proto", "=http,https", "--dump-header", "-", "-X", "POST"])
+		.args(["-H", "Content-Type: application/json", "--data-binary", "@-", &jobs_url])
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.map_err(|e| Error::tool_exec("curl", "curl", e))?;
+
+	if let Some(mut stdin) = child.stdin.take() {
+		stdin
+			.write_all(body.as_bytes())
+			.map_err(|e| format!("Cannot write the job request. Cause: {e}"))?;
+	}
+
+	let output = child.wait_with_output().map_err(Error::custom_from_err)?;
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!("POST '{jobs_url}' failed: {}", stderr.trim())));
+	}
+
+	http::parse_curl_response(&String::from_utf8_lossy(&output.stdout))
+		.ok_or_else(|| Error::custom(format!("POST '{jobs_url}' failed: invalid HTTP response")))
+}
+
+/// Uploads a Sketch file to an export server (`PUT /files/<hash>`).
+fn upload_to_export_server(url: &str, sketch_file: &SPath, hash: &str) -> Result<()> {
+	let files_url = format!("{url}/files/{hash}");
+	let output = http::curl_command(&files_url)?
+		.args([
+			"-sS",
+			"--fail",
+			"--proto",
+			"=http,https",
+			"-H",
+			"Expect:",
+			"-T",
+			sketch_file.as_str(),
+		])
+		.arg(&files_url)
+		.output()
+		.map_err(|e| Error::tool_exec("curl", "curl", e))?;
+
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(format!("Failed to upload '{sketch_file}' to '{url}': {}", stderr.trim()).into());
+	}
+
+	Ok(())
+}
+
+// endregion: --- Export Server
@@ -0,0 +1,37 @@
+use crate::Result;
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The file name of the UID map written in the output directory (see `ExportOptions::uid_map`).
+pub const UID_MAP_FILE: &str = "uid-map.json";
+
+/// The `uid-map.json` manifest, relating each exported artboard UID to its name and output files, so external tools
+/// can track the assets across artboard renames (the UID of an artboard does not change).
+#[derive(Debug, Default, Serialize)]
+pub struct UidMap {
+	/// In export order.
+	pub artboards: Vec<UidMapEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UidMapEntry {
+	pub uid: String,
+	/// The artboard name.
+	pub name: String,
+	pub page: String,
+	/// The output files of the artboard (all formats and scales, the sprite for svg-symbols), relative to the map
+	/// directory.
+	pub files: Vec<String>,
+	/// The `<symbol>` id (svg-symbols only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub symbol_id: Option<String>,
+}
+
+impl UidMap {
+	pub fn write(&self, map_file: &SPath) -> Result<()> {
+		let content = serde_json::to_string_pretty(self)?;
+		std::fs::write(map_file.as_std_path(), format!("{content}\n"))
+			.map_err(|e| format!("Failed to write UID map '{}': {e}", map_file))?;
+		Ok(())
+	}
+}
@@ -0,0 +1,9 @@
+// region:    --- Modules
+
+mod snapshot_projects;
+mod snapshot_store;
+
+pub use snapshot_projects::*;
+pub use snapshot_store::*;
+
+// endregion: --- Modules
@@ -0,0 +1,99 @@
+use crate::handlers::config::ProjectConfig;
+use crate::handlers::run::{self, RunOptions};
+use crate::handlers::snapshot::{RASTERS_DIR, SNAPSHOT_DIR, Snapshot, SnapshotDiff, copy_rasters, snapshot_path};
+use crate::support::files;
+use crate::{Error, Result};
+use simple_fs::SPath;
+
+/// The snapshot of the exports of a project, compared with its recorded snapshot.
+#[derive(Debug)]
+pub struct ProjectSnapshot {
+	pub config_file: String,
+	pub base_dir: SPath,
+	/// The `__webtk_snapshots__` directory of the project.
+	pub snapshot_dir: SPath,
+	/// The recorded snapshot (None when never recorded).
+	pub recorded: Option<Snapshot>,
+	/// The snapshot of the files just exported.
+	pub actual: Snapshot,
+	/// The files just exported (for the raster copies).
+	pub exported_files: Vec<String>,
+	/// The differences of `actual` from `recorded` (all the files added when never recorded).
+	pub diff: SnapshotDiff,
+}
+
+/// Runs the jobs of the projects (see `run::run_projects`), then snapshots their exported files.
+/// Fails when a job fails (a partial export cannot be compared).
+pub fn snapshot_projects(projects: &[ProjectConfig], job_names: &[String]) -> Result<Vec<ProjectSnapshot>> {
+	let report = run::run_projects(projects, job_names, &RunOptions::default())?;
+
+	if report.jobs().count() == 0 {
+		return Err(Error::custom("No jobs to snapshot"));
+	}
+	if let Some(job) = report.jobs().find(|job| job.error.is_some()) {
+		return Err(Error::custom(format!(
+			"Job '{}' failed, no snapshot taken. Cause: {}",
+			job.name,
+			job.error.as_deref().unwrap_or_default()
+		)));
+	}
+
+	let mut snapshots = Vec::new();
+	for (project, project_report) in projects.iter().zip(&report.projects) {
+		let exported_files: Vec<String> = project_report
+			.jobs
+			.iter()
+			.filter_map(|job| job.report.as_ref())
+			.flat_map(|r| r.exported_files.iter().cloned())
+			.collect();
+
+		let snapshot_dir = project.base_dir.join(SNAPSHOT_DIR);
+		let actual = Snapshot::from_files(&project.base_dir, &exported_files)?;
+		let recorded = Snapshot::load(&snapshot_dir)?;
+		let diff = recorded
+			.as_ref()
+			.map(|r| r.diff(&actual))
+			.unwrap_or_else(|| Snapshot::default().diff(&actual));
+
+		snapshots.push(ProjectSnapshot {
+			config_file: project.config_file.to_string(),
+			base_dir: project.base_dir.clone(),
+			snapshot_dir,
+			recorded,
+			actual,
+			exported_files,
+			diff,
+		});
+	}
+
+	Ok(snapshots)
+}
+
+impl ProjectSnapshot {
+	/// Records the actual snapshot (`record`, or `check --update`), with the raster copies when `rasters` is set
+	/// (replacing the previous ones, including the `.new` rasters of a failed check).
+	pub fn accept(&self, rasters: bool) -> Result<()> {
+		self.actual.write(&self.snapshot_dir)?;
+
+		let _ = files::safer_delete_dir(&self.snapshot_dir.join(RASTERS_DIR));
+		if rasters {
+			copy_rasters(&self.base_dir, &self.snapshot_dir, &self.exported_files, "")?;
+		}
+
+		Ok(())
+	}
+
+	/// Writes the changed and added rasters as `<name>.new.<ext>` next to the recorded ones, for review.
+	pub fn write_new_rasters(&self) -> Result<Vec<String>> {
+		let files: Vec<String> = self
+			.exported_files
+			.iter()
+			.filter(|file| {
+				let path = snapshot_path(&self.base_dir, file);
+				self.diff.added.contains(&path) || self.diff.changed.contains(&path)
+			})
+			.cloned()
+			.collect();
+		copy_rasters(&self.base_dir, &self.snapshot_dir, &files, ".new")
+	}
+}
@@ -0,0 +1,171 @@
+//! The snapshots of the exported assets of a project (`__webtk_snapshots__/snapshots.json`):
+//! one normalized content hash per file, so formatting-only changes are not differences.
+
+use crate::support::{hashes, xmls};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use simple_fs::{SPath, ensure_dir, read_to_string};
+use std::collections::BTreeMap;
+
+/// The snapshot directory, in the project directory (next to webtk.toml).
+pub const SNAPSHOT_DIR: &str = "__webtk_snapshots__";
+
+/// The snapshot file, in the snapshot directory.
+const SNAPSHOT_FILE: &str = "snapshots.json";
+
+/// The directory of the raster copies (`--rasters`), in the snapshot directory.
+pub const RASTERS_DIR: &str = "rasters";
+
+/// The raster formats copied with `--rasters` (for reviewing the differences).
+const RASTER_EXTS: &[&str] = &["png", "jpg", "jpeg", "webp", "tiff"];
+
+/// The exported files of a project with their normalized hashes, by path relative to the project directory.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+	pub files: BTreeMap<String, SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+	/// The FNV-1a hash of the normalized content (see `snapshot_hash`).
+	pub hash: String,
+	/// The file size (informative, not compared).
+	pub bytes: u64,
+}
+
+/// The differences between a recorded snapshot and the current exports (file paths).
+#[derive(Debug, Default, Serialize)]
+pub struct SnapshotDiff {
+	pub added: Vec<String>,
+	pub removed: Vec<String>,
+	pub changed: Vec<String>,
+}
+
+impl SnapshotDiff {
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+	}
+
+	pub fn len(&self) -> usize {
+		self.added.len() + self.removed.len() + self.changed.len()
+	}
+}
+
+impl Snapshot {
+	/// Builds the snapshot of exported files (paths relative to `base_dir` when below it).
+	pub fn from_files(base_dir: &SPath, files: &[String]) -> Result<Self> {
+		let mut snapshot = Snapshot::default();
+		for file in files {
+			let path = SPath::new(file);
+			let content = std::fs::read(path.as_std_path())
+				.map_err(|e| format!("Cannot read exported file '{path}' for the snapshot. Cause: {e}"))?;
+			let entry = SnapshotEntry {
+				hash: snapshot_hash(&path.ext().to_lowercase(), &content),
+				bytes: content.len() as u64,
+			};
+			snapshot.files.insert(snapshot_path(base_dir, file), entry);
+		}
+		Ok(snapshot)
+	}
+
+	/// Loads the snapshot of a snapshot directory (None when none was recorded).
+	pub fn load(snapshot_dir: &SPath) -> Result<Option<Self>> {
+		let snapshot_file = snapshot_dir.join(SNAPSHOT_FILE);
+		if !snapshot_file.exists() {
+			return Ok(None);
+		}
+		let content = read_to_string(snapshot_file.as_std_path()).map_err(Error::custom_from_err)?;
+		let snapshot = serde_json::from_str(&content)
+			.map_err(|e| format!("Invalid snapshot file '{snapshot_file}'. Cause: {e}"))?;
+		Ok(Some(snapshot))
+	}
+
+	/// Writes the snapshot file in the snapshot directory (created if needed).
+	pub fn write(&self, snapshot_dir: &SPath) -> Result<()> {
+		ensure_dir(snapshot_dir.as_std_path())
+			.map_err(|e| format!("Failed to create snapshot directory '{snapshot_dir}': {e}"))?;
+		let snapshot_file = snapshot_dir.join(SNAPSHOT_FILE);
+		let content = serde_json::to_string_pretty(self)?;
+		std::fs::write(snapshot_file.as_std_path(), format!("{content}\n"))
+			.map_err(|e| format!("Failed to write snapshot file '{snapshot_file}': {e}"))?;
+		Ok(())
+	}
+
+	/// Returns the differences of `actual` from this (recorded) snapshot.
+	pub fn diff(&self, actual: &Snapshot) -> SnapshotDiff {
+		let mut diff = SnapshotDiff::default();
+		for (path, entry) in &actual.files {
+			match self.files.get(path) {
+				None => diff.added.push(path.clone()),
+				Some(recorded) if recorded.hash != entry.hash => diff.changed.push(path.clone()),
+				Some(_) => (),
+			}
+		}
+		diff.removed = self
+			.files
+			.keys()
+			.filter(|path| !actual.files.contains_key(*path))
+			.cloned()
+			.collect();
+		diff
+	}
+}
+
+/// Returns the hash of a file content, normalized by format:
+/// - svg: the markup without the XML declaration and formatting whitespace (see `xmls::normalize_markup`),
+/// - json: the parsed value (key order and formatting ignored),
+/// - others: the raw bytes.
+pub fn snapshot_hash(ext: &str, content: &[u8]) -> String {
+	match ext {
+		"svg" => match std::str::from_utf8(content) {
+			Ok(text) => hashes::fnv1a_64_hex(xmls::normalize_markup(text)),
+			Err(_) => hashes::fnv1a_64_hex(content),
+		},
+		"json" => match serde_json::from_slice::<serde_json::Value>(content) {
+			Ok(mut value) => {
+				value.sort_all_objects();
+				hashes::fnv1a_64_hex(value.to_string())
+			}
+			Err(_) => hashes::fnv1a_64_hex(content),
+		},
+		_ => hashes::fnv1a_64_hex(content),
+	}
+}
+
+/// Copies the raster files (png, jpeg, ...) to `<snapshot_dir>/rasters/<path>`, with `suffix` before the extension
+/// (e.g., ".new" for the rasters of a failed check), so the differences can be reviewed as images.
+/// Returns the written files.
+pub fn copy_rasters(base_dir: &SPath, snapshot_dir: &SPath, files: &[String], suffix: &str) -> Result<Vec<String>> {
+	let mut written = Vec::new();
+	for file in files {
+		let path = SPath::new(file);
+		let ext = path.ext().to_lowercase();
+		if !RASTER_EXTS.contains(&ext.as_str()) {
+			continue;
+		}
+		let relative = snapshot_path(base_dir, file);
+		let relative = match relative.rsplit_once('.') {
+			Some((stem, ext)) => format!("{stem}{suffix}.{ext}"),
+			None => format!("{relative}{suffix}"),
+		};
+		let target = snapshot_dir.join(RASTERS_DIR).join(relative);
+		if let Some(parent) = target.parent() {
+			ensure_dir(parent.as_std_path())
+				.map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+		}
+		std::fs::copy(path.as_std_path(), target.as_std_path())
+			.map_err(|e| format!("Failed to copy raster '{path}' to '{target}': {e}"))?;
+		written.push(target.to_string());
+	}
+	Ok(written)
+}
+
+/// Returns the snapshot path of a file: relative to `base_dir` (with `/` separators), or the path itself when not below it.
+pub fn snapshot_path(base_dir: &SPath, file: &str) -> String {
+	let file = file.replace('\\', "/");
+	let base = base_dir.as_str().replace('\\', "/");
+	match file.strip_prefix(&format!("{}/", base.trim_end_matches('/'))) {
+		Some(relative) if !base.is_empty() => relative.to_string(),
+		_ => file,
+	}
+}
@@ -0,0 +1,91 @@
+//! The design sources (Sketch files, Figma files) behind one interface, so the generic commands
+//! (`webtk list`, `webtk export`) work the same on each backend.
+//! The source of an input is detected from its extension or URL (see `SourceKind::detect`).
+
+use crate::handlers::figma::{self, FigmaSource};
+use crate::handlers::sketch::SketchSource;
+use crate::support::{files, http};
+use crate::{Error, Result, Warning};
+use simple_fs::SPath;
+
+/// An exportable item of a design source (a Sketch artboard, a Figma frame).
+#[derive(Debug, Clone)]
+pub struct DesignItem {
+	/// The id in the source (Sketch artboard uid, Figma node id).
+	pub id: String,
+	pub name: String,
+	pub page: String,
+}
+
+/// The outcome of `DesignSource::export_items`.
+#[derive(Debug, Default)]
+pub struct DesignExport {
+	pub exported_files: Vec<String>,
+	pub warnings: Vec<Warning>,
+}
+
+pub trait DesignSource {
+	/// Returns the items matching the globs (all the items when empty), sorted by name.
+	fn list_items(&self, globs: &[String]) -> Result<Vec<DesignItem>>;
+
+	/// Exports the items matching the globs in each format (e.g., "svg", "png") to `output_dir`,
+	/// as `<item name>.<ext>`. Fails with `Error::NoArtboardsMatched` when no item matches.
+	fn export_items(&self, globs: &[String], formats: &[String], output_dir: &SPath) -> Result<DesignExport>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+	Sketch,
+	Figma,
+}
+
+impl SourceKind {
+	/// Detects the source of an input: a `.sketch` file, or a figma.com URL.
+	pub fn detect(input: &str) -> Option<SourceKind> {
+		if http::is_url(input) {
+			let host = input.split("://").nth(1)?.split(['/', '?', '#']).next()?;
+			let is_figma = host == "figma.com" || host.ends_with(".figma.com");
+			is_figma.then_some(SourceKind::Figma)
+		} else if input.to_lowercase().ends_with(".sketch") {
+			Some(SourceKind::Sketch)
+		} else {
+			None
+		}
+	}
+}
+
+impl TryFrom<&str> for SourceKind {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"sketch" => Ok(Self::Sketch),
+			"figma" => Ok(Self::Figma),
+			other => Err(Error::custom(format!("Invalid source '{other}'. Expected: sketch, figma"))),
+		}
+	}
+}
+
+/// Opens the design source of an input (a file path, a URL, or a Figma file key with `kind`),
+/// detecting its kind when not given.
+pub fn open_source(input: &str, kind: Option<SourceKind>) -> Result<Box<dyn DesignSource>> {
+	let kind = kind.or_else(|| SourceKind::detect(input)).ok_or_else(|| {
+		Error::custom(format!(
+			"Cannot detect the design source of '{input}' (expected a .sketch file or a figma.com URL). Use --source sketch|figma"
+		))
+	})?;
+
+	match kind {
+		SourceKind::Sketch => {
+			let sketch_file = SPath::new(files::expand_path(input)?);
+			files::check_file_exists(&sketch_file)?;
+			Ok(Box::new(SketchSource::new(sketch_file)))
+		}
+		SourceKind::Figma => {
+			let file_key = figma::parse_file_key(input).ok_or_else(|| {
+				Error::custom(format!("Invalid Figma file '{input}'. Expected a file key or a figma.com file URL"))
+			})?;
+			Ok(Box::new(FigmaSource::new(figma::FigmaClient::from_auth()?, file_key)))
+		}
+	}
+}
@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod design_source;
+
+pub use design_source::*;
+
+// endregion: --- Modules
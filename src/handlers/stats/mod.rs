@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod usage_stats;
+
+pub use usage_stats::*;
+
+// endregion: --- Modules
@@ -0,0 +1,142 @@
+//! The local usage stats (opt-in with `[stats] enabled = true` in `webtk.toml`): one JSON line per command run,
+//! appended to a local file, with the command, its duration, result, and artboard count.
+//! Never any argument (file names, globs, URLs), and nothing is ever sent over the network.
+
+use crate::handlers::config::{self, StatsSection};
+use crate::support::files;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use simple_fs::{SPath, ensure_dir};
+use std::collections::BTreeMap;
+use std::io::Write as _;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// The stats file when `[stats] file` is not set (one for all the projects of the user).
+pub const DEFAULT_STATS_FILE: &str = "~/.webtk/stats.jsonl";
+
+/// The artboards exported by the running command (see `count_artboards`).
+static ARTBOARD_COUNT: AtomicUsize = AtomicUsize::new(0);
+static ARTBOARDS_COUNTED: AtomicBool = AtomicBool::new(false);
+
+/// A command run, as a line of the stats file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+	/// The end of the run, in unix seconds.
+	pub time: i64,
+	/// The subcommand names only (e.g., "sketch export").
+	pub command: String,
+	pub duration_ms: u64,
+	/// The process exit code (see `Error::exit_code`).
+	pub exit_code: i32,
+	/// The artboards exported, for the exporting commands.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub artboards: Option<usize>,
+}
+
+/// The stats of a command, over the records of the stats file.
+#[derive(Debug, Default, Serialize)]
+pub struct CommandUsage {
+	pub command: String,
+	pub runs: usize,
+	/// The runs with a non-zero exit code.
+	pub failures: usize,
+	pub total_ms: u64,
+	pub avg_ms: u64,
+	pub artboards: usize,
+}
+
+/// The stats of the records of the stats file, by command (most run first).
+#[derive(Debug, Default, Serialize)]
+pub struct UsageSummary {
+	pub stats_file: String,
+	/// The time of the first and last records (unix seconds).
+	pub since: Option<i64>,
+	pub until: Option<i64>,
+	pub commands: Vec<CommandUsage>,
+}
+
+/// Adds artboards to the count of the running command (called by the exports).
+pub fn count_artboards(count: usize) {
+	ARTBOARD_COUNT.fetch_add(count, Ordering::Relaxed);
+	ARTBOARDS_COUNTED.store(true, Ordering::Relaxed);
+}
+
+/// Returns the artboards exported by the running command, None when it exported none (e.g., not an export).
+pub fn counted_artboards() -> Option<usize> {
+	ARTBOARDS_COUNTED
+		.load(Ordering::Relaxed)
+		.then(|| ARTBOARD_COUNT.load(Ordering::Relaxed))
+}
+
+/// Returns the `[stats]` section of the `webtk.toml` of the current directory (or of its workspace root).
+/// An absent or invalid config has no stats section (the stats are never a reason for a command to fail).
+pub fn find_stats_section() -> Option<StatsSection> {
+	let current_dir = std::env::current_dir().ok()?;
+	let current_dir = SPath::from_std_path(current_dir).ok()?;
+	let project = config::find_workspace_root(&current_dir).ok()??;
+	let mut section = project.config.stats.clone()?;
+	section.file = section.file.map(|file| project.resolve_path(&file).to_string());
+	Some(section)
+}
+
+/// Returns the stats file of a `[stats]` section (expanded, default `DEFAULT_STATS_FILE`).
+pub fn stats_file(section: Option<&StatsSection>) -> Result<SPath> {
+	let file = section
+		.and_then(|section| section.file.as_deref())
+		.unwrap_or(DEFAULT_STATS_FILE);
+	Ok(SPath::new(files::expand_path(file)?))
+}
+
+/// Appends a record to the stats file (creating it and its directory).
+pub fn append_usage_record(stats_file: &SPath, record: &UsageRecord) -> Result<()> {
+	if let Some(parent) = stats_file.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+	}
+	let line = serde_json::to_string(record)?;
+	let mut file = std::fs::OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(stats_file.as_std_path())
+		.map_err(|e| format!("Failed to open '{stats_file}': {e}"))?;
+	writeln!(file, "{line}").map_err(|e| format!("Failed to write '{stats_file}': {e}"))?;
+	Ok(())
+}
+
+/// Reads the records of a stats file. The invalid lines are skipped (e.g., a line cut by a full disk).
+pub fn read_usage_records(stats_file: &SPath) -> Result<Vec<UsageRecord>> {
+	if !stats_file.exists() {
+		return Err(Error::FileNotFound(stats_file.clone()));
+	}
+	let content =
+		std::fs::read_to_string(stats_file.as_std_path()).map_err(|e| format!("Failed to read '{stats_file}': {e}"))?;
+	Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
+
+/// Summarizes the records by command.
+pub fn summarize_usage(stats_file: &SPath, records: &[UsageRecord]) -> UsageSummary {
+	let mut by_command: BTreeMap<&str, CommandUsage> = BTreeMap::new();
+	for record in records {
+		let usage = by_command
+			.entry(record.command.as_str())
+			.or_insert_with(|| CommandUsage { command: record.command.clone(), ..Default::default() });
+		usage.runs += 1;
+		if record.exit_code != 0 {
+			usage.failures += 1;
+		}
+		usage.total_ms += record.duration_ms;
+		usage.artboards += record.artboards.unwrap_or(0);
+	}
+
+	let mut commands: Vec<CommandUsage> = by_command.into_values().collect();
+	for usage in &mut commands {
+		usage.avg_ms = usage.total_ms / usage.runs as u64;
+	}
+	commands.sort_by_key(|usage| std::cmp::Reverse(usage.runs));
+
+	UsageSummary {
+		stats_file: stats_file.to_string(),
+		since: records.iter().map(|record| record.time).min(),
+		until: records.iter().map(|record| record.time).max(),
+		commands,
+	}
+}
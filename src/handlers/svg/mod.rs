@@ -0,0 +1,27 @@
+// region:    --- Modules
+
+mod svg_bench;
+mod svg_embed_images;
+mod svg_extract_images;
+mod svg_icon_path;
+mod svg_local_refs;
+mod svg_padding;
+mod svg_recolor;
+mod svg_sprite;
+mod svg_stroke;
+mod svg_unsprite;
+mod symbols_cache;
+
+pub use svg_bench::*;
+pub use svg_embed_images::*;
+pub use svg_extract_images::*;
+pub use svg_icon_path::*;
+pub use svg_local_refs::*;
+pub use svg_padding::*;
+pub use svg_recolor::*;
+pub use svg_sprite::*;
+pub use svg_stroke::*;
+pub use svg_unsprite::*;
+pub use symbols_cache::*;
+
+// endregion: --- Modules
@@ -0,0 +1,161 @@
+//! Throughput of the SVG transforms (`webtk svg bench`, hidden): each transform runs over the SVG files of a
+//! directory (or the built-in fixtures), to gather real-world MB/s numbers on the user's own assets, e.g., before and
+//! after a rewrite of the transform layer.
+
+use crate::handlers::svg;
+use crate::support::xmls;
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::{SPath, read_to_string};
+use std::hint::black_box;
+use std::time::Instant;
+
+pub const DEFAULT_BENCH_ITERATIONS: u32 = 5;
+
+/// The benchmarked transforms, in run order.
+pub const BENCH_TRANSFORMS: &[&str] = &["symbol", "padding", "non-scaling-stroke", "recolor", "normalize"];
+
+/// The shape counts of the built-in fixtures (see `bench_fixtures`).
+pub const BENCH_FIXTURE_SHAPE_COUNTS: &[usize] = &[4, 120, 3000];
+
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+	/// The directory of the SVG files (None for the built-in fixtures).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub dir: Option<String>,
+	pub file_count: usize,
+	/// The size of the files (one iteration).
+	pub bytes: u64,
+	pub iterations: u32,
+	pub results: Vec<BenchResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchResult {
+	pub transform: String,
+	/// The total duration of the iterations (after a warm-up pass).
+	pub duration_ms: f64,
+	/// The input megabytes (1 MB = 1024 KB) transformed per second.
+	pub mb_per_sec: f64,
+	/// The files the transform rejects (e.g., no viewBox for `padding`), still counted in the throughput.
+	pub failed_count: usize,
+}
+
+/// Runs each transform `iterations` times over the SVG files of `dir` (recursively), or over the built-in fixtures
+/// when None.
+pub fn bench_svg_transforms(dir: Option<&SPath>, iterations: u32) -> Result<BenchReport> {
+	if iterations == 0 {
+		return Err(Error::custom("The iterations must be greater than 0"));
+	}
+	let contents = match dir {
+		Some(dir) => load_svg_files(dir)?,
+		None => bench_fixtures(),
+	};
+	if contents.is_empty() {
+		return Err(Error::custom(format!("No SVG files in '{}'", dir.map(|dir| dir.as_str()).unwrap_or_default())));
+	}
+	let bytes: u64 = contents.iter().map(|content| content.len() as u64).sum();
+
+	let mut results = Vec::new();
+	for transform in BENCH_TRANSFORMS {
+		// The warm-up pass also counts the rejected files
+		let failed_count = contents
+			.iter()
+			.filter(|content| !run_bench_transform(transform, content))
+			.count();
+
+		let started = Instant::now();
+		for _ in 0..iterations {
+			for content in &contents {
+				black_box(run_bench_transform(transform, black_box(content)));
+			}
+		}
+		let secs = started.elapsed().as_secs_f64();
+
+		let megabytes = (bytes * iterations as u64) as f64 / (1024.0 * 1024.0);
+		results.push(BenchResult {
+			transform: transform.to_string(),
+			duration_ms: secs * 1000.0,
+			mb_per_sec: if secs > 0.0 { megabytes / secs } else { 0.0 },
+			failed_count,
+		});
+	}
+
+	Ok(BenchReport { dir: dir.map(|dir| dir.to_string()), file_count: contents.len(), bytes, iterations, results })
+}
+
+/// The built-in fixtures: Sketch-like exports of growing sizes (an icon, an illustration with gradients and
+/// masks, and a large map-like drawing), for numbers comparable across machines.
+pub fn bench_fixtures() -> Vec<String> {
+	BENCH_FIXTURE_SHAPE_COUNTS
+		.iter()
+		.map(|&shape_count| fixture_svg(shape_count))
+		.collect()
+}
+
+/// Runs a transform of `BENCH_TRANSFORMS`, returning false when it rejects the content (or for an unknown transform).
+pub fn run_bench_transform(transform: &str, content: &str) -> bool {
+	match transform {
+		"symbol" => svg::convert_svg_to_symbol(content, "bench", &[], &["sketch"]).is_some(),
+		"padding" => svg::pad_svg(content, 4.0).is_some(),
+		"non-scaling-stroke" => svg::add_non_scaling_stroke(content).is_some(),
+		"recolor" => svg::recolor_svg(content, "#000000").is_some(),
+		"normalize" => !xmls::normalize_markup(content).is_empty(),
+		_ => false,
+	}
+}
+
+// region:    --- Support
+
+fn load_svg_files(dir: &SPath) -> Result<Vec<String>> {
+	if !dir.is_dir() {
+		return Err(Error::custom(format!("Directory not found: '{dir}'")));
+	}
+	let files = simple_fs::list_files(dir.as_std_path(), Some(&["**/*.svg"]), None).map_err(Error::custom_from_err)?;
+	files
+		.iter()
+		.map(|file| {
+			read_to_string(file.as_std_path()).map_err(|e| Error::custom(format!("Failed to read '{file}': {e}")))
+		})
+		.collect()
+}
+
+/// An SVG like the sketchtool exports: `sketch` namespace, groups with ids, gradients and a mask referenced by url.
+fn fixture_svg(shape_count: usize) -> String {
+	let mut shapes = String::new();
+	for idx in 0..shape_count {
+		let (x, y) = ((idx * 37) % 1000, (idx * 53) % 1000);
+		let fill =
+			if idx % 5 == 0 { "url(#Gradient-1)".to_string() } else { format!("#{:06X}", (idx * 7919) % 0xFFFFFF) };
+		shapes.push_str(&format!(
+			r##"            <g id="Shape-{idx}" transform="translate({x}.5, {y}.25)" sketch:type="MSShapeGroup">
+                <path d="M0,0 L{w},0 C{w},8 12,{h} 0,{h} Z" id="Path-{idx}" fill="{fill}" stroke="#333333" stroke-width="1.5"></path>
+            </g>
+"##,
+			w = 10 + idx % 40,
+			h = 8 + idx % 30,
+		));
+	}
+
+	format!(
+		r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg width="1000px" height="1000px" viewBox="0 0 1000 1000" version="1.1" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:sketch="http://www.bohemiancoding.com/sketch/ns">
+    <title>fixture-{shape_count}</title>
+    <defs>
+        <linearGradient x1="50%" y1="0%" x2="50%" y2="100%" id="Gradient-1">
+            <stop stop-color="#FFFFFF" offset="0%"></stop>
+            <stop stop-color="#000000" offset="100%"></stop>
+        </linearGradient>
+        <rect id="Mask-Path" x="0" y="0" width="1000" height="1000"></rect>
+    </defs>
+    <g id="Page-1" stroke="none" fill="none" fill-rule="evenodd">
+        <mask id="Mask-1" fill="white"><use xlink:href="#Mask-Path"></use></mask>
+        <g id="Artboard" mask="url(#Mask-1)">
+{shapes}        </g>
+    </g>
+</svg>
+"##
+	)
+}
+
+// endregion: --- Support
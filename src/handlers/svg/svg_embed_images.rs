@@ -0,0 +1,111 @@
+//! The raster images referenced by an SVG (an `href` to a png, jpeg, gif, or webp file or URL) inlined as `data:`
+//! URIs, so the illustrations stay single self-contained files (see `ExportOptions::embed_images`).
+
+use crate::handlers::svg::{is_local_reference, local_reference_path};
+use crate::support::{http, strings, xmls_stream};
+use crate::{Error, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use simple_fs::SPath;
+use std::collections::HashMap;
+
+/// The extensions of the embedded images.
+pub const RASTER_IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+/// The file size over which an embedded image is reported (`large-embedded-image` warning), as it grows the SVG by a
+/// third more (base64) and is not cached apart from it.
+pub const EMBED_IMAGE_WARN_BYTES: u64 = 256 * 1024;
+
+/// An image inlined by `embed_svg_images`.
+#[derive(Debug, Clone)]
+pub struct EmbeddedImage {
+	/// The reference as written in the SVG (e.g., "images/hero.png").
+	pub reference: String,
+	/// The file size (before the base64 encoding).
+	pub bytes: u64,
+}
+
+/// Inlines the raster images referenced by an SVG document: the URLs (downloaded through the HTTP cache, see
+/// `http::fetch_to_file`), the local paths (see `is_local_reference`), and the relative paths (from `base_dir`, the
+/// directory of the SVG file). Returns the content and the embedded images.
+/// Fails when an image cannot be read or downloaded.
+pub fn embed_svg_images(svg_content: &str, base_dir: &SPath) -> Result<(String, Vec<EmbeddedImage>)> {
+	let references = xmls_stream::find_attribute_values(svg_content, |name, value| {
+		(name == "href" || name == "xlink:href") && is_raster_image_reference(value)
+	})
+	.ok_or("Invalid SVG content")?;
+
+	let mut data_uris: HashMap<String, String> = HashMap::new();
+	let mut images = Vec::new();
+	for reference in references {
+		if data_uris.contains_key(&reference) {
+			continue;
+		}
+		let file = image_file(&reference, base_dir)?;
+		let (data_uri, bytes) = file_data_uri(&file)?;
+		data_uris.insert(reference.clone(), data_uri);
+		images.push(EmbeddedImage { reference, bytes });
+	}
+	if images.is_empty() {
+		return Ok((svg_content.to_string(), images));
+	}
+
+	let content = xmls_stream::transform_attributes(svg_content, &["href", "xlink:href"], |value| {
+		data_uris.get(value).cloned().unwrap_or_else(|| value.to_string())
+	})
+	.ok_or("Invalid SVG content")?;
+
+	Ok((content, images))
+}
+
+/// Returns the `data:` URI (base64) of a file, with its size.
+pub fn file_data_uri(file: &SPath) -> Result<(String, u64)> {
+	let content =
+		std::fs::read(file.as_std_path()).map_err(|e| Error::custom(format!("Cannot read the file '{file}': {e}")))?;
+	let data_uri = format!("data:{};base64,{}", media_type(file.ext()), BASE64.encode(&content));
+	Ok((data_uri, content.len() as u64))
+}
+
+// region:    --- Support
+
+/// Returns true when a reference is a raster image file or URL (by its extension), not already inlined.
+fn is_raster_image_reference(value: &str) -> bool {
+	let value = value.trim();
+	if value.is_empty() || value.starts_with('#') || value.starts_with("data:") {
+		return false;
+	}
+	let path = value.split(['?', '#']).next().unwrap_or_default();
+	path.rsplit_once('.')
+		.is_some_and(|(_, ext)| RASTER_IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Returns the file of an image reference: downloaded (URL), local, or relative to `base_dir` (percent-decoded).
+fn image_file(reference: &str, base_dir: &SPath) -> Result<SPath> {
+	let reference = reference.trim();
+	if http::is_url(reference) {
+		return http::fetch_to_file(reference);
+	}
+	if is_local_reference(reference) {
+		let path = local_reference_path(reference)
+			.ok_or_else(|| Error::custom(format!("Invalid local reference '{reference}'")))?;
+		return Ok(SPath::new(path));
+	}
+	let path = reference.split(['?', '#']).next().unwrap_or_default();
+	let path = strings::percent_decode(&path.replace('+', "%2B"))
+		.ok_or_else(|| Error::custom(format!("Invalid image reference '{reference}'")))?;
+	Ok(base_dir.join(path))
+}
+
+/// Returns the media type of an embedded file, from its extension.
+fn media_type(extension: &str) -> &'static str {
+	match extension.to_ascii_lowercase().as_str() {
+		"png" => "image/png",
+		"jpg" | "jpeg" => "image/jpeg",
+		"gif" => "image/gif",
+		"webp" => "image/webp",
+		"svg" => "image/svg+xml",
+		_ => "application/octet-stream",
+	}
+}
+
+// endregion: --- Support
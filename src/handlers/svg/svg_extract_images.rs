@@ -0,0 +1,85 @@
+//! The raster images inlined in an SVG (base64 `data:` URIs, e.g., the image fills of a Sketch export) written out
+//! as files, the references rewritten to them, so the sprites shrink and the images can go through the image
+//! optimizers (see `ExportOptions::extract_images`). The reverse of `embed_svg_images`.
+
+use crate::support::{hashes, xmls_stream};
+use crate::{Error, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use simple_fs::{SPath, ensure_dir};
+use std::collections::HashMap;
+
+/// Writes the raster images inlined in an SVG document to `image_dir`, named by their content hash (e.g.,
+/// "img-3f2a1b9c0d4e5f60.png", so the images shared by several SVGs are written once), and rewrites their references
+/// to `href_dir` (the path of `image_dir` relative to the SVG file, "" for the same directory) plus the file name.
+/// Returns the content and the image files. Fails when an image cannot be decoded or written.
+pub fn extract_svg_images(svg_content: &str, image_dir: &SPath, href_dir: &str) -> Result<(String, Vec<SPath>)> {
+	let data_uris = xmls_stream::find_attribute_values(svg_content, |name, value| {
+		(name == "href" || name == "xlink:href") && data_uri_extension(value).is_some()
+	})
+	.ok_or("Invalid SVG content")?;
+
+	let mut hrefs: HashMap<String, String> = HashMap::new();
+	let mut image_files = Vec::new();
+	for data_uri in data_uris {
+		if hrefs.contains_key(&data_uri) {
+			continue;
+		}
+		let (extension, content) = decode_image_data_uri(&data_uri)?;
+		let file_name = format!("img-{}.{extension}", hashes::fnv1a_64_hex(&content));
+		let image_file = image_dir.join(&file_name);
+		if !image_file.exists() {
+			ensure_dir(image_dir.as_std_path())
+				.map_err(|e| format!("Failed to create directory '{image_dir}': {e}"))?;
+			std::fs::write(image_file.as_std_path(), &content)
+				.map_err(|e| format!("Failed to write '{image_file}': {e}"))?;
+		}
+		let href = match href_dir.trim_end_matches('/') {
+			"" => file_name,
+			dir => format!("{dir}/{file_name}"),
+		};
+		hrefs.insert(data_uri, href);
+		if !image_files.contains(&image_file) {
+			image_files.push(image_file);
+		}
+	}
+	if image_files.is_empty() {
+		return Ok((svg_content.to_string(), image_files));
+	}
+
+	let content = xmls_stream::transform_attributes(svg_content, &["href", "xlink:href"], |value| {
+		hrefs.get(value).cloned().unwrap_or_else(|| value.to_string())
+	})
+	.ok_or("Invalid SVG content")?;
+
+	Ok((content, image_files))
+}
+
+// region:    --- Support
+
+/// Returns the file extension of a base64 raster image `data:` URI (None for the other values, e.g., an SVG image).
+fn data_uri_extension(value: &str) -> Option<&'static str> {
+	let (media_type, _) = value.trim().strip_prefix("data:")?.split_once(";base64,")?;
+	// Note: the media type can have parameters (e.g., "image/png;name=hero.png")
+	match media_type.split(';').next()?.trim().to_ascii_lowercase().as_str() {
+		"image/png" => Some("png"),
+		"image/jpeg" | "image/jpg" => Some("jpg"),
+		"image/gif" => Some("gif"),
+		"image/webp" => Some("webp"),
+		_ => None,
+	}
+}
+
+/// Returns the file extension and the decoded content of a base64 raster image `data:` URI.
+fn decode_image_data_uri(data_uri: &str) -> Result<(&'static str, Vec<u8>)> {
+	let invalid =
+		|| Error::custom(format!("Invalid image data URI '{}...'", data_uri.chars().take(40).collect::<String>()));
+	let extension = data_uri_extension(data_uri).ok_or_else(invalid)?;
+	let (_, data) = data_uri.split_once(";base64,").ok_or_else(invalid)?;
+	// The base64 of the attributes can be wrapped (whitespace is not significant)
+	let data: String = data.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+	let content = BASE64.decode(data).map_err(|_| invalid())?;
+	Ok((extension, content))
+}
+
+// endregion: --- Support
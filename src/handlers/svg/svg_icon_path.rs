@@ -0,0 +1,143 @@
+//! The single-path icons: the SVGs whose only shape is a filled `<path>`, once the `translate` of its groups is
+//! flattened into the path data. Their viewBox and path data are enough to render them (e.g., the icon components
+//! rendering `<path d>` directly, without a sprite, see `ExportOptions::paths_ts_out`).
+
+use crate::support::path_data;
+use xmltree::{Element, XMLNode};
+
+/// The elements that do not render (skipped with their content).
+const NON_RENDERING_TAGS: &[&str] = &["title", "desc", "metadata", "defs"];
+
+/// The attributes an icon rendered from its path data alone would lose.
+const UNSUPPORTED_ATTRS: &[&str] = &["mask", "clip-path", "filter", "style"];
+
+/// The path of a single-path icon (see `single_path_icon`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct IconPath {
+	pub view_box: String,
+	/// The path data, the translations of the groups applied.
+	pub path: String,
+	/// "evenodd" when the path is filled with this rule (the default "nonzero" is None).
+	pub fill_rule: Option<String>,
+}
+
+/// Returns the path of an SVG document made of a single filled `<path>`, in groups with at most a `translate`.
+/// Returns None for the other icons (several shapes, strokes, gradients, masks, other transforms, no viewBox),
+/// or when the content is invalid.
+pub fn single_path_icon(svg_content: &str) -> Option<IconPath> {
+	let root = Element::parse(svg_content.as_bytes()).ok()?;
+	let view_box = root
+		.attributes
+		.get("viewBox")
+		.map(|value| value.trim())
+		.filter(|value| !value.is_empty())?;
+
+	let mut found: Option<FoundPath> = None;
+	let paint = Paint::default().inherit(&root)?;
+	collect_path(&root, (0.0, 0.0), &paint, &mut found)?;
+	let found = found?;
+
+	if found.paint.stroke.as_deref().is_some_and(|stroke| stroke != "none")
+		|| found
+			.paint
+			.fill
+			.as_deref()
+			.is_some_and(|fill| fill == "none" || fill.starts_with("url("))
+	{
+		return None;
+	}
+
+	let (dx, dy) = found.offset;
+	let path = path_data::translate_path_data(&found.path_data, dx, dy)?;
+
+	Some(IconPath {
+		view_box: view_box.to_string(),
+		path,
+		fill_rule: found.paint.fill_rule.filter(|rule| rule == "evenodd"),
+	})
+}
+
+// region:    --- Support
+
+struct FoundPath {
+	path_data: String,
+	offset: (f64, f64),
+	paint: Paint,
+}
+
+/// The paint properties inherited by the path (the nearest value set wins).
+#[derive(Debug, Clone, Default)]
+struct Paint {
+	fill: Option<String>,
+	stroke: Option<String>,
+	fill_rule: Option<String>,
+}
+
+impl Paint {
+	/// Returns the paint of an element, None when it has an unsupported attribute.
+	fn inherit(&self, element: &Element) -> Option<Paint> {
+		if UNSUPPORTED_ATTRS.iter().any(|name| element.attributes.contains_key(*name)) {
+			return None;
+		}
+		let value = |name: &str, inherited: &Option<String>| {
+			element
+				.attributes
+				.get(name)
+				.map(|value| value.trim().to_string())
+				.or_else(|| inherited.clone())
+		};
+		Some(Paint {
+			fill: value("fill", &self.fill),
+			stroke: value("stroke", &self.stroke),
+			fill_rule: value("fill-rule", &self.fill_rule),
+		})
+	}
+}
+
+/// Finds the only shape of an element, a `<path>`, with the offset of its groups.
+/// Returns None when the element cannot be a single-path icon (another shape, a second path, a transform).
+fn collect_path(element: &Element, offset: (f64, f64), paint: &Paint, found: &mut Option<FoundPath>) -> Option<()> {
+	for child in element.children.iter().filter_map(XMLNode::as_element) {
+		if NON_RENDERING_TAGS.contains(&child.name.as_str()) {
+			continue;
+		}
+		let paint = paint.inherit(child)?;
+		let (dx, dy) = match child.attributes.get("transform") {
+			Some(transform) => parse_translate(transform)?,
+			None => (0.0, 0.0),
+		};
+		let offset = (offset.0 + dx, offset.1 + dy);
+		match child.name.as_str() {
+			"g" => collect_path(child, offset, &paint, found)?,
+			"path" if found.is_none() => {
+				let path_data = child.attributes.get("d")?.trim().to_string();
+				*found = Some(FoundPath { path_data, offset, paint });
+			}
+			_ => return None,
+		}
+	}
+	Some(())
+}
+
+/// Returns the offset of a `transform` made of translations (e.g., "translate(-120.000000, -40.000000)").
+fn parse_translate(transform: &str) -> Option<(f64, f64)> {
+	let mut offset = (0.0, 0.0);
+	let mut rest = transform.trim();
+	while !rest.is_empty() {
+		let args = rest.strip_prefix("translate")?.trim_start().strip_prefix('(')?;
+		let (args, after) = args.split_once(')')?;
+		let mut numbers = args
+			.split(|c: char| c == ',' || c.is_ascii_whitespace())
+			.filter(|s| !s.is_empty());
+		let x: f64 = numbers.next()?.parse().ok()?;
+		let y: f64 = numbers.next().map(|y| y.parse().ok()).unwrap_or(Some(0.0))?;
+		if numbers.next().is_some() {
+			return None;
+		}
+		offset = (offset.0 + x, offset.1 + y);
+		rest = after.trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+	}
+	Some(offset)
+}
+
+// endregion: --- Support
@@ -0,0 +1,107 @@
+//! The references of an SVG to local files: the `href` (or `xlink:href`) values that are filesystem paths, e.g., the
+//! image fills Sketch exports with the path of the image on the designer's machine. They leak internal paths (user
+//! names, project directories) into the shipped assets, and render nowhere else (see `ExportOptions::local_refs`).
+
+use crate::handlers::svg::file_data_uri;
+use crate::support::{strings, xmls_stream};
+use crate::{Error, Result};
+use simple_fs::SPath;
+use std::collections::{HashMap, HashSet};
+
+/// The path prefixes of the user, volume, and temp directories (macOS, Linux), besides the `file:` URLs
+/// and the Windows paths. Other absolute paths (e.g., "/images/logo.png") are site-root URLs.
+const LOCAL_PATH_PREFIXES: &[&str] = &[
+	"/Users/",
+	"/home/",
+	"/root/",
+	"/Volumes/",
+	"/private/",
+	"/var/folders/",
+	"/tmp/",
+	"/mnt/",
+	"~/",
+];
+
+/// Returns true when a reference is a local file path: a `file:` URL, a path in a user, volume, or temp directory
+/// (e.g., "/Users/jen/Dropbox/hero.png", "~/hero.png"), or a Windows path (e.g., "C:\Users\jen\hero.png").
+pub fn is_local_reference(value: &str) -> bool {
+	let value = value.trim();
+	let bytes = value.as_bytes();
+	let is_windows_path =
+		(bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && matches!(bytes[2], b'\\' | b'/'))
+			|| value.starts_with("\\\\");
+
+	strip_file_scheme(value).is_some()
+		|| LOCAL_PATH_PREFIXES.iter().any(|prefix| value.starts_with(prefix))
+		|| is_windows_path
+}
+
+/// Returns the local references of an SVG document, in document order (without duplicates).
+/// Returns None when the content is invalid.
+pub fn find_local_references(svg_content: &str) -> Option<Vec<String>> {
+	let mut references = xmls_stream::find_attribute_values(svg_content, is_local_reference_attr)?;
+	let mut seen = HashSet::new();
+	references.retain(|reference| seen.insert(reference.clone()));
+	Some(references)
+}
+
+/// Removes the reference attributes with a local path (e.g., an `<image>` then renders nothing).
+/// Returns None when the content is invalid.
+pub fn strip_local_references(svg_content: &str) -> Option<String> {
+	xmls_stream::remove_attributes(svg_content, is_local_reference_attr)
+}
+
+/// Replaces the local references with `data:` URIs of the files (base64), so the SVG is self-contained.
+/// Fails when a referenced file cannot be read (e.g., the export does not run on the designer's machine).
+pub fn embed_local_references(svg_content: &str) -> Result<String> {
+	let references = find_local_references(svg_content).ok_or("Invalid SVG content")?;
+	if references.is_empty() {
+		return Ok(svg_content.to_string());
+	}
+
+	let mut data_uris: HashMap<String, String> = HashMap::new();
+	for reference in references {
+		let path = local_reference_path(&reference).ok_or_else(|| format!("Invalid local reference '{reference}'"))?;
+		let (data_uri, _) = file_data_uri(&SPath::new(path))?;
+		data_uris.insert(reference, data_uri);
+	}
+
+	xmls_stream::transform_attributes(svg_content, &["href", "xlink:href"], |value| {
+		data_uris.get(value).cloned().unwrap_or_else(|| value.to_string())
+	})
+	.ok_or_else(|| Error::custom("Invalid SVG content"))
+}
+
+/// Returns the file path of a local reference (the `file:` URLs decoded, `~` the home directory).
+pub fn local_reference_path(reference: &str) -> Option<String> {
+	let reference = reference.trim();
+	if let Some(url_path) = strip_file_scheme(reference) {
+		// file:///Users/jen/a.png or file://localhost/Users/jen/a.png ("+" is not a space in a path)
+		let url_path = match url_path.strip_prefix("//") {
+			Some(rest) => &rest[rest.find('/')?..],
+			None => url_path,
+		};
+		return strings::percent_decode(&url_path.replace('+', "%2B"));
+	}
+	if let Some(rest) = reference.strip_prefix("~/") {
+		let home = std::env::var("HOME").ok()?;
+		return Some(format!("{home}/{rest}"));
+	}
+	Some(reference.to_string())
+}
+
+// region:    --- Support
+
+fn is_local_reference_attr(name: &str, value: &str) -> bool {
+	(name == "href" || name == "xlink:href") && is_local_reference(value)
+}
+
+/// Returns the rest of a `file:` URL (the scheme case insensitive).
+fn strip_file_scheme(value: &str) -> Option<&str> {
+	value
+		.get(..5)
+		.filter(|scheme| scheme.eq_ignore_ascii_case("file:"))
+		.map(|_| &value[5..])
+}
+
+// endregion: --- Support
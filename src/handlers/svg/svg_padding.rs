@@ -0,0 +1,49 @@
+use crate::support::{strings, xmls_stream};
+
+/// Pads an SVG document by `padding` px on each side: the root `viewBox` grows around the content
+/// (by the padding in viewBox units, from the px size), and the px `width` and `height` grow by twice the padding.
+/// Returns None when the root has no valid `viewBox`, or the content is invalid.
+pub fn pad_svg(svg_content: &str, padding: f64) -> Option<String> {
+	let view_box = xmls_stream::extract_root_attribute(svg_content, "viewBox")?;
+	let [x, y, vb_width, vb_height] = parse_view_box(&view_box)?;
+
+	let width = px_length(xmls_stream::extract_root_attribute(svg_content, "width").as_deref());
+	let height = px_length(xmls_stream::extract_root_attribute(svg_content, "height").as_deref());
+	let pad_x = padding * vb_width / width.filter(|w| *w > 0.0).unwrap_or(vb_width);
+	let pad_y = padding * vb_height / height.filter(|h| *h > 0.0).unwrap_or(vb_height);
+
+	let number = |value: f64| strings::format_svg_number(value, Some(4));
+	let padded_view_box = format!(
+		"{} {} {} {}",
+		number(x - pad_x),
+		number(y - pad_y),
+		number(vb_width + 2.0 * pad_x),
+		number(vb_height + 2.0 * pad_y)
+	);
+
+	xmls_stream::rewrite_root_attributes(svg_content, |name, value| match name {
+		"viewBox" => Some(padded_view_box.clone()),
+		"width" | "height" => {
+			let length = px_length(Some(value))?;
+			let suffix = if value.trim().ends_with("px") { "px" } else { "" };
+			Some(format!("{}{suffix}", number(length + 2.0 * padding)))
+		}
+		_ => None,
+	})
+}
+
+/// Parses a `viewBox` value (`min-x min-y width height`, space or comma separated).
+fn parse_view_box(view_box: &str) -> Option<[f64; 4]> {
+	let values: Vec<f64> = view_box
+		.split(|c: char| c.is_whitespace() || c == ',')
+		.filter(|s| !s.is_empty())
+		.map(|s| s.parse().ok())
+		.collect::<Option<_>>()?;
+	values.try_into().ok()
+}
+
+/// Parses a px length (e.g., "24", "24px"). Other units (e.g., "100%", "2em") are None.
+fn px_length(value: Option<&str>) -> Option<f64> {
+	let value = value?.trim();
+	value.strip_suffix("px").unwrap_or(value).trim().parse().ok()
+}
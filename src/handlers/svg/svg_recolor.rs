@@ -0,0 +1,46 @@
+use crate::support::xmls_stream;
+
+/// The paint attributes (and `style` properties) set to the color by `recolor_svg`.
+const PAINT_PROPERTIES: &[&str] = &["fill", "stroke", "stop-color", "flood-color", "lighting-color", "color"];
+
+/// The paint values kept by `recolor_svg` (the references, e.g., "url(#gradient)", are kept too).
+const KEPT_PAINTS: &[&str] = &["none", "transparent", "inherit", "currentcolor"];
+
+/// Recolors an SVG document to a single color (e.g., the monochrome variant of a logo): the paints of the
+/// `fill`, `stroke`, `stop-color`, ... attributes and `style` properties become the color (but "none" and the
+/// references, the gradients being recolored by their stops), and the root element gets a `fill` for the shapes
+/// without one (black by default). Returns None when the content is invalid.
+pub fn recolor_svg(svg_content: &str, color: &str) -> Option<String> {
+	let recolored =
+		xmls_stream::transform_attributes(svg_content, PAINT_PROPERTIES, |value| recolor_paint(value, color))?;
+	let recolored = xmls_stream::transform_attributes(&recolored, &["style"], |style| recolor_style(style, color))?;
+	xmls_stream::insert_attribute(&recolored, "fill", color, |path| path.len() == 1)
+}
+
+/// Returns the color, or the paint when kept (e.g., "none", "url(#gradient)").
+fn recolor_paint(paint: &str, color: &str) -> String {
+	let paint_lower = paint.trim().to_ascii_lowercase();
+	if KEPT_PAINTS.contains(&paint_lower.as_str()) || paint_lower.starts_with("url(") {
+		paint.to_string()
+	} else {
+		color.to_string()
+	}
+}
+
+/// Returns the `style` with its paint properties recolored (see `recolor_paint`).
+fn recolor_style(style: &str, color: &str) -> String {
+	style
+		.split(';')
+		.map(|declaration| match declaration.split_once(':') {
+			Some((property, value)) if PAINT_PROPERTIES.contains(&property.trim()) => {
+				let important = value.contains("!important");
+				let value = value.replace("!important", "");
+				let recolored = recolor_paint(&value, color);
+				let important = if important { " !important" } else { "" };
+				format!("{property}:{recolored}{important}")
+			}
+			_ => declaration.to_string(),
+		})
+		.collect::<Vec<_>>()
+		.join(";")
+}
@@ -0,0 +1,368 @@
+use crate::support::{files, hashes, strings, xmls, xmls_stream};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use simple_fs::{SPath, ensure_dir, read_to_string};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write as _};
+
+/// Symbol attribute holding the original artboard name (see `ExportOptions::symbol_data_attrs`).
+pub const DATA_NAME_ATTR: &str = "data-name";
+
+/// Symbol attribute holding the original artboard page name.
+pub const DATA_PAGE_ATTR: &str = "data-page";
+
+/// A `<symbol>` element of an SVG sprite file.
+/// The markup is kept exactly as written in the sprite, so it can be re-emitted without reformatting.
+#[derive(Debug, Clone)]
+pub struct SpriteSymbol {
+	pub id: String,
+	/// The symbol attributes (unescaped), in source order.
+	pub attributes: Vec<(String, String)>,
+	/// The full `<symbol>...</symbol>` markup as written in the sprite, prefixed with its line indentation.
+	pub markup: String,
+}
+
+impl SpriteSymbol {
+	/// Returns the value of a symbol attribute.
+	pub fn attribute(&self, name: &str) -> Option<&str> {
+		self.attributes
+			.iter()
+			.find(|(attr_name, _)| attr_name == name)
+			.map(|(_, value)| value.as_str())
+	}
+
+	/// Returns the inner content of the symbol, as written in the sprite.
+	pub fn inner_content(&self) -> &str {
+		xmls_stream::extract_root_inner_content(&self.markup).unwrap_or_default()
+	}
+
+	/// Returns the symbol markup, indented to be placed inside the sprite root `<svg>`.
+	pub fn to_markup(&self) -> String {
+		indent_content(&self.markup, 2)
+	}
+
+	/// Returns a stable hash of the symbol content, ignoring its `id`, its name annotations, and formatting.
+	pub fn content_hash(&self) -> String {
+		let attrs: String = self
+			.attributes
+			.iter()
+			.filter(|(name, _)| !matches!(name.as_str(), "id" | DATA_NAME_ATTR | DATA_PAGE_ATTR))
+			.map(|(name, value)| format!(" {name}={value:?}"))
+			.collect();
+		let content = format!("{attrs}>{}", xmls::normalize_markup(self.inner_content()));
+		hashes::fnv1a_64_hex(content)
+	}
+}
+
+/// An SVG sprite file: its root namespace declarations and its `<symbol>` elements.
+#[derive(Debug, Clone)]
+pub struct Sprite {
+	/// The root `<svg>` namespace declarations, as `(attribute_name, uri)`.
+	pub namespaces: Vec<(String, String)>,
+	pub symbols: Vec<SpriteSymbol>,
+}
+
+/// Loads an SVG sprite file.
+pub fn load_sprite(sprite_file: &SPath) -> Result<Sprite> {
+	files::check_file_exists(sprite_file)?;
+
+	let content = read_to_string(sprite_file.as_std_path()).map_err(Error::custom_from_err)?;
+
+	parse_sprite(&content).map_err(|err| Error::custom(format!("Invalid sprite file '{sprite_file}'. {err}")))
+}
+
+/// Parses an SVG sprite content (the `<symbol>` elements are the direct children of the root `<svg>`).
+/// Symbols without an `id` attribute are rejected, since they cannot be referenced.
+pub fn parse_sprite(sprite_content: &str) -> Result<Sprite> {
+	let namespaces = xmls_stream::extract_root_namespace_declarations(sprite_content).ok_or("Cannot parse SVG")?;
+	let elements = xmls_stream::extract_root_child_elements(sprite_content, "symbol").ok_or("Cannot parse SVG")?;
+
+	let mut symbols = Vec::new();
+	for element in elements {
+		let id = element
+			.attributes
+			.iter()
+			.find(|(name, _)| name == "id")
+			.map(|(_, value)| value.clone())
+			.ok_or("Found a <symbol> without an 'id' attribute")?;
+		symbols.push(SpriteSymbol {
+			id,
+			attributes: element.attributes,
+			markup: format!("{}{}", element.line_indent, element.markup),
+		});
+	}
+
+	Ok(Sprite { namespaces, symbols })
+}
+
+/// Converts an SVG file content to a symbol element.
+/// The `extra_attrs` are added to the `<symbol>` element after `id` and `viewBox`.
+/// The inner content is streamed from the source, so only the rewritten ids differ from the exported SVG.
+pub fn convert_svg_to_symbol(
+	svg_content: &str,
+	symbol_id: &str,
+	extra_attrs: &[(&str, &str)],
+	strip_namespaces: &[&str],
+) -> Option<ConvertedSymbol> {
+	// Extract viewBox from the SVG
+	let viewbox = xmls_stream::extract_root_attribute(svg_content, "viewBox")?;
+
+	// Extract the inner content (everything between <svg ...> and </svg>), as written
+	let inner_content = xmls_stream::extract_root_inner_content(svg_content)?;
+
+	// If no inner content, return None to signal an error
+	if inner_content.trim().is_empty() {
+		return None;
+	}
+
+	// Canonicalize all id attributes within the inner content, along with their references (url(#..), href="#..")
+	let inner_content = xmls_stream::transform_id_references(inner_content, strings::canonicalize_name)?;
+
+	// Strip the unwanted namespaces (e.g., "sketch"), then keep the declarations the content still needs
+	let inner_content = xmls_stream::strip_namespaces(&inner_content, strip_namespaces)?;
+	let root_declarations = xmls_stream::extract_root_namespace_declarations(svg_content)?;
+	let namespaces = xmls_stream::needed_namespace_declarations(&root_declarations, &inner_content)
+		.into_iter()
+		.filter(|(name, _)| {
+			!name
+				.strip_prefix("xmlns:")
+				.is_some_and(|prefix| strip_namespaces.contains(&prefix))
+		})
+		.collect();
+
+	// Indent the inner content for proper formatting
+	let indented_content = indent_content(inner_content.trim_start_matches(['\n', '\r']).trim_end(), 4);
+
+	// Final check: if indented content is empty, something went wrong
+	if indented_content.trim().is_empty() {
+		return None;
+	}
+
+	let extra_attrs: String = extra_attrs
+		.iter()
+		.map(|(name, value)| format!(r#" {name}="{}""#, xmls::escape_attribute_value(value)))
+		.collect();
+
+	let markup = format!(
+		r#"  <symbol id="{symbol_id}" viewBox="{viewbox}"{extra_attrs}>
+{indented_content}
+  </symbol>"#
+	);
+
+	Some(ConvertedSymbol { markup, namespaces })
+}
+
+/// Renames the ids of an SVG that are (once canonicalized, see `convert_svg_to_symbol`) the id of a symbol of the
+/// sprite, as `<symbol_id>-<id>`, with their references, so the sprite has no duplicate ids. E.g., the
+/// `<g id="ico/user/fill">` of a Sketch export, in the symbol "ico-user-fill", or in "ico-user-fill-2" when renamed.
+/// Returns None if the content is invalid.
+pub fn scope_symbol_ids(svg_content: &str, symbol_id: &str, symbol_ids: &HashSet<String>) -> Option<String> {
+	xmls_stream::transform_id_references(svg_content, |id| {
+		let canonical_id = strings::canonicalize_name(id);
+		if symbol_ids.contains(&canonical_id) { format!("{symbol_id}-{canonical_id}") } else { id.to_string() }
+	})
+}
+
+/// A `<symbol>` converted from an exported SVG.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConvertedSymbol {
+	pub markup: String,
+	/// The namespace declarations the symbol content needs on the sprite root (`(attribute_name, uri)`).
+	pub namespaces: Vec<(String, String)>,
+}
+
+/// Builds the combined SVG symbols file.
+/// The `namespaces` (`(attribute_name, uri)` declarations, e.g., `xmlns:xlink`) are declared on the root `<svg>`.
+/// See `SpriteWriter` to write a sprite without holding all its symbols in memory.
+pub fn build_svg_symbols_file(symbols: &[String], namespaces: &[(String, String)]) -> String {
+	let mut result = String::new();
+	result.push_str(&sprite_open_tag(namespaces));
+
+	for (idx, symbol) in symbols.iter().enumerate() {
+		// Add empty line before symbols, except for the first one
+		if idx > 0 {
+			result.push('\n');
+		}
+		result.push_str(symbol);
+		result.push('\n');
+	}
+
+	result.push_str(SPRITE_CLOSE_TAG);
+	result
+}
+
+/// The closing tag of a sprite file (with its final newline).
+const SPRITE_CLOSE_TAG: &str = "</svg>\n";
+
+/// Returns the root `<svg>` opening tag of a sprite file (with its newline), declaring the namespaces.
+fn sprite_open_tag(namespaces: &[(String, String)]) -> String {
+	let declarations: String = namespaces
+		.iter()
+		.map(|(name, uri)| format!(r#" {name}="{}""#, xmls::escape_attribute_value(uri)))
+		.collect();
+	format!("<svg{declarations} width=\"0\" height=\"0\" style=\"position:absolute\">\n")
+}
+
+/// Writes a sprite file symbol by symbol, so only the symbol being converted is in memory
+/// (same content as `build_svg_symbols_file`).
+///
+/// Note: The root `<svg>` declares the namespaces of all the symbols, only known after the last one,
+///       so the symbols are streamed to a temporary file next to the target, copied after the root tag by `finish`.
+///       The target is untouched until `finish`, and the temporary file is removed on drop.
+pub struct SpriteWriter {
+	target_file: SPath,
+	body_file: SPath,
+	body: BufWriter<File>,
+	namespaces: Vec<(String, String)>,
+	symbol_count: usize,
+}
+
+impl SpriteWriter {
+	/// Starts a sprite file (creating its parent directory).
+	pub fn create(target_file: &SPath) -> Result<Self> {
+		let parent = target_file.parent().unwrap_or_else(|| SPath::new("."));
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+
+		let body_file = parent.join(format!(".{}.symbols.tmp", target_file.name()));
+		let body = File::create(body_file.as_std_path()).map_err(|e| format!("Failed to create '{body_file}': {e}"))?;
+
+		Ok(Self {
+			target_file: target_file.clone(),
+			body_file,
+			body: BufWriter::new(body),
+			namespaces: Vec::new(),
+			symbol_count: 0,
+		})
+	}
+
+	/// Appends a symbol markup, with the namespace declarations it needs on the root `<svg>`.
+	pub fn write_symbol(&mut self, markup: &str, namespaces: Vec<(String, String)>) -> Result<()> {
+		// Empty line before symbols, except for the first one
+		let separator = if self.symbol_count > 0 { "\n" } else { "" };
+		writeln!(self.body, "{separator}{markup}").map_err(|e| format!("Failed to write '{}': {e}", self.body_file))?;
+		merge_namespace_declarations(&mut self.namespaces, namespaces);
+		self.symbol_count += 1;
+		Ok(())
+	}
+
+	/// Writes the sprite file: the root tag, the symbols, and the closing tag.
+	pub fn finish(mut self) -> Result<()> {
+		self.body
+			.flush()
+			.map_err(|e| format!("Failed to write '{}': {e}", self.body_file))?;
+
+		let write_err = |e: std::io::Error| format!("Failed to write symbols file '{}': {e}", self.target_file);
+		let target = File::create(self.target_file.as_std_path()).map_err(write_err)?;
+		let mut target = BufWriter::new(target);
+		target
+			.write_all(sprite_open_tag(&self.namespaces).as_bytes())
+			.map_err(write_err)?;
+		let mut body = File::open(self.body_file.as_std_path())
+			.map_err(|e| format!("Failed to read '{}': {e}", self.body_file))?;
+		std::io::copy(&mut body, &mut target).map_err(write_err)?;
+		target.write_all(SPRITE_CLOSE_TAG.as_bytes()).map_err(write_err)?;
+		target.flush().map_err(write_err)?;
+
+		Ok(())
+	}
+}
+
+impl Drop for SpriteWriter {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_file(self.body_file.as_std_path());
+	}
+}
+
+/// Adds namespace declarations to a list, skipping the ones already declared (first declaration wins).
+pub fn merge_namespace_declarations(target: &mut Vec<(String, String)>, declarations: Vec<(String, String)>) {
+	for (name, uri) in declarations {
+		if !target.iter().any(|(existing, _)| *existing == name) {
+			target.push((name, uri));
+		}
+	}
+}
+
+/// Indents each line of content by the specified number of spaces.
+/// First removes common leading whitespace, then applies the new base indentation
+/// while preserving relative indentation between lines.
+pub fn indent_content(content: &str, base_spaces: usize) -> String {
+	if content.is_empty() {
+		return String::new();
+	}
+
+	// Find the minimum indentation among non-empty lines
+	let min_indent = content
+		.lines()
+		.filter(|line| !line.trim().is_empty())
+		.map(|line| line.len() - line.trim_start().len())
+		.min()
+		.unwrap_or(0);
+
+	// Written into a single buffer (no String per line), as illustrations can have many thousand lines
+	let mut result = String::with_capacity(content.len() + content.len() / 8);
+	for (idx, line) in content.lines().enumerate() {
+		if idx > 0 {
+			result.push('\n');
+		}
+		let trimmed = line.trim_start();
+		if trimmed.is_empty() {
+			continue;
+		}
+		// This line's indentation relative to min_indent
+		let relative_indent = (line.len() - trimmed.len()).saturating_sub(min_indent);
+		result.extend(std::iter::repeat_n(' ', base_spaces + relative_indent));
+		result.push_str(trimmed);
+	}
+	result
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_svg_sprite_scope_symbol_ids_no_duplicate_ids() -> Result<()> {
+		// -- Setup & Fixtures
+		// Sketch-like exports: the artboard group named like the artboard, "ico/user/fill" renamed "ico-user-fill-2"
+		let artboards = [("ico-user-fill", "ico/user-fill"), ("ico-user-fill-2", "ico/user/fill")];
+		let symbol_ids: HashSet<String> = artboards.iter().map(|(symbol_id, _)| symbol_id.to_string()).collect();
+
+		// -- Exec
+		let mut symbols = Vec::new();
+		for (symbol_id, name) in artboards {
+			let svg_content = format!(
+				r##"<svg viewBox="0 0 16 16" xmlns="http://www.w3.org/2000/svg"><g id="{name}" fill="url(#{name})"><path d="M0 0"/></g></svg>"##
+			);
+			let svg_content = scope_symbol_ids(&svg_content, symbol_id, &symbol_ids).ok_or("invalid SVG")?;
+			symbols.push(
+				convert_svg_to_symbol(&svg_content, symbol_id, &[], &[])
+					.ok_or("invalid SVG")?
+					.markup,
+			);
+		}
+		let sprite = build_svg_symbols_file(&symbols, &[]);
+
+		// -- Check
+		let ids: Vec<&str> = sprite
+			.split(r#" id=""#)
+			.skip(1)
+			.filter_map(|rest| rest.split('"').next())
+			.collect();
+		let unique_ids: HashSet<&str> = ids.iter().copied().collect();
+		assert_eq!(ids.len(), 4);
+		assert_eq!(unique_ids.len(), ids.len(), "duplicate ids in: {ids:?}");
+		assert!(
+			sprite.contains(r#"<g id="ico-user-fill-2-ico-user-fill" fill="url(#ico-user-fill-2-ico-user-fill)">"#)
+		);
+		assert!(sprite.contains(r#"<g id="ico-user-fill-ico-user-fill" fill="url(#ico-user-fill-ico-user-fill)">"#));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,46 @@
+use crate::support::xmls_stream::{self, PathElement};
+use std::borrow::Cow;
+
+/// The attribute set by `add_non_scaling_stroke`.
+const VECTOR_EFFECT_ATTR: &str = "vector-effect";
+
+/// The SVG elements a stroke is drawn on.
+const STROKABLE_TAGS: &[&str] = &["path", "rect", "circle", "ellipse", "line", "polyline", "polygon", "text"];
+
+/// Sets `vector-effect="non-scaling-stroke"` on the stroked shapes of an SVG document, so their strokes keep
+/// their width at any rendered size. The stroke is resolved like the browsers do (inherited from the ancestors,
+/// the `style` property over the attribute), and the elements with a `vector-effect` are kept as is.
+/// Returns None when the content is invalid.
+pub fn add_non_scaling_stroke(svg_content: &str) -> Option<String> {
+	xmls_stream::insert_attribute(svg_content, VECTOR_EFFECT_ATTR, "non-scaling-stroke", |path| {
+		let is_strokable = path.last().is_some_and(|(name, _)| STROKABLE_TAGS.contains(&local_name(name)));
+		is_strokable && is_stroked(path)
+	})
+}
+
+/// Returns true when the stroke of the last element of the path is set and not "none".
+fn is_stroked(path: &[PathElement<'_>]) -> bool {
+	path.iter()
+		.rev()
+		.find_map(|(_, attributes)| stroke_value(attributes))
+		.is_some_and(|stroke| stroke != "none")
+}
+
+/// Returns the stroke set by an element (its `style` property, otherwise its attribute), None when inherited.
+fn stroke_value<'a>(attributes: &'a [(&str, Cow<'_, str>)]) -> Option<&'a str> {
+	let attribute = |name: &str| attributes.iter().find(|(n, _)| *n == name).map(|(_, value)| value.as_ref());
+	let style_stroke = attribute("style").and_then(|style| {
+		style.split(';').find_map(|declaration| {
+			let (property, value) = declaration.split_once(':')?;
+			(property.trim() == "stroke").then(|| value.trim().trim_end_matches("!important").trim())
+		})
+	});
+	style_stroke
+		.or_else(|| attribute("stroke").map(str::trim))
+		.filter(|stroke| *stroke != "inherit")
+}
+
+/// Returns the name without its namespace prefix (e.g., "svg:path" -> "path").
+fn local_name(name: &str) -> &str {
+	name.rsplit_once(':').map(|(_, local)| local).unwrap_or(name)
+}
@@ -0,0 +1,76 @@
+use crate::handlers::svg::{self, DATA_NAME_ATTR, DATA_PAGE_ATTR, SpriteSymbol};
+use crate::support::{files, xmls, xmls_stream};
+use crate::{Error, Result};
+use simple_fs::{SPath, ensure_dir};
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+/// Splits an SVG sprite file into standalone SVG files (one per `<symbol>`).
+/// Each file is named after the symbol `data-name` (original artboard name) when present,
+/// otherwise after the symbol id (sanitized, see `files::sanitize_file_path`), and its root `<svg>` gets the symbol `viewBox`.
+/// Returns the list of written file paths.
+pub fn unsprite(sprite_file: impl AsRef<SPath>, output_dir: impl AsRef<SPath>) -> Result<Vec<String>> {
+	let sprite_file = sprite_file.as_ref();
+	let output_dir = output_dir.as_ref();
+
+	let sprite = svg::load_sprite(sprite_file)?;
+
+	ensure_dir(output_dir.as_std_path())
+		.map_err(|e| format!("Failed to create output directory '{}': {e}", output_dir))?;
+
+	let mut written = Vec::new();
+	for symbol in &sprite.symbols {
+		let name = symbol.attribute(DATA_NAME_ATTR).unwrap_or(&symbol.id);
+		let target_path = output_dir.join(format!("{}.svg", files::sanitize_file_path(name)));
+		let svg_content = symbol_to_svg(symbol, &sprite.namespaces);
+
+		if let Some(parent) = target_path.parent() {
+			ensure_dir(parent.as_std_path())
+				.map_err(|e| format!("Failed to create parent directory '{}': {e}", parent))?;
+		}
+
+		std::fs::write(target_path.as_std_path(), svg_content)
+			.map_err(|e| Error::custom(format!("Failed to write SVG file '{target_path}': {e}")))?;
+
+		written.push(target_path.to_string());
+	}
+
+	Ok(written)
+}
+
+/// Reconstructs a standalone SVG document from a sprite symbol.
+/// The symbol attributes (except `id` and the sprite annotations) are carried to the root `<svg>` element,
+/// along with the sprite namespace declarations the symbol content needs.
+fn symbol_to_svg(symbol: &SpriteSymbol, sprite_namespaces: &[(String, String)]) -> String {
+	let inner_content = symbol.inner_content().trim_start_matches(['\n', '\r']).trim_end();
+
+	let mut namespaces = vec![("xmlns".to_string(), SVG_NAMESPACE.to_string())];
+	svg::merge_namespace_declarations(
+		&mut namespaces,
+		xmls_stream::needed_namespace_declarations(sprite_namespaces, inner_content),
+	);
+	// Sprites written by hand may use xlink without declaring it
+	if xmls_stream::used_namespace_prefixes(inner_content).is_some_and(|prefixes| prefixes.contains("xlink")) {
+		svg::merge_namespace_declarations(
+			&mut namespaces,
+			vec![("xmlns:xlink".to_string(), XLINK_NAMESPACE.to_string())],
+		);
+	}
+
+	let mut root_attrs: String = namespaces
+		.iter()
+		.map(|(name, uri)| format!(r#" {name}="{}""#, xmls::escape_attribute_value(uri)))
+		.collect();
+	for (name, value) in symbol
+		.attributes
+		.iter()
+		.filter(|(name, _)| !matches!(name.as_str(), "id" | DATA_NAME_ATTR | DATA_PAGE_ATTR))
+	{
+		root_attrs.push_str(&format!(r#" {name}="{}""#, xmls::escape_attribute_value(value)));
+	}
+
+	let indented_content = svg::indent_content(inner_content, 2);
+
+	format!("<svg{root_attrs}>\n{indented_content}\n</svg>\n")
+}
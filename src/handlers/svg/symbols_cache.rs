@@ -0,0 +1,83 @@
+//! The cache of the converted symbols of a sprite (see `ExportOptions::symbols_cache`), so a rebuild only converts
+//! the symbols of the changed artboards (e.g., in watch mode).
+//! One JSON file per symbol, named by the hash of the exported SVG and of the conversion parameters,
+//! so only the symbol being converted is in memory.
+
+use crate::handlers::svg::ConvertedSymbol;
+use crate::support::hashes;
+use crate::{Error, Result};
+use simple_fs::{SPath, ensure_dir};
+use std::collections::HashSet;
+
+/// The cache directory, next to the sprite file (`.cache-symbols/<sprite file name>/<key>.json`).
+pub const SYMBOLS_CACHE_DIR: &str = ".cache-symbols";
+
+/// The converted symbols of a sprite file, across its builds.
+pub struct SymbolsCache {
+	dir: SPath,
+	/// The keys read or written by this build (the others are pruned).
+	used_keys: HashSet<String>,
+}
+
+impl SymbolsCache {
+	/// Opens (or creates) the cache of a sprite file.
+	pub fn open(sprite_file: &SPath) -> Result<Self> {
+		let parent = sprite_file.parent().unwrap_or_else(|| SPath::new("."));
+		let dir = parent.join(SYMBOLS_CACHE_DIR).join(sprite_file.name());
+		ensure_dir(dir.as_std_path()).map_err(|e| format!("Failed to create cache directory '{dir}': {e}"))?;
+		Ok(Self { dir, used_keys: HashSet::new() })
+	}
+
+	/// Returns the cached symbol of a key, otherwise converts it with `convert` and caches it.
+	/// An unreadable entry (e.g., from another webtk version) is converted again.
+	pub fn get_or_convert(
+		&mut self,
+		key: &str,
+		convert: impl FnOnce() -> Result<ConvertedSymbol>,
+	) -> Result<ConvertedSymbol> {
+		self.used_keys.insert(key.to_string());
+		let entry_file = self.dir.join(format!("{key}.json"));
+
+		let cached = std::fs::read_to_string(entry_file.as_std_path())
+			.ok()
+			.and_then(|content| serde_json::from_str::<ConvertedSymbol>(&content).ok());
+		if let Some(symbol) = cached {
+			return Ok(symbol);
+		}
+
+		let symbol = convert()?;
+		let content = serde_json::to_string(&symbol)?;
+		std::fs::write(entry_file.as_std_path(), content)
+			.map_err(|e| format!("Failed to write cache file '{entry_file}': {e}"))?;
+		Ok(symbol)
+	}
+
+	/// Removes the entries not used by this build (the symbols of the changed or removed artboards).
+	pub fn prune(&self) -> Result<()> {
+		let entries = std::fs::read_dir(self.dir.as_std_path())
+			.map_err(|e| Error::custom(format!("Cannot list cache directory '{}'. Cause: {e}", self.dir)))?;
+		for entry in entries.flatten() {
+			let path = entry.path();
+			let is_used = path
+				.file_stem()
+				.and_then(|stem| stem.to_str())
+				.is_some_and(|key| self.used_keys.contains(key));
+			if !is_used && path.extension().is_some_and(|ext| ext == "json") {
+				let _ = std::fs::remove_file(&path);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Returns the cache key of a symbol conversion: the hash of the SVG content (as converted, e.g., padded),
+/// of the conversion parameters, and of the webtk version (the conversion may change between versions).
+pub fn symbol_cache_key(
+	svg_content: &str,
+	symbol_id: &str,
+	extra_attrs: &[(&str, &str)],
+	strip_namespaces: &[&str],
+) -> String {
+	let params = format!("{}\n{symbol_id}\n{extra_attrs:?}\n{strip_namespaces:?}\n", env!("CARGO_PKG_VERSION"));
+	hashes::sha256_hex(format!("{params}{svg_content}"))
+}
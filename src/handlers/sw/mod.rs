@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod sw_precache;
+
+pub use sw_precache::*;
+
+// endregion: --- Modules
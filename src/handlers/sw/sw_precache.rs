@@ -0,0 +1,95 @@
+//! The service worker precache manifest of a directory (e.g., the `dist/` of the webtk outputs): the Workbox
+//! manifest format (`[{ "url": "...", "revision": "..." }]`, as `precacheAndRoute` takes it), so the PWAs cache
+//! the assets offline without the Workbox build tools.
+
+use crate::support::{globs, hashes, strings};
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::{ListOptions, SPath};
+
+/// Options for `build_precache_manifest`.
+#[derive(Debug, Clone, Default)]
+pub struct PrecacheOptions {
+	/// The globs of the files to precache, relative to the directory (all the files when empty).
+	pub globs: Vec<String>,
+	/// The globs of the files not to precache (e.g., "**/*.map").
+	pub ignore_globs: Vec<String>,
+	/// The prefix of the URLs (e.g., "/assets/"), the relative paths when None.
+	pub url_prefix: Option<String>,
+}
+
+/// A precached file.
+#[derive(Debug, Serialize)]
+pub struct PrecacheEntry {
+	/// The percent-encoded path relative to the directory, with the URL prefix (e.g., "/assets/ico/user-fill.svg").
+	pub url: String,
+	/// The SHA-256 of the content (the cache key of the file, so a changed file is fetched again).
+	pub revision: String,
+}
+
+/// Returns the precache entries of the files of a directory, sorted by URL.
+/// The hidden files and directories (e.g., ".cache-raw-export/", ".DS_Store") and `exclude_file` (e.g., the manifest
+/// itself when written in the directory) are skipped.
+pub fn build_precache_manifest(
+	dir: impl AsRef<SPath>,
+	options: &PrecacheOptions,
+	exclude_file: Option<&SPath>,
+) -> Result<Vec<PrecacheEntry>> {
+	let dir = dir.as_ref();
+	if !dir.is_dir() {
+		return Err(Error::custom(format!("Precache directory '{dir}' not found")));
+	}
+
+	let include_globs: Vec<&str> = options.globs.iter().map(String::as_str).collect();
+	let include_set = globs::build_glob_set(Some(&include_globs))?;
+	let ignore_globs: Vec<&str> = options.ignore_globs.iter().map(String::as_str).collect();
+	let ignore_set = globs::build_glob_set(Some(&ignore_globs))?;
+	let exclude_file = exclude_file.and_then(|file| std::fs::canonicalize(file.as_std_path()).ok());
+
+	let list_options = ListOptions::from_relative_glob(true);
+	let dir_files = simple_fs::list_files(dir.as_std_path(), Some(&["**/*"]), Some(list_options))
+		.map_err(Error::custom_from_err)?;
+
+	let prefix = options.url_prefix.as_deref().unwrap_or_default();
+	let mut entries = Vec::new();
+	for file in dir_files {
+		let Ok(rel_path) = file.as_std_path().strip_prefix(dir.as_std_path()) else {
+			continue;
+		};
+		let rel_path = rel_path.to_string_lossy().replace('\\', "/");
+		let is_hidden = rel_path.split('/').any(|segment| segment.starts_with('.'));
+		let is_excluded = exclude_file
+			.as_ref()
+			.is_some_and(|exclude| std::fs::canonicalize(file.as_std_path()).is_ok_and(|path| path == *exclude));
+		if is_hidden
+			|| is_excluded
+			|| !globs::matches_glob_set(include_set.as_ref(), &rel_path)
+			|| ignore_set.as_ref().is_some_and(|set| set.is_match(&rel_path))
+		{
+			continue;
+		}
+
+		let content = std::fs::read(file.as_std_path()).map_err(|e| format!("Failed to read '{file}': {e}"))?;
+		entries.push(PrecacheEntry {
+			url: format!("{prefix}{}", strings::percent_encode_path(&rel_path)),
+			revision: hashes::sha256_hex(content),
+		});
+	}
+	entries.sort_by(|a, b| a.url.cmp(&b.url));
+
+	Ok(entries)
+}
+
+/// Writes the precache entries as a JSON array (the Workbox manifest format).
+pub fn write_precache_manifest(entries: &[PrecacheEntry], manifest_file: &SPath) -> Result<()> {
+	if let Some(parent) = manifest_file.parent()
+		&& !parent.as_str().is_empty()
+	{
+		simple_fs::ensure_dir(parent.as_std_path())
+			.map_err(|e| format!("Failed to create directory '{parent}': {e}"))?;
+	}
+	let content = serde_json::to_string_pretty(entries)?;
+	std::fs::write(manifest_file.as_std_path(), format!("{content}\n"))
+		.map_err(|e| Error::custom(format!("Cannot write '{manifest_file}'. Cause: {e}")))?;
+	Ok(())
+}
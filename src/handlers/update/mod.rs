@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod self_update;
+
+pub use self_update::*;
+
+// endregion: --- Modules
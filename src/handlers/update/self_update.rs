@@ -0,0 +1,262 @@
+//! The self-update of the webtk binary from the GitHub releases: the platform binary asset (`webtk-<os>-<arch>`),
+//! verified against the `SHA256SUMS` asset of the release, then swapped in by a rename in the directory of the current
+//! binary (atomic, a running webtk keeps its open binary).
+//!
+//! The checksum comes with the binary from the same release, so it only catches a corrupted download. The publisher
+//! is verified on macOS only, when webtk is built with `WEBTK_SIGNING_TEAM_ID` (the release builds): the code
+//! signature of the binary must then be of this Apple team (see `verify_signature`).
+//!
+//! The binary is downloaded with curl, so it gets no quarantine attribute, and a signed and notarized release binary
+//! stays as notarized (it is not modified).
+
+use crate::handlers::checksums::{self, SHA256SUMS_FILE};
+use crate::support::versions::Version;
+use crate::support::{hashes, http};
+use crate::{Error, Result};
+use serde::Deserialize;
+use simple_fs::SPath;
+
+/// The GitHub releases API of webtk (newest first).
+pub const RELEASES_URL: &str = "https://api.github.com/repos/jeremychone/rust-webtk/releases";
+
+/// The Apple team id (e.g., "A1B2C3D4E5") of the Developer ID certificate signing the macOS release binaries, set when
+/// building them. Without it, the signature of a downloaded binary is not checked.
+const SIGNING_TEAM_ID: Option<&str> = option_env!("WEBTK_SIGNING_TEAM_ID");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseChannel {
+	/// The releases (not the pre-releases).
+	Stable,
+	/// The releases and the pre-releases.
+	Beta,
+}
+
+impl TryFrom<&str> for ReleaseChannel {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"stable" => Ok(Self::Stable),
+			"beta" => Ok(Self::Beta),
+			other => Err(Error::custom(format!("Invalid release channel '{other}'. Expected: stable, beta"))),
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Release {
+	pub tag_name: String,
+	#[serde(default)]
+	pub prerelease: bool,
+	#[serde(default)]
+	pub draft: bool,
+	#[serde(default)]
+	pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReleaseAsset {
+	pub name: String,
+	pub browser_download_url: String,
+}
+
+impl Release {
+	fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+		self.assets.iter().find(|asset| asset.name == name)
+	}
+}
+
+pub struct SelfUpdateOptions {
+	pub channel: ReleaseChannel,
+	/// Only check for a newer version.
+	pub check_only: bool,
+	/// Install the latest release even when it is not newer (e.g., to repair the binary, or to leave the beta channel).
+	pub force: bool,
+	/// The releases API (default `RELEASES_URL`, e.g., a mirror).
+	pub releases_url: String,
+}
+
+#[derive(Debug)]
+pub enum SelfUpdateOutcome {
+	UpToDate {
+		current: Version,
+	},
+	/// A newer version exists (`check_only`).
+	Available {
+		current: Version,
+		latest: Version,
+	},
+	Updated {
+		previous: Version,
+		installed: Version,
+		binary: SPath,
+	},
+}
+
+/// Returns the name of the release asset of the platform binary (e.g., "webtk-macos-aarch64", "webtk-windows-x86_64.exe").
+pub fn platform_asset_name() -> String {
+	let ext = if cfg!(windows) { ".exe" } else { "" };
+	format!("webtk-{}-{}{ext}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Returns the version of the running binary.
+pub fn current_version() -> Result<Version> {
+	let version = env!("CARGO_PKG_VERSION");
+	Version::parse(version).ok_or_else(|| Error::custom(format!("Invalid current version '{version}'")))
+}
+
+/// Fetches the releases (newest first).
+pub fn fetch_releases(releases_url: &str) -> Result<Vec<Release>> {
+	let response = http::get(releases_url, &[("Accept", "application/vnd.github+json"), ("User-Agent", "webtk")])?;
+	if !response.is_success() {
+		return Err(Error::custom(format!("Cannot list the releases of '{releases_url}' (HTTP {})", response.status)));
+	}
+	serde_json::from_str(&response.body)
+		.map_err(|e| Error::custom(format!("Invalid releases response of '{releases_url}'. Cause: {e}")))
+}
+
+/// Returns the latest release of a channel, with its version (the drafts and the unparsable tags are skipped).
+pub fn latest_release(releases: &[Release], channel: ReleaseChannel) -> Option<(&Release, Version)> {
+	releases
+		.iter()
+		.filter(|release| !release.draft && (channel == ReleaseChannel::Beta || !release.prerelease))
+		.filter_map(|release| Version::parse(&release.tag_name).map(|version| (release, version)))
+		.max_by(|(_, a), (_, b)| a.cmp(b))
+}
+
+/// Checks for a newer release of the channel, and installs it unless `check_only`.
+pub fn self_update(options: &SelfUpdateOptions) -> Result<SelfUpdateOutcome> {
+	let current = current_version()?;
+	let releases = fetch_releases(&options.releases_url)?;
+	let (release, latest) = latest_release(&releases, options.channel)
+		.ok_or_else(|| Error::custom(format!("No release found in '{}'", options.releases_url)))?;
+
+	if latest <= current && !options.force {
+		return Ok(SelfUpdateOutcome::UpToDate { current });
+	}
+	if options.check_only {
+		return Ok(SelfUpdateOutcome::Available { current, latest });
+	}
+
+	let asset_name = platform_asset_name();
+	let asset = release.asset(&asset_name).ok_or_else(|| {
+		Error::custom(format!("Release '{}' has no '{asset_name}' binary for this platform", release.tag_name))
+	})?;
+	let sums_asset = release.asset(SHA256SUMS_FILE).ok_or_else(|| {
+		Error::custom(format!("Release '{}' has no {SHA256SUMS_FILE} to verify the binary", release.tag_name))
+	})?;
+
+	let binary = current_binary()?;
+	// The binary moved aside by the previous update on Windows, no longer running
+	let _ = std::fs::remove_file(old_binary_file(&binary).as_std_path());
+	let dir = binary.parent().unwrap_or_else(|| SPath::new("."));
+	let download_file = dir.join(format!(".{asset_name}.download"));
+	let sums_file = dir.join(format!(".{asset_name}.{SHA256SUMS_FILE}"));
+
+	let result = download_and_verify(asset, sums_asset, &download_file, &sums_file)
+		.and_then(|_| replace_binary(&download_file, &binary));
+	let _ = std::fs::remove_file(sums_file.as_std_path());
+	if result.is_err() {
+		let _ = std::fs::remove_file(download_file.as_std_path());
+	}
+	result?;
+
+	Ok(SelfUpdateOutcome::Updated { previous: current, installed: latest, binary })
+}
+
+/// Returns the file where the replaced binary is moved on Windows (e.g., ".webtk.exe.old" next to it), removed by the
+/// next update.
+fn old_binary_file(binary: &SPath) -> SPath {
+	let dir = binary.parent().unwrap_or_else(|| SPath::new("."));
+	dir.join(format!(".{}.old", binary.name()))
+}
+
+/// Returns the path of the running binary (symlinks resolved, e.g., a Homebrew link).
+fn current_binary() -> Result<SPath> {
+	let exe = std::env::current_exe()
+		.and_then(|exe| exe.canonicalize())
+		.map_err(|e| Error::custom(format!("Cannot locate the webtk binary. Cause: {e}")))?;
+	SPath::from_std_path_buf(exe).map_err(Error::custom_from_err)
+}
+
+fn download_and_verify(
+	asset: &ReleaseAsset,
+	sums_asset: &ReleaseAsset,
+	download_file: &SPath,
+	sums_file: &SPath,
+) -> Result<()> {
+	http::download(&sums_asset.browser_download_url, sums_file)?;
+	http::download(&asset.browser_download_url, download_file)?;
+
+	let sums =
+		std::fs::read_to_string(sums_file.as_std_path()).map_err(|e| format!("Failed to read '{sums_file}': {e}"))?;
+	let expected = checksums::parse_sha256_sums(&sums)?
+		.into_iter()
+		.find(|entry| entry.path == asset.name)
+		.map(|entry| entry.hash)
+		.ok_or_else(|| Error::custom(format!("{SHA256SUMS_FILE} of the release has no entry for '{}'", asset.name)))?;
+
+	let content =
+		std::fs::read(download_file.as_std_path()).map_err(|e| format!("Failed to read '{download_file}': {e}"))?;
+	let actual = hashes::sha256_hex(&content);
+	if actual != expected {
+		return Err(Error::custom(format!(
+			"Checksum mismatch of '{}' (expected {expected}, got {actual}). The binary was not installed",
+			asset.name
+		)));
+	}
+
+	verify_signature(download_file)
+}
+
+/// Verifies that the binary is signed by the publisher on macOS, the team of `SIGNING_TEAM_ID`. No-op on the other
+/// platforms, or when webtk was built without it.
+fn verify_signature(file: &SPath) -> Result<()> {
+	let Some(team_id) = SIGNING_TEAM_ID.filter(|_| cfg!(target_os = "macos")) else {
+		return Ok(());
+	};
+	// A plain `--verify` accepts any valid signature (e.g., ad hoc), the requirement pins the Developer ID team
+	let requirement = format!(r#"-R=anchor apple generic and certificate leaf[subject.OU] = "{team_id}""#);
+	let output = std::process::Command::new("codesign")
+		.args(["--verify", "--strict", &requirement, file.as_str()])
+		.output()
+		.map_err(|e| Error::tool_exec("codesign", "codesign", e))?;
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!(
+			"The downloaded binary is not signed by the webtk team ({team_id}): {}",
+			stderr.trim()
+		)));
+	}
+	Ok(())
+}
+
+/// Makes the downloaded file executable and renames it over the binary (same directory, so atomic).
+/// On Windows, where a running executable cannot be replaced but can be renamed, the binary is moved aside first
+/// (see `old_binary_file`).
+fn replace_binary(download_file: &SPath, binary: &SPath) -> Result<()> {
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt as _;
+		std::fs::set_permissions(download_file.as_std_path(), std::fs::Permissions::from_mode(0o755))
+			.map_err(|e| format!("Failed to make '{download_file}' executable: {e}"))?;
+	}
+
+	if cfg!(windows) {
+		let old_binary = old_binary_file(binary);
+		let _ = std::fs::remove_file(old_binary.as_std_path());
+		std::fs::rename(binary.as_std_path(), old_binary.as_std_path()).map_err(|e| {
+			Error::custom(format!("Cannot move '{binary}' aside ({e}). Check the permissions of its directory"))
+		})?;
+		if let Err(err) = std::fs::rename(download_file.as_std_path(), binary.as_std_path()) {
+			let _ = std::fs::rename(old_binary.as_std_path(), binary.as_std_path());
+			return Err(Error::custom(format!("Cannot replace '{binary}' ({err}). The binary was not updated")));
+		}
+		return Ok(());
+	}
+
+	std::fs::rename(download_file.as_std_path(), binary.as_std_path()).map_err(|e| {
+		Error::custom(format!("Cannot replace '{binary}' ({e}). Check the permissions of its directory, or reinstall"))
+	})?;
+	Ok(())
+}
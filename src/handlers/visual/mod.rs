@@ -0,0 +1,7 @@
+// region:    --- Modules
+
+mod visual_test;
+
+pub use visual_test::*;
+
+// endregion: --- Modules
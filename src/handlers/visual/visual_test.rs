@@ -0,0 +1,152 @@
+//! Visual regression testing (`webtk visual-test`): the exported SVGs are rendered with the native rasterizer,
+//! and compared with baseline PNGs (perceptual diff), writing the diff images of the failures.
+
+use crate::handlers::raster;
+use crate::support::files;
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::SPath;
+
+/// The default directory of the diff images.
+pub const DEFAULT_VISUAL_DIFF_DIR: &str = ".visual-diff";
+
+#[derive(Debug, Clone)]
+pub struct VisualTestOptions {
+	/// The baseline PNGs, mirroring the SVG paths (e.g., "ico/user.svg" -> "<baseline_dir>/ico/user.png").
+	pub baseline_dir: SPath,
+	/// Where the diff and actual images of the failures are written.
+	pub diff_dir: SPath,
+	/// The ratio of differing pixels (0 to 1) above which an SVG fails.
+	pub threshold: f64,
+	/// The color distance (0 to 1) above which a pixel differs (see `raster::diff_images`).
+	pub pixel_threshold: f64,
+	/// The render scale (e.g., 2 for the @2x baselines).
+	pub scale: f32,
+	/// Write the baselines of the missing and failed SVGs instead of failing.
+	pub update: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VisualStatus {
+	Pass,
+	Fail,
+	/// No baseline (a failure, unless updating).
+	Missing,
+	/// The baseline was written (`update`).
+	Updated,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VisualResult {
+	pub svg_file: String,
+	pub baseline_file: String,
+	pub status: VisualStatus,
+	/// The ratio of differing pixels, when compared.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub diff_ratio: Option<f64>,
+	/// The diff image (failures only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub diff_file: Option<String>,
+	/// Why it failed, when not a pixel difference (e.g., sizes differ).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub message: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct VisualTestReport {
+	pub results: Vec<VisualResult>,
+}
+
+impl VisualTestReport {
+	pub fn failed_count(&self) -> usize {
+		self.results
+			.iter()
+			.filter(|r| matches!(r.status, VisualStatus::Fail | VisualStatus::Missing))
+			.count()
+	}
+}
+
+/// Renders the SVG files of `input` (a directory, recursively, or an SVG file) and compares them with their baselines.
+/// The failures get `<diff_dir>/<path>.diff.png` (differing pixels in red) and `<path>.actual.png` (the render).
+pub fn visual_test(input: &SPath, options: &VisualTestOptions) -> Result<VisualTestReport> {
+	let svg_files: Vec<(SPath, String)> = if input.is_dir() {
+		let files =
+			simple_fs::list_files(input.as_std_path(), Some(&["**/*.svg"]), None).map_err(Error::custom_from_err)?;
+		let mut svg_files: Vec<(SPath, String)> = files
+			.into_iter()
+			.map(|file| {
+				let relative = file
+					.diff(input)
+					.map(|p| p.to_string())
+					.unwrap_or_else(|| file.name().to_string());
+				(file, relative)
+			})
+			.collect();
+		svg_files.sort_by(|a, b| a.1.cmp(&b.1));
+		svg_files
+	} else if input.exists() {
+		vec![(input.clone(), input.name().to_string())]
+	} else {
+		return Err(Error::FileNotFound(input.clone()));
+	};
+
+	if svg_files.is_empty() {
+		return Err(Error::custom(format!("No SVG files found in '{input}'")));
+	}
+
+	// The images of a previous run are removed (only below the current dir, see `files::safer_delete_dir`)
+	let _ = files::safer_delete_dir(&options.diff_dir);
+
+	let mut report = VisualTestReport::default();
+	for (svg_file, relative) in svg_files {
+		let stem = relative.strip_suffix(".svg").unwrap_or(&relative);
+		let baseline_file = options.baseline_dir.join(format!("{stem}.png"));
+		let actual = raster::render_svg_file(&svg_file, options.scale)?;
+
+		let mut result = VisualResult {
+			svg_file: svg_file.to_string(),
+			baseline_file: baseline_file.to_string(),
+			status: VisualStatus::Pass,
+			diff_ratio: None,
+			diff_file: None,
+			message: None,
+		};
+
+		if !baseline_file.exists() {
+			result.status = VisualStatus::Missing;
+		} else {
+			let baseline = raster::read_png(&baseline_file)?;
+			match raster::diff_images(&baseline, &actual, options.pixel_threshold) {
+				Ok(diff) => {
+					result.diff_ratio = Some(diff.diff_ratio());
+					if diff.diff_ratio() > options.threshold {
+						result.status = VisualStatus::Fail;
+						if !options.update {
+							let diff_file = options.diff_dir.join(format!("{stem}.diff.png"));
+							raster::write_png(&diff.diff_image, &diff_file)?;
+							result.diff_file = Some(diff_file.to_string());
+						}
+					}
+				}
+				Err(err) => {
+					result.status = VisualStatus::Fail;
+					result.message = Some(err.to_string());
+				}
+			}
+		}
+
+		if result.status != VisualStatus::Pass {
+			if options.update {
+				raster::write_png(&actual, &baseline_file)?;
+				result.status = VisualStatus::Updated;
+			} else {
+				raster::write_png(&actual, &options.diff_dir.join(format!("{stem}.actual.png")))?;
+			}
+		}
+
+		report.results.push(result);
+	}
+
+	Ok(report)
+}
@@ -0,0 +1,33 @@
+//! The webtk library target: the CLI run by the `webtk` binary, and the SVG transforms benchmarked by the criterion
+//! benches (`benches/`). The modules stay private, the binary being the product.
+
+// The `json!` of the config schema (`config::config_schema`) nests deeper than the default limit
+#![recursion_limit = "256"]
+
+// region:    --- Modules
+
+mod cli;
+mod error;
+mod handlers;
+mod support;
+mod warning;
+
+pub use error::{Error, Result};
+pub use warning::{Warning, WarningCode};
+
+// endregion: --- Modules
+
+/// The SVG transforms of `webtk svg bench`, and its built-in fixtures, for the criterion benches.
+pub mod bench {
+	pub use crate::handlers::svg::{BENCH_FIXTURE_SHAPE_COUNTS, BENCH_TRANSFORMS, bench_fixtures, run_bench_transform};
+}
+
+/// Runs the CLI, printing the error (if any) and exiting with its exit code.
+pub fn run() {
+	let res = cli::execute();
+
+	if let Err(err) = res {
+		support::console::eprint_error(&err);
+		std::process::exit(err.exit_code());
+	}
+}
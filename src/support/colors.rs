@@ -0,0 +1,110 @@
+//! sRGB colors: parsing of hex notations, and formatting for the CSS and the platforms.
+
+/// A sRGB color with components from 0.0 to 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgba {
+	pub red: f64,
+	pub green: f64,
+	pub blue: f64,
+	pub alpha: f64,
+}
+
+impl Rgba {
+	/// Returns the color with the components clamped to 0.0..=1.0.
+	pub fn new(red: f64, green: f64, blue: f64, alpha: f64) -> Self {
+		Self {
+			red: red.clamp(0.0, 1.0),
+			green: green.clamp(0.0, 1.0),
+			blue: blue.clamp(0.0, 1.0),
+			alpha: alpha.clamp(0.0, 1.0),
+		}
+	}
+
+	/// Returns the CSS hex notation, lowercase: "#rrggbb", or "#rrggbbaa" when not opaque.
+	pub fn to_hex(self) -> String {
+		let [r, g, b, a] = self.to_bytes();
+		if a == 255 { format!("#{r:02x}{g:02x}{b:02x}") } else { format!("#{r:02x}{g:02x}{b:02x}{a:02x}") }
+	}
+
+	/// Returns the Android notation, uppercase with the alpha first: "#AARRGGBB".
+	pub fn to_argb_hex(self) -> String {
+		let [r, g, b, a] = self.to_bytes();
+		format!("#{a:02X}{r:02X}{g:02X}{b:02X}")
+	}
+
+	fn to_bytes(self) -> [u8; 4] {
+		[self.red, self.green, self.blue, self.alpha].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+	}
+}
+
+/// Parses a hex color: "#rgb", "#rgba", "#rrggbb" or "#rrggbbaa" (the "#" is optional).
+pub fn parse_hex_color(value: &str) -> Option<Rgba> {
+	let hex = value.trim().trim_start_matches('#');
+	if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+		return None;
+	}
+
+	let digits: Vec<u8> = match hex.len() {
+		3 | 4 => hex
+			.chars()
+			.map(|c| c.to_digit(16).map(|d| (d * 17) as u8))
+			.collect::<Option<_>>()?,
+		6 | 8 => (0..hex.len())
+			.step_by(2)
+			.map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+			.collect::<Option<_>>()?,
+		_ => return None,
+	};
+
+	let component = |i: usize| digits.get(i).map(|d| *d as f64 / 255.0);
+	Some(Rgba::new(component(0)?, component(1)?, component(2)?, component(3).unwrap_or(1.0)))
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_colors_parse_hex_color_notations() -> Result<()> {
+		// -- Setup & Fixtures
+		let inputs = ["#ff0000", "f00", "#FF000080", "#f008", "#12345", "#gg0000"];
+
+		// -- Exec
+		let hexes: Vec<Option<String>> = inputs.iter().map(|s| parse_hex_color(s).map(|c| c.to_hex())).collect();
+
+		// -- Check
+		assert_eq!(
+			hexes,
+			vec![
+				Some("#ff0000".to_string()),
+				Some("#ff0000".to_string()),
+				Some("#ff000080".to_string()),
+				Some("#ff000088".to_string()),
+				None,
+				None
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_colors_to_argb_hex_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let color = Rgba::new(1.0, 0.5, 0.0, 0.5);
+
+		// -- Exec
+		let argb = color.to_argb_hex();
+
+		// -- Check
+		assert_eq!(argb, "#80FF8000");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
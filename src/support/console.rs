@@ -0,0 +1,222 @@
+//! Terminal output: colorized labels and aligned tables.
+//! Colors follow the `--color auto|always|never` choice; with `auto`, only on a terminal,
+//! and never when `NO_COLOR` is set (see https://no-color.org) or `TERM=dumb`.
+
+use crate::{Error, Result};
+use std::fmt::Display;
+use std::io::{BufRead as _, IsTerminal as _, Write as _};
+use std::process::Command;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+static COLOR_CHOICE: AtomicU8 = AtomicU8::new(ColorChoice::Auto as u8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+	Auto = 0,
+	Always = 1,
+	Never = 2,
+}
+
+impl TryFrom<&str> for ColorChoice {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"auto" => Ok(Self::Auto),
+			"always" => Ok(Self::Always),
+			"never" => Ok(Self::Never),
+			other => Err(Error::custom(format!("Invalid color choice '{other}'. Expected: auto, always, never"))),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Stream {
+	Stdout,
+	Stderr,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Style {
+	Bold,
+	Dim,
+	Red,
+	Green,
+	Yellow,
+	Cyan,
+}
+
+impl Style {
+	fn ansi_code(self) -> &'static str {
+		match self {
+			Style::Bold => "1",
+			Style::Dim => "2",
+			Style::Red => "31",
+			Style::Green => "32",
+			Style::Yellow => "33",
+			Style::Cyan => "36",
+		}
+	}
+}
+
+/// Sets the color choice of the process (from `--color`).
+pub fn set_color_choice(choice: ColorChoice) {
+	COLOR_CHOICE.store(choice as u8, Ordering::Relaxed);
+}
+
+/// Returns true if the output to `stream` is colorized.
+pub fn use_color(stream: Stream) -> bool {
+	match COLOR_CHOICE.load(Ordering::Relaxed) {
+		choice if choice == ColorChoice::Always as u8 => true,
+		choice if choice == ColorChoice::Never as u8 => false,
+		_ => {
+			let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+			let dumb_term = std::env::var("TERM").is_ok_and(|term| term == "dumb");
+			let is_terminal = match stream {
+				Stream::Stdout => std::io::stdout().is_terminal(),
+				Stream::Stderr => std::io::stderr().is_terminal(),
+			};
+			!no_color && !dumb_term && is_terminal
+		}
+	}
+}
+
+/// Returns the text with the style, when the output to `stream` is colorized.
+pub fn paint(text: impl Display, style: Style, stream: Stream) -> String {
+	if use_color(stream) { format!("\x1b[{}m{text}\x1b[0m", style.ansi_code()) } else { text.to_string() }
+}
+
+/// Prints `Label: message` on stdout, with the label styled (e.g., "Exported" in green).
+pub fn print_label(label: &str, style: Style, message: impl Display) {
+	println!("{} {message}", paint(format!("{label}:"), style, Stream::Stdout));
+}
+
+/// Prints `Warning: message` on stderr.
+pub fn eprint_warning(message: impl Display) {
+	eprintln!("{} {message}", paint("Warning:", Style::Yellow, Stream::Stderr));
+}
+
+/// Prints `Error: message` on stderr.
+pub fn eprint_error(message: impl Display) {
+	eprintln!("{} {message}", paint("Error:", Style::Red, Stream::Stderr));
+}
+
+/// Reads a secret line from stdin (e.g., a token), without the line break. From a terminal, `prompt` is printed on
+/// stderr and the typed characters are not echoed (with `stty`, restored after the read).
+pub fn read_secret_line(prompt: &str) -> Result<String> {
+	let stdin = std::io::stdin();
+	let is_terminal = stdin.is_terminal();
+	if is_terminal {
+		eprint!("{prompt}");
+		std::io::stderr().flush().map_err(Error::custom_from_err)?;
+		set_terminal_echo(false)?;
+	}
+
+	let mut line = String::new();
+	let read_res = stdin.lock().read_line(&mut line);
+
+	if is_terminal {
+		// The Enter of the user was not echoed either
+		eprintln!();
+		set_terminal_echo(true)?;
+	}
+	read_res.map_err(Error::custom_from_err)?;
+
+	Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Prints the rows as aligned columns on stdout (see `format_table`), the first row (header) in bold.
+pub fn print_table(rows: &[Vec<String>], indent: &str) {
+	for (idx, line) in format_table(rows).into_iter().enumerate() {
+		let line = if idx == 0 { paint(line, Style::Bold, Stream::Stdout) } else { line };
+		println!("{indent}{line}");
+	}
+}
+
+/// Formats the rows as columns aligned on the widest cell (2 spaces apart), without trailing spaces.
+/// Widths are in chars (the cells are plain text, styles are applied after alignment).
+pub fn format_table(rows: &[Vec<String>]) -> Vec<String> {
+	let column_count = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+	let mut widths = vec![0; column_count];
+	for row in rows {
+		for (width, cell) in widths.iter_mut().zip(row) {
+			*width = (*width).max(cell.chars().count());
+		}
+	}
+
+	rows.iter()
+		.map(|row| {
+			let cells: Vec<String> = row.iter().zip(&widths).map(|(cell, width)| format!("{cell:<width$}")).collect();
+			cells.join("  ").trim_end().to_string()
+		})
+		.collect()
+}
+
+// region:    --- Support
+
+/// Turns the echo of the terminal of stdin on or off (`stty` inherits stdin, the terminal).
+fn set_terminal_echo(echo: bool) -> Result<()> {
+	let status = Command::new("stty")
+		.arg(if echo { "echo" } else { "-echo" })
+		.status()
+		.map_err(|e| Error::tool_exec("stty", "stty", e))?;
+	if !status.success() {
+		return Err(Error::custom(format!(
+			"Cannot turn the terminal echo {} ({status})",
+			if echo { "on" } else { "off" }
+		)));
+	}
+	Ok(())
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_console_format_table_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let rows = vec![
+			vec!["ARTBOARD".to_string(), "FORMAT".to_string(), "OUTPUT".to_string()],
+			vec![
+				"ico/user/fill".to_string(),
+				"svg".to_string(),
+				"out/ico-user-fill.svg".to_string(),
+			],
+			vec!["ico/é".to_string(), "png".to_string(), String::new()],
+		];
+
+		// -- Exec
+		let lines = format_table(&rows);
+
+		// -- Check
+		assert_eq!(
+			lines,
+			vec![
+				"ARTBOARD       FORMAT  OUTPUT",
+				"ico/user/fill  svg     out/ico-user-fill.svg",
+				"ico/é          png",
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_console_color_choice_try_from() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(ColorChoice::try_from("never")?, ColorChoice::Never);
+		assert_eq!(ColorChoice::try_from("always")?, ColorChoice::Always);
+		assert!(ColorChoice::try_from("yes").is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
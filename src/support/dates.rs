@@ -0,0 +1,94 @@
+//! Minimal UTC date formatting (no timezone database).
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Returns the current time as seconds since the Unix epoch.
+/// Honors `SOURCE_DATE_EPOCH` (reproducible builds) when set.
+pub fn now_unix_secs() -> i64 {
+	if let Some(epoch) = source_date_epoch() {
+		return epoch;
+	}
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs() as i64)
+		.unwrap_or_default()
+}
+
+/// Returns the `SOURCE_DATE_EPOCH` (reproducible builds) seconds, when set.
+pub fn source_date_epoch() -> Option<i64> {
+	std::env::var("SOURCE_DATE_EPOCH").ok().and_then(|v| v.trim().parse().ok())
+}
+
+/// Formats a Unix timestamp (UTC) with a strftime subset:
+/// `%Y` (year), `%m` (month), `%d` (day), `%H`, `%M`, `%S`, and `%%`.
+/// Other `%x` sequences are kept as is.
+pub fn format_utc_date(unix_secs: i64, format: &str) -> String {
+	let days = unix_secs.div_euclid(86_400);
+	let secs_of_day = unix_secs.rem_euclid(86_400);
+	let (year, month, day) = civil_from_days(days);
+	let (hour, minute, second) = (secs_of_day / 3600, (secs_of_day % 3600) / 60, secs_of_day % 60);
+
+	let mut result = String::with_capacity(format.len() + 8);
+	let mut chars = format.chars();
+	while let Some(ch) = chars.next() {
+		if ch != '%' {
+			result.push(ch);
+			continue;
+		}
+		match chars.next() {
+			Some('Y') => result.push_str(&format!("{year:04}")),
+			Some('m') => result.push_str(&format!("{month:02}")),
+			Some('d') => result.push_str(&format!("{day:02}")),
+			Some('H') => result.push_str(&format!("{hour:02}")),
+			Some('M') => result.push_str(&format!("{minute:02}")),
+			Some('S') => result.push_str(&format!("{second:02}")),
+			Some('%') => result.push('%'),
+			Some(other) => {
+				result.push('%');
+				result.push(other);
+			}
+			None => result.push('%'),
+		}
+	}
+	result
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) civil date (proleptic Gregorian).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+	let z = days + 719_468;
+	let era = z.div_euclid(146_097);
+	let doe = z.rem_euclid(146_097);
+	let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+	let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+	(year, month, day)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_dates_format_utc_date_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		// 2024-06-01T13:45:07Z
+		let secs = 1_717_249_507;
+
+		// -- Exec & Check
+		assert_eq!(format_utc_date(secs, "%Y%m%d"), "20240601");
+		assert_eq!(format_utc_date(secs, "%Y-%m-%d %H:%M:%S %%"), "2024-06-01 13:45:07 %");
+		assert_eq!(format_utc_date(0, "%Y-%m-%d"), "1970-01-01");
+		assert_eq!(format_utc_date(951_782_400, "%Y-%m-%d"), "2000-02-29");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,8 +1,17 @@
+use crate::support::{hashes, profile};
 use crate::{Error, Result};
 use simple_fs::{SPath, SaferRemoveOptions};
+use std::time::SystemTime;
 
 /// Allowed substrings for directory deletion (safety check).
-const DIR_DELETE_ALLOW_CONTAINS: &[&str] = &[".cache-raw-export", ".cache-symbols", ".cache"];
+const DIR_DELETE_ALLOW_CONTAINS: &[&str] = &[
+	".cache-raw-export",
+	".cache-symbols",
+	".cache",
+	"__webtk_snapshots__",
+	".visual-diff",
+	"_tiles",
+];
 
 /// Allowed extensions for file deletion (safety check).
 const FILE_DELETE_ALLOW_CONTAINS: &[&str] = &[".svg", ".png", ".jpeg"];
@@ -21,6 +30,12 @@ pub fn looks_like_file_path(path: &SPath) -> bool {
 	!path.ext().is_empty()
 }
 
+/// Returns the absolute path (from the current directory), e.g., for a command run in another working directory.
+pub fn absolute_path(path: &SPath) -> Result<SPath> {
+	let absolute = std::path::absolute(path.as_std_path()).map_err(|e| format!("Invalid path '{path}': {e}"))?;
+	SPath::from_std_path(absolute).map_err(|e| Error::custom(format!("Invalid path '{path}': {e}")))
+}
+
 /// Safely deletes a directory if it passes safety checks.
 /// Safety checks:
 /// - The directory path must be below the current directory
@@ -47,3 +62,305 @@ pub fn safer_delete_file(file_path: &SPath) -> Result<bool> {
 
 	simple_fs::safer_remove_file(file_path, options).map_err(Error::custom_from_err)
 }
+
+/// Sets the modification time of a file.
+pub fn set_file_mtime(path: &SPath, mtime: SystemTime) -> Result<()> {
+	std::fs::File::options()
+		.write(true)
+		.open(path.as_std_path())
+		.and_then(|file| file.set_modified(mtime))
+		.map_err(|e| Error::custom(format!("Cannot set the modification time of '{path}': {e}")))
+}
+
+/// Parses an octal file mode (e.g., "644", "0640").
+pub fn parse_file_mode(value: &str) -> Result<u32> {
+	let digits = value.strip_prefix('0').filter(|rest| rest.len() >= 3).unwrap_or(value);
+	match u32::from_str_radix(digits, 8) {
+		Ok(mode) if (3..=4).contains(&digits.len()) && mode <= 0o7777 => Ok(mode),
+		_ => Err(Error::custom(format!("Invalid file mode '{value}'. Expected an octal mode (e.g., 644)"))),
+	}
+}
+
+/// Sets the permissions of a file to an octal mode (see `parse_file_mode`). Ignored on Windows.
+pub fn set_file_mode(path: &SPath, mode: u32) -> Result<()> {
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt as _;
+		std::fs::set_permissions(path.as_std_path(), std::fs::Permissions::from_mode(mode))
+			.map_err(|e| Error::custom(format!("Cannot set the mode of '{path}': {e}")))?;
+	}
+	#[cfg(not(unix))]
+	let _ = (path, mode);
+
+	Ok(())
+}
+
+/// Copies a file into a new file with the default permissions (666 less the umask), unlike `std::fs::copy` which
+/// copies the source permissions (e.g., 600 of a file exported as root in a container).
+pub fn copy_file(src: &SPath, target: &SPath) -> Result<()> {
+	let _span = profile::span("io", "copy").arg("file", target.as_str());
+	if target.exists() {
+		std::fs::remove_file(target.as_std_path()).map_err(|e| format!("Failed to replace '{target}': {e}"))?;
+	}
+	let mut reader = std::fs::File::open(src.as_std_path()).map_err(|e| format!("Failed to read '{src}': {e}"))?;
+	let mut writer =
+		std::fs::File::create(target.as_std_path()).map_err(|e| format!("Failed to create '{target}': {e}"))?;
+	std::io::copy(&mut reader, &mut writer).map_err(|e| format!("Failed to copy '{src}' to '{target}': {e}"))?;
+
+	Ok(())
+}
+
+/// Expands a user path: a leading `~` (home directory), `$VAR` / `${VAR}`, and `%VAR%` environment variables
+/// (e.g., `~/design/icons.sketch`, `$HOME/out`, `%USERPROFILE%/out`), for paths not expanded by a shell
+/// (e.g., CI templating, config files). Fails on an undefined variable.
+pub fn expand_path(path: &str) -> Result<String> {
+	expand_path_with(path, |name| std::env::var(name).ok())
+}
+
+/// Expands a path like `expand_path`, with the variables from `lookup`.
+/// The home directory is `HOME`, or `USERPROFILE` (Windows).
+pub fn expand_path_with(path: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+	let var = |name: &str| {
+		lookup(name)
+			.ok_or_else(|| Error::custom(format!("Environment variable '{name}' is not set (in path '{path}')")))
+	};
+
+	let mut rest = path;
+	let mut expanded = String::new();
+
+	// -- Home directory (only as the first component)
+	if rest == "~" || rest.starts_with("~/") || rest.starts_with("~\\") {
+		let home = lookup("HOME")
+			.or_else(|| lookup("USERPROFILE"))
+			.ok_or_else(|| Error::custom(format!("Cannot expand '~' (HOME is not set) in path '{path}'")))?;
+		expanded.push_str(&home);
+		rest = &rest[1..];
+	}
+
+	// -- Variables
+	while let Some(idx) = rest.find(['$', '%']) {
+		expanded.push_str(&rest[..idx]);
+		let after = &rest[idx + 1..];
+
+		let (name, consumed) = if rest[idx..].starts_with('%') {
+			match after.find('%') {
+				Some(end) if is_var_name(&after[..end]) => (&after[..end], end + 1),
+				_ => ("", 0),
+			}
+		} else if let Some(braced) = after.strip_prefix('{') {
+			match braced.find('}') {
+				Some(end) if is_var_name(&braced[..end]) => (&braced[..end], end + 2),
+				_ => ("", 0),
+			}
+		} else {
+			let end = after
+				.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+				.unwrap_or(after.len());
+			(&after[..end], end)
+		};
+
+		if name.is_empty() {
+			// Not a variable (e.g., "50%" or a lone "$"), kept as is
+			expanded.push_str(&rest[idx..idx + 1]);
+		} else {
+			expanded.push_str(&var(name)?);
+		}
+		rest = &after[consumed..];
+	}
+	expanded.push_str(rest);
+
+	Ok(expanded)
+}
+
+fn is_var_name(name: &str) -> bool {
+	!name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Returns the deepest directory containing all the files (e.g., "out/icons" for "out/icons/a.svg" and "out/icons/ico/b.svg").
+/// Returns an empty string when the files have no common directory (or for no files).
+pub fn common_dir(files: &[&str]) -> String {
+	let mut common: Option<Vec<&str>> = None;
+
+	for file in files {
+		let dir: Vec<&str> = match file.rsplit_once('/') {
+			Some((dir, _)) => dir.split('/').collect(),
+			None => Vec::new(),
+		};
+		common = Some(match common {
+			None => dir,
+			Some(common) => common.iter().zip(&dir).take_while(|(a, b)| a == b).map(|(a, _)| *a).collect(),
+		});
+	}
+
+	match common {
+		// The root of absolute paths ("/a.svg" and "/b/c.svg")
+		Some(common) if common == [""] => "/".to_string(),
+		Some(common) => common.join("/"),
+		None => String::new(),
+	}
+}
+
+/// Characters not allowed in file names (Windows being the strictest).
+const ILLEGAL_FILE_NAME_CHARS: &[char] = &['<', '>', ':', '"', '\\', '|', '?', '*'];
+
+/// Windows reserved device names (also reserved with an extension, e.g., "con.svg").
+const RESERVED_FILE_NAMES: &[&str] = &[
+	"CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1", "LPT2",
+	"LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Max bytes of a sanitized file name, leaving room for a scale suffix and an extension (e.g., "@1.5x.jpeg").
+const MAX_FILE_NAME_BYTES: usize = 255 - 16;
+
+/// Returns a file path safe on all the platforms for a `/` separated name (e.g., an artboard name), segment by segment.
+/// See `sanitize_file_name`. Returns the path unchanged when it is already safe.
+pub fn sanitize_file_path(path: &str) -> String {
+	path.split('/').map(sanitize_file_name).collect::<Vec<_>>().join("/")
+}
+
+/// Returns a file name safe on all the platforms:
+/// - the illegal characters (`<>:"\|?*`) and the control characters are replaced by `_`,
+/// - the emoji are replaced by their code point (e.g., "😀" -> "u1f600"), the joiners and variation selectors removed,
+/// - the trailing dots and spaces are removed, and the reserved names suffixed (e.g., "con" -> "con_"),
+/// - the names too long are truncated, with the hash of the full name as a suffix.
+///
+/// The other unicode characters (e.g., "café", "图标") are kept.
+pub fn sanitize_file_name(name: &str) -> String {
+	let mut result = String::with_capacity(name.len());
+	for ch in name.chars() {
+		if ch.is_control() || ILLEGAL_FILE_NAME_CHARS.contains(&ch) {
+			result.push('_');
+		} else if is_emoji_joiner(ch) {
+			// skip (part of an emoji sequence)
+		} else if is_emoji(ch) {
+			result.push_str(&format!("u{:x}", ch as u32));
+		} else {
+			result.push(ch);
+		}
+	}
+
+	let mut result = result.trim_end_matches(['.', ' ']).to_string();
+	if result.is_empty() {
+		result = "_".to_string();
+	}
+
+	let stem = result.split('.').next().unwrap_or_default();
+	if RESERVED_FILE_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem)) {
+		result.insert(stem.len(), '_');
+	}
+
+	if result.len() > MAX_FILE_NAME_BYTES {
+		let hash = &hashes::fnv1a_64_hex(name)[..8];
+		let mut end = MAX_FILE_NAME_BYTES - hash.len() - 1;
+		while !result.is_char_boundary(end) {
+			end -= 1;
+		}
+		result = format!("{}-{hash}", &result[..end]);
+	}
+
+	result
+}
+
+/// Returns true for the emoji and pictograph code points (e.g., "😀", "❤", "🇫").
+fn is_emoji(ch: char) -> bool {
+	matches!(ch as u32, 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x1F000..=0x1FAFF)
+}
+
+/// Returns true for the code points joining or qualifying emoji (zero width joiner, variation selectors).
+fn is_emoji_joiner(ch: char) -> bool {
+	matches!(ch as u32, 0x200D | 0xFE00..=0xFE0F)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_files_expand_path_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let lookup = |name: &str| match name {
+			"HOME" => Some("/Users/jen".to_string()),
+			"USERPROFILE" => Some("C:\\Users\\jen".to_string()),
+			"OUT" => Some("dist".to_string()),
+			_ => None,
+		};
+
+		// -- Exec & Check
+		assert_eq!(expand_path_with("~/design/icons.sketch", lookup)?, "/Users/jen/design/icons.sketch");
+		assert_eq!(expand_path_with("$HOME/out/${OUT}/icons", lookup)?, "/Users/jen/out/dist/icons");
+		assert_eq!(expand_path_with("%USERPROFILE%\\out", lookup)?, "C:\\Users\\jen\\out");
+		assert_eq!(expand_path_with("out/{git_short_sha}/a~b", lookup)?, "out/{git_short_sha}/a~b");
+		assert_eq!(expand_path_with("out/50%/$", lookup)?, "out/50%/$");
+		assert!(expand_path_with("$MISSING/out", lookup).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_files_parse_file_mode_simple() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(parse_file_mode("644")?, 0o644);
+		assert_eq!(parse_file_mode("0640")?, 0o640);
+		assert_eq!(parse_file_mode("2775")?, 0o2775);
+		assert!(parse_file_mode("64").is_err());
+		assert!(parse_file_mode("648").is_err());
+		assert!(parse_file_mode("u+rw").is_err());
+		assert!(parse_file_mode("17777").is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_files_common_dir_simple() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(common_dir(&["out/icons/a.svg", "out/icons/ico/b.svg"]), "out/icons");
+		assert_eq!(common_dir(&["/tmp/x/a.png", "/tmp/y/b.png"]), "/tmp");
+		assert_eq!(common_dir(&["/a.svg", "/b/c.svg"]), "/");
+		assert_eq!(common_dir(&["a.svg", "out/b.svg"]), "");
+		assert_eq!(common_dir(&["out/icons/symbols.svg"]), "out/icons");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_files_sanitize_file_path_unicode() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(sanitize_file_path("ico/user/fill"), "ico/user/fill");
+		assert_eq!(sanitize_file_path("ico/café/图标"), "ico/café/图标");
+		assert_eq!(sanitize_file_path("ico/a:b*c?"), "ico/a_b_c_");
+		assert_eq!(sanitize_file_path("emoji/😀 smile"), "emoji/u1f600 smile");
+		assert_eq!(sanitize_file_path("emoji/❤️"), "emoji/u2764");
+		assert_eq!(sanitize_file_path("emoji/👩‍💻"), "emoji/u1f469u1f4bb");
+		assert_eq!(sanitize_file_path("misc/con"), "misc/con_");
+		assert_eq!(sanitize_file_path("misc/nul.v2"), "misc/nul_.v2");
+		assert_eq!(sanitize_file_path("misc/end. "), "misc/end");
+		assert_eq!(sanitize_file_path("misc/.."), "misc/_");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_files_sanitize_file_name_long() -> Result<()> {
+		// -- Setup & Fixtures
+		let long_a = "图".repeat(100);
+		let long_b = format!("{long_a}b");
+
+		// -- Exec
+		let name_a = sanitize_file_name(&long_a);
+		let name_b = sanitize_file_name(&long_b);
+
+		// -- Check
+		assert!(name_a.len() <= MAX_FILE_NAME_BYTES);
+		assert!(name_a.starts_with("图图"));
+		assert_ne!(name_a, name_b);
+		assert_eq!(name_a, sanitize_file_name(&long_a));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,19 +1,52 @@
-use crate::Result;
-use globset::GlobSet;
-use simple_fs::get_glob_set;
+use crate::{Error, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use simple_fs::{ListOptions, SPath};
+
+/// How the globs match the letter case of the values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlobCase {
+	#[default]
+	Sensitive,
+	/// "ico/user" matches "Ico/User".
+	Insensitive,
+	/// Case insensitive, unless the glob has an uppercase letter (like ripgrep `--smart-case`).
+	Smart,
+}
 
 /// Builds a GlobSet from an optional slice of pattern strings.
 /// Returns None if no patterns are provided.
 pub fn build_glob_set(patterns: Option<&[&str]>) -> Result<Option<GlobSet>> {
-	match patterns {
-		Some(globs) if !globs.is_empty() => {
-			let set = get_glob_set(globs)
-				.map_err(|e| format!("Invalid glob pattern(s): {globs:?}. Error: {e}"))
-				.map_err(crate::Error::custom)?;
-			Ok(Some(set))
-		}
-		_ => Ok(None),
+	build_glob_set_with_case(patterns, GlobCase::Sensitive)
+}
+
+/// Builds a GlobSet from an optional slice of pattern strings, matching the case as `glob_case`.
+/// Returns None if no patterns are provided.
+pub fn build_glob_set_with_case(patterns: Option<&[&str]>, glob_case: GlobCase) -> Result<Option<GlobSet>> {
+	let globs = match patterns {
+		Some(globs) if !globs.is_empty() => globs,
+		_ => return Ok(None),
+	};
+
+	let mut builder = GlobSetBuilder::new();
+	for glob in globs {
+		let case_insensitive = match glob_case {
+			GlobCase::Sensitive => false,
+			GlobCase::Insensitive => true,
+			GlobCase::Smart => !glob.chars().any(char::is_uppercase),
+		};
+		let glob = GlobBuilder::new(glob)
+			// So a single "*" does not match "/"
+			.literal_separator(true)
+			.case_insensitive(case_insensitive)
+			.build()
+			.map_err(|e| crate::Error::custom(format!("Invalid glob pattern(s): {globs:?}. Error: {e}")))?;
+		builder.add(glob);
 	}
+	let set = builder
+		.build()
+		.map_err(|e| crate::Error::custom(format!("Invalid glob pattern(s): {globs:?}. Error: {e}")))?;
+
+	Ok(Some(set))
 }
 
 /// Checks if a value matches the glob set (if present).
@@ -21,3 +54,70 @@ pub fn build_glob_set(patterns: Option<&[&str]>) -> Result<Option<GlobSet>> {
 pub fn matches_glob_set(glob_set: Option<&GlobSet>, value: &str) -> bool {
 	glob_set.as_ref().is_none_or(|gs| gs.is_match(value))
 }
+
+/// Returns the files of glob patterns, sorted without duplicates. A pattern is matched from its leading directories
+/// without glob characters (e.g., "assets/" for "assets/**/*.json"), or is a file path (which must exist).
+pub fn list_glob_files(globs: &[String]) -> Result<Vec<SPath>> {
+	let mut files = Vec::new();
+	for glob in globs {
+		if !glob.contains(['*', '?', '[', '{']) {
+			let file = SPath::new(glob);
+			if !file.is_file() {
+				return Err(Error::FileNotFound(file));
+			}
+			files.push(file);
+			continue;
+		}
+
+		let (base_dir, pattern) = match glob.find(['*', '?', '[', '{']).and_then(|idx| glob[..idx].rfind('/')) {
+			Some(idx) => (&glob[..idx], &glob[idx + 1..]),
+			None => (".", glob.as_str()),
+		};
+		let base_dir = if base_dir.is_empty() { "/" } else { base_dir };
+		let list_options = ListOptions::from_relative_glob(true);
+		let dir_files =
+			simple_fs::list_files(base_dir, Some(&[pattern]), Some(list_options)).map_err(Error::custom_from_err)?;
+		for file in dir_files {
+			let file = match file.as_str().strip_prefix("./") {
+				Some(rel_path) => SPath::new(rel_path),
+				None => file,
+			};
+			files.push(file);
+		}
+	}
+
+	files.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+	files.dedup();
+	Ok(files)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_globs_build_glob_set_with_case_smart() -> Result<()> {
+		// -- Setup & Fixtures
+		let sensitive = build_glob_set_with_case(Some(&["ico/*"]), GlobCase::Sensitive)?;
+		let insensitive = build_glob_set_with_case(Some(&["ico/*"]), GlobCase::Insensitive)?;
+		let smart_lower = build_glob_set_with_case(Some(&["ico/*"]), GlobCase::Smart)?;
+		let smart_upper = build_glob_set_with_case(Some(&["Ico/*"]), GlobCase::Smart)?;
+
+		// -- Check
+		assert!(!matches_glob_set(sensitive.as_ref(), "Ico/User"));
+		assert!(matches_glob_set(insensitive.as_ref(), "Ico/User"));
+		assert!(matches_glob_set(smart_lower.as_ref(), "Ico/User"));
+		assert!(matches_glob_set(smart_upper.as_ref(), "Ico/User"));
+		assert!(!matches_glob_set(smart_upper.as_ref(), "ico/user"));
+		// The separator stays literal
+		assert!(!matches_glob_set(insensitive.as_ref(), "Ico/User/Fill"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,69 @@
+//! Small, stable hashing utilities: FNV-1a (non-cryptographic, for the content comparisons)
+//! and SHA-256 (for the integrity checks).
+
+use sha2::{Digest, Sha256};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Computes the 64-bit FNV-1a hash of the given content.
+/// Unlike `std::hash::DefaultHasher`, the result is stable across runs and Rust versions,
+/// so it can be persisted or compared between files.
+pub fn fnv1a_64(content: &[u8]) -> u64 {
+	let mut hash = FNV_OFFSET_BASIS;
+	for byte in content {
+		hash ^= *byte as u64;
+		hash = hash.wrapping_mul(FNV_PRIME);
+	}
+	hash
+}
+
+/// Returns the FNV-1a 64-bit hash of the content as a 16 chars lowercase hex string.
+pub fn fnv1a_64_hex(content: impl AsRef<[u8]>) -> String {
+	format!("{:016x}", fnv1a_64(content.as_ref()))
+}
+
+/// Returns the SHA-256 hash of the content as a 64 chars lowercase hex string.
+pub fn sha256_hex(content: impl AsRef<[u8]>) -> String {
+	Sha256::digest(content.as_ref())
+		.iter()
+		.map(|byte| format!("{byte:02x}"))
+		.collect()
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_hashes_fnv1a_64_hex_known_values() -> Result<()> {
+		// -- Exec
+		let empty = fnv1a_64_hex("");
+		let foobar = fnv1a_64_hex("foobar");
+
+		// -- Check
+		assert_eq!(empty, "cbf29ce484222325");
+		assert_eq!(foobar, "85944171f73967e8");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_hashes_sha256_hex_known_values() -> Result<()> {
+		// -- Exec
+		let empty = sha256_hex("");
+		let abc = sha256_hex("abc");
+
+		// -- Check
+		assert_eq!(empty, "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+		assert_eq!(abc, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
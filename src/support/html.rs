@@ -0,0 +1,194 @@
+//! Minimal HTML scanning (no DOM): the attributes of the start tags, with their line numbers.
+//! The comments and the content of the `<script>` and `<style>` elements are skipped.
+
+/// An attribute of a start tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlAttr {
+	/// The tag name, lowercase (e.g., "img").
+	pub tag: String,
+	/// The attribute name, lowercase (e.g., "src").
+	pub name: String,
+	/// The value, with the common character references decoded (empty for a boolean attribute).
+	pub value: String,
+	/// The 1-based line of the attribute.
+	pub line: usize,
+}
+
+/// Returns the attributes of the start tags of an HTML document, in document order.
+pub fn scan_attributes(content: &str) -> Vec<HtmlAttr> {
+	let bytes = content.as_bytes();
+	let mut attrs = Vec::new();
+	let mut line = 1;
+	let mut idx = 0;
+
+	// Advances to `end`, counting the lines
+	let advance = |idx: &mut usize, line: &mut usize, end: usize| {
+		*line += bytes[*idx..end].iter().filter(|b| **b == b'\n').count();
+		*idx = end;
+	};
+
+	while idx < bytes.len() {
+		let Some(offset) = content[idx..].find('<') else {
+			break;
+		};
+		let tag_start = idx + offset;
+		advance(&mut idx, &mut line, tag_start);
+
+		// -- Comments
+		if content[idx..].starts_with("<!--") {
+			let end = content[idx + 4..]
+				.find("-->")
+				.map(|end| idx + 4 + end + 3)
+				.unwrap_or(bytes.len());
+			advance(&mut idx, &mut line, end);
+			continue;
+		}
+
+		// -- Start tag name
+		let name_start = idx + 1;
+		let name_len = content[name_start..]
+			.find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+			.unwrap_or(bytes.len() - name_start);
+		if name_len == 0 || !bytes[name_start].is_ascii_alphabetic() {
+			idx += 1;
+			continue;
+		}
+		let tag = content[name_start..name_start + name_len].to_ascii_lowercase();
+		idx = name_start + name_len;
+
+		// -- Attributes, until the end of the tag
+		loop {
+			while idx < bytes.len() && (bytes[idx].is_ascii_whitespace() || bytes[idx] == b'/') {
+				if bytes[idx] == b'\n' {
+					line += 1;
+				}
+				idx += 1;
+			}
+			if idx >= bytes.len() || bytes[idx] == b'>' {
+				idx += 1;
+				break;
+			}
+
+			let attr_line = line;
+			let attr_start = idx;
+			while idx < bytes.len() && !bytes[idx].is_ascii_whitespace() && !matches!(bytes[idx], b'=' | b'>' | b'/') {
+				idx += 1;
+			}
+			let name = content[attr_start..idx].to_ascii_lowercase();
+			if name.is_empty() {
+				// A stray character (e.g., a "=" without a name)
+				idx += 1;
+				continue;
+			}
+
+			// The optional value, after the spaces around "="
+			let mut value_idx = idx;
+			while value_idx < bytes.len() && bytes[value_idx].is_ascii_whitespace() {
+				value_idx += 1;
+			}
+			let mut value = String::new();
+			if value_idx < bytes.len() && bytes[value_idx] == b'=' {
+				value_idx += 1;
+				while value_idx < bytes.len() && bytes[value_idx].is_ascii_whitespace() {
+					value_idx += 1;
+				}
+				advance(&mut idx, &mut line, value_idx);
+				let (raw, end) = match bytes.get(idx) {
+					Some(quote @ (b'"' | b'\'')) => {
+						let end = content[idx + 1..]
+							.find(*quote as char)
+							.map(|end| idx + 1 + end)
+							.unwrap_or(bytes.len());
+						(&content[idx + 1..end], (end + 1).min(bytes.len()))
+					}
+					_ => {
+						let end = content[idx..]
+							.find(|c: char| c.is_ascii_whitespace() || c == '>')
+							.map(|end| idx + end)
+							.unwrap_or(bytes.len());
+						(&content[idx..end], end)
+					}
+				};
+				value = decode_char_refs(raw);
+				advance(&mut idx, &mut line, end);
+			}
+
+			attrs.push(HtmlAttr { tag: tag.clone(), name, value, line: attr_line });
+		}
+
+		// -- The raw text of the script and style elements
+		if tag == "script" || tag == "style" {
+			let closing = format!("</{tag}");
+			let text_start = idx.min(bytes.len());
+			let end = content[text_start..]
+				.to_ascii_lowercase()
+				.find(&closing)
+				.map(|end| text_start + end)
+				.unwrap_or(bytes.len());
+			advance(&mut idx, &mut line, end);
+		}
+	}
+
+	attrs
+}
+
+/// Decodes the common character references of an attribute value (e.g., "&amp;" in the URLs).
+fn decode_char_refs(value: &str) -> String {
+	if !value.contains('&') {
+		return value.to_string();
+	}
+	value
+		.replace("&quot;", "\"")
+		.replace("&#39;", "'")
+		.replace("&apos;", "'")
+		.replace("&lt;", "<")
+		.replace("&gt;", ">")
+		.replace("&amp;", "&")
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_html_scan_attributes_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let content = r#"<!doctype html>
+<html lang=en>
+<!-- <img src="commented.png"> -->
+<a href="/docs/?a=1&amp;b=2" class='nav'>Docs</a>
+<img
+  src="logo.png" alt="">
+<script>if (a < b) { document.write('<img src="script.png">'); }</script>
+<input disabled><IMG SRCSET="a.png 1x, b.png 2x"/>"#;
+
+		// -- Exec
+		let attrs = scan_attributes(content);
+
+		// -- Check
+		let summary: Vec<(&str, &str, &str, usize)> = attrs
+			.iter()
+			.map(|attr| (attr.tag.as_str(), attr.name.as_str(), attr.value.as_str(), attr.line))
+			.collect();
+		assert_eq!(
+			summary,
+			vec![
+				("html", "lang", "en", 2),
+				("a", "href", "/docs/?a=1&b=2", 4),
+				("a", "class", "nav", 4),
+				("img", "src", "logo.png", 6),
+				("img", "alt", "", 6),
+				("input", "disabled", "", 8),
+				("img", "srcset", "a.png 1x, b.png 2x", 8),
+			]
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,373 @@
+//! HTTP fetch of remote inputs (e.g., `https://cdn.example.com/icons.svg`), downloaded with `curl`
+//! and cached on disk by URL, so a command can take a URL wherever it takes an SVG/image file.
+//! The timeout and cache follow the `--http-timeout`, `--http-cache-dir`, and `--no-http-cache` options.
+//!
+//! All the network requests (fetches, webhooks) go through `curl_command`, which applies the proxy
+//! (`HTTPS_PROXY`, `HTTP_PROXY`, `NO_PROXY`) and TLS (`--cacert`, `--insecure`) settings, and fails in offline mode
+//! (see `set_offline`).
+
+use crate::support::{console, files, hashes, profile};
+use crate::{Error, Result};
+use simple_fs::{SPath, ensure_dir};
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Once, OnceLock};
+
+pub const DEFAULT_HTTP_CACHE_DIR: &str = ".cache-http";
+pub const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 30;
+
+static HTTP_OPTIONS: OnceLock<HttpOptions> = OnceLock::new();
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+	/// The maximum time of a download (connection included).
+	pub timeout_secs: u64,
+	/// The downloads, as `<cache_dir>/<url hash>/<file name>`.
+	pub cache_dir: String,
+	/// Download even when the URL is in the cache (the cache is still written).
+	pub no_cache: bool,
+	/// A CA bundle (PEM) to verify the servers with, e.g., the certificate of a TLS-intercepting proxy.
+	pub cacert: Option<String>,
+	/// Skip the TLS certificate verification (a warning is printed on the first request).
+	pub insecure: bool,
+}
+
+impl Default for HttpOptions {
+	fn default() -> Self {
+		Self {
+			timeout_secs: DEFAULT_HTTP_TIMEOUT_SECS,
+			cache_dir: DEFAULT_HTTP_CACHE_DIR.to_string(),
+			no_cache: false,
+			cacert: None,
+			insecure: false,
+		}
+	}
+}
+
+/// Sets the HTTP options of the process (from the global CLI options). Only the first call applies.
+pub fn set_http_options(options: HttpOptions) {
+	let _ = HTTP_OPTIONS.set(options);
+}
+
+pub fn http_options() -> HttpOptions {
+	HTTP_OPTIONS.get().cloned().unwrap_or_default()
+}
+
+/// Forbids all the network access of the process (`--offline`, or `[network] offline = true`), for hermetic builds:
+/// the requests and the remote sketchtool runs fail with `Error::NetworkDisabled` (the cached URL inputs still resolve).
+/// Offline mode cannot be turned off.
+pub fn set_offline() {
+	OFFLINE.store(true, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+	OFFLINE.load(Ordering::Relaxed)
+}
+
+/// The network capability check: fails in offline mode, with the URL or host about to be reached.
+pub fn check_network(target: &str) -> Result<()> {
+	if is_offline() {
+		return Err(Error::NetworkDisabled(target.to_string()));
+	}
+	Ok(())
+}
+
+/// Returns true if the value is an `http://` or `https://` URL.
+pub fn is_url(value: &str) -> bool {
+	value.starts_with("https://") || value.starts_with("http://")
+}
+
+/// Resolves an input argument: a URL is fetched (see `fetch_to_file`), a path is expanded (see `files::expand_path`).
+pub fn input_path(value: &str) -> Result<SPath> {
+	if is_url(value) { fetch_to_file(value) } else { Ok(SPath::new(files::expand_path(value)?)) }
+}
+
+/// Downloads a URL into the cache (unless already there) and returns the cached file.
+/// The file keeps the name of the last URL path segment (e.g., "icons.svg"), so commands see the same name as for a local file.
+pub fn fetch_to_file(url: &str) -> Result<SPath> {
+	let options = http_options();
+	let cache_dir = SPath::new(files::expand_path(&options.cache_dir)?).join(hashes::fnv1a_64_hex(url));
+	let file = cache_dir.join(url_file_name(url));
+
+	if file.exists() && !options.no_cache {
+		return Ok(file);
+	}
+
+	download(url, &file)?;
+
+	Ok(file)
+}
+
+/// Downloads a URL to a file (following redirects, failing on HTTP errors).
+/// The content is written next to the file, then renamed, so an interrupted download never leaves a partial file.
+pub fn download(url: &str, file: &SPath) -> Result<()> {
+	let _span = profile::span("io", "download").arg("url", url);
+	let options = http_options();
+	if let Some(parent) = file.parent() {
+		ensure_dir(parent.as_std_path()).map_err(|e| format!("Failed to create directory '{parent}': {e}"))?;
+	}
+
+	let part_file = SPath::new(format!("{file}.part"));
+	let output = curl_command(url)?
+		.args(["-sS", "--fail", "--location", "--proto", "=http,https"])
+		.args(["--max-time", &options.timeout_secs.to_string()])
+		.args(["-o", part_file.as_str(), url])
+		.output()
+		.map_err(|e| Error::tool_exec("curl", "curl", e))?;
+
+	if !output.status.success() {
+		let _ = std::fs::remove_file(part_file.as_std_path());
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!("Failed to fetch '{url}': {}", stderr.trim())));
+	}
+
+	std::fs::rename(part_file.as_std_path(), file.as_std_path())
+		.map_err(|e| format!("Failed to write the download of '{url}' to '{file}': {e}"))?;
+
+	Ok(())
+}
+
+/// A response of `get`.
+#[derive(Debug)]
+pub struct HttpResponse {
+	pub status: u16,
+	pub headers: Vec<(String, String)>,
+	pub body: String,
+}
+
+impl HttpResponse {
+	/// Returns the value of a header (case-insensitive name).
+	pub fn header(&self, name: &str) -> Option<&str> {
+		self.headers
+			.iter()
+			.find(|(n, _)| n.eq_ignore_ascii_case(name))
+			.map(|(_, v)| v.as_str())
+	}
+
+	pub fn is_success(&self) -> bool {
+		(200..300).contains(&self.status)
+	}
+}
+
+/// GETs a URL, returning the response whatever its status (only transport failures are errors).
+/// The headers are passed to curl on stdin, so secrets (e.g., API tokens) are not visible in the process list.
+pub fn get(url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse> {
+	let options = http_options();
+	let mut child = curl_command(url)?
+		.args(["-sS", "--proto", "=http,https", "--max-time", &options.timeout_secs.to_string()])
+		.args(["--dump-header", "-", "-H", "@-", url])
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.map_err(|e| Error::tool_exec("curl", "curl", e))?;
+
+	if let Some(mut stdin) = child.stdin.take() {
+		let header_lines: String = headers.iter().map(|(name, value)| format!("{name}: {value}\n")).collect();
+		stdin
+			.write_all(header_lines.as_bytes())
+			.map_err(|e| format!("Cannot write the request headers. Cause: {e}"))?;
+	}
+
+	let output = child.wait_with_output().map_err(Error::custom_from_err)?;
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!("GET '{url}' failed: {}", stderr.trim())));
+	}
+
+	parse_curl_response(&String::from_utf8_lossy(&output.stdout))
+		.ok_or_else(|| Error::custom(format!("GET '{url}' failed: invalid HTTP response")))
+}
+
+/// Returns the status of a URL, after the redirects: a HEAD request, then a GET of the first byte for the servers
+/// not supporting HEAD (405, 501). Only the transport failures are errors (e.g., unknown host, timeout).
+pub fn url_status(url: &str) -> Result<u16> {
+	match request_status(url, true)? {
+		405 | 501 => request_status(url, false),
+		status => Ok(status),
+	}
+}
+
+fn request_status(url: &str, head: bool) -> Result<u16> {
+	let options = http_options();
+	let mut command = curl_command(url)?;
+	command.args([
+		"-sS",
+		"-L",
+		"--proto",
+		"=http,https",
+		"--max-time",
+		&options.timeout_secs.to_string(),
+	]);
+	if head {
+		command.arg("--head");
+	} else {
+		command.args(["--range", "0-0", "--dump-header", "-"]);
+	}
+	let output = command.arg(url).output().map_err(|e| Error::tool_exec("curl", "curl", e))?;
+	let method = if head { "HEAD" } else { "GET" };
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(Error::custom(format!("{method} '{url}' failed: {}", stderr.trim())));
+	}
+
+	parse_curl_response(&String::from_utf8_lossy(&output.stdout))
+		.map(|response| response.status)
+		.ok_or_else(|| Error::custom(format!("{method} '{url}' failed: invalid HTTP response")))
+}
+
+/// Parses the output of `curl --dump-header -`: the header blocks (several with a proxy `CONNECT`
+/// or redirects), then the body. The last header block is the response.
+pub fn parse_curl_response(raw: &str) -> Option<HttpResponse> {
+	let mut rest = raw;
+	let mut response = None;
+
+	while rest.starts_with("HTTP/") {
+		let (block, body) = rest
+			.split_once("\r\n\r\n")
+			.or_else(|| rest.split_once("\n\n"))
+			.unwrap_or((rest, ""));
+		let mut lines = block.lines();
+		let status = lines.next()?.split_whitespace().nth(1)?.parse::<u16>().ok()?;
+		let headers = lines
+			.filter_map(|line| line.split_once(':'))
+			.map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+			.collect();
+		response = Some(HttpResponse { status, headers, body: String::new() });
+		rest = body;
+	}
+
+	response.map(|response| HttpResponse { body: rest.to_string(), ..response })
+}
+
+/// Returns a `curl` command with the proxy and TLS settings (the caller adds the request arguments), for a request
+/// to `url` (an error in offline mode, see `check_network`).
+/// curl reads `HTTPS_PROXY`, `ALL_PROXY`, and `NO_PROXY` itself, but only the lowercase `http_proxy`,
+/// so `HTTP_PROXY` is passed as `http_proxy` when that one is not set.
+pub fn curl_command(url: &str) -> Result<Command> {
+	check_network(url)?;
+	let options = http_options();
+	let mut command = Command::new("curl");
+
+	for (name, value) in proxy_env_fallbacks(|name| std::env::var(name).ok()) {
+		command.env(name, value);
+	}
+
+	if let Some(cacert) = &options.cacert {
+		command.args(["--cacert", cacert]);
+	}
+
+	if options.insecure {
+		static INSECURE_WARNING: Once = Once::new();
+		INSECURE_WARNING.call_once(|| {
+			console::eprint_warning(
+				"TLS certificate verification is DISABLED (--insecure). Any server, or anyone between, can impersonate the \
+				 hosts. Prefer --cacert with the CA bundle of your proxy",
+			)
+		});
+		command.arg("--insecure");
+	}
+
+	Ok(command)
+}
+
+/// Returns the proxy env vars to set for curl: `http_proxy` from `HTTP_PROXY`, when only the uppercase one is set.
+pub fn proxy_env_fallbacks(lookup: impl Fn(&str) -> Option<String>) -> Vec<(&'static str, String)> {
+	let is_set = |name: &str| lookup(name).is_some_and(|value| !value.is_empty());
+
+	match lookup("HTTP_PROXY") {
+		Some(value) if !value.is_empty() && !is_set("http_proxy") => vec![("http_proxy", value)],
+		_ => Vec::new(),
+	}
+}
+
+/// Returns the file name of a URL: its last path segment (without query or fragment), sanitized,
+/// or "download" when the path has none.
+pub fn url_file_name(url: &str) -> String {
+	let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+	let path = without_scheme.split(['?', '#']).next().unwrap_or_default();
+	// Without the host
+	let name = path
+		.split_once('/')
+		.and_then(|(_, path)| path.split('/').rfind(|segment| !segment.is_empty()));
+
+	match name {
+		Some(name) => files::sanitize_file_name(name),
+		None => "download".to_string(),
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_http_url_file_name_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let urls = [
+			"https://cdn.example.com/assets/icons.svg",
+			"https://cdn.example.com/assets/logo.png?v=3#top",
+			"https://cdn.example.com/assets/",
+			"https://cdn.example.com",
+			"https://example.com/a:b.svg",
+		];
+
+		// -- Exec
+		let names: Vec<String> = urls.iter().map(|url| url_file_name(url)).collect();
+
+		// -- Check
+		assert_eq!(names, ["icons.svg", "logo.png", "assets", "download", "a_b.svg"]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_http_parse_curl_response_with_proxy_connect() -> Result<()> {
+		// -- Setup & Fixtures
+		let raw = "HTTP/1.1 200 Connection established\r\n\r\n\
+			HTTP/2 429\r\ncontent-type: application/json\r\nRetry-After: 7\r\n\r\n\
+			{\"status\":429}";
+
+		// -- Exec
+		let response = parse_curl_response(raw).ok_or("Should parse")?;
+
+		// -- Check
+		assert_eq!(response.status, 429);
+		assert_eq!(response.header("retry-after"), Some("7"));
+		assert_eq!(response.body, r#"{"status":429}"#);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_http_proxy_env_fallbacks_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let upper_only = |name: &str| (name == "HTTP_PROXY").then(|| "http://proxy:3128".to_string());
+		let both = |name: &str| match name {
+			"HTTP_PROXY" => Some("http://upper:3128".to_string()),
+			"http_proxy" => Some("http://lower:3128".to_string()),
+			_ => None,
+		};
+
+		// -- Exec
+		let from_upper = proxy_env_fallbacks(upper_only);
+		let from_both = proxy_env_fallbacks(both);
+		let from_none = proxy_env_fallbacks(|_| None);
+
+		// -- Check
+		assert_eq!(from_upper, [("http_proxy", "http://proxy:3128".to_string())]);
+		assert!(from_both.is_empty());
+		assert!(from_none.is_empty());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
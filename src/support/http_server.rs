@@ -0,0 +1,117 @@
+//! Minimal HTTP/1.1 server support (std `TcpStream`, one request per connection, JSON responses),
+//! for the local endpoints (e.g., `webtk serve --ingest`, `webtk export-server`).
+
+use crate::support::strings;
+use crate::{Error, Result};
+use serde_json::{Value, json};
+use std::io::{BufRead as _, BufReader, Read as _, Write as _};
+use std::net::TcpStream;
+
+/// A parsed HTTP request (only what the endpoints need).
+pub struct HttpRequest {
+	pub method: String,
+	pub path: String,
+	pub query: Vec<(String, String)>,
+	pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+	/// Returns the (percent-decoded) value of a query parameter.
+	pub fn query_value(&self, key: &str) -> Option<&str> {
+		self.query.iter().find(|(k, _)| k == key).map(|(_, value)| value.as_str())
+	}
+}
+
+/// An HTTP response with a JSON body.
+pub struct HttpResponse {
+	pub status: u16,
+	pub body: Value,
+}
+
+/// Reads a request (request line, headers, and `Content-Length` body of at most `max_body_bytes`).
+/// Returns the error response for a request that cannot be read.
+pub fn read_request(stream: &mut TcpStream, max_body_bytes: usize) -> core::result::Result<HttpRequest, HttpResponse> {
+	let mut reader = BufReader::new(stream);
+
+	let mut request_line = String::new();
+	reader
+		.read_line(&mut request_line)
+		.map_err(|_| error_response(400, "Cannot read the request"))?;
+	let mut parts = request_line.split_whitespace();
+	let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+		return Err(error_response(400, "Invalid request line"));
+	};
+
+	let mut content_length = 0;
+	loop {
+		let mut line = String::new();
+		reader
+			.read_line(&mut line)
+			.map_err(|_| error_response(400, "Cannot read the request headers"))?;
+		let line = line.trim_end();
+		if line.is_empty() {
+			break;
+		}
+		let Some((name, value)) = line.split_once(':') else { continue };
+		let value = value.trim();
+		if name.eq_ignore_ascii_case("content-length") {
+			content_length = value.parse().map_err(|_| error_response(400, "Invalid Content-Length"))?;
+		} else if name.eq_ignore_ascii_case("transfer-encoding") && !value.eq_ignore_ascii_case("identity") {
+			return Err(error_response(411, "Content-Length required (chunked bodies are not supported)"));
+		}
+	}
+
+	if content_length > max_body_bytes {
+		return Err(error_response(413, &format!("Body too large (max {max_body_bytes} bytes)")));
+	}
+	let mut body = vec![0; content_length];
+	reader
+		.read_exact(&mut body)
+		.map_err(|_| error_response(400, "Cannot read the request body"))?;
+
+	let (path, query) = target.split_once('?').unwrap_or((target, ""));
+	let query = query
+		.split('&')
+		.filter(|pair| !pair.is_empty())
+		.filter_map(|pair| {
+			let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+			Some((strings::percent_decode(key)?, strings::percent_decode(value)?))
+		})
+		.collect();
+
+	Ok(HttpRequest { method: method.to_string(), path: path.to_string(), query, body })
+}
+
+/// Writes a JSON response, and closes the connection.
+pub fn write_response(stream: &mut TcpStream, response: &HttpResponse) -> Result<()> {
+	let body = response.body.to_string();
+	let head = format!(
+		"HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+		response.status,
+		reason_phrase(response.status),
+		body.len()
+	);
+	stream
+		.write_all(head.as_bytes())
+		.and_then(|_| stream.write_all(body.as_bytes()))
+		.map_err(Error::custom_from_err)
+}
+
+/// A JSON response `{"error": message}`.
+pub fn error_response(status: u16, message: &str) -> HttpResponse {
+	HttpResponse { status, body: json!({ "error": message }) }
+}
+
+/// The reason phrase of the status line (e.g., "Not Found" for 404).
+pub fn reason_phrase(status: u16) -> &'static str {
+	match status {
+		200 => "OK",
+		400 => "Bad Request",
+		404 => "Not Found",
+		405 => "Method Not Allowed",
+		411 => "Length Required",
+		413 => "Payload Too Large",
+		500 => "Internal Server Error",
+		_ => "Error",
+	}
+}
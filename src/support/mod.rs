@@ -1,8 +1,25 @@
 // region:    --- Modules
 
+pub mod colors;
+pub mod console;
+pub mod dates;
 pub mod files;
 pub mod globs;
+pub mod hashes;
+pub mod html;
+pub mod http;
+pub mod http_server;
+pub mod path_data;
+pub mod pngs;
+pub mod porcelain;
+pub mod profile;
+pub mod sandbox;
 pub mod strings;
+pub mod threads;
+pub mod tokens;
+pub mod versions;
 pub mod xmls;
+pub mod xmls_stream;
+pub mod zips;
 
 // endregion: --- Modules
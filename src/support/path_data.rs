@@ -0,0 +1,198 @@
+//! The SVG path data (the `d` attribute of a `<path>`): its commands and numbers, rewritten without a full
+//! geometry model (e.g., to flatten the `translate` of the groups around a path).
+
+use crate::support::strings;
+
+/// The decimals of the rewritten coordinates (the additions of a translation are not exact in f64).
+const PATH_DATA_PRECISION: usize = 6;
+
+/// Returns the path data moved by `dx`, `dy`: the absolute coordinates of the commands (and the first moveto,
+/// absolute even when relative) are translated, the relative ones are kept as is.
+/// Returns None when the path data is invalid.
+pub fn translate_path_data(path_data: &str, dx: f64, dy: f64) -> Option<String> {
+	let mut out = String::with_capacity(path_data.len() + 16);
+	let mut tokens = PathTokens { rest: path_data };
+	let mut command: Option<char> = None;
+	let mut is_first = true;
+
+	loop {
+		let cmd = match tokens.next_token()? {
+			Token::End => break,
+			Token::Command(cmd) => {
+				out.push(cmd);
+				cmd
+			}
+			// Implicit repetition of the previous command (after a moveto, the pairs are linetos, same arguments)
+			Token::Number => {
+				out.push(' ');
+				command?
+			}
+		};
+		command = Some(cmd);
+
+		// The first moveto of a path is absolute, even when written relative ("m")
+		let is_absolute = cmd.is_ascii_uppercase() || (is_first && cmd == 'm');
+		is_first = false;
+		let (offset_x, offset_y) = if is_absolute { (dx, dy) } else { (0.0, 0.0) };
+
+		// The argument kinds: x, y, or another number (arc radii, rotation, flags)
+		let args: &[Arg] = match cmd.to_ascii_uppercase() {
+			'M' | 'L' | 'T' => &[Arg::X, Arg::Y],
+			'H' => &[Arg::X],
+			'V' => &[Arg::Y],
+			'C' => &[Arg::X, Arg::Y, Arg::X, Arg::Y, Arg::X, Arg::Y],
+			'S' | 'Q' => &[Arg::X, Arg::Y, Arg::X, Arg::Y],
+			'A' => &[Arg::Other, Arg::Other, Arg::Other, Arg::Flag, Arg::Flag, Arg::X, Arg::Y],
+			'Z' => &[],
+			_ => return None,
+		};
+		for (idx, arg) in args.iter().enumerate() {
+			if idx > 0 {
+				out.push(' ');
+			}
+			match arg {
+				Arg::Flag => out.push(tokens.next_flag()?),
+				Arg::Other => out.push_str(tokens.next_number()?.0),
+				Arg::X | Arg::Y => {
+					let (text, value) = tokens.next_number()?;
+					let offset = if *arg == Arg::X { offset_x } else { offset_y };
+					if offset == 0.0 {
+						out.push_str(text);
+					} else {
+						out.push_str(&strings::format_svg_number(value + offset, Some(PATH_DATA_PRECISION)));
+					}
+				}
+			}
+		}
+		// A "Z" is not repeated implicitly
+		if cmd.eq_ignore_ascii_case(&'z') {
+			command = None;
+		}
+	}
+
+	Some(out)
+}
+
+// region:    --- Support
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Arg {
+	X,
+	Y,
+	Other,
+	Flag,
+}
+
+enum Token {
+	Command(char),
+	Number,
+	End,
+}
+
+struct PathTokens<'a> {
+	rest: &'a str,
+}
+
+impl<'a> PathTokens<'a> {
+	/// Returns the next token, a command letter consumed, or a number not consumed.
+	/// Returns None for an invalid character.
+	fn next_token(&mut self) -> Option<Token> {
+		self.skip_separators();
+		let Some(c) = self.rest.chars().next() else {
+			return Some(Token::End);
+		};
+		if c.is_ascii_alphabetic() {
+			if !"MmLlHhVvCcSsQqTtAaZz".contains(c) {
+				return None;
+			}
+			self.rest = &self.rest[1..];
+			return Some(Token::Command(c));
+		}
+		Some(Token::Number)
+	}
+
+	fn next_number(&mut self) -> Option<(&'a str, f64)> {
+		self.skip_separators();
+		let bytes = self.rest.as_bytes();
+		let mut end = 0;
+		if matches!(bytes.first(), Some(b'+' | b'-')) {
+			end += 1;
+		}
+		let digits_start = end;
+		while end < bytes.len() && bytes[end].is_ascii_digit() {
+			end += 1;
+		}
+		// Note: "1.5.5" is two numbers, "1.5" and ".5"
+		if end < bytes.len() && bytes[end] == b'.' {
+			end += 1;
+			while end < bytes.len() && bytes[end].is_ascii_digit() {
+				end += 1;
+			}
+		}
+		if end == digits_start || &self.rest[digits_start..end] == "." {
+			return None;
+		}
+		if end < bytes.len() && matches!(bytes[end], b'e' | b'E') {
+			let mut exp_end = end + 1;
+			if exp_end < bytes.len() && matches!(bytes[exp_end], b'+' | b'-') {
+				exp_end += 1;
+			}
+			let exp_digits_start = exp_end;
+			while exp_end < bytes.len() && bytes[exp_end].is_ascii_digit() {
+				exp_end += 1;
+			}
+			if exp_end > exp_digits_start {
+				end = exp_end;
+			}
+		}
+		let text = &self.rest[..end];
+		self.rest = &self.rest[end..];
+		Some((text, text.parse().ok()?))
+	}
+
+	/// The arc flags are a single "0" or "1", possibly without separator (e.g., "a1 1 0 00 1 1").
+	fn next_flag(&mut self) -> Option<char> {
+		self.skip_separators();
+		let flag = self.rest.chars().next().filter(|c| *c == '0' || *c == '1')?;
+		self.rest = &self.rest[1..];
+		Some(flag)
+	}
+
+	fn skip_separators(&mut self) {
+		self.rest = self.rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_path_data_translate_path_data_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let fixtures = [
+			("M10,20 L30.5,40 H5 V-2 Z", "M20 0L40.5 20H15V-22Z"),
+			("m1 2 3 4l5-6", "m11 -18 3 4l5 -6"),
+			("M0 0C1 2 3 4 5 6S7 8 9 10", "M10 -20C11 -18 13 -16 15 -14S17 -12 19 -10"),
+			("M0 0A5 5 0 01 10 10a1 1 0 1 0 2 2z", "M10 -20A5 5 0 0 1 20 -10a1 1 0 1 0 2 2z"),
+			("M.5.5L1e1-1", "M10.5 -19.5L20 -21"),
+		];
+
+		// -- Exec & Check
+		for (path_data, expected) in fixtures {
+			assert_eq!(translate_path_data(path_data, 10.0, -20.0).as_deref(), Some(expected), "{path_data}");
+		}
+		assert_eq!(translate_path_data("M0 0 X1", 1.0, 1.0), None);
+		assert_eq!(translate_path_data("M0", 1.0, 1.0), None);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
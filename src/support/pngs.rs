@@ -0,0 +1,81 @@
+//! PNG chunk reading (no decoding), e.g., to tell an animated PNG (APNG) from a still one.
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Returns true when a PNG is animated (an `acTL` chunk before its image data).
+pub fn is_apng(content: &[u8]) -> bool {
+	if !content.starts_with(PNG_SIGNATURE) {
+		return false;
+	}
+
+	// Each chunk: length (4 bytes, big endian), type (4), data, CRC (4)
+	let mut pos = PNG_SIGNATURE.len();
+	while pos + 8 <= content.len() {
+		let length = u32::from_be_bytes([content[pos], content[pos + 1], content[pos + 2], content[pos + 3]]) as usize;
+		match &content[pos + 4..pos + 8] {
+			b"acTL" => return true,
+			b"IDAT" | b"IEND" => return false,
+			_ => pos = pos.saturating_add(12).saturating_add(length),
+		}
+	}
+	false
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	/// Returns a chunk with its length, type, data, and a (not checked) zero CRC.
+	fn chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+		let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+		chunk.extend_from_slice(kind);
+		chunk.extend_from_slice(data);
+		chunk.extend_from_slice(&[0; 4]);
+		chunk
+	}
+
+	fn png(chunks: &[Vec<u8>]) -> Vec<u8> {
+		let mut content = PNG_SIGNATURE.to_vec();
+		for chunk in chunks {
+			content.extend_from_slice(chunk);
+		}
+		content
+	}
+
+	#[test]
+	fn test_support_pngs_is_apng_animated() -> Result<()> {
+		// -- Setup & Fixtures
+		let ihdr = chunk(b"IHDR", &[0; 13]);
+		// acTL: the frame count (2) and the play count (0, forever)
+		let actl = chunk(b"acTL", &[0, 0, 0, 2, 0, 0, 0, 0]);
+		let content = png(&[ihdr, actl, chunk(b"IDAT", &[0; 8]), chunk(b"IEND", &[])]);
+
+		// -- Exec & Check
+		assert!(is_apng(&content));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_pngs_is_apng_still() -> Result<()> {
+		// -- Setup & Fixtures
+		let ihdr = chunk(b"IHDR", &[0; 13]);
+		let still = png(&[ihdr.clone(), chunk(b"IDAT", &[0; 8]), chunk(b"IEND", &[])]);
+		// An acTL after the image data does not make an APNG
+		let late_actl = png(&[ihdr, chunk(b"IDAT", &[0; 8]), chunk(b"acTL", &[0, 0, 0, 2, 0, 0, 0, 0])]);
+
+		// -- Exec & Check
+		assert!(!is_apng(&still));
+		assert!(!is_apng(&late_actl));
+		assert!(!is_apng(b"GIF89a"));
+		assert!(!is_apng(&still[..10]));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,118 @@
+//! The machine-friendly output of `--porcelain` (like git's): a header line with the format version, then one
+//! record per line, `<type>` and its fields separated by tabs. A version never changes once released (the human
+//! output can), new record types or trailing fields come with a new version.
+//! The fields escape `\`, tab, newline, and carriage return as `\\`, `\t`, `\n`, and `\r`.
+
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PorcelainVersion {
+	#[default]
+	V1,
+}
+
+impl PorcelainVersion {
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::V1 => "v1",
+		}
+	}
+}
+
+impl TryFrom<&str> for PorcelainVersion {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self> {
+		match value {
+			"v1" => Ok(Self::V1),
+			other => Err(Error::custom(format!("Invalid porcelain version '{other}'. Expected: v1"))),
+		}
+	}
+}
+
+/// Returns the header line of a version (e.g., "# porcelain v1").
+pub fn format_header(version: PorcelainVersion) -> String {
+	format!("# porcelain {}", version.as_str())
+}
+
+/// Returns the line of a record (e.g., "file\tout/ico-add.svg").
+pub fn format_record(record_type: &str, fields: &[&str]) -> String {
+	let mut line = record_type.to_string();
+	for field in fields {
+		line.push('\t');
+		line.push_str(&escape_field(field));
+	}
+	line
+}
+
+/// Prints the header line on stdout (see `format_header`).
+pub fn print_header(version: PorcelainVersion) {
+	println!("{}", format_header(version));
+}
+
+/// Prints a record on stdout (see `format_record`).
+pub fn print_record(record_type: &str, fields: &[&str]) {
+	println!("{}", format_record(record_type, fields));
+}
+
+fn escape_field(field: &str) -> String {
+	let mut escaped = String::with_capacity(field.len());
+	for ch in field.chars() {
+		match ch {
+			'\\' => escaped.push_str("\\\\"),
+			'\t' => escaped.push_str("\\t"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => escaped.push_str("\\r"),
+			ch => escaped.push(ch),
+		}
+	}
+	escaped
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_porcelain_format_header_v1() -> Result<()> {
+		// -- Exec & Check
+		// The v1 lines are a contract with the scripts, this test must not change
+		assert_eq!(format_header(PorcelainVersion::V1), "# porcelain v1");
+		assert_eq!(PorcelainVersion::try_from("v1")?, PorcelainVersion::V1);
+		assert!(PorcelainVersion::try_from("v2").is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_porcelain_format_record_simple() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(format_record("file", &["out/ico-add.svg"]), "file\tout/ico-add.svg");
+		assert_eq!(format_record("item", &["A1B2", "Icons", "ico/add"]), "item\tA1B2\tIcons\tico/add");
+		assert_eq!(format_record("ok", &[]), "ok");
+		assert_eq!(format_record("item", &["A1B2", "", "ico/add"]), "item\tA1B2\t\tico/add");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_porcelain_format_record_escape() -> Result<()> {
+		// -- Setup & Fixtures
+		let message = "line 1\nline 2\twith a tab\r, and C:\\path";
+
+		// -- Exec
+		let line = format_record("warning", &[message]);
+
+		// -- Check
+		assert_eq!(line, "warning\tline 1\\nline 2\\twith a tab\\r, and C:\\\\path");
+		assert_eq!(line.lines().count(), 1);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
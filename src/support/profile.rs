@@ -0,0 +1,164 @@
+//! The run profile (`--profile-output FILE`): timed spans of the command, the tool invocations (sketchtool), the
+//! transforms, and the file IO, written in the Chrome trace format (open it in `about://tracing` or Perfetto).
+//! The spans are only recorded once `enable` is called, so `span` costs nothing otherwise.
+
+use crate::Result;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use simple_fs::SPath;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::Instant;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static STARTED: OnceLock<Instant> = OnceLock::new();
+static EVENTS: Mutex<Vec<TraceEvent>> = Mutex::new(Vec::new());
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+	/// The trace id of the current thread (1 for the first recording thread, the main one).
+	static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A complete event ("ph": "X") of the Chrome trace format, times in microseconds from the start of the profile.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEvent {
+	pub name: String,
+	/// The span kind (e.g., "command", "tool", "transform", "io").
+	pub cat: String,
+	pub ts: u64,
+	pub dur: u64,
+	pub tid: u64,
+	#[serde(skip_serializing_if = "Map::is_empty")]
+	pub args: Map<String, Value>,
+}
+
+/// A running span, recorded when dropped.
+pub struct Span {
+	event: Option<TraceEvent>,
+}
+
+impl Span {
+	/// Adds an argument shown with the span (e.g., the file).
+	pub fn arg(mut self, key: &str, value: impl Into<Value>) -> Self {
+		if let Some(event) = self.event.as_mut() {
+			event.args.insert(key.to_string(), value.into());
+		}
+		self
+	}
+}
+
+impl Drop for Span {
+	fn drop(&mut self) {
+		if let Some(mut event) = self.event.take() {
+			event.dur = elapsed_micros().saturating_sub(event.ts);
+			EVENTS.lock().unwrap_or_else(PoisonError::into_inner).push(event);
+		}
+	}
+}
+
+/// Starts recording the spans of the process.
+pub fn enable() {
+	STARTED.get_or_init(Instant::now);
+	ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+	ENABLED.load(Ordering::Relaxed)
+}
+
+/// Starts a span, ended when the returned `Span` is dropped (a no-op when the profile is not enabled).
+pub fn span(cat: &str, name: impl Into<String>) -> Span {
+	if !is_enabled() {
+		return Span { event: None };
+	}
+	let event = TraceEvent {
+		name: name.into(),
+		cat: cat.to_string(),
+		ts: elapsed_micros(),
+		dur: 0,
+		tid: THREAD_ID.with(|id| *id),
+		args: Map::new(),
+	};
+	Span { event: Some(event) }
+}
+
+/// Writes the spans recorded so far as a Chrome trace JSON file.
+pub fn write_trace(trace_file: &SPath) -> Result<()> {
+	let events = EVENTS.lock().unwrap_or_else(PoisonError::into_inner).clone();
+	let content = serde_json::to_string(&trace_json(&events, std::process::id()))?;
+	std::fs::write(trace_file.as_std_path(), content).map_err(|e| format!("Failed to write '{trace_file}': {e}"))?;
+	Ok(())
+}
+
+/// Returns the Chrome trace JSON object of the events: the process name, then the events by start time.
+pub fn trace_json(events: &[TraceEvent], pid: u32) -> Value {
+	let mut events: Vec<&TraceEvent> = events.iter().collect();
+	events.sort_by_key(|event| (event.ts, event.tid));
+
+	let mut trace_events = vec![json!({ "name": "process_name", "ph": "M", "pid": pid, "args": { "name": "webtk" } })];
+	for event in events {
+		let mut value = serde_json::to_value(event).unwrap_or_default();
+		if let Some(object) = value.as_object_mut() {
+			object.insert("ph".to_string(), json!("X"));
+			object.insert("pid".to_string(), json!(pid));
+		}
+		trace_events.push(value);
+	}
+
+	json!({ "traceEvents": trace_events, "displayTimeUnit": "ms" })
+}
+
+// region:    --- Support
+
+fn elapsed_micros() -> u64 {
+	STARTED
+		.get()
+		.map(|started| started.elapsed().as_micros() as u64)
+		.unwrap_or_default()
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_profile_trace_json_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let event = |name: &str, cat: &str, ts: u64, tid: u64| TraceEvent {
+			name: name.to_string(),
+			cat: cat.to_string(),
+			ts,
+			dur: 10,
+			tid,
+			args: Map::new(),
+		};
+		let mut tool = event("sketchtool export artboards", "tool", 20, 2);
+		tool.args.insert("file".to_string(), json!("icons.sketch"));
+		let events = [tool, event("sketch export", "command", 0, 1)];
+
+		// -- Exec
+		let trace = trace_json(&events, 42);
+
+		// -- Check
+		let trace_events = trace["traceEvents"].as_array().ok_or("Should have traceEvents")?;
+		assert_eq!(trace_events.len(), 3);
+		assert_eq!(trace_events[0]["ph"], "M");
+		assert_eq!(trace_events[1]["name"], "sketch export");
+		assert_eq!(trace_events[1]["ph"], "X");
+		assert_eq!(trace_events[1]["pid"], 42);
+		assert!(trace_events[1].get("args").is_none());
+		assert_eq!(trace_events[2]["tid"], 2);
+		assert_eq!(trace_events[2]["args"]["file"], "icons.sketch");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,85 @@
+//! The restricted execution profile of the design tool child processes (e.g., sketchtool): a scrubbed environment,
+//! so the credentials of a CI job (tokens, cloud keys) are not visible to them, and a confined working directory.
+//! The extra variables to pass through are set with `--allow-env KEY`.
+
+use crate::{Error, Result};
+use simple_fs::SPath;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// The environment variables always passed through (system paths, user, temp dir, locale, time zone).
+pub const SANDBOX_BASE_ENV: &[&str] = &[
+	"PATH", "HOME", "USER", "LOGNAME", "SHELL", "TMPDIR", "TMP", "TEMP", "LANG", "LC_ALL", "LC_CTYPE", "TZ",
+];
+
+static ALLOWED_ENV: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Sets the extra environment variables passed through (from the global `--allow-env` options).
+/// Only the first call applies.
+pub fn set_allowed_env(keys: Vec<String>) -> Result<()> {
+	if let Some(key) = keys.iter().find(|key| key.is_empty() || key.contains('=')) {
+		return Err(Error::custom(format!("Invalid environment variable name '{key}' (--allow-env)")));
+	}
+	let _ = ALLOWED_ENV.set(keys);
+	Ok(())
+}
+
+/// Returns a command of `program` with the sandbox environment (`sandbox_env`) and `work_dir` as working directory.
+/// The paths passed to the program must be absolute (the working directory is not the current one).
+pub fn sandboxed_command(program: &str, work_dir: &SPath) -> Command {
+	let allowed = ALLOWED_ENV.get().map(Vec::as_slice).unwrap_or_default();
+	let mut command = Command::new(program);
+	command
+		.env_clear()
+		.envs(sandbox_env(|key| std::env::var(key).ok(), allowed))
+		.current_dir(work_dir.as_std_path());
+	command
+}
+
+/// Returns the environment of a sandboxed command: the `SANDBOX_BASE_ENV` and `allowed` variables that are set.
+pub fn sandbox_env(lookup: impl Fn(&str) -> Option<String>, allowed: &[String]) -> Vec<(String, String)> {
+	let mut env: Vec<(String, String)> = Vec::new();
+	for key in SANDBOX_BASE_ENV.iter().copied().chain(allowed.iter().map(String::as_str)) {
+		if env.iter().any(|(name, _)| name == key) {
+			continue;
+		}
+		if let Some(value) = lookup(key) {
+			env.push((key.to_string(), value));
+		}
+	}
+	env
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_sandbox_sandbox_env_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let lookup = |key: &str| match key {
+			"PATH" => Some("/usr/bin:/bin".to_string()),
+			"HOME" => Some("/Users/ci".to_string()),
+			"AWS_SECRET_ACCESS_KEY" => Some("secret".to_string()),
+			"SKETCH_LICENSE" => Some("license".to_string()),
+			_ => None,
+		};
+		let allowed = ["SKETCH_LICENSE".to_string(), "HOME".to_string(), "NOT_SET".to_string()];
+
+		// -- Exec
+		let env = sandbox_env(lookup, &allowed);
+
+		// -- Check
+		let keys: Vec<&str> = env.iter().map(|(key, _)| key.as_str()).collect();
+		assert_eq!(keys, ["PATH", "HOME", "SKETCH_LICENSE"]);
+		assert_eq!(env[2].1, "license");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 /// Canonicalizes a name by replacing non-alphanumeric characters with dashes
 /// and collapsing consecutive dashes into a single dash.
 pub fn canonicalize_name(name: &str) -> String {
@@ -19,6 +21,210 @@ pub fn canonicalize_name(name: &str) -> String {
 	result.trim_matches('-').to_string()
 }
 
+/// Returns `name`, or `name-2`, `name-3`, ... if already taken, and marks the returned name as taken.
+pub fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+	let mut candidate = name.to_string();
+	let mut n = 2;
+	while taken.contains(&candidate) {
+		candidate = format!("{name}-{n}");
+		n += 1;
+	}
+	taken.insert(candidate.clone());
+	candidate
+}
+
+/// Returns the Levenshtein edit distance between two strings (in chars).
+pub fn edit_distance(a: &str, b: &str) -> usize {
+	let b_chars: Vec<char> = b.chars().collect();
+	let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+	let mut curr: Vec<usize> = vec![0; b_chars.len() + 1];
+
+	for (i, a_ch) in a.chars().enumerate() {
+		curr[0] = i + 1;
+		for (j, b_ch) in b_chars.iter().enumerate() {
+			let cost = if a_ch == *b_ch { 0 } else { 1 };
+			curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+		}
+		std::mem::swap(&mut prev, &mut curr);
+	}
+
+	prev[b_chars.len()]
+}
+
+/// Returns the candidate closest to `value` (by edit distance), if close enough to be a likely typo
+/// (at most a third of the value length, and at least 1), for "did you mean" hints.
+pub fn closest_match<'a>(value: &str, candidates: &[&'a str]) -> Option<&'a str> {
+	let threshold = (value.chars().count() / 3).max(1);
+	candidates
+		.iter()
+		.map(|candidate| (edit_distance(value, candidate), *candidate))
+		.filter(|(distance, _)| *distance <= threshold)
+		.min_by_key(|(distance, _)| *distance)
+		.map(|(_, candidate)| candidate)
+}
+
+/// Returns true if the name is a `/`-separated path of lowercase kebab-case segments
+/// (e.g., "ico/user-fill"; not "Ico/User Fill", "ico//user", or "ico/user-").
+pub fn is_kebab_path(name: &str) -> bool {
+	!name.is_empty()
+		&& name.split('/').all(|segment| {
+			!segment.is_empty()
+				&& segment
+					.split('-')
+					.all(|word| !word.is_empty() && word.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()))
+		})
+}
+
+/// Formats a byte count for humans (e.g., "512 B", "1.5 KB", "2.3 MB"), with 1 KB = 1024 bytes.
+pub fn format_bytes(bytes: u64) -> String {
+	const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+	if bytes < 1024 {
+		return format!("{bytes} B");
+	}
+	let mut value = bytes as f64 / 1024.0;
+	let mut unit = UNITS[0];
+	for next_unit in &UNITS[1..] {
+		if value < 1024.0 {
+			break;
+		}
+		value /= 1024.0;
+		unit = next_unit;
+	}
+
+	format!("{value:.1} {unit}")
+}
+
+/// Parses a byte count, a number with an optional unit (e.g., "300000", "200KB", "1.5 MB"), 1 KB = 1024 bytes
+/// like `format_bytes`. Returns None when invalid.
+pub fn parse_bytes(value: &str) -> Option<u64> {
+	let value = value.trim();
+	let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+	let (number, unit) = value.split_at(split);
+	let number: f64 = number.parse().ok()?;
+	let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+		"" | "B" => 1,
+		"K" | "KB" => 1024,
+		"M" | "MB" => 1024 * 1024,
+		"G" | "GB" => 1024 * 1024 * 1024,
+		_ => return None,
+	};
+	Some((number * multiplier as f64).round() as u64)
+}
+
+/// Decodes a percent-encoded URL component (e.g., a query value), with `+` as a space.
+/// Returns None for an invalid escape or a result that is not UTF-8.
+pub fn percent_decode(value: &str) -> Option<String> {
+	let bytes = value.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+
+	let mut idx = 0;
+	while idx < bytes.len() {
+		match bytes[idx] {
+			b'%' => {
+				let hex = value.get(idx + 1..idx + 3)?;
+				decoded.push(u8::from_str_radix(hex, 16).ok()?);
+				idx += 3;
+			}
+			b'+' => {
+				decoded.push(b' ');
+				idx += 1;
+			}
+			byte => {
+				decoded.push(byte);
+				idx += 1;
+			}
+		}
+	}
+
+	String::from_utf8(decoded).ok()
+}
+
+/// Percent-encodes a URL path (e.g., "ico/user fill.svg" -> "ico/user%20fill.svg"): all the bytes but the
+/// unreserved characters (`A-Z a-z 0-9 - . _ ~`), `@` (e.g., "logo@2x.png"), and `/`.
+pub fn percent_encode_path(path: &str) -> String {
+	let mut encoded = String::with_capacity(path.len());
+	for byte in path.bytes() {
+		if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'@' | b'/') {
+			encoded.push(byte as char);
+		} else {
+			encoded.push_str(&format!("%{byte:02X}"));
+		}
+	}
+	encoded
+}
+
+/// Parses a ratio from 0 to 1, as a percentage (e.g., "0.1%" -> 0.001) or a number (e.g., "0.25").
+/// Returns None when invalid or out of range.
+pub fn parse_ratio(value: &str) -> Option<f64> {
+	let value = value.trim();
+	let ratio = match value.strip_suffix('%') {
+		Some(percent) => percent.trim().parse::<f64>().ok()? / 100.0,
+		None => value.parse::<f64>().ok()?,
+	};
+	(0.0..=1.0).contains(&ratio).then_some(ratio)
+}
+
+/// Formats a number for the generated files: at most `decimals` decimals, without trailing zeros
+/// (e.g., 0.5 -> "0.5", 16.0 -> "16").
+pub fn format_number(value: f64, decimals: usize) -> String {
+	let formatted = format!("{value:.decimals$}");
+	let formatted = if formatted.contains('.') {
+		formatted.trim_end_matches('0').trim_end_matches('.')
+	} else {
+		formatted.as_str()
+	};
+	if formatted == "-0" { "0".to_string() } else { formatted.to_string() }
+}
+
+/// Formats a number for the SVG attributes, the same on all platforms and Rust versions: the shortest decimal that
+/// reads back as the same value (None), or rounded to at most `precision` decimals (Some), without trailing zeros,
+/// "-0", or exponent notation (e.g., 1e-7 -> "0.0000001"). Non-finite values (NaN, infinity) are "0".
+pub fn format_svg_number(value: f64, precision: Option<usize>) -> String {
+	if !value.is_finite() {
+		return "0".to_string();
+	}
+	match precision {
+		Some(decimals) => format_number(value, decimals),
+		// The f64 Display is the shortest round-trip representation, never in exponent notation
+		None if value == 0.0 => "0".to_string(),
+		None => value.to_string(),
+	}
+}
+
+/// Formats a CSV field: quoted (with the quotes doubled) when it has a comma, a quote, or a line break.
+pub fn csv_field(value: &str) -> String {
+	if value.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_string()
+	}
+}
+
+/// Parses a CSV line into its fields (the reverse of `csv_field`, quoted fields on a single line).
+pub fn parse_csv_line(line: &str) -> Vec<String> {
+	let mut fields = Vec::new();
+	let mut field = String::new();
+	let mut in_quotes = false;
+	let mut chars = line.chars().peekable();
+
+	while let Some(ch) = chars.next() {
+		match ch {
+			'"' if in_quotes && chars.peek() == Some(&'"') => {
+				field.push('"');
+				chars.next();
+			}
+			'"' if in_quotes => in_quotes = false,
+			'"' if field.is_empty() => in_quotes = true,
+			',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+			ch => field.push(ch),
+		}
+	}
+	fields.push(field);
+
+	fields
+}
+
 // region:    --- Tests
 
 #[cfg(test)]
@@ -82,6 +288,175 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_support_strings_unique_name_collisions() -> Result<()> {
+		// -- Setup & Fixtures
+		let mut taken = HashSet::new();
+
+		// -- Exec
+		let names: Vec<String> = ["ico-user", "ico-user", "ico-home", "ico-user"]
+			.iter()
+			.map(|name| unique_name(name, &mut taken))
+			.collect();
+
+		// -- Check
+		assert_eq!(names, vec!["ico-user", "ico-user-2", "ico-home", "ico-user-3"]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_edit_distance_simple() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(edit_distance("ico/usr", "ico/user"), 1);
+		assert_eq!(edit_distance("kitten", "sitting"), 3);
+		assert_eq!(edit_distance("", "abc"), 3);
+		assert_eq!(edit_distance("same", "same"), 0);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_closest_match_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let candidates = ["formats", "globs", "output", "sketch_file"];
+
+		// -- Exec & Check
+		assert_eq!(closest_match("formts", &candidates), Some("formats"));
+		assert_eq!(closest_match("sketch-file", &candidates), Some("sketch_file"));
+		assert_eq!(closest_match("ouput", &candidates), Some("output"));
+		assert_eq!(closest_match("flatten", &candidates), None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_is_kebab_path_simple() -> Result<()> {
+		// -- Exec & Check
+		assert!(is_kebab_path("ico/user-fill"));
+		assert!(is_kebab_path("illus/hero-2x"));
+		assert!(!is_kebab_path("Ico/User Fill"));
+		assert!(!is_kebab_path("ico//user"));
+		assert!(!is_kebab_path("ico/user-"));
+		assert!(!is_kebab_path("ico/user_fill"));
+		assert!(!is_kebab_path(""));
+
+		Ok(())
+	}
+	#[test]
+	fn test_support_strings_format_bytes_simple() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(format_bytes(512), "512 B");
+		assert_eq!(format_bytes(1536), "1.5 KB");
+		assert_eq!(format_bytes(5 * 1024 * 1024 + 300 * 1024), "5.3 MB");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_parse_bytes_simple() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(parse_bytes("300000"), Some(300000));
+		assert_eq!(parse_bytes("200KB"), Some(200 * 1024));
+		assert_eq!(parse_bytes("1.5 mb"), Some(1536 * 1024));
+		assert_eq!(parse_bytes("12 B"), Some(12));
+		assert_eq!(parse_bytes("KB"), None);
+		assert_eq!(parse_bytes("2 TB"), None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_percent_decode_simple() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(percent_decode("ico%2Fuser%2Ffill").as_deref(), Some("ico/user/fill"));
+		assert_eq!(percent_decode("ico/user+line").as_deref(), Some("ico/user line"));
+		assert_eq!(percent_decode("caf%C3%A9").as_deref(), Some("café"));
+		assert_eq!(percent_decode("bad%2"), None);
+		assert_eq!(percent_decode("bad%zz"), None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_percent_encode_path_simple() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(percent_encode_path("ico/user-fill.svg"), "ico/user-fill.svg");
+		assert_eq!(percent_encode_path("ico/user fill@2x.png"), "ico/user%20fill@2x.png");
+		assert_eq!(percent_encode_path("ico/a#b?c.svg"), "ico/a%23b%3Fc.svg");
+		assert_eq!(percent_encode_path("café.svg"), "caf%C3%A9.svg");
+		assert_eq!(percent_decode(&percent_encode_path("a b/c+d")).as_deref(), Some("a b/c+d"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_parse_ratio_simple() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(parse_ratio("0.1%"), Some(0.001));
+		assert_eq!(parse_ratio("25 %"), Some(0.25));
+		assert_eq!(parse_ratio("0.5"), Some(0.5));
+		assert_eq!(parse_ratio("150%"), None);
+		assert_eq!(parse_ratio("-1"), None);
+		assert_eq!(parse_ratio("abc%"), None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_format_number_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let values = [16.0, 0.5, 1.333333, -0.0001];
+
+		// -- Exec
+		let formatted: Vec<String> = values.iter().map(|v| format_number(*v, 3)).collect();
+
+		// -- Check
+		assert_eq!(formatted, vec!["16", "0.5", "1.333", "0"]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_format_svg_number_simple() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(format_svg_number(12.5, None), "12.5");
+		assert_eq!(format_svg_number(0.1 + 0.2, None), "0.30000000000000004");
+		assert_eq!(format_svg_number(0.1 + 0.2, Some(4)), "0.3");
+		assert_eq!(format_svg_number(1e-7, None), "0.0000001");
+		assert_eq!(format_svg_number(1e21, None), "1000000000000000000000");
+		assert_eq!(format_svg_number(-0.0, None), "0");
+		assert_eq!(format_svg_number(-0.00001, Some(2)), "0");
+		assert_eq!(format_svg_number(f64::NAN, None), "0");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_csv_field_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let values = ["Sign in", "Hello, world", "Say \"hi\"", "Line 1\nLine 2"];
+
+		// -- Exec
+		let fields: Vec<String> = values.iter().map(|v| csv_field(v)).collect();
+
+		// -- Check
+		assert_eq!(fields, vec!["Sign in", "\"Hello, world\"", "\"Say \"\"hi\"\"\"", "\"Line 1\nLine 2\""]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_strings_parse_csv_line_quoted() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(parse_csv_line("ico/user,ico/person"), vec!["ico/user", "ico/person"]);
+		assert_eq!(parse_csv_line("\"Hello, world\",\"Say \"\"hi\"\"\""), vec!["Hello, world", "Say \"hi\""]);
+		assert_eq!(parse_csv_line("a,,"), vec!["a", "", ""]);
+		assert_eq!(parse_csv_line(""), vec![""]);
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
@@ -0,0 +1,63 @@
+//! The concurrent runs of the independent items (e.g., the export jobs, the external link checks).
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+/// Runs `f` on the items from up to `parallel` threads (the items are started in order),
+/// and returns the results in the order of the items.
+pub fn run_concurrently<T: Sync, R: Send>(items: &[T], parallel: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+	let next_idx = AtomicUsize::new(0);
+	let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+	std::thread::scope(|scope| {
+		for _ in 0..parallel.clamp(1, items.len().max(1)) {
+			scope.spawn(|| {
+				loop {
+					let idx = next_idx.fetch_add(1, Ordering::Relaxed);
+					let Some(item) = items.get(idx) else {
+						break;
+					};
+					let result = f(item);
+					results.lock().unwrap_or_else(PoisonError::into_inner)[idx] = Some(result);
+				}
+			});
+		}
+	});
+
+	results
+		.into_inner()
+		.unwrap_or_else(PoisonError::into_inner)
+		.into_iter()
+		.flatten()
+		.collect()
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_threads_run_concurrently_order() -> Result<()> {
+		// -- Setup & Fixtures
+		let items: Vec<u64> = (0..20).collect();
+
+		// -- Exec
+		let results = run_concurrently(&items, 4, |item| {
+			// The first items finish last
+			std::thread::sleep(std::time::Duration::from_millis(20 - item));
+			item * 2
+		});
+
+		// -- Check
+		assert_eq!(results, items.iter().map(|item| item * 2).collect::<Vec<_>>());
+		assert!(run_concurrently(&[] as &[u64], 4, |item| *item).is_empty());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
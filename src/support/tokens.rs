@@ -0,0 +1,96 @@
+//! `{name}` / `{name:arg}` token templating (e.g., "cdn/{date:%Y%m%d}-{git_short_sha}/").
+
+use crate::{Error, Result};
+
+/// Returns true if the template has at least one `{...}` token.
+pub fn has_tokens(template: &str) -> bool {
+	template.replace("{{", "").contains('{')
+}
+
+/// Replaces the `{name}` and `{name:arg}` tokens of a template with the values of the resolver.
+/// `{{` and `}}` are literal braces.
+/// The resolver returns `Ok(None)` for unknown tokens, which is an error.
+pub fn render_tokens<F>(template: &str, mut resolve_fn: F) -> Result<String>
+where
+	F: FnMut(&str, Option<&str>) -> Result<Option<String>>,
+{
+	let mut result = String::with_capacity(template.len());
+	let mut rest = template;
+
+	while let Some(idx) = rest.find(['{', '}']) {
+		result.push_str(&rest[..idx]);
+		let after = &rest[idx..];
+
+		if after.starts_with("{{") || after.starts_with("}}") {
+			result.push_str(&after[..1]);
+			rest = &after[2..];
+			continue;
+		}
+		if after.starts_with('}') {
+			return Err(Error::custom(format!("Unmatched '}}' in '{template}'")));
+		}
+
+		let end = after
+			.find('}')
+			.ok_or_else(|| Error::custom(format!("Unclosed token in '{template}'")))?;
+		let token = &after[1..end];
+		let (name, arg) = match token.split_once(':') {
+			Some((name, arg)) => (name.trim(), Some(arg)),
+			None => (token.trim(), None),
+		};
+
+		let value = resolve_fn(name, arg)?
+			.ok_or_else(|| Error::custom(format!("Unknown token '{{{token}}}' in '{template}'")))?;
+		result.push_str(&value);
+		rest = &after[end + 1..];
+	}
+
+	result.push_str(rest);
+	Ok(result)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_tokens_render_tokens_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let template = "cdn/{date:%Y%m%d}-{git_short_sha}/{{raw}}";
+
+		// -- Exec
+		let result = render_tokens(template, |name, arg| {
+			Ok(match (name, arg) {
+				("date", Some(fmt)) => Some(format!("D[{fmt}]")),
+				("git_short_sha", None) => Some("abc123".to_string()),
+				_ => None,
+			})
+		})?;
+
+		// -- Check
+		assert_eq!(result, "cdn/D[%Y%m%d]-abc123/{raw}");
+		assert!(has_tokens(template));
+		assert!(!has_tokens("cdn/{{raw}}"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_tokens_render_tokens_unknown_err() -> Result<()> {
+		// -- Exec
+		let unknown = render_tokens("out/{nope}", |_, _| Ok(None));
+		let unclosed = render_tokens("out/{date", |_, _| Ok(Some(String::new())));
+
+		// -- Check
+		assert!(unknown.is_err());
+		assert!(unclosed.is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
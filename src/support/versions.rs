@@ -0,0 +1,117 @@
+//! Version numbers: parsing and ordering of "MAJOR.MINOR.PATCH[-PRERELEASE]" (e.g., release tags "v0.2.0-beta.1").
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A parsed version. A version with a pre-release is lower than the same version without
+/// (e.g., "0.2.0-beta.1" < "0.2.0").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+	pub numbers: [u64; 3],
+	pub pre_release: Option<String>,
+}
+
+impl Version {
+	/// Parses a version, with an optional "v" prefix and missing numbers as 0 (e.g., "v1.2" is 1.2.0).
+	pub fn parse(value: &str) -> Option<Self> {
+		let value = value.trim();
+		let value = value.strip_prefix('v').unwrap_or(value);
+		let (core, pre_release) = match value.split_once('-') {
+			Some((core, pre_release)) => (core, Some(pre_release.to_string())),
+			None => (value, None),
+		};
+
+		let mut numbers = [0; 3];
+		let parts: Vec<&str> = core.split('.').collect();
+		if parts.is_empty() || parts.len() > 3 {
+			return None;
+		}
+		for (number, part) in numbers.iter_mut().zip(parts) {
+			*number = part.parse().ok()?;
+		}
+
+		Some(Self { numbers, pre_release })
+	}
+}
+
+impl fmt::Display for Version {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let [major, minor, patch] = self.numbers;
+		write!(f, "{major}.{minor}.{patch}")?;
+		if let Some(pre_release) = &self.pre_release {
+			write!(f, "-{pre_release}")?;
+		}
+		Ok(())
+	}
+}
+
+impl Ord for Version {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.numbers
+			.cmp(&other.numbers)
+			.then_with(|| match (&self.pre_release, &other.pre_release) {
+				(None, None) => Ordering::Equal,
+				(None, Some(_)) => Ordering::Greater,
+				(Some(_), None) => Ordering::Less,
+				(Some(a), Some(b)) => compare_pre_releases(a, b),
+			})
+	}
+}
+
+impl PartialOrd for Version {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+/// Compares the pre-releases by dot-separated identifiers, numerically when both are numbers
+/// (e.g., "beta.2" < "beta.10").
+fn compare_pre_releases(a: &str, b: &str) -> Ordering {
+	let mut a_ids = a.split('.');
+	let mut b_ids = b.split('.');
+	loop {
+		match (a_ids.next(), b_ids.next()) {
+			(None, None) => return Ordering::Equal,
+			(None, Some(_)) => return Ordering::Less,
+			(Some(_), None) => return Ordering::Greater,
+			(Some(a_id), Some(b_id)) => {
+				let ordering = match (a_id.parse::<u64>(), b_id.parse::<u64>()) {
+					(Ok(a_num), Ok(b_num)) => a_num.cmp(&b_num),
+					_ => a_id.cmp(b_id),
+				};
+				if ordering != Ordering::Equal {
+					return ordering;
+				}
+			}
+		}
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_versions_parse_and_order() -> Result<()> {
+		// -- Setup & Fixtures
+		let versions = ["v0.2.0", "0.1.1-WIP", "0.2.0-beta.10", "0.1.1", "0.2.0-beta.2", "0.10"];
+
+		// -- Exec
+		let mut parsed: Vec<Version> = versions.iter().filter_map(|v| Version::parse(v)).collect();
+		parsed.sort();
+
+		// -- Check
+		let ordered: Vec<String> = parsed.iter().map(|v| v.to_string()).collect();
+		assert_eq!(parsed.len(), versions.len());
+		assert_eq!(ordered, ["0.1.1-WIP", "0.1.1", "0.2.0-beta.2", "0.2.0-beta.10", "0.2.0", "0.10.0"]);
+		assert!(Version::parse("1.x").is_none());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
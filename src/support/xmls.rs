@@ -1,42 +1,77 @@
 //! High-level XML utilities using xmltree.
 
+use crate::{Error, Result};
 use xmltree::{Element, EmitterConfig, XMLNode};
 
-/// Extracts an attribute value from an XML string's root element.
-/// Returns None if the attribute is not found or the content is invalid.
-pub fn extract_root_attribute(xml_content: &str, attr_name: &str) -> Option<String> {
-	let root = Element::parse(xml_content.as_bytes()).ok()?;
-	root.attributes.get(attr_name).cloned()
-}
-
-/// Extracts the inner content of the root element as a list of XMLNode.
-/// Returns the children of the root element.
-pub fn extract_root_inner_nodes(xml_content: &str) -> Option<Vec<XMLNode>> {
-	let root = Element::parse(xml_content.as_bytes()).ok()?;
-	Some(root.children)
+/// Transforms all `id` attribute values, and the references to them, in a list of XMLNodes.
+/// References are `url(#id)` in any attribute (e.g., `fill`, `clip-path`, `mask`, `style`)
+/// and `href="#id"` (including `xlink:href`), so renamed ids stay coordinated.
+/// Returns the transformed nodes.
+pub fn transform_id_references<F>(nodes: Vec<XMLNode>, transform_fn: F) -> Vec<XMLNode>
+where
+	F: Fn(&str) -> String,
+{
+	transform_nodes_attributes(nodes, &|name: &str, value: &str| {
+		Some(transform_id_reference_value(name, value, &transform_fn))
+	})
 }
 
-/// Extracts the inner content of the root element as a string.
-/// Returns the serialized children of the root element.
-#[allow(unused)]
-pub fn extract_root_inner_content(xml_content: &str) -> Option<String> {
-	let nodes = extract_root_inner_nodes(xml_content)?;
-	Some(nodes_to_string(&nodes))
+/// Returns the value of an attribute with its id or id references transformed.
+/// - `id` values are transformed as a whole
+/// - `href` / `xlink:href` values starting with `#` have their fragment transformed
+/// - `url(#id)` occurrences (unquoted, single, or double quoted) are transformed in any other attribute
+pub fn transform_id_reference_value<F>(attr_name: &str, value: &str, transform_fn: &F) -> String
+where
+	F: Fn(&str) -> String,
+{
+	let local_name = attr_name.rsplit(':').next().unwrap_or(attr_name);
+	match local_name {
+		"id" => transform_fn(value),
+		"href" => match value.strip_prefix('#') {
+			Some(id) => format!("#{}", transform_fn(id)),
+			None => value.to_string(),
+		},
+		_ => transform_url_references(value, transform_fn),
+	}
 }
 
-/// Transforms all `id` attribute values in a list of XMLNodes using the provided function.
-/// Returns the transformed nodes.
-pub fn transform_nodes_id_attributes<F>(nodes: Vec<XMLNode>, transform_fn: F) -> Vec<XMLNode>
+/// Transforms the `#id` part of each `url(#id)` occurrence in a value.
+fn transform_url_references<F>(value: &str, transform_fn: &F) -> String
 where
 	F: Fn(&str) -> String,
 {
-	let mut nodes = nodes;
-	for node in &mut nodes {
-		if let Some(elem) = node.as_mut_element() {
-			transform_element_ids_recursive(elem, &transform_fn);
+	let mut result = String::with_capacity(value.len());
+	let mut rest = value;
+
+	while let Some(start) = rest.find("url(") {
+		let (before, after) = rest.split_at(start + 4);
+		result.push_str(before);
+
+		let Some(end) = after.find(')') else {
+			rest = after;
+			break;
+		};
+		let inner = &after[..end];
+		let trimmed = inner.trim();
+		let quote = trimmed.chars().next().filter(|c| *c == '"' || *c == '\'');
+		let unquoted = match quote {
+			Some(q) => trimmed.strip_prefix(q).and_then(|v| v.strip_suffix(q)).unwrap_or(trimmed),
+			None => trimmed,
+		};
+
+		match unquoted.strip_prefix('#') {
+			Some(id) => {
+				let q = quote.map(String::from).unwrap_or_default();
+				result.push_str(&format!("{q}#{}{q}", transform_fn(id)));
+			}
+			None => result.push_str(inner),
 		}
+		result.push(')');
+		rest = &after[end + 1..];
 	}
-	nodes
+
+	result.push_str(rest);
+	result
 }
 
 /// Converts a list of XMLNodes to a string.
@@ -58,21 +93,291 @@ pub fn nodes_to_string(nodes: &[XMLNode]) -> String {
 	result.trim().to_string()
 }
 
-/// Recursively transforms id attributes in an element and its children.
-fn transform_element_ids_recursive<F>(element: &mut Element, transform_fn: &F)
+/// Normalizes an XML string for content comparison (hashing).
+/// Removes the XML declaration, collapses whitespace runs into a single space,
+/// and drops whitespace between tags, so formatting-only differences are ignored.
+pub fn normalize_markup(xml_content: &str) -> String {
+	let mut content = xml_content.trim();
+
+	// Remove the `<?xml ... ?>` declaration if present
+	if content.starts_with("<?xml")
+		&& let Some(end) = content.find("?>")
+	{
+		content = content[end + 2..].trim_start();
+	}
+
+	let mut result = String::with_capacity(content.len());
+	let mut pending_space = false;
+	for ch in content.chars() {
+		if ch.is_whitespace() {
+			pending_space = true;
+			continue;
+		}
+		if pending_space && ch != '<' && !result.ends_with('>') && !result.is_empty() {
+			result.push(' ');
+		}
+		pending_space = false;
+		result.push(ch);
+	}
+
+	result
+}
+
+/// Escapes a value to be safely placed in a double-quoted XML attribute.
+pub fn escape_attribute_value(value: &str) -> String {
+	let mut result = String::with_capacity(value.len());
+	for ch in value.chars() {
+		match ch {
+			'&' => result.push_str("&amp;"),
+			'<' => result.push_str("&lt;"),
+			'>' => result.push_str("&gt;"),
+			'"' => result.push_str("&quot;"),
+			_ => result.push(ch),
+		}
+	}
+	result
+}
+
+// region:    --- Select
+
+/// Selects the elements of a parsed root element matching a CSS-like selector (e.g., `svg > defs > linearGradient[id]`).
+/// Returns the matched elements in document order.
+///
+/// Supported syntax:
+/// - Tag name (optionally prefixed, e.g., `sketch:page`) or `*`
+/// - `#id`, `[attr]`, `[attr=value]` (value may be quoted)
+/// - Descendant (`a b`) and child (`a > b`) combinators
+///
+/// As in CSS, the first compound can match any element, including the root.
+/// Note: xmltree keys attributes by local name, so `[xlink:href]` matches `href`.
+pub fn select_elements<'a>(root: &'a Element, selector: &str) -> Result<Vec<&'a Element>> {
+	let steps = parse_selector(selector)?;
+	let mut matched: Vec<&Element> = Vec::new();
+	collect_descendant_matches(root, &steps, 0, &mut matched);
+	// An element can be reached through several paths with descendant combinators.
+	let mut unique: Vec<&Element> = Vec::with_capacity(matched.len());
+	for el in matched {
+		if !unique.iter().any(|u| std::ptr::eq(*u, el)) {
+			unique.push(el);
+		}
+	}
+	Ok(unique)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+	Descendant,
+	Child,
+}
+
+#[derive(Debug, Default)]
+struct Compound {
+	/// `None` for `*`.
+	tag: Option<String>,
+	/// `(name, expected_value)`, where `None` value means presence only.
+	attrs: Vec<(String, Option<String>)>,
+}
+
+#[derive(Debug)]
+struct Step {
+	/// How this step relates to the previous one (ignored for the first step).
+	combinator: Combinator,
+	compound: Compound,
+}
+
+fn parse_selector(selector: &str) -> Result<Vec<Step>> {
+	let invalid = |reason: &str| Error::custom(format!("Invalid selector '{selector}'. {reason}"));
+
+	let mut steps: Vec<Step> = Vec::new();
+	let mut combinator = Combinator::Descendant;
+	let mut chars = selector.trim().chars().peekable();
+
+	while let Some(&ch) = chars.peek() {
+		if ch.is_whitespace() {
+			chars.next();
+			continue;
+		}
+		if ch == '>' {
+			if steps.is_empty() || combinator == Combinator::Child {
+				return Err(invalid("Unexpected '>'."));
+			}
+			combinator = Combinator::Child;
+			chars.next();
+			continue;
+		}
+
+		let mut compound = Compound::default();
+
+		// -- Tag name or `*`
+		if ch == '*' {
+			chars.next();
+		} else {
+			let tag = take_name(&mut chars);
+			if !tag.is_empty() {
+				compound.tag = Some(tag);
+			} else if ch != '#' && ch != '[' {
+				return Err(invalid(&format!("Unsupported character '{ch}'.")));
+			}
+		}
+
+		// -- `#id` and `[attr(=value)]` qualifiers
+		while let Some(&c) = chars.peek() {
+			match c {
+				'#' => {
+					chars.next();
+					let id = take_name(&mut chars);
+					if id.is_empty() {
+						return Err(invalid("Missing id after '#'."));
+					}
+					compound.attrs.push(("id".to_string(), Some(id)));
+				}
+				'[' => {
+					chars.next();
+					let mut body = String::new();
+					loop {
+						match chars.next() {
+							Some(']') => break,
+							Some(c) => body.push(c),
+							None => return Err(invalid("Missing ']'.")),
+						}
+					}
+					let (name, value) = match body.split_once('=') {
+						Some((name, value)) => (name.trim(), Some(unquote(value.trim()).to_string())),
+						None => (body.trim(), None),
+					};
+					if name.is_empty() || !name.chars().all(is_name_char) {
+						return Err(invalid(&format!("Invalid attribute name '{name}'.")));
+					}
+					compound.attrs.push((name.to_string(), value));
+				}
+				c if c.is_whitespace() || c == '>' => break,
+				c => return Err(invalid(&format!("Unsupported character '{c}'."))),
+			}
+		}
+
+		steps.push(Step { combinator, compound });
+		combinator = Combinator::Descendant;
+	}
+
+	if steps.is_empty() {
+		return Err(invalid("Empty selector."));
+	}
+	if combinator == Combinator::Child {
+		return Err(invalid("Selector cannot end with '>'."));
+	}
+
+	Ok(steps)
+}
+
+fn collect_matches<'a>(element: &'a Element, steps: &[Step], idx: usize, matched: &mut Vec<&'a Element>) {
+	let Some(step) = steps.get(idx) else {
+		return;
+	};
+
+	if compound_matches(element, &step.compound) {
+		if idx + 1 == steps.len() {
+			matched.push(element);
+		} else {
+			let next = &steps[idx + 1];
+			for child in child_elements(element) {
+				match next.combinator {
+					Combinator::Child => collect_matches(child, steps, idx + 1, matched),
+					Combinator::Descendant => collect_descendant_matches(child, steps, idx + 1, matched),
+				}
+			}
+		}
+	}
+}
+
+/// Tries the step at `idx` on the element and all its descendants.
+fn collect_descendant_matches<'a>(element: &'a Element, steps: &[Step], idx: usize, matched: &mut Vec<&'a Element>) {
+	collect_matches(element, steps, idx, matched);
+	for child in child_elements(element) {
+		collect_descendant_matches(child, steps, idx, matched);
+	}
+}
+
+fn compound_matches(element: &Element, compound: &Compound) -> bool {
+	if let Some(tag) = &compound.tag {
+		let tag_matches = match tag.split_once(':') {
+			Some((prefix, name)) => element.prefix.as_deref() == Some(prefix) && element.name == name,
+			None => element.name == *tag,
+		};
+		if !tag_matches {
+			return false;
+		}
+	}
+
+	compound.attrs.iter().all(|(name, expected)| {
+		// xmltree keys attributes by local name
+		let local_name = name.rsplit(':').next().unwrap_or(name);
+		match (element.attributes.get(local_name), expected) {
+			(Some(actual), Some(expected)) => actual == expected,
+			(Some(_), None) => true,
+			(None, _) => false,
+		}
+	})
+}
+
+fn child_elements(element: &Element) -> impl Iterator<Item = &Element> {
+	element.children.iter().filter_map(|node| node.as_element())
+}
+
+fn take_name(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+	let mut name = String::new();
+	while let Some(&c) = chars.peek() {
+		if !is_name_char(c) {
+			break;
+		}
+		name.push(c);
+		chars.next();
+	}
+	name
+}
+
+fn is_name_char(c: char) -> bool {
+	c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')
+}
+
+fn unquote(value: &str) -> &str {
+	value
+		.strip_prefix('"')
+		.and_then(|v| v.strip_suffix('"'))
+		.or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+		.unwrap_or(value)
+}
+
+// endregion: --- Select
+/// Applies the attribute rewrite function on all the elements of a list of XMLNodes.
+fn transform_nodes_attributes<F>(nodes: Vec<XMLNode>, rewrite_fn: &F) -> Vec<XMLNode>
 where
-	F: Fn(&str) -> String,
+	F: Fn(&str, &str) -> Option<String>,
+{
+	let mut nodes = nodes;
+	for node in &mut nodes {
+		if let Some(elem) = node.as_mut_element() {
+			transform_element_attributes_recursive(elem, rewrite_fn);
+		}
+	}
+	nodes
+}
+
+/// Recursively rewrites the attributes of an element and its children.
+/// The rewrite function takes `(attr_name, value)` and returns the new value, or None to keep it.
+fn transform_element_attributes_recursive<F>(element: &mut Element, rewrite_fn: &F)
+where
+	F: Fn(&str, &str) -> Option<String>,
 {
-	// Transform id attribute if present
-	if let Some(id_value) = element.attributes.get("id").cloned() {
-		let transformed = transform_fn(&id_value);
-		element.attributes.insert("id".to_string(), transformed);
+	for (name, value) in element.attributes.iter_mut() {
+		if let Some(new_value) = rewrite_fn(name, value) {
+			*value = new_value;
+		}
 	}
 
 	// Recurse into children
 	for child in &mut element.children {
 		if let Some(child_elem) = child.as_mut_element() {
-			transform_element_ids_recursive(child_elem, transform_fn);
+			transform_element_attributes_recursive(child_elem, rewrite_fn);
 		}
 	}
 }
@@ -115,111 +420,102 @@ mod tests {
 	use super::*;
 
 	#[test]
-	fn test_support_xmls_extract_root_attribute_simple() -> Result<()> {
+	fn test_support_xmls_transform_id_references_simple() -> Result<()> {
 		// -- Setup & Fixtures
-		let xml = r#"<svg viewBox="0 0 24 24" width="24" height="24"></svg>"#;
+		let xml = r##"<svg>
+    <clipPath id="clip"/>
+    <g class="a" clip-path="url(#clip)"/>
+</svg>"##;
 
 		// -- Exec
-		let result = extract_root_attribute(xml, "viewBox");
+		let nodes = Element::parse(xml.as_bytes())?.children;
+		let nodes = transform_id_references(nodes, |id| format!("p-{id}"));
+		let result = nodes_to_string(&nodes);
 
 		// -- Check
-		assert_eq!(result, Some("0 0 24 24".to_string()));
+		assert!(result.contains(r#"id="p-clip""#));
+		assert!(result.contains(r#"class="a""#));
+		assert!(result.contains(r#"clip-path="url(#p-clip)""#));
 
 		Ok(())
 	}
 
 	#[test]
-	fn test_support_xmls_extract_root_attribute_not_found() -> Result<()> {
+	fn test_support_xmls_normalize_markup_formatting_insensitive() -> Result<()> {
 		// -- Setup & Fixtures
-		let xml = r#"<svg width="24" height="24"></svg>"#;
+		let xml_a = r#"<?xml version="1.0" encoding="UTF-8"?>
+<svg viewBox="0 0 24 24">
+    <g   fill="none">
+        <path d="M0 0"/>
+    </g>
+</svg>"#;
+		let xml_b = r#"<svg viewBox="0 0 24 24"><g fill="none"><path d="M0 0"/></g></svg>"#;
 
 		// -- Exec
-		let result = extract_root_attribute(xml, "viewBox");
+		let norm_a = normalize_markup(xml_a);
+		let norm_b = normalize_markup(xml_b);
 
 		// -- Check
-		assert_eq!(result, None);
+		assert_eq!(norm_a, norm_b);
+		assert_eq!(norm_b, xml_b);
 
 		Ok(())
 	}
 
 	#[test]
-	fn test_support_xmls_extract_root_inner_content_simple() -> Result<()> {
-		// -- Setup & Fixtures
-		let xml = r#"<svg viewBox="0 0 24 24"><path d="M0 0"/></svg>"#;
-
+	fn test_support_xmls_escape_attribute_value_simple() -> Result<()> {
 		// -- Exec
-		let result = extract_root_inner_content(xml);
+		let result = escape_attribute_value(r#"a & "b" <c>"#);
 
 		// -- Check
-		let content = result.ok_or("Should have content")?;
-		assert!(content.contains("path"));
-		assert!(content.contains(r#"d="M0 0""#));
+		assert_eq!(result, "a &amp; &quot;b&quot; &lt;c&gt;");
 
 		Ok(())
 	}
 
 	#[test]
-	fn test_support_xmls_extract_root_inner_content_with_xml_decl() -> Result<()> {
+	fn test_support_xmls_select_elements_child_and_attr() -> Result<()> {
 		// -- Setup & Fixtures
-		let xml = r##"<?xml version="1.0" encoding="UTF-8"?>
-<svg width="16px" height="16px" viewBox="0 0 16 16" version="1.1" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
-    <title>ico/chevron-down</title>
-    <g id="ico/chevron-down" stroke="none" fill="none" fill-rule="evenodd">
-        <polygon id="Shape" fill="#CECECE" points="3.41 4.58 8 9.17 12.59 4.58 14 6 8 12 2 6"></polygon>
-    </g>
+		let xml = r##"<svg xmlns="http://www.w3.org/2000/svg">
+    <defs>
+        <linearGradient id="grad-a"/>
+        <linearGradient/>
+        <g><linearGradient id="nested"/></g>
+    </defs>
+    <linearGradient id="outside"/>
 </svg>"##;
 
-		// -- Exec
-		let result = extract_root_inner_content(xml);
-
-		// -- Check
-		let content = result.ok_or("Should have content")?;
-		// Note: xmltree may output <title /> for empty-looking elements or handle whitespace differently
-		assert!(content.contains("title"), "content should contain 'title', got: {content}");
-		assert!(content.contains("ico/chevron-down"), "content should contain the title text");
-		assert!(
-			content.contains("g id=") || content.contains("g\n") || content.contains("<g"),
-			"content should contain g element"
-		);
-		assert!(content.contains("polygon"), "content should contain polygon");
-		assert!(content.contains("points="), "content should contain points attribute");
-
-		Ok(())
-	}
-
-	#[test]
-	fn test_support_xmls_extract_root_inner_content_empty() -> Result<()> {
-		// -- Setup & Fixtures
-		let xml = r#"<svg viewBox="0 0 24 24"/>"#;
+		let root = Element::parse(xml.as_bytes())?;
 
 		// -- Exec
-		let result = extract_root_inner_content(xml);
+		let child = select_elements(&root, "svg > defs > linearGradient[id]")?;
+		let descendant = select_elements(&root, "svg linearGradient[id]")?;
+		let by_id = select_elements(&root, "defs #nested")?;
 
 		// -- Check
-		assert_eq!(result, Some(String::new()));
+		let ids =
+			|els: &[&Element]| -> Vec<String> { els.iter().filter_map(|e| e.attributes.get("id").cloned()).collect() };
+		assert_eq!(ids(&child), vec!["grad-a"]);
+		assert_eq!(ids(&descendant), vec!["grad-a", "nested", "outside"]);
+		assert_eq!(ids(&by_id), vec!["nested"]);
 
 		Ok(())
 	}
 
 	#[test]
-	fn test_support_xmls_transform_nodes_id_attributes_siblings() -> Result<()> {
+	fn test_support_xmls_select_elements_attr_value_and_invalid() -> Result<()> {
 		// -- Setup & Fixtures
-		let xml = r##"<svg>
-    <title>ico/chevron-down</title>
-    <g id="ico/chevron-down">
-        <polygon id="Shape"/>
-    </g>
-</svg>"##;
+		let xml = r#"<svg><g fill="none"><path fill="red"/><path fill="blue"/></g></svg>"#;
+		let root = Element::parse(xml.as_bytes())?;
 
 		// -- Exec
-		let nodes = extract_root_inner_nodes(xml).ok_or("Should have nodes")?;
-		let transformed = transform_nodes_id_attributes(nodes, |id| id.replace('/', "-"));
-		let result = nodes_to_string(&transformed);
+		let red = select_elements(&root, r#"* > g path[fill="red"]"#)?;
+		let invalid = select_elements(&root, "svg > > g");
 
 		// -- Check
-		assert!(result.contains("<title>"));
-		assert!(result.contains(r#"id="ico-chevron-down""#));
-		assert!(result.contains(r#"id="Shape""#));
+		assert_eq!(red.len(), 1);
+		assert_eq!(red[0].name, "path");
+		assert!(invalid.is_err());
 
 		Ok(())
 	}
@@ -0,0 +1,755 @@
+//! Streaming XML utilities using quick-xml.
+//!
+//! Unlike `xmls` (xmltree based), these functions never build a document tree.
+//! They work directly on the source text and only rewrite what they need to,
+//! so untouched content is passed through byte-for-byte.
+//!
+//! The names and values handed to the callbacks are borrowed from the source (`Cow` values only owned when
+//! unescaped), so the cost stays linear on multi-MB illustrations. The transforms that need the whole document
+//! (e.g., the export checks selectors and content hashes) keep the `xmls` tree.
+
+use crate::support::xmls;
+use quick_xml::escape::{minimal_escape, unescape};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// Extracts an attribute value (unescaped) from an XML string's root element.
+/// Returns None if the attribute is not found or the content is invalid.
+pub fn extract_root_attribute(xml_content: &str, attr_name: &str) -> Option<String> {
+	let mut reader = Reader::from_str(xml_content);
+
+	loop {
+		match reader.read_event().ok()? {
+			Event::Start(start) | Event::Empty(start) => {
+				let attr = start.try_get_attribute(attr_name).ok()??;
+				return attr.unescape_value().ok().map(|v| v.into_owned());
+			}
+			Event::Eof => return None,
+			_ => continue,
+		}
+	}
+}
+
+/// Extracts the inner content of the root element, exactly as written in the source.
+/// Returns an empty string for a self-closing root, and None if the content is invalid.
+pub fn extract_root_inner_content(xml_content: &str) -> Option<&str> {
+	let mut reader = Reader::from_str(xml_content);
+
+	loop {
+		match reader.read_event().ok()? {
+			Event::Start(start) => {
+				let end_name = start.to_end().into_owned();
+				let span = reader.read_to_end(end_name.name()).ok()?;
+				return xml_content.get(span.start as usize..span.end as usize);
+			}
+			Event::Empty(_) => return Some(""),
+			Event::Eof => return None,
+			_ => continue,
+		}
+	}
+}
+
+/// A child element of the root, as written in the source.
+#[derive(Debug, Clone)]
+pub struct RawElement<'a> {
+	/// The attributes of the element (unescaped), in source order.
+	pub attributes: Vec<(String, String)>,
+	/// The full element markup, from its start tag to its end tag.
+	pub markup: &'a str,
+	/// The whitespace preceding the start tag on its line (empty if the tag does not start the line).
+	pub line_indent: &'a str,
+}
+
+/// Extracts the direct children of the root element with the given tag name, exactly as written in the source.
+/// Returns None if the content is invalid.
+pub fn extract_root_child_elements<'a>(xml_content: &'a str, tag_name: &str) -> Option<Vec<RawElement<'a>>> {
+	let mut reader = Reader::from_str(xml_content);
+	let mut elements = Vec::new();
+	let mut depth = 0;
+
+	loop {
+		let event_start = reader.buffer_position() as usize;
+		match reader.read_event().ok()? {
+			Event::Start(start) if depth == 1 && start.name().as_ref() == tag_name.as_bytes() => {
+				let attributes = read_attributes(&start)?;
+				let end_name = start.to_end().into_owned();
+				reader.read_to_end(end_name.name()).ok()?;
+				let markup = xml_content.get(event_start..reader.buffer_position() as usize)?;
+				let line_indent = line_indent_before(xml_content, event_start);
+				elements.push(RawElement { attributes, markup, line_indent });
+			}
+			Event::Empty(start) if depth == 1 && start.name().as_ref() == tag_name.as_bytes() => {
+				let attributes = read_attributes(&start)?;
+				let markup = xml_content.get(event_start..reader.buffer_position() as usize)?;
+				let line_indent = line_indent_before(xml_content, event_start);
+				elements.push(RawElement { attributes, markup, line_indent });
+			}
+			Event::Start(_) => depth += 1,
+			Event::End(_) => depth -= 1,
+			Event::Eof => break,
+			_ => continue,
+		}
+	}
+
+	Some(elements)
+}
+
+// region:    --- Namespaces
+
+/// Extracts the namespace declarations of the root element, as `(attribute_name, uri)` pairs
+/// (e.g., `("xmlns", "http://www.w3.org/2000/svg")`, `("xmlns:xlink", "http://www.w3.org/1999/xlink")`).
+/// Returns None if the content is invalid.
+pub fn extract_root_namespace_declarations(xml_content: &str) -> Option<Vec<(String, String)>> {
+	let mut reader = Reader::from_str(xml_content);
+
+	loop {
+		match reader.read_event().ok()? {
+			Event::Start(start) | Event::Empty(start) => {
+				let declarations = read_attributes(&start)?
+					.into_iter()
+					.filter(|(name, _)| name == "xmlns" || name.starts_with("xmlns:"))
+					.collect();
+				return Some(declarations);
+			}
+			Event::Eof => return None,
+			_ => continue,
+		}
+	}
+}
+
+/// Returns the namespace prefixes used by the element and attribute names of an XML content (document or fragment).
+/// The `xmlns` declarations themselves and the reserved `xml` prefix are not included.
+/// Returns None if the content is invalid.
+pub fn used_namespace_prefixes(xml_content: &str) -> Option<BTreeSet<&str>> {
+	let mut reader = Reader::from_str(xml_content);
+	let mut prefixes = BTreeSet::new();
+
+	loop {
+		match reader.read_event().ok()? {
+			Event::Start(start) | Event::Empty(start) => {
+				if let Some(prefix) = start.name().prefix() {
+					prefixes.insert(source_str(xml_content, prefix.as_ref())?);
+				}
+				for attr in start.attributes() {
+					let attr = attr.ok()?;
+					if let Some(prefix) = attr.key.prefix() {
+						prefixes.insert(source_str(xml_content, prefix.as_ref())?);
+					}
+				}
+			}
+			Event::Eof => break,
+			_ => continue,
+		}
+	}
+
+	prefixes.remove("xmlns");
+	prefixes.remove("xml");
+	Some(prefixes)
+}
+
+/// Filters namespace declarations (see `extract_root_namespace_declarations`) to the ones needed by a content:
+/// the default namespace, plus the prefixes used in the content.
+pub fn needed_namespace_declarations(declarations: &[(String, String)], xml_content: &str) -> Vec<(String, String)> {
+	let used_prefixes = used_namespace_prefixes(xml_content).unwrap_or_default();
+
+	declarations
+		.iter()
+		.filter(|(name, _)| match name.strip_prefix("xmlns:") {
+			Some(prefix) => used_prefixes.contains(prefix),
+			None => true,
+		})
+		.cloned()
+		.collect()
+}
+
+/// Deliberately strips namespaces from an XML content (document or fragment).
+/// For each prefix, removes the `xmlns:prefix` declarations, the `prefix:*` attributes,
+/// and the `prefix:*` elements (with their content). Everything else is kept byte-for-byte.
+/// Returns None if the content is invalid.
+pub fn strip_namespaces(xml_content: &str, prefixes: &[&str]) -> Option<String> {
+	if prefixes.is_empty() {
+		return Some(xml_content.to_string());
+	}
+
+	let mut reader = Reader::from_str(xml_content);
+	let mut patches: Vec<(Range<usize>, String)> = Vec::new();
+
+	let has_stripped_prefix =
+		|qname_prefix: Option<&[u8]>| qname_prefix.is_some_and(|p| prefixes.iter().any(|sp| sp.as_bytes() == p));
+
+	loop {
+		let event_start = reader.buffer_position() as usize;
+		match reader.read_event().ok()? {
+			Event::Start(start) if has_stripped_prefix(start.name().prefix().map(|p| p.into_inner())) => {
+				let end_name = start.to_end().into_owned();
+				reader.read_to_end(end_name.name()).ok()?;
+				patches.push((event_start..reader.buffer_position() as usize, String::new()));
+			}
+			Event::Empty(start) if has_stripped_prefix(start.name().prefix().map(|p| p.into_inner())) => {
+				patches.push((event_start..reader.buffer_position() as usize, String::new()));
+			}
+			Event::Start(start) | Event::Empty(start) => {
+				for attr in start.attributes() {
+					let attr = attr.ok()?;
+					let key = attr.key.as_ref();
+					let is_stripped_declaration = key
+						.strip_prefix(b"xmlns:")
+						.is_some_and(|declared| prefixes.iter().any(|sp| sp.as_bytes() == declared));
+					let is_stripped_attr = key != b"xmlns"
+						&& !key.starts_with(b"xmlns:")
+						&& has_stripped_prefix(attr.key.prefix().map(|p| p.into_inner()));
+
+					if is_stripped_declaration || is_stripped_attr {
+						patches.push((attribute_source_range(xml_content, key, &attr.value)?, String::new()));
+					}
+				}
+			}
+			Event::Eof => break,
+			_ => continue,
+		}
+	}
+
+	Some(apply_patches(xml_content, patches))
+}
+
+/// Returns the source range of a whole attribute (`key="value"`), including its leading whitespace.
+fn attribute_source_range(source: &str, key: &[u8], value: &[u8]) -> Option<Range<usize>> {
+	let key_range = source_range(source, key)?;
+	let value_range = source_range(source, value)?;
+
+	// Include the leading whitespace and the closing quote
+	let start = source[..key_range.start].trim_end().len();
+	let end = value_range.end + 1;
+
+	(end <= source.len()).then_some(start..end)
+}
+
+// endregion: --- Namespaces
+
+/// Transforms the values of the given attributes (matched by their full name, e.g., `xlink:href`)
+/// using the provided function. Everything else is kept byte-for-byte.
+/// Returns None if the content is invalid.
+pub fn transform_attributes<F>(xml_content: &str, attr_names: &[&str], transform_fn: F) -> Option<String>
+where
+	F: Fn(&str) -> String,
+{
+	rewrite_attribute_values(xml_content, |name, value| attr_names.contains(&name).then(|| transform_fn(value)))
+}
+
+/// Transforms all `id` attribute values and the references to them (`url(#id)` in any attribute,
+/// `href="#id"` and `xlink:href="#id"`), so renamed ids stay coordinated.
+/// See `xmls::transform_id_reference_value` for the rules.
+/// Returns None if the content is invalid.
+pub fn transform_id_references<F>(xml_content: &str, transform_fn: F) -> Option<String>
+where
+	F: Fn(&str) -> String,
+{
+	rewrite_attribute_values(xml_content, |name, value| {
+		// Most attributes (e.g., path data) cannot reference an id, so they are skipped without a copy
+		let local_name = name.rsplit(':').next().unwrap_or(name);
+		let may_reference = match local_name {
+			"id" => true,
+			"href" => value.starts_with('#'),
+			_ => value.contains("url("),
+		};
+		may_reference.then(|| xmls::transform_id_reference_value(name, value, &transform_fn))
+	})
+}
+
+/// Rewrites the attribute values of the root element only (e.g., the `viewBox`, `width`, and `height` of an SVG).
+/// The rewrite function takes `(attr_name, value)` (unescaped) and returns the new value, or None to keep it.
+/// Everything else is kept byte-for-byte. Returns None if the content is invalid or has no root element.
+pub fn rewrite_root_attributes<F>(xml_content: &str, rewrite_fn: F) -> Option<String>
+where
+	F: Fn(&str, &str) -> Option<String>,
+{
+	let mut reader = Reader::from_str(xml_content);
+
+	loop {
+		match reader.read_event().ok()? {
+			Event::Start(start) | Event::Empty(start) => {
+				let mut patches = Vec::new();
+				collect_value_patches(xml_content, &start, &rewrite_fn, &mut patches)?;
+				return Some(apply_patches(xml_content, patches));
+			}
+			Event::Eof => return None,
+			_ => continue,
+		}
+	}
+}
+
+/// An element of the path of a selected element: its name and attributes (unescaped, in source order),
+/// borrowed from the source.
+pub type PathElement<'a> = (&'a str, Vec<(&'a str, Cow<'a, str>)>);
+
+/// Inserts an attribute (right after the tag name) on the elements selected by the select function,
+/// unless they already have it. The select function takes the path of the element from the root
+/// (the ancestors, then the element), e.g., to resolve an inherited attribute.
+/// Everything else is kept byte-for-byte. Returns None if the content is invalid.
+pub fn insert_attribute<'a, F>(xml_content: &'a str, attr_name: &str, attr_value: &str, select_fn: F) -> Option<String>
+where
+	F: Fn(&[PathElement<'_>]) -> bool,
+{
+	let mut reader = Reader::from_str(xml_content);
+	let mut path: Vec<PathElement<'a>> = Vec::new();
+	let mut patches: Vec<(Range<usize>, String)> = Vec::new();
+	let inserted = format!(" {attr_name}=\"{}\"", minimal_escape(attr_value).replace('"', "&quot;"));
+
+	let mut visit = |start: &BytesStart, path: &mut Vec<PathElement<'a>>| -> Option<()> {
+		let name = source_str(xml_content, start.name().as_ref())?;
+		let attributes = read_source_attributes(xml_content, start)?;
+		let has_attr = attributes.iter().any(|(name, _)| *name == attr_name);
+		path.push((name, attributes));
+		if !has_attr && select_fn(path) {
+			let name_end = source_range(xml_content, start.name().as_ref())?.end;
+			patches.push((name_end..name_end, inserted.clone()));
+		}
+		Some(())
+	};
+
+	loop {
+		match reader.read_event().ok()? {
+			Event::Start(start) => visit(&start, &mut path)?,
+			Event::Empty(start) => {
+				visit(&start, &mut path)?;
+				path.pop();
+			}
+			Event::End(_) => {
+				path.pop();
+			}
+			Event::Eof => break,
+			_ => continue,
+		}
+	}
+
+	Some(apply_patches(xml_content, patches))
+}
+
+/// Returns the values (unescaped) of the attributes selected by the select function, which takes
+/// `(attr_name, value)`, in document order. Returns None if the content is invalid.
+pub fn find_attribute_values<F>(xml_content: &str, select_fn: F) -> Option<Vec<String>>
+where
+	F: Fn(&str, &str) -> bool,
+{
+	let mut reader = Reader::from_str(xml_content);
+	let mut values = Vec::new();
+
+	loop {
+		match reader.read_event().ok()? {
+			Event::Start(start) | Event::Empty(start) => {
+				for (name, value) in read_source_attributes(xml_content, &start)? {
+					if select_fn(name, &value) {
+						values.push(value.into_owned());
+					}
+				}
+			}
+			Event::Eof => break,
+			_ => continue,
+		}
+	}
+
+	Some(values)
+}
+
+/// Removes the attributes selected by the select function, which takes `(attr_name, value)` (unescaped),
+/// with their leading whitespace. Everything else is kept byte-for-byte. Returns None if the content is invalid.
+pub fn remove_attributes<F>(xml_content: &str, select_fn: F) -> Option<String>
+where
+	F: Fn(&str, &str) -> bool,
+{
+	let mut reader = Reader::from_str(xml_content);
+	let mut patches: Vec<(Range<usize>, String)> = Vec::new();
+
+	loop {
+		match reader.read_event().ok()? {
+			Event::Start(start) | Event::Empty(start) => {
+				for attr in start.attributes() {
+					let attr = attr.ok()?;
+					let name = std::str::from_utf8(attr.key.as_ref()).ok()?;
+					let value = unescape(std::str::from_utf8(&attr.value).ok()?).ok()?;
+					if select_fn(name, &value) {
+						patches.push((
+							attribute_source_range(xml_content, attr.key.as_ref(), &attr.value)?,
+							String::new(),
+						));
+					}
+				}
+			}
+			Event::Eof => break,
+			_ => continue,
+		}
+	}
+
+	Some(apply_patches(xml_content, patches))
+}
+
+/// Rewrites the attribute values of all elements.
+/// The rewrite function takes `(attr_name, value)` (unescaped) and returns the new value, or None to keep it.
+fn rewrite_attribute_values<F>(xml_content: &str, rewrite_fn: F) -> Option<String>
+where
+	F: Fn(&str, &str) -> Option<String>,
+{
+	let mut reader = Reader::from_str(xml_content);
+	let mut patches: Vec<(Range<usize>, String)> = Vec::new();
+
+	loop {
+		match reader.read_event().ok()? {
+			Event::Start(start) | Event::Empty(start) => {
+				collect_value_patches(xml_content, &start, &rewrite_fn, &mut patches)?;
+			}
+			Event::Eof => break,
+			_ => continue,
+		}
+	}
+
+	Some(apply_patches(xml_content, patches))
+}
+
+/// Returns the whitespace between the start of the line and `pos`, or "" if there is other content.
+fn line_indent_before(content: &str, pos: usize) -> &str {
+	let line_start = content[..pos].rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+	let prefix = &content[line_start..pos];
+	if prefix.trim().is_empty() { prefix } else { "" }
+}
+
+/// Reads the attributes of a start tag as unescaped `(name, value)` pairs, in source order.
+fn read_attributes(start: &BytesStart) -> Option<Vec<(String, String)>> {
+	let mut attributes = Vec::new();
+	for attr in start.attributes() {
+		let attr = attr.ok()?;
+		let name = std::str::from_utf8(attr.key.as_ref()).ok()?.to_string();
+		let value = attr.unescape_value().ok()?.into_owned();
+		attributes.push((name, value));
+	}
+	Some(attributes)
+}
+
+/// Reads the attributes of a start tag as unescaped `(name, value)` pairs, in source order, borrowed from the source
+/// (the values only owned when unescaped).
+fn read_source_attributes<'a>(source: &'a str, start: &BytesStart) -> Option<Vec<(&'a str, Cow<'a, str>)>> {
+	let mut attributes = Vec::new();
+	for attr in start.attributes() {
+		let attr = attr.ok()?;
+		let name = source_str(source, attr.key.as_ref())?;
+		let value = unescape(source_str(source, &attr.value)?).ok()?;
+		attributes.push((name, value));
+	}
+	Some(attributes)
+}
+
+/// Collects the value patches of a start tag for the attribute values changed by the rewrite function.
+fn collect_value_patches<F>(
+	xml_content: &str,
+	start: &BytesStart,
+	rewrite_fn: &F,
+	patches: &mut Vec<(Range<usize>, String)>,
+) -> Option<()>
+where
+	F: Fn(&str, &str) -> Option<String>,
+{
+	for attr in start.attributes() {
+		let attr = attr.ok()?;
+		let name = std::str::from_utf8(attr.key.as_ref()).ok()?;
+		let raw_value = std::str::from_utf8(&attr.value).ok()?;
+		let value = unescape(raw_value).ok()?;
+		let Some(transformed) = rewrite_fn(name, &value) else {
+			continue;
+		};
+		if transformed == value {
+			continue;
+		}
+
+		// Only escape the delimiting quote (plus `&` and `<`), to keep the value as close as possible to the source
+		let range = source_range(xml_content, &attr.value)?;
+		let quote = xml_content[..range.start].chars().next_back()?;
+		let escaped =
+			minimal_escape(transformed.as_str()).replace(quote, if quote == '"' { "&quot;" } else { "&apos;" });
+		patches.push((range, escaped));
+	}
+
+	Some(())
+}
+
+/// Returns the byte range of a slice borrowed from the source content.
+/// Returns None if the slice does not point into the source (e.g., an owned value).
+fn source_range(source: &str, slice: &[u8]) -> Option<Range<usize>> {
+	let source_start = source.as_ptr() as usize;
+	let slice_start = slice.as_ptr() as usize;
+	let offset = slice_start.checked_sub(source_start)?;
+	let end = offset + slice.len();
+	(end <= source.len()).then_some(offset..end)
+}
+
+/// Returns the source text of a slice borrowed from the source content (see `source_range`).
+fn source_str<'a>(source: &'a str, slice: &[u8]) -> Option<&'a str> {
+	source.get(source_range(source, slice)?)
+}
+
+/// Rebuilds the content with the patches applied (patches must be in source order and non-overlapping).
+fn apply_patches(source: &str, patches: Vec<(Range<usize>, String)>) -> String {
+	let mut result = String::with_capacity(source.len());
+	let mut cursor = 0;
+	for (range, replacement) in patches {
+		result.push_str(&source[cursor..range.start]);
+		result.push_str(&replacement);
+		cursor = range.end;
+	}
+	result.push_str(&source[cursor..]);
+	result
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_xmls_stream_extract_root_attribute_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let xml = r#"<?xml version="1.0"?><svg viewBox="0 0 24 24" width="24"><g id="a"/></svg>"#;
+
+		// -- Exec
+		let view_box = extract_root_attribute(xml, "viewBox");
+		let not_found = extract_root_attribute(xml, "height");
+
+		// -- Check
+		assert_eq!(view_box.as_deref(), Some("0 0 24 24"));
+		assert_eq!(not_found, None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_xmls_stream_extract_root_inner_content_exact() -> Result<()> {
+		// -- Setup & Fixtures
+		let xml = r#"<svg viewBox="0 0 16 16">
+    <title>ico/chevron-down</title>
+    <g id="ico/chevron-down"  fill='none'><polygon points="3 4 8"></polygon></g>
+</svg>"#;
+
+		// -- Exec
+		let inner = extract_root_inner_content(xml).ok_or("Should have content")?;
+
+		// -- Check
+		assert_eq!(
+			inner,
+			r#"
+    <title>ico/chevron-down</title>
+    <g id="ico/chevron-down"  fill='none'><polygon points="3 4 8"></polygon></g>
+"#
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_xmls_stream_extract_root_inner_content_empty() -> Result<()> {
+		// -- Exec
+		let inner = extract_root_inner_content(r#"<svg viewBox="0 0 24 24"/>"#);
+
+		// -- Check
+		assert_eq!(inner, Some(""));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_xmls_stream_transform_attributes_id_byte_exact() -> Result<()> {
+		// -- Setup & Fixtures
+		let xml = r##"
+    <title>ico/chevron-down</title>
+    <g id="ico/chevron-down"   stroke="none" fill='none'>
+        <polygon id='Shape' fill="#CECECE"></polygon>
+        <path id="a&amp;b" d="M0 0"/>
+    </g>
+"##;
+
+		// -- Exec
+		let result = transform_attributes(xml, &["id"], |id| id.replace(['/', '&'], "-")).ok_or("Should transform")?;
+
+		// -- Check
+		let expected = r##"
+    <title>ico/chevron-down</title>
+    <g id="ico-chevron-down"   stroke="none" fill='none'>
+        <polygon id='Shape' fill="#CECECE"></polygon>
+        <path id="a-b" d="M0 0"/>
+    </g>
+"##;
+		assert_eq!(result, expected);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_xmls_stream_transform_id_references_coordinated() -> Result<()> {
+		// -- Setup & Fixtures
+		let xml = r##"
+    <defs>
+        <linearGradient id="Grad 1"/>
+        <path id="Path 1" d="M0 0"/>
+    </defs>
+    <g fill="url(#Grad 1)" style="mask: url('#Grad 1')" class="Grad 1">
+        <use xlink:href="#Path 1" href="https://example.com/#Path 1"/>
+    </g>
+"##;
+
+		// -- Exec
+		let result = transform_id_references(xml, |id| id.replace(' ', "-")).ok_or("Should transform")?;
+
+		// -- Check
+		let expected = r##"
+    <defs>
+        <linearGradient id="Grad-1"/>
+        <path id="Path-1" d="M0 0"/>
+    </defs>
+    <g fill="url(#Grad-1)" style="mask: url('#Grad-1')" class="Grad 1">
+        <use xlink:href="#Path-1" href="https://example.com/#Path 1"/>
+    </g>
+"##;
+		assert_eq!(result, expected);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_xmls_stream_needed_namespace_declarations_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let xml = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:sketch="http://www.bohemiancoding.com/sketch/ns">
+    <use xlink:href="#a"/>
+</svg>"##;
+
+		// -- Exec
+		let declarations = extract_root_namespace_declarations(xml).ok_or("Should have declarations")?;
+		let inner = extract_root_inner_content(xml).ok_or("Should have content")?;
+		let needed = needed_namespace_declarations(&declarations, inner);
+
+		// -- Check
+		assert_eq!(declarations.len(), 3);
+		assert_eq!(
+			needed,
+			vec![
+				("xmlns".to_string(), "http://www.w3.org/2000/svg".to_string()),
+				("xmlns:xlink".to_string(), "http://www.w3.org/1999/xlink".to_string()),
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_xmls_stream_strip_namespaces_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let xml = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:sketch="http://www.bohemiancoding.com/sketch/ns">
+    <g id="a" sketch:type="MSArtboardGroup"  fill="none">
+        <sketch:meta><x/></sketch:meta>
+        <use xlink:href="#a"/>
+    </g>
+</svg>"##;
+
+		// -- Exec
+		let result = strip_namespaces(xml, &["sketch"]).ok_or("Should strip")?;
+
+		// -- Check
+		let expected = r##"<svg xmlns="http://www.w3.org/2000/svg">
+    <g id="a"  fill="none">
+        
+        <use xlink:href="#a"/>
+    </g>
+</svg>"##;
+		assert_eq!(result, expected);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_xmls_stream_extract_root_child_elements_byte_exact() -> Result<()> {
+		// -- Setup & Fixtures
+		let xml = r#"<svg width="0" height="0">
+  <symbol id="a"   viewBox="0 0 16 16">
+    <path d="M0 0"/>
+    <symbol id="nested"/>
+  </symbol>
+  <defs><symbol id="in-defs"/></defs>
+
+  <symbol id='b' viewBox="0 0 8 8"/>
+</svg>"#;
+
+		// -- Exec
+		let elements = extract_root_child_elements(xml, "symbol").ok_or("Should parse")?;
+
+		// -- Check
+		assert_eq!(elements.len(), 2);
+		assert_eq!(
+			elements[0].markup,
+			r#"<symbol id="a"   viewBox="0 0 16 16">
+    <path d="M0 0"/>
+    <symbol id="nested"/>
+  </symbol>"#
+		);
+		assert_eq!(elements[0].attributes[0], ("id".to_string(), "a".to_string()));
+		assert_eq!(elements[0].line_indent, "  ");
+		assert_eq!(elements[1].markup, r#"<symbol id='b' viewBox="0 0 8 8"/>"#);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_xmls_stream_rewrite_root_attributes_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let xml = r#"<svg width="24px" viewBox="0 0 24 24"><rect width="24" height="24"/></svg>"#;
+
+		// -- Exec
+		let result = rewrite_root_attributes(xml, |name, _| (name == "width").then(|| "28px".to_string()))
+			.ok_or("Should rewrite")?;
+
+		// -- Check
+		assert_eq!(result, r#"<svg width="28px" viewBox="0 0 24 24"><rect width="24" height="24"/></svg>"#);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_xmls_stream_remove_attributes_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let xml = r#"<svg><image xlink:href="/Users/jen/a.png"  width="4"/><use href='#a'/></svg>"#;
+
+		// -- Exec
+		let result = remove_attributes(xml, |name, value| name.ends_with("href") && value.starts_with('/'))
+			.ok_or("Should remove")?;
+
+		// -- Check
+		assert_eq!(result, r#"<svg><image  width="4"/><use href='#a'/></svg>"#);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_xmls_stream_insert_attribute_inherited() -> Result<()> {
+		// -- Setup & Fixtures
+		let xml =
+			r##"<svg><g stroke="#000"><path d="M0 0"/><path d='M1 1' fill="red"></path></g><path d="M2 2"/></svg>"##;
+
+		// -- Exec
+		let result = insert_attribute(xml, "vector-effect", "non-scaling-stroke", |path| {
+			path.last().is_some_and(|(name, _)| *name == "path")
+				&& path.iter().any(|(_, attrs)| attrs.iter().any(|(name, _)| *name == "stroke"))
+		})
+		.ok_or("Should insert")?;
+
+		// -- Check
+		assert_eq!(
+			result,
+			r##"<svg><g stroke="#000"><path vector-effect="non-scaling-stroke" d="M0 0"/><path vector-effect="non-scaling-stroke" d='M1 1' fill="red"></path></g><path d="M2 2"/></svg>"##
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
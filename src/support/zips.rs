@@ -0,0 +1,358 @@
+//! Minimal zip archive reading and writing (stored and deflated entries, no zip64 or encryption), enough to edit
+//! the files of a zip container (e.g., the JSON of a .sketch file) and write it back.
+//! The entries not edited are written back as is (same compressed data, versions, and extra fields).
+
+use crate::{Error, Result};
+use flate2::Crc;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use std::io::{Read as _, Write as _};
+
+const LOCAL_HEADER_SIG: u32 = 0x04034b50;
+const CENTRAL_HEADER_SIG: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIR_SIG: u32 = 0x06054b50;
+const END_OF_CENTRAL_DIR_LEN: usize = 22;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+const FLAG_ENCRYPTED: u16 = 0x0001;
+/// The sizes and CRC are in a data descriptor after the data (not written back, the local header has them).
+const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+const FLAG_UTF8: u16 = 0x0800;
+
+/// "2.0", the version needed for deflate and directories.
+const ZIP_VERSION: u16 = 20;
+
+/// A file of a zip archive, with its compressed data.
+#[derive(Debug, Clone)]
+pub struct ZipEntry {
+	/// The path in the archive (e.g., "pages/3F0A....json").
+	pub name: String,
+	/// The host system (high byte, e.g., 3 for Unix) and zip version of the archiver, for `external_attrs`.
+	version_made_by: u16,
+	version_needed: u16,
+	flags: u16,
+	method: u16,
+	dos_time: u16,
+	dos_date: u16,
+	crc32: u32,
+	size: u32,
+	internal_attrs: u16,
+	external_attrs: u32,
+	/// The extra fields of the local header (e.g., the Unix timestamps), which may differ from the central ones.
+	local_extra: Vec<u8>,
+	central_extra: Vec<u8>,
+	comment: Vec<u8>,
+	data: Vec<u8>,
+}
+
+impl Default for ZipEntry {
+	fn default() -> Self {
+		Self {
+			name: String::new(),
+			version_made_by: ZIP_VERSION,
+			version_needed: ZIP_VERSION,
+			flags: 0,
+			method: METHOD_STORED,
+			dos_time: 0,
+			dos_date: 0,
+			crc32: 0,
+			size: 0,
+			internal_attrs: 0,
+			external_attrs: 0,
+			local_extra: Vec::new(),
+			central_extra: Vec::new(),
+			comment: Vec::new(),
+			data: Vec::new(),
+		}
+	}
+}
+
+impl ZipEntry {
+	/// Returns the uncompressed content, checking its CRC.
+	pub fn content(&self) -> Result<Vec<u8>> {
+		let content = match self.method {
+			METHOD_STORED => self.data.clone(),
+			METHOD_DEFLATED => {
+				let mut content = Vec::with_capacity(self.size as usize);
+				DeflateDecoder::new(self.data.as_slice())
+					.read_to_end(&mut content)
+					.map_err(|e| format!("Cannot inflate zip entry '{}': {e}", self.name))?;
+				content
+			}
+			other => {
+				return Err(Error::custom(format!(
+					"Unsupported compression method {other} of zip entry '{}'",
+					self.name
+				)));
+			}
+		};
+
+		if crc32(&content) != self.crc32 {
+			return Err(Error::custom(format!("Invalid CRC of zip entry '{}'", self.name)));
+		}
+		Ok(content)
+	}
+
+	/// Replaces the content (deflated), keeping the name, date, attributes, and extra fields.
+	pub fn set_content(&mut self, content: &[u8]) -> Result<()> {
+		let size = u32::try_from(content.len())
+			.map_err(|_| format!("Zip entry '{}' too large (zip64 not supported)", self.name))?;
+		let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+		encoder
+			.write_all(content)
+			.map_err(|e| format!("Cannot deflate zip entry '{}': {e}", self.name))?;
+		self.data = encoder
+			.finish()
+			.map_err(|e| format!("Cannot deflate zip entry '{}': {e}", self.name))?;
+		self.method = METHOD_DEFLATED;
+		self.version_needed = self.version_needed.max(ZIP_VERSION);
+		self.crc32 = crc32(content);
+		self.size = size;
+		Ok(())
+	}
+}
+
+/// Reads the entries of a zip archive, in their central directory order.
+pub fn read_zip(bytes: &[u8]) -> Result<Vec<ZipEntry>> {
+	let eocd = find_end_of_central_dir(bytes).ok_or("Not a zip archive (no end of central directory)")?;
+	let entry_count = read_u16(bytes, eocd + 10)? as usize;
+	let mut pos = read_u32(bytes, eocd + 16)? as usize;
+
+	let mut entries = Vec::with_capacity(entry_count);
+	for _ in 0..entry_count {
+		if read_u32(bytes, pos)? != CENTRAL_HEADER_SIG {
+			return Err(Error::custom("Invalid zip central directory"));
+		}
+		let flags = read_u16(bytes, pos + 8)?;
+		let compressed_size = read_u32(bytes, pos + 20)?;
+		let size = read_u32(bytes, pos + 24)?;
+		let name_len = read_u16(bytes, pos + 28)? as usize;
+		let extra_len = read_u16(bytes, pos + 30)? as usize;
+		let comment_len = read_u16(bytes, pos + 32)? as usize;
+		let local_offset = read_u32(bytes, pos + 42)?;
+		let name = String::from_utf8_lossy(slice(bytes, pos + 46, name_len)?).to_string();
+
+		if flags & FLAG_ENCRYPTED != 0 {
+			return Err(Error::custom(format!("Encrypted zip entry '{name}' not supported")));
+		}
+		if [compressed_size, size, local_offset].contains(&u32::MAX) {
+			return Err(Error::custom(format!("Zip64 entry '{name}' not supported")));
+		}
+
+		let local = local_offset as usize;
+		if read_u32(bytes, local)? != LOCAL_HEADER_SIG {
+			return Err(Error::custom(format!("Invalid zip local header of '{name}'")));
+		}
+		let local_name_len = read_u16(bytes, local + 26)? as usize;
+		let local_extra_len = read_u16(bytes, local + 28)? as usize;
+		let data_start = local + 30 + local_name_len + local_extra_len;
+
+		entries.push(ZipEntry {
+			version_made_by: read_u16(bytes, pos + 4)?,
+			version_needed: read_u16(bytes, pos + 6)?,
+			flags: flags & !FLAG_DATA_DESCRIPTOR,
+			method: read_u16(bytes, pos + 10)?,
+			dos_time: read_u16(bytes, pos + 12)?,
+			dos_date: read_u16(bytes, pos + 14)?,
+			crc32: read_u32(bytes, pos + 16)?,
+			size,
+			internal_attrs: read_u16(bytes, pos + 36)?,
+			external_attrs: read_u32(bytes, pos + 38)?,
+			local_extra: slice(bytes, local + 30 + local_name_len, local_extra_len)?.to_vec(),
+			central_extra: slice(bytes, pos + 46 + name_len, extra_len)?.to_vec(),
+			comment: slice(bytes, pos + 46 + name_len + extra_len, comment_len)?.to_vec(),
+			data: slice(bytes, data_start, compressed_size as usize)?.to_vec(),
+			name,
+		});
+		pos += 46 + name_len + extra_len + comment_len;
+	}
+
+	Ok(entries)
+}
+
+/// Writes the entries as a zip archive (without an archive comment).
+pub fn write_zip(entries: &[ZipEntry]) -> Result<Vec<u8>> {
+	let mut bytes = Vec::new();
+	let mut central_dir = Vec::new();
+
+	for entry in entries {
+		let offset = u32::try_from(bytes.len()).map_err(|_| "Zip archive too large (zip64 not supported)")?;
+		let compressed_size =
+			u32::try_from(entry.data.len()).map_err(|_| format!("Zip entry '{}' too large", entry.name))?;
+		let name = entry.name.as_bytes();
+		let name_len = u16::try_from(name.len()).map_err(|_| format!("Zip entry name '{}' too long", entry.name))?;
+		let flags = if entry.name.is_ascii() { entry.flags } else { entry.flags | FLAG_UTF8 };
+		let local_extra_len = u16_len(&entry.local_extra, "extra field", &entry.name)?;
+		let central_extra_len = u16_len(&entry.central_extra, "extra field", &entry.name)?;
+		let comment_len = u16_len(&entry.comment, "comment", &entry.name)?;
+
+		// -- Local header, then the data
+		push_u32(&mut bytes, LOCAL_HEADER_SIG);
+		push_u16(&mut bytes, entry.version_needed);
+		push_common_fields(&mut bytes, entry, flags, compressed_size, name_len);
+		push_u16(&mut bytes, local_extra_len);
+		bytes.extend_from_slice(name);
+		bytes.extend_from_slice(&entry.local_extra);
+		bytes.extend_from_slice(&entry.data);
+
+		// -- Central directory header
+		push_u32(&mut central_dir, CENTRAL_HEADER_SIG);
+		push_u16(&mut central_dir, entry.version_made_by);
+		push_u16(&mut central_dir, entry.version_needed);
+		push_common_fields(&mut central_dir, entry, flags, compressed_size, name_len);
+		push_u16(&mut central_dir, central_extra_len);
+		push_u16(&mut central_dir, comment_len);
+		push_u16(&mut central_dir, 0); // disk number
+		push_u16(&mut central_dir, entry.internal_attrs);
+		push_u32(&mut central_dir, entry.external_attrs);
+		push_u32(&mut central_dir, offset);
+		central_dir.extend_from_slice(name);
+		central_dir.extend_from_slice(&entry.central_extra);
+		central_dir.extend_from_slice(&entry.comment);
+	}
+
+	let entry_count = u16::try_from(entries.len()).map_err(|_| "Too many zip entries (zip64 not supported)")?;
+	let central_dir_offset = u32::try_from(bytes.len()).map_err(|_| "Zip archive too large (zip64 not supported)")?;
+	let central_dir_len = central_dir.len() as u32;
+	bytes.extend_from_slice(&central_dir);
+
+	push_u32(&mut bytes, END_OF_CENTRAL_DIR_SIG);
+	push_u16(&mut bytes, 0); // disk number
+	push_u16(&mut bytes, 0); // disk of the central directory
+	push_u16(&mut bytes, entry_count);
+	push_u16(&mut bytes, entry_count);
+	push_u32(&mut bytes, central_dir_len);
+	push_u32(&mut bytes, central_dir_offset);
+	push_u16(&mut bytes, 0); // comment length
+
+	Ok(bytes)
+}
+
+// region:    --- Support
+
+/// The fields shared by the local and central headers, from the flags to the name length.
+fn push_common_fields(bytes: &mut Vec<u8>, entry: &ZipEntry, flags: u16, compressed_size: u32, name_len: u16) {
+	push_u16(bytes, flags);
+	push_u16(bytes, entry.method);
+	push_u16(bytes, entry.dos_time);
+	push_u16(bytes, entry.dos_date);
+	push_u32(bytes, entry.crc32);
+	push_u32(bytes, compressed_size);
+	push_u32(bytes, entry.size);
+	push_u16(bytes, name_len);
+}
+
+fn u16_len(field: &[u8], what: &str, name: &str) -> Result<u16> {
+	u16::try_from(field.len()).map_err(|_| Error::custom(format!("Zip entry {what} of '{name}' too long")))
+}
+
+/// The end of central directory record is last, followed by a comment of at most 64 KB.
+fn find_end_of_central_dir(bytes: &[u8]) -> Option<usize> {
+	let last = bytes.len().checked_sub(END_OF_CENTRAL_DIR_LEN)?;
+	let first = last.saturating_sub(u16::MAX as usize);
+	(first..=last)
+		.rev()
+		.find(|&pos| read_u32(bytes, pos).is_ok_and(|sig| sig == END_OF_CENTRAL_DIR_SIG))
+}
+
+fn crc32(content: &[u8]) -> u32 {
+	let mut crc = Crc::new();
+	crc.update(content);
+	crc.sum()
+}
+
+fn slice(bytes: &[u8], pos: usize, len: usize) -> Result<&[u8]> {
+	bytes
+		.get(pos..pos.saturating_add(len))
+		.ok_or_else(|| Error::custom("Truncated zip archive"))
+}
+
+fn read_u16(bytes: &[u8], pos: usize) -> Result<u16> {
+	let field = slice(bytes, pos, 2)?;
+	Ok(u16::from_le_bytes([field[0], field[1]]))
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> Result<u32> {
+	let field = slice(bytes, pos, 4)?;
+	Ok(u32::from_le_bytes([field[0], field[1], field[2], field[3]]))
+}
+
+fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+	bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+	bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_zips_write_zip_roundtrip() -> Result<()> {
+		// -- Setup & Fixtures
+		let mut meta = ZipEntry { name: "meta.json".to_string(), ..Default::default() };
+		meta.set_content(br#"{"app":"com.bohemiancoding.sketch3"}"#)?;
+		let mut page = ZipEntry { name: "pages/ÉCRAN.json".to_string(), ..Default::default() };
+		page.set_content(&b"{\"layers\":[]}".repeat(100))?;
+
+		// -- Exec
+		let bytes = write_zip(&[meta, page])?;
+		let mut entries = read_zip(&bytes)?;
+		entries[0].set_content(b"{}")?;
+		let entries = read_zip(&write_zip(&entries)?)?;
+
+		// -- Check
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].name, "meta.json");
+		assert_eq!(entries[0].content()?, b"{}");
+		assert_eq!(entries[1].name, "pages/ÉCRAN.json");
+		assert_eq!(entries[1].content()?, b"{\"layers\":[]}".repeat(100));
+		assert!(read_zip(b"not a zip").is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_zips_write_zip_keep_unix_entry() -> Result<()> {
+		// -- Setup & Fixtures
+		// A Unix archiver entry (made by 3.0 on Unix, mode 0644) with its timestamp extra fields
+		let mut entry = ZipEntry {
+			name: "document.json".to_string(),
+			version_made_by: 0x031e,
+			external_attrs: 0o100644 << 16,
+			local_extra: b"UT\x09\x00\x03\x01\x00\x00\x00\x02\x00\x00\x00".to_vec(),
+			central_extra: b"UT\x05\x00\x03\x01\x00\x00\x00".to_vec(),
+			comment: b"doc".to_vec(),
+			..Default::default()
+		};
+		entry.set_content(b"{}")?;
+		let bytes = write_zip(&[entry])?;
+
+		// -- Exec
+		let entries = read_zip(&bytes)?;
+		let rewritten = write_zip(&entries)?;
+
+		// -- Check
+		assert_eq!(rewritten, bytes);
+		assert_eq!(entries[0].version_made_by, 0x031e);
+		assert_eq!(entries[0].external_attrs, 0o100644 << 16);
+		assert_eq!(entries[0].local_extra.len(), 13);
+		assert_eq!(entries[0].central_extra.len(), 9);
+		assert_eq!(entries[0].comment, b"doc");
+		assert_eq!(entries[0].content()?, b"{}");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,68 @@
+use derive_more::Display;
+use serde::Serialize;
+
+/// Machine-readable warning codes (serialized as kebab-case, e.g., "non-square-artboard").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningCode {
+	#[display("non-square-artboard")]
+	NonSquareArtboard,
+
+	#[display("empty-artboard")]
+	EmptyArtboard,
+
+	#[display("stroke-detected")]
+	StrokeDetected,
+
+	#[display("id-collision-resolved")]
+	IdCollisionResolved,
+
+	#[display("duplicate-content")]
+	DuplicateContent,
+
+	#[display("compatibility-risk")]
+	CompatibilityRisk,
+
+	#[display("render-failed")]
+	RenderFailed,
+
+	#[display("duplicate-token")]
+	DuplicateToken,
+
+	#[display("unsupported-token")]
+	UnsupportedToken,
+
+	#[display("unsupported-preset")]
+	UnsupportedPreset,
+
+	#[display("duplicate-artboard-name")]
+	DuplicateArtboardName,
+
+	#[display("local-reference")]
+	LocalReference,
+
+	#[display("large-embedded-image")]
+	LargeEmbeddedImage,
+}
+
+/// A non-fatal issue found by a handler (e.g., during export).
+/// Collected in the reports and surfaced by the CLI (or turned into failures with `--deny-warnings`).
+#[derive(Debug, Clone, Display, Serialize)]
+#[display("[{code}] {message}")]
+pub struct Warning {
+	pub code: WarningCode,
+	/// The artboard name the warning is about, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub artboard: Option<String>,
+	pub message: String,
+}
+
+impl Warning {
+	pub fn new(code: WarningCode, message: impl Into<String>) -> Self {
+		Self { code, artboard: None, message: message.into() }
+	}
+
+	pub fn for_artboard(code: WarningCode, artboard: impl Into<String>, message: impl Into<String>) -> Self {
+		Self { code, artboard: Some(artboard.into()), message: message.into() }
+	}
+}